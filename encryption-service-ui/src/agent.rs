@@ -0,0 +1,189 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::atomic::AtomicBool;
+use std::time::{Duration, Instant};
+
+use crate::api::{ApiClient, ApiClientConfig};
+use crate::docker;
+use crate::tasks;
+use crate::models::{AgentSettings, DockerHost, MiddlewareContainer, NetworkProfile, RuntimeKind, SshTunnel};
+
+/// 部署后等待Agent响应的最长时间
+const READY_TIMEOUT: Duration = Duration::from_secs(30);
+/// 检查Agent的请求超时（毫秒）
+const CHECK_TIMEOUT_MS: u64 = 3000;
+/// Agent的健康检查路径
+const HEALTH_PATH: &str = "/health";
+/// Agent输出的日志文件
+const LOG_FILE: &str = "/tmp/encryption-agent.log";
+/// 记录Agent进程号的文件名，位于安装目录中，重新部署时据此结束旧进程
+const PID_FILE_NAME: &str = "encryption-agent.pid";
+/// 复制到容器内的文件权限
+const EXEC_MODE: u32 = 0o755;
+
+/// 部署Agent的方式
+pub enum AgentTarget {
+    /// 由Docker管理的中间层：复制到容器内并通过docker exec启动
+    Docker { host: Option<DockerHost>, id: String, params: String },
+    /// 直接运行在主机上的中间层：通过scp复制并经ssh启动，配置了隧道时经同一跳板机
+    Ssh { host: String, jump: Option<SshTunnel> },
+}
+
+impl AgentTarget {
+    /// 按中间层的运行方式选择部署方式，`host` 为中间层所在的Docker主机
+    pub fn for_middleware(middleware: &MiddlewareContainer, host: Option<DockerHost>) -> Result<Self> {
+        if let RuntimeKind::Kubernetes { .. } = middleware.runtime {
+            anyhow::bail!("Kubernetes中的中间层无法单独安装Agent，请使用内置Agent的镜像");
+        }
+        if !middleware.docker_run_params.trim().is_empty() {
+            return Ok(AgentTarget::Docker {
+                host,
+                id: middleware.id.clone(),
+                params: middleware.docker_run_params.clone(),
+            });
+        }
+        let url = reqwest::Url::parse(&middleware.url).with_context(|| format!("无效的地址: {}", middleware.url))?;
+        let host = url.host_str().with_context(|| format!("地址中没有主机: {}", middleware.url))?;
+        Ok(AgentTarget::Ssh {
+            host: host.to_string(),
+            jump: middleware.tunnel.clone(),
+        })
+    }
+
+    /// 部署方式的显示名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            AgentTarget::Docker { .. } => "docker exec",
+            AgentTarget::Ssh { .. } => "SSH",
+        }
+    }
+}
+
+/// Agent的访问地址：中间层地址换为Agent端口
+pub fn agent_url(middleware: &MiddlewareContainer, settings: &AgentSettings) -> Result<String> {
+    let base = middleware.api_base_url();
+    let mut url = reqwest::Url::parse(&base).with_context(|| format!("无效的地址: {}", base))?;
+    url.set_port(Some(settings.port)).map_err(|_| anyhow::anyhow!("地址不支持端口: {}", base))?;
+    Ok(url.as_str().trim_end_matches('/').to_string())
+}
+
+/// 复制Agent程序并在后台启动，上次启动的进程先结束
+pub fn deploy(target: &AgentTarget, settings: &AgentSettings) -> Result<()> {
+    let binary = settings.binary_path.trim();
+    if binary.is_empty() {
+        anyhow::bail!("未配置Agent程序路径");
+    }
+    let file_name = Path::new(binary)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .with_context(|| format!("无效的Agent程序路径: {}", binary))?;
+    let contents = std::fs::read(binary).with_context(|| format!("无法读取Agent程序 {}", binary))?;
+    let install_dir = settings.install_dir.trim().trim_end_matches('/');
+    let path = quote(&format!("{}/{}", install_dir, file_name))?;
+    let start = start_command(&path, &quote(&format!("{}/{}", install_dir, PID_FILE_NAME))?, settings)?;
+
+    match target {
+        AgentTarget::Docker { host, id, params } => {
+            docker::upload(host.as_ref(), id, params, install_dir, file_name, &contents, EXEC_MODE)?;
+            let mut output = String::new();
+            let exit = docker::exec(host.as_ref(), id, params, &start, "", &AtomicBool::new(false), |chunk| output.push_str(chunk))?;
+            if exit.is_some_and(|code| code != 0) {
+                anyhow::bail!("启动Agent失败: {}", output.trim());
+            }
+            Ok(())
+        }
+        AgentTarget::Ssh { host, jump } => {
+            let destination = format!("{}@{}", settings.ssh_user.trim(), host);
+            let mut scp = Command::new("scp");
+            scp.args(ssh_options(settings, jump.as_ref(), "-P"))
+                .arg(binary)
+                .arg(format!("{}:{}/{}", destination, install_dir, file_name));
+            run(scp, "复制Agent程序")?;
+            let mut ssh = Command::new("ssh");
+            ssh.args(ssh_options(settings, jump.as_ref(), "-p"))
+                .arg(&destination)
+                .arg(format!("chmod {:o} {} && {}", EXEC_MODE, path, start));
+            run(ssh, "启动Agent")
+        }
+    }
+}
+
+/// 转义为shell中的单个参数
+fn quote(value: &str) -> Result<String> {
+    shlex::try_quote(value)
+        .map(|quoted| quoted.into_owned())
+        .map_err(|_| anyhow::anyhow!("无效的安装路径: {}", value))
+}
+
+/// 在后台启动Agent的shell命令，`path` 与 `pid` 为已转义的程序与进程号文件路径
+///
+/// 启动参数按shell规则拆分后逐个转义，参数中的shell元字符不会被远端执行
+fn start_command(path: &str, pid: &str, settings: &AgentSettings) -> Result<String> {
+    let args = settings.args.replace("{port}", &settings.port.to_string());
+    let args = shlex::split(&args).with_context(|| format!("Agent启动参数格式错误: {}", settings.args))?;
+    let args = shlex::try_join(args.iter().map(String::as_str))
+        .map_err(|_| anyhow::anyhow!("Agent启动参数无效: {}", settings.args))?;
+    Ok(format!(
+        "if [ -f {pid} ]; then kill $(cat {pid}) 2>/dev/null; fi; nohup {path} {args} > {log} 2>&1 & echo $! > {pid}",
+        pid = pid,
+        path = path,
+        args = args,
+        log = LOG_FILE,
+    ))
+}
+
+/// ssh与scp共用的选项，两者指定端口的参数名不同
+fn ssh_options(settings: &AgentSettings, jump: Option<&SshTunnel>, port_flag: &str) -> Vec<String> {
+    let mut options = vec![
+        "-o".to_string(),
+        "BatchMode=yes".to_string(),
+        port_flag.to_string(),
+        settings.ssh_port.to_string(),
+    ];
+    if !settings.ssh_key.trim().is_empty() {
+        options.extend(["-i".to_string(), settings.ssh_key.trim().to_string()]);
+    }
+    if let Some(jump) = jump {
+        options.extend(["-o".to_string(), format!("ProxyJump={}:{}", jump.bastion.trim(), jump.port)]);
+    }
+    options
+}
+
+/// 执行命令，失败时返回其错误输出
+fn run(mut command: Command, action: &str) -> Result<()> {
+    let output = command
+        .stdin(Stdio::null())
+        .output()
+        .with_context(|| format!("{}失败: 无法执行命令", action))?;
+    if !output.status.success() {
+        anyhow::bail!("{}失败: {}", action, String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
+
+/// 检查Agent是否在其端口上响应
+pub fn check(url: &str, network: Option<NetworkProfile>) -> Result<()> {
+    ApiClient::new(ApiClientConfig {
+        base_url: url.to_string(),
+        timeout: CHECK_TIMEOUT_MS,
+        network,
+        trace_id: None,
+    })
+    .and_then(|client| tasks::block_on(client.probe(HEALTH_PATH)))
+    .with_context(|| format!("Agent未响应: {}", url))
+}
+
+/// 等待刚启动的Agent响应，超时后返回最后一次的错误
+pub fn wait_ready(url: &str, network: Option<NetworkProfile>) -> Result<()> {
+    let started = Instant::now();
+    loop {
+        match check(url, network.clone()) {
+            Ok(()) => return Ok(()),
+            Err(e) if started.elapsed() >= READY_TIMEOUT => {
+                return Err(e).with_context(|| format!("Agent在 {} 秒内未响应", READY_TIMEOUT.as_secs()));
+            }
+            Err(_) => std::thread::sleep(Duration::from_secs(1)),
+        }
+    }
+}
@@ -0,0 +1,243 @@
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{HashMap, VecDeque};
+
+use crate::events::EntityKind;
+use crate::models::{Alert, AlertPolicy};
+
+/// 一次健康检查的结果
+#[derive(Debug, Clone)]
+pub struct HealthObservation {
+    pub kind: EntityKind,
+    pub id: String,
+    pub name: String,
+    pub healthy: bool,
+}
+
+/// 经过迟滞与抖动判断后需要告警的健康变化
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HealthSignal {
+    /// 连续失败达到阈值
+    Down,
+    /// 连续成功达到阈值
+    Recovered,
+    /// 短时间内反复上下线
+    Flapping,
+    /// 抖动平息
+    Stable,
+}
+
+/// 单个实体的健康跟踪状态
+#[derive(Debug, Default)]
+struct EntityHealth {
+    consecutive_failures: u32,
+    consecutive_successes: u32,
+    /// 是否已判定为故障
+    down: bool,
+    /// 窗口内确认的状态切换时间
+    transitions: VecDeque<DateTime<Utc>>,
+    flapping: bool,
+}
+
+/// 健康告警跟踪器，对检查结果做迟滞处理并检测抖动
+#[derive(Debug, Default)]
+pub struct HealthTracker {
+    entities: HashMap<String, EntityHealth>,
+}
+
+impl HealthTracker {
+    /// 创建跟踪器
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 记录一次检查结果，返回需要告警的信号
+    pub fn observe(&mut self, observation: &HealthObservation, policy: &AlertPolicy, now: DateTime<Utc>) -> Option<HealthSignal> {
+        let entity = self.entities.entry(observation.id.clone()).or_default();
+        if observation.healthy {
+            entity.consecutive_successes += 1;
+            entity.consecutive_failures = 0;
+        } else {
+            entity.consecutive_failures += 1;
+            entity.consecutive_successes = 0;
+        }
+
+        let window = Duration::seconds(policy.flap_window_secs as i64);
+        while entity.transitions.front().is_some_and(|t| now - *t > window) {
+            entity.transitions.pop_front();
+        }
+
+        let transition = if !entity.down && entity.consecutive_failures >= policy.failure_threshold.max(1) {
+            Some(HealthSignal::Down)
+        } else if entity.down && entity.consecutive_successes >= policy.recovery_threshold.max(1) {
+            Some(HealthSignal::Recovered)
+        } else {
+            None
+        };
+
+        let Some(signal) = transition else {
+            // 窗口内不再切换时解除抖动
+            if entity.flapping && entity.transitions.is_empty() {
+                entity.flapping = false;
+                return Some(HealthSignal::Stable);
+            }
+            return None;
+        };
+
+        entity.down = signal == HealthSignal::Down;
+        entity.transitions.push_back(now);
+        if entity.flapping {
+            return None;
+        }
+        if policy.flap_threshold > 0 && entity.transitions.len() >= policy.flap_threshold as usize {
+            entity.flapping = true;
+            return Some(HealthSignal::Flapping);
+        }
+        Some(signal)
+    }
+}
+
+/// 去重窗口内存在未确认的相同告警时只累加次数与最近触发时间，否则追加，返回是否新增
+pub fn push_deduplicated(alerts: &mut Vec<Alert>, alert: Alert, policy: &AlertPolicy) -> bool {
+    let window = Duration::seconds(policy.dedup_window_secs as i64);
+    let duplicate = alerts.iter_mut().rev().find(|a| {
+        !a.acknowledged
+            && a.source == alert.source
+            && a.message == alert.message
+            && alert.created_at - a.last_seen.unwrap_or(a.created_at) <= window
+    });
+    match duplicate {
+        Some(existing) => {
+            existing.occurrences += 1;
+            existing.last_seen = Some(alert.created_at);
+            false
+        }
+        None => {
+            alerts.push(alert);
+            true
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::AlertSeverity;
+
+    fn policy() -> AlertPolicy {
+        AlertPolicy {
+            failure_threshold: 3,
+            recovery_threshold: 2,
+            dedup_window_secs: 600,
+            flap_threshold: 4,
+            flap_window_secs: 600,
+        }
+    }
+
+    fn observation(healthy: bool) -> HealthObservation {
+        HealthObservation { kind: EntityKind::Backend, id: "b-1".to_string(), name: "db".to_string(), healthy }
+    }
+
+    /// 依次记录检查结果，每次间隔一秒，返回各次的信号
+    fn run(tracker: &mut HealthTracker, policy: &AlertPolicy, start: DateTime<Utc>, results: &[bool]) -> Vec<Option<HealthSignal>> {
+        results
+            .iter()
+            .enumerate()
+            .map(|(i, healthy)| tracker.observe(&observation(*healthy), policy, start + Duration::seconds(i as i64)))
+            .collect()
+    }
+
+    #[test]
+    fn down_and_recovery_wait_for_thresholds() {
+        let mut tracker = HealthTracker::new();
+        let signals = run(&mut tracker, &policy(), Utc::now(), &[false, false, true, false, false, false, false, true, true, true]);
+        assert_eq!(signals, [
+            None,
+            None,
+            // 中途的一次成功重新计数
+            None,
+            None,
+            None,
+            Some(HealthSignal::Down),
+            // 已告警的故障不重复告警
+            None,
+            None,
+            Some(HealthSignal::Recovered),
+            None,
+        ]);
+    }
+
+    #[test]
+    fn zero_thresholds_act_as_one() {
+        let policy = AlertPolicy { failure_threshold: 0, recovery_threshold: 0, flap_threshold: 0, ..policy() };
+        let mut tracker = HealthTracker::new();
+        let signals = run(&mut tracker, &policy, Utc::now(), &[true, false, true, false, true]);
+        // 不检测抖动时每次切换都告警
+        assert_eq!(signals, [
+            None,
+            Some(HealthSignal::Down),
+            Some(HealthSignal::Recovered),
+            Some(HealthSignal::Down),
+            Some(HealthSignal::Recovered),
+        ]);
+    }
+
+    #[test]
+    fn flapping_suppresses_alerts_until_stable() {
+        let policy = AlertPolicy { failure_threshold: 1, recovery_threshold: 1, ..policy() };
+        let mut tracker = HealthTracker::new();
+        let start = Utc::now();
+        let signals = run(&mut tracker, &policy, start, &[false, true, false, true, false, true]);
+        assert_eq!(signals, [
+            Some(HealthSignal::Down),
+            Some(HealthSignal::Recovered),
+            Some(HealthSignal::Down),
+            Some(HealthSignal::Flapping),
+            None,
+            None,
+        ]);
+
+        // 窗口内没有新的切换后解除抖动，之后恢复正常告警
+        let later = start + Duration::seconds(policy.flap_window_secs as i64 + 10);
+        assert_eq!(tracker.observe(&observation(true), &policy, later), Some(HealthSignal::Stable));
+        assert_eq!(tracker.observe(&observation(true), &policy, later), None);
+        assert_eq!(tracker.observe(&observation(false), &policy, later), Some(HealthSignal::Down));
+    }
+
+    #[test]
+    fn entities_are_tracked_separately() {
+        let policy = AlertPolicy { failure_threshold: 2, ..policy() };
+        let mut tracker = HealthTracker::new();
+        let now = Utc::now();
+        let other = HealthObservation { id: "b-2".to_string(), ..observation(false) };
+        assert_eq!(tracker.observe(&observation(false), &policy, now), None);
+        assert_eq!(tracker.observe(&other, &policy, now), None);
+        assert_eq!(tracker.observe(&observation(false), &policy, now), Some(HealthSignal::Down));
+    }
+
+    #[test]
+    fn duplicate_alerts_merge_within_window() {
+        let policy = policy();
+        let start = Utc::now();
+        let alert = |message: &str, seconds: i64| Alert {
+            created_at: start + Duration::seconds(seconds),
+            ..Alert::new("db", message, AlertSeverity::Warning)
+        };
+        let mut alerts = Vec::new();
+        assert!(push_deduplicated(&mut alerts, alert("健康检查连续失败", 0), &policy));
+        assert!(!push_deduplicated(&mut alerts, alert("健康检查连续失败", 300), &policy));
+        // 窗口从最近一次触发起算
+        assert!(!push_deduplicated(&mut alerts, alert("健康检查连续失败", 800), &policy));
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].occurrences, 3);
+        assert_eq!(alerts[0].last_seen, Some(start + Duration::seconds(800)));
+
+        assert!(push_deduplicated(&mut alerts, alert("健康检查已恢复", 801), &policy));
+        assert!(push_deduplicated(&mut alerts, alert("健康检查连续失败", 1500), &policy));
+        assert_eq!(alerts.len(), 3);
+
+        // 已确认的告警不再合并
+        alerts.iter_mut().for_each(|a| a.acknowledged = true);
+        assert!(push_deduplicated(&mut alerts, alert("健康检查连续失败", 1501), &policy));
+        assert_eq!(alerts.len(), 4);
+    }
+}
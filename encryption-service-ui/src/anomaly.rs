@@ -0,0 +1,230 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+use regex::Regex;
+
+use crate::models::AnomalyRule;
+
+/// 激增检测的统计窗口
+const SPIKE_WINDOW: Duration = Duration::from_secs(60);
+
+/// 规则匹配器
+enum Matcher {
+    /// 关键字匹配（忽略大小写）
+    Keyword(String),
+    Regex(Regex),
+}
+
+impl Matcher {
+    fn is_match(&self, line: &str) -> bool {
+        match self {
+            Matcher::Keyword(keyword) => line.to_lowercase().contains(keyword),
+            Matcher::Regex(regex) => regex.is_match(line),
+        }
+    }
+}
+
+/// 异常检测事件
+#[derive(Debug, Clone)]
+pub enum AnomalyEvent {
+    /// 命中需要告警的规则
+    RuleMatched(AnomalyRule),
+    /// 统计窗口内异常日志数量超过阈值
+    Spike(usize),
+}
+
+/// 日志异常检测器
+pub struct AnomalyDetector {
+    rules: Vec<(AnomalyRule, Matcher)>,
+    spike_threshold: u32,
+    recent_hits: VecDeque<Instant>,
+    spike_active: bool,
+    /// 累计命中次数
+    pub hit_count: usize,
+}
+
+impl AnomalyDetector {
+    /// 创建新的检测器
+    pub fn new() -> Self {
+        Self {
+            rules: Vec::new(),
+            spike_threshold: 0,
+            recent_hits: VecDeque::new(),
+            spike_active: false,
+            hit_count: 0,
+        }
+    }
+
+    /// 更新检测规则，返回无效规则的错误信息
+    pub fn set_rules(&mut self, rules: &[AnomalyRule], spike_threshold: u32) -> Vec<String> {
+        let mut errors = Vec::new();
+        self.rules = rules
+            .iter()
+            .filter(|rule| rule.enabled && !rule.pattern.is_empty())
+            .filter_map(|rule| {
+                let matcher = if rule.is_regex {
+                    match Regex::new(&rule.pattern) {
+                        Ok(regex) => Matcher::Regex(regex),
+                        Err(e) => {
+                            errors.push(format!("规则 {} 的正则表达式无效: {}", rule.name, e));
+                            return None;
+                        }
+                    }
+                } else {
+                    Matcher::Keyword(rule.pattern.to_lowercase())
+                };
+                Some((rule.clone(), matcher))
+            })
+            .collect();
+        self.spike_threshold = spike_threshold;
+        errors
+    }
+
+    /// 匹配单行日志，返回第一个命中的规则
+    pub fn matches(&self, line: &str) -> Option<&AnomalyRule> {
+        self.rules
+            .iter()
+            .find(|(_, matcher)| matcher.is_match(line))
+            .map(|(rule, _)| rule)
+    }
+
+    /// 处理新日志行，记录命中并返回需要告警的事件
+    pub fn observe(&mut self, line: &str) -> Vec<AnomalyEvent> {
+        self.observe_at(line, Instant::now())
+    }
+
+    /// 按给定时间处理日志行
+    fn observe_at(&mut self, line: &str, now: Instant) -> Vec<AnomalyEvent> {
+        let rule = match self.matches(line) {
+            Some(rule) => rule.clone(),
+            None => return Vec::new(),
+        };
+
+        let mut events = Vec::new();
+        self.hit_count += 1;
+        if rule.raise_alert {
+            events.push(AnomalyEvent::RuleMatched(rule));
+        }
+
+        if self.spike_threshold > 0 {
+            self.recent_hits.push_back(now);
+            while self.recent_hits.front().is_some_and(|t| now.duration_since(*t) > SPIKE_WINDOW) {
+                self.recent_hits.pop_front();
+            }
+
+            let hits = self.recent_hits.len();
+            if hits >= self.spike_threshold as usize {
+                if !self.spike_active {
+                    self.spike_active = true;
+                    events.push(AnomalyEvent::Spike(hits));
+                }
+            } else if hits < (self.spike_threshold as usize).div_ceil(2) {
+                // 回落到阈值一半以下后才允许再次触发，避免反复告警
+                self.spike_active = false;
+            }
+        }
+
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::AlertSeverity;
+
+    fn detector(spike_threshold: u32) -> AnomalyDetector {
+        let mut detector = AnomalyDetector::new();
+        let alerting = AnomalyRule { raise_alert: true, ..AnomalyRule::keyword("panic", AlertSeverity::Critical) };
+        let regex = AnomalyRule { is_regex: true, ..AnomalyRule::keyword(r"status=5\d\d", AlertSeverity::Warning) };
+        let disabled = AnomalyRule { enabled: false, ..AnomalyRule::keyword("info", AlertSeverity::Info) };
+        assert!(detector.set_rules(&[alerting, regex, disabled], spike_threshold).is_empty());
+        detector
+    }
+
+    /// 从起点开始按给定的秒数依次处理日志行，返回每行触发的激增数量
+    fn spikes(detector: &mut AnomalyDetector, start: Instant, series: &[(u64, &str)]) -> Vec<Option<usize>> {
+        series
+            .iter()
+            .map(|(secs, line)| {
+                detector.observe_at(line, start + Duration::from_secs(*secs)).into_iter().find_map(|event| match event {
+                    AnomalyEvent::Spike(hits) => Some(hits),
+                    AnomalyEvent::RuleMatched(_) => None,
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn rules_match_keywords_and_regexes() {
+        let detector = detector(0);
+        assert_eq!(detector.matches("thread main PANIC at src/lib.rs").map(|r| r.pattern.as_str()), Some("panic"));
+        assert_eq!(detector.matches("GET /encrypt status=503").map(|r| r.severity), Some(AlertSeverity::Warning));
+        assert!(detector.matches("GET /encrypt status=200").is_none());
+        assert!(detector.matches("info: started").is_none());
+
+        let mut invalid = AnomalyDetector::new();
+        let errors = invalid.set_rules(&[AnomalyRule { is_regex: true, ..AnomalyRule::keyword("(", AlertSeverity::Info) }], 0);
+        assert_eq!(errors.len(), 1);
+        assert!(invalid.matches("(").is_none());
+    }
+
+    #[test]
+    fn only_alerting_rules_raise_events() {
+        let mut detector = detector(0);
+        let events = detector.observe("panic: index out of bounds");
+        assert!(matches!(events.as_slice(), [AnomalyEvent::RuleMatched(rule)] if rule.pattern == "panic"));
+        assert!(detector.observe("status=500").is_empty());
+        assert!(detector.observe("all good").is_empty());
+        assert_eq!(detector.hit_count, 2);
+    }
+
+    #[test]
+    fn steady_baseline_does_not_spike() {
+        let mut detector = detector(5);
+        // 每20秒一条异常日志，窗口内最多4条
+        let series: Vec<(u64, &str)> = (0..30).map(|i| (i * 20, "status=500")).collect();
+        assert!(spikes(&mut detector, Instant::now(), &series).iter().all(Option::is_none));
+        assert_eq!(detector.hit_count, 30);
+    }
+
+    #[test]
+    fn spike_fires_once_and_rearms_after_falling_back() {
+        let mut detector = detector(4);
+        let start = Instant::now();
+        let series = [
+            (0, "status=500"),
+            (1, "status=502"),
+            (2, "request ok"),
+            (3, "status=503"),
+            (4, "status=500"),
+            // 仍在激增中，不重复告警
+            (5, "status=500"),
+            (6, "status=500"),
+            // 窗口内只剩1条，低于阈值一半，解除激增
+            (120, "status=500"),
+            (121, "status=500"),
+            (122, "status=500"),
+            (123, "status=500"),
+        ];
+        assert_eq!(spikes(&mut detector, start, &series), [
+            None,
+            None,
+            None,
+            None,
+            Some(4),
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(4),
+        ]);
+    }
+
+    #[test]
+    fn zero_threshold_disables_spike_detection() {
+        let mut detector = detector(0);
+        let series: Vec<(u64, &str)> = (0..100).map(|_| (0, "status=500")).collect();
+        assert!(spikes(&mut detector, Instant::now(), &series).iter().all(Option::is_none));
+    }
+}
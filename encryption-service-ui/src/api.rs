@@ -1,14 +1,30 @@
-use anyhow::Result;
-use reqwest::{blocking::Client, StatusCode};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use reqwest::{Client, RequestBuilder, Certificate, Method, NoProxy, Proxy, StatusCode};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::net::{IpAddr, SocketAddr};
+use std::pin::Pin;
+use std::sync::{Arc, LazyLock, Mutex, RwLock};
+use std::task::{Context as TaskContext, Poll};
+use std::time::{Duration, Instant};
+use tower_layer::Layer;
+use tower_service::Service;
+use uuid::Uuid;
 
-use crate::models::{AppConfig, HealthStatus};
+use crate::models::{AppConfig, ConnectionPoolSettings, HealthStatus, HttpMethod, HttpVersionPreference, NetworkProfile, SavedRequest};
+use crate::tunnel;
 
 /// API客户端配置
 #[derive(Debug, Clone)]
 pub struct ApiClientConfig {
     pub base_url: String,
     pub timeout: u64,
+    /// 所在业务组的网络配置
+    pub network: Option<NetworkProfile>,
+    /// 发起本次操作的追踪ID，设置后随每个请求发送
+    pub trace_id: Option<String>,
 }
 
 /// API客户端
@@ -16,6 +32,246 @@ pub struct ApiClientConfig {
 pub struct ApiClient {
     client: Client,
     config: ApiClientConfig,
+    tracker: Arc<ClientTracker>,
+}
+
+/// 携带追踪ID的请求头
+pub const TRACE_HEADER: &str = "X-Trace-Id";
+
+/// 为一次界面发起的操作生成追踪ID
+pub fn new_trace_id() -> String {
+    Uuid::new_v4().simple().to_string()
+}
+
+/// 客户端在各处按需创建且分布在多个线程中，连接池设置与统计因此在进程内共享，
+/// 由界面在启动与修改设置时写入
+static POOL_SETTINGS: RwLock<Option<ConnectionPoolSettings>> = RwLock::new(None);
+static POOL_STATS: Mutex<BTreeMap<String, HostPoolStats>> = Mutex::new(BTreeMap::new());
+
+/// 按主机与网络配置ID缓存的底层客户端，同一目标的调用共用一个连接池；
+/// 网络配置修改或删除后对应的客户端被替换或移除
+static CLIENTS: LazyLock<Mutex<HashMap<ClientKey, CachedClient>>> = LazyLock::new(Default::default);
+
+/// 缓存键：（主机, 网络配置ID），不使用网络配置时ID为空
+type ClientKey = (String, Option<String>);
+
+struct CachedClient {
+    /// 创建时的网络配置，与调用时的不同说明配置已修改，需按新配置重建
+    network: Option<NetworkProfile>,
+    client: Client,
+    tracker: Arc<ClientTracker>,
+}
+
+/// 设置客户端使用的连接池参数，设置变化时丢弃缓存的客户端，之后的调用按新设置重建
+pub fn set_pool_settings(settings: ConnectionPoolSettings) {
+    if let Ok(mut current) = POOL_SETTINGS.write() {
+        if current.as_ref() == Some(&settings) {
+            return;
+        }
+        *current = Some(settings);
+    }
+    if let Ok(mut clients) = CLIENTS.lock() {
+        clients.clear();
+    }
+}
+
+/// 丢弃使用该网络配置的缓存客户端，网络配置修改或删除后调用
+pub fn evict_network_profile(profile_id: &str) {
+    if let Ok(mut clients) = CLIENTS.lock() {
+        clients.retain(|(_, id), _| id.as_deref() != Some(profile_id));
+    }
+}
+
+/// 取得目标主机与网络配置对应的缓存客户端，没有或网络配置已修改时按当前连接池设置新建
+fn cached_client(host: &str, network: Option<&NetworkProfile>) -> Result<(Client, Arc<ClientTracker>)> {
+    let mut clients = CLIENTS.lock().unwrap_or_else(|e| e.into_inner());
+    let key = (host.to_string(), network.map(|n| n.id.clone()));
+    if let Some(cached) = clients.get(&key).filter(|c| c.network.as_ref() == network) {
+        return Ok((cached.client.clone(), cached.tracker.clone()));
+    }
+    let mut builder = Client::builder().connector_layer(ConnectCounter { host: host.into() });
+    let pool = POOL_SETTINGS.read().ok().and_then(|p| p.clone()).unwrap_or_default();
+    builder = apply_pool(builder, &pool);
+    if let Some(network) = network {
+        builder = apply_network(builder, network)?;
+    }
+    let client = builder.build()?;
+    let tracker = Arc::new(ClientTracker::new(host.to_string()));
+    // 替换掉的旧客户端在仍持有它的调用结束后释放，存活数随之减少
+    clients.insert(key, CachedClient {
+        network: network.cloned(),
+        client: client.clone(),
+        tracker: tracker.clone(),
+    });
+    Ok((client, tracker))
+}
+
+/// 各主机的连接统计，按主机排序
+pub fn pool_stats() -> Vec<(String, HostPoolStats)> {
+    POOL_STATS
+        .lock()
+        .map(|stats| stats.iter().map(|(host, s)| (host.clone(), s.clone())).collect())
+        .unwrap_or_default()
+}
+
+/// 清空连接统计，仍存活的客户端数保留
+pub fn reset_pool_stats() {
+    if let Ok(mut stats) = POOL_STATS.lock() {
+        stats.retain(|_, s| s.clients > 0);
+        for s in stats.values_mut() {
+            *s = HostPoolStats { clients: s.clients, ..HostPoolStats::default() };
+        }
+    }
+}
+
+fn with_stats(host: &str, update: impl FnOnce(&mut HostPoolStats)) {
+    if let Ok(mut stats) = POOL_STATS.lock() {
+        update(stats.entry(host.to_string()).or_default());
+    }
+}
+
+/// 单个主机的连接统计
+#[derive(Debug, Clone, Default)]
+pub struct HostPoolStats {
+    /// 存活的客户端数，每个客户端各有一个连接池
+    pub clients: usize,
+    /// 发出的请求数
+    pub requests: u64,
+    /// 新建的连接数，其余请求复用了池中的连接
+    pub connections: u64,
+    /// 正在建立的连接数
+    pub connecting: usize,
+    pub connect_failures: u64,
+    /// 建立连接的累计耗时
+    pub connect_ms_total: f64,
+    pub last_request: Option<DateTime<Utc>>,
+}
+
+impl HostPoolStats {
+    /// 请求复用已有连接的比例
+    pub fn reuse_rate(&self) -> Option<f64> {
+        (self.requests > 0).then(|| 1.0 - (self.connections.min(self.requests) as f64 / self.requests as f64))
+    }
+
+    /// 建立连接的平均耗时
+    pub fn avg_connect_ms(&self) -> Option<f64> {
+        let completed = self.connections + self.connect_failures;
+        (completed > 0).then(|| self.connect_ms_total / completed as f64)
+    }
+}
+
+/// 随客户端的最后一个副本释放，用于统计存活的客户端
+#[derive(Debug)]
+struct ClientTracker {
+    host: String,
+}
+
+impl ClientTracker {
+    fn new(host: String) -> Self {
+        with_stats(&host, |s| s.clients += 1);
+        Self { host }
+    }
+
+    fn record_request(&self) {
+        with_stats(&self.host, |s| {
+            s.requests += 1;
+            s.last_request = Some(Utc::now());
+        });
+    }
+}
+
+impl Drop for ClientTracker {
+    fn drop(&mut self) {
+        with_stats(&self.host, |s| s.clients = s.clients.saturating_sub(1));
+    }
+}
+
+/// 统计连接建立次数与耗时的连接器中间层；连接器只在池中没有可用连接时被调用
+#[derive(Clone)]
+struct ConnectCounter {
+    host: Arc<str>,
+}
+
+impl<S> Layer<S> for ConnectCounter {
+    type Service = CountingConnector<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        CountingConnector { inner, host: self.host.clone() }
+    }
+}
+
+#[derive(Clone)]
+struct CountingConnector<S> {
+    inner: S,
+    host: Arc<str>,
+}
+
+impl<S, R> Service<R> for CountingConnector<S>
+where
+    S: Service<R>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut TaskContext<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: R) -> Self::Future {
+        let host = self.host.clone();
+        with_stats(&host, |s| s.connecting += 1);
+        let started = Instant::now();
+        let connecting = self.inner.call(request);
+        Box::pin(async move {
+            let result = connecting.await;
+            let elapsed = started.elapsed().as_secs_f64() * 1000.0;
+            with_stats(&host, |s| {
+                s.connecting = s.connecting.saturating_sub(1);
+                s.connect_ms_total += elapsed;
+                if result.is_ok() {
+                    s.connections += 1;
+                } else {
+                    s.connect_failures += 1;
+                }
+            });
+            result
+        })
+    }
+}
+
+/// 统计所用的主机键，取地址中的主机与端口
+fn host_key(base_url: &str) -> String {
+    match reqwest::Url::parse(base_url) {
+        Ok(url) => match (url.host_str(), url.port_or_known_default()) {
+            (Some(host), Some(port)) => format!("{}:{}", host, port),
+            (Some(host), None) => host.to_string(),
+            _ => base_url.to_string(),
+        },
+        Err(_) => base_url.to_string(),
+    }
+}
+
+/// 按连接池设置调整客户端
+fn apply_pool(mut builder: reqwest::ClientBuilder, pool: &ConnectionPoolSettings) -> reqwest::ClientBuilder {
+    if pool.max_idle_per_host > 0 {
+        builder = builder.pool_max_idle_per_host(pool.max_idle_per_host);
+    }
+    builder = builder.pool_idle_timeout((pool.idle_timeout_secs > 0).then(|| Duration::from_secs(pool.idle_timeout_secs)));
+    if pool.tcp_keepalive_secs > 0 {
+        builder = builder.tcp_keepalive(Duration::from_secs(pool.tcp_keepalive_secs));
+    }
+    builder = builder.tcp_nodelay(pool.tcp_nodelay);
+    builder = match pool.http_version {
+        HttpVersionPreference::Auto => builder,
+        HttpVersionPreference::Http1Only => builder.http1_only(),
+        HttpVersionPreference::Http2PriorKnowledge => builder.http2_prior_knowledge(),
+    };
+    if pool.http2_adaptive_window {
+        builder = builder.http2_adaptive_window(true);
+    }
+    builder
 }
 
 /// 健康检查响应
@@ -51,58 +307,126 @@ pub struct DecryptResponse {
     pub data: String,
 }
 
+/// Agent命令执行请求
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AgentExecRequest {
+    pub command: String,
+}
+
+/// Agent命令执行响应
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AgentExecResponse {
+    pub exit_code: i32,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// 调试请求的原始响应
+#[derive(Debug, Clone)]
+pub struct RawResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+    pub elapsed_ms: f64,
+}
+
+/// 按网络配置设置代理、解析覆盖、证书与超时
+fn apply_network(mut builder: reqwest::ClientBuilder, network: &NetworkProfile) -> Result<reqwest::ClientBuilder> {
+    if !network.proxy.trim().is_empty() {
+        let proxy = Proxy::all(network.proxy.trim())
+            .with_context(|| format!("网络配置 {} 的代理地址无效: {}", network.name, network.proxy))?
+            .no_proxy(NoProxy::from_string(&network.no_proxy));
+        builder = builder.proxy(proxy);
+    }
+    for entry in &network.dns_overrides {
+        let address: IpAddr = entry
+            .address
+            .trim()
+            .parse()
+            .with_context(|| format!("网络配置 {} 中 {} 的解析地址无效: {}", network.name, entry.host, entry.address))?;
+        // 端口取自请求地址，此处的端口不生效
+        // 请求地址中的主机名已转为小写，覆盖表需一致才能命中
+        builder = builder.resolve(&entry.host.trim().to_ascii_lowercase(), SocketAddr::new(address, 0));
+    }
+    if !network.ca_cert_path.trim().is_empty() {
+        let path = network.ca_cert_path.trim();
+        let pem = std::fs::read(path).with_context(|| format!("无法读取CA证书: {}", path))?;
+        let certificate = Certificate::from_pem(&pem).with_context(|| format!("无法解析CA证书: {}", path))?;
+        builder = builder.add_root_certificate(certificate);
+    }
+    if network.accept_invalid_certs {
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+    if network.connect_timeout_ms > 0 {
+        builder = builder.connect_timeout(Duration::from_millis(network.connect_timeout_ms));
+    }
+    if network.request_timeout_ms > 0 {
+        builder = builder.timeout(Duration::from_millis(network.request_timeout_ms));
+    }
+    Ok(builder)
+}
+
 impl ApiClient {
     /// 创建新的API客户端
-    pub fn new(config: ApiClientConfig) -> Result<Self> {
-        let client = Client::builder()
-            .timeout(std::time::Duration::from_millis(config.timeout))
-            .build()?;
-        
-        Ok(Self {
-            client,
-            config,
-        })
+    pub fn new(mut config: ApiClientConfig) -> Result<Self> {
+        let host = host_key(&config.base_url);
+        // 经SSH隧道访问的目标改用本机转发地址，连接统计仍按原目标归类
+        config.base_url = tunnel::route(&config.base_url);
+        let (client, tracker) = cached_client(&host, config.network.as_ref())?;
+        Ok(Self { client, config, tracker })
     }
     
-    /// 获取配置
-    pub fn get_config(&self) -> Result<AppConfig> {
-        let url = format!("{}/config", self.config.base_url);
-        
-        let response = self.client
-            .get(&url)
-            .send()?;
-        
-        if response.status() != StatusCode::OK {
-            anyhow::bail!("获取配置失败: {} {}", response.status(), response.text()?);
+    /// 创建请求并计入连接统计，超时按本客户端的配置逐个请求设置
+    fn request(&self, method: Method, url: &str) -> RequestBuilder {
+        self.tracker.record_request();
+        let builder = self.client
+            .request(method, url)
+            .timeout(Duration::from_millis(self.config.timeout));
+        match &self.config.trace_id {
+            Some(trace_id) => builder.header(TRACE_HEADER, trace_id),
+            None => builder,
         }
-        
-        let config = response.json()?;
-        Ok(config)
     }
     
     /// 更新配置
-    pub fn update_config(&self, config: &AppConfig) -> Result<()> {
+    #[tracing::instrument(name = "api.update_config", skip_all, fields(base_url = %self.config.base_url, trace_id = self.config.trace_id.as_deref()), err(level = "debug"))]
+    pub async fn update_config(&self, config: &AppConfig) -> Result<()> {
         let url = format!("{}/config", self.config.base_url);
         
-        let response = self.client
-            .put(&url)
+        let response = self.request(Method::PUT, &url)
             .json(config)
-            .send()?;
+            .send().await?;
         
         if response.status() != StatusCode::OK {
-            anyhow::bail!("更新配置失败: {} {}", response.status(), response.text()?);
+            anyhow::bail!("更新配置失败: {} {}", response.status(), response.text().await?);
         }
         
         Ok(())
     }
     
+    /// 请求指定路径的健康探测，返回2xx视为健康
+    #[tracing::instrument(name = "api.probe", skip_all, fields(base_url = %self.config.base_url, path = path, trace_id = self.config.trace_id.as_deref()), err(level = "debug"))]
+    pub async fn probe(&self, path: &str) -> Result<()> {
+        let path = path.trim();
+        let url = if path.starts_with('/') {
+            format!("{}{}", self.config.base_url, path)
+        } else {
+            format!("{}/{}", self.config.base_url, path)
+        };
+        let response = self.request(Method::GET, &url).send().await?;
+        if !response.status().is_success() {
+            anyhow::bail!("探测返回 {}", response.status());
+        }
+        Ok(())
+    }
+    
     /// 健康检查
-    pub fn health_check(&self) -> Result<HealthStatus> {
+    #[tracing::instrument(name = "api.health_check", skip_all, fields(base_url = %self.config.base_url, trace_id = self.config.trace_id.as_deref()), err(level = "debug"))]
+    pub async fn health_check(&self) -> Result<HealthStatus> {
         let url = format!("{}/health", self.config.base_url);
         
-        let response = self.client
-            .get(&url)
-            .send()?;
+        let response = self.request(Method::GET, &url)
+            .send().await?;
         
         if response.status() == StatusCode::OK {
             Ok(HealthStatus::Healthy)
@@ -112,91 +436,183 @@ impl ApiClient {
     }
     
     /// 获取状态
-    pub fn get_status(&self) -> Result<HealthCheckResponse> {
+    #[tracing::instrument(name = "api.get_status", skip_all, fields(base_url = %self.config.base_url, trace_id = self.config.trace_id.as_deref()), err(level = "debug"))]
+    pub async fn get_status(&self) -> Result<HealthCheckResponse> {
         let url = format!("{}/health", self.config.base_url);
         
-        let response = self.client
-            .get(&url)
-            .send()?;
+        let response = self.request(Method::GET, &url)
+            .send().await?;
         
         if response.status() != StatusCode::OK {
-            anyhow::bail!("获取状态失败: {} {}", response.status(), response.text()?);
+            anyhow::bail!("获取状态失败: {} {}", response.status(), response.text().await?);
         }
         
-        let status = response.json()?;
+        let status = response.json().await?;
         Ok(status)
     }
     
-    /// 重启服务
-    pub fn restart(&self) -> Result<()> {
-        let url = format!("{}/restart", self.config.base_url);
-        
-        let response = self.client
-            .post(&url)
-            .send()?;
-        
-        if response.status() != StatusCode::OK {
-            anyhow::bail!("重启服务失败: {} {}", response.status(), response.text()?);
-        }
-        
-        Ok(())
-    }
-    
     /// 加密数据
-    pub fn encrypt(&self, data: &str) -> Result<String> {
+    #[tracing::instrument(name = "api.encrypt", skip_all, fields(base_url = %self.config.base_url, trace_id = self.config.trace_id.as_deref()), err(level = "debug"))]
+    pub async fn encrypt(&self, data: &str) -> Result<String> {
         let url = format!("{}/encrypt", self.config.base_url);
         
         let request = EncryptRequest {
             data: data.to_string(),
         };
         
-        let response = self.client
-            .post(&url)
+        let response = self.request(Method::POST, &url)
             .json(&request)
-            .send()?;
+            .send().await?;
         
         if response.status() != StatusCode::OK {
-            anyhow::bail!("加密失败: {} {}", response.status(), response.text()?);
+            anyhow::bail!("加密失败: {} {}", response.status(), response.text().await?);
         }
         
-        let result: EncryptResponse = response.json()?;
+        let result: EncryptResponse = response.json().await?;
         Ok(result.encrypted_data)
     }
     
     /// 解密数据
-    pub fn decrypt(&self, encrypted_data: &str) -> Result<String> {
+    #[tracing::instrument(name = "api.decrypt", skip_all, fields(base_url = %self.config.base_url, trace_id = self.config.trace_id.as_deref()), err(level = "debug"))]
+    pub async fn decrypt(&self, encrypted_data: &str) -> Result<String> {
         let url = format!("{}/decrypt", self.config.base_url);
         
         let request = DecryptRequest {
             encrypted_data: encrypted_data.to_string(),
         };
         
-        let response = self.client
-            .post(&url)
+        let response = self.request(Method::POST, &url)
             .json(&request)
-            .send()?;
+            .send().await?;
         
         if response.status() != StatusCode::OK {
-            anyhow::bail!("解密失败: {} {}", response.status(), response.text()?);
+            anyhow::bail!("解密失败: {} {}", response.status(), response.text().await?);
         }
         
-        let result: DecryptResponse = response.json()?;
+        let result: DecryptResponse = response.json().await?;
         Ok(result.data)
     }
     
     /// 获取日志
-    pub fn get_logs(&self, limit: u32) -> Result<Vec<String>> {
+    #[tracing::instrument(name = "api.get_logs", skip_all, fields(base_url = %self.config.base_url, trace_id = self.config.trace_id.as_deref()), err(level = "debug"))]
+    pub async fn get_logs(&self, limit: u32) -> Result<Vec<String>> {
         let url = format!("{}/logs?limit={}", self.config.base_url, limit);
         
-        let response = self.client
-            .get(&url)
-            .send()?;
+        let response = self.request(Method::GET, &url)
+            .send().await?;
         
         if response.status() != StatusCode::OK {
-            anyhow::bail!("获取日志失败: {} {}", response.status(), response.text()?);
+            anyhow::bail!("获取日志失败: {} {}", response.status(), response.text().await?);
         }
         
-        let logs: Vec<String> = response.json()?;
+        let logs: Vec<String> = response.json().await?;
         Ok(logs)
     }
+    
+    /// 获取指标（Prometheus文本格式）
+    #[tracing::instrument(name = "api.get_metrics", skip_all, fields(base_url = %self.config.base_url, trace_id = self.config.trace_id.as_deref()), err(level = "debug"))]
+    pub async fn get_metrics(&self) -> Result<String> {
+        let url = format!("{}/metrics", self.config.base_url);
+        
+        let response = self.request(Method::GET, &url)
+            .send().await?;
+        
+        if response.status() != StatusCode::OK {
+            anyhow::bail!("获取指标失败: {} {}", response.status(), response.text().await?);
+        }
+        
+        Ok(response.text().await?)
+    }
+    
+    /// 通过主机Agent执行命令
+    #[tracing::instrument(name = "api.agent_exec", skip_all, fields(base_url = %self.config.base_url, trace_id = self.config.trace_id.as_deref()), err(level = "debug"))]
+    pub async fn agent_exec(&self, command: &str) -> Result<AgentExecResponse> {
+        let url = format!("{}/agent/exec", self.config.base_url);
+        
+        let request = AgentExecRequest {
+            command: command.to_string(),
+        };
+        
+        let response = self.request(Method::POST, &url)
+            .json(&request)
+            .send().await?;
+        
+        if response.status() != StatusCode::OK {
+            anyhow::bail!("执行命令失败: {} {}", response.status(), response.text().await?);
+        }
+        
+        let result = response.json().await?;
+        Ok(result)
+    }
+    
+    /// 发送任意请求，任何状态码都作为响应返回
+    #[tracing::instrument(name = "api.send_raw", skip_all, fields(base_url = %self.config.base_url, trace_id = self.config.trace_id.as_deref()), err(level = "debug"))]
+    pub async fn send_raw(&self, request: &SavedRequest) -> Result<RawResponse> {
+        let url = format!("{}/{}", self.config.base_url.trim_end_matches('/'), request.path.trim_start_matches('/'));
+        let method = match request.method {
+            HttpMethod::Get => Method::GET,
+            HttpMethod::Post => Method::POST,
+            HttpMethod::Put => Method::PUT,
+            HttpMethod::Patch => Method::PATCH,
+            HttpMethod::Delete => Method::DELETE,
+        };
+        
+        let mut builder = self.request(method, &url);
+        for (name, value) in &request.headers {
+            if !name.trim().is_empty() {
+                builder = builder.header(name.trim(), value);
+            }
+        }
+        if !request.body.is_empty() {
+            builder = builder.body(request.body.clone());
+        }
+        
+        let started = std::time::Instant::now();
+        let response = builder.send().await?;
+        let status = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_str().unwrap_or("<二进制>").to_string()))
+            .collect();
+        let body = response.text().await?;
+        
+        Ok(RawResponse {
+            status,
+            headers,
+            body,
+            elapsed_ms: started.elapsed().as_secs_f64() * 1000.0,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn live_clients(host: &str) -> usize {
+        pool_stats().into_iter().find(|(h, _)| h == host).map_or(0, |(_, s)| s.clients)
+    }
+
+    #[test]
+    fn cached_clients_follow_network_profile_changes() {
+        let host = "client-cache.test:443";
+        let mut profile = NetworkProfile::new("lab");
+        let first = cached_client(host, Some(&profile)).map(|(_, tracker)| tracker).unwrap();
+        let again = cached_client(host, Some(&profile)).map(|(_, tracker)| tracker).unwrap();
+        assert!(Arc::ptr_eq(&first, &again));
+        drop((first, again));
+        assert_eq!(live_clients(host), 1);
+
+        // 修改后的配置替换原客户端而不是追加
+        profile.connect_timeout_ms = 500;
+        let changed = cached_client(host, Some(&profile)).map(|(_, tracker)| tracker).unwrap();
+        assert_eq!(live_clients(host), 1);
+        drop(changed);
+
+        cached_client(host, None).unwrap();
+        assert_eq!(live_clients(host), 2);
+        evict_network_profile(&profile.id);
+        assert_eq!(live_clients(host), 1);
+    }
 }
@@ -1,19 +1,317 @@
 use eframe::{egui::{self, CentralPanel, SidePanel, TopBottomPanel, Window, RichText, ScrollArea, CollapsingHeader}, epaint::{Color32}};
-use chrono::Utc;
+use chrono::{DateTime, Datelike, Local, NaiveDate, Utc};
+use egui_plot::{Bar, BarChart, Legend, Line, Plot, PlotPoints, VLine};
+use std::cell::RefCell;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::mpsc::Receiver;
+use std::time::{Duration, Instant, SystemTime};
 
-use crate::models::{BusinessGroup, MiddlewareContainer, BackendContainer, GroupStatus, ContainerStatus, HealthStatus, SchedulerStrategy};
-use crate::services::{BusinessGroupService, MiddlewareService, BackendService, ApiService};
-use crate::config::{ConfigManager, Config};
+use crate::models::{Alert, AppConfig, BackendStartOrder, OperationPlan, PlannedAction, ResourceLimits, ReadScaleOut, MAX_SCALE_OUT, ScaleInSelection, JobCheckpoint, RestartMode, SshTunnel, AgentSettings, HealthProbe, ProbeKind, ConfigPreset, AlertPolicy, AllowedCommand, AuditEntry, StatusTransition, TransitionField, AppConfigField, JobKind, JobRecord, JobStatus, RetryPolicy, validate_ports, validate_volumes, PortConflict, AlertSeverity, AnomalyRule, DashboardWidget, DashboardWidgetKind, EntityJson, LogEntry, LogEvidence, BusinessGroup, MiddlewareContainer, BackendContainer, GroupStatus, ContainerStatus, HealthStatus, HttpMethod, RequestCollection, SavedRequest, SchedulerStrategy, ContainerSpec, ContainerEngine, DockerConnection, DockerHost, GroupDockerNetwork, HistoryRedaction, PlaygroundHistoryEntry, AutoRestartMode, AutoRestartPolicy, ConnectionPoolSettings, ContainerRestartPolicy, DnsOverride, HttpVersionPreference, EnvVar, OtlpSettings, NetworkProfile, PortMapping, PolicyImportMode, UiTheme, DiscoveryTtl, RuntimeKind, VolumeMount, WeightAdjustment, Webhook, WebhookAction, UiProfile, UiRoleAssignment, UiSession, LocalUser, PasswordPolicy, CostSettings, GroupAccess, GroupId, MiddlewareId, BackendId, ui_profile_for};
+use crate::services::{AgentAction, ContainerAction, ContainerActionService, AgentService, TunnelService, BusinessGroupService, MiddlewareService, BackendService, ApiService, MetricsService, AlertService, AuditService, CommandService, RoleService, HealthService, JobService, KubernetesService, WebhookService, MigrationService, PlaygroundService, PayloadService, VerificationService, WarmupService, WeightService, DockerService, ImageService, NetworkService, PendingStart, StatsService, LogStreamService, ImageUpdateService, StatusHistoryService, ConsoleService, ConsoleStream, SupervisorService, TelemetryService, ConfigPresetService, TraceSearch, TraceService, SessionCheck, SessionService, LoginOutcome, UserService};
+use crate::anomaly::{AnomalyDetector, AnomalyEvent};
+use crate::topology::{self, TopologyFormat};
+use crate::scheduler;
+use crate::jobs::{self, RollingProgress, StartProgress};
+use crate::tunnel::TunnelStatus;
+use crate::docker;
+use crate::image_export::{self, ImageFormat};
+use crate::metrics::{EndpointKind, RatePoint};
+use crate::api::{self, RawResponse};
+use crate::verification::{self, VerificationReport};
+use crate::migration::{Migration, MigrationStep};
+use crate::systemd::{self, RestartPolicy, UnitOptions};
+use crate::terraform::TerraformProvider;
+use crate::chunking::PayloadMode;
+use crate::kubernetes::{self, Workload};
+use crate::webhook::{self, WebhookEvent, WebhookServer};
+use crate::config::{self, ConfigManager, Config, PreferencesManager, UserPreferences};
+use crate::compose;
+use crate::state::{AutoSaver, StateStore};
+use crate::telemetry;
+use crate::events::{EntityKind, EventBus, ModelEvent};
+use crate::json_editor::{JsonEditor, SchemaNode};
+use crate::upstream::{UpstreamBlock, UpstreamFormat};
+use crate::audit_export::{AuditChainStatus, AuditExportFormat, AuditFilter, AuditIntegrity};
+use crate::reconcile::{Drift, Orphan, ReconcileReport};
+use crate::snapshot;
+use crate::password;
+use crate::repaint::{self, RepaintSource};
+use crate::cost::CostEstimator;
+use crate::diagnostics::{self, Metric, MetricSummary};
+use crate::capacity::{self, CapacityInput, InstanceEstimate, SimInstance, Topology};
+use crate::polling::{PollCoordinator, PollEndpoint, PollResponse, Subscription};
+use crate::tuning::{self, BackendTuning, LatencyDistribution, TuningSuggestion};
 
 /// 应用状态枚举
 #[derive(Debug, Clone, PartialEq, Eq)]
 enum AppTab {
+    Dashboard,
     BusinessGroups,
     Middleware,
     Backend,
     Config,
     Monitor,
     Logs,
+    Jobs,
+    Playground,
+    Verify,
+    Migration,
+    DockerHosts,
+    Network,
+    Console,
+}
+
+impl AppTab {
+    /// 所有标签页，按导航中的顺序排列
+    const ALL: [AppTab; 14] = [
+        AppTab::Dashboard,
+        AppTab::BusinessGroups,
+        AppTab::Middleware,
+        AppTab::Backend,
+        AppTab::Config,
+        AppTab::Monitor,
+        AppTab::Logs,
+        AppTab::Jobs,
+        AppTab::Playground,
+        AppTab::Verify,
+        AppTab::Migration,
+        AppTab::DockerHosts,
+        AppTab::Network,
+        AppTab::Console,
+    ];
+
+    /// 显示名称
+    fn label(&self) -> &'static str {
+        match self {
+            AppTab::Dashboard => "仪表盘",
+            AppTab::BusinessGroups => "业务组",
+            AppTab::Middleware => "中间层",
+            AppTab::Backend => "后端",
+            AppTab::Config => "配置",
+            AppTab::Monitor => "监控",
+            AppTab::Logs => "日志",
+            AppTab::Jobs => "任务",
+            AppTab::Playground => "接口调试",
+            AppTab::Verify => "数据校验",
+            AppTab::Migration => "迁移",
+            AppTab::DockerHosts => "Docker主机",
+            AppTab::Network => "网络配置",
+            AppTab::Console => "容器控制台",
+        }
+    }
+
+    /// 在界面配置下是否可见，审计视图只显示监控与日志（含审计日志）
+    fn visible_in(&self, profile: UiProfile) -> bool {
+        match profile {
+            UiProfile::Full | UiProfile::Operator => true,
+            UiProfile::Auditor => matches!(self, AppTab::Monitor | AppTab::Logs),
+        }
+    }
+}
+
+/// 配置字段批量下发的待确认操作
+struct ConfigPropagation {
+    field: AppConfigField,
+    value: String,
+    /// 发起下发的中间层
+    source_id: String,
+    /// 选中的目标中间层
+    targets: BTreeSet<String>,
+}
+
+/// 待确认的扩容读实例操作
+struct ScaleOutDraft {
+    group_id: String,
+    middleware_id: String,
+    plan: ReadScaleOut,
+}
+
+/// 待确认的缩容读实例操作
+struct ScaleInDraft {
+    group_id: String,
+    middleware_id: String,
+    count: usize,
+    selection: ScaleInSelection,
+}
+
+/// 从负载均衡配置导入后端的草稿
+struct UpstreamImportDraft {
+    middleware_id: String,
+    format: UpstreamFormat,
+    path: String,
+    blocks: Vec<UpstreamBlock>,
+    /// 选中的服务器：（块序号, 服务器序号）
+    selected: BTreeSet<(usize, usize)>,
+}
+
+/// 待确认的业务组快照
+struct SnapshotDraft {
+    group_id: String,
+    group_name: String,
+    path: String,
+    include_volumes: bool,
+    /// 组内容器挂载的命名卷
+    volumes: Vec<String>,
+}
+
+/// 由快照恢复业务组的表单
+struct SnapshotRestoreForm {
+    path: String,
+    /// 目标Docker主机，本机为空
+    host_id: Option<String>,
+    /// 恢复后的名称，为空时沿用快照中的名称
+    name: String,
+    restore_volumes: bool,
+}
+
+/// 审计日志的筛选与导出表单
+struct AuditExportForm {
+    /// 起止日期，格式为 YYYY-MM-DD，留空不限
+    from: String,
+    to: String,
+    actor: String,
+    entity: String,
+    action: String,
+    path: String,
+    format: AuditExportFormat,
+    integrity: AuditIntegrity,
+    /// HMAC签名密钥，只保存在内存中
+    key: String,
+}
+
+/// 后端超时与重试调优对话框
+#[derive(Default)]
+struct TuningDialog {
+    /// 选中要应用建议值的后端ID
+    selected: HashSet<String>,
+    /// 显示延迟分布的后端ID
+    focus: Option<String>,
+}
+
+/// 调优对话框中的一个后端
+struct TuningRow {
+    group_id: String,
+    middleware_id: Option<String>,
+    backend: BackendContainer,
+    /// 所在位置，如 `业务组 / 中间层`
+    location: String,
+    distribution: Option<LatencyDistribution>,
+    error_percent: f64,
+    suggestion: Option<TuningSuggestion>,
+    manageable: bool,
+}
+
+/// 各面板对中间层接口的订阅：（面板, 中间层ID）到（订阅, 最近使用时间）
+type PanelSubscriptions = HashMap<(&'static str, String), (Subscription, Instant)>;
+
+/// 本地用户的登录表单，密码只保存在内存中
+#[derive(Default)]
+struct LoginForm {
+    name: String,
+    password: String,
+    /// 须修改密码时的原因，此时填写新密码
+    change_reason: Option<String>,
+    new_password: String,
+    confirm: String,
+    error: Option<String>,
+}
+
+/// 侧边栏业务组树展开后的一行，按下标引用业务组中的实体
+#[derive(Debug, Clone, Copy)]
+enum TreeRow {
+    Group(usize),
+    Middleware(usize, usize),
+    /// （业务组, 所在中间层, 后端）
+    Backend(usize, Option<usize>, usize),
+}
+
+/// 执行计划确认后执行的业务组操作
+enum PlannedOperation {
+    Start,
+    Stop,
+    /// （容器ID, 目标镜像）
+    Upgrade(Vec<(String, String)>),
+}
+
+/// 待确认的执行计划
+struct PlanConfirmation {
+    /// 计划涉及的业务组（ID, 名称），在仪表盘上启停全部业务组时有多个
+    groups: Vec<(String, String)>,
+    plan: OperationPlan,
+    operation: PlannedOperation,
+}
+
+/// 正在编辑的容器定义
+struct ContainerSpecDraft {
+    /// 所属中间层或后端的ID
+    entity_id: String,
+    spec: ContainerSpec,
+    /// 以shell语法编辑的启动命令
+    command: String,
+}
+
+/// 校验字段取值是否合法
+fn field_validation(field: AppConfigField, value: &str) -> anyhow::Result<()> {
+    let mut probe = MiddlewareContainer::default().config;
+    field.set(&mut probe, value)
+}
+
+/// 仪表盘组件触发的操作
+#[derive(Debug, Clone)]
+enum DashboardAction {
+    NewGroup,
+    ScrapeMetrics,
+    AcknowledgeAlert(String),
+    StartAllGroups,
+    StopAllGroups,
+}
+
+/// 状态快速筛选
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StatusFilter {
+    Running,
+    Stopped,
+    Error,
+    Unhealthy,
+}
+
+impl StatusFilter {
+    const ALL: [StatusFilter; 4] = [
+        StatusFilter::Running,
+        StatusFilter::Stopped,
+        StatusFilter::Error,
+        StatusFilter::Unhealthy,
+    ];
+    
+    /// 显示名称
+    fn label(&self) -> &'static str {
+        match self {
+            StatusFilter::Running => "运行中",
+            StatusFilter::Stopped => "已停止",
+            StatusFilter::Error => "错误",
+            StatusFilter::Unhealthy => "不健康",
+        }
+    }
+    
+    /// 容器是否匹配
+    fn matches_container(&self, status: &ContainerStatus, health: &HealthStatus) -> bool {
+        match self {
+            StatusFilter::Running => *status == ContainerStatus::Running,
+            StatusFilter::Stopped => *status == ContainerStatus::Stopped,
+            StatusFilter::Error => *status == ContainerStatus::Error,
+            StatusFilter::Unhealthy => *health == HealthStatus::Unhealthy,
+        }
+    }
+    
+    /// 业务组自身状态是否匹配
+    fn matches_group(&self, status: &GroupStatus) -> bool {
+        match self {
+            StatusFilter::Running => *status == GroupStatus::Running,
+            StatusFilter::Stopped => *status == GroupStatus::Stopped,
+            StatusFilter::Error => *status == GroupStatus::Error,
+            StatusFilter::Unhealthy => *status == GroupStatus::Degraded,
+        }
+    }
 }
 
 /// 应用结构体
@@ -49,11 +347,417 @@ pub struct App {
     /// 新建后端数据
     new_backend: BackendContainer,
     /// 日志列表
-    logs: Vec<String>,
+    logs: Vec<LogEntry>,
     /// 配置管理
     config_manager: ConfigManager,
+    /// 指标采集服务
+    metrics_service: MetricsService,
+    /// 是否自动采集指标
+    metrics_auto_scrape: bool,
+    /// 自动采集间隔（秒）
+    metrics_scrape_interval: u64,
+    /// 上次采集指标的时间
+    last_metrics_scrape: Option<Instant>,
+    /// 告警服务
+    alert_service: AlertService,
+    /// 告警列表
+    alerts: Vec<Alert>,
+    /// 日志异常检测器
+    anomaly_detector: AnomalyDetector,
+    /// 正在编辑的异常检测规则
+    anomaly_rules: Vec<AnomalyRule>,
+    /// 异常日志激增阈值
+    anomaly_spike_threshold: u32,
+    /// 异常检测规则错误信息
+    anomaly_rule_errors: Vec<String>,
+    /// 是否只显示异常日志
+    show_only_anomalies: bool,
+    /// 选中的日志行索引
+    selected_log_indices: BTreeSet<usize>,
+    /// 证据要附加到的告警ID，为空时新建事件
+    evidence_alert_id: Option<String>,
+    /// 证据关联实体
+    evidence_entity: String,
+    /// 证据备注
+    evidence_note: String,
+    /// 拓扑导出格式
+    topology_format: TopologyFormat,
+    /// 等待截图的区域及PNG保存路径
+    pending_screenshot: Option<(egui::Rect, String)>,
+    /// 启用的状态筛选，为空时不筛选
+    status_filters: Vec<StatusFilter>,
+    /// 用户偏好文件管理器
+    preferences_manager: PreferencesManager,
+    /// 当前用户的界面偏好
+    preferences: UserPreferences,
+    /// 仪表盘组件布局
+    dashboard_widgets: Vec<DashboardWidget>,
+    /// 仪表盘网格列数
+    dashboard_columns: u32,
+    /// 是否处于布局编辑模式
+    dashboard_editing: bool,
+    /// 待添加组件在目录中的索引
+    new_widget_kind: usize,
+    /// 正在编辑配置的中间层
+    config_edit_middleware_id: Option<String>,
+    /// 配置字段编辑缓冲
+    config_edit_values: HashMap<AppConfigField, String>,
+    /// 开始编辑时的字段取值，用于发现其他实例的并发修改
+    config_edit_base: HashMap<AppConfigField, String>,
+    /// 中间层配置的原始JSON编辑器：（中间层ID, 开始编辑时的JSON, 编辑器）
+    raw_middleware_config: Option<(String, String, JsonEditor)>,
+    /// 配置文件的原始JSON编辑器及打开时配置文件的修订号
+    raw_config: Option<(u64, JsonEditor)>,
+    /// 配置字段批量下发对话框
+    config_propagation: Option<ConfigPropagation>,
+    tuning_dialog: Option<TuningDialog>,
+    /// 各中间层的容量规划输入，首次展开时按观测值初始化
+    capacity_inputs: HashMap<String, CapacityInput>,
+    /// 中间层接口的轮询协调器，多个面板的相同请求只发出一次
+    poll_coordinator: PollCoordinator,
+    /// 各面板对中间层接口的订阅，渲染时按需创建，面板不再显示后由 [`App::prune_poll_subscriptions`] 释放
+    poll_subscriptions: RefCell<PanelSubscriptions>,
+    /// 扩容读实例对话框
+    scale_out: Option<ScaleOutDraft>,
+    /// 缩容读实例对话框
+    scale_in: Option<ScaleInDraft>,
+    /// 从负载均衡配置导入后端
+    upstream_import: Option<UpstreamImportDraft>,
+    /// 导出负载均衡配置的格式
+    upstream_export_format: UpstreamFormat,
+    /// 业务组操作的执行计划确认对话框
+    plan_confirmation: Option<PlanConfirmation>,
+    /// 后台任务服务
+    job_service: JobService,
+    /// 已结束的任务记录
+    job_history: Vec<JobRecord>,
+    /// 新建任务的重试策略
+    job_retry_policy: RetryPolicy,
+    /// 启动任务选中的业务组
+    job_group_id: Option<String>,
+    /// 待拉取的镜像
+    job_image: String,
+    /// 压测任务选中的中间层
+    job_middleware_id: Option<String>,
+    /// 压测请求数
+    job_benchmark_requests: u32,
+    /// 批量解密任务选中的中间层
+    job_decrypt_middleware_id: Option<String>,
+    /// 批量解密的密文文件
+    job_decrypt_input: String,
+    /// 批量解密的结果文件
+    job_decrypt_output: String,
+    /// 批量解密的并发数
+    job_decrypt_workers: u32,
+    /// 恢复模式下可用的备份文件
+    recovery_backups: Option<Vec<String>>,
+    /// 恢复模式下选中的备份
+    recovery_backup_index: usize,
+    /// 配置未写入时关闭窗口的确认对话框是否打开
+    show_unsaved_exit_dialog: bool,
+    /// 用户已确认放弃未写入的配置并退出
+    exit_confirmed: bool,
+    /// 各服务共享的应用状态
+    state_store: StateStore,
+    /// 界面刷新订阅的模型变更事件
+    model_events: Receiver<ModelEvent>,
+    /// 审计服务
+    audit_service: AuditService,
+    /// 审计日志
+    audit_entries: Vec<AuditEntry>,
+    /// 容器状态历史服务
+    status_history_service: StatusHistoryService,
+    /// 各容器的状态变化记录
+    status_history: Vec<StatusTransition>,
+    /// 拓扑自动导出订阅的模型变更事件
+    topology_events: Receiver<ModelEvent>,
+    /// 模型变更后是否自动导出拓扑
+    topology_auto_export: bool,
+    /// 健康轮询服务
+    health_service: HealthService,
+    /// 同时进行的健康探测数上限
+    health_concurrency: usize,
+    /// 后台状态变化触发的两次重绘之间的最短间隔（毫秒）
+    min_repaint_interval_ms: u64,
+    /// 成本估算的费用设置
+    cost_settings: CostSettings,
+    /// 启动预热服务
+    warmup_service: WarmupService,
+    /// 后台执行界面发起的容器启停
+    container_action_service: ContainerActionService,
+    /// 接口调试服务
+    playground_service: PlaygroundService,
+    /// 已保存的请求集合
+    request_collections: Vec<RequestCollection>,
+    /// 调试请求的目标中间层
+    playground_middleware_id: Option<String>,
+    /// 正在编辑的调试请求
+    playground_request: SavedRequest,
+    /// 调试请求保存到的集合
+    playground_collection_id: Option<String>,
+    /// 新建集合名称
+    new_collection_name: String,
+    /// 最近一次调试请求的响应
+    playground_response: Option<Result<RawResponse, String>>,
+    /// 调试历史
+    playground_history: Vec<PlaygroundHistoryEntry>,
+    /// 调试历史中请求内容的保存方式
+    playground_history_redaction: HistoryRedaction,
+    /// 调试历史搜索关键字
+    playground_history_query: String,
+    /// 大数据加解密服务
+    payload_service: PayloadService,
+    /// 大数据处理方向
+    payload_mode: PayloadMode,
+    /// 大数据输入文件
+    payload_input: String,
+    /// 大数据输出文件
+    payload_output: String,
+    /// 分块大小（字节）
+    payload_chunk_bytes: usize,
+    /// 最近一次大数据处理的结果
+    payload_result: Option<Result<String, String>>,
+    /// 数据完整性校验服务
+    verification_service: VerificationService,
+    /// 待校验的密文输入
+    verify_input: String,
+    /// 密文文件路径
+    verify_file: String,
+    /// 选中的校验目标中间层
+    verify_targets: BTreeSet<String>,
+    /// 最近一次校验报告
+    verify_report: Option<VerificationReport>,
+    /// 中间层迁移服务
+    migration_service: MigrationService,
+    /// 迁移源（业务组ID, 中间层ID）
+    migration_source: Option<(String, String)>,
+    /// 迁移目标（业务组ID, 中间层ID）
+    migration_target: Option<(String, String)>,
+    /// 远程命令服务
+    command_service: CommandService,
+    /// 界面角色服务
+    role_service: RoleService,
+    /// 本实例的会话
+    session_service: SessionService,
+    /// 管理员查看的活动会话
+    sessions: Vec<UiSession>,
+    /// 最近一次界面操作的时间
+    last_input: DateTime<Utc>,
+    /// 上次会话心跳的时间
+    last_heartbeat: Option<Instant>,
+    /// 会话最长时长（分钟），0表示不限
+    max_session_minutes: u64,
+    /// 会话被强制登出或超时后的原因，之后不再心跳
+    session_ended: Option<String>,
+    user_service: UserService,
+    /// 配置了本地用户时的登录表单，登录前不显示主界面
+    login: Option<LoginForm>,
+    local_users: Vec<LocalUser>,
+    password_policy: PasswordPolicy,
+    new_user_name: String,
+    new_user_password: String,
+    /// 正在重置密码的用户与临时密码
+    password_reset: Option<(String, String)>,
+    /// 当前使用的界面配置
+    ui_profile: UiProfile,
+    /// 当前操作人可使用的最宽界面配置
+    ui_profile_limit: UiProfile,
+    /// 界面角色分配
+    ui_roles: Vec<UiRoleAssignment>,
+    /// 未分配操作人使用的界面配置
+    default_ui_profile: UiProfile,
+    /// 待分配角色的操作人
+    new_role_actor: String,
+    new_role_profile: UiProfile,
+    /// 待分配角色可管理的业务组，为空时不限
+    new_role_groups: Vec<String>,
+    /// Agent部署服务
+    agent_service: AgentService,
+    /// Agent部署设置编辑缓冲
+    agent_settings: AgentSettings,
+    /// 远程命令白名单
+    command_allowlist: Vec<AllowedCommand>,
+    /// 授权操作人编辑缓冲
+    command_operators_text: String,
+    /// 选中的远程命令
+    command_selected: Option<String>,
+    /// 最近一次远程命令输出（中间层ID, 输出）
+    command_output: Option<(String, String)>,
+    /// 新白名单命令名称
+    new_command_name: String,
+    /// 新白名单命令内容
+    new_command_text: String,
+    /// 生成systemd单元的业务组
+    systemd_group_id: Option<String>,
+    /// systemd单元生成选项
+    systemd_options: UnitOptions,
+    /// 导出Terraform的业务组
+    terraform_group_id: Option<String>,
+    /// Terraform提供方
+    terraform_provider: TerraformProvider,
+    /// Kubernetes服务
+    kubernetes_service: KubernetesService,
+    /// 导入使用的命名空间
+    kubernetes_namespace: String,
+    /// 最近一次发现的工作负载（命名空间, 工作负载）
+    kubernetes_workloads: Option<(String, Vec<Workload>)>,
+    /// 发现后端的存活期限设置
+    discovery_ttl: DiscoveryTtl,
+    /// 待导入的docker-compose文件
+    compose_path: String,
+    /// 业务组快照对话框
+    snapshot_draft: Option<SnapshotDraft>,
+    /// 由快照恢复业务组
+    snapshot_restore: SnapshotRestoreForm,
+    audit_export: AuditExportForm,
+    /// 最近一次审计日志完整性校验的结果
+    audit_integrity: Option<Result<AuditChainStatus, String>>,
+    /// Webhook服务
+    webhook_service: WebhookService,
+    /// 运行中的Webhook监听
+    webhook_server: Option<WebhookServer>,
+    /// 已配置的Webhook
+    webhooks: Vec<Webhook>,
+    /// Webhook监听地址
+    webhook_listen: String,
+    /// 新Webhook名称
+    new_webhook_name: String,
+    /// 新Webhook是否推送配置（否则重启业务组）
+    new_webhook_push_config: bool,
+    /// 新Webhook的目标业务组或中间层ID
+    new_webhook_target: Option<String>,
+    /// 健康告警的迟滞、去重与抖动抑制设置
+    alert_policy: AlertPolicy,
+    /// 监控策略的导出与导入路径
+    monitoring_policy_path: String,
+    /// 是否暂停后台任务
+    background_paused: bool,
+    /// Docker容器状态同步服务
+    docker_service: DockerService,
+    /// 正在编辑的容器定义
+    container_spec_draft: Option<ContainerSpecDraft>,
+    /// 正在编辑的卷挂载（所属中间层或后端的ID, 挂载列表）
+    volume_draft: Option<(String, Vec<VolumeMount>)>,
+    /// 正在编辑的资源上限（所属中间层或后端的ID, 上限）
+    limits_draft: Option<(String, ResourceLimits)>,
+    /// 正在编辑的健康探测设置（所属中间层或后端的ID, 设置）
+    probe_draft: Option<(String, HealthProbe)>,
+    /// 正在编辑的运行时与副本数
+    runtime_draft: Option<(String, RuntimeKind, u32)>,
+    /// 正在编辑的SSH隧道设置（所属中间层或后端的ID, 设置）
+    tunnel_draft: Option<(String, SshTunnel)>,
+    /// 正在编辑的端口映射（所属中间层或后端的ID, 映射列表）
+    port_draft: Option<(String, Vec<PortMapping>)>,
+    /// 新建或编辑中的Docker主机
+    docker_host_form: DockerHost,
+    /// 各主机最近一次连接测试的结果，本机的键为空字符串
+    docker_host_tests: HashMap<String, Result<String, String>>,
+    /// 最近一次容器对账的结果
+    reconcile_report: Option<ReconcileReport>,
+    /// 孤儿容器纳管到的位置：（业务组ID, 中间层ID），中间层为空时作为业务组直属后端
+    adopt_target: Option<(String, Option<String>)>,
+    /// 自适应权重服务
+    weight_service: WeightService,
+    /// 权重调整记录
+    weight_history: Vec<WeightAdjustment>,
+    /// 镜像服务
+    image_service: ImageService,
+    /// 网络配置服务
+    network_service: NetworkService,
+    /// 容器资源统计服务
+    stats_service: StatsService,
+    /// 容器日志读取服务
+    log_stream_service: LogStreamService,
+    /// 镜像更新检查服务
+    image_update_service: ImageUpdateService,
+    /// 日志中心中查看日志的容器
+    container_log_id: Option<String>,
+    /// 容器日志的筛选文本
+    container_log_filter: String,
+    /// 按自动重启策略重新启动意外退出的容器
+    supervisor_service: SupervisorService,
+    /// 经跳板机访问实体的SSH隧道
+    tunnel_service: TunnelService,
+    /// 接口客户端的连接池与保活设置
+    connection_pool: ConnectionPoolSettings,
+    /// 链路导出服务
+    telemetry_service: TelemetryService,
+    /// 编辑中的OTLP导出设置
+    otlp_settings: OtlpSettings,
+    /// 中间层配置模板服务
+    config_preset_service: ConfigPresetService,
+    /// 内置与用户保存的配置模板
+    config_presets: Vec<ConfigPreset>,
+    /// 配置编辑中选中的模板
+    selected_config_preset: Option<String>,
+    /// 另存为模板时填写的名称
+    new_config_preset_name: String,
+    /// 容器控制台服务
+    console_service: ConsoleService,
+    /// 控制台选中的容器
+    console_target: Option<String>,
+    console_input: String,
+    /// 控制台命令的工作目录，为空时使用镜像默认目录
+    console_working_dir: String,
+    /// 控制台执行过的命令，用方向键翻阅
+    console_history: Vec<String>,
+    console_history_index: Option<usize>,
+    /// 新建或编辑中的网络配置
+    network_profile_form: NetworkProfile,
+    /// hosts格式的解析覆盖文本
+    network_hosts_text: String,
+    /// 导入解析覆盖的hosts文件路径
+    network_hosts_path: String,
+    /// 链路追踪服务
+    trace_service: TraceService,
+    /// 日志中心检索的追踪ID
+    trace_query: String,
+    /// 最近一次中间层日志检索结果
+    trace_search: Option<TraceSearch>,
+    /// 事件日历中选中的日期，显示当天的事件时间线
+    incident_day: Option<NaiveDate>,
+    /// 上次检查到的配置文件修改时间
+    config_modified: Option<SystemTime>,
+    /// 上次检查配置文件的时间
+    last_sync_check: Instant,
+    /// 后台自动保存业务数据修改
+    auto_saver: AutoSaver,
+    /// 自动保存设置
+    auto_save: bool,
+    save_interval: u64,
+    /// 是否按业务组分文件保存
+    split_storage: bool,
 }
 
+/// 检查共享配置文件是否被其他实例修改的间隔
+const CONFIG_SYNC_INTERVAL: Duration = Duration::from_secs(2);
+/// 状态历史中显示的最近变化条数
+const STATUS_TIMELINE_ROWS: usize = 20;
+/// 事件日历显示的周数
+const INCIDENT_CALENDAR_WEEKS: i64 = 53;
+/// 事件日历中每天方格的边长与间距
+const INCIDENT_CELL_SIZE: f32 = 10.0;
+const INCIDENT_CELL_GAP: f32 = 2.0;
+/// 事件日历按当天告警数占最多一天的比例分级着色，由浅到深
+const INCIDENT_LEVEL_COLORS: [Color32; 4] = [
+    Color32::from_rgb(254, 217, 118),
+    Color32::from_rgb(253, 141, 60),
+    Color32::from_rgb(227, 26, 28),
+    Color32::from_rgb(128, 0, 38),
+];
+/// 侧边栏业务组树每级的缩进
+const TREE_INDENT: f32 = 16.0;
+/// 监控中心每页显示的业务组、中间层或后端数
+const MONITOR_PAGE_SIZE: usize = 50;
+/// 面板超过该时长未渲染时释放其轮询订阅
+const POLL_SUBSCRIPTION_IDLE: Duration = Duration::from_secs(10);
+/// 会话心跳间隔
+const SESSION_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+/// 资源用量达到上限的该比例时高亮显示
+const LIMIT_WARNING_RATIO: f64 = 0.9;
+/// 克隆后端按钮的说明
+const CLONE_BACKEND_HINT: &str = "复制为已停止的新后端，名称后缀递增，端口顺延到下一个空闲端口";
+
 impl App {
     /// 创建新的应用实例
     pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
@@ -88,11 +792,98 @@ impl App {
         
         // 初始化配置管理器
         let config_manager = ConfigManager::new(ConfigManager::default_config_path());
-        let business_group_service = BusinessGroupService::new(config_manager.clone());
-        let middleware_service = MiddlewareService::new(config_manager.clone());
-        let backend_service = BackendService::new(config_manager.clone());
+        let event_bus = EventBus::new();
+        let (state_store, state_result) = StateStore::load(config_manager.clone(), event_bus.clone());
+        if let Err(e) = &state_result {
+            tracing::error!("加载应用状态失败: {:#}", e);
+        }
+        let model_events = event_bus.subscribe();
+        let topology_events = event_bus.subscribe();
+        // 后台的状态变化直接请求重绘，不等待用户操作
+        repaint::attach(&cc.egui_ctx);
+        repaint::forward(event_bus.subscribe());
+        let business_group_service = BusinessGroupService::new(state_store.clone());
+        let middleware_service = MiddlewareService::new(state_store.clone());
+        let backend_service = BackendService::new(state_store.clone());
+        
+        let alert_service = AlertService::new(config_manager.clone(), state_store.clone(), &event_bus);
+        let audit_service = AuditService::new(state_store.clone(), &event_bus);
+        let status_history_service = StatusHistoryService::new(state_store.clone(), &event_bus);
+        let job_service = JobService::new(config_manager.clone(), state_store.clone());
+        let playground_service = PlaygroundService::new(config_manager.clone(), state_store.clone());
+        let payload_service = PayloadService::new(config_manager.clone(), state_store.clone());
+        let warmup_service = WarmupService::new(state_store.clone());
+        let container_action_service = ContainerActionService::new(state_store.clone());
+        let migration_service = MigrationService::new(state_store.clone());
+        let command_service = CommandService::new(config_manager.clone(), state_store.clone());
+        let role_service = RoleService::new(config_manager.clone());
+        let session_service = SessionService::new(&config_manager, audit_service.actor());
+        let user_service = UserService::new(config_manager.clone());
+        let agent_service = AgentService::new(config_manager.clone(), state_store.clone());
+        let kubernetes_service = KubernetesService::new(config_manager.clone(), state_store.clone());
+        let webhook_service = WebhookService::new(config_manager.clone(), state_store.clone());
+        let docker_service = DockerService::new(state_store.clone());
+        let weight_service = WeightService::new(config_manager.clone(), state_store.clone());
+        let image_service = ImageService::new(state_store.clone());
+        let network_service = NetworkService::new(config_manager.clone(), state_store.clone());
+        let stats_service = StatsService::new(state_store.clone());
+        let log_stream_service = LogStreamService::new(state_store.clone());
+        let image_update_service = ImageUpdateService::new(state_store.clone());
+        let trace_service = TraceService::new(state_store.clone());
+        let supervisor_service = SupervisorService::new(state_store.clone(), &event_bus);
+        let tunnel_service = TunnelService::new(state_store.clone());
+        let console_service = ConsoleService::new(config_manager.clone(), state_store.clone());
+        let telemetry_service = TelemetryService::new(config_manager.clone());
+        let config_preset_service = ConfigPresetService::new(config_manager.clone());
+        let config_presets = config_preset_service.get_presets().unwrap_or_default();
         
         let business_groups = business_group_service.get_all_business_groups().unwrap_or_default();
+        let alerts = alert_service.get_alerts().unwrap_or_default();
+        let alert_policy = alert_service.policy();
+        
+        // 配置损坏时不能按默认配置放开登录与权限：以最近一次完好的备份代替，备份也不可用时只允许审计视图
+        let config = config_manager.load_config().or_else(|e| {
+            tracing::error!("加载配置失败，按最近一次完好的备份确定登录与权限: {:#}", e);
+            config_manager.last_good_config()
+        }).unwrap_or_else(|e| {
+            tracing::error!("加载配置备份失败，仅允许审计视图: {:#}", e);
+            Config { default_ui_profile: UiProfile::Auditor, ..Config::default() }
+        });
+        let config_modified = config_manager.modified_time();
+        let health_service = HealthService::new(state_store.clone(), config.health_concurrency);
+        repaint::set_min_interval(Duration::from_millis(config.min_repaint_interval_ms));
+        let auto_saver = AutoSaver::spawn(state_store.clone(), config.auto_save, config.save_interval);
+        let ui_profile_limit = ui_profile_for(&config.ui_roles, config.default_ui_profile, audit_service.actor());
+        state_store.set_access(GroupAccess::for_actor(&config.ui_roles, config.default_ui_profile, audit_service.actor()));
+        api::set_pool_settings(config.connection_pool.clone());
+        telemetry::configure(config.otlp.clone());
+        let mut anomaly_detector = AnomalyDetector::new();
+        let anomaly_rule_errors = anomaly_detector.set_rules(&config.anomaly_rules, config.anomaly_spike_threshold);
+        
+        let preferences_manager = PreferencesManager::new(PreferencesManager::default_path());
+        let preferences = match preferences_manager.load(&config) {
+            Ok(mut preferences) => {
+                preferences.last_opened = Utc::now().to_string();
+                if let Err(e) = preferences_manager.save(&preferences) {
+                    tracing::warn!("保存用户偏好失败: {:#}", e);
+                }
+                preferences
+            }
+            Err(e) => {
+                tracing::error!("加载用户偏好失败: {:#}", e);
+                UserPreferences::default()
+            }
+        };
+        Self::apply_preferences(&cc.egui_ctx, &preferences);
+        diagnostics::set_enabled(preferences.show_diagnostics);
+        
+        let webhook_server = if config.webhook_enabled {
+            WebhookServer::start(&config.webhook_listen, config_manager.clone())
+                .inspect_err(|e| tracing::error!("启动Webhook监听失败: {:#}", e))
+                .ok()
+        } else {
+            None
+        };
         
         Self {
             business_group_service,
@@ -112,74 +903,836 @@ impl App {
             new_backend: BackendContainer::default(),
             logs: Vec::new(),
             config_manager,
+            metrics_service: MetricsService::new(),
+            metrics_auto_scrape: false,
+            metrics_scrape_interval: 10,
+            last_metrics_scrape: None,
+            alert_service,
+            alerts,
+            anomaly_detector,
+            anomaly_rules: config.anomaly_rules,
+            anomaly_spike_threshold: config.anomaly_spike_threshold,
+            anomaly_rule_errors,
+            show_only_anomalies: false,
+            selected_log_indices: BTreeSet::new(),
+            evidence_alert_id: None,
+            evidence_entity: String::new(),
+            evidence_note: String::new(),
+            topology_format: TopologyFormat::Dot,
+            pending_screenshot: None,
+            status_filters: Vec::new(),
+            dashboard_widgets: preferences.dashboard_widgets.clone(),
+            dashboard_columns: preferences.dashboard_columns.max(1),
+            preferences_manager,
+            preferences,
+            dashboard_editing: false,
+            new_widget_kind: 0,
+            config_edit_middleware_id: None,
+            config_edit_values: HashMap::new(),
+            config_edit_base: HashMap::new(),
+            raw_middleware_config: None,
+            raw_config: None,
+            config_propagation: None,
+            tuning_dialog: None,
+            capacity_inputs: HashMap::new(),
+            poll_coordinator: PollCoordinator::new(state_store.clone()),
+            poll_subscriptions: RefCell::new(HashMap::new()),
+            scale_out: None,
+            upstream_import: None,
+            upstream_export_format: UpstreamFormat::Nginx,
+            scale_in: None,
+            plan_confirmation: None,
+            job_history: config.job_history,
+            job_retry_policy: config.job_retry_policy,
+            job_service,
+            job_group_id: None,
+            job_image: String::new(),
+            job_middleware_id: None,
+            job_benchmark_requests: 100,
+            job_decrypt_middleware_id: None,
+            job_decrypt_input: String::new(),
+            job_decrypt_output: String::new(),
+            job_decrypt_workers: 4,
+            recovery_backups: None,
+            recovery_backup_index: 0,
+            show_unsaved_exit_dialog: false,
+            exit_confirmed: false,
+            state_store,
+            model_events,
+            audit_entries: audit_service.get_entries().unwrap_or_default(),
+            status_history: status_history_service.get_history().unwrap_or_default(),
+            status_history_service,
+            audit_service,
+            topology_events,
+            topology_auto_export: false,
+            health_service,
+            health_concurrency: config.health_concurrency,
+            min_repaint_interval_ms: config.min_repaint_interval_ms,
+            cost_settings: config.cost.clone(),
+            warmup_service,
+            container_action_service,
+            playground_service,
+            request_collections: config.request_collections,
+            playground_middleware_id: None,
+            playground_request: SavedRequest::default(),
+            playground_collection_id: None,
+            new_collection_name: String::new(),
+            playground_response: None,
+            playground_history: config.playground_history.clone(),
+            playground_history_redaction: config.playground_history_redaction,
+            playground_history_query: String::new(),
+            payload_service,
+            payload_mode: PayloadMode::Encrypt,
+            payload_input: String::new(),
+            payload_output: String::new(),
+            payload_chunk_bytes: config.payload_chunk_bytes,
+            payload_result: None,
+            verification_service: VerificationService::new(),
+            verify_input: String::new(),
+            verify_file: String::new(),
+            verify_targets: BTreeSet::new(),
+            verify_report: None,
+            migration_service,
+            migration_source: None,
+            migration_target: None,
+            command_service,
+            role_service,
+            session_service,
+            sessions: Vec::new(),
+            last_input: Utc::now(),
+            last_heartbeat: None,
+            max_session_minutes: config.max_session_minutes,
+            session_ended: None,
+            user_service,
+            login: (!config.local_users.is_empty()).then(LoginForm::default),
+            local_users: config.local_users.clone(),
+            password_policy: config.password_policy.clone(),
+            new_user_name: String::new(),
+            new_user_password: String::new(),
+            password_reset: None,
+            ui_profile: ui_profile_limit,
+            ui_profile_limit,
+            ui_roles: config.ui_roles.clone(),
+            default_ui_profile: config.default_ui_profile,
+            new_role_actor: String::new(),
+            new_role_profile: UiProfile::Operator,
+            new_role_groups: Vec::new(),
+            agent_service,
+            agent_settings: config.agent.clone(),
+            command_allowlist: config.command_allowlist,
+            command_operators_text: config.command_operators.join(", "),
+            command_selected: None,
+            command_output: None,
+            new_command_name: String::new(),
+            new_command_text: String::new(),
+            systemd_group_id: None,
+            systemd_options: UnitOptions::default(),
+            terraform_group_id: None,
+            terraform_provider: TerraformProvider::Docker,
+            kubernetes_service,
+            kubernetes_namespace: config.kubernetes_namespace.clone(),
+            kubernetes_workloads: None,
+            discovery_ttl: config.discovery_ttl.clone(),
+            compose_path: "docker-compose.yml".to_string(),
+            snapshot_draft: None,
+            snapshot_restore: SnapshotRestoreForm {
+                path: String::new(),
+                host_id: None,
+                name: String::new(),
+                restore_volumes: true,
+            },
+            audit_export: AuditExportForm {
+                from: String::new(),
+                to: String::new(),
+                actor: String::new(),
+                entity: String::new(),
+                action: String::new(),
+                path: "audit_log.csv".to_string(),
+                format: AuditExportFormat::Csv,
+                integrity: AuditIntegrity::HashChain,
+                key: String::new(),
+            },
+            audit_integrity: None,
+            webhook_service,
+            webhook_server,
+            webhooks: config.webhooks.clone(),
+            webhook_listen: config.webhook_listen.clone(),
+            new_webhook_name: String::new(),
+            new_webhook_push_config: false,
+            new_webhook_target: None,
+            alert_policy,
+            monitoring_policy_path: "monitoring_policy.json".to_string(),
+            background_paused: config.background_paused,
+            docker_service,
+            container_spec_draft: None,
+            volume_draft: None,
+            limits_draft: None,
+            probe_draft: None,
+            runtime_draft: None,
+            tunnel_draft: None,
+            port_draft: None,
+            docker_host_form: DockerHost::new("", DockerConnection::Tcp, ""),
+            docker_host_tests: HashMap::new(),
+            reconcile_report: None,
+            adopt_target: None,
+            weight_service,
+            weight_history: config.weight_history.clone(),
+            image_service,
+            network_service,
+            stats_service,
+            log_stream_service,
+            image_update_service,
+            container_log_id: None,
+            container_log_filter: String::new(),
+            supervisor_service,
+            tunnel_service,
+            connection_pool: config.connection_pool.clone(),
+            telemetry_service,
+            otlp_settings: config.otlp.clone(),
+            config_preset_service,
+            config_presets,
+            selected_config_preset: None,
+            new_config_preset_name: String::new(),
+            console_service,
+            console_target: None,
+            console_input: String::new(),
+            console_working_dir: String::new(),
+            console_history: Vec::new(),
+            console_history_index: None,
+            network_profile_form: NetworkProfile::new(""),
+            network_hosts_text: String::new(),
+            network_hosts_path: String::new(),
+            trace_service,
+            trace_query: String::new(),
+            trace_search: None,
+            incident_day: None,
+            config_modified,
+            last_sync_check: Instant::now(),
+            auto_saver,
+            auto_save: config.auto_save,
+            save_interval: config.save_interval,
+            split_storage: config.split_storage,
         }
     }
     
-    /// 加载业务组数据
+    /// 加载业务组数据，失败时保留内存中的数据
     fn load_business_groups(&mut self) {
-        self.business_groups = self.business_group_service.get_all_business_groups().unwrap_or_default();
+        match self.business_group_service.get_all_business_groups() {
+            Ok(groups) => self.business_groups = groups,
+            Err(e) => self.push_log(LogEntry::new("配置", &format!("加载业务组失败: {:#}", e))),
+        }
     }
     
-    /// 获取当前选中的业务组
-    fn get_selected_group(&self) -> Option<&BusinessGroup> {
-        if let Some(group_id) = &self.selected_group_id {
-            self.business_groups.iter().find(|g| g.id == *group_id)
-        } else {
-            None
+    /// 加载告警数据，失败时保留内存中的数据
+    fn load_alerts(&mut self) {
+        match self.alert_service.get_alerts() {
+            Ok(alerts) => self.alerts = alerts,
+            Err(e) => tracing::error!("加载告警失败: {:#}", e),
+        }
+    }
+    
+    /// 记录操作失败的原因
+    fn report_error(&mut self, result: anyhow::Result<()>) {
+        if let Err(e) = result {
+            self.push_log(LogEntry::new("操作", &format!("操作失败: {:#}", e)));
         }
     }
     
-    /// 获取当前选中的中间层
-    fn get_selected_middleware(&self) -> Option<(&BusinessGroup, &MiddlewareContainer)> {
-        if let (Some(group_id), Some(middleware_id)) = (&self.selected_group_id, &self.selected_middleware_id) {
-            if let Some(group) = self.business_groups.iter().find(|g| g.id == *group_id) {
-                if let Some(middleware) = group.middlewares.iter().find(|m| m.id == *middleware_id) {
-                    return Some((group, middleware));
+    /// 立即写入未保存的业务数据修改，并保存自动保存与分文件保存设置
+    fn save_app_config(&mut self) {
+        let result = self.state_store.flush().and_then(|_| {
+            self.config_manager.update_if(|config| {
+                if config.auto_save == self.auto_save
+                    && config.save_interval == self.save_interval
+                    && config.split_storage == self.split_storage
+                {
+                    return Ok(false);
                 }
+                config.auto_save = self.auto_save;
+                config.save_interval = self.save_interval;
+                config.split_storage = self.split_storage;
+                Ok(true)
+            })?;
+            Ok(())
+        });
+        match result {
+            Ok(()) => {
+                self.auto_saver.configure(self.auto_save, self.save_interval);
+                self.push_log(LogEntry::new("配置", "配置已保存"));
             }
+            Err(e) => self.push_log(LogEntry::new("配置", &format!("保存配置失败: {:#}", e))),
+        }
+    }
+    
+    /// 渲染自动保存设置
+    fn render_auto_save(&mut self, ui: &mut egui::Ui) {
+        ui.heading("自动保存");
+        ui.label("业务组、中间层与后端的修改先保存在内存中，由后台线程按间隔合并写入配置文件；退出时总会写入。");
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.auto_save, "自动保存");
+            ui.label("间隔 (秒):");
+            ui.add_enabled(self.auto_save, egui::DragValue::new(&mut self.save_interval).clamp_range(1..=3600));
+        });
+        ui.checkbox(&mut self.split_storage, "按业务组分文件保存").on_hover_text(format!(
+            "每个业务组保存在 {} 下各自的文件中，只写入有变化的业务组；多个实例修改不同业务组时互不覆盖",
+            self.config_manager.groups_dir().display()
+        ));
+        ui.horizontal(|ui| {
+            if self.state_store.is_dirty() {
+                ui.colored_label(Color32::YELLOW, "有未保存的修改");
+            } else {
+                ui.label("所有修改已保存");
+            }
+            if let Some(saved) = self.auto_saver.last_saved() {
+                ui.weak(format!("上次自动保存: {}", saved.with_timezone(&chrono::Local).format("%H:%M:%S")));
+            }
+        });
+        ui.horizontal(|ui| {
+            if ui.button("保存").clicked() {
+                self.save_app_config();
+            }
+            if ui.button("立即备份").on_hover_text("写入未保存的修改后，把配置另存为带时间戳的备份，可在只读恢复模式下从中恢复").clicked() {
+                self.backup_app_config();
+            }
+        });
+    }
+    
+    /// 写入未保存的修改后备份配置
+    fn backup_app_config(&mut self) {
+        let result = self.state_store.flush()
+            .and_then(|_| self.config_manager.load_config())
+            .and_then(|config| self.config_manager.backup_config(&config));
+        match result {
+            Ok(path) => self.push_log(LogEntry::new("配置", &format!("已备份配置: {}", path))),
+            Err(e) => self.push_log(LogEntry::new("配置", &format!("备份配置失败: {:#}", e))),
+        }
+    }
+    
+    /// 渲染健康探测的并发设置
+    fn render_health_concurrency(&mut self, ui: &mut egui::Ui) {
+        ui.heading("健康探测");
+        ui.label("到期的探测与立即检查在并发上限内执行，超出的排队等待，立即检查优先；延迟从探测实际开始时计算。");
+        ui.horizontal(|ui| {
+            ui.label("并发上限:");
+            let response = ui.add(egui::DragValue::new(&mut self.health_concurrency).clamp_range(1..=256));
+            if response.drag_stopped() || response.lost_focus() {
+                self.save_health_concurrency();
+            }
+            let queue = self.health_service.queue_stats();
+            ui.weak(format!("进行中 {}，排队 {}", queue.active, queue.queued));
+        });
+    }
+    
+    /// 保存健康探测的并发上限并立即生效
+    fn save_health_concurrency(&mut self) {
+        let concurrency = self.health_concurrency;
+        let result = self.config_manager.update_if(|config| {
+            if config.health_concurrency == concurrency {
+                return Ok(false);
+            }
+            config.health_concurrency = concurrency;
+            Ok(true)
+        });
+        match result {
+            Ok(_) => self.health_service.set_concurrency(concurrency),
+            Err(e) => self.push_log(LogEntry::new("配置", &format!("保存健康探测并发上限失败: {:#}", e))),
         }
-        None
     }
     
-    /// 获取当前选中的后端
-    fn get_selected_backend(&self) -> Option<(&BusinessGroup, &MiddlewareContainer, &BackendContainer)> {
-        if let (Some(group_id), Some(middleware_id), Some(backend_id)) = (&self.selected_group_id, &self.selected_middleware_id, &self.selected_backend_id) {
-            if let Some(group) = self.business_groups.iter().find(|g| g.id == *group_id) {
-                if let Some(middleware) = group.middlewares.iter().find(|m| m.id == *middleware_id) {
-                    if let Some(backend) = middleware.backend_containers.iter().find(|b| b.id == *backend_id) {
-                        return Some((group, middleware, backend));
+    /// 渲染后台重绘设置：状态变化、探测与后台任务完成时请求重绘，间隔内的请求合并为一次
+    fn render_repaint_interval(&mut self, ui: &mut egui::Ui) {
+        ui.heading("界面刷新");
+        ui.label("后台状态变化、健康探测与后台任务完成时立即刷新界面，最短间隔内的多次变化合并为一次刷新。");
+        ui.horizontal(|ui| {
+            ui.label("最短间隔 (毫秒):");
+            let response = ui.add(egui::DragValue::new(&mut self.min_repaint_interval_ms).clamp_range(0..=5000));
+            if response.drag_stopped() || response.lost_focus() {
+                let interval = self.min_repaint_interval_ms;
+                let result = self.config_manager.update_if(|config| {
+                    if config.min_repaint_interval_ms == interval {
+                        return Ok(false);
                     }
+                    config.min_repaint_interval_ms = interval;
+                    Ok(true)
+                });
+                match result {
+                    Ok(_) => repaint::set_min_interval(Duration::from_millis(interval)),
+                    Err(e) => self.push_log(LogEntry::new("配置", &format!("保存界面刷新间隔失败: {:#}", e))),
+                }
+            }
+            let stats = repaint::stats();
+            let requested = RepaintSource::ALL
+                .iter()
+                .map(|source| format!("{} {}", source.label(), stats.requested[*source as usize]))
+                .collect::<Vec<_>>()
+                .join("，");
+            ui.weak(format!("已合并 {}", stats.coalesced)).on_hover_text(format!("各来源的刷新请求: {}", requested));
+        });
+    }
+    
+    /// 渲染成本设置与各业务组的月成本估算
+    fn render_cost_settings(&mut self, ui: &mut egui::Ui) {
+        ui.heading("成本估算");
+        ui.label("只计运行中与启动中的实例，已停止的实例不计费用；部署在设置了月费用的Docker主机上的实例平均分摊主机费用，其余实例按角色计费。");
+        let mut changed = false;
+        egui::Grid::new("cost_settings").num_columns(2).show(ui, |ui| {
+            ui.label("货币符号:");
+            changed |= ui.add(egui::TextEdit::singleline(&mut self.cost_settings.currency).desired_width(60.0)).lost_focus();
+            ui.end_row();
+            ui.label("中间层实例月费用:");
+            let response = ui.add(egui::DragValue::new(&mut self.cost_settings.middleware_monthly).clamp_range(0.0..=f64::MAX).speed(1.0));
+            changed |= response.drag_stopped() || response.lost_focus();
+            ui.end_row();
+            ui.label("后端实例月费用:");
+            let response = ui.add(egui::DragValue::new(&mut self.cost_settings.backend_monthly).clamp_range(0.0..=f64::MAX).speed(1.0));
+            changed |= response.drag_stopped() || response.lost_focus();
+            ui.end_row();
+        });
+        if changed {
+            let settings = self.cost_settings.clone();
+            let result = self.config_manager.update_if(|config| {
+                if config.cost == settings {
+                    return Ok(false);
                 }
+                config.cost = settings;
+                Ok(true)
+            });
+            match result {
+                Ok(true) => self.record_audit("修改成本设置", None, None),
+                Ok(false) => {}
+                Err(e) => self.push_log(LogEntry::new("配置", &format!("保存成本设置失败: {:#}", e))),
             }
         }
-        None
+        
+        ui.add_space(4.0);
+        let hosts = self.docker_service.get_hosts();
+        let estimator = CostEstimator::new(&self.cost_settings, &hosts, &self.business_groups);
+        let settings = &self.cost_settings;
+        let mut total = 0.0;
+        egui::Grid::new("group_costs").striped(true).show(ui, |ui| {
+            ui.strong("业务组");
+            ui.strong("运行中实例");
+            ui.strong("实例费用");
+            ui.strong("主机分摊");
+            ui.strong("月成本");
+            ui.end_row();
+            for group in &self.business_groups {
+                let cost = estimator.group_cost(group);
+                total += cost.total();
+                ui.label(&group.name);
+                ui.label(cost.instances.to_string());
+                ui.label(settings.format(cost.containers));
+                ui.label(settings.format(cost.hosts));
+                ui.strong(settings.format(cost.total()));
+                ui.end_row();
+            }
+        });
+        ui.label(format!("合计: {} / 月", settings.format(total)));
+    }
+    
+    /// 暂停或恢复后台任务，写入配置使其在重启后及其他实例中同样生效
+    fn set_background_paused(&mut self, paused: bool) {
+        let result = self.config_manager.update(|config| {
+            config.background_paused = paused;
+            Ok(())
+        });
+        match result {
+            Ok(()) => {
+                self.background_paused = paused;
+                let action = if paused { "暂停后台任务" } else { "恢复后台任务" };
+                self.push_log(LogEntry::new("后台任务", action));
+                self.record_audit(action, None, None);
+            }
+            Err(e) => self.push_log(LogEntry::new("配置", &format!("保存后台任务开关失败: {:#}", e))),
+        }
+    }
+    
+    /// 从配置文件重新加载全部数据
+    fn reload_from_config(&mut self) {
+        let config = match self.config_manager.load_config() {
+            Ok(config) => config,
+            Err(e) => {
+                self.push_log(LogEntry::new("配置", &format!("重新加载配置失败: {:#}", e)));
+                return;
+            }
+        };
+        
+        if let Err(e) = self.state_store.reload() {
+            self.push_log(LogEntry::new("配置", &format!("重新加载配置失败: {:#}", e)));
+            return;
+        }
+        self.load_business_groups();
+        self.apply_shared_config(config);
+        self.push_log(LogEntry::new("配置", "配置已重新加载"));
+    }
+    
+    /// 应用配置文件中除业务组外的共享数据，告警、审计日志与状态历史取自状态中的记录
+    fn apply_shared_config(&mut self, config: Config) {
+        self.load_alerts();
+        self.anomaly_rules = config.anomaly_rules;
+        self.anomaly_spike_threshold = config.anomaly_spike_threshold;
+        self.anomaly_rule_errors = self.anomaly_detector.set_rules(&self.anomaly_rules, self.anomaly_spike_threshold);
+        self.job_retry_policy = config.job_retry_policy;
+        self.job_history = config.job_history;
+        self.audit_entries = self.audit_service.get_entries().unwrap_or_default();
+        self.status_history = self.status_history_service.get_history().unwrap_or_default();
+        self.request_collections = config.request_collections;
+        self.playground_history = config.playground_history;
+        self.playground_history_redaction = config.playground_history_redaction;
+        self.payload_chunk_bytes = config.payload_chunk_bytes;
+        self.health_concurrency = config.health_concurrency;
+        self.health_service.set_concurrency(config.health_concurrency);
+        self.min_repaint_interval_ms = config.min_repaint_interval_ms;
+        repaint::set_min_interval(Duration::from_millis(config.min_repaint_interval_ms));
+        self.cost_settings = config.cost;
+        self.command_allowlist = config.command_allowlist;
+        self.command_operators_text = config.command_operators.join(", ");
+        self.ui_profile_limit = ui_profile_for(&config.ui_roles, config.default_ui_profile, self.audit_service.actor());
+        self.state_store.set_access(GroupAccess::for_actor(&config.ui_roles, config.default_ui_profile, self.audit_service.actor()));
+        if !self.ui_profile.within(self.ui_profile_limit) {
+            self.ui_profile = self.ui_profile_limit;
+        }
+        self.ui_roles = config.ui_roles;
+        self.default_ui_profile = config.default_ui_profile;
+        self.auto_saver.configure(config.auto_save, config.save_interval);
+        self.auto_save = config.auto_save;
+        self.save_interval = config.save_interval;
+        self.split_storage = config.split_storage;
+        self.max_session_minutes = config.max_session_minutes;
+        self.local_users = config.local_users.clone();
+        self.password_policy = config.password_policy.clone();
+        self.kubernetes_namespace = config.kubernetes_namespace;
+        self.discovery_ttl = config.discovery_ttl;
+        self.webhooks = config.webhooks;
+        self.background_paused = config.background_paused;
+        self.alert_policy = self.alert_service.policy();
+        self.weight_history = config.weight_history;
+        api::set_pool_settings(config.connection_pool.clone());
+        self.connection_pool = config.connection_pool;
+        telemetry::configure(config.otlp.clone());
+        self.otlp_settings = config.otlp;
+        self.config_presets = ConfigPreset::builtin();
+        self.config_presets.extend(config.config_presets);
+    }
+    
+    /// 检查配置文件是否被其他实例修改，是则同步到界面
+    fn sync_with_peers(&mut self) {
+        if self.last_sync_check.elapsed() < CONFIG_SYNC_INTERVAL {
+            return;
+        }
+        self.last_sync_check = Instant::now();
+        
+        // 未写入的修改保存后再同步，不记录修改时间，以便下次检查时仍能发现其他实例的修改
+        if self.state_store.is_dirty() {
+            return;
+        }
+        let modified = self.config_manager.modified_time();
+        if modified == self.config_modified {
+            return;
+        }
+        self.config_modified = modified;
+        
+        match self.state_store.sync() {
+            Ok(true) => {
+                if let Ok(config) = self.config_manager.load_config() {
+                    self.apply_shared_config(config);
+                }
+                self.push_log(LogEntry::new("配置", "已同步其他实例的修改"));
+            }
+            Ok(false) => {}
+            Err(e) => tracing::warn!("同步配置失败: {:#}", e),
+        }
+    }
+    
+    /// 按退避间隔重试写入排队的配置
+    fn retry_pending_save(&mut self, force: bool) {
+        match self.config_manager.retry_pending(force) {
+            Some(Ok(())) => {
+                self.config_modified = self.config_manager.modified_time();
+                self.push_log(LogEntry::new("配置", "排队的配置修改已写入文件"));
+            }
+            Some(Err(e)) if force => self.push_log(LogEntry::new("配置", &format!("重试写入配置失败: {:#}", e))),
+            _ => {}
+        }
+    }
+    
+    /// 渲染配置写入失败的提示栏
+    fn render_pending_save_banner(&mut self, ui: &mut egui::Ui) {
+        let Some(pending) = self.config_manager.pending_save() else {
+            return;
+        };
+        ui.colored_label(
+            Color32::YELLOW,
+            format!(
+                "配置文件写入失败（自 {} 起已失败 {} 次），未写入的修改保留在内存中，将在 {} 秒后自动重试；退出前请排除问题，否则修改会丢失。",
+                pending.since.with_timezone(&chrono::Local).format("%H:%M:%S"),
+                pending.attempts,
+                pending.retry_in.as_secs(),
+            ),
+        );
+        ui.horizontal(|ui| {
+            ui.label(format!("错误: {}", pending.last_error));
+            if ui.button("立即重试").clicked() {
+                self.retry_pending_save(true);
+            }
+        });
+    }
+    
+    /// 渲染配置未写入时关闭窗口的确认对话框
+    fn render_unsaved_exit_dialog(&mut self, ctx: &egui::Context) {
+        if !self.show_unsaved_exit_dialog {
+            return;
+        }
+        Window::new("配置尚未保存")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label("配置文件仍无法写入，现在退出会丢失写入失败以来的所有修改。");
+                if let Some(pending) = self.config_manager.pending_save() {
+                    ui.label(format!("错误: {}", pending.last_error));
+                }
+                ui.horizontal(|ui| {
+                    if ui.button("重试写入").clicked() {
+                        self.retry_pending_save(true);
+                        if self.config_manager.pending_save().is_none() {
+                            self.show_unsaved_exit_dialog = false;
+                            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                        }
+                    }
+                    if ui.button("放弃修改并退出").clicked() {
+                        self.exit_confirmed = true;
+                        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                    }
+                    if ui.button("取消").clicked() {
+                        self.show_unsaved_exit_dialog = false;
+                    }
+                });
+            });
+    }
+    
+    /// 渲染只读恢复模式提示栏
+    fn render_recovery_banner(&mut self, ui: &mut egui::Ui) {
+        ui.colored_label(
+            Color32::RED,
+            format!(
+                "配置文件连续加载失败 {} 次，已进入只读恢复模式：界面显示最后一次加载成功的数据，所有修改都不会保存。",
+                self.config_manager.load_failures(),
+            ),
+        );
+        if let Some(error) = self.config_manager.last_error() {
+            ui.label(format!("错误: {}", error));
+        }
+        
+        ui.horizontal(|ui| {
+            if ui.button("重试加载").clicked() {
+                self.reload_from_config();
+            }
+            
+            if ui.button("刷新备份列表").clicked() || self.recovery_backups.is_none() {
+                self.recovery_backups = Some(self.config_manager.list_backups().unwrap_or_default());
+                self.recovery_backup_index = 0;
+            }
+            let backups = self.recovery_backups.clone().unwrap_or_default();
+            if backups.is_empty() {
+                ui.label("没有可用的备份");
+                return;
+            }
+            
+            egui::ComboBox::from_id_source("recovery_backup")
+                .selected_text(backups.get(self.recovery_backup_index).cloned().unwrap_or_default())
+                .show_ui(ui, |ui| {
+                    for (index, backup) in backups.iter().enumerate() {
+                        ui.selectable_value(&mut self.recovery_backup_index, index, backup);
+                    }
+                });
+            if ui.button("从备份恢复").clicked()
+                && let Some(backup) = backups.get(self.recovery_backup_index)
+            {
+                match self.config_manager.restore_config(backup) {
+                    Ok(_) => {
+                        self.push_log(LogEntry::new("配置", &format!("已从备份恢复: {}", backup)));
+                        self.reload_from_config();
+                    }
+                    Err(e) => self.push_log(LogEntry::new("配置", &format!("从备份恢复失败: {:#}", e))),
+                }
+            }
+        });
+    }
+    
+    /// 追加日志，并进行异常检测
+    fn push_log(&mut self, entry: LogEntry) {
+        for event in self.anomaly_detector.observe(&entry.message) {
+            let alert = match event {
+                AnomalyEvent::RuleMatched(rule) => Alert::new(&entry.source, &format!("命中规则 {}: {}", rule.name, entry.message), rule.severity),
+                AnomalyEvent::Spike(hits) => Alert::new("日志", &format!("异常日志激增: 1分钟内 {} 条", hits), AlertSeverity::Critical),
+            };
+            if let Err(e) = self.alert_service.raise_alert(alert) {
+                tracing::error!("触发告警失败: {}", e);
+            }
+            self.load_alerts();
+        }
+        self.logs.push(entry);
+    }
+    
+    /// 将选中的日志作为证据附加到告警，未指定告警时新建事件
+    fn attach_selected_logs(&mut self) {
+        let entries: Vec<LogEntry> = self.selected_log_indices
+            .iter()
+            .filter_map(|index| self.logs.get(*index).cloned())
+            .collect();
+        if entries.is_empty() {
+            return;
+        }
+        
+        let entity = if self.evidence_entity.trim().is_empty() {
+            let sources: BTreeSet<&str> = entries.iter().map(|e| e.source.as_str()).collect();
+            sources.into_iter().collect::<Vec<_>>().join(", ")
+        } else {
+            self.evidence_entity.trim().to_string()
+        };
+        let evidence = LogEvidence::new(&entity, entries, &self.evidence_note);
+        
+        let result = match &self.evidence_alert_id {
+            Some(alert_id) => self.alert_service.attach_evidence(alert_id, evidence),
+            None => {
+                let message = if self.evidence_note.trim().is_empty() { "手动创建的事件" } else { self.evidence_note.trim() };
+                let mut alert = Alert::new(&entity, message, AlertSeverity::Warning);
+                alert.evidence.push(evidence);
+                self.alert_service.raise_alert(alert)
+            }
+        };
+        
+        match result {
+            Ok(()) => {
+                self.selected_log_indices.clear();
+                self.evidence_note.clear();
+                self.evidence_entity.clear();
+                self.load_alerts();
+            }
+            Err(e) => self.push_log(LogEntry::new("告警", &format!("附加日志证据失败: {}", e))),
+        }
+    }
+    
+    /// 保存异常检测规则并重建检测器
+    fn save_anomaly_rules(&mut self) {
+        self.anomaly_rule_errors = self.anomaly_detector.set_rules(&self.anomaly_rules, self.anomaly_spike_threshold);
+        
+        let result = self.config_manager.update(|config| {
+            config.anomaly_rules = self.anomaly_rules.clone();
+            config.anomaly_spike_threshold = self.anomaly_spike_threshold;
+            Ok(())
+        });
+        if let Err(e) = result {
+            self.anomaly_rule_errors.push(format!("保存规则失败: {}", e));
+        }
+    }
+    
+    /// 复制实体JSON到剪贴板
+    fn copy_entity_json<T: serde::Serialize>(ui: &egui::Ui, entity: &T) {
+        if let Ok(json) = serde_json::to_string_pretty(entity) {
+            ui.output_mut(|o| o.copied_text = json);
+        }
+    }
+    
+    /// 从剪贴板JSON创建实体，所有ID重新生成
+    fn paste_entity_from_clipboard(&mut self) {
+        let result = arboard::Clipboard::new()
+            .and_then(|mut clipboard| clipboard.get_text())
+            .map_err(anyhow::Error::from)
+            .and_then(|text| EntityJson::parse(&text).ok_or_else(|| anyhow::anyhow!("剪贴板内容不是有效的业务组/中间层/后端JSON")))
+            .and_then(|entity| match entity {
+                EntityJson::Group(group) => {
+                    let group = (*group).duplicate();
+                    let message = format!("正在粘贴业务组 {}", group.name);
+                    self.selected_group_id = Some(group.id.clone());
+                    self.container_action_service.submit(ContainerAction::CreateGroup { group: Box::new(group) });
+                    Ok(message)
+                }
+                EntityJson::Middleware(middleware) => {
+                    let group_id = self.selected_group_id.clone().ok_or_else(|| anyhow::anyhow!("请先选择一个业务组"))?;
+                    let middleware = (*middleware).duplicate();
+                    let message = format!("已粘贴中间层 {}", middleware.name);
+                    self.middleware_service.add_middleware_to_group(&GroupId::from(&group_id), middleware).map(|_| message)
+                }
+                EntityJson::Backend(backend) => {
+                    let group_id = self.selected_group_id.clone().ok_or_else(|| anyhow::anyhow!("请先选择一个业务组"))?;
+                    let backend = (*backend).duplicate();
+                    let message = format!("已粘贴后端 {}", backend.name);
+                    match &self.selected_middleware_id {
+                        Some(middleware_id) => self.backend_service.add_backend_to_middleware(&GroupId::from(&group_id), &MiddlewareId::from(middleware_id), backend),
+                        None => self.backend_service.add_backend_to_group(&GroupId::from(&group_id), backend),
+                    }
+                    .map(|_| message)
+                }
+            });
+        
+        let entry = match result {
+            Ok(message) => LogEntry::new("剪贴板", &message),
+            Err(e) => LogEntry::new("剪贴板", &format!("粘贴失败: {}", e)),
+        };
+        self.push_log(entry);
+        self.load_business_groups();
+    }
+    
+    /// 后端是否通过状态筛选
+    fn backend_visible(filters: &[StatusFilter], backend: &BackendContainer) -> bool {
+        filters.is_empty() || filters.iter().any(|f| f.matches_container(&backend.status, &backend.health))
+    }
+    
+    /// 中间层是否通过状态筛选（自身或任一后端匹配）
+    fn middleware_visible(filters: &[StatusFilter], middleware: &MiddlewareContainer) -> bool {
+        filters.is_empty()
+            || filters.iter().any(|f| f.matches_container(&middleware.status, &middleware.health))
+            || middleware.backend_containers.iter().any(|b| Self::backend_visible(filters, b))
+    }
+    
+    /// 业务组是否通过状态筛选（自身或任一子实体匹配）
+    fn group_visible(filters: &[StatusFilter], group: &BusinessGroup) -> bool {
+        filters.is_empty()
+            || filters.iter().any(|f| f.matches_group(&group.rollup_status()))
+            || group.middlewares.iter().any(|m| Self::middleware_visible(filters, m))
+            || group.backend_containers.iter().any(|b| Self::backend_visible(filters, b))
+    }
+    
+    /// 渲染状态筛选栏
+    fn render_filter_bar(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("筛选:");
+            for filter in StatusFilter::ALL {
+                let active = self.status_filters.contains(&filter);
+                if ui.selectable_label(active, filter.label()).clicked() {
+                    if active {
+                        self.status_filters.retain(|f| *f != filter);
+                    } else {
+                        self.status_filters.push(filter);
+                    }
+                }
+            }
+            if !self.status_filters.is_empty() && ui.button("清除筛选").clicked() {
+                self.status_filters.clear();
+            }
+        });
     }
     
     /// 渲染顶部菜单栏
     fn render_menu_bar(&mut self, ui: &mut egui::Ui) {
         ui.horizontal(|ui| {
+            let auditor = self.ui_profile == UiProfile::Auditor;
             ui.menu_button("文件", |ui| {
-                if ui.button("新建业务组").clicked() {
-                    self.show_new_group_dialog = true;
-                    ui.close_menu();
-                }
-                if ui.button("保存配置").clicked() {
-                    // 简化保存逻辑
-                    let business_groups = self.business_group_service.get_all_business_groups().unwrap();
-                    let config = Config {
-                        app_state: crate::models::AppState {
-                            business_groups,
-                            selected_group_id: None,
-                            selected_middleware_id: None,
-                            selected_backend_id: None,
-                        },
-                        last_opened: Utc::now().to_string(),
-                        theme: "dark".to_string(),
-                        auto_save: true,
-                        save_interval: 30,
-                    };
-                    self.config_manager.save_config(&config).unwrap();
-                    ui.close_menu();
+                if !auditor {
+                    if ui.button("新建业务组").clicked() {
+                        self.show_new_group_dialog = true;
+                        ui.close_menu();
+                    }
+                    if ui.button("保存配置").clicked() {
+                        self.save_app_config();
+                        ui.close_menu();
+                    }
+                    let mut paused = self.background_paused;
+                    if ui.checkbox(&mut paused, "暂停后台任务").clicked() {
+                        self.set_background_paused(paused);
+                        ui.close_menu();
+                    }
                 }
                 if ui.button("退出").clicked() {
                     // 退出应用
@@ -187,42 +1740,45 @@ impl App {
                 }
             });
             
-            ui.menu_button("编辑", |ui| {
-                if ui.button("添加中间层").clicked() {
-                    self.show_new_middleware_dialog = true;
-                    ui.close_menu();
-                }
-                if ui.button("添加后端").clicked() {
-                    self.show_new_backend_dialog = true;
-                    ui.close_menu();
-                }
-            });
+            if !auditor {
+                ui.menu_button("编辑", |ui| {
+                    if ui.button("添加中间层").clicked() {
+                        self.show_new_middleware_dialog = true;
+                        ui.close_menu();
+                    }
+                    if ui.button("添加后端").clicked() {
+                        self.show_new_backend_dialog = true;
+                        ui.close_menu();
+                    }
+                    if ui.button("从剪贴板粘贴").clicked() {
+                        self.paste_entity_from_clipboard();
+                        ui.close_menu();
+                    }
+                });
+            }
             
             ui.menu_button("视图", |ui| {
-                if ui.button("业务组").clicked() {
-                    self.current_tab = AppTab::BusinessGroups;
-                    ui.close_menu();
-                }
-                if ui.button("中间层").clicked() {
-                    self.current_tab = AppTab::Middleware;
-                    ui.close_menu();
-                }
-                if ui.button("后端").clicked() {
-                    self.current_tab = AppTab::Backend;
-                    ui.close_menu();
-                }
-                if ui.button("配置").clicked() {
-                    self.current_tab = AppTab::Config;
-                    ui.close_menu();
-                }
-                if ui.button("监控").clicked() {
-                    self.current_tab = AppTab::Monitor;
-                    ui.close_menu();
+                for tab in AppTab::ALL.into_iter().filter(|t| t.visible_in(self.ui_profile)) {
+                    if ui.button(tab.label()).clicked() {
+                        self.current_tab = tab;
+                        ui.close_menu();
+                    }
                 }
-                if ui.button("日志").clicked() {
-                    self.current_tab = AppTab::Logs;
+                ui.separator();
+                let mut show_diagnostics = self.preferences.show_diagnostics;
+                if ui.checkbox(&mut show_diagnostics, "诊断浮层").on_hover_text("显示帧耗时、状态锁等待、配置保存耗时与待完成的后台任务").changed() {
+                    self.set_show_diagnostics(show_diagnostics);
                     ui.close_menu();
                 }
+                ui.menu_button("界面配置", |ui| {
+                    let limit = self.ui_profile_limit;
+                    for profile in UiProfile::ALL.into_iter().filter(|p| p.within(limit)) {
+                        if ui.radio(self.ui_profile == profile, profile.label()).on_hover_text(profile.description()).clicked() {
+                            self.switch_ui_profile(profile);
+                            ui.close_menu();
+                        }
+                    }
+                });
             });
             
             ui.menu_button("帮助", |ui| {
@@ -239,42 +1795,129 @@ impl App {
             ui.heading("加密服务管理器");
             ui.separator();
             
-            if ui.selectable_label(self.current_tab == AppTab::BusinessGroups, "业务组").clicked() {
-                self.current_tab = AppTab::BusinessGroups;
-            }
-            if ui.selectable_label(self.current_tab == AppTab::Middleware, "中间层").clicked() {
-                self.current_tab = AppTab::Middleware;
+            for tab in AppTab::ALL.into_iter().filter(|t| t.visible_in(self.ui_profile)) {
+                if ui.selectable_label(self.current_tab == tab, tab.label()).clicked() {
+                    self.current_tab = tab;
+                }
             }
-            if ui.selectable_label(self.current_tab == AppTab::Backend, "后端").clicked() {
-                self.current_tab = AppTab::Backend;
-            }
-            if ui.selectable_label(self.current_tab == AppTab::Config, "配置").clicked() {
-                self.current_tab = AppTab::Config;
-            }
-            if ui.selectable_label(self.current_tab == AppTab::Monitor, "监控").clicked() {
-                self.current_tab = AppTab::Monitor;
-            }
-            if ui.selectable_label(self.current_tab == AppTab::Logs, "日志").clicked() {
-                self.current_tab = AppTab::Logs;
+            
+            // 审计视图不能进入业务组、中间层与后端页面，不显示业务组树
+            if self.ui_profile == UiProfile::Auditor {
+                return;
             }
             
             ui.separator();
             
             ui.heading("业务组列表");
-            ScrollArea::vertical().show(ui, |ui| {
-                for group in &self.business_groups {
-                    let is_selected = self.selected_group_id == Some(group.id.clone());
-                    if ui.selectable_label(is_selected, &group.name).clicked() {
-                        self.selected_group_id = Some(group.id.clone());
-                        self.selected_middleware_id = None;
-                        self.selected_backend_id = None;
-                        self.current_tab = AppTab::BusinessGroups;
-                    }
+            // 只渲染滚动区域内可见的行，大量后端时每帧的开销与可见行数相关
+            let rows = self.tree_rows(ui.ctx());
+            let row_height = ui.spacing().interact_size.y;
+            ScrollArea::vertical().show_rows(ui, row_height, rows.len(), |ui, range| {
+                for row in &rows[range] {
+                    ui.horizontal(|ui| {
+                        ui.set_height(row_height);
+                        self.render_tree_row(ui, *row);
+                    });
                 }
             });
         });
     }
     
+    /// 侧边栏业务组节点的展开状态
+    fn group_tree_state(ctx: &egui::Context, group_id: &str) -> egui::collapsing_header::CollapsingState {
+        egui::collapsing_header::CollapsingState::load_with_default_open(ctx, egui::Id::new(("group_tree", group_id)), false)
+    }
+    
+    /// 按筛选条件与展开状态把业务组树展开为行
+    fn tree_rows(&self, ctx: &egui::Context) -> Vec<TreeRow> {
+        let mut rows = Vec::new();
+        for (g, group) in self.business_groups.iter().enumerate() {
+            if !Self::group_visible(&self.status_filters, group) {
+                continue;
+            }
+            rows.push(TreeRow::Group(g));
+            if !Self::group_tree_state(ctx, &group.id).is_open() {
+                continue;
+            }
+            for (m, middleware) in group.middlewares.iter().enumerate() {
+                if !Self::middleware_visible(&self.status_filters, middleware) {
+                    continue;
+                }
+                rows.push(TreeRow::Middleware(g, m));
+                rows.extend(middleware.backend_containers
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, b)| Self::backend_visible(&self.status_filters, b))
+                    .map(|(b, _)| TreeRow::Backend(g, Some(m), b)));
+            }
+            rows.extend(group.backend_containers
+                .iter()
+                .enumerate()
+                .filter(|(_, b)| Self::backend_visible(&self.status_filters, b))
+                .map(|(b, _)| TreeRow::Backend(g, None, b)));
+        }
+        rows
+    }
+    
+    /// 渲染侧边栏业务组树的一行，组节点汇总显示故障实体数量
+    fn render_tree_row(&mut self, ui: &mut egui::Ui, row: TreeRow) {
+        match row {
+            TreeRow::Group(g) => {
+                let group = &self.business_groups[g];
+                let (group_id, name, status, unhealthy) = (group.id.clone(), group.name.clone(), group.rollup_status(), group.unhealthy_count());
+                let mut state = Self::group_tree_state(ui.ctx(), &group_id);
+                state.show_toggle_button(ui, egui::collapsing_header::paint_default_icon);
+                state.store(ui.ctx());
+                ui.label(RichText::new("●").color(Self::group_status_color(&status)))
+                    .on_hover_text(status.label());
+                let is_selected = self.selected_group_id.as_ref() == Some(&group_id)
+                    && self.selected_middleware_id.is_none()
+                    && self.selected_backend_id.is_none();
+                if ui.selectable_label(is_selected, &name).clicked() {
+                    self.selected_group_id = Some(group_id.clone());
+                    self.selected_middleware_id = None;
+                    self.selected_backend_id = None;
+                    self.current_tab = AppTab::BusinessGroups;
+                }
+                if unhealthy > 0 {
+                    ui.label(RichText::new(format!("⚠ {}", unhealthy)).color(Color32::RED))
+                        .on_hover_text(format!("{} 个中间层或后端故障", unhealthy));
+                }
+                if !self.state_store.access().can_manage(&group_id) {
+                    ui.weak("只读").on_hover_text("当前操作人不能修改此业务组");
+                }
+            }
+            TreeRow::Middleware(g, m) => {
+                let group = &self.business_groups[g];
+                let middleware = &group.middlewares[m];
+                let (group_id, middleware_id, name) = (group.id.clone(), middleware.id.clone(), middleware.name.clone());
+                ui.add_space(TREE_INDENT);
+                Self::container_glyphs(ui, &middleware.status, &middleware.health);
+                let is_selected = self.selected_middleware_id.as_ref() == Some(&middleware_id);
+                if ui.selectable_label(is_selected, &name).clicked() {
+                    self.selected_group_id = Some(group_id);
+                    self.selected_middleware_id = Some(middleware_id);
+                    self.current_tab = AppTab::Middleware;
+                }
+            }
+            TreeRow::Backend(g, m, b) => {
+                let group = &self.business_groups[g];
+                let backends = m.map_or(&group.backend_containers, |m| &group.middlewares[m].backend_containers);
+                let backend = &backends[b];
+                let (group_id, backend_id, name) = (group.id.clone(), backend.id.clone(), backend.name.clone());
+                ui.add_space(if m.is_some() { TREE_INDENT * 2.0 } else { TREE_INDENT });
+                Self::container_glyphs(ui, &backend.status, &backend.health);
+                let is_selected = self.selected_backend_id.as_ref() == Some(&backend_id);
+                if ui.selectable_label(is_selected, &name).clicked() {
+                    self.selected_group_id = Some(group_id);
+                    self.selected_backend_id = Some(backend_id.clone());
+                    self.current_tab = AppTab::Backend;
+                }
+                self.render_probe_badge(ui, &backend_id);
+            }
+        }
+    }
+    
     /// 渲染业务组标签页
     fn render_business_groups_tab(&mut self, ui: &mut egui::Ui) {
         ui.vertical(|ui| {
@@ -283,15 +1926,19 @@ impl App {
                 if ui.button("新建业务组").clicked() {
                     self.show_new_group_dialog = true;
                 }
+                if ui.button("从剪贴板粘贴").clicked() {
+                    self.paste_entity_from_clipboard();
+                }
             });
             ui.separator();
             
             // 复制选中的组ID，避免借用冲突
             let selected_group_id = self.selected_group_id.clone();
+            let filters = self.status_filters.clone();
             
             if let Some(selected_group_id) = selected_group_id {
                 // 重新获取组数据，避免借用冲突
-                if let Some(group) = self.business_group_service.get_business_group(&selected_group_id).unwrap() {
+                if let Some(group) = self.business_groups.iter().find(|g| g.id == selected_group_id).cloned() {
                     ui.heading(&group.name);
                     
                     // 保存组ID用于闭包中使用
@@ -299,34 +1946,115 @@ impl App {
                     
                     ui.horizontal(|ui| {
                         ui.label("状态:");
-                        ui.label(Self::get_status_text(&group.status));
+                        ui.label(Self::get_status_text(&group.rollup_status()));
                         
                         ui.add_space(10.0);
                         
-                        if ui.button("启动").clicked() {
-                            self.business_group_service.start_business_group(&group_id).unwrap();
-                            self.load_business_groups();
+                        if ui.button("启动").on_hover_text("先列出每个容器将执行的操作，确认后启动").clicked() {
+                            self.open_plan(&[&group], PlannedOperation::Start);
                         }
-                        if ui.button("停止").clicked() {
-                            self.business_group_service.stop_business_group(&group_id).unwrap();
-                            self.load_business_groups();
+                        if ui.button("停止").on_hover_text("先列出每个容器将执行的操作，确认后停止").clicked() {
+                            self.open_plan(&[&group], PlannedOperation::Stop);
                         }
-                        if ui.button("重启").clicked() {
-                            self.business_group_service.restart_business_group(&group_id).unwrap();
-                            self.load_business_groups();
+                        let upgrades = self.group_upgrade_targets(&group);
+                        let hover = if upgrades.is_empty() { "组内容器的镜像没有可用的更新".to_string() } else { format!("{} 个容器的镜像可升级", upgrades.len()) };
+                        if ui.add_enabled(!upgrades.is_empty() && !self.image_service.is_pulling(), egui::Button::new("升级镜像"))
+                            .on_hover_text(hover)
+                            .clicked()
+                        {
+                            self.open_plan(&[&group], PlannedOperation::Upgrade(upgrades));
                         }
-                        if ui.button("删除").clicked() {
-                            self.business_group_service.delete_business_group(&group_id).unwrap();
-                            self.selected_group_id = None;
-                            self.load_business_groups();
+                        if ui.add_enabled(!self.container_action_service.is_busy(&group_id), egui::Button::new("重启")).clicked() {
+                            self.container_action_service.submit(ContainerAction::RestartGroup { group_id: group_id.clone() });
+                        }
+                        if ui.button("滚动重启")
+                            .on_hover_text("逐个重启运行中的后端，每个后端通过健康探测后再重启下一个")
+                            .clicked()
+                        {
+                            self.enqueue_job(JobKind::RestartGroup {
+                                group_id: group_id.clone(),
+                                group_name: group.name.clone(),
+                                mode: RestartMode::Rolling,
+                            });
+                        }
+                        if ui.button("后台启动").on_hover_text("先列出每个容器将执行的操作，确认后在后台任务中启动").clicked() {
+                            self.open_plan(&[&group], PlannedOperation::Start);
                         }
+                        if ui.add_enabled(!self.health_service.is_checking_group(), egui::Button::new("立即检查全部"))
+                            .on_hover_text("立即并发探测组内所有运行中的容器，不等待探测间隔")
+                            .clicked()
+                        {
+                            let result = self.health_service.check_group_now(&GroupId::from(&group_id));
+                            self.report_error(result);
+                        }
+                        if ui.add_enabled(!self.container_action_service.is_busy(&group_id), egui::Button::new("删除")).clicked() {
+                            self.container_action_service.submit(ContainerAction::DeleteGroup { group_id: group_id.clone() });
+                        }
+                        if ui.button("复制JSON").clicked() {
+                            Self::copy_entity_json(ui, &group);
+                        }
+                        if ui.button("导出为compose").clicked() {
+                            self.export_compose(&group);
+                        }
+                        if ui.button("创建快照").on_hover_text("将容器定义、中间层配置与命名卷数据保存为一个快照文件").clicked() {
+                            self.snapshot_draft = Some(SnapshotDraft {
+                                group_id: group_id.clone(),
+                                group_name: group.name.clone(),
+                                path: format!("snapshot_{}_{}.tar.gz", group.name, Utc::now().format("%Y%m%d_%H%M%S")),
+                                include_volumes: true,
+                                volumes: snapshot::named_volumes(&group).into_iter().map(|v| v.name).collect(),
+                            });
+                        }
+                    });
+                    
+                    if let Some(profile_id) = self.render_network_profile_picker(ui, &group) {
+                        self.report_error(self.network_service.assign(&GroupId::from(&group_id), profile_id));
+                        self.load_business_groups();
+                    }
+                    ui.horizontal(|ui| {
+                        ui.label("Docker网络:");
+                        match &group.docker_network {
+                            Some(network) if network.subnet.trim().is_empty() => ui.label(&network.name),
+                            Some(network) => ui.label(format!("{} ({})", network.name, network.subnet.trim())),
+                            None => ui.label("未创建，容器使用运行参数中的网络"),
+                        };
                     });
+                    ui.horizontal(|ui| {
+                        ui.label("后端启动顺序:");
+                        let mut order = group.backend_start_order;
+                        egui::ComboBox::from_id_source(("backend_start_order", &group_id))
+                            .selected_text(order.label())
+                            .show_ui(ui, |ui| {
+                                for option in BackendStartOrder::ALL {
+                                    ui.selectable_value(&mut order, option, option.label());
+                                }
+                            });
+                        if order != group.backend_start_order {
+                            let mut updated = group.clone();
+                            updated.backend_start_order = order;
+                            self.report_error(self.business_group_service.update_business_group(updated));
+                            self.load_business_groups();
+                        }
+                        let stages: Vec<String> = order
+                            .stages(group.middlewares.iter().flat_map(|m| m.backend_containers.iter()).chain(group.backend_containers.iter()))
+                            .iter()
+                            .map(|(label, backends)| format!("{} ({})", label, backends.len()))
+                            .collect();
+                        if !stages.is_empty() {
+                            ui.weak(stages.join(" → "));
+                        }
+                    })
+                    .response
+                    .on_hover_text("写实例优先时，前一批后端通过健康探测后才启动下一批");
                     
                     ui.add_space(10.0);
                     
                     CollapsingHeader::new("中间层容器").show(ui, |ui| {
                         ScrollArea::vertical().show(ui, |ui| {
                             for middleware in &group.middlewares {
+                                if !Self::middleware_visible(&filters, middleware) {
+                                    continue;
+                                }
                                 let middleware_id = middleware.id.clone();
                                 let group_id_clone = group_id.clone();
                                 
@@ -344,9 +2072,12 @@ impl App {
                                             self.current_tab = AppTab::Middleware;
                                         }
                                         if ui.button("删除").clicked() {
-                                            self.middleware_service.delete_middleware(&group_id_clone, &middleware_id).unwrap();
+                                            self.report_error(self.middleware_service.delete_middleware(&GroupId::from(&group_id_clone), &MiddlewareId::from(&middleware_id)));
                                             self.load_business_groups();
                                         }
+                                        if ui.button("复制JSON").clicked() {
+                                            Self::copy_entity_json(ui, middleware);
+                                        }
                                     });
                                 });
                             }
@@ -361,36 +2092,51 @@ impl App {
                     CollapsingHeader::new("直接管理的后端容器").show(ui, |ui| {
                         ScrollArea::vertical().show(ui, |ui| {
                             for backend in &group.backend_containers {
+                                if !Self::backend_visible(&filters, backend) {
+                                    continue;
+                                }
                                 let backend_id = backend.id.clone();
                                 let group_id_clone = group_id.clone();
                                 
-                                ui.collapsing(&backend.name, |ui| {
-                                    ui.horizontal(|ui| {
-                                        ui.label("URL:");
-                                        ui.label(&backend.url);
-                                    });
-                                    ui.horizontal(|ui| {
-                                        ui.label("类型:");
-                                        ui.label(&backend.instance_type);
-                                    });
-                                    ui.horizontal(|ui| {
-                                        ui.label("状态:");
-                                        ui.label(Self::get_container_status_text(&backend.status));
-                                        ui.label("健康状态:");
-                                        ui.label(Self::get_health_status_text(&backend.health));
-                                    });
+                                let id = ui.make_persistent_id(("backend_row", &backend_id));
+                                egui::collapsing_header::CollapsingState::load_with_default_open(ui.ctx(), id, false)
+                                    .show_header(ui, |ui| {
+                                        ui.label(&backend.name);
+                                        self.render_probe_badge(ui, &backend_id);
+                                    })
+                                    .body(|ui| {
+                                            ui.horizontal(|ui| {
+                                                ui.label("URL:");
+                                                ui.label(&backend.url);
+                                            });
+                                            ui.horizontal(|ui| {
+                                                ui.label("类型:");
+                                                ui.label(&backend.instance_type);
+                                            });
+                                            ui.horizontal(|ui| {
+                                                ui.label("状态:");
+                                                ui.label(Self::get_container_status_text(&backend.status));
+                                                ui.label("健康状态:");
+                                                ui.label(Self::get_health_status_text(&backend.health));
+                                            });
                                     
-                                    ui.horizontal(|ui| {
-                                        if ui.button("编辑").clicked() {
-                                            self.selected_backend_id = Some(backend_id.clone());
-                                            self.current_tab = AppTab::Backend;
-                                        }
-                                        if ui.button("删除").clicked() {
-                                            self.backend_service.delete_backend(&group_id_clone, None, &backend_id).unwrap();
-                                            self.load_business_groups();
-                                        }
+                                            ui.horizontal(|ui| {
+                                                if ui.button("编辑").clicked() {
+                                                    self.selected_backend_id = Some(backend_id.clone());
+                                                    self.current_tab = AppTab::Backend;
+                                                }
+                                                if ui.button("删除").clicked() {
+                                                    self.report_error(self.backend_service.delete_backend(&GroupId::from(&group_id_clone), None, &BackendId::from(&backend_id)));
+                                                    self.load_business_groups();
+                                                }
+                                                if ui.button("克隆后端").on_hover_text(CLONE_BACKEND_HINT).clicked() {
+                                                    self.clone_backend(&group_id_clone, None, &backend_id);
+                                                }
+                                                if ui.button("复制JSON").clicked() {
+                                                    Self::copy_entity_json(ui, backend);
+                                                }
+                                            });
                                     });
-                                });
                             }
                         });
                     });
@@ -410,10 +2156,11 @@ impl App {
             // 复制选中的ID，避免借用冲突
             let selected_group_id = self.selected_group_id.clone();
             let selected_middleware_id = self.selected_middleware_id.clone();
+            let filters = self.status_filters.clone();
             
             if let (Some(selected_group_id), Some(selected_middleware_id)) = (selected_group_id, selected_middleware_id) {
-                // 重新获取业务组数据
-                if let Some(group) = self.business_group_service.get_business_group(&selected_group_id).unwrap() {
+                // 使用内存中的业务组数据，配置加载失败时仍可查看
+                if let Some(group) = self.business_groups.iter().find(|g| g.id == selected_group_id).cloned() {
                     // 重新获取中间层数据
                     if let Some(middleware) = group.middlewares.iter().find(|m| m.id == selected_middleware_id) {
                         ui.horizontal(|ui| {
@@ -431,18 +2178,101 @@ impl App {
                         ui.horizontal(|ui| {
                             ui.label("访问URL:");
                             ui.label(&middleware.url);
+                            let profiles = self.network_service.get_profiles();
+                            let host = reqwest::Url::parse(&middleware.api_base_url()).ok().and_then(|u| u.host_str().map(str::to_string));
+                            if let Some(profile) = group.network_profile(&profiles)
+                                && let Some(address) = host.as_deref().and_then(|h| profile.resolve(h))
+                            {
+                                ui.weak(format!("解析为 {}（网络配置 {}）", address, profile.name));
+                            }
                         });
                         
-                        ui.vertical(|ui| {
-                            ui.label("Docker Run参数:");
-                            ui.label(&middleware.docker_run_params);
+                        ui.horizontal(|ui| {
+                            ui.label("服务状态:");
+                            self.render_service_status(ui, "middleware", middleware);
                         });
                         
-                        ui.horizontal(|ui| {
-                            ui.label("Agent状态:");
-                            ui.label(if middleware.agent_installed { "已安装" } else { "未安装" });
+                        if let Some(tunnel) = self.render_ssh_tunnel(ui, &middleware.id, &middleware.tunnel) {
+                            let mut updated = middleware.clone();
+                            updated.tunnel = tunnel;
+                            self.report_error(self.middleware_service.update_middleware(&GroupId::from(&group.id), updated));
+                            self.load_business_groups();
+                        }
+                        
+                        CollapsingHeader::new("容器定义").id_source(("container_spec", &middleware.id)).show(ui, |ui| {
+                            if let Some((runtime, replicas)) = self.render_runtime(ui, &middleware.id, &middleware.runtime, middleware.replicas, true) {
+                                if runtime == middleware.runtime {
+                                    self.container_action_service.submit(ContainerAction::ScaleMiddleware {
+                                        group_id: group.id.clone(),
+                                        middleware_id: middleware.id.clone(),
+                                        replicas,
+                                    });
+                                } else {
+                                    let mut updated = middleware.clone();
+                                    updated.runtime = runtime;
+                                    updated.replicas = replicas;
+                                    self.report_error(self.middleware_service.update_middleware(&GroupId::from(&group.id), updated));
+                                }
+                                self.load_business_groups();
+                            }
+                            if let Some(host_id) = self.render_docker_host_picker(ui, &middleware.id, &middleware.docker_host_id) {
+                                let mut updated = middleware.clone();
+                                updated.docker_host_id = host_id;
+                                self.report_error(self.middleware_service.update_middleware(&GroupId::from(&group.id), updated));
+                                self.load_business_groups();
+                            }
+                            if let Some(policy) = Self::render_restart_policy(ui, &middleware.id, &middleware.restart_policy) {
+                                let mut updated = middleware.clone();
+                                updated.restart_policy = policy;
+                                self.report_error(self.middleware_service.update_middleware(&GroupId::from(&group.id), updated));
+                                self.load_business_groups();
+                            }
+                            if let Some(params) = self.render_container_spec(ui, &middleware.id, &middleware.docker_run_params) {
+                                let mut updated = middleware.clone();
+                                updated.docker_run_params = params;
+                                self.report_error(self.middleware_service.update_middleware(&GroupId::from(&group.id), updated));
+                                self.load_business_groups();
+                            }
+                            if let Some(target) = self.render_image_version(ui, &middleware.id, &middleware.docker_run_params) {
+                                let result = self.upgrade_middleware_image(&group.id, middleware, &target);
+                                self.report_error(result);
+                                self.load_business_groups();
+                            }
+                            if let Some(ports) = self.render_port_mappings(ui, &middleware.id, &middleware.docker_host_id, &middleware.docker_run_params, &middleware.ports) {
+                                let mut updated = middleware.clone();
+                                updated.ports = ports;
+                                self.report_error(self.middleware_service.update_middleware(&GroupId::from(&group.id), updated));
+                                self.load_business_groups();
+                            }
+                            if let Some(volumes) = self.render_volume_mounts(ui, &middleware.id, &middleware.volumes) {
+                                let mut updated = middleware.clone();
+                                updated.volumes = volumes;
+                                self.report_error(self.middleware_service.update_middleware(&GroupId::from(&group.id), updated));
+                                self.load_business_groups();
+                            }
+                            if let Some(limits) = self.render_resource_limits(ui, &middleware.id, &middleware.limits) {
+                                let mut updated = middleware.clone();
+                                updated.limits = limits;
+                                self.report_error(self.middleware_service.update_middleware(&GroupId::from(&group.id), updated));
+                                self.load_business_groups();
+                            }
+                            if let Some(depends_on) = Self::render_start_dependencies(ui, &group, &middleware.id, &middleware.depends_on) {
+                                let mut updated = middleware.clone();
+                                updated.depends_on = depends_on;
+                                self.report_error(self.middleware_service.update_middleware(&GroupId::from(&group.id), updated));
+                                self.load_business_groups();
+                            }
                         });
                         
+                        self.render_agent_status(ui, &group.id, middleware);
+                        
+                        if let Some(link) = &middleware.kubernetes {
+                            ui.horizontal(|ui| {
+                                ui.label("Kubernetes:");
+                                ui.label(format!("{}/{}（状态随集群同步）", link.namespace, link.deployment));
+                            });
+                        }
+                        
                         // 保存ID用于闭包中使用
                         let group_id = group.id.clone();
                         let middleware_id = middleware.id.clone();
@@ -451,68 +2281,163 @@ impl App {
                             ui.label("状态:");
                             ui.label(Self::get_container_status_text(&middleware.status));
                             
-                            if ui.button("启动").clicked() {
-                                self.middleware_service.start_middleware(&group_id, &middleware_id).unwrap();
-                                self.load_business_groups();
+                            let idle = !self.container_action_service.is_busy(&middleware_id);
+                            if ui.add_enabled(idle, egui::Button::new("启动")).clicked() {
+                                self.container_action_service.submit(ContainerAction::StartMiddleware { group_id: group_id.clone(), middleware_id: middleware_id.clone() });
                             }
-                            if ui.button("停止").clicked() {
-                                self.middleware_service.stop_middleware(&group_id, &middleware_id).unwrap();
-                                self.load_business_groups();
+                            if ui.add_enabled(idle, egui::Button::new("停止")).clicked() {
+                                self.container_action_service.submit(ContainerAction::StopMiddleware { group_id: group_id.clone(), middleware_id: middleware_id.clone() });
+                            }
+                            if ui.add_enabled(idle, egui::Button::new("重启")).clicked() {
+                                self.container_action_service.submit(ContainerAction::RestartMiddleware { group_id: group_id.clone(), middleware_id: middleware_id.clone() });
+                            }
+                            if !idle {
+                                ui.spinner();
+                            }
+                            if ui.button("复制JSON").clicked() {
+                                Self::copy_entity_json(ui, middleware);
+                            }
+                            if ui.button("扩容读实例")
+                                .on_hover_text("新建多个读实例并启动，全部通过健康探测后加入调度实例列表")
+                                .clicked()
+                            {
+                                self.scale_out = Some(ScaleOutDraft {
+                                    group_id: group_id.clone(),
+                                    middleware_id: middleware_id.clone(),
+                                    plan: ReadScaleOut::default(),
+                                });
+                            }
+                            let has_reads = middleware.backend_containers.iter().any(|b| b.instance_type == "read");
+                            if ui.add_enabled(has_reads, egui::Button::new("缩容读实例"))
+                                .on_hover_text("将选中的读实例移出调度实例列表，请求完成后停止并删除；写实例不参与选择")
+                                .clicked()
+                            {
+                                self.scale_in = Some(ScaleInDraft {
+                                    group_id: group_id.clone(),
+                                    middleware_id: middleware_id.clone(),
+                                    count: 1,
+                                    selection: ScaleInSelection::default(),
+                                });
                             }
-                            if ui.button("重启").clicked() {
-                                self.middleware_service.restart_middleware(&group_id, &middleware_id).unwrap();
+                        });
+                        
+                        if let Some(probe) = self.render_health_probe(ui, &middleware.id, &middleware.health_probe) {
+                            let mut updated = middleware.clone();
+                            updated.health_probe = probe;
+                            self.report_error(self.middleware_service.update_middleware(&GroupId::from(&group_id), updated));
+                            self.load_business_groups();
+                        }
+                        self.render_status_timeline(ui, &middleware.id, &middleware.status);
+                        
+                        ui.horizontal(|ui| {
+                            let mut warmup = middleware.warmup.clone();
+                            ui.label("启动预热: 连续健康");
+                            ui.add(egui::DragValue::new(&mut warmup.required_successes).clamp_range(1..=20));
+                            ui.label("次，超时 (秒):");
+                            ui.add(egui::DragValue::new(&mut warmup.timeout_secs).clamp_range(1..=600));
+                            ui.checkbox(&mut warmup.round_trip, "加密解密往返测试");
+                            if warmup != middleware.warmup {
+                                let mut updated = middleware.clone();
+                                updated.warmup = warmup;
+                                self.report_error(self.middleware_service.update_middleware(&GroupId::from(&group_id), updated));
                                 self.load_business_groups();
                             }
                         });
                         
+                        if middleware.agent_installed {
+                            CollapsingHeader::new("远程命令").id_source(("remote_commands", &middleware.id)).show(ui, |ui| {
+                                self.render_remote_commands(ui, middleware);
+                            });
+                        }
+                        
                         ui.add_space(10.0);
                         
                         CollapsingHeader::new("调度策略").show(ui, |ui| {
                             ui.horizontal(|ui| {
                                 ui.label("策略:");
-                                let strategy = middleware.config.crud_api.strategy.clone();
-                                ui.label(match strategy {
-                                    SchedulerStrategy::Single => "单容器模式",
-                                    SchedulerStrategy::ReadWriteSplit => "读写分离模式",
-                                    SchedulerStrategy::LoadBalance => "负载均衡模式",
-                                });
+                                ui.label(middleware.config.crud_api.strategy.label());
+                            });
+                            Self::render_strategy_explainer(ui, middleware);
+                        });
+                        
+                        CollapsingHeader::new("容量规划").id_source(("capacity_planner", &middleware.id)).show(ui, |ui| {
+                            self.render_capacity_planner(ui, middleware);
+                        });
+                        
+                        if middleware.config.crud_api.strategy == SchedulerStrategy::LoadBalance {
+                            CollapsingHeader::new("自适应权重").id_source(("adaptive_weights", &middleware.id)).show(ui, |ui| {
+                                self.render_adaptive_weights(ui, &group_id, middleware);
+                            });
+                        }
+                        
+                        CollapsingHeader::new("中间层配置").show(ui, |ui| {
+                            self.render_middleware_config_fields(ui, &group_id, middleware);
+                        });
+                        
+                        // 原始配置包含加密设置，运维视图不显示
+                        if self.ui_profile.can_edit_encryption() {
+                            CollapsingHeader::new("中间层配置 (JSON)").id_source(("middleware_config_json", &middleware.id)).show(ui, |ui| {
+                                self.render_middleware_config_json(ui, &group_id, middleware);
                             });
+                        }
+                        
+                        CollapsingHeader::new("从负载均衡配置导入").id_source(("upstream_import", &middleware.id)).show(ui, |ui| {
+                            self.render_upstream_import(ui, &group_id, middleware);
+                        });
+                        
+                        CollapsingHeader::new("导出负载均衡配置").id_source(("upstream_export", &middleware.id)).show(ui, |ui| {
+                            self.render_upstream_export(ui, middleware);
                         });
                         
                         CollapsingHeader::new("后端容器").show(ui, |ui| {
                             ScrollArea::vertical().show(ui, |ui| {
                                 for backend in &middleware.backend_containers {
+                                    if !Self::backend_visible(&filters, backend) {
+                                        continue;
+                                    }
                                     let backend_id = backend.id.clone();
                                     let group_id_clone = group_id.clone();
                                     let middleware_id_clone = middleware_id.clone();
                                     
-                                    ui.collapsing(&backend.name, |ui| {
-                                        ui.horizontal(|ui| {
-                                            ui.label("URL:");
-                                            ui.label(&backend.url);
-                                        });
-                                        ui.horizontal(|ui| {
-                                            ui.label("类型:");
-                                            ui.label(&backend.instance_type);
-                                        });
-                                        ui.horizontal(|ui| {
-                                            ui.label("状态:");
-                                            ui.label(Self::get_container_status_text(&backend.status));
-                                            ui.label("健康状态:");
-                                            ui.label(Self::get_health_status_text(&backend.health));
-                                        });
+                                    let id = ui.make_persistent_id(("backend_row", &backend_id));
+                                    egui::collapsing_header::CollapsingState::load_with_default_open(ui.ctx(), id, false)
+                                        .show_header(ui, |ui| {
+                                            ui.label(&backend.name);
+                                            self.render_probe_badge(ui, &backend_id);
+                                        })
+                                        .body(|ui| {
+                                                ui.horizontal(|ui| {
+                                                    ui.label("URL:");
+                                                    ui.label(&backend.url);
+                                                });
+                                                ui.horizontal(|ui| {
+                                                    ui.label("类型:");
+                                                    ui.label(&backend.instance_type);
+                                                });
+                                                ui.horizontal(|ui| {
+                                                    ui.label("状态:");
+                                                    ui.label(Self::get_container_status_text(&backend.status));
+                                                    ui.label("健康状态:");
+                                                    ui.label(Self::get_health_status_text(&backend.health));
+                                                });
                                         
-                                        ui.horizontal(|ui| {
-                                            if ui.button("编辑").clicked() {
-                                                self.selected_backend_id = Some(backend_id.clone());
-                                                self.current_tab = AppTab::Backend;
-                                            }
-                                            if ui.button("删除").clicked() {
-                                                self.backend_service.delete_backend(&group_id_clone, Some(&middleware_id_clone as &str), &backend_id).unwrap();
-                                                self.load_business_groups();
-                                            }
+                                                ui.horizontal(|ui| {
+                                                    if ui.button("编辑").clicked() {
+                                                        self.selected_backend_id = Some(backend_id.clone());
+                                                        self.current_tab = AppTab::Backend;
+                                                    }
+                                                    if ui.button("删除").clicked() {
+                                                        self.report_error(self.backend_service.delete_backend(&GroupId::from(&group_id_clone), Some(&MiddlewareId::from(&middleware_id_clone)), &BackendId::from(&backend_id)));
+                                                        self.load_business_groups();
+                                                    }
+                                                    if ui.button("克隆后端").on_hover_text(CLONE_BACKEND_HINT).clicked() {
+                                                        self.clone_backend(&group_id_clone, Some(&middleware_id_clone as &str), &backend_id);
+                                                    }
+                                                    if ui.button("复制JSON").clicked() {
+                                                        Self::copy_entity_json(ui, backend);
+                                                    }
+                                                });
                                         });
-                                    });
                                 }
                                 
                                 if ui.button("添加后端").clicked() {
@@ -528,178 +2453,7433 @@ impl App {
         });
     }
     
-    /// 渲染后端标签页
-    fn render_backend_tab(&mut self, ui: &mut egui::Ui) {
-        ui.vertical(|ui| {
-            ui.heading("后端管理");
-            ui.separator();
-            
-            // 复制选中的ID，避免借用冲突
-            let selected_group_id = self.selected_group_id.clone();
-            let selected_middleware_id = self.selected_middleware_id.clone();
-            let selected_backend_id = self.selected_backend_id.clone();
-            
-            if let (Some(selected_group_id), Some(selected_middleware_id), Some(selected_backend_id)) = 
-                (selected_group_id, selected_middleware_id, selected_backend_id) {
-                
-                // 重新获取业务组数据
-                if let Some(group) = self.business_group_service.get_business_group(&selected_group_id).unwrap() {
-                    // 重新获取中间层数据
-                    if let Some(middleware) = group.middlewares.iter().find(|m| m.id == selected_middleware_id) {
-                        // 重新获取后端数据
-                        if let Some(backend) = middleware.backend_containers.iter().find(|b| b.id == selected_backend_id) {
-                            ui.heading(&backend.name);
-                            
-                            ui.horizontal(|ui| {
-                                ui.label("URL:");
-                                ui.label(&backend.url);
-                            });
-                            
-                            ui.horizontal(|ui| {
-                                ui.label("类型:");
-                                ui.label(&backend.instance_type);
-                            });
-                            
-                            ui.horizontal(|ui| {
-                                ui.label("超时时间 (毫秒):");
-                                ui.label(&backend.timeout.to_string());
-                            });
-                            
-                            ui.horizontal(|ui| {
-                                ui.label("重试次数:");
-                                ui.label(&backend.retries.to_string());
-                            });
-                            
-                            // 保存ID用于闭包中使用
-                            let group_id = group.id.clone();
-                            let middleware_id = middleware.id.clone();
-                            let backend_id = backend.id.clone();
-                            
-                            ui.horizontal(|ui| {
-                                ui.label("状态:");
-                                ui.label(Self::get_container_status_text(&backend.status));
-                                
-                                if ui.button("启动").clicked() {
-                                    self.backend_service.start_backend(
-                                        &group_id,
-                                        Some(&middleware_id as &str),
-                                        &backend_id
-                                    ).unwrap();
-                                    self.load_business_groups();
-                                }
-                                if ui.button("停止").clicked() {
-                                    self.backend_service.stop_backend(
-                                        &group_id,
-                                        Some(&middleware_id as &str),
-                                        &backend_id
-                                    ).unwrap();
-                                    self.load_business_groups();
-                                }
-                                if ui.button("重启").clicked() {
-                                    self.backend_service.restart_backend(
-                                        &group_id,
-                                        Some(&middleware_id as &str),
-                                        &backend_id
-                                    ).unwrap();
-                                    self.load_business_groups();
-                                }
-                            });
-                        }
+    /// 渲染容器所在Docker主机的选择框，修改时返回新的主机ID
+    fn render_docker_host_picker(&self, ui: &mut egui::Ui, entity_id: &str, current: &Option<String>) -> Option<Option<String>> {
+        let hosts = self.docker_service.get_hosts();
+        let name = |id: &Option<String>| match id {
+            None => "本机".to_string(),
+            Some(id) => hosts.iter().find(|h| &h.id == id).map(|h| h.name.clone()).unwrap_or_else(|| "主机已删除".to_string()),
+        };
+        let mut selected = current.clone();
+        ui.horizontal(|ui| {
+            ui.label("Docker主机:");
+            egui::ComboBox::from_id_source(("docker_host", entity_id))
+                .selected_text(name(current))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut selected, None, "本机");
+                    for host in &hosts {
+                        ui.selectable_value(&mut selected, Some(host.id.clone()), format!("{} ({})", host.name, host.url()));
+                    }
+                });
+        });
+        (&selected != current).then_some(selected)
+    }
+    
+    /// 渲染业务组所用网络配置的选择框，修改时返回新的网络配置ID
+    fn render_network_profile_picker(&self, ui: &mut egui::Ui, group: &BusinessGroup) -> Option<Option<String>> {
+        let profiles = self.network_service.get_profiles();
+        let current = &group.network_profile_id;
+        let name = |id: &Option<String>| match id {
+            None => "默认".to_string(),
+            Some(id) => profiles.iter().find(|p| &p.id == id).map(|p| p.name.clone()).unwrap_or_else(|| "配置已删除".to_string()),
+        };
+        let mut selected = current.clone();
+        ui.horizontal(|ui| {
+            ui.label("网络配置:");
+            egui::ComboBox::from_id_source(("network_profile", &group.id))
+                .selected_text(name(current))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut selected, None, "默认");
+                    for profile in &profiles {
+                        ui.selectable_value(&mut selected, Some(profile.id.clone()), &profile.name);
                     }
+                });
+        });
+        (&selected != current).then_some(selected)
+    }
+    
+    /// 渲染容器的状态变化时间线与当前状态的持续时长
+    fn render_status_timeline(&self, ui: &mut egui::Ui, entity_id: &str, status: &ContainerStatus) {
+        let transitions: Vec<&StatusTransition> = self.status_history
+            .iter()
+            .filter(|t| t.entity_id == entity_id)
+            .collect();
+        // 当前状态从最近一次变为该状态时开始计算
+        let since = transitions
+            .iter()
+            .rev()
+            .find(|t| t.field == TransitionField::Status)
+            .filter(|t| t.to == status.label())
+            .map(|t| t.timestamp);
+        let header = match since {
+            Some(since) => format!("状态历史（{}已持续 {}）", status.label(), Self::format_elapsed(chrono::Utc::now() - since)),
+            None => "状态历史".to_string(),
+        };
+        CollapsingHeader::new(header).id_source(("status_timeline", entity_id)).show(ui, |ui| {
+            if transitions.is_empty() {
+                ui.label("暂无状态变化记录");
+                return;
+            }
+            egui::Grid::new(("status_timeline_grid", entity_id)).striped(true).show(ui, |ui| {
+                for transition in transitions.iter().rev().take(STATUS_TIMELINE_ROWS) {
+                    ui.label(transition.timestamp.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S").to_string());
+                    ui.label(transition.field.label());
+                    ui.label(format!("{} → {}", transition.from, transition.to));
+                    ui.end_row();
+                }
+            });
+        });
+    }
+    
+    /// 以天、小时、分钟显示时长，只显示最大的两级
+    fn format_elapsed(elapsed: chrono::Duration) -> String {
+        let minutes = elapsed.num_minutes().max(0);
+        let (days, hours, minutes) = (minutes / 1440, minutes / 60 % 24, minutes % 60);
+        if days > 0 {
+            format!("{}天{}小时", days, hours)
+        } else if hours > 0 {
+            format!("{}小时{}分钟", hours, minutes)
+        } else {
+            format!("{}分钟", minutes)
+        }
+    }
+    
+    /// 渲染健康探测设置，编辑后保存时返回新的设置
+    fn render_health_probe(&mut self, ui: &mut egui::Ui, entity_id: &str, current: &HealthProbe) -> Option<HealthProbe> {
+        let Some((_, probe)) = self.probe_draft.as_mut().filter(|(id, _)| id == entity_id) else {
+            ui.horizontal(|ui| {
+                if current.enabled {
+                    let target = match current.kind {
+                        ProbeKind::Http => current.path.clone(),
+                        ProbeKind::Tcp => "连接端口".to_string(),
+                        ProbeKind::Exec => current.command.clone(),
+                    };
+                    ui.label(format!(
+                        "健康探测: {} {}，每 {} 秒，超时 {} 毫秒，连续失败 {} 次判定不健康",
+                        current.kind.label(),
+                        target,
+                        current.interval_secs,
+                        current.timeout_ms,
+                        current.failure_threshold,
+                    ));
+                    let failures = self.health_service.failure_count(entity_id);
+                    if failures > 0 {
+                        ui.colored_label(Color32::YELLOW, format!("已连续失败 {} 次", failures));
+                    }
+                } else {
+                    ui.label("健康探测: 未启用");
+                }
+                if ui.small_button("编辑").clicked() {
+                    self.probe_draft = Some((entity_id.to_string(), current.clone()));
+                }
+            });
+            return None;
+        };
+        
+        egui::Grid::new(("health_probe_edit", entity_id)).num_columns(2).show(ui, |ui| {
+            ui.label("健康探测:");
+            ui.checkbox(&mut probe.enabled, "启用");
+            ui.end_row();
+            ui.label("方式:");
+            ui.horizontal(|ui| {
+                for kind in ProbeKind::ALL {
+                    ui.radio_value(&mut probe.kind, kind, kind.label());
+                }
+            });
+            ui.end_row();
+            match probe.kind {
+                ProbeKind::Http => {
+                    ui.label("路径:");
+                    ui.text_edit_singleline(&mut probe.path);
+                    ui.end_row();
+                }
+                ProbeKind::Tcp => {}
+                ProbeKind::Exec => {
+                    ui.label("命令:");
+                    ui.add(egui::TextEdit::singleline(&mut probe.command).hint_text("退出码为0视为健康"));
+                    ui.end_row();
                 }
-            } else {
-                ui.label("请选择一个业务组、中间层和后端");
             }
+            ui.label("间隔 (秒):");
+            ui.add(egui::DragValue::new(&mut probe.interval_secs).clamp_range(1..=86400));
+            ui.end_row();
+            ui.label("超时 (毫秒):");
+            ui.add(egui::DragValue::new(&mut probe.timeout_ms).clamp_range(100..=600000));
+            ui.end_row();
+            ui.label("失败阈值:");
+            ui.add(egui::DragValue::new(&mut probe.failure_threshold).clamp_range(1..=100));
+            ui.end_row();
+        });
+        let error = match probe.kind {
+            ProbeKind::Exec if probe.command.trim().is_empty() => Some("请填写探测命令"),
+            _ => None,
+        };
+        if let Some(error) = error {
+            ui.colored_label(Color32::RED, error);
+        }
+        let (mut save, mut cancel) = (false, false);
+        ui.horizontal(|ui| {
+            save = ui.add_enabled(error.is_none(), egui::Button::new("保存")).clicked();
+            cancel = ui.button("取消").clicked();
         });
+        if !save && !cancel {
+            return None;
+        }
+        let (_, probe) = self.probe_draft.take()?;
+        (save && &probe != current).then_some(probe)
+    }
+    
+    /// 渲染容器运行时与副本数，保存时返回新的运行时与副本数；
+    /// 不可选择运行时（中间层下的后端跟随中间层）时只编辑副本数
+    fn render_runtime(&mut self, ui: &mut egui::Ui, entity_id: &str, current: &RuntimeKind, replicas: u32, selectable: bool) -> Option<(RuntimeKind, u32)> {
+        let Some((_, runtime, draft_replicas)) = self.runtime_draft.as_mut().filter(|(id, _, _)| id == entity_id) else {
+            ui.horizontal(|ui| {
+                match current {
+                    RuntimeKind::Docker if selectable => {
+                        ui.label("运行时: Docker");
+                    }
+                    RuntimeKind::Docker => {
+                        ui.label(format!("运行时: Docker，{} 个副本", replicas));
+                    }
+                    RuntimeKind::Kubernetes { namespace } => {
+                        ui.label(format!("运行时: Kubernetes，命名空间 {}，{} 个副本", namespace, replicas));
+                    }
+                }
+                if ui.small_button("编辑").clicked() {
+                    self.runtime_draft = Some((entity_id.to_string(), current.clone(), replicas));
+                }
+            });
+            return None;
+        };
+        
+        egui::Grid::new(("runtime_edit", entity_id)).num_columns(2).show(ui, |ui| {
+            if selectable {
+                ui.label("运行时:");
+                ui.horizontal(|ui| {
+                    let kubernetes = matches!(runtime, RuntimeKind::Kubernetes { .. });
+                    if ui.radio(!kubernetes, "Docker").clicked() {
+                        *runtime = RuntimeKind::Docker;
+                    }
+                    if ui.radio(kubernetes, "Kubernetes").clicked() && !kubernetes {
+                        let namespace = self.kubernetes_service.get_namespace().unwrap_or_default();
+                        *runtime = RuntimeKind::Kubernetes {
+                            namespace: if namespace.is_empty() { "default".to_string() } else { namespace },
+                        };
+                    }
+                });
+                ui.end_row();
+            }
+            match runtime {
+                RuntimeKind::Kubernetes { namespace } => {
+                    if selectable {
+                        ui.label("命名空间:");
+                        ui.text_edit_singleline(namespace);
+                        ui.end_row();
+                    }
+                    ui.label("副本数:");
+                    ui.add(egui::DragValue::new(draft_replicas).clamp_range(0..=100));
+                    ui.end_row();
+                }
+                // Docker运行时只有后端可以运行多个副本
+                RuntimeKind::Docker if !selectable => {
+                    ui.label("副本数:");
+                    ui.add(egui::DragValue::new(draft_replicas).clamp_range(1..=20))
+                        .on_hover_text("每个副本是单独的容器，宿主机端口与地址端口依次加1，并注册到中间层的实例列表");
+                    ui.end_row();
+                }
+                RuntimeKind::Docker => {}
+            }
+        });
+        let error = match runtime {
+            RuntimeKind::Kubernetes { namespace } if namespace.trim().is_empty() => Some("请填写命名空间"),
+            _ => None,
+        };
+        if let Some(error) = error {
+            ui.colored_label(Color32::RED, error);
+        } else if selectable && runtime != current {
+            ui.weak("切换运行时不会迁移已运行的容器，请先停止后再切换");
+        }
+        let (mut save, mut cancel) = (false, false);
+        ui.horizontal(|ui| {
+            save = ui.add_enabled(error.is_none(), egui::Button::new("保存")).clicked();
+            cancel = ui.button("取消").clicked();
+        });
+        if !save && !cancel {
+            return None;
+        }
+        let (_, runtime, draft_replicas) = self.runtime_draft.take()?;
+        (save && (&runtime != current || draft_replicas != replicas)).then_some((runtime, draft_replicas))
+    }
+    
+    /// 渲染SSH隧道的设置与连接状态，保存或移除时返回新的设置
+    fn render_ssh_tunnel(&mut self, ui: &mut egui::Ui, entity_id: &str, current: &Option<SshTunnel>) -> Option<Option<SshTunnel>> {
+        let Some((_, draft)) = self.tunnel_draft.as_mut().filter(|(id, _)| id == entity_id) else {
+            let mut removed = false;
+            ui.horizontal(|ui| {
+                ui.label("SSH隧道:");
+                let Some(tunnel) = current else {
+                    ui.weak("未使用");
+                    if ui.small_button("配置").clicked() {
+                        self.tunnel_draft = Some((entity_id.to_string(), SshTunnel::default()));
+                    }
+                    return;
+                };
+                ui.label(format!("经 {}", tunnel.bastion));
+                match self.tunnel_service.status(entity_id) {
+                    Some(status @ TunnelStatus::Connected { .. }) => ui.colored_label(Color32::GREEN, status.label()),
+                    Some(status @ TunnelStatus::Failed(_)) => ui.colored_label(Color32::RED, status.label()),
+                    Some(TunnelStatus::Connecting) | None => ui.weak("连接中"),
+                };
+                if ui.small_button("重新连接").clicked() {
+                    self.tunnel_service.reconnect(entity_id);
+                }
+                if ui.small_button("编辑").clicked() {
+                    self.tunnel_draft = Some((entity_id.to_string(), tunnel.clone()));
+                }
+                removed = ui.small_button("移除").clicked();
+            });
+            return removed.then_some(None);
+        };
+        
+        egui::Grid::new(("tunnel_edit", entity_id)).num_columns(2).show(ui, |ui| {
+            ui.label("跳板机:");
+            ui.add(egui::TextEdit::singleline(&mut draft.bastion).hint_text("user@bastion.example.com"));
+            ui.end_row();
+            ui.label("SSH端口:");
+            ui.add(egui::DragValue::new(&mut draft.port).clamp_range(1..=65535));
+            ui.end_row();
+            ui.label("私钥路径:");
+            ui.add(egui::TextEdit::singleline(&mut draft.ssh_key).hint_text("为空时使用ssh默认配置"));
+            ui.end_row();
+            ui.label("本机端口:");
+            ui.add(egui::DragValue::new(&mut draft.local_port).clamp_range(0..=65535))
+                .on_hover_text("0表示自动选择空闲端口");
+            ui.end_row();
+        });
+        let error = draft.bastion.trim().is_empty().then_some("请填写跳板机");
+        if let Some(error) = error {
+            ui.colored_label(Color32::RED, error);
+        } else {
+            ui.weak("使用系统的ssh命令以非交互方式连接，需事先配置好密钥认证");
+        }
+        let (mut save, mut cancel) = (false, false);
+        ui.horizontal(|ui| {
+            save = ui.add_enabled(error.is_none(), egui::Button::new("保存")).clicked();
+            cancel = ui.button("取消").clicked();
+        });
+        if !save && !cancel {
+            return None;
+        }
+        let (_, mut tunnel) = self.tunnel_draft.take()?;
+        tunnel.bastion = tunnel.bastion.trim().to_string();
+        (save && current.as_ref() != Some(&tunnel)).then_some(Some(tunnel))
+    }
+    
+    /// 渲染自动重启策略，修改时返回新的策略
+    fn render_restart_policy(ui: &mut egui::Ui, entity_id: &str, current: &AutoRestartPolicy) -> Option<AutoRestartPolicy> {
+        let mut policy = current.clone();
+        ui.horizontal(|ui| {
+            ui.label("自动重启:");
+            egui::ComboBox::from_id_source(("auto_restart", entity_id))
+                .selected_text(policy.mode.label())
+                .show_ui(ui, |ui| {
+                    for mode in AutoRestartMode::ALL {
+                        ui.selectable_value(&mut policy.mode, mode, mode.label());
+                    }
+                });
+            if policy.mode != AutoRestartMode::Never {
+                ui.label("最多连续");
+                ui.add(egui::DragValue::new(&mut policy.max_retries).clamp_range(0..=100));
+                ui.label("次，首次等待 (秒):");
+                ui.add(egui::DragValue::new(&mut policy.backoff_secs).clamp_range(1..=3600));
+                ui.label("最长等待 (秒):");
+                ui.add(egui::DragValue::new(&mut policy.max_backoff_secs).clamp_range(1..=86400));
+            }
+        })
+        .response
+        .on_hover_text("容器意外退出后由管理器重新启动，等待时间按次数翻倍；次数为0表示不限");
+        (&policy != current).then_some(policy)
+    }
+    
+    /// 渲染端口映射编辑行，返回映射列表的校验结果
+    fn edit_port_mappings(ui: &mut egui::Ui, ports: &mut Vec<PortMapping>) -> anyhow::Result<()> {
+        let mut remove = None;
+        for (index, port) in ports.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.add(egui::TextEdit::singleline(&mut port.host_ip).hint_text("所有地址").desired_width(100.0));
+                ui.label("宿主机:");
+                ui.add(egui::DragValue::new(&mut port.host_port)).on_hover_text("0表示由Docker随机分配");
+                ui.label("→ 容器:");
+                ui.add(egui::DragValue::new(&mut port.container_port));
+                egui::ComboBox::from_id_source(("port_protocol", index))
+                    .width(60.0)
+                    .selected_text(&port.protocol)
+                    .show_ui(ui, |ui| {
+                        for protocol in ["tcp", "udp"] {
+                            ui.selectable_value(&mut port.protocol, protocol.to_string(), protocol);
+                        }
+                    });
+                if ui.small_button("删除").clicked() {
+                    remove = Some(index);
+                }
+            });
+        }
+        if let Some(index) = remove {
+            ports.remove(index);
+        }
+        if ui.small_button("添加端口").clicked() {
+            ports.push(PortMapping::default());
+        }
+        let result = validate_ports(ports);
+        if let Err(e) = &result {
+            ui.colored_label(Color32::RED, e.to_string());
+        }
+        result
+    }
+    
+    /// 容器合并端口映射后与其他容器的冲突，未配置运行参数或参数无法解析的容器不检查
+    fn port_conflicts(&self, entity_id: &str, host_id: &Option<String>, params: &str, ports: &[PortMapping]) -> Vec<PortConflict> {
+        if params.trim().is_empty() {
+            return Vec::new();
+        }
+        let Ok(mut spec) = ContainerSpec::parse(params) else {
+            return Vec::new();
+        };
+        spec.merge_ports(ports);
+        self.docker_service.port_conflicts(entity_id, host_id, &spec.ports)
+    }
+    
+    /// 显示端口冲突警告
+    fn show_port_conflicts(ui: &mut egui::Ui, conflicts: &[PortConflict]) {
+        for conflict in conflicts {
+            ui.colored_label(Color32::YELLOW, format!("⚠ {}", conflict));
+        }
+    }
+    
+    /// 渲染容器上单独配置的端口映射，编辑后保存时返回新的映射列表；存在冲突时保存前提示
+    fn render_port_mappings(&mut self, ui: &mut egui::Ui, entity_id: &str, host_id: &Option<String>, params: &str, current: &[PortMapping]) -> Option<Vec<PortMapping>> {
+        ui.separator();
+        ui.label("附加端口:").on_hover_text("创建容器时附加，与运行参数中容器端口相同的映射以此为准");
+        let Some((_, ports)) = self.port_draft.as_mut().filter(|(id, _)| id == entity_id) else {
+            if current.is_empty() {
+                ui.weak("无");
+            }
+            for port in current {
+                ui.label(port.to_arg());
+            }
+            Self::show_port_conflicts(ui, &self.port_conflicts(entity_id, host_id, params, current));
+            if ui.button("编辑端口").clicked() {
+                self.port_draft = Some((entity_id.to_string(), current.to_vec()));
+            }
+            return None;
+        };
+        
+        let valid = Self::edit_port_mappings(ui, ports).is_ok();
+        let ports = ports.clone();
+        let conflicts = self.port_conflicts(entity_id, host_id, params, &ports);
+        Self::show_port_conflicts(ui, &conflicts);
+        let (mut save, mut cancel) = (false, false);
+        ui.horizontal(|ui| {
+            save = ui.add_enabled(valid, egui::Button::new(if conflicts.is_empty() { "保存" } else { "仍然保存" })).clicked();
+            cancel = ui.button("取消").clicked();
+        });
+        if !save && !cancel {
+            return None;
+        }
+        self.port_draft = None;
+        (save && ports != current).then_some(ports)
+    }
+    
+    /// 渲染卷挂载编辑行，返回挂载列表的校验结果
+    fn edit_volume_mounts(ui: &mut egui::Ui, volumes: &mut Vec<VolumeMount>) -> anyhow::Result<()> {
+        let mut remove = None;
+        for (index, volume) in volumes.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.add(egui::TextEdit::singleline(&mut volume.source).hint_text("宿主机路径或卷名").desired_width(160.0));
+                ui.add(egui::TextEdit::singleline(&mut volume.target).hint_text("容器路径").desired_width(160.0));
+                ui.checkbox(&mut volume.read_only, "只读");
+                if ui.small_button("删除").clicked() {
+                    remove = Some(index);
+                }
+            });
+        }
+        if let Some(index) = remove {
+            volumes.remove(index);
+        }
+        if ui.small_button("添加挂载").clicked() {
+            volumes.push(VolumeMount::default());
+        }
+        let result = validate_volumes(volumes);
+        if let Err(e) = &result {
+            ui.colored_label(Color32::RED, e.to_string());
+        }
+        result
+    }
+    
+    /// 渲染容器上单独配置的卷挂载，编辑后保存时返回新的挂载列表
+    fn render_volume_mounts(&mut self, ui: &mut egui::Ui, entity_id: &str, current: &[VolumeMount]) -> Option<Vec<VolumeMount>> {
+        ui.separator();
+        ui.label("附加挂载:").on_hover_text("创建容器时附加，与运行参数中容器路径相同的挂载以此为准");
+        let Some((_, volumes)) = self.volume_draft.as_mut().filter(|(id, _)| id == entity_id) else {
+            if current.is_empty() {
+                ui.weak("无");
+            }
+            for volume in current {
+                ui.label(format!("{} → {}{}", volume.source, volume.target, if volume.read_only { "（只读）" } else { "" }));
+            }
+            if ui.button("编辑挂载").clicked() {
+                self.volume_draft = Some((entity_id.to_string(), current.to_vec()));
+            }
+            return None;
+        };
+        
+        let valid = Self::edit_volume_mounts(ui, volumes).is_ok();
+        let (mut save, mut cancel) = (false, false);
+        ui.horizontal(|ui| {
+            save = ui.add_enabled(valid, egui::Button::new("保存")).clicked();
+            cancel = ui.button("取消").clicked();
+        });
+        if !save && !cancel {
+            return None;
+        }
+        let (_, volumes) = self.volume_draft.take()?;
+        (save && volumes != current).then_some(volumes)
+    }
+    
+    /// 渲染CPU与内存上限编辑行，返回上限的校验结果
+    fn edit_resource_limits(ui: &mut egui::Ui, limits: &mut ResourceLimits) -> anyhow::Result<()> {
+        ui.horizontal(|ui| {
+            let mut limit_cpus = limits.cpus.is_some();
+            if ui.checkbox(&mut limit_cpus, "限制CPU").changed() {
+                limits.cpus = limit_cpus.then_some(1.0);
+            }
+            if let Some(cpus) = &mut limits.cpus {
+                ui.add(egui::DragValue::new(cpus).speed(0.05).clamp_range(0.01..=1024.0).suffix(" 核"));
+            }
+            let mut limit_memory = limits.memory_mb.is_some();
+            if ui.checkbox(&mut limit_memory, "限制内存").changed() {
+                limits.memory_mb = limit_memory.then_some(512);
+            }
+            if let Some(memory) = &mut limits.memory_mb {
+                ui.add(egui::DragValue::new(memory).speed(16.0).clamp_range(6..=1048576).suffix(" MiB"));
+            }
+        });
+        let result = limits.validate();
+        if let Err(e) = &result {
+            ui.colored_label(Color32::RED, e.to_string());
+        }
+        result
+    }
+    
+    /// 渲染容器上单独配置的资源上限，编辑后保存时返回新的上限；重新创建容器后生效
+    fn render_resource_limits(&mut self, ui: &mut egui::Ui, entity_id: &str, current: &ResourceLimits) -> Option<ResourceLimits> {
+        ui.separator();
+        ui.label("资源上限:").on_hover_text("创建容器时设置，与运行参数中的 --cpus、--memory 同时存在时以此为准；修改后重新创建容器生效");
+        let Some((_, limits)) = self.limits_draft.as_mut().filter(|(id, _)| id == entity_id) else {
+            if current.is_empty() {
+                ui.weak("不限制");
+            } else {
+                ui.label(current.label());
+            }
+            if ui.button("编辑上限").clicked() {
+                self.limits_draft = Some((entity_id.to_string(), current.clone()));
+            }
+            return None;
+        };
+        
+        let valid = Self::edit_resource_limits(ui, limits).is_ok();
+        let (mut save, mut cancel) = (false, false);
+        ui.horizontal(|ui| {
+            save = ui.add_enabled(valid, egui::Button::new("保存")).clicked();
+            cancel = ui.button("取消").clicked();
+        });
+        if !save && !cancel {
+            return None;
+        }
+        let (_, limits) = self.limits_draft.take()?;
+        (save && limits != *current).then_some(limits)
+    }
+    
+    /// 渲染容器的启动依赖，勾选变化时返回新的依赖列表；会形成循环的容器不可勾选，中间层的下属后端总是先启动
+    fn render_start_dependencies(ui: &mut egui::Ui, group: &BusinessGroup, entity_id: &str, current: &[String]) -> Option<Vec<String>> {
+        // 勾选后的启动顺序是否有效，无效时返回循环说明
+        let cycle_with = |id: &str| -> Option<String> {
+            let mut candidate = group.clone();
+            let depends_on: Vec<String> = current.iter().cloned().chain([id.to_string()]).collect();
+            for middleware in &mut candidate.middlewares {
+                if middleware.id == entity_id {
+                    middleware.depends_on = depends_on.clone();
+                }
+                for backend in middleware.backend_containers.iter_mut().filter(|b| b.id == entity_id) {
+                    backend.depends_on = depends_on.clone();
+                }
+            }
+            for backend in candidate.backend_containers.iter_mut().filter(|b| b.id == entity_id) {
+                backend.depends_on = depends_on.clone();
+            }
+            candidate.start_order().err().map(|e| e.to_string())
+        };
+        
+        let mut changed = None;
+        CollapsingHeader::new(format!("启动依赖 ({})", current.len())).id_source(("start_dependencies", entity_id)).show(ui, |ui| {
+            ui.label(RichText::new("启动业务组时，勾选的容器先于本容器启动").small().weak());
+            let own_backends: Vec<&str> = group.middlewares
+                .iter()
+                .filter(|m| m.id == entity_id)
+                .flat_map(|m| m.backend_containers.iter().map(|b| b.id.as_str()))
+                .collect();
+            let candidates = group.middlewares
+                .iter()
+                .map(|m| (m.id.as_str(), format!("中间层 {}", m.name)))
+                .chain(
+                    group.middlewares
+                        .iter()
+                        .flat_map(|m| m.backend_containers.iter())
+                        .chain(group.backend_containers.iter())
+                        .map(|b| (b.id.as_str(), format!("后端 {}", b.name))),
+                )
+                .filter(|(id, _)| *id != entity_id);
+            for (id, label) in candidates {
+                if own_backends.contains(&id) {
+                    ui.add_enabled(false, egui::Checkbox::new(&mut true, label)).on_disabled_hover_text("下属后端总是先于中间层启动");
+                    continue;
+                }
+                let mut checked = current.iter().any(|d| d == id);
+                let cycle = if checked { None } else { cycle_with(id) };
+                let response = ui.add_enabled(cycle.is_none(), egui::Checkbox::new(&mut checked, label));
+                if let Some(cycle) = cycle {
+                    response.on_disabled_hover_text(cycle);
+                } else if response.changed() {
+                    let mut depends_on: Vec<String> = current.iter().filter(|d| *d != id).cloned().collect();
+                    if checked {
+                        depends_on.push(id.to_string());
+                    }
+                    changed = Some(depends_on);
+                }
+            }
+            if let Err(e) = group.start_order() {
+                ui.colored_label(Color32::RED, e.to_string());
+            }
+        });
+        changed
+    }
+    
+    /// 渲染容器定义，编辑后保存时返回新的Docker运行参数
+    fn render_container_spec(&mut self, ui: &mut egui::Ui, entity_id: &str, params: &str) -> Option<String> {
+        if self.container_spec_draft.as_ref().is_some_and(|d| d.entity_id == entity_id) {
+            return self.render_container_spec_editor(ui);
+        }
+        
+        let spec = if params.trim().is_empty() {
+            ui.label("未配置Docker运行参数，启动和停止只更新状态，容器由外部管理。");
+            None
+        } else {
+            match ContainerSpec::parse(params) {
+                Ok(spec) => Some(spec),
+                Err(e) => {
+                    ui.colored_label(Color32::RED, format!("无法解析Docker运行参数: {:#}", e));
+                    ui.label(params);
+                    return None;
+                }
+            }
+        };
+        
+        if let Some(spec) = &spec {
+            egui::Grid::new(("container_spec_view", entity_id)).num_columns(2).show(ui, |ui| {
+                let rows = [
+                    ("镜像:", spec.image.clone()),
+                    ("名称:", if spec.name.is_empty() { "自动生成".to_string() } else { spec.name.clone() }),
+                    ("启动命令:", spec.command.join(" ")),
+                    ("端口:", spec.ports.iter().map(|p| format!("{}→{}/{}", p.host_port, p.container_port, p.protocol)).collect::<Vec<_>>().join(", ")),
+                    ("环境变量:", spec.env.iter().map(|e| e.key.as_str()).collect::<Vec<_>>().join(", ")),
+                    ("卷:", spec.volumes.iter().map(|v| v.to_arg()).collect::<Vec<_>>().join(", ")),
+                    ("网络:", spec.network.clone()),
+                    ("重启策略:", spec.restart.label().to_string()),
+                ];
+                for (label, value) in rows {
+                    ui.label(label);
+                    ui.label(value);
+                    ui.end_row();
+                }
+            });
+        }
+        if ui.button(if spec.is_some() { "编辑" } else { "配置容器" }).clicked() {
+            let spec = spec.unwrap_or_default();
+            self.container_spec_draft = Some(ContainerSpecDraft {
+                entity_id: entity_id.to_string(),
+                command: shlex::try_join(spec.command.iter().map(String::as_str)).unwrap_or_default(),
+                spec,
+            });
+        }
+        None
+    }
+    
+    /// 渲染容器定义的编辑表单
+    fn render_container_spec_editor(&mut self, ui: &mut egui::Ui) -> Option<String> {
+        let draft = self.container_spec_draft.as_mut()?;
+        let spec = &mut draft.spec;
+        
+        egui::Grid::new(("container_spec_edit", &draft.entity_id)).num_columns(2).show(ui, |ui| {
+            ui.label("镜像:");
+            ui.text_edit_singleline(&mut spec.image);
+            ui.end_row();
+            ui.label("名称:");
+            ui.add(egui::TextEdit::singleline(&mut spec.name).hint_text("留空自动生成"));
+            ui.end_row();
+            ui.label("启动命令:");
+            ui.text_edit_singleline(&mut draft.command);
+            ui.end_row();
+            ui.label("网络:");
+            ui.add(egui::TextEdit::singleline(&mut spec.network).hint_text("默认网络"));
+            ui.end_row();
+            ui.label("重启策略:");
+            egui::ComboBox::from_id_source(("container_restart", &draft.entity_id))
+                .selected_text(spec.restart.label())
+                .show_ui(ui, |ui| {
+                    for policy in ContainerRestartPolicy::ALL {
+                        ui.selectable_value(&mut spec.restart, policy, policy.label());
+                    }
+                });
+            ui.end_row();
+        });
+        
+        ui.label("端口映射（宿主机地址 / 宿主机端口 / 容器端口 / 协议）:");
+        let mut remove = None;
+        for (index, port) in spec.ports.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.add(egui::TextEdit::singleline(&mut port.host_ip).hint_text("所有地址").desired_width(100.0));
+                ui.add(egui::DragValue::new(&mut port.host_port));
+                ui.add(egui::DragValue::new(&mut port.container_port));
+                egui::ComboBox::from_id_source(("container_port_protocol", index))
+                    .selected_text(port.protocol.clone())
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut port.protocol, "tcp".to_string(), "tcp");
+                        ui.selectable_value(&mut port.protocol, "udp".to_string(), "udp");
+                    });
+                if ui.small_button("删除").clicked() {
+                    remove = Some(index);
+                }
+            });
+        }
+        if let Some(index) = remove.take() {
+            spec.ports.remove(index);
+        }
+        if ui.small_button("添加端口").clicked() {
+            spec.ports.push(PortMapping::default());
+        }
+        
+        ui.label("环境变量:");
+        for (index, env) in spec.env.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.add(egui::TextEdit::singleline(&mut env.key).hint_text("名称").desired_width(140.0));
+                ui.label("=");
+                ui.add(egui::TextEdit::singleline(&mut env.value).hint_text("值"));
+                if ui.small_button("删除").clicked() {
+                    remove = Some(index);
+                }
+            });
+        }
+        if let Some(index) = remove.take() {
+            spec.env.remove(index);
+        }
+        if ui.small_button("添加环境变量").clicked() {
+            spec.env.push(EnvVar::default());
+        }
+        
+        ui.label("卷挂载（来源 / 容器路径）:");
+        for (index, volume) in spec.volumes.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.add(egui::TextEdit::singleline(&mut volume.source).hint_text("宿主机路径或卷名").desired_width(160.0));
+                ui.add(egui::TextEdit::singleline(&mut volume.target).hint_text("容器路径").desired_width(160.0));
+                ui.checkbox(&mut volume.read_only, "只读");
+                if ui.small_button("删除").clicked() {
+                    remove = Some(index);
+                }
+            });
+        }
+        if let Some(index) = remove.take() {
+            spec.volumes.remove(index);
+        }
+        if ui.small_button("添加卷").clicked() {
+            spec.volumes.push(VolumeMount::default());
+        }
+        
+        let command = shlex::split(&draft.command);
+        let error = if spec.image.trim().is_empty() {
+            Some("请填写镜像")
+        } else if command.is_none() {
+            Some("启动命令中的引号不匹配")
+        } else if spec.env.iter().any(|e| e.key.trim().is_empty()) {
+            Some("环境变量名称不能为空")
+        } else if spec.volumes.iter().any(|v| v.source.trim().is_empty() || v.target.trim().is_empty()) {
+            Some("卷挂载的来源和容器路径不能为空")
+        } else {
+            None
+        };
+        spec.command = command.unwrap_or_default();
+        
+        ui.add_space(5.0);
+        ui.label("生成的运行参数:");
+        ui.code(spec.to_params());
+        if let Some(error) = error {
+            ui.colored_label(Color32::RED, error);
+        }
+        
+        let params = spec.to_params();
+        let (mut save, mut cancel) = (false, false);
+        ui.horizontal(|ui| {
+            save = ui.add_enabled(error.is_none(), egui::Button::new("保存")).clicked();
+            cancel = ui.button("取消").clicked();
+        });
+        if save || cancel {
+            self.container_spec_draft = None;
+        }
+        save.then_some(params)
+    }
+    
+    /// 渲染负载均衡中间层的自适应权重设置、各后端权重与调整记录
+    fn render_adaptive_weights(&mut self, ui: &mut egui::Ui, group_id: &str, middleware: &MiddlewareContainer) {
+        ui.label("按健康接口的延迟与错误率定期重算未固定后端的权重，并通过配置接口推送到中间层。");
+        ui.horizontal(|ui| {
+            let mut adaptive = middleware.adaptive_weights.clone();
+            ui.checkbox(&mut adaptive.enabled, "自动调整");
+            ui.label("间隔 (秒):");
+            ui.add(egui::DragValue::new(&mut adaptive.interval_secs).clamp_range(10..=86400));
+            ui.label("探测次数:");
+            ui.add(egui::DragValue::new(&mut adaptive.probes).clamp_range(1..=20));
+            if adaptive != middleware.adaptive_weights {
+                let mut updated = middleware.clone();
+                updated.adaptive_weights = adaptive;
+                self.report_error(self.middleware_service.update_middleware(&GroupId::from(group_id), updated));
+                self.load_business_groups();
+            }
+            
+            let adjusting = self.weight_service.is_adjusting(&MiddlewareId::from(&middleware.id));
+            if ui.add_enabled(!adjusting, egui::Button::new("立即调整")).clicked() {
+                let trace_id = api::new_trace_id();
+                self.weight_service.adjust_now(middleware, &trace_id);
+                let action = format!("手动调整 {} 的后端权重", middleware.name);
+                self.record_traced_audit(&action, Some(EntityKind::Middleware), Some(&middleware.id), &trace_id);
+            }
+            if adjusting {
+                ui.spinner();
+            }
+        });
+        
+        if middleware.backend_containers.is_empty() {
+            ui.label("该中间层还没有后端容器。");
+            return;
+        }
+        
+        egui::Grid::new(("backend_weights", &middleware.id)).striped(true).show(ui, |ui| {
+            ui.strong("后端");
+            ui.strong("权重");
+            ui.strong("固定");
+            ui.strong("探测");
+            ui.end_row();
+            for backend in &middleware.backend_containers {
+                ui.label(&backend.name);
+                let mut weight = backend.weight;
+                let mut pinned = backend.weight_pinned;
+                ui.add(egui::DragValue::new(&mut weight).clamp_range(0..=1000));
+                ui.checkbox(&mut pinned, "").on_hover_text("固定后自动调整不再修改该后端的权重");
+                ui.horizontal(|ui| self.render_probe_badge(ui, &backend.id));
+                ui.end_row();
+                // 手动修改权重即视为固定
+                if weight != backend.weight {
+                    pinned = true;
+                }
+                if weight != backend.weight || pinned != backend.weight_pinned {
+                    let result = self.weight_service.set_backend_weight(&MiddlewareId::from(&middleware.id), &BackendId::from(&backend.id), weight, pinned);
+                    self.report_error(result);
+                    self.load_business_groups();
+                }
+            }
+        });
+        
+        let history: Vec<&WeightAdjustment> = self.weight_history
+            .iter()
+            .rev()
+            .filter(|a| a.middleware_id == middleware.id)
+            .take(10)
+            .collect();
+        if history.is_empty() {
+            return;
+        }
+        ui.add_space(5.0);
+        ui.label("调整记录:");
+        for adjustment in history {
+            let trigger = if adjustment.manual { "手动" } else { "自动" };
+            let title = match &adjustment.error {
+                Some(e) => format!("{} {} 失败: {}", adjustment.timestamp.format("%Y-%m-%d %H:%M:%S"), trigger, e),
+                None => format!("{} {}", adjustment.timestamp.format("%Y-%m-%d %H:%M:%S"), trigger),
+            };
+            CollapsingHeader::new(title).id_source(("weight_adjustment", &adjustment.id)).show(ui, |ui| {
+                for entry in &adjustment.entries {
+                    let latency = entry.latency_ms.map(|ms| format!("{:.1} ms", ms)).unwrap_or_else(|| "不可用".to_string());
+                    let pinned = if entry.pinned { "（固定）" } else { "" };
+                    ui.label(format!(
+                        "{}: 延迟 {}，错误率 {:.0}%，权重 {}{}",
+                        entry.backend_name, latency, entry.error_rate * 100.0, entry.weight, pinned,
+                    ));
+                }
+                if let Some(trace_id) = &adjustment.trace_id {
+                    ui.label(format!("追踪ID: {}", trace_id));
+                }
+            });
+        }
+    }
+    
+    /// 收取自适应权重调整结果
+    fn poll_weight_adjustments(&mut self) {
+        let adjustments = self.weight_service.poll();
+        if adjustments.is_empty() {
+            return;
+        }
+        for adjustment in &adjustments {
+            let message = match &adjustment.error {
+                Some(e) => format!("调整 {} 的后端权重失败: {}", adjustment.middleware_name, e),
+                None => {
+                    let weights: Vec<String> = adjustment.entries.iter().map(|e| format!("{}={}", e.backend_name, e.weight)).collect();
+                    format!("已调整 {} 的后端权重: {}", adjustment.middleware_name, weights.join(", "))
+                }
+            };
+            self.push_log(LogEntry::new("权重", &message));
+        }
+        match self.weight_service.get_history() {
+            Ok(history) => self.weight_history = history,
+            Err(e) => tracing::error!("加载权重调整记录失败: {:#}", e),
+        }
+        self.load_business_groups();
+    }
+    
+    /// 渲染调度策略说明，用中间层实际的后端演示请求分配
+    fn render_strategy_explainer(ui: &mut egui::Ui, middleware: &MiddlewareContainer) {
+        for strategy in SchedulerStrategy::ALL {
+            let current = strategy == middleware.config.crud_api.strategy;
+            let title = if current {
+                format!("{}（当前）", strategy.label())
+            } else {
+                strategy.label().to_string()
+            };
+            
+            CollapsingHeader::new(title)
+                .id_source(("strategy_explainer", &middleware.id, strategy.label()))
+                .default_open(current)
+                .show(ui, |ui| {
+                    ui.label(strategy.description());
+                    if middleware.backend_containers.is_empty() {
+                        ui.label("该中间层还没有后端容器，添加后即可查看示例。");
+                        return;
+                    }
+                    
+                    let example = scheduler::examples(&strategy, &middleware.backend_containers);
+                    if example.assumed_running {
+                        ui.colored_label(Color32::GRAY, "当前没有运行中的后端，示例假设所有后端均在运行。");
+                    }
+                    egui::Grid::new(("strategy_examples", &middleware.id, strategy.label()))
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.strong("请求");
+                            ui.strong("类型");
+                            ui.strong("处理实例");
+                            ui.end_row();
+                            for route in &example.routes {
+                                ui.monospace(route.request);
+                                ui.label(route.kind.label());
+                                match &route.backend {
+                                    Some(name) => ui.label(name),
+                                    None => ui.colored_label(Color32::RED, "无可用实例"),
+                                };
+                                ui.end_row();
+                            }
+                        });
+                });
+        }
+    }
+    
+    /// 按观测值生成容量规划的初始输入：单实例容量取观测到的单实例吞吐，基础延迟取后端探测延迟P50的中位数
+    fn observed_capacity_input(&self, middleware: &MiddlewareContainer) -> CapacityInput {
+        let strategy = middleware.config.crud_api.strategy.clone();
+        let observed = capacity::observed_instance_throughput(&self.metrics_service.store, &middleware.id, &strategy, &middleware.backend_containers);
+        let mut latencies: Vec<f64> = middleware.backend_containers
+            .iter()
+            .filter_map(|b| self.health_service.probe_stats(&b.id))
+            .filter_map(|s| LatencyDistribution::from_samples(&s.latency_samples()))
+            .map(|d| d.p50)
+            .collect();
+        latencies.sort_by(|a, b| a.total_cmp(b));
+        let serving = middleware.backend_containers.iter().map(|b| b.replicas.max(1)).sum::<u32>().max(1);
+        CapacityInput {
+            target_qps: observed.map_or(100.0, |qps| (qps * serving as f64).ceil()),
+            read_ratio: 0.8,
+            instance_capacity: observed.map_or(100.0, f64::ceil),
+            base_latency_ms: latencies.get(latencies.len() / 2).copied().unwrap_or(20.0),
+            target_utilization: 0.7,
+            strategy,
+        }
+    }
+    
+    /// 渲染容量规划：按目标负载模拟现有后端在所选策略下的延迟，并推荐所需的读写实例数
+    fn render_capacity_planner(&mut self, ui: &mut egui::Ui, middleware: &MiddlewareContainer) {
+        if !self.capacity_inputs.contains_key(&middleware.id) {
+            let input = self.observed_capacity_input(middleware);
+            self.capacity_inputs.insert(middleware.id.clone(), input);
+        }
+        let observed = capacity::observed_instance_throughput(
+            &self.metrics_service.store,
+            &middleware.id,
+            &middleware.config.crud_api.strategy,
+            &middleware.backend_containers,
+        );
+        let mut reset = false;
+        let Some(input) = self.capacity_inputs.get_mut(&middleware.id) else {
+            return;
+        };
+        
+        ui.label("按M/M/1排队模型估算：利用率为ρ时平均延迟为基础延迟的 1/(1-ρ) 倍，利用率达到100%时视为过载。");
+        match observed {
+            Some(qps) => ui.weak(format!("观测到的单实例吞吐峰值 {:.1} QPS，实例容量不低于此值", qps)),
+            None => ui.weak("尚未采集到请求指标，单实例容量请按压测结果填写"),
+        };
+        egui::Grid::new(("capacity_input", &middleware.id)).num_columns(2).show(ui, |ui| {
+            ui.label("目标QPS:");
+            ui.add(egui::DragValue::new(&mut input.target_qps).clamp_range(0.0..=1_000_000.0).speed(10.0));
+            ui.end_row();
+            ui.label("读请求占比:");
+            ui.add(egui::Slider::new(&mut input.read_ratio, 0.0..=1.0).custom_formatter(|v, _| format!("{:.0}%", v * 100.0)));
+            ui.end_row();
+            ui.label("单实例容量 (QPS):");
+            ui.add(egui::DragValue::new(&mut input.instance_capacity).clamp_range(1.0..=1_000_000.0).speed(10.0));
+            ui.end_row();
+            ui.label("基础延迟 (毫秒):");
+            ui.add(egui::DragValue::new(&mut input.base_latency_ms).clamp_range(0.1..=60_000.0).speed(1.0));
+            ui.end_row();
+            ui.label("目标利用率:");
+            ui.add(egui::Slider::new(&mut input.target_utilization, 0.1..=1.0).custom_formatter(|v, _| format!("{:.0}%", v * 100.0)));
+            ui.end_row();
+            ui.label("调度策略:");
+            egui::ComboBox::from_id_source(("capacity_strategy", &middleware.id))
+                .selected_text(input.strategy.label())
+                .show_ui(ui, |ui| {
+                    for strategy in SchedulerStrategy::ALL {
+                        let label = strategy.label();
+                        ui.selectable_value(&mut input.strategy, strategy, label);
+                    }
+                });
+            ui.end_row();
+        });
+        if ui.small_button("按观测值重置").clicked() {
+            reset = true;
+        }
+        let input = input.clone();
+        
+        ui.separator();
+        let current = SimInstance::from_backends(&middleware.backend_containers);
+        let current_topology = Topology::of(&current);
+        ui.strong(format!(
+            "现有拓扑：读 {}，写 {}，混合 {}",
+            current_topology.read, current_topology.write, current_topology.mixed,
+        ));
+        match capacity::unserved(&input, &current) {
+            Some(problem) => {
+                ui.colored_label(Color32::RED, format!("{}，目标负载中的部分请求无法处理", problem));
+            }
+            None => Self::render_capacity_estimates(ui, ("capacity_current", &middleware.id), &capacity::simulate(&input, &current), input.target_utilization),
+        }
+        
+        ui.separator();
+        let plan = capacity::plan(&input);
+        let delta = |recommended: u32, current: u32| match recommended as i64 - current as i64 {
+            0 => String::new(),
+            d => format!("（{:+}）", d),
+        };
+        ui.strong(format!(
+            "推荐拓扑：读 {}{}，写 {}{}，混合 {}{}，共 {} 个实例",
+            plan.topology.read, delta(plan.topology.read, current_topology.read),
+            plan.topology.write, delta(plan.topology.write, current_topology.write),
+            plan.topology.mixed, delta(plan.topology.mixed, current_topology.mixed),
+            plan.topology.total(),
+        ));
+        for warning in &plan.warnings {
+            ui.colored_label(Color32::from_rgb(255, 165, 0), warning);
+        }
+        Self::render_capacity_estimates(ui, ("capacity_plan", &middleware.id), &plan.estimates, input.target_utilization);
+        
+        if reset {
+            let input = self.observed_capacity_input(middleware);
+            self.capacity_inputs.insert(middleware.id.clone(), input);
+        }
+    }
+    
+    /// 渲染各组实例在目标负载下的单实例估算，利用率超过目标值时以橙色标出，过载时以红色标出
+    fn render_capacity_estimates(ui: &mut egui::Ui, id: impl std::hash::Hash, estimates: &[InstanceEstimate], target_utilization: f64) {
+        egui::Grid::new(id).striped(true).show(ui, |ui| {
+            ui.strong("实例");
+            ui.strong("类型");
+            ui.strong("数量");
+            ui.strong("单实例负载 (QPS)");
+            ui.strong("利用率");
+            ui.strong("平均延迟");
+            ui.strong("P95延迟");
+            ui.end_row();
+            for estimate in estimates {
+                ui.label(&estimate.name);
+                ui.label(&estimate.instance_type);
+                ui.label(estimate.count.to_string());
+                ui.label(format!("{:.1}", estimate.load_qps));
+                let utilization = format!("{:.0}%", estimate.utilization * 100.0);
+                if estimate.utilization >= 1.0 {
+                    ui.colored_label(Color32::RED, utilization);
+                } else if estimate.utilization > target_utilization {
+                    ui.colored_label(Color32::from_rgb(255, 165, 0), utilization);
+                } else {
+                    ui.label(utilization);
+                }
+                match (estimate.mean_latency_ms, estimate.p95_latency_ms) {
+                    (Some(mean), Some(p95)) => {
+                        ui.label(format!("{:.1} ms", mean));
+                        ui.label(format!("{:.1} ms", p95));
+                    }
+                    _ => {
+                        ui.colored_label(Color32::RED, "过载");
+                        ui.colored_label(Color32::RED, "过载");
+                    }
+                }
+                ui.end_row();
+            }
+        });
+    }
+    
+    /// 启动请求成功后开始预热检查
+    fn begin_warmup(&mut self, group_id: &str, middleware: &MiddlewareContainer, result: anyhow::Result<()>) {
+        match result {
+            Ok(()) => {
+                self.push_log(LogEntry::new(&middleware.name, "已启动，开始预热检查"));
+                self.warmup_service.begin(&GroupId::from(group_id), middleware.clone());
+            }
+            Err(e) => self.push_log(LogEntry::new(&middleware.name, &format!("启动失败: {:#}", e))),
+        }
+        self.load_business_groups();
+    }
+    
+    /// 启动失败的原因是镜像不存在时开始拉取镜像，成功后再启动容器；返回是否已开始拉取
+    fn pull_missing_image(&mut self, result: &anyhow::Result<()>, host_id: &Option<String>, pending: PendingStart) -> bool {
+        let Some(missing) = result.as_ref().err().and_then(|e| e.downcast_ref::<docker::MissingImage>()) else {
+            return false;
+        };
+        let image = missing.image.clone();
+        match self.image_service.pull(host_id, &image, Some(pending)) {
+            Ok(()) => {
+                self.push_log(LogEntry::new("镜像", &format!("镜像 {} 不存在，开始拉取", image)));
+                self.load_business_groups();
+                true
+            }
+            Err(e) => {
+                self.push_log(LogEntry::new("镜像", &format!("无法拉取镜像 {}: {:#}", image, e)));
+                false
+            }
+        }
+    }
+    
+    /// 克隆后端并记录审计日志
+    fn clone_backend(&mut self, group_id: &str, middleware_id: Option<&str>, backend_id: &str) {
+        match self.backend_service.clone_backend(&GroupId::from(group_id), middleware_id.map(MiddlewareId::from).as_ref(), &BackendId::from(backend_id)) {
+            Ok(clone) => {
+                let action = format!("克隆后端为 {}（{}）", clone.name, clone.url);
+                self.push_log(LogEntry::new(&clone.name, &action));
+                self.record_audit(&action, Some(EntityKind::Backend), Some(&clone.id));
+            }
+            Err(e) => self.report_error(Err(e)),
+        }
+        self.load_business_groups();
+    }
+    
+    /// 渲染容器镜像的版本检查结果，点击升级时返回目标镜像
+    fn render_image_version(&mut self, ui: &mut egui::Ui, entity_id: &str, params: &str) -> Option<String> {
+        let image = ContainerSpec::parse(params).ok().map(|spec| spec.image).filter(|i| !i.is_empty())?;
+        let mut upgrade = None;
+        ui.horizontal(|ui| {
+            ui.label("镜像:");
+            ui.monospace(&image);
+            if let Some(version) = self.image_update_service.version(entity_id, &image) {
+                if let Some(digest) = &version.digest {
+                    let short = digest.trim_start_matches("sha256:").get(..12).unwrap_or(digest);
+                    ui.label(RichText::new(short).color(Color32::GRAY)).on_hover_text(digest);
+                }
+                let checked = format!("检查于 {}", version.checked_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"));
+                match version.upgrade_target() {
+                    Some(target) => {
+                        let text = match &version.newer_tag {
+                            Some(tag) => format!("有新版本 {}", tag),
+                            None => "仓库中的镜像已更新".to_string(),
+                        };
+                        ui.colored_label(Color32::from_rgb(255, 165, 0), text).on_hover_text(checked);
+                        let button = ui.add_enabled(!self.image_service.is_pulling(), egui::Button::new("升级"))
+                            .on_hover_text("拉取新镜像后按原有配置与挂载重新创建容器");
+                        if button.clicked() {
+                            upgrade = Some(target);
+                        }
+                    }
+                    None if version.error.is_none() => {
+                        ui.colored_label(Color32::GREEN, "已是最新").on_hover_text(checked);
+                    }
+                    None => {}
+                }
+                if let Some(error) = &version.error {
+                    ui.colored_label(Color32::YELLOW, "检查失败").on_hover_text(error);
+                }
+            }
+            if self.image_update_service.is_checking() {
+                ui.spinner();
+            } else if ui.small_button("检查更新").clicked() {
+                self.image_update_service.check_now();
+            }
+        });
+        upgrade
+    }
+    
+    /// 将中间层升级到目标镜像：修改容器定义中的镜像并拉取，运行中的中间层在拉取后重新创建
+    fn upgrade_middleware_image(&mut self, group_id: &str, middleware: &MiddlewareContainer, target: &str) -> anyhow::Result<()> {
+        let mut spec = ContainerSpec::parse(&middleware.docker_run_params)?;
+        let previous = std::mem::replace(&mut spec.image, target.to_string());
+        if previous != target {
+            let mut updated = middleware.clone();
+            updated.docker_run_params = spec.to_params();
+            self.middleware_service.update_middleware(&GroupId::from(group_id), updated)?;
+        }
+        let pending = matches!(middleware.status, ContainerStatus::Running | ContainerStatus::Starting).then(|| PendingStart::Middleware {
+            group_id: group_id.to_string(),
+            middleware_id: middleware.id.clone(),
+        });
+        self.image_service.pull(&middleware.docker_host_id, target, pending)?;
+        self.image_update_service.forget(&middleware.id);
+        let action = format!("升级中间层 {} 的镜像: {} → {}", middleware.name, previous, target);
+        self.push_log(LogEntry::new("镜像", &action));
+        self.record_audit(&action, Some(EntityKind::Middleware), Some(&middleware.id));
+        Ok(())
+    }
+    
+    /// 将后端升级到目标镜像：修改容器定义中的镜像并拉取，运行中的后端在拉取后重新创建
+    fn upgrade_backend_image(&mut self, group_id: &str, middleware_id: Option<&str>, backend: &BackendContainer, target: &str) -> anyhow::Result<()> {
+        let mut spec = ContainerSpec::parse(&backend.docker_run_params)?;
+        let previous = std::mem::replace(&mut spec.image, target.to_string());
+        if previous != target {
+            let mut updated = backend.clone();
+            updated.docker_run_params = spec.to_params();
+            self.backend_service.update_backend(&GroupId::from(group_id), middleware_id.map(MiddlewareId::from).as_ref(), updated)?;
+        }
+        let pending = matches!(backend.status, ContainerStatus::Running | ContainerStatus::Starting).then(|| PendingStart::Backend {
+            group_id: group_id.to_string(),
+            middleware_id: middleware_id.map(str::to_string),
+            backend_id: backend.id.clone(),
+        });
+        self.image_service.pull(&backend.docker_host_id, target, pending)?;
+        self.image_update_service.forget(&backend.id);
+        let action = format!("升级后端 {} 的镜像: {} → {}", backend.name, previous, target);
+        self.push_log(LogEntry::new("镜像", &action));
+        self.record_audit(&action, Some(EntityKind::Backend), Some(&backend.id));
+        Ok(())
+    }
+    
+    /// 处理后端启动结果，镜像不存在时先拉取
+    fn finish_backend_start(
+        &mut self,
+        group_id: &str,
+        middleware_id: Option<&str>,
+        backend_id: &str,
+        host_id: &Option<String>,
+        result: anyhow::Result<()>,
+    ) {
+        let pending = PendingStart::Backend {
+            group_id: group_id.to_string(),
+            middleware_id: middleware_id.map(str::to_string),
+            backend_id: backend_id.to_string(),
+        };
+        if !self.pull_missing_image(&result, host_id, pending) {
+            self.report_error(result);
+            self.load_business_groups();
+        }
+    }
+    
+    /// 收取镜像拉取进度，成功后启动等待该镜像的容器
+    fn poll_image_pull(&mut self) {
+        let Some((image, then)) = self.image_service.poll() else {
+            return;
+        };
+        self.push_log(LogEntry::new("镜像", &format!("镜像 {} 拉取完成", image)));
+        match then {
+            Some(PendingStart::Middleware { group_id, middleware_id }) => {
+                self.container_action_service.submit(ContainerAction::StartMiddleware { group_id, middleware_id });
+            }
+            Some(PendingStart::Backend { group_id, middleware_id, backend_id }) => {
+                self.container_action_service.submit(ContainerAction::StartBackend { group_id, middleware_id, backend_id });
+            }
+            None => {}
+        }
+    }
+
+    /// 收取后台容器操作的结果：启动成功的中间层开始预热，镜像不存在时先拉取镜像，
+    /// 业务组增删与孤儿容器删除完成后刷新界面
+    fn poll_container_actions(&mut self) {
+        for (action, result) in self.container_action_service.poll() {
+            if let Some((kind, id)) = action.audited_entity() {
+                let outcome = match &result {
+                    Ok(()) => String::new(),
+                    Err(e) => format!(" 失败: {:#}", e),
+                };
+                self.record_audit(&format!("{}{} {}{}", action.label(), kind.label(), id, outcome), Some(kind), Some(id));
+            }
+            match action {
+                ContainerAction::StartMiddleware { group_id, middleware_id } | ContainerAction::RestartMiddleware { group_id, middleware_id } => {
+                    let middleware = self.business_groups
+                        .iter()
+                        .flat_map(|g| g.middlewares.iter())
+                        .find(|m| m.id == middleware_id)
+                        .cloned();
+                    let Some(middleware) = middleware else {
+                        self.report_error(result);
+                        continue;
+                    };
+                    let pending = PendingStart::Middleware { group_id: group_id.clone(), middleware_id };
+                    if !self.pull_missing_image(&result, &middleware.docker_host_id, pending) {
+                        self.begin_warmup(&group_id, &middleware, result);
+                    }
+                }
+                ContainerAction::StartBackend { group_id, middleware_id, backend_id } | ContainerAction::RestartBackend { group_id, middleware_id, backend_id } => {
+                    let host_id = self.business_groups
+                        .iter()
+                        .flat_map(|g| g.middlewares.iter().flat_map(|m| m.backend_containers.iter()).chain(g.backend_containers.iter()))
+                        .find(|b| b.id == backend_id)
+                        .and_then(|b| b.docker_host_id.clone());
+                    self.finish_backend_start(&group_id, middleware_id.as_deref(), &backend_id, &host_id, result);
+                }
+                ContainerAction::StopMiddleware { .. }
+                | ContainerAction::StopBackend { .. }
+                | ContainerAction::RestartGroup { .. }
+                | ContainerAction::ScaleMiddleware { .. }
+                | ContainerAction::ScaleBackend { .. } => {
+                    self.report_error(result);
+                    self.load_business_groups();
+                }
+                ContainerAction::CreateGroup { group } => {
+                    if result.is_ok() {
+                        self.push_log(LogEntry::new("业务组", &format!("已添加业务组 {}", group.name)));
+                    }
+                    self.report_error(result);
+                    self.load_business_groups();
+                }
+                ContainerAction::DeleteGroup { group_id } => {
+                    if result.is_ok() && self.selected_group_id.as_deref() == Some(group_id.as_str()) {
+                        self.selected_group_id = None;
+                    }
+                    self.report_error(result);
+                    self.load_business_groups();
+                }
+                ContainerAction::RemoveOrphan { orphan } => self.finish_remove_orphan(&orphan, result),
+            }
+        }
+    }
+    
+    /// 渲染镜像拉取进度对话框
+    fn render_image_pull_dialog(&mut self, ctx: &egui::Context) {
+        let Some(pull) = self.image_service.current() else {
+            return;
+        };
+        let mut cancel = false;
+        let mut dismiss = false;
+        Window::new("拉取镜像")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("镜像:");
+                    ui.monospace(&pull.image);
+                });
+                ui.horizontal(|ui| {
+                    ui.label("主机:");
+                    ui.label(&pull.host_name);
+                });
+                
+                let (completed, layers) = pull.progress.layer_counts();
+                let (current, total) = pull.progress.bytes();
+                let text = if total > 0 {
+                    format!("{}/{} 层，{:.1}/{:.1} MB", completed, layers, current as f64 / 1048576.0, total as f64 / 1048576.0)
+                } else {
+                    format!("{}/{} 层", completed, layers)
+                };
+                let bar = match pull.progress.fraction() {
+                    Some(fraction) => egui::ProgressBar::new(fraction).text(text),
+                    None => egui::ProgressBar::new(0.0).text("正在连接…").animate(pull.is_running()),
+                };
+                ui.add(bar.desired_width(360.0));
+                ui.label(RichText::new(&pull.progress.status).small().weak());
+                
+                if let Some(error) = &pull.error {
+                    ui.colored_label(Color32::RED, error);
+                }
+                ui.horizontal(|ui| {
+                    if pull.is_running() {
+                        cancel = ui.button("取消").clicked();
+                    } else {
+                        dismiss = ui.button("关闭").clicked();
+                    }
+                });
+            });
+        if cancel {
+            self.image_service.cancel();
+        }
+        if dismiss {
+            self.image_service.dismiss();
+        }
+    }
+    
+    /// 渲染中间层配置字段编辑
+    fn render_middleware_config_fields(&mut self, ui: &mut egui::Ui, group_id: &str, middleware: &MiddlewareContainer) {
+        if self.config_edit_middleware_id.as_deref() != Some(middleware.id.as_str()) {
+            self.config_edit_middleware_id = Some(middleware.id.clone());
+            self.config_edit_values = AppConfigField::ALL
+                .iter()
+                .map(|field| (*field, field.get(&middleware.config)))
+                .collect();
+            self.config_edit_base = self.config_edit_values.clone();
+        }
+        
+        // 配置模板会写入加密设置
+        let encryption = self.ui_profile.can_edit_encryption();
+        if encryption {
+            self.render_config_presets(ui, group_id, middleware);
+        } else {
+            ui.weak(format!("{}不显示加密设置与配置模板", self.ui_profile.label()));
+        }
+        ui.separator();
+        
+        egui::Grid::new("middleware_config_fields").striped(true).show(ui, |ui| {
+            for field in AppConfigField::ALL.into_iter().filter(|f| encryption || !f.is_encryption()) {
+                let current = field.get(&middleware.config);
+                ui.label(field.label());
+                let base = self.config_edit_base.entry(field).or_insert_with(|| current.clone());
+                let value = self.config_edit_values.entry(field).or_insert_with(|| current.clone());
+                // 其他实例修改了该字段：未编辑的直接跟随，已编辑的提示冲突
+                if *base != current && value == base {
+                    *value = current.clone();
+                    *base = current.clone();
+                }
+                let conflict = *base != current;
+                ui.text_edit_singleline(value);
+                if conflict {
+                    ui.colored_label(Color32::YELLOW, format!("冲突: 其他实例已改为 {}", current));
+                    if ui.button("采用对方").clicked() {
+                        *value = current.clone();
+                        *base = current.clone();
+                    }
+                }
+                let value = value.clone();
+                
+                if ui.add_enabled(value != current, egui::Button::new("保存")).clicked() {
+                    let mut updated = middleware.clone();
+                    let result = field
+                        .set(&mut updated.config, &value)
+                        .and_then(|_| self.middleware_service.update_middleware(&GroupId::from(group_id), updated));
+                    match result {
+                        Ok(_) => {
+                            self.config_edit_base.insert(field, value.clone());
+                        }
+                        Err(e) => self.push_log(LogEntry::new(&middleware.name, &format!("保存配置失败: {}", e))),
+                    }
+                    self.load_business_groups();
+                }
+                if ui.button("应用到其他中间层").clicked() {
+                    self.config_propagation = Some(ConfigPropagation {
+                        field,
+                        value,
+                        source_id: middleware.id.clone(),
+                        targets: BTreeSet::new(),
+                    });
+                }
+                ui.end_row();
+            }
+        });
+    }
+    
+    /// 渲染从nginx upstream块或HAProxy backend段导入后端的面板，备用与停用的服务器默认不选
+    fn render_upstream_import(&mut self, ui: &mut egui::Ui, group_id: &str, middleware: &MiddlewareContainer) {
+        if self.upstream_import.as_ref().is_none_or(|d| d.middleware_id != middleware.id) {
+            self.upstream_import = Some(UpstreamImportDraft {
+                middleware_id: middleware.id.clone(),
+                format: UpstreamFormat::Nginx,
+                path: String::new(),
+                blocks: Vec::new(),
+                selected: BTreeSet::new(),
+            });
+        }
+        let Some(draft) = self.upstream_import.as_mut() else {
+            return;
+        };
+        let mut parse = false;
+        ui.horizontal(|ui| {
+            ui.label("格式:");
+            for format in UpstreamFormat::ALL {
+                ui.radio_value(&mut draft.format, format, format.label());
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("文件:");
+            ui.text_edit_singleline(&mut draft.path);
+            parse = ui.add_enabled(!draft.path.trim().is_empty(), egui::Button::new("解析")).clicked();
+        });
+        let mut error = None;
+        if parse {
+            let result = std::fs::read_to_string(draft.path.trim())
+                .map_err(anyhow::Error::from)
+                .and_then(|content| draft.format.parse(&content));
+            match result {
+                Ok(blocks) => {
+                    draft.selected = blocks
+                        .iter()
+                        .enumerate()
+                        .flat_map(|(b, block)| {
+                            block.servers.iter().enumerate()
+                                .filter(|(_, server)| !server.backup && !server.disabled)
+                                .map(move |(s, _)| (b, s))
+                        })
+                        .collect();
+                    draft.blocks = blocks;
+                }
+                Err(e) => {
+                    draft.blocks.clear();
+                    draft.selected.clear();
+                    error = Some(format!("解析 {} 失败: {:#}", draft.path.trim(), e));
+                }
+            }
+        }
+        
+        for (b, block) in draft.blocks.iter().enumerate() {
+            ui.label(RichText::new(&block.name).strong());
+            egui::Grid::new(("upstream_servers", &middleware.id, b)).striped(true).show(ui, |ui| {
+                ui.label("");
+                ui.label("名称");
+                ui.label("地址");
+                ui.label("权重");
+                ui.label("备注");
+                ui.end_row();
+                for (s, server) in block.servers.iter().enumerate() {
+                    let exists = middleware.backend_containers.iter().any(|backend| backend.url == server.url);
+                    let mut checked = draft.selected.contains(&(b, s));
+                    if ui.add_enabled(!exists, egui::Checkbox::without_text(&mut checked)).changed() {
+                        if checked {
+                            draft.selected.insert((b, s));
+                        } else {
+                            draft.selected.remove(&(b, s));
+                        }
+                    }
+                    ui.label(&server.name);
+                    ui.label(&server.url);
+                    ui.label(server.weight.to_string());
+                    let notes: Vec<&str> = [
+                        (exists, "已存在"),
+                        (server.backup, "备用"),
+                        (server.disabled, "已停用"),
+                    ]
+                    .into_iter()
+                    .filter_map(|(set, note)| set.then_some(note))
+                    .collect();
+                    ui.label(notes.join("、"));
+                    ui.end_row();
+                }
+            });
+            for skipped in &block.skipped {
+                ui.colored_label(Color32::YELLOW, format!("跳过 {}", skipped));
+            }
+        }
+        
+        let servers: Vec<_> = draft.selected
+            .iter()
+            .filter_map(|&(b, s)| draft.blocks.get(b)?.servers.get(s).cloned())
+            .filter(|server| !middleware.backend_containers.iter().any(|backend| backend.url == server.url))
+            .collect();
+        let path = draft.path.trim().to_string();
+        let format = draft.format;
+        let import = !draft.blocks.is_empty()
+            && ui.add_enabled(!servers.is_empty(), egui::Button::new(format!("导入 {} 个后端", servers.len()))).clicked();
+        if let Some(error) = error {
+            self.push_log(LogEntry::new("配置", &error));
+        }
+        if !import {
+            return;
+        }
+        match self.backend_service.import_upstream(&GroupId::from(group_id), &MiddlewareId::from(&middleware.id), &servers) {
+            Ok(backends) => {
+                let action = format!(
+                    "从 {} ({}) 向中间层 {} 导入 {} 个后端",
+                    path,
+                    format.label(),
+                    middleware.name,
+                    backends.len(),
+                );
+                self.push_log(LogEntry::new("配置", &action));
+                self.record_audit(&action, Some(EntityKind::Middleware), Some(&middleware.id));
+                self.upstream_import = None;
+                self.load_business_groups();
+            }
+            Err(e) => self.push_log(LogEntry::new("配置", &format!("导入后端失败: {:#}", e))),
+        }
+    }
+    
+    /// 渲染按中间层的后端与调度策略生成的nginx或HAProxy配置片段
+    fn render_upstream_export(&mut self, ui: &mut egui::Ui, middleware: &MiddlewareContainer) {
+        ui.label("供以外部负载均衡器直接分发CRUD API请求的部署使用，实例列表与权重取自当前配置。");
+        ui.horizontal(|ui| {
+            ui.label("格式:");
+            for format in UpstreamFormat::ALL {
+                ui.radio_value(&mut self.upstream_export_format, format, format.label());
+            }
+        });
+        let format = self.upstream_export_format;
+        let snippet = format.export(middleware);
+        ui.horizontal(|ui| {
+            if ui.button("复制").clicked() {
+                ui.output_mut(|o| o.copied_text = snippet.clone());
+            }
+            if ui.button("导出文件").clicked() {
+                let path = format!(
+                    "{}_{}.{}",
+                    format.label().split_whitespace().next().unwrap_or("upstream").to_lowercase(),
+                    Utc::now().format("%Y%m%d_%H%M%S"),
+                    format.extension(),
+                );
+                let entry = match std::fs::write(&path, &snippet) {
+                    Ok(()) => LogEntry::new("配置", &format!("已导出中间层 {} 的{}配置到 {}", middleware.name, format.label(), path)),
+                    Err(e) => LogEntry::new("配置", &format!("导出{}配置失败: {}", format.label(), e)),
+                };
+                self.push_log(entry);
+            }
+        });
+        let mut preview = snippet;
+        ScrollArea::vertical().id_source(("upstream_export_preview", &middleware.id)).max_height(300.0).show(ui, |ui| {
+            ui.add(egui::TextEdit::multiline(&mut preview).code_editor().interactive(false).desired_width(f32::INFINITY));
+        });
+    }
+    
+    /// 渲染中间层配置的原始JSON编辑器，保存前按AppConfig结构校验
+    fn render_middleware_config_json(&mut self, ui: &mut egui::Ui, group_id: &str, middleware: &MiddlewareContainer) {
+        let current = serde_json::to_string_pretty(&middleware.config).unwrap_or_default();
+        // 切换中间层，或其他实例修改了配置而本地未编辑时重新打开
+        let reopen = match &self.raw_middleware_config {
+            Some((id, base, editor)) => *id != middleware.id || (*base != current && !editor.is_modified()),
+            None => true,
+        };
+        if reopen {
+            let schema = SchemaNode::from_samples(&[
+                serde_json::to_value(AppConfig::schema_sample()).unwrap_or_default(),
+                serde_json::to_value(&middleware.config).unwrap_or_default(),
+            ]);
+            let editor = JsonEditor::new(current.clone(), schema, |text| serde_json::from_str::<AppConfig>(text).map(|_| ()));
+            self.raw_middleware_config = Some((middleware.id.clone(), current.clone(), editor));
+        }
+        let Some((_, base, editor)) = &mut self.raw_middleware_config else {
+            return;
+        };
+        let conflict = *base != current;
+        editor.show(ui, &format!("middleware_config_json_{}", middleware.id));
+        let valid = editor.error().is_none();
+        let text = editor.text().to_string();
+        let mut save = false;
+        let mut revert = false;
+        ui.horizontal(|ui| {
+            save = ui.add_enabled(valid && text != current, egui::Button::new("保存")).clicked();
+            revert = ui.add_enabled(text != current, egui::Button::new("放弃修改")).clicked();
+            if conflict {
+                ui.colored_label(Color32::YELLOW, "冲突: 其他实例已修改该中间层的配置，保存将覆盖对方的修改");
+            }
+        });
+        if revert {
+            self.raw_middleware_config = None;
+        }
+        if save {
+            let mut updated = middleware.clone();
+            let result = serde_json::from_str::<AppConfig>(&text)
+                .map_err(anyhow::Error::from)
+                .and_then(|config| {
+                    updated.config = config;
+                    self.middleware_service.update_middleware(&GroupId::from(group_id), updated)
+                });
+            match result {
+                Ok(()) => {
+                    self.raw_middleware_config = None;
+                    self.config_edit_middleware_id = None;
+                    self.push_log(LogEntry::new(&middleware.name, "已保存编辑的JSON配置"));
+                    self.record_audit("以JSON编辑中间层配置", Some(EntityKind::Middleware), Some(&middleware.id));
+                }
+                Err(e) => self.push_log(LogEntry::new(&middleware.name, &format!("保存配置失败: {:#}", e))),
+            }
+            self.load_business_groups();
+        }
+    }
+    
+    /// 渲染任务进度，启动业务组时显示后端的启动批次，滚动重启时显示正在重启的后端
+    fn render_job_progress(ui: &mut egui::Ui, job: &JobRecord, checkpoint: &JobCheckpoint) -> egui::Response {
+        let progress = format!("进度 {}/{}", checkpoint.completed, checkpoint.total);
+        if let JobKind::RestartGroup { mode: RestartMode::Rolling, .. } = &job.kind {
+            let Some(rolling) = serde_json::from_value::<RollingProgress>(checkpoint.data.clone()).ok().filter(|r| !r.backends.is_empty()) else {
+                return ui.label(progress);
+            };
+            let plan: Vec<String> = rolling.backends
+                .iter()
+                .enumerate()
+                .map(|(index, name)| {
+                    let marker = match index.cmp(&rolling.current) {
+                        std::cmp::Ordering::Less => "✔ ",
+                        std::cmp::Ordering::Equal => "▶ ",
+                        std::cmp::Ordering::Greater => "",
+                    };
+                    format!("{}{}", marker, name)
+                })
+                .collect();
+            let text = match rolling.backends.get(rolling.current) {
+                Some(name) => format!("{}，正在重启 {}", progress, name),
+                None => progress,
+            };
+            return ui.label(text).on_hover_text(plan.join("\n"));
+        }
+        let start = match &job.kind {
+            JobKind::StartGroup { .. } | JobKind::RestartGroup { .. } => serde_json::from_value::<StartProgress>(checkpoint.data.clone()).ok(),
+            _ => None,
+        };
+        let Some(start) = start.filter(|s| !s.stages.is_empty()) else {
+            return ui.label(progress);
+        };
+        let stage = start.stages.get(start.current).map(|(label, _)| label.as_str()).unwrap_or("");
+        let plan: Vec<String> = start.stages
+            .iter()
+            .enumerate()
+            .map(|(index, (label, backends))| {
+                let marker = if index == start.current { "▶ " } else { "" };
+                format!("{}{}: {}", marker, label, backends.join(", "))
+            })
+            .collect();
+        ui.label(format!("{}，正在启动{}（{}）", progress, stage, start.order))
+            .on_hover_text(plan.join("\n"))
+    }
+    
+    /// 渲染配置模板选择，应用时一次写入模板涉及的字段
+    fn render_config_presets(&mut self, ui: &mut egui::Ui, group_id: &str, middleware: &MiddlewareContainer) {
+        let selected = self.config_presets.iter().find(|p| self.selected_config_preset.as_ref() == Some(&p.id)).cloned();
+        let mut apply = false;
+        let mut delete = false;
+        let mut save = false;
+        ui.horizontal(|ui| {
+            ui.label("配置模板:");
+            egui::ComboBox::from_id_source(("config_preset", &middleware.id))
+                .selected_text(selected.as_ref().map(|p| p.name.as_str()).unwrap_or("选择模板"))
+                .show_ui(ui, |ui| {
+                    for preset in &self.config_presets {
+                        let label = if preset.builtin { format!("{}（内置）", preset.name) } else { preset.name.clone() };
+                        ui.selectable_value(&mut self.selected_config_preset, Some(preset.id.clone()), label)
+                            .on_hover_text(&preset.description);
+                    }
+                });
+            apply = ui.add_enabled(selected.is_some(), egui::Button::new("应用")).clicked();
+            delete = ui.add_enabled(selected.as_ref().is_some_and(|p| !p.builtin), egui::Button::new("删除模板")).clicked();
+        });
+        if let Some(preset) = &selected {
+            if !preset.description.is_empty() {
+                ui.weak(&preset.description);
+            }
+            ui.weak(format!(
+                "JWT {}秒/刷新 {}秒，{} {}字节密钥 {}次迭代，{}，健康检查 {}秒，超时 {}毫秒，重试 {}次",
+                preset.jwt_expires_in,
+                preset.jwt_refresh_in,
+                preset.encryption_algorithm,
+                preset.encryption_key_length,
+                preset.encryption_iterations,
+                preset.strategy.label(),
+                preset.health_check_interval,
+                preset.timeout,
+                preset.retries,
+            ));
+        }
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.new_config_preset_name).hint_text("模板名称").desired_width(140.0));
+            save = ui
+                .add_enabled(!self.new_config_preset_name.trim().is_empty(), egui::Button::new("当前配置另存为模板"))
+                .clicked();
+        });
+        
+        if apply && let Some(preset) = selected.as_ref() {
+            let mut updated = middleware.clone();
+            preset.apply(&mut updated.config);
+            match self.middleware_service.update_middleware(&GroupId::from(group_id), updated) {
+                Ok(()) => {
+                    self.push_log(LogEntry::new(&middleware.name, &format!("已应用配置模板 {}", preset.name)));
+                    self.record_audit(&format!("应用配置模板 {}", preset.name), Some(EntityKind::Middleware), Some(&middleware.id));
+                }
+                Err(e) => self.push_log(LogEntry::new(&middleware.name, &format!("应用配置模板失败: {}", e))),
+            }
+            // 重新载入编辑中的字段
+            self.config_edit_middleware_id = None;
+            self.load_business_groups();
+        }
+        if delete && let Some(preset) = selected {
+            self.report_error(self.config_preset_service.delete_preset(&preset.id));
+            self.selected_config_preset = None;
+            self.config_presets = self.config_preset_service.get_presets().unwrap_or_default();
+        }
+        if save {
+            let preset = ConfigPreset::from_config(&self.new_config_preset_name, &middleware.config);
+            self.report_error(self.config_preset_service.save_preset(preset));
+            self.new_config_preset_name.clear();
+            self.config_presets = self.config_preset_service.get_presets().unwrap_or_default();
+        }
+    }
+    
+    /// 渲染业务组立即检查的结果汇总
+    fn render_group_check_dialog(&mut self, ctx: &egui::Context) {
+        let Some(check) = self.health_service.group_check() else {
+            return;
+        };
+        
+        let mut open = true;
+        let mut recheck = false;
+        let finished = check.is_finished();
+        let done = check.rows.iter().filter(|r| r.result.is_some()).count();
+        let healthy = check.rows.iter().filter(|r| r.current() == HealthStatus::Healthy).count();
+        Window::new(format!("健康检查 - {}", check.group_name))
+            .open(&mut open)
+            .resizable(true)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(format!(
+                        "开始于 {}",
+                        check.started_at.with_timezone(&chrono::Local).format("%H:%M:%S"),
+                    ));
+                    if finished {
+                        ui.label(format!("已完成，{}/{} 健康", healthy, check.rows.len()));
+                    } else {
+                        ui.spinner();
+                        ui.label(format!("已返回 {}/{}", done, check.rows.len()));
+                    }
+                });
+                if check.skipped > 0 {
+                    ui.label(RichText::new(format!("跳过 {} 个未运行的容器", check.skipped)).color(Color32::GRAY));
+                }
+                ui.separator();
+                
+                ScrollArea::vertical().id_source("group_check_rows").max_height(320.0).show(ui, |ui| {
+                    egui::Grid::new("group_check_grid").striped(true).show(ui, |ui| {
+                        ui.strong("类型");
+                        ui.strong("名称");
+                        ui.strong("检查前");
+                        ui.strong("检查后");
+                        ui.strong("耗时");
+                        ui.end_row();
+                        for row in &check.rows {
+                            let current = row.current();
+                            ui.label(row.kind.label());
+                            ui.label(&row.name);
+                            ui.label(Self::get_health_status_text(&row.previous));
+                            let changed = row.result.is_some() && current != row.previous;
+                            let text = Self::get_health_status_text(&current);
+                            let response = ui.label(if changed { text.strong() } else { text });
+                            if let Some(Err(e)) = &row.result {
+                                response.on_hover_text(e);
+                            }
+                            match &row.result {
+                                Some(Ok(latency)) => ui.label(format!("{} ms", latency.as_millis())),
+                                Some(Err(_)) => ui.label("-"),
+                                None => ui.spinner(),
+                            };
+                            ui.end_row();
+                        }
+                    });
+                });
+                
+                ui.separator();
+                if ui.add_enabled(finished, egui::Button::new("重新检查")).clicked() {
+                    recheck = true;
+                }
+            });
+        
+        if recheck {
+            let group_id = check.group_id.clone();
+            let result = self.health_service.check_group_now(&GroupId::from(&group_id));
+            self.report_error(result);
+        } else if !open {
+            self.health_service.dismiss_group_check();
+        }
+    }
+    
+    /// 渲染配置字段批量下发对话框
+    fn render_config_propagation_dialog(&mut self, ctx: &egui::Context) {
+        let Some(propagation) = &mut self.config_propagation else {
+            return;
+        };
+        
+        let mut open = true;
+        let mut apply = false;
+        let mut cancel = false;
+        // 先校验取值，避免下发到一半失败
+        let validation = field_validation(propagation.field, &propagation.value);
+        
+        Window::new("应用到其他中间层")
+            .open(&mut open)
+            .resizable(true)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("字段:");
+                    ui.monospace(propagation.field.label());
+                });
+                ui.horizontal(|ui| {
+                    ui.label("新值:");
+                    ui.text_edit_singleline(&mut propagation.value);
+                });
+                if let Err(e) = &validation {
+                    ui.colored_label(Color32::RED, e.to_string());
+                }
+                ui.separator();
+                
+                ui.horizontal(|ui| {
+                    ui.label("目标中间层:");
+                    if ui.small_button("全选").clicked() {
+                        propagation.targets = self.business_groups
+                            .iter()
+                            .flat_map(|g| g.middlewares.iter())
+                            .filter(|m| m.id != propagation.source_id)
+                            .map(|m| m.id.clone())
+                            .collect();
+                    }
+                    if ui.small_button("清空").clicked() {
+                        propagation.targets.clear();
+                    }
+                });
+                ScrollArea::vertical().id_source("propagation_targets").max_height(160.0).show(ui, |ui| {
+                    for group in &self.business_groups {
+                        for middleware in group.middlewares.iter().filter(|m| m.id != propagation.source_id) {
+                            let mut checked = propagation.targets.contains(&middleware.id);
+                            if ui.checkbox(&mut checked, format!("{} / {}", group.name, middleware.name)).changed() {
+                                if checked {
+                                    propagation.targets.insert(middleware.id.clone());
+                                } else {
+                                    propagation.targets.remove(&middleware.id);
+                                }
+                            }
+                        }
+                    }
+                });
+                ui.separator();
+                
+                ui.label("变更预览:");
+                egui::Grid::new("propagation_preview").striped(true).show(ui, |ui| {
+                    ui.strong("中间层");
+                    ui.strong("当前值");
+                    ui.strong("新值");
+                    ui.end_row();
+                    for middleware in self.business_groups
+                        .iter()
+                        .flat_map(|g| g.middlewares.iter())
+                        .filter(|m| propagation.targets.contains(&m.id))
+                    {
+                        let current = propagation.field.get(&middleware.config);
+                        ui.label(&middleware.name);
+                        ui.label(&current);
+                        if current == propagation.value.trim() {
+                            ui.label(RichText::new("无变化").color(Color32::GRAY));
+                        } else {
+                            ui.label(RichText::new(propagation.value.trim()).color(Color32::from_rgb(255, 165, 0)));
+                        }
+                        ui.end_row();
+                    }
+                });
+                
+                ui.horizontal(|ui| {
+                    let enabled = validation.is_ok() && !propagation.targets.is_empty();
+                    if ui.add_enabled(enabled, egui::Button::new(format!("应用到 {} 个中间层", propagation.targets.len()))).clicked() {
+                        apply = true;
+                    }
+                    if ui.button("取消").clicked() {
+                        cancel = true;
+                    }
+                });
+            });
+        
+        if apply && let Some(propagation) = self.config_propagation.take() {
+            let targets: Vec<String> = propagation.targets.into_iter().collect();
+            let entry = match self.middleware_service.apply_config_field(&targets, propagation.field, &propagation.value) {
+                Ok(count) => LogEntry::new("配置", &format!("已将 {} 应用到 {} 个中间层", propagation.field.label(), count)),
+                Err(e) => LogEntry::new("配置", &format!("批量应用配置失败: {}", e)),
+            };
+            self.push_log(entry);
+            self.config_edit_middleware_id = None;
+            self.load_business_groups();
+        } else if cancel || !open {
+            self.config_propagation = None;
+        }
+    }
+    
+    /// 调优对话框中列出的后端及其延迟分布与建议值
+    fn tuning_rows(&self) -> Vec<TuningRow> {
+        let access = self.state_store.access();
+        let mut rows = Vec::new();
+        for group in &self.business_groups {
+            let backends = group.backend_containers
+                .iter()
+                .map(|b| (None, group.name.clone(), b))
+                .chain(group.middlewares.iter().flat_map(|m| {
+                    m.backend_containers.iter().map(move |b| (Some(m.id.clone()), format!("{} / {}", group.name, m.name), b))
+                }));
+            for (middleware_id, location, backend) in backends {
+                let stats = self.health_service.probe_stats(&backend.id);
+                let distribution = stats.and_then(|s| LatencyDistribution::from_samples(&s.latency_samples()));
+                let error_percent = stats.map_or(0.0, |s| s.error_percent());
+                rows.push(TuningRow {
+                    group_id: group.id.clone(),
+                    middleware_id,
+                    backend: backend.clone(),
+                    location,
+                    suggestion: distribution.and_then(|d| tuning::suggest(&d, error_percent)),
+                    distribution,
+                    error_percent,
+                    manageable: access.can_manage(&group.id),
+                });
+            }
+        }
+        rows
+    }
+    
+    /// 渲染后端超时与重试调优对话框，确认后把建议值写入选中的后端并推送实例列表
+    fn render_tuning_dialog(&mut self, ctx: &egui::Context) {
+        if self.tuning_dialog.is_none() {
+            return;
+        }
+        let rows = self.tuning_rows();
+        let Some(dialog) = &mut self.tuning_dialog else {
+            return;
+        };
+        let selectable = |row: &TuningRow| row.manageable && row.suggestion.is_some();
+        dialog.selected.retain(|id| rows.iter().any(|r| &r.backend.id == id && selectable(r)));
+        
+        let mut open = true;
+        let mut apply = false;
+        let mut cancel = false;
+        Window::new("超时与重试调优")
+            .open(&mut open)
+            .resizable(true)
+            .default_width(760.0)
+            .show(ctx, |ui| {
+                ui.weak(format!(
+                    "按最近成功的健康探测计算延迟分布，至少 {} 个样本才给出建议：超时取P99的两倍，重试次数随错误率增加。",
+                    tuning::MIN_SAMPLES,
+                ));
+                ui.horizontal(|ui| {
+                    if ui.small_button("全选有建议的").clicked() {
+                        dialog.selected = rows
+                            .iter()
+                            .filter(|r| selectable(r) && r.suggestion != Some(TuningSuggestion { timeout_ms: r.backend.timeout, retries: r.backend.retries }))
+                            .map(|r| r.backend.id.clone())
+                            .collect();
+                    }
+                    if ui.small_button("清空").clicked() {
+                        dialog.selected.clear();
+                    }
+                });
+                
+                ScrollArea::vertical().id_source("tuning_rows").max_height(280.0).show(ui, |ui| {
+                    egui::Grid::new("tuning_grid").striped(true).show(ui, |ui| {
+                        ui.label("");
+                        ui.strong("后端");
+                        ui.strong("样本");
+                        ui.strong("P50 / P90 / P99 (ms)");
+                        ui.strong("错误率");
+                        ui.strong("当前");
+                        ui.strong("建议");
+                        ui.label("");
+                        ui.end_row();
+                        for row in &rows {
+                            let mut checked = dialog.selected.contains(&row.backend.id);
+                            let checkbox = ui.add_enabled(selectable(row), egui::Checkbox::without_text(&mut checked));
+                            if checkbox.changed() {
+                                if checked {
+                                    dialog.selected.insert(row.backend.id.clone());
+                                } else {
+                                    dialog.selected.remove(&row.backend.id);
+                                }
+                            }
+                            ui.label(&row.backend.name).on_hover_text(&row.location);
+                            match &row.distribution {
+                                Some(d) => {
+                                    ui.label(d.samples.to_string());
+                                    ui.label(format!("{:.0} / {:.0} / {:.0}", d.p50, d.p90, d.p99));
+                                }
+                                None => {
+                                    ui.label("0");
+                                    ui.weak("无数据");
+                                }
+                            }
+                            ui.label(format!("{:.0}%", row.error_percent));
+                            ui.label(format!("{}ms × {}", row.backend.timeout, row.backend.retries));
+                            match row.suggestion {
+                                Some(s) if s.timeout_ms == row.backend.timeout && s.retries == row.backend.retries => {
+                                    ui.label(RichText::new("无变化").color(Color32::GRAY));
+                                }
+                                Some(s) => {
+                                    ui.label(RichText::new(format!("{}ms × {}", s.timeout_ms, s.retries)).color(Color32::from_rgb(255, 165, 0)));
+                                }
+                                None => {
+                                    ui.weak("样本不足");
+                                }
+                            }
+                            if row.distribution.is_some() && ui.small_button("分布").clicked() {
+                                dialog.focus = Some(row.backend.id.clone());
+                            }
+                            ui.end_row();
+                        }
+                    });
+                });
+                
+                if let Some(row) = dialog.focus.as_ref().and_then(|id| rows.iter().find(|r| &r.backend.id == id)) {
+                    ui.separator();
+                    ui.label(format!("{} 的延迟分布", row.backend.name));
+                    let samples = self.health_service.probe_stats(&row.backend.id).map(|s| s.latency_samples()).unwrap_or_default();
+                    let bars: Vec<Bar> = tuning::histogram(&samples, 20)
+                        .into_iter()
+                        .map(|(low, high, count)| Bar::new((low + high) / 2.0, count as f64).width(high - low))
+                        .collect();
+                    Plot::new(("tuning_histogram", &row.backend.id))
+                        .height(140.0)
+                        .legend(Legend::default())
+                        .x_axis_label("延迟 (ms)")
+                        .show(ui, |plot_ui| {
+                            plot_ui.bar_chart(BarChart::new(bars).name("探测次数"));
+                            plot_ui.vline(VLine::new(row.backend.timeout as f64).name("当前超时"));
+                            if let Some(s) = row.suggestion {
+                                plot_ui.vline(VLine::new(s.timeout_ms as f64).color(Color32::from_rgb(255, 165, 0)).name("建议超时"));
+                            }
+                        });
+                }
+                ui.separator();
+                
+                ui.horizontal(|ui| {
+                    let label = format!("应用到 {} 个后端并推送", dialog.selected.len());
+                    if ui.add_enabled(!dialog.selected.is_empty(), egui::Button::new(label)).clicked() {
+                        apply = true;
+                    }
+                    if ui.button("取消").clicked() {
+                        cancel = true;
+                    }
+                });
+            });
+        
+        if apply && let Some(dialog) = self.tuning_dialog.take() {
+            let tunings: Vec<BackendTuning> = rows
+                .into_iter()
+                .filter(|r| dialog.selected.contains(&r.backend.id))
+                .filter_map(|r| Some(BackendTuning {
+                    suggestion: r.suggestion?,
+                    group_id: r.group_id,
+                    middleware_id: r.middleware_id,
+                    backend_id: r.backend.id,
+                }))
+                .collect();
+            match self.backend_service.apply_tuning(&tunings) {
+                Ok(count) => {
+                    self.push_log(LogEntry::new("配置", &format!("已调整 {} 个后端的超时与重试次数", count)));
+                    for tuning in &tunings {
+                        self.record_audit(
+                            &format!("按延迟分布调整超时为 {}ms、重试 {} 次", tuning.suggestion.timeout_ms, tuning.suggestion.retries),
+                            Some(EntityKind::Backend),
+                            Some(&tuning.backend_id),
+                        );
+                    }
+                }
+                Err(e) => self.push_log(LogEntry::new("配置", &format!("批量调整超时与重试失败: {:#}", e))),
+            }
+            self.load_business_groups();
+        } else if cancel || !open {
+            self.tuning_dialog = None;
+        }
+    }
+    
+    /// 渲染扩容读实例对话框，确认后新建实例并提交启动与注册任务
+    fn render_scale_out_dialog(&mut self, ctx: &egui::Context) {
+        let Some(draft) = &mut self.scale_out else {
+            return;
+        };
+        let Some(middleware) = self.business_groups
+            .iter()
+            .filter(|g| g.id == draft.group_id)
+            .flat_map(|g| g.middlewares.iter())
+            .find(|m| m.id == draft.middleware_id)
+        else {
+            self.scale_out = None;
+            return;
+        };
+        
+        let hosts = self.docker_service.get_hosts();
+        let estimator = CostEstimator::new(&self.cost_settings, &hosts, &self.business_groups);
+        let group_cost = self.business_groups
+            .iter()
+            .find(|g| g.id == draft.group_id)
+            .map(|g| estimator.group_cost(g).total())
+            .unwrap_or_default();
+        
+        let mut open = true;
+        let mut confirm = false;
+        let mut cancel = false;
+        Window::new(format!("扩容读实例 - {}", middleware.name))
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let plan = &mut draft.plan;
+                egui::Grid::new("scale_out_form").num_columns(2).show(ui, |ui| {
+                    ui.label("数量:");
+                    ui.add(egui::DragValue::new(&mut plan.count).clamp_range(1..=MAX_SCALE_OUT));
+                    ui.end_row();
+                    ui.label("名称前缀:");
+                    ui.text_edit_singleline(&mut plan.name_prefix);
+                    ui.end_row();
+                    ui.label("地址模板:");
+                    ui.text_edit_singleline(&mut plan.url_pattern);
+                    ui.end_row();
+                    ui.label("起始端口:");
+                    ui.add(egui::DragValue::new(&mut plan.start_port).clamp_range(1..=u16::MAX));
+                    ui.end_row();
+                });
+                ui.label("Docker Run参数模板:");
+                ui.add(egui::TextEdit::multiline(&mut plan.docker_run_params).hint_text("-p {port}:8000 --name read-{index} image:tag").desired_rows(2));
+                ui.label(RichText::new("{index} 替换为序号（从1起），{port} 替换为起始端口加序号减1").small().weak());
+                ui.separator();
+                
+                let backends = plan.backends(middleware);
+                match &backends {
+                    Ok(backends) => {
+                        egui::Grid::new("scale_out_preview").striped(true).show(ui, |ui| {
+                            ui.strong("名称");
+                            ui.strong("地址");
+                            ui.end_row();
+                            for backend in backends {
+                                ui.label(&backend.name);
+                                ui.monospace(&backend.url);
+                                ui.end_row();
+                            }
+                        });
+                        if estimator.is_configured() {
+                            let delta = estimator.backend_delta(backends, &[]);
+                            let shared = backends.iter().any(|b| estimator.shares_host(b));
+                            Self::render_cost_delta(ui, &self.cost_settings, group_cost, delta, shared);
+                        }
+                    }
+                    Err(e) => {
+                        ui.colored_label(Color32::RED, e.to_string());
+                    }
+                }
+                ui.horizontal(|ui| {
+                    confirm = ui.add_enabled(backends.is_ok(), egui::Button::new("创建并启动")).clicked();
+                    cancel = ui.button("取消").clicked();
+                });
+            });
+        
+        if confirm && let Some(draft) = self.scale_out.take() {
+            let middleware_name = middleware.name.clone();
+            match self.backend_service.add_read_replicas(&GroupId::from(&draft.group_id), &MiddlewareId::from(&draft.middleware_id), &draft.plan) {
+                Ok(backends) => self.enqueue_job(JobKind::ScaleOutReads {
+                    group_id: draft.group_id,
+                    middleware_id: draft.middleware_id,
+                    middleware_name,
+                    backend_ids: backends.into_iter().map(|b| b.id).collect(),
+                }),
+                Err(e) => self.push_log(LogEntry::new("后端", &format!("扩容读实例失败: {}", e))),
+            }
+            self.load_business_groups();
+        } else if cancel || !open {
+            self.scale_out = None;
+        }
+    }
+    
+    /// 渲染扩缩容前后业务组的月成本变化
+    fn render_cost_delta(ui: &mut egui::Ui, settings: &CostSettings, before: f64, delta: f64, shared: bool) {
+        ui.horizontal(|ui| {
+            ui.label("预计月成本:");
+            ui.label(format!("{} → {}", settings.format(before), settings.format(before + delta)));
+            let (text, color) = if delta > 0.0 {
+                (format!("+{}", settings.format(delta)), Color32::from_rgb(255, 165, 0))
+            } else if delta < 0.0 {
+                (settings.format(delta), Color32::GREEN)
+            } else {
+                (settings.format(0.0), Color32::GRAY)
+            };
+            ui.colored_label(color, text);
+        });
+        if shared {
+            ui.label(RichText::new("部分实例部署在有月费用的主机上，只改变主机费用的分摊，不改变总成本").small().weak());
+        }
+    }
+    
+    /// 组内有可用更新的容器及其目标镜像
+    fn group_upgrade_targets(&self, group: &BusinessGroup) -> Vec<(String, String)> {
+        let containers = group.middlewares
+            .iter()
+            .map(|m| (&m.id, &m.docker_run_params))
+            .chain(
+                group.middlewares
+                    .iter()
+                    .flat_map(|m| m.backend_containers.iter())
+                    .chain(group.backend_containers.iter())
+                    .map(|b| (&b.id, &b.docker_run_params)),
+            );
+        containers
+            .filter_map(|(id, params)| {
+                let image = ContainerSpec::parse(params).ok()?.image;
+                let target = self.image_update_service.version(id, &image)?.upgrade_target()?;
+                Some((id.clone(), target))
+            })
+            .collect()
+    }
+
+    /// 生成一个或多个业务组操作的执行计划并打开确认对话框
+    fn open_plan(&mut self, groups: &[&BusinessGroup], operation: PlannedOperation) {
+        let mut combined: Option<OperationPlan> = None;
+        for group in groups {
+            let group_id = GroupId::from(&group.id);
+            let plan = match &operation {
+                PlannedOperation::Start => self.business_group_service.plan_start(&group_id),
+                PlannedOperation::Stop => self.business_group_service.plan_stop(&group_id),
+                PlannedOperation::Upgrade(targets) => self.business_group_service.plan_upgrade(&group_id, targets),
+            };
+            let mut plan = match plan {
+                Ok(plan) => plan,
+                Err(e) => {
+                    self.report_error(Err(e));
+                    return;
+                }
+            };
+            // 多个业务组合并为一个计划时，容器名称前加上所属业务组
+            if groups.len() > 1 {
+                for change in &mut plan.changes {
+                    change.name = format!("{} / {}", group.name, change.name);
+                }
+            }
+            match &mut combined {
+                Some(combined) => combined.changes.extend(plan.changes),
+                None => combined = Some(plan),
+            }
+        }
+        let Some(mut plan) = combined else {
+            return;
+        };
+        if groups.len() > 1 {
+            let verb = match &operation {
+                PlannedOperation::Start => "启动",
+                PlannedOperation::Stop => "停止",
+                PlannedOperation::Upgrade(_) => "升级",
+            };
+            plan.title = format!("{}全部 {} 个业务组", verb, groups.len());
+        }
+        self.plan_confirmation = Some(PlanConfirmation {
+            groups: groups.iter().map(|g| (g.id.clone(), g.name.clone())).collect(),
+            plan,
+            operation,
+        });
+    }
+
+    /// 渲染执行计划确认对话框，确认后为每个业务组提交对应的后台任务
+    fn render_plan_dialog(&mut self, ctx: &egui::Context) {
+        let Some(confirmation) = &self.plan_confirmation else {
+            return;
+        };
+        let plan = &confirmation.plan;
+        let mut open = true;
+        let mut confirm = false;
+        let mut cancel = false;
+        Window::new(format!("执行计划 - {}", plan.title))
+            .open(&mut open)
+            .resizable(true)
+            .show(ctx, |ui| {
+                ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                    egui::Grid::new("operation_plan").striped(true).show(ui, |ui| {
+                        ui.strong("操作");
+                        ui.strong("类型");
+                        ui.strong("名称");
+                        ui.strong("说明");
+                        ui.end_row();
+                        for change in &plan.changes {
+                            let color = match change.action {
+                                PlannedAction::Create => Color32::GREEN,
+                                PlannedAction::Stop => Color32::RED,
+                                PlannedAction::Recreate => Color32::from_rgb(255, 165, 0),
+                                PlannedAction::Start | PlannedAction::Update | PlannedAction::StatusOnly => Color32::LIGHT_BLUE,
+                                PlannedAction::Skip => Color32::GRAY,
+                            };
+                            ui.colored_label(color, change.action.label());
+                            ui.label(change.kind.label());
+                            ui.label(&change.name);
+                            ui.label(&change.detail);
+                            ui.end_row();
+                        }
+                    });
+                });
+                ui.separator();
+                if plan.has_changes() {
+                    ui.label(format!("共 {} 个容器: {}", plan.changes.len(), plan.summary()));
+                } else {
+                    ui.label("没有需要执行的操作");
+                }
+                ui.horizontal(|ui| {
+                    confirm = ui.add_enabled(plan.has_changes(), egui::Button::new("执行")).clicked();
+                    cancel = ui.button("取消").clicked();
+                });
+            });
+
+        if confirm && let Some(confirmation) = self.plan_confirmation.take() {
+            for (group_id, group_name) in confirmation.groups {
+                self.enqueue_job(match &confirmation.operation {
+                    PlannedOperation::Start => JobKind::StartGroup { group_id, group_name },
+                    PlannedOperation::Stop => JobKind::StopGroup { group_id, group_name },
+                    PlannedOperation::Upgrade(targets) => JobKind::UpgradeGroup { group_id, group_name, targets: targets.clone() },
+                });
+            }
+        } else if cancel || !open {
+            self.plan_confirmation = None;
+        }
+    }
+
+    /// 渲染缩容读实例对话框，预览按选择方式选中的读实例，确认后提交缩容任务
+    fn render_scale_in_dialog(&mut self, ctx: &egui::Context) {
+        let Some(draft) = &mut self.scale_in else {
+            return;
+        };
+        let Some(middleware) = self.business_groups
+            .iter()
+            .filter(|g| g.id == draft.group_id)
+            .flat_map(|g| g.middlewares.iter())
+            .find(|m| m.id == draft.middleware_id)
+        else {
+            self.scale_in = None;
+            return;
+        };
+        let reads = middleware.backend_containers.iter().filter(|b| b.instance_type == "read").count();
+        let load = |b: &BackendContainer| self.stats_service.latest(&b.id).map(|s| s.stats.cpu_percent).unwrap_or(0.0);
+        
+        let mut open = true;
+        let mut confirm = false;
+        let mut cancel = false;
+        let hosts = self.docker_service.get_hosts();
+        let estimator = CostEstimator::new(&self.cost_settings, &hosts, &self.business_groups);
+        let group_cost = self.business_groups
+            .iter()
+            .find(|g| g.id == draft.group_id)
+            .map(|g| estimator.group_cost(g).total())
+            .unwrap_or_default();
+        let mut selected = Vec::new();
+        Window::new(format!("缩容读实例 - {}", middleware.name))
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("数量:");
+                    ui.add(egui::DragValue::new(&mut draft.count).clamp_range(1..=reads.max(1)));
+                    ui.label(format!("/ {} 个读实例", reads));
+                });
+                ui.horizontal(|ui| {
+                    ui.label("选择:");
+                    for selection in ScaleInSelection::ALL {
+                        ui.radio_value(&mut draft.selection, selection, selection.label());
+                    }
+                });
+                ui.label(RichText::new("写实例与混合实例不参与选择；未采集到负载的实例按0计").small().weak());
+                ui.separator();
+                
+                selected = draft.selection.pick(&middleware.backend_containers, draft.count, load);
+                egui::Grid::new("scale_in_preview").striped(true).show(ui, |ui| {
+                    ui.strong("名称");
+                    ui.strong("地址");
+                    ui.strong("CPU");
+                    ui.end_row();
+                    for backend in &selected {
+                        ui.label(&backend.name);
+                        ui.monospace(&backend.url);
+                        match self.stats_service.latest(&backend.id) {
+                            Some(sample) => ui.label(format!("{:.1}%", sample.stats.cpu_percent)),
+                            None => ui.weak("-"),
+                        };
+                        ui.end_row();
+                    }
+                });
+                if estimator.is_configured() && !selected.is_empty() {
+                    let delta = estimator.backend_delta(&[], &selected);
+                    let shared = selected.iter().any(|b| estimator.shares_host(b));
+                    Self::render_cost_delta(ui, &self.cost_settings, group_cost, delta, shared);
+                }
+                if selected.len() == reads {
+                    ui.colored_label(Color32::YELLOW, "将删除全部读实例，读请求将由写实例或混合实例处理");
+                }
+                ui.horizontal(|ui| {
+                    confirm = ui.add_enabled(!selected.is_empty(), egui::Button::new("移出并删除")).clicked();
+                    cancel = ui.button("取消").clicked();
+                });
+            });
+        
+        if confirm && let Some(draft) = self.scale_in.take() {
+            let kind = JobKind::ScaleInReads {
+                group_id: draft.group_id,
+                middleware_id: draft.middleware_id,
+                middleware_name: middleware.name.clone(),
+                backend_ids: selected.iter().map(|b| b.id.clone()).collect(),
+            };
+            self.enqueue_job(kind);
+        } else if cancel || !open {
+            self.scale_in = None;
+        }
+    }
+    
+    /// 渲染后端标签页
+    fn render_backend_tab(&mut self, ui: &mut egui::Ui) {
+        ui.vertical(|ui| {
+            ui.heading("后端管理");
+            ui.separator();
+            
+            // 复制选中的ID，避免借用冲突
+            let selected_group_id = self.selected_group_id.clone();
+            let selected_middleware_id = self.selected_middleware_id.clone();
+            let selected_backend_id = self.selected_backend_id.clone();
+            
+            if let (Some(selected_group_id), Some(selected_middleware_id), Some(selected_backend_id)) = 
+                (selected_group_id, selected_middleware_id, selected_backend_id) {
+                
+                // 使用内存中的业务组数据，配置加载失败时仍可查看
+                if let Some(group) = self.business_groups.iter().find(|g| g.id == selected_group_id).cloned() {
+                    // 重新获取中间层数据
+                    if let Some(middleware) = group.middlewares.iter().find(|m| m.id == selected_middleware_id) {
+                        // 重新获取后端数据
+                        if let Some(backend) = middleware.backend_containers.iter().find(|b| b.id == selected_backend_id) {
+                            ui.heading(&backend.name);
+                            
+                            ui.horizontal(|ui| {
+                                ui.label("URL:");
+                                ui.label(&backend.url);
+                            });
+                            
+                            ui.horizontal(|ui| {
+                                ui.label("类型:");
+                                ui.label(&backend.instance_type);
+                            });
+                            
+                            ui.horizontal(|ui| {
+                                ui.label("超时时间 (毫秒):");
+                                ui.label(backend.timeout.to_string());
+                            });
+                            
+                            ui.horizontal(|ui| {
+                                ui.label("重试次数:");
+                                ui.label(backend.retries.to_string());
+                            });
+                            
+                            // 保存ID用于闭包中使用
+                            let group_id = group.id.clone();
+                            let middleware_id = middleware.id.clone();
+                            let backend_id = backend.id.clone();
+                            
+                            if let Some(tunnel) = self.render_ssh_tunnel(ui, &backend.id, &backend.tunnel) {
+                                let mut updated = backend.clone();
+                                updated.tunnel = tunnel;
+                                self.report_error(self.backend_service.update_backend(&GroupId::from(&group_id), Some(&MiddlewareId::from(&middleware_id)), updated));
+                                self.load_business_groups();
+                            }
+                            
+                            CollapsingHeader::new("容器定义").id_source(("container_spec", &backend.id)).show(ui, |ui| {
+                                if let Some((_, replicas)) = self.render_runtime(ui, &backend.id, &middleware.runtime, backend.replicas, false) {
+                                    self.container_action_service.submit(ContainerAction::ScaleBackend {
+                                        group_id: group_id.clone(),
+                                        middleware_id: Some(middleware_id.clone()),
+                                        backend_id: backend_id.clone(),
+                                        replicas,
+                                    });
+                                }
+                                if let Some(host_id) = self.render_docker_host_picker(ui, &backend.id, &backend.docker_host_id) {
+                                    let mut updated = backend.clone();
+                                    updated.docker_host_id = host_id;
+                                    self.report_error(self.backend_service.update_backend(&GroupId::from(&group_id), Some(&MiddlewareId::from(&middleware_id)), updated));
+                                    self.load_business_groups();
+                                }
+                                if let Some(policy) = Self::render_restart_policy(ui, &backend.id, &backend.restart_policy) {
+                                    let mut updated = backend.clone();
+                                    updated.restart_policy = policy;
+                                    self.report_error(self.backend_service.update_backend(&GroupId::from(&group_id), Some(&MiddlewareId::from(&middleware_id)), updated));
+                                    self.load_business_groups();
+                                }
+                                if let Some(probe) = self.render_health_probe(ui, &backend.id, &backend.health_probe) {
+                                    let mut updated = backend.clone();
+                                    updated.health_probe = probe;
+                                    self.report_error(self.backend_service.update_backend(&GroupId::from(&group_id), Some(&MiddlewareId::from(&middleware_id)), updated));
+                                    self.load_business_groups();
+                                }
+                                self.render_status_timeline(ui, &backend.id, &backend.status);
+                                if let Some(params) = self.render_container_spec(ui, &backend.id, &backend.docker_run_params) {
+                                    let mut updated = backend.clone();
+                                    updated.docker_run_params = params;
+                                    self.report_error(self.backend_service.update_backend(&GroupId::from(&group_id), Some(&MiddlewareId::from(&middleware_id)), updated));
+                                    self.load_business_groups();
+                                }
+                                if let Some(target) = self.render_image_version(ui, &backend.id, &backend.docker_run_params) {
+                                    let result = self.upgrade_backend_image(&group_id, Some(&middleware_id), backend, &target);
+                                    self.report_error(result);
+                                    self.load_business_groups();
+                                }
+                                if let Some(ports) = self.render_port_mappings(ui, &backend.id, &backend.docker_host_id, &backend.docker_run_params, &backend.ports) {
+                                    let mut updated = backend.clone();
+                                    updated.ports = ports;
+                                    self.report_error(self.backend_service.update_backend(&GroupId::from(&group_id), Some(&MiddlewareId::from(&middleware_id)), updated));
+                                    self.load_business_groups();
+                                }
+                                if let Some(volumes) = self.render_volume_mounts(ui, &backend.id, &backend.volumes) {
+                                    let mut updated = backend.clone();
+                                    updated.volumes = volumes;
+                                    self.report_error(self.backend_service.update_backend(&GroupId::from(&group_id), Some(&MiddlewareId::from(&middleware_id)), updated));
+                                    self.load_business_groups();
+                                }
+                                if let Some(limits) = self.render_resource_limits(ui, &backend.id, &backend.limits) {
+                                    let mut updated = backend.clone();
+                                    updated.limits = limits;
+                                    self.report_error(self.backend_service.update_backend(&GroupId::from(&group_id), Some(&MiddlewareId::from(&middleware_id)), updated));
+                                    self.load_business_groups();
+                                }
+                                if let Some(depends_on) = Self::render_start_dependencies(ui, &group, &backend.id, &backend.depends_on) {
+                                    let mut updated = backend.clone();
+                                    updated.depends_on = depends_on;
+                                    self.report_error(self.backend_service.update_backend(&GroupId::from(&group_id), Some(&MiddlewareId::from(&middleware_id)), updated));
+                                    self.load_business_groups();
+                                }
+                            });
+                            
+                            ui.horizontal(|ui| {
+                                ui.label("状态:");
+                                ui.label(Self::get_container_status_text(&backend.status));
+                                
+                                let idle = !self.container_action_service.is_busy(&backend_id);
+                                let target = (group_id.clone(), Some(middleware_id.clone()), backend_id.clone());
+                                if ui.add_enabled(idle, egui::Button::new("启动")).clicked() {
+                                    let (group_id, middleware_id, backend_id) = target.clone();
+                                    self.container_action_service.submit(ContainerAction::StartBackend { group_id, middleware_id, backend_id });
+                                }
+                                if ui.add_enabled(idle, egui::Button::new("停止")).clicked() {
+                                    let (group_id, middleware_id, backend_id) = target.clone();
+                                    self.container_action_service.submit(ContainerAction::StopBackend { group_id, middleware_id, backend_id });
+                                }
+                                if ui.add_enabled(idle, egui::Button::new("重启")).clicked() {
+                                    let (group_id, middleware_id, backend_id) = target;
+                                    self.container_action_service.submit(ContainerAction::RestartBackend { group_id, middleware_id, backend_id });
+                                }
+                                if !idle {
+                                    ui.spinner();
+                                }
+                            });
+                        }
+                    }
+                }
+            } else {
+                ui.label("请选择一个业务组、中间层和后端");
+            }
+        });
+    }
+    
+    /// 渲染配置标签页
+    fn render_config_tab(&mut self, ui: &mut egui::Ui) {
+        ui.vertical(|ui| {
+            ui.heading("配置管理");
+            ui.separator();
+            
+            ui.horizontal(|ui| {
+                if ui.button("保存配置").clicked() {
+                    self.save_app_config();
+                }
+                if ui.button("导入配置").clicked() {
+                    // TODO: 实现导入配置功能
+                }
+                if ui.button("导出配置").clicked() {
+                    // TODO: 实现导出配置功能
+                }
+            });
+            
+            ui.separator();
+            
+            ui.heading("应用配置");
+            ScrollArea::vertical().show(ui, |ui| {
+                ui.label("这里显示应用配置详情");
+                
+                ui.separator();
+                CollapsingHeader::new("界面偏好").show(ui, |ui| {
+                    self.render_preferences(ui);
+                });
+                
+                ui.separator();
+                self.render_topology_export(ui);
+                
+                ui.separator();
+                self.render_agent_settings(ui);
+                
+                ui.separator();
+                self.render_command_allowlist(ui);
+                
+                ui.separator();
+                self.render_ui_roles(ui);
+                
+                ui.separator();
+                self.render_sessions(ui);
+                
+                ui.separator();
+                self.render_local_users(ui);
+                
+                ui.separator();
+                self.render_webhooks(ui);
+                
+                ui.separator();
+                self.render_systemd_units(ui);
+                
+                ui.separator();
+                self.render_terraform_export(ui);
+                
+                ui.separator();
+                self.render_kubernetes_import(ui);
+                
+                ui.separator();
+                self.render_compose_import(ui);
+                
+                ui.separator();
+                self.render_snapshot_restore(ui);
+                
+                ui.separator();
+                self.render_auto_save(ui);
+                
+                ui.separator();
+                self.render_connection_pool(ui);
+                
+                ui.separator();
+                self.render_health_concurrency(ui);
+                
+                ui.separator();
+                self.render_repaint_interval(ui);
+                
+                ui.separator();
+                self.render_cost_settings(ui);
+                
+                ui.separator();
+                self.render_otlp_export(ui);
+                
+                // 原始配置文件包含加密设置与界面角色
+                if self.ui_profile.can_edit_encryption() {
+                    ui.separator();
+                    self.render_raw_config(ui);
+                }
+            });
+        });
+    }
+    
+    /// 渲染配置文件的原始JSON编辑器，保存前按配置结构校验并检查配置文件是否已被他人修改
+    fn render_raw_config(&mut self, ui: &mut egui::Ui) {
+        ui.heading("原始配置 (JSON)");
+        ui.label("直接编辑整个配置文件，保存后重新加载全部数据；编辑期间其他实例的修改不会合并。");
+        let Some((revision, editor)) = &mut self.raw_config else {
+            if ui.button("打开编辑器").clicked() {
+                self.open_raw_config();
+            }
+            return;
+        };
+        editor.show(ui, "raw_config");
+        let revision = *revision;
+        let valid = editor.error().is_none();
+        let modified = editor.is_modified();
+        let mut save = false;
+        let mut reload = false;
+        let mut close = false;
+        ui.horizontal(|ui| {
+            save = ui.add_enabled(valid && modified, egui::Button::new("保存")).clicked();
+            reload = ui.button("重新加载").clicked();
+            close = ui.button("关闭").clicked();
+            ui.weak(format!("修订号 {}", revision));
+        });
+        if save {
+            match self.save_raw_config() {
+                Ok(()) => self.open_raw_config(),
+                Err(e) => self.push_log(LogEntry::new("配置", &format!("保存原始配置失败: {:#}", e))),
+            }
+        } else if reload {
+            self.open_raw_config();
+        } else if close {
+            self.raw_config = None;
+        }
+    }
+    
+    /// 先写入未保存的修改，再按配置文件当前的内容打开原始配置编辑器
+    fn open_raw_config(&mut self) {
+        if let Err(e) = self.state_store.flush() {
+            self.push_log(LogEntry::new("配置", &format!("写入未保存的修改失败，无法打开原始配置: {:#}", e)));
+            return;
+        }
+        match self.config_manager.load_config() {
+            Ok(config) => {
+                let value = serde_json::to_value(&config).unwrap_or_default();
+                let schema = SchemaNode::from_samples(&[serde_json::to_value(Config::schema_sample()).unwrap_or_default(), value.clone()]);
+                let text = serde_json::to_string_pretty(&value).unwrap_or_default();
+                let editor = JsonEditor::new(text, schema, |text| serde_json::from_str::<Config>(text).map(|_| ()));
+                self.raw_config = Some((config.revision, editor));
+            }
+            Err(e) => self.push_log(LogEntry::new("配置", &format!("读取配置失败: {:#}", e))),
+        }
+    }
+    
+    /// 保存原始配置编辑器中的内容，配置文件在打开编辑器后被修改时拒绝保存
+    ///
+    /// 保存后从配置文件重新加载状态，打开编辑器后界面中尚未写入的修改会因此丢失，此时拒绝保存
+    fn save_raw_config(&mut self) -> anyhow::Result<()> {
+        let Some((revision, editor)) = &self.raw_config else {
+            return Ok(());
+        };
+        if self.state_store.is_dirty() {
+            anyhow::bail!("打开编辑器后有尚未写入的修改，保存原始配置会丢弃这些修改；请先保存配置并重新加载编辑器");
+        }
+        let edited: Config = serde_json::from_str(editor.text()).map_err(|e| anyhow::anyhow!("配置格式错误: {}", e))?;
+        let revision = *revision;
+        self.config_manager.update(|config| {
+            if config.revision != revision {
+                anyhow::bail!("配置文件已被修改（修订号 {} → {}），请重新加载后再编辑", revision, config.revision);
+            }
+            *config = Config { revision, ..edited };
+            Ok(())
+        })?;
+        self.reload_from_config();
+        self.push_log(LogEntry::new("配置", "已保存编辑的原始配置"));
+        self.record_audit("编辑原始配置", None, None);
+        Ok(())
+    }
+    
+    /// 渲染连接池设置与各主机的连接统计
+    fn render_connection_pool(&mut self, ui: &mut egui::Ui) {
+        ui.heading("连接池");
+        ui.label("对所有中间层与后端的接口客户端生效，同一目标的请求共用连接池，保存后按新设置重建客户端。");
+        let pool = &mut self.connection_pool;
+        egui::Grid::new("connection_pool").num_columns(2).show(ui, |ui| {
+            ui.label("每主机空闲连接上限:");
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut pool.max_idle_per_host).clamp_range(0..=1024));
+                ui.weak("0为不限");
+            });
+            ui.end_row();
+            ui.label("空闲回收 (秒):");
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut pool.idle_timeout_secs).clamp_range(0..=3600));
+                ui.weak("0为不回收");
+            });
+            ui.end_row();
+            ui.label("TCP保活 (秒):");
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut pool.tcp_keepalive_secs).clamp_range(0..=3600));
+                ui.weak("0为关闭");
+            });
+            ui.end_row();
+            ui.label("");
+            ui.checkbox(&mut pool.tcp_nodelay, "TCP_NODELAY");
+            ui.end_row();
+            ui.label("HTTP版本:");
+            egui::ComboBox::from_id_source("http_version")
+                .selected_text(pool.http_version.label())
+                .show_ui(ui, |ui| {
+                    for version in HttpVersionPreference::ALL {
+                        ui.selectable_value(&mut pool.http_version, version, version.label());
+                    }
+                });
+            ui.end_row();
+            ui.label("");
+            ui.add_enabled(
+                pool.http_version != HttpVersionPreference::Http1Only,
+                egui::Checkbox::new(&mut pool.http2_adaptive_window, "HTTP/2自适应流控窗口"),
+            );
+            ui.end_row();
+        });
+        ui.horizontal(|ui| {
+            if ui.button("保存").clicked() {
+                let result = self.network_service.set_pool_settings(self.connection_pool.clone());
+                if result.is_ok() {
+                    self.record_audit("修改连接池设置", None, None);
+                }
+                self.report_error(result);
+            }
+            if ui.button("恢复默认").clicked() {
+                self.connection_pool = ConnectionPoolSettings::default();
+            }
+        });
+        
+        CollapsingHeader::new("连接诊断").show(ui, |ui| {
+            ui.ctx().request_repaint_after(Duration::from_secs(1));
+            ui.label("连接器只在池中没有可用连接时被调用，复用率越高说明连接池越有效；每个客户端各有独立的连接池。");
+            let stats = api::pool_stats();
+            if stats.is_empty() {
+                ui.label("尚无请求");
+            } else {
+                egui::Grid::new("pool_stats").striped(true).show(ui, |ui| {
+                    ui.strong("主机");
+                    ui.strong("客户端");
+                    ui.strong("请求");
+                    ui.strong("新建连接");
+                    ui.strong("建立中");
+                    ui.strong("连接失败");
+                    ui.strong("复用率");
+                    ui.strong("平均建连");
+                    ui.strong("最近请求");
+                    ui.end_row();
+                    for (host, s) in &stats {
+                        ui.label(host);
+                        ui.label(s.clients.to_string());
+                        ui.label(s.requests.to_string());
+                        ui.label(s.connections.to_string());
+                        ui.label(s.connecting.to_string());
+                        ui.label(s.connect_failures.to_string());
+                        ui.label(s.reuse_rate().map(|r| format!("{:.1}%", r * 100.0)).unwrap_or_else(|| "-".to_string()));
+                        ui.label(s.avg_connect_ms().map(|ms| format!("{:.1} ms", ms)).unwrap_or_else(|| "-".to_string()));
+                        ui.label(s.last_request.map(|t| t.format("%H:%M:%S").to_string()).unwrap_or_default());
+                        ui.end_row();
+                    }
+                });
+            }
+            if ui.button("清空统计").clicked() {
+                api::reset_pool_stats();
+            }
+        });
+    }
+    
+    /// 渲染管理器自身链路的OTLP导出设置与导出统计
+    fn render_otlp_export(&mut self, ui: &mut egui::Ui) {
+        ui.heading("链路导出 (OTLP)");
+        ui.label("将接口调用、健康检查与后台任务的span以OTLP/HTTP JSON格式发送到收集器；界面操作的追踪ID用作链路的trace ID。");
+        let settings = &mut self.otlp_settings;
+        ui.checkbox(&mut settings.enabled, "启用导出");
+        egui::Grid::new("otlp_settings").num_columns(2).show(ui, |ui| {
+            ui.label("收集器地址:");
+            ui.add(egui::TextEdit::singleline(&mut settings.endpoint).hint_text("http://localhost:4318"));
+            ui.end_row();
+            ui.label("服务名称:");
+            ui.text_edit_singleline(&mut settings.service_name);
+            ui.end_row();
+        });
+        ui.label("请求头:");
+        let mut remove_header = None;
+        egui::Grid::new("otlp_headers").show(ui, |ui| {
+            for (index, (name, value)) in settings.headers.iter_mut().enumerate() {
+                ui.text_edit_singleline(name);
+                ui.add(egui::TextEdit::singleline(value).password(true));
+                if ui.small_button("移除").clicked() {
+                    remove_header = Some(index);
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(index) = remove_header {
+            settings.headers.remove(index);
+        }
+        ui.horizontal(|ui| {
+            if ui.button("添加请求头").clicked() {
+                self.otlp_settings.headers.push((String::new(), String::new()));
+            }
+            if ui.button("保存").clicked() {
+                let result = self.telemetry_service.set_settings(self.otlp_settings.clone());
+                if result.is_ok() {
+                    let action = if self.otlp_settings.enabled { "启用链路导出" } else { "关闭链路导出" };
+                    self.record_audit(action, None, None);
+                }
+                self.report_error(result);
+            }
+        });
+        
+        let stats = telemetry::stats();
+        ui.horizontal(|ui| {
+            ui.label(format!("已导出 {} 个span，失败 {} 批，丢弃 {} 个", stats.exported, stats.failed, stats.dropped));
+            if let Some(t) = stats.last_export {
+                ui.weak(format!("最近导出 {}", t.format("%H:%M:%S")));
+            }
+        });
+        if let Some(error) = &stats.last_error {
+            ui.colored_label(Color32::RED, format!("最近一次导出失败: {}", error));
+        }
+    }
+    
+    /// 渲染拓扑导出面板
+    fn render_topology_export(&mut self, ui: &mut egui::Ui) {
+        ui.heading("拓扑导出");
+        
+        ui.horizontal(|ui| {
+            ui.label("格式:");
+            for format in [TopologyFormat::Dot, TopologyFormat::Mermaid] {
+                ui.radio_value(&mut self.topology_format, format, format.label());
+            }
+        });
+        
+        let content = self.topology_format.render(&self.business_groups);
+        
+        ui.horizontal(|ui| {
+            if ui.button("复制到剪贴板").clicked() {
+                ui.output_mut(|o| o.copied_text = content.clone());
+            }
+            if ui.button("保存到文件").clicked() {
+                self.export_topology(&content);
+            }
+        });
+        
+        ui.checkbox(
+            &mut self.topology_auto_export,
+            format!("模型变更时自动导出到 topology.{}", self.topology_format.extension()),
+        );
+        
+        ScrollArea::vertical()
+            .id_source("topology_preview")
+            .max_height(300.0)
+            .show(ui, |ui| {
+                ui.add(egui::TextEdit::multiline(&mut content.as_str())
+                    .code_editor()
+                    .desired_width(f32::INFINITY));
+            });
+    }
+    
+    /// 将拓扑保存到当前目录
+    fn export_topology(&mut self, content: &str) {
+        let timestamp = Utc::now().format("%Y%m%d_%H%M%S");
+        let path = format!("topology_{}.{}", timestamp, self.topology_format.extension());
+        self.write_topology(&path, content);
+    }
+    
+    /// 将业务组导出为docker-compose文件
+    fn export_compose(&mut self, group: &BusinessGroup) {
+        let timestamp = Utc::now().format("%Y%m%d_%H%M%S");
+        let path = format!("docker-compose_{}_{}.yml", compose::normalize_name(&group.name), timestamp);
+        let result = self
+            .business_group_service
+            .export_compose(&GroupId::from(&group.id))
+            .and_then(|content| std::fs::write(&path, content).map_err(anyhow::Error::from));
+        let entry = match result {
+            Ok(()) => LogEntry::new("配置", &format!("业务组 {} 已导出到 {}", group.name, path)),
+            Err(e) => LogEntry::new("配置", &format!("导出业务组 {} 失败: {:#}", group.name, e)),
+        };
+        self.push_log(entry);
+    }
+    
+    /// 写入拓扑文件
+    fn write_topology(&mut self, path: &str, content: &str) {
+        let entry = match std::fs::write(path, content) {
+            Ok(()) => LogEntry::new("拓扑", &format!("拓扑已导出到 {}", path)),
+            Err(e) => LogEntry::new("拓扑", &format!("导出拓扑失败 {}: {}", path, e)),
+        };
+        self.push_log(entry);
+    }
+    
+    /// 分发模型变更事件给界面、告警、审计、状态历史与拓扑导出
+    fn process_model_events(&mut self) {
+        let events: Vec<ModelEvent> = self.model_events.try_iter().collect();
+        for event in &events {
+            // 已删除中间层的采样不再展示，也不再参与分组汇总
+            if let ModelEvent::Deleted { kind: EntityKind::Middleware, id, .. } = event {
+                self.metrics_service.store.remove(id);
+            }
+        }
+        if !events.is_empty() {
+            self.load_business_groups();
+        }
+        
+        match self.alert_service.process_events() {
+            Ok(0) => {}
+            Ok(_) => self.load_alerts(),
+            Err(e) => tracing::error!("处理告警事件失败: {:#}", e),
+        }
+        
+        match self.audit_service.process_events() {
+            Ok(0) => {}
+            Ok(_) => self.audit_entries = self.audit_service.get_entries().unwrap_or_default(),
+            Err(e) => tracing::error!("写入审计日志失败: {:#}", e),
+        }
+        
+        match self.status_history_service.process_events() {
+            Ok(false) => {}
+            Ok(true) => self.status_history = self.status_history_service.get_history().unwrap_or_default(),
+            Err(e) => tracing::error!("写入状态历史失败: {:#}", e),
+        }
+        
+        if self.topology_events.try_iter().count() > 0 && self.topology_auto_export {
+            let path = format!("topology.{}", self.topology_format.extension());
+            let content = self.topology_format.render(&self.business_groups);
+            self.write_topology(&path, &content);
+        }
+    }
+    
+    /// 保存仪表盘布局到用户偏好
+    fn save_dashboard_layout(&mut self) {
+        self.preferences.dashboard_widgets = self.dashboard_widgets.clone();
+        self.preferences.dashboard_columns = self.dashboard_columns;
+        if let Err(e) = self.preferences_manager.save(&self.preferences) {
+            self.push_log(LogEntry::new("仪表盘", &format!("保存布局失败: {:#}", e)));
+        }
+    }
+    
+    /// 应用界面主题与字号，各类文字按正文字号的比例缩放
+    fn apply_preferences(ctx: &egui::Context, preferences: &UserPreferences) {
+        ctx.set_visuals(match preferences.theme {
+            UiTheme::Dark => egui::Visuals::dark(),
+            UiTheme::Light => egui::Visuals::light(),
+        });
+        let scale = preferences.font_size / config::DEFAULT_FONT_SIZE;
+        let defaults = egui::Style::default().text_styles;
+        ctx.style_mut(|style| {
+            for (text_style, font) in style.text_styles.iter_mut() {
+                if let Some(default) = defaults.get(text_style) {
+                    font.size = default.size * scale;
+                }
+            }
+        });
+    }
+    
+    /// 显示或隐藏诊断浮层，只在显示时记录耗时
+    fn set_show_diagnostics(&mut self, show: bool) {
+        diagnostics::set_enabled(show);
+        self.preferences.show_diagnostics = show;
+        if let Err(e) = self.preferences_manager.save(&self.preferences) {
+            self.push_log(LogEntry::new("配置", &format!("保存偏好失败: {:#}", e)));
+        }
+    }
+    
+    /// 渲染诊断浮层：最近时间窗口内各耗时指标的平均与最大值，以及待完成的后台任务
+    fn render_diagnostics_overlay(&self, ctx: &egui::Context) {
+        if !self.preferences.show_diagnostics {
+            return;
+        }
+        let millis = |d: Duration| format!("{:.2}", d.as_secs_f64() * 1000.0);
+        let summaries = diagnostics::summaries();
+        egui::Area::new(egui::Id::new("diagnostics_overlay"))
+            .anchor(egui::Align2::RIGHT_BOTTOM, [-8.0, -8.0])
+            .order(egui::Order::Foreground)
+            .interactable(false)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.strong(format!("诊断（最近 {} 秒，毫秒）", diagnostics::WINDOW.as_secs()));
+                    egui::Grid::new("diagnostics_metrics").num_columns(5).show(ui, |ui| {
+                        ui.weak("");
+                        ui.weak("最近");
+                        ui.weak("平均");
+                        ui.weak("最大");
+                        ui.weak("次数");
+                        ui.end_row();
+                        for (metric, summary) in Metric::ALL.iter().zip(summaries) {
+                            let MetricSummary { count, average, max, last } = summary;
+                            ui.label(metric.label());
+                            ui.monospace(last.map_or("-".to_string(), millis));
+                            ui.monospace(if count > 0 { millis(average) } else { "-".to_string() });
+                            ui.monospace(if count > 0 { millis(max) } else { "-".to_string() });
+                            ui.monospace(count.to_string());
+                            ui.end_row();
+                        }
+                    });
+                    ui.label(format!("待完成的后台请求: {}", self.api_service.pending()));
+                    if self.config_manager.pending_save().is_some() {
+                        ui.colored_label(Color32::YELLOW, "配置写入失败，排队重试中");
+                    }
+                    let repaints = repaint::stats();
+                    ui.weak(format!("后台重绘请求: {}，已合并 {}", repaints.requested.iter().sum::<u64>(), repaints.coalesced));
+                });
+            });
+        ctx.request_repaint_after(Duration::from_millis(500));
+    }
+    
+    /// 渲染界面偏好，偏好只保存在当前用户的偏好文件中，不写入共享配置
+    fn render_preferences(&mut self, ui: &mut egui::Ui) {
+        let mut preferences = self.preferences.clone();
+        egui::Grid::new("user_preferences").num_columns(2).show(ui, |ui| {
+            ui.label("主题:");
+            ui.horizontal(|ui| {
+                for theme in UiTheme::ALL {
+                    ui.radio_value(&mut preferences.theme, theme, theme.label());
+                }
+            });
+            ui.end_row();
+            ui.label("字号:");
+            ui.add(egui::Slider::new(&mut preferences.font_size, 10.0..=20.0).step_by(0.5));
+            ui.end_row();
+        });
+        ui.weak(format!("偏好文件: {}", self.preferences_manager.path()));
+        if preferences.theme != self.preferences.theme || preferences.font_size != self.preferences.font_size {
+            Self::apply_preferences(ui.ctx(), &preferences);
+            if let Err(e) = self.preferences_manager.save(&preferences) {
+                self.push_log(LogEntry::new("配置", &format!("保存偏好失败: {:#}", e)));
+            }
+            self.preferences = preferences;
+        }
+    }
+    
+    /// 渲染仪表盘标签页
+    fn render_dashboard_tab(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.heading("仪表盘");
+            ui.toggle_value(&mut self.dashboard_editing, "编辑布局");
+        });
+        ui.separator();
+        
+        let mut widgets = self.dashboard_widgets.clone();
+        let mut columns = self.dashboard_columns;
+        let mut changed = false;
+        
+        if self.dashboard_editing {
+            ui.horizontal(|ui| {
+                ui.label("列数:");
+                changed |= ui.add(egui::DragValue::new(&mut columns).clamp_range(1..=4)).changed();
+                
+                let catalog = DashboardWidgetKind::catalog();
+                egui::ComboBox::from_id_source("dashboard_widget_catalog")
+                    .selected_text(catalog[self.new_widget_kind].label())
+                    .show_ui(ui, |ui| {
+                        for (index, kind) in catalog.iter().enumerate() {
+                            ui.selectable_value(&mut self.new_widget_kind, index, kind.label());
+                        }
+                    });
+                if ui.button("添加组件").clicked() {
+                    let row = widgets.iter().map(|w| w.row + 1).max().unwrap_or(0);
+                    widgets.push(DashboardWidget::new(catalog[self.new_widget_kind].clone(), row, 0, 1));
+                    changed = true;
+                }
+            });
+            ui.separator();
+        }
+        
+        widgets.sort_by_key(|w| (w.row, w.column));
+        let mut rows: Vec<u32> = widgets.iter().map(|w| w.row).collect();
+        rows.dedup();
+        
+        let spacing = ui.spacing().item_spacing.x;
+        let cell_width = ((ui.available_width() + spacing) / columns as f32 - spacing).max(120.0);
+        let mut removed = None;
+        let mut action = None;
+        
+        ScrollArea::vertical().show(ui, |ui| {
+            for row in rows {
+                ui.horizontal_top(|ui| {
+                    let mut next_column = 0;
+                    for widget in widgets.iter_mut().filter(|w| w.row == row) {
+                        let column = widget.column.min(columns - 1).max(next_column);
+                        if column >= columns {
+                            break;
+                        }
+                        if column > next_column {
+                            ui.add_space((cell_width + spacing) * (column - next_column) as f32);
+                        }
+                        let span = widget.span.clamp(1, columns - column);
+                        next_column = column + span;
+                        
+                        let width = cell_width * span as f32 + spacing * (span - 1) as f32;
+                        ui.allocate_ui(egui::vec2(width, 0.0), |ui| {
+                            egui::Frame::group(ui.style()).show(ui, |ui| {
+                                ui.set_width(width - 14.0);
+                                ui.strong(widget.kind.label());
+                                if self.dashboard_editing {
+                                    changed |= Self::render_widget_editor(ui, widget, columns, &self.business_groups);
+                                    if ui.button("移除").clicked() {
+                                        removed = Some(widget.id.clone());
+                                    }
+                                } else if let Some(a) = self.render_widget(ui, widget) {
+                                    action = Some(a);
+                                }
+                            });
+                        });
+                    }
+                });
+                ui.add_space(6.0);
+            }
+        });
+        
+        if let Some(id) = removed {
+            widgets.retain(|w| w.id != id);
+            changed = true;
+        }
+        if changed {
+            self.dashboard_widgets = widgets;
+            self.dashboard_columns = columns;
+            self.save_dashboard_layout();
+        }
+        if let Some(action) = action {
+            self.apply_dashboard_action(action);
+        }
+    }
+    
+    /// 渲染组件的位置编辑控件，返回是否有修改
+    fn render_widget_editor(ui: &mut egui::Ui, widget: &mut DashboardWidget, columns: u32, groups: &[BusinessGroup]) -> bool {
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            if ui.small_button("↑").clicked() && widget.row > 0 {
+                widget.row -= 1;
+                changed = true;
+            }
+            if ui.small_button("↓").clicked() {
+                widget.row += 1;
+                changed = true;
+            }
+            if ui.small_button("←").clicked() && widget.column > 0 {
+                widget.column -= 1;
+                changed = true;
+            }
+            if ui.small_button("→").clicked() && widget.column + 1 < columns {
+                widget.column += 1;
+                changed = true;
+            }
+            ui.label("宽度:");
+            changed |= ui.add(egui::DragValue::new(&mut widget.span).clamp_range(1..=columns)).changed();
+        });
+        
+        if let DashboardWidgetKind::LatencyChart { middleware_id } = &mut widget.kind {
+            let selected = groups
+                .iter()
+                .flat_map(|g| g.middlewares.iter())
+                .find(|m| Some(&m.id) == middleware_id.as_ref())
+                .map(|m| m.name.clone())
+                .unwrap_or_else(|| "选择中间层".to_string());
+            egui::ComboBox::from_id_source(format!("latency_middleware_{}", widget.id))
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    for middleware in groups.iter().flat_map(|g| g.middlewares.iter()) {
+                        changed |= ui.selectable_value(middleware_id, Some(middleware.id.clone()), &middleware.name).changed();
+                    }
+                });
+        }
+        changed
+    }
+    
+    /// 渲染单个仪表盘组件，返回用户触发的操作
+    fn render_widget(&self, ui: &mut egui::Ui, widget: &DashboardWidget) -> Option<DashboardAction> {
+        let mut action = None;
+        match &widget.kind {
+            DashboardWidgetKind::UptimeCard => {
+                let middlewares: Vec<&MiddlewareContainer> = self.business_groups.iter().flat_map(|g| g.middlewares.iter()).collect();
+                let backends: Vec<&BackendContainer> = self.business_groups
+                    .iter()
+                    .flat_map(|g| g.backend_containers.iter().chain(g.middlewares.iter().flat_map(|m| m.backend_containers.iter())))
+                    .collect();
+                let running_groups = self.business_groups.iter().filter(|g| g.status == GroupStatus::Running).count();
+                let running_middlewares = middlewares.iter().filter(|m| m.status == ContainerStatus::Running).count();
+                let running_backends = backends.iter().filter(|b| b.status == ContainerStatus::Running).count();
+                let healthy = middlewares.iter().filter(|m| m.health == HealthStatus::Healthy).count()
+                    + backends.iter().filter(|b| b.health == HealthStatus::Healthy).count();
+                let total = middlewares.len() + backends.len();
+                
+                egui::Grid::new(format!("uptime_{}", widget.id)).show(ui, |ui| {
+                    ui.label("业务组运行:");
+                    ui.label(format!("{} / {}", running_groups, self.business_groups.len()));
+                    ui.end_row();
+                    ui.label("中间层运行:");
+                    ui.label(format!("{} / {}", running_middlewares, middlewares.len()));
+                    ui.end_row();
+                    ui.label("后端运行:");
+                    ui.label(format!("{} / {}", running_backends, backends.len()));
+                    ui.end_row();
+                    ui.label("健康率:");
+                    if total > 0 {
+                        ui.label(format!("{:.1}%", healthy as f64 / total as f64 * 100.0));
+                    } else {
+                        ui.label("-");
+                    }
+                    ui.end_row();
+                });
+            }
+            DashboardWidgetKind::AlertList => {
+                let alerts: Vec<&Alert> = self.alerts.iter().rev().filter(|a| !a.acknowledged).take(5).collect();
+                if alerts.is_empty() {
+                    ui.label("暂无未确认告警");
+                }
+                for alert in alerts {
+                    ui.horizontal(|ui| {
+                        ui.label(RichText::new(Self::get_alert_severity_text(&alert.severity)).color(Self::get_alert_severity_color(&alert.severity)));
+                        ui.label(format!("[{}] {}", alert.source, alert.message));
+                        if ui.small_button("确认").clicked() {
+                            action = Some(DashboardAction::AcknowledgeAlert(alert.id.clone()));
+                        }
+                    });
+                }
+            }
+            DashboardWidgetKind::LatencyChart { middleware_id } => {
+                let Some(middleware_id) = middleware_id else {
+                    ui.label("请在编辑布局中选择中间层");
+                    return None;
+                };
+                let series = self.metrics_service.store.latency_series(middleware_id);
+                match series.last() {
+                    Some((_, latency)) => ui.label(format!("最新延迟: {:.0} ms", latency)),
+                    None => ui.label("暂无指标数据，请先采集指标"),
+                };
+                let now = Utc::now();
+                let points: PlotPoints = series
+                    .iter()
+                    .map(|(timestamp, latency)| [(*timestamp - now).num_milliseconds() as f64 / 1000.0, *latency])
+                    .collect();
+                Plot::new(format!("latency_{}", widget.id))
+                    .height(140.0)
+                    .show(ui, |plot_ui| plot_ui.line(Line::new(points).name("延迟 (ms)")));
+            }
+            DashboardWidgetKind::QuickActions => {
+                ui.horizontal_wrapped(|ui| {
+                    if ui.button("新建业务组").clicked() {
+                        action = Some(DashboardAction::NewGroup);
+                    }
+                    if ui.add_enabled(!self.metrics_service.is_scraping(), egui::Button::new("采集指标")).clicked() {
+                        action = Some(DashboardAction::ScrapeMetrics);
+                    }
+                    if ui.button("启动全部业务组").clicked() {
+                        action = Some(DashboardAction::StartAllGroups);
+                    }
+                    if ui.button("停止全部业务组").clicked() {
+                        action = Some(DashboardAction::StopAllGroups);
+                    }
+                });
+            }
+            DashboardWidgetKind::CostSummary => {
+                let hosts = self.docker_service.get_hosts();
+                let estimator = CostEstimator::new(&self.cost_settings, &hosts, &self.business_groups);
+                if !estimator.is_configured() {
+                    ui.label("未设置费用，请在配置页设置实例或主机的月费用");
+                    return None;
+                }
+                let mut total = 0.0;
+                egui::Grid::new(format!("cost_{}", widget.id)).show(ui, |ui| {
+                    for group in &self.business_groups {
+                        let cost = estimator.group_cost(group);
+                        total += cost.total();
+                        ui.label(&group.name);
+                        ui.label(self.cost_settings.format(cost.total()));
+                        ui.end_row();
+                    }
+                    ui.strong("合计 / 月:");
+                    ui.strong(self.cost_settings.format(total));
+                    ui.end_row();
+                });
+            }
+        }
+        action
+    }
+    
+    /// 执行仪表盘组件触发的操作
+    fn apply_dashboard_action(&mut self, action: DashboardAction) {
+        match action {
+            DashboardAction::NewGroup => self.show_new_group_dialog = true,
+            DashboardAction::ScrapeMetrics => self.scrape_metrics(),
+            DashboardAction::AcknowledgeAlert(alert_id) => {
+                if let Err(e) = self.alert_service.acknowledge_alert(&alert_id) {
+                    self.push_log(LogEntry::new("告警", &format!("确认告警失败: {}", e)));
+                }
+                self.load_alerts();
+            }
+            DashboardAction::StartAllGroups | DashboardAction::StopAllGroups => {
+                let operation = if matches!(action, DashboardAction::StartAllGroups) { PlannedOperation::Start } else { PlannedOperation::Stop };
+                let groups = self.business_groups.clone();
+                self.open_plan(&groups.iter().collect::<Vec<_>>(), operation);
+            }
+        }
+    }
+    
+    /// 渲染监控标签页
+    fn render_monitor_tab(&mut self, ui: &mut egui::Ui) {
+        ui.vertical(|ui| {
+            ui.heading("监控中心");
+            ui.separator();
+            
+            ui.horizontal(|ui| {
+                ui.heading("业务组状态");
+                let queue = self.health_service.queue_stats();
+                if queue.active > 0 || queue.queued > 0 {
+                    ui.weak(format!("健康探测: 进行中 {}/{}，排队 {}", queue.active, queue.concurrency, queue.queued));
+                }
+                let (stats, subscribed) = self.poll_coordinator.stats();
+                if subscribed > 0 {
+                    ui.weak(format!("状态轮询: 请求 {}，合并 {}，缓存 {}", stats.requests, stats.coalesced, stats.cache_hits))
+                        .on_hover_text("多个面板轮询同一中间层时，进行中的相同请求只发出一次，最近的响应在有效期内直接复用");
+                }
+                if ui.button("超时与重试调优").on_hover_text("按健康探测观测到的延迟分布建议后端的超时与重试次数").clicked() {
+                    self.tuning_dialog = Some(TuningDialog::default());
+                }
+            });
+            ScrollArea::vertical().show(ui, |ui| {
+                // 折叠的节点不渲染内容，展开后按页渲染，大量实体时每帧只渲染一页
+                let groups: Vec<&BusinessGroup> = self.business_groups
+                    .iter()
+                    .filter(|g| Self::group_visible(&self.status_filters, g))
+                    .collect();
+                for group in Self::monitor_page(ui, "monitor_groups", &groups) {
+                    ui.collapsing(&group.name, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label("状态:");
+                            ui.label(Self::get_status_text(&group.rollup_status()));
+                        });
+                        
+                        let middlewares: Vec<&MiddlewareContainer> = group.middlewares
+                            .iter()
+                            .filter(|m| Self::middleware_visible(&self.status_filters, m))
+                            .collect();
+                        for middleware in Self::monitor_page(ui, &group.id, &middlewares) {
+                            ui.collapsing(&middleware.name, |ui| {
+                                ui.horizontal(|ui| {
+                                    ui.label("状态:");
+                                    ui.label(Self::get_container_status_text(&middleware.status));
+                                    ui.label("健康状态:");
+                                    ui.label(Self::get_health_status_text(&middleware.health));
+                                    self.render_container_stats(ui, &middleware.id, &middleware.limits);
+                                    self.render_service_status(ui, "monitor", middleware);
+                                });
+                                self.render_stats_plot(ui, &middleware.id);
+                                
+                                let backends: Vec<&BackendContainer> = middleware.backend_containers
+                                    .iter()
+                                    .filter(|b| Self::backend_visible(&self.status_filters, b))
+                                    .collect();
+                                for backend in Self::monitor_page(ui, &middleware.id, &backends) {
+                                    ui.horizontal(|ui| {
+                                        ui.label("  - ");
+                                        ui.label(&backend.name);
+                                        ui.label(":");
+                                        ui.label(Self::get_container_status_text(&backend.status));
+                                        ui.label(Self::get_health_status_text(&backend.health));
+                                        self.render_probe_badge(ui, &backend.id);
+                                        self.render_container_stats(ui, &backend.id, &backend.limits);
+                                    });
+                                    self.render_stats_plot(ui, &backend.id);
+                                }
+                            });
+                        }
+                    });
+                }
+                
+                ui.add_space(10.0);
+                self.render_topology_graph(ui);
+                
+                ui.add_space(10.0);
+                self.render_incident_calendar(ui);
+                
+                ui.add_space(10.0);
+                self.render_alerts_section(ui);
+                
+                ui.add_space(10.0);
+                self.render_metrics_section(ui);
+            });
+        });
+    }
+    
+    /// 超过一页时显示翻页控件，返回当前页的条目；页码按列表ID保存在界面状态中
+    fn monitor_page<'a, T>(ui: &mut egui::Ui, list_id: &str, items: &'a [T]) -> &'a [T] {
+        let pages = items.len().div_ceil(MONITOR_PAGE_SIZE);
+        if pages <= 1 {
+            return items;
+        }
+        let id = egui::Id::new(("monitor_page", list_id));
+        let mut page = ui.data(|d| d.get_temp::<usize>(id)).unwrap_or(0).min(pages - 1);
+        ui.horizontal(|ui| {
+            if ui.add_enabled(page > 0, egui::Button::new("◀").small()).clicked() {
+                page -= 1;
+            }
+            ui.label(format!("第 {}/{} 页，共 {} 项", page + 1, pages, items.len()));
+            if ui.add_enabled(page + 1 < pages, egui::Button::new("▶").small()).clicked() {
+                page += 1;
+            }
+        });
+        ui.data_mut(|d| d.insert_temp(id, page));
+        let start = page * MONITOR_PAGE_SIZE;
+        &items[start..(start + MONITOR_PAGE_SIZE).min(items.len())]
+    }
+    
+    /// 渲染容器最新的CPU、内存与网络用量，配置了上限时显示用量与上限的比例，尚无统计时不显示
+    fn render_container_stats(&self, ui: &mut egui::Ui, id: &str, limits: &ResourceLimits) {
+        let Some(sample) = self.stats_service.latest(id) else {
+            return;
+        };
+        let mb = |bytes: u64| bytes as f64 / 1048576.0;
+        let usage_label = |ui: &mut egui::Ui, ratio: f64, text: String| {
+            if ratio >= LIMIT_WARNING_RATIO {
+                ui.colored_label(Color32::YELLOW, text);
+            } else {
+                ui.label(text);
+            }
+        };
+        let stats = &sample.stats;
+        ui.separator();
+        match limits.cpus {
+            Some(cpus) => {
+                let ratio = stats.cpu_percent / (cpus * 100.0);
+                usage_label(ui, ratio, format!("CPU {:.1}%/{:.0}% ({}核上限)", stats.cpu_percent, cpus * 100.0, cpus));
+            }
+            None => {
+                ui.label(format!("CPU {:.1}%", stats.cpu_percent));
+            }
+        }
+        // 配置的上限优先，Docker报告的上限在未限制时为主机内存
+        let memory_limit = limits.memory_mb.map(|m| m * 1048576).unwrap_or(stats.memory_limit);
+        if memory_limit > 0 {
+            let ratio = stats.memory_bytes as f64 / memory_limit as f64;
+            usage_label(ui, ratio, format!(
+                "内存 {:.1}/{:.0} MB ({:.0}%)",
+                mb(stats.memory_bytes),
+                mb(memory_limit),
+                ratio * 100.0
+            ));
+        } else {
+            ui.label(format!("内存 {:.1} MB", mb(stats.memory_bytes)));
+        }
+        let network = match self.stats_service.network_rate(id) {
+            Some((rx, tx)) => format!("网络 ↓{:.1} ↑{:.1} KB/s", rx / 1024.0, tx / 1024.0),
+            None => format!("网络 ↓{:.1} ↑{:.1} MB", mb(stats.rx_bytes), mb(stats.tx_bytes)),
+        };
+        ui.label(network);
+    }
+    
+    /// 渲染容器最近的CPU与内存曲线
+    fn render_stats_plot(&self, ui: &mut egui::Ui, id: &str) {
+        let Some(samples) = self.stats_service.samples(id) else {
+            return;
+        };
+        CollapsingHeader::new("资源曲线").id_source(("stats_plot", id)).show(ui, |ui| {
+            let now = Utc::now();
+            let to_line = |value: fn(&docker::ContainerStats) -> f64| -> PlotPoints {
+                samples
+                    .iter()
+                    .map(|s| [(s.timestamp - now).num_milliseconds() as f64 / 1000.0, value(&s.stats)])
+                    .collect()
+            };
+            Plot::new(("stats", id))
+                .height(120.0)
+                .legend(Legend::default())
+                .show(ui, |plot_ui| {
+                    plot_ui.line(Line::new(to_line(|s| s.cpu_percent)).name("CPU (%)"));
+                    plot_ui.line(Line::new(to_line(|s| s.memory_bytes as f64 / 1048576.0)).name("内存 (MB)"));
+                });
+        });
+    }
+    
+    /// 渲染请求指标面板
+    fn render_metrics_section(&mut self, ui: &mut egui::Ui) {
+        ui.heading("请求指标");
+        ui.horizontal(|ui| {
+            if ui.add_enabled(!self.metrics_service.is_scraping(), egui::Button::new("采集指标")).clicked() {
+                self.scrape_metrics();
+            }
+            ui.checkbox(&mut self.metrics_auto_scrape, "自动采集");
+            ui.label("间隔 (秒):");
+            ui.add(egui::DragValue::new(&mut self.metrics_scrape_interval).clamp_range(1..=3600));
+            if self.metrics_service.is_scraping() {
+                ui.spinner();
+            }
+        });
+        
+        let mut export = None;
+        for group in &self.business_groups {
+            let middleware_ids: Vec<String> = group.middlewares.iter().map(|m| m.id.clone()).collect();
+            
+            CollapsingHeader::new(format!("{} (聚合)", group.name))
+                .id_source(format!("metrics_group_{}", group.id))
+                .show(ui, |ui| {
+                    let series: Vec<(EndpointKind, Vec<RatePoint>)> = EndpointKind::ALL
+                        .iter()
+                        .map(|kind| (*kind, self.metrics_service.store.group_rate_series(&middleware_ids, *kind)))
+                        .collect();
+                    let title = format!("{} (聚合)", group.name);
+                    if let Some((format, rect)) = Self::render_rate_charts(ui, &format!("group_{}", group.id), &series) {
+                        export = Some((format, rect, title, series));
+                    }
+                    
+                    for middleware in &group.middlewares {
+                        CollapsingHeader::new(&middleware.name)
+                            .id_source(format!("metrics_middleware_{}", middleware.id))
+                            .show(ui, |ui| {
+                                if self.metrics_service.store.latest(&middleware.id).is_none() {
+                                    ui.label("暂无指标数据");
+                                    return;
+                                }
+                                let series: Vec<(EndpointKind, Vec<RatePoint>)> = EndpointKind::ALL
+                                    .iter()
+                                    .map(|kind| (*kind, self.metrics_service.store.rate_series(&middleware.id, *kind)))
+                                    .collect();
+                                if let Some((format, rect)) = Self::render_rate_charts(ui, &format!("middleware_{}", middleware.id), &series) {
+                                    export = Some((format, rect, middleware.name.clone(), series));
+                                }
+                            });
+                    }
+                });
+        }
+        
+        if let Some((format, rect, title, series)) = export {
+            let path = image_export::export_file_name("metrics", format);
+            match format {
+                ImageFormat::Svg => self.save_svg(&path, &image_export::rate_charts_svg(&title, &series)),
+                ImageFormat::Png => self.request_screenshot(ui.ctx(), rect, path),
+            }
+        }
+    }
+    
+    /// 渲染QPS与错误率图表，返回用户请求导出的格式及图表区域
+    fn render_rate_charts(ui: &mut egui::Ui, id: &str, series: &[(EndpointKind, Vec<RatePoint>)]) -> Option<(ImageFormat, egui::Rect)> {
+        let mut export = None;
+        ui.horizontal(|ui| {
+            if ui.button("导出图像 (PNG)").clicked() {
+                export = Some(ImageFormat::Png);
+            }
+            if ui.button("导出图像 (SVG)").clicked() {
+                export = Some(ImageFormat::Svg);
+            }
+        });
+        
+        let response = ui.scope(|ui| Self::render_rate_plots(ui, id, series)).response;
+        export.map(|format| (format, response.rect.intersect(ui.clip_rect())))
+    }
+    
+    /// 渲染QPS与错误率的表格和曲线
+    fn render_rate_plots(ui: &mut egui::Ui, id: &str, series: &[(EndpointKind, Vec<RatePoint>)]) {
+        egui::Grid::new(format!("rate_table_{}", id)).striped(true).show(ui, |ui| {
+            ui.label("接口");
+            ui.label("QPS");
+            ui.label("错误率");
+            ui.end_row();
+            for (kind, points) in series {
+                ui.label(kind.label());
+                match points.last() {
+                    Some(point) => {
+                        ui.label(format!("{:.2}", point.qps));
+                        ui.label(format!("{:.1}%", point.error_rate * 100.0));
+                    }
+                    None => {
+                        ui.label("-");
+                        ui.label("-");
+                    }
+                }
+                ui.end_row();
+            }
+        });
+        
+        let now = Utc::now();
+        let to_line = |points: &[RatePoint], value: fn(&RatePoint) -> f64| -> PlotPoints {
+            points
+                .iter()
+                .map(|p| [(p.timestamp - now).num_milliseconds() as f64 / 1000.0, value(p)])
+                .collect()
+        };
+        
+        ui.label("QPS");
+        Plot::new(format!("qps_{}", id))
+            .height(140.0)
+            .legend(Legend::default())
+            .show(ui, |plot_ui| {
+                for (kind, points) in series {
+                    plot_ui.line(Line::new(to_line(points, |p| p.qps)).name(kind.label()));
+                }
+            });
+        
+        ui.label("错误率 (%)");
+        Plot::new(format!("error_rate_{}", id))
+            .height(140.0)
+            .legend(Legend::default())
+            .show(ui, |plot_ui| {
+                for (kind, points) in series {
+                    plot_ui.line(Line::new(to_line(points, |p| p.error_rate * 100.0)).name(kind.label()));
+                }
+            });
+    }
+    
+    /// 渲染拓扑图
+    fn render_topology_graph(&mut self, ui: &mut egui::Ui) {
+        let layout = topology::layout(&self.business_groups);
+        
+        ui.horizontal(|ui| {
+            ui.heading("拓扑图");
+            if ui.button("导出图像 (PNG)").clicked() {
+                let rect = ui.data(|d| d.get_temp::<egui::Rect>(egui::Id::new("topology_graph_rect")));
+                if let Some(rect) = rect {
+                    self.request_screenshot(ui.ctx(), rect, image_export::export_file_name("topology", ImageFormat::Png));
+                }
+            }
+            if ui.button("导出图像 (SVG)").clicked() {
+                let path = image_export::export_file_name("topology", ImageFormat::Svg);
+                self.save_svg(&path, &image_export::topology_svg(&layout));
+            }
+        });
+        
+        ScrollArea::horizontal().id_source("topology_graph").show(ui, |ui| {
+            let (response, painter) = ui.allocate_painter(egui::vec2(layout.width, layout.height), egui::Sense::hover());
+            let origin = response.rect.min.to_vec2();
+            ui.data_mut(|d| d.insert_temp(egui::Id::new("topology_graph_rect"), response.rect.intersect(ui.clip_rect())));
+            
+            painter.rect_filled(response.rect, 0.0, Color32::WHITE);
+            let stroke = egui::Stroke::new(1.5, Color32::from_gray(97));
+            for edge in &layout.edges {
+                let from = &layout.nodes[edge.from];
+                let to = &layout.nodes[edge.to];
+                let points = [
+                    egui::pos2(from.x + from.width, from.y + from.height / 2.0) + origin,
+                    egui::pos2(to.x, to.y + to.height / 2.0) + origin,
+                ];
+                if edge.dashed {
+                    painter.extend(egui::Shape::dashed_line(&points, stroke, 6.0, 4.0));
+                } else {
+                    painter.line_segment(points, stroke);
+                }
+            }
+            
+            for node in &layout.nodes {
+                let rect = egui::Rect::from_min_size(egui::pos2(node.x, node.y) + origin, egui::vec2(node.width, node.height));
+                painter.rect(rect, 6.0, Color32::from_rgb(node.fill[0], node.fill[1], node.fill[2]), stroke);
+                for (index, line) in node.lines.iter().enumerate() {
+                    let font = if index == 0 { egui::FontId::proportional(13.0) } else { egui::FontId::proportional(11.0) };
+                    painter.text(
+                        rect.min + egui::vec2(8.0, 6.0 + index as f32 * 15.0),
+                        egui::Align2::LEFT_TOP,
+                        line,
+                        font,
+                        Color32::BLACK,
+                    );
+                }
+            }
+        });
+    }
+    
+    /// 保存SVG图像
+    fn save_svg(&mut self, path: &str, content: &str) {
+        let entry = match std::fs::write(path, content) {
+            Ok(()) => LogEntry::new("导出", &format!("图像已导出到 {}", path)),
+            Err(e) => LogEntry::new("导出", &format!("导出图像失败 {}: {}", path, e)),
+        };
+        self.push_log(entry);
+    }
+    
+    /// 请求截取界面区域，截图在下一帧返回后保存为PNG
+    fn request_screenshot(&mut self, ctx: &egui::Context, rect: egui::Rect, path: String) {
+        let rect = rect.intersect(ctx.screen_rect());
+        if rect.width() <= 0.0 || rect.height() <= 0.0 {
+            self.push_log(LogEntry::new("导出", "导出区域不在可见范围内"));
+            return;
+        }
+        self.pending_screenshot = Some((rect, path));
+        ctx.send_viewport_cmd(egui::ViewportCommand::Screenshot);
+    }
+    
+    /// 处理截图结果
+    fn handle_screenshot(&mut self, ctx: &egui::Context) {
+        if self.pending_screenshot.is_none() {
+            return;
+        }
+        
+        let image = ctx.input(|i| {
+            i.events.iter().find_map(|e| match e {
+                egui::Event::Screenshot { image, .. } => Some(image.clone()),
+                _ => None,
+            })
+        });
+        
+        if let Some(image) = image
+            && let Some((rect, path)) = self.pending_screenshot.take()
+        {
+            let region = image.region(&rect, Some(ctx.pixels_per_point()));
+            let entry = match image_export::save_png(&path, &region) {
+                Ok(()) => LogEntry::new("导出", &format!("图像已导出到 {}", path)),
+                Err(e) => LogEntry::new("导出", &format!("导出图像失败: {}", e)),
+            };
+            self.push_log(entry);
+        }
+    }
+    
+    /// 采集所有中间层的指标
+    fn scrape_metrics(&mut self) {
+        self.metrics_service.scrape(&self.business_groups, &self.network_service.get_profiles());
+        self.last_metrics_scrape = Some(Instant::now());
+    }
+    
+    /// 提交后台任务
+    fn enqueue_job(&mut self, kind: JobKind) {
+        self.enqueue_job_as(kind, None);
+    }
+    
+    /// 提交以指定操作人的业务组权限执行的后台任务
+    fn enqueue_job_as(&mut self, kind: JobKind, actor: Option<&str>) {
+        let description = kind.describe();
+        let trace_id = api::new_trace_id();
+        let entry = match self.job_service.enqueue(kind, &trace_id, actor) {
+            Ok(()) => {
+                self.record_traced_audit(&format!("提交任务: {}", description), None, None, &trace_id);
+                LogEntry::new("任务", &format!("已提交任务: {} (追踪ID {})", description, trace_id))
+            }
+            Err(e) => LogEntry::new("任务", &format!("提交任务失败: {}", e)),
+        };
+        self.push_log(entry);
+    }
+    
+    /// 收取后台任务结果
+    fn poll_jobs(&mut self) {
+        let finished = self.job_service.poll();
+        if finished.is_empty() {
+            return;
+        }
+        
+        for job in &finished {
+            self.push_log(LogEntry::new("任务", &format!("{} {}: {}", job.kind.describe(), job.status.label(), job.message)));
+            // 升级后的镜像需重新检查，旧的检查结果不再适用
+            if let JobKind::UpgradeGroup { targets, .. } = &job.kind {
+                for (id, _) in targets {
+                    self.image_update_service.forget(id);
+                }
+                self.image_update_service.check_now();
+            }
+        }
+        self.job_history = self.job_service.get_history().unwrap_or_default();
+        self.load_business_groups();
+    }
+    
+    /// 渲染任务标签页
+    fn render_jobs_tab(&mut self, ui: &mut egui::Ui) {
+        ui.heading("后台任务");
+        ui.separator();
+        
+        ui.horizontal(|ui| {
+            ui.label("启动业务组:");
+            let selected = self.business_groups
+                .iter()
+                .find(|g| Some(&g.id) == self.job_group_id.as_ref())
+                .map(|g| g.name.clone())
+                .unwrap_or_else(|| "选择业务组".to_string());
+            egui::ComboBox::from_id_source("job_group").selected_text(selected).show_ui(ui, |ui| {
+                for group in &self.business_groups {
+                    ui.selectable_value(&mut self.job_group_id, Some(group.id.clone()), &group.name);
+                }
+            });
+            let group = self.business_groups.iter().find(|g| Some(&g.id) == self.job_group_id.as_ref());
+            if ui.add_enabled(group.is_some(), egui::Button::new("提交")).clicked()
+                && let Some(group) = group
+            {
+                let kind = JobKind::StartGroup { group_id: group.id.clone(), group_name: group.name.clone() };
+                self.enqueue_job(kind);
+            }
+        });
+        
+        ui.horizontal(|ui| {
+            ui.label("拉取镜像:");
+            ui.text_edit_singleline(&mut self.job_image);
+            if ui.add_enabled(!self.job_image.trim().is_empty(), egui::Button::new("提交")).clicked() {
+                let kind = JobKind::PullImage { image: self.job_image.trim().to_string() };
+                self.enqueue_job(kind);
+            }
+        });
+        
+        ui.horizontal(|ui| {
+            ui.label("压测中间层:");
+            let middlewares: Vec<&MiddlewareContainer> = self.business_groups.iter().flat_map(|g| g.middlewares.iter()).collect();
+            let selected = middlewares
+                .iter()
+                .find(|m| Some(&m.id) == self.job_middleware_id.as_ref())
+                .map(|m| m.name.clone())
+                .unwrap_or_else(|| "选择中间层".to_string());
+            egui::ComboBox::from_id_source("job_middleware").selected_text(selected).show_ui(ui, |ui| {
+                for middleware in &middlewares {
+                    ui.selectable_value(&mut self.job_middleware_id, Some(middleware.id.clone()), &middleware.name);
+                }
+            });
+            ui.label("请求数:");
+            ui.add(egui::DragValue::new(&mut self.job_benchmark_requests).clamp_range(1..=10000));
+            let middleware = middlewares.iter().find(|m| Some(&m.id) == self.job_middleware_id.as_ref());
+            if ui.add_enabled(middleware.is_some(), egui::Button::new("提交")).clicked()
+                && let Some(middleware) = middleware
+            {
+                let kind = JobKind::Benchmark {
+                    middleware_id: middleware.id.clone(),
+                    middleware_name: middleware.name.clone(),
+                    url: middleware.url.clone(),
+                    timeout: middleware.config.crud_api.timeout,
+                    requests: self.job_benchmark_requests,
+                };
+                self.enqueue_job(kind);
+            }
+        });
+        
+        ui.horizontal(|ui| {
+            ui.label("批量解密:");
+            let middlewares: Vec<&MiddlewareContainer> = self.business_groups.iter().flat_map(|g| g.middlewares.iter()).collect();
+            let selected = middlewares
+                .iter()
+                .find(|m| Some(&m.id) == self.job_decrypt_middleware_id.as_ref())
+                .map(|m| m.name.clone())
+                .unwrap_or_else(|| "选择中间层".to_string());
+            egui::ComboBox::from_id_source("job_decrypt_middleware").selected_text(selected).show_ui(ui, |ui| {
+                for middleware in &middlewares {
+                    ui.selectable_value(&mut self.job_decrypt_middleware_id, Some(middleware.id.clone()), &middleware.name);
+                }
+            });
+            ui.label("密文文件:");
+            ui.add(egui::TextEdit::singleline(&mut self.job_decrypt_input).desired_width(160.0));
+            ui.label("结果文件:");
+            ui.add(egui::TextEdit::singleline(&mut self.job_decrypt_output).desired_width(160.0));
+            ui.label("并发数:");
+            ui.add(egui::DragValue::new(&mut self.job_decrypt_workers).clamp_range(1..=jobs::MAX_BULK_WORKERS));
+            let middleware = middlewares.iter().find(|m| Some(&m.id) == self.job_decrypt_middleware_id.as_ref());
+            let ready = !self.job_decrypt_input.trim().is_empty() && !self.job_decrypt_output.trim().is_empty();
+            if ui.add_enabled(middleware.is_some() && ready, egui::Button::new("提交")).clicked()
+                && let Some(middleware) = middleware
+            {
+                let kind = JobKind::BulkDecrypt {
+                    middleware_id: middleware.id.clone(),
+                    middleware_name: middleware.name.clone(),
+                    input: self.job_decrypt_input.trim().to_string(),
+                    output: self.job_decrypt_output.trim().to_string(),
+                    workers: self.job_decrypt_workers,
+                };
+                self.enqueue_job(kind);
+            }
+        });
+        if !self.job_decrypt_output.trim().is_empty() {
+            ui.label(
+                egui::RichText::new(format!(
+                    "按调度策略分发到运行中的读实例；失败记录写入 {}",
+                    jobs::error_report_path(self.job_decrypt_output.trim())
+                ))
+                .small()
+                .weak(),
+            );
+        }
+        
+        ui.horizontal(|ui| {
+            ui.label("重试策略 - 最多执行次数:");
+            let mut changed = ui.add(egui::DragValue::new(&mut self.job_retry_policy.max_attempts).clamp_range(1..=10)).changed();
+            ui.label("重试间隔 (秒):");
+            changed |= ui.add(egui::DragValue::new(&mut self.job_retry_policy.backoff_secs).clamp_range(0..=3600)).changed();
+            if changed && let Err(e) = self.job_service.set_retry_policy(self.job_retry_policy.clone()) {
+                self.push_log(LogEntry::new("任务", &format!("保存重试策略失败: {}", e)));
+            }
+        });
+        
+        ui.add_space(10.0);
+        ui.heading("进行中");
+        let mut cancel = None;
+        if !self.job_service.has_active_jobs() {
+            ui.label("暂无进行中的任务");
+        }
+        egui::Grid::new("active_jobs").striped(true).show(ui, |ui| {
+            for job in self.job_service.active_jobs() {
+                ui.label(job.kind.describe());
+                ui.label(job.status.label());
+                ui.label(format!("第 {}/{} 次", job.attempts.max(1), job.policy.max_attempts));
+                match &job.checkpoint {
+                    Some(checkpoint) => Self::render_job_progress(ui, job, checkpoint),
+                    None => ui.label(""),
+                };
+                if job.status == JobStatus::Running {
+                    ui.spinner();
+                } else {
+                    ui.label(&job.message);
+                }
+                if ui.button("取消").clicked() {
+                    cancel = Some(job.id.clone());
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(job_id) = cancel
+            && let Err(e) = self.job_service.cancel(&job_id)
+        {
+            self.push_log(LogEntry::new("任务", &format!("取消任务失败: {}", e)));
+        }
+        
+        ui.add_space(10.0);
+        ui.horizontal(|ui| {
+            ui.heading("历史记录");
+            if ui.button("清空").clicked() {
+                if let Err(e) = self.job_service.clear_history() {
+                    self.push_log(LogEntry::new("任务", &format!("清空任务记录失败: {}", e)));
+                }
+                self.job_history = self.job_service.get_history().unwrap_or_default();
+            }
+        });
+        ScrollArea::vertical().id_source("job_history").show(ui, |ui| {
+            egui::Grid::new("job_history_grid").striped(true).show(ui, |ui| {
+                for job in self.job_history.iter().rev() {
+                    ui.label(job.finished_at.unwrap_or(job.created_at).format("%Y-%m-%d %H:%M:%S").to_string());
+                    ui.label(job.kind.describe());
+                    let color = match job.status {
+                        JobStatus::Succeeded => Color32::GREEN,
+                        JobStatus::Failed => Color32::RED,
+                        _ => Color32::GRAY,
+                    };
+                    ui.label(RichText::new(job.status.label()).color(color));
+                    ui.label(format!("{} 次", job.attempts));
+                    ui.label(&job.message);
+                    ui.end_row();
+                }
+            });
+        });
+    }
+    
+    /// 收取接口调试请求的结果
+    fn poll_playground(&mut self) {
+        if let Some(result) = self.playground_service.poll() {
+            self.playground_response = Some(result.map_err(|e| format!("{:#}", e)));
+            match self.playground_service.get_history() {
+                Ok(history) => self.playground_history = history,
+                Err(e) => self.push_log(LogEntry::new("接口调试", &format!("加载调试历史失败: {:#}", e))),
+            }
+        }
+    }
+    
+    /// 重新读取请求集合
+    fn load_request_collections(&mut self) {
+        match self.playground_service.get_collections() {
+            Ok(collections) => self.request_collections = collections,
+            Err(e) => self.push_log(LogEntry::new("接口调试", &format!("加载请求集合失败: {:#}", e))),
+        }
+    }
+    
+    /// 渲染接口调试标签页
+    fn render_playground_tab(&mut self, ui: &mut egui::Ui) {
+        ui.heading("接口调试");
+        ui.separator();
+        
+        SidePanel::left("playground_collections").resizable(true).show_inside(ui, |ui| {
+            self.render_request_collections(ui);
+        });
+        
+        ScrollArea::vertical().show(ui, |ui| {
+            self.render_request_editor(ui);
+            ui.separator();
+            self.render_playground_response(ui);
+            ui.separator();
+            CollapsingHeader::new(format!("历史记录 ({})", self.playground_history.len()))
+                .id_source("playground_history")
+                .show(ui, |ui| self.render_playground_history(ui));
+            CollapsingHeader::new("大数据加解密")
+                .id_source("playground_payload")
+                .show(ui, |ui| self.render_payload_tool(ui));
+        });
+    }
+    
+    /// 渲染请求集合列表
+    fn render_request_collections(&mut self, ui: &mut egui::Ui) {
+        ui.strong("请求集合");
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut self.new_collection_name);
+            if ui.add_enabled(!self.new_collection_name.trim().is_empty(), egui::Button::new("新建")).clicked() {
+                let result = self.playground_service.add_collection(self.new_collection_name.trim());
+                self.report_error(result);
+                self.new_collection_name.clear();
+                self.load_request_collections();
+            }
+        });
+        ui.separator();
+        
+        let mut open_request = None;
+        let mut delete_request = None;
+        let mut delete_collection = None;
+        ScrollArea::vertical().id_source("playground_collection_list").show(ui, |ui| {
+            for collection in &self.request_collections {
+                CollapsingHeader::new(&collection.name).id_source(&collection.id).show(ui, |ui| {
+                    for request in &collection.requests {
+                        ui.horizontal(|ui| {
+                            let selected = request.id == self.playground_request.id;
+                            let text = format!("{} {}", request.method.label(), request.name);
+                            if ui.selectable_label(selected, text).clicked() {
+                                open_request = Some((collection.id.clone(), request.clone()));
+                            }
+                            if ui.small_button("删除").clicked() {
+                                delete_request = Some((collection.id.clone(), request.id.clone()));
+                            }
+                        });
+                    }
+                    if collection.requests.is_empty() {
+                        ui.label("暂无请求");
+                    }
+                    if ui.button("删除集合").clicked() {
+                        delete_collection = Some(collection.id.clone());
+                    }
+                });
+            }
+        });
+        
+        if let Some((collection_id, request)) = open_request {
+            self.playground_collection_id = Some(collection_id);
+            self.playground_request = request;
+        }
+        if let Some((collection_id, request_id)) = delete_request {
+            let result = self.playground_service.delete_request(&collection_id, &request_id);
+            self.report_error(result);
+            self.load_request_collections();
+        }
+        if let Some(collection_id) = delete_collection {
+            let result = self.playground_service.delete_collection(&collection_id);
+            self.report_error(result);
+            if self.playground_collection_id.as_ref() == Some(&collection_id) {
+                self.playground_collection_id = None;
+            }
+            self.load_request_collections();
+        }
+    }
+    
+    /// 以新的追踪ID发送调试请求并写入审计日志
+    fn send_playground_request(&mut self, middleware: &MiddlewareContainer, request: SavedRequest) {
+        let trace_id = api::new_trace_id();
+        let action = format!("向 {} 发送调试请求: {} {}", middleware.name, request.method.label(), request.path);
+        self.playground_service.send(middleware, request, &trace_id);
+        self.record_traced_audit(&action, Some(EntityKind::Middleware), Some(&middleware.id), &trace_id);
+    }
+    
+    /// 渲染请求编辑器
+    fn render_request_editor(&mut self, ui: &mut egui::Ui) {
+        let middlewares: Vec<MiddlewareContainer> = self.business_groups
+            .iter()
+            .flat_map(|g| g.middlewares.iter().cloned())
+            .collect();
+        let middleware = middlewares.iter().find(|m| Some(&m.id) == self.playground_middleware_id.as_ref());
+        
+        ui.horizontal(|ui| {
+            ui.label("目标中间层:");
+            let selected = middleware.map(|m| m.name.clone()).unwrap_or_else(|| "选择中间层".to_string());
+            egui::ComboBox::from_id_source("playground_middleware").selected_text(selected).show_ui(ui, |ui| {
+                for middleware in &middlewares {
+                    ui.selectable_value(&mut self.playground_middleware_id, Some(middleware.id.clone()), &middleware.name);
+                }
+            });
+            if let Some(middleware) = middleware {
+                ui.label(middleware.api_base_url());
+            }
+        });
+        
+        let request = &mut self.playground_request;
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_source("playground_method")
+                .selected_text(request.method.label())
+                .width(80.0)
+                .show_ui(ui, |ui| {
+                    for method in HttpMethod::ALL {
+                        ui.selectable_value(&mut request.method, method, method.label());
+                    }
+                });
+            ui.add(egui::TextEdit::singleline(&mut request.path).desired_width(400.0));
+        });
+        
+        ui.label("请求头:");
+        let mut remove_header = None;
+        egui::Grid::new("playground_headers").show(ui, |ui| {
+            for (index, (name, value)) in request.headers.iter_mut().enumerate() {
+                ui.text_edit_singleline(name);
+                ui.text_edit_singleline(value);
+                if ui.small_button("移除").clicked() {
+                    remove_header = Some(index);
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(index) = remove_header {
+            request.headers.remove(index);
+        }
+        if ui.button("添加请求头").clicked() {
+            request.headers.push((String::new(), String::new()));
+        }
+        
+        ui.label("请求体:");
+        ui.add(egui::TextEdit::multiline(&mut request.body).code_editor().desired_rows(6).desired_width(f32::INFINITY));
+        
+        ui.horizontal(|ui| {
+            let sending = self.playground_service.is_sending();
+            if ui.add_enabled(middleware.is_some() && !sending, egui::Button::new("发送")).clicked()
+                && let Some(middleware) = middleware
+            {
+                self.playground_response = None;
+                self.send_playground_request(middleware, self.playground_request.clone());
+            }
+            if sending {
+                ui.spinner();
+            }
+        });
+        
+        ui.horizontal(|ui| {
+            ui.label("名称:");
+            ui.text_edit_singleline(&mut self.playground_request.name);
+            let selected = self.request_collections
+                .iter()
+                .find(|c| Some(&c.id) == self.playground_collection_id.as_ref())
+                .map(|c| c.name.clone())
+                .unwrap_or_else(|| "选择集合".to_string());
+            egui::ComboBox::from_id_source("playground_collection").selected_text(selected).show_ui(ui, |ui| {
+                for collection in &self.request_collections {
+                    ui.selectable_value(&mut self.playground_collection_id, Some(collection.id.clone()), &collection.name);
+                }
+            });
+            
+            let collection_id = self.playground_collection_id.clone();
+            let can_save = collection_id.is_some() && !self.playground_request.name.trim().is_empty();
+            let save = ui.add_enabled(can_save, egui::Button::new("保存")).clicked();
+            let save_as = ui.add_enabled(can_save, egui::Button::new("另存为新请求")).clicked();
+            if (save || save_as)
+                && let Some(collection_id) = collection_id
+            {
+                if save_as {
+                    self.playground_request.id = uuid::Uuid::new_v4().to_string();
+                }
+                let result = self.playground_service.save_request(&collection_id, self.playground_request.clone());
+                self.report_error(result);
+                self.load_request_collections();
+            }
+            if ui.button("新建请求").clicked() {
+                self.playground_request = SavedRequest::default();
+            }
+        });
+    }
+    
+    /// 渲染调试响应
+    fn render_playground_response(&mut self, ui: &mut egui::Ui) {
+        ui.strong("响应");
+        match &self.playground_response {
+            None => {
+                ui.label("尚未发送请求");
+            }
+            Some(Err(e)) => {
+                ui.colored_label(Color32::RED, format!("请求失败: {}", e));
+            }
+            Some(Ok(response)) => {
+                let color = if response.status < 400 { Color32::GREEN } else { Color32::RED };
+                ui.horizontal(|ui| {
+                    ui.colored_label(color, format!("状态码 {}", response.status));
+                    ui.label(format!("耗时 {:.1} ms", response.elapsed_ms));
+                });
+                CollapsingHeader::new(format!("响应头 ({})", response.headers.len())).show(ui, |ui| {
+                    for (name, value) in &response.headers {
+                        ui.monospace(format!("{}: {}", name, value));
+                    }
+                });
+                
+                // JSON响应格式化后显示
+                let mut body = serde_json::from_str::<serde_json::Value>(&response.body)
+                    .ok()
+                    .and_then(|value| serde_json::to_string_pretty(&value).ok())
+                    .unwrap_or_else(|| response.body.clone());
+                ui.add(egui::TextEdit::multiline(&mut body).code_editor().interactive(false).desired_width(f32::INFINITY));
+            }
+        }
+    }
+    
+    /// 渲染大数据加解密工具，超过块大小的数据分块发送到目标中间层
+    fn render_payload_tool(&mut self, ui: &mut egui::Ui) {
+        ui.label("对文件内容调用目标中间层加密或解密。超过块大小的数据逐块加密，结果为包含各块密文的清单；解密时自动识别清单并校验完整性。");
+        let middleware = self.business_groups
+            .iter()
+            .flat_map(|g| g.middlewares.iter())
+            .find(|m| Some(&m.id) == self.playground_middleware_id.as_ref())
+            .cloned();
+        
+        egui::Grid::new("payload_tool").num_columns(2).show(ui, |ui| {
+            ui.label("目标中间层:");
+            ui.label(middleware.as_ref().map(|m| m.name.as_str()).unwrap_or("请在上方选择"));
+            ui.end_row();
+            ui.label("操作:");
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut self.payload_mode, PayloadMode::Encrypt, PayloadMode::Encrypt.label());
+                ui.radio_value(&mut self.payload_mode, PayloadMode::Decrypt, PayloadMode::Decrypt.label());
+            });
+            ui.end_row();
+            ui.label("输入文件:");
+            ui.text_edit_singleline(&mut self.payload_input);
+            ui.end_row();
+            ui.label("输出文件:");
+            ui.text_edit_singleline(&mut self.payload_output);
+            ui.end_row();
+            ui.label("块大小 (KiB):");
+            let mut kib = self.payload_chunk_bytes / 1024;
+            if ui.add(egui::DragValue::new(&mut kib).clamp_range(1..=65536)).changed() {
+                self.payload_chunk_bytes = kib * 1024;
+                if let Err(e) = self.payload_service.set_chunk_bytes(self.payload_chunk_bytes) {
+                    self.push_log(LogEntry::new("接口调试", &format!("保存块大小失败: {:#}", e)));
+                }
+            }
+            ui.end_row();
+        });
+        
+        let running = self.payload_service.is_running();
+        let ready = middleware.is_some() && !self.payload_input.trim().is_empty() && !self.payload_output.trim().is_empty();
+        ui.horizontal(|ui| {
+            if ui.add_enabled(ready && !running, egui::Button::new("开始")).clicked()
+                && let Some(middleware) = &middleware
+            {
+                self.payload_result = None;
+                let trace_id = api::new_trace_id();
+                self.payload_service.start(
+                    middleware,
+                    self.payload_mode,
+                    self.payload_input.trim(),
+                    self.payload_output.trim(),
+                    self.payload_chunk_bytes,
+                    &trace_id,
+                );
+                let action = format!("在 {} 上{}文件 {}", middleware.name, self.payload_mode.label(), self.payload_input.trim());
+                self.record_traced_audit(&action, Some(EntityKind::Middleware), Some(&middleware.id), &trace_id);
+            }
+            if running {
+                let (done, total) = self.payload_service.progress();
+                let fraction = if total == 0 { 0.0 } else { done as f32 / total as f32 };
+                ui.add(egui::ProgressBar::new(fraction).text(format!("{}/{} 块", done, total)).desired_width(200.0));
+            }
+        });
+        match &self.payload_result {
+            Some(Ok(message)) => {
+                ui.colored_label(Color32::GREEN, message);
+            }
+            Some(Err(e)) => {
+                ui.colored_label(Color32::RED, e);
+            }
+            None => {}
+        }
+    }
+    
+    /// 渲染调试历史，支持搜索、载入与重新发送
+    fn render_playground_history(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("请求内容:");
+            let mut redaction = self.playground_history_redaction;
+            egui::ComboBox::from_id_source("playground_history_redaction")
+                .selected_text(redaction.label())
+                .show_ui(ui, |ui| {
+                    for option in HistoryRedaction::ALL {
+                        ui.selectable_value(&mut redaction, option, option.label());
+                    }
+                });
+            if redaction != self.playground_history_redaction {
+                match self.playground_service.set_redaction(redaction) {
+                    Ok(()) => self.playground_history_redaction = redaction,
+                    Err(e) => self.push_log(LogEntry::new("接口调试", &format!("保存历史设置失败: {:#}", e))),
+                }
+            }
+            ui.label("（只影响之后的记录，请求体与凭据类请求头按此方式保存）");
+        });
+        ui.horizontal(|ui| {
+            ui.label("搜索:");
+            ui.text_edit_singleline(&mut self.playground_history_query);
+            if ui.button("清空历史").clicked() {
+                match self.playground_service.clear_history() {
+                    Ok(()) => self.playground_history.clear(),
+                    Err(e) => self.push_log(LogEntry::new("接口调试", &format!("清空调试历史失败: {:#}", e))),
+                }
+            }
+        });
+        
+        let entries: Vec<PlaygroundHistoryEntry> = self.playground_history
+            .iter()
+            .rev()
+            .filter(|e| e.matches(&self.playground_history_query))
+            .cloned()
+            .collect();
+        if entries.is_empty() {
+            ui.label("没有匹配的记录");
+            return;
+        }
+        
+        let middlewares: Vec<MiddlewareContainer> = self.business_groups
+            .iter()
+            .flat_map(|g| g.middlewares.iter().cloned())
+            .collect();
+        let sending = self.playground_service.is_sending();
+        let mut load = None;
+        let mut rerun = None;
+        let mut trace = None;
+        egui::Grid::new("playground_history_list").striped(true).show(ui, |ui| {
+            ui.strong("时间");
+            ui.strong("中间层");
+            ui.strong("请求");
+            ui.strong("结果");
+            ui.strong("耗时");
+            ui.strong("追踪ID");
+            ui.strong("");
+            ui.end_row();
+            for entry in &entries {
+                ui.label(entry.timestamp.format("%Y-%m-%d %H:%M:%S").to_string());
+                ui.label(&entry.middleware_name);
+                ui.label(format!("{} {}", entry.request.method.label(), entry.request.path))
+                    .on_hover_text(&entry.request.body);
+                match (entry.status, &entry.error) {
+                    (Some(status), _) => {
+                        let color = if status < 400 { Color32::GREEN } else { Color32::RED };
+                        ui.colored_label(color, status.to_string()).on_hover_text(&entry.response);
+                    }
+                    (None, error) => {
+                        ui.colored_label(Color32::RED, "失败").on_hover_text(error.as_deref().unwrap_or_default());
+                    }
+                }
+                ui.label(entry.elapsed_ms.map(|ms| format!("{:.1} ms", ms)).unwrap_or_default());
+                match &entry.trace_id {
+                    Some(trace_id) => {
+                        if ui.small_button(Self::short_trace_id(trace_id)).on_hover_text(format!("{}\n点击在日志中心检索", trace_id)).clicked() {
+                            trace = Some(trace_id.clone());
+                        }
+                    }
+                    None => {
+                        ui.label("");
+                    }
+                }
+                ui.horizontal(|ui| {
+                    if ui.small_button("载入").clicked() {
+                        load = Some(entry.clone());
+                    }
+                    let middleware = middlewares.iter().find(|m| m.id == entry.middleware_id);
+                    let button = ui.add_enabled(
+                        entry.replayable && middleware.is_some() && !sending,
+                        egui::Button::new("重新发送").small(),
+                    );
+                    let button = if entry.replayable {
+                        button
+                    } else {
+                        button.on_disabled_hover_text("请求内容未保存原文，请载入后补全再发送")
+                    };
+                    if button.clicked()
+                        && let Some(middleware) = middleware
+                    {
+                        rerun = Some((middleware.clone(), entry.request.clone()));
+                    }
+                });
+                ui.end_row();
+            }
+        });
+        
+        if let Some(trace_id) = trace {
+            self.search_trace(&trace_id);
+            self.current_tab = AppTab::Logs;
+        }
+        
+        if let Some(entry) = load {
+            let mut request = entry.request;
+            // 未保存原文的内容无法还原，清空后由用户补全
+            if !entry.replayable {
+                request.body.clear();
+                for (name, value) in &mut request.headers {
+                    if SavedRequest::is_sensitive_header(name) {
+                        value.clear();
+                    }
+                }
+            }
+            self.playground_middleware_id = Some(entry.middleware_id);
+            self.playground_request = request;
+        }
+        if let Some((middleware, request)) = rerun {
+            self.playground_middleware_id = Some(middleware.id.clone());
+            self.playground_request = request.clone();
+            self.playground_response = None;
+            self.send_playground_request(&middleware, request);
+        }
+    }
+    
+    /// 渲染数据校验标签页
+    fn render_verify_tab(&mut self, ui: &mut egui::Ui) {
+        ui.heading("数据完整性校验");
+        ui.label("检查样例密文能否在指定中间层上成功解密，适用于密钥轮换或迁移之后。");
+        ui.separator();
+        
+        ui.label("校验目标:");
+        for group in &self.business_groups {
+            if group.middlewares.is_empty() {
+                continue;
+            }
+            ui.horizontal_wrapped(|ui| {
+                ui.label(format!("{}:", group.name));
+                for middleware in &group.middlewares {
+                    let mut checked = self.verify_targets.contains(&middleware.id);
+                    if ui.checkbox(&mut checked, &middleware.name).changed() {
+                        if checked {
+                            self.verify_targets.insert(middleware.id.clone());
+                        } else {
+                            self.verify_targets.remove(&middleware.id);
+                        }
+                    }
+                }
+            });
+        }
+        
+        ui.label("密文（每行一条，# 开头为注释）:");
+        ui.add(egui::TextEdit::multiline(&mut self.verify_input).code_editor().desired_rows(6).desired_width(f32::INFINITY));
+        ui.horizontal(|ui| {
+            ui.label("密文文件:");
+            ui.text_edit_singleline(&mut self.verify_file);
+            if ui.add_enabled(!self.verify_file.trim().is_empty(), egui::Button::new("从文件加载")).clicked() {
+                match verification::load_ciphertexts(self.verify_file.trim()) {
+                    Ok(ciphertexts) => {
+                        if !self.verify_input.is_empty() && !self.verify_input.ends_with('\n') {
+                            self.verify_input.push('\n');
+                        }
+                        self.verify_input.push_str(&ciphertexts.join("\n"));
+                    }
+                    Err(e) => self.push_log(LogEntry::new("数据校验", &format!("{:#}", e))),
+                }
+            }
+        });
+        
+        let ciphertexts = verification::parse_ciphertexts(&self.verify_input);
+        let profiles = self.network_service.get_profiles();
+        let targets: Vec<(MiddlewareContainer, Option<NetworkProfile>)> = self.business_groups
+            .iter()
+            .flat_map(|g| g.middlewares.iter().map(|m| (m, g.network_profile(&profiles))))
+            .filter(|(m, _)| self.verify_targets.contains(&m.id))
+            .map(|(m, network)| (m.clone(), network.cloned()))
+            .collect();
+        ui.horizontal(|ui| {
+            let running = self.verification_service.is_running();
+            let enabled = !running && !ciphertexts.is_empty() && !targets.is_empty();
+            if ui.add_enabled(enabled, egui::Button::new("开始校验")).clicked() {
+                self.verify_report = None;
+                let trace_id = api::new_trace_id();
+                self.verification_service.start(&targets, ciphertexts.clone(), &trace_id);
+                let action = format!("校验 {} 条密文在 {} 个中间层上的解密", ciphertexts.len(), targets.len());
+                self.record_traced_audit(&action, None, None, &trace_id);
+            }
+            ui.label(format!("{} 条密文 × {} 个目标", ciphertexts.len(), targets.len()));
+            if running {
+                ui.spinner();
+            }
+        });
+        ui.separator();
+        
+        let Some(report) = &self.verify_report else {
+            return;
+        };
+        let mut export = false;
+        ui.horizontal(|ui| {
+            let color = if report.failed() == 0 { Color32::GREEN } else { Color32::RED };
+            ui.colored_label(color, format!("通过 {}，失败 {}", report.passed(), report.failed()));
+            ui.label(format!("完成于 {}", report.finished_at.format("%Y-%m-%d %H:%M:%S")));
+            export = ui.button("导出报告").clicked();
+        });
+        ScrollArea::vertical().show(ui, |ui| {
+            egui::Grid::new("verify_report").striped(true).show(ui, |ui| {
+                ui.strong("结果");
+                ui.strong("密文");
+                ui.strong("目标");
+                ui.strong("说明");
+                ui.end_row();
+                for result in &report.results {
+                    match &result.outcome {
+                        Ok(len) => {
+                            ui.colored_label(Color32::GREEN, "通过");
+                            ui.monospace(result.preview());
+                            ui.label(&result.target);
+                            ui.label(format!("明文 {} 字节", len));
+                        }
+                        Err(e) => {
+                            ui.colored_label(Color32::RED, "失败");
+                            ui.monospace(result.preview());
+                            ui.label(&result.target);
+                            ui.label(e);
+                        }
+                    }
+                    ui.end_row();
+                }
+            });
+        });
+        
+        if export {
+            let path = format!("verification_{}.txt", Utc::now().format("%Y%m%d_%H%M%S"));
+            let entry = match std::fs::write(&path, report.to_text()) {
+                Ok(()) => LogEntry::new("数据校验", &format!("报告已导出到 {}", path)),
+                Err(e) => LogEntry::new("数据校验", &format!("导出报告失败 {}: {}", path, e)),
+            };
+            self.push_log(entry);
+        }
+    }
+    
+    /// 渲染Docker主机管理标签页
+    fn render_docker_hosts_tab(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Docker主机");
+        ui.label("配置了Docker运行参数的中间层和后端可以指定所在主机，启动、停止与状态同步都通过该主机的Docker守护进程进行。");
+        ui.label("Podman主机需先运行 `podman system service` 提供兼容API，rootless模式可直接使用本机用户的套接字。");
+        ui.separator();
+        
+        if let Some((host_id, result)) = self.docker_service.poll_test() {
+            self.docker_host_tests.insert(host_id, result.map_err(|e| format!("{:#}", e)));
+        }
+        let testing = self.docker_service.is_testing();
+        if testing {
+            ui.ctx().request_repaint_after(Duration::from_millis(200));
+        }
+        
+        let local = DockerHost {
+            id: String::new(),
+            ..DockerHost::new("本机", DockerConnection::Local, "")
+        };
+        let hosts = self.docker_service.get_hosts();
+        let mut test = None;
+        let mut edit = None;
+        let mut delete = None;
+        egui::Grid::new("docker_hosts").striped(true).show(ui, |ui| {
+            ui.strong("名称");
+            ui.strong("引擎");
+            ui.strong("连接");
+            ui.strong("月费用");
+            ui.strong("测试结果");
+            ui.strong("");
+            ui.end_row();
+            for host in std::iter::once(&local).chain(hosts.iter()) {
+                ui.label(&host.name);
+                ui.label(host.engine.label());
+                ui.label(host.url());
+                if host.monthly_cost > 0.0 {
+                    ui.label(self.cost_settings.format(host.monthly_cost));
+                } else {
+                    ui.weak("-");
+                }
+                match self.docker_host_tests.get(&host.id) {
+                    Some(Ok(version)) => ui.colored_label(Color32::GREEN, version),
+                    Some(Err(e)) => ui.colored_label(Color32::RED, e),
+                    None => ui.label("未测试"),
+                };
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(!testing, egui::Button::new("测试")).clicked() {
+                        test = Some(host.clone());
+                    }
+                    if !host.id.is_empty() {
+                        if ui.button("编辑").clicked() {
+                            edit = Some(host.clone());
+                        }
+                        if ui.button("删除").clicked() {
+                            delete = Some(host.id.clone());
+                        }
+                    }
+                });
+                ui.end_row();
+            }
+        });
+        if let Some(host) = test {
+            self.docker_host_tests.remove(&host.id);
+            self.docker_service.test_host(host);
+        }
+        if let Some(host) = edit {
+            self.docker_host_form = host;
+        }
+        if let Some(host_id) = delete {
+            self.report_error(self.docker_service.delete_host(&host_id));
+            self.docker_host_tests.remove(&host_id);
+        }
+        
+        ui.separator();
+        let editing = hosts.iter().any(|h| h.id == self.docker_host_form.id);
+        ui.strong(if editing { "编辑主机" } else { "添加主机" });
+        let form = &mut self.docker_host_form;
+        egui::Grid::new("docker_host_form").num_columns(2).show(ui, |ui| {
+            ui.label("名称:");
+            ui.text_edit_singleline(&mut form.name);
+            ui.end_row();
+            ui.label("容器引擎:");
+            ui.horizontal(|ui| {
+                for engine in ContainerEngine::ALL {
+                    ui.radio_value(&mut form.engine, engine, engine.label());
+                }
+            });
+            ui.end_row();
+            if !form.engine.connections().contains(&form.connection) {
+                form.connection = DockerConnection::Tcp;
+            }
+            ui.label("连接方式:");
+            ui.horizontal(|ui| {
+                for &connection in form.engine.connections() {
+                    ui.radio_value(&mut form.connection, connection, connection.label());
+                }
+            });
+            ui.end_row();
+            ui.label("地址:");
+            let hint = match form.connection {
+                DockerConnection::Ssh => "用户@主机[:端口]",
+                DockerConnection::Local => "API套接字，留空使用默认套接字",
+                DockerConnection::Tcp => "主机:端口",
+            };
+            ui.add(egui::TextEdit::singleline(&mut form.address).hint_text(hint));
+            ui.end_row();
+            if form.connection == DockerConnection::Ssh {
+                ui.label("SSH私钥:");
+                ui.add(egui::TextEdit::singleline(&mut form.ssh_key).hint_text("留空使用默认密钥"));
+                ui.end_row();
+            }
+            ui.label("月费用:");
+            ui.add(egui::DragValue::new(&mut form.monthly_cost).clamp_range(0.0..=f64::MAX).speed(1.0))
+                .on_hover_text("主机的月费用由其上的所有实例平均分摊，为0时实例按角色计费");
+            ui.end_row();
+        });
+        let valid = !form.name.trim().is_empty() && (form.connection == DockerConnection::Local || !form.address.trim().is_empty());
+        ui.horizontal(|ui| {
+            if ui.add_enabled(valid, egui::Button::new(if editing { "保存" } else { "添加" })).clicked() {
+                let host = self.docker_host_form.clone();
+                let result = if editing {
+                    self.docker_service.update_host(host)
+                } else {
+                    self.docker_service.add_host(host)
+                };
+                if result.is_ok() {
+                    self.docker_host_form = DockerHost::new("", DockerConnection::Tcp, "");
+                }
+                self.report_error(result);
+            }
+            if editing && ui.button("取消").clicked() {
+                self.docker_host_form = DockerHost::new("", DockerConnection::Tcp, "");
+            }
+        });
+        
+        ui.separator();
+        self.render_reconciliation(ui);
+    }
+    
+    /// 渲染容器对账面板：列出主机上不对应配置的孤儿容器与配置中缺失容器的漂移，可纳管、删除或重新启动
+    fn render_reconciliation(&mut self, ui: &mut egui::Ui) {
+        if let Some(report) = self.docker_service.poll_scan() {
+            self.push_log(LogEntry::new("Docker", &format!(
+                "对账完成：{} 个孤儿容器，{} 个漂移",
+                report.orphans.len(),
+                report.drifts.len(),
+            )));
+            self.reconcile_report = Some(report);
+        }
+        let scanning = self.docker_service.is_scanning();
+        if scanning {
+            ui.ctx().request_repaint_after(Duration::from_millis(200));
+        }
+        
+        ui.strong("容器对账");
+        ui.label(format!(
+            "列出所有主机上带有 {} 标签的容器并与配置对比：不对应配置的为孤儿容器，配置为运行中但容器不存在的为漂移。",
+            docker::MANAGED_LABEL,
+        ));
+        ui.horizontal(|ui| {
+            if ui.add_enabled(!scanning, egui::Button::new("扫描")).clicked() {
+                self.docker_service.scan();
+            }
+            if scanning {
+                ui.spinner();
+            }
+        });
+        let Some(report) = &self.reconcile_report else {
+            return;
+        };
+        ui.label(format!("扫描于 {}", report.scanned_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S")));
+        for (host, result) in &report.hosts {
+            match result {
+                Ok(count) => ui.label(format!("{}: {} 个容器", host, count)),
+                Err(e) => ui.colored_label(Color32::RED, format!("{}: {}", host, e)),
+            };
+        }
+        
+        let mut adopt = None;
+        let mut remove = None;
+        ui.label(RichText::new(format!("孤儿容器 ({})", report.orphans.len())).strong());
+        if report.orphans.iter().any(Orphan::adoptable) {
+            let targets: Vec<((String, Option<String>), String)> = self.business_groups
+                .iter()
+                .flat_map(|g| {
+                    std::iter::once(((g.id.clone(), None), format!("{}（直属后端）", g.name)))
+                        .chain(g.middlewares.iter().map(|m| ((g.id.clone(), Some(m.id.clone())), format!("{} / {}", g.name, m.name))))
+                })
+                .collect();
+            if self.adopt_target.as_ref().is_none_or(|t| !targets.iter().any(|(id, _)| id == t)) {
+                self.adopt_target = targets.first().map(|(id, _)| id.clone());
+            }
+            ui.horizontal(|ui| {
+                ui.label("纳管到:");
+                let selected = targets
+                    .iter()
+                    .find(|(id, _)| Some(id) == self.adopt_target.as_ref())
+                    .map_or("无业务组", |(_, label)| label.as_str());
+                egui::ComboBox::from_id_source("adopt_target").selected_text(selected).show_ui(ui, |ui| {
+                    for (id, label) in &targets {
+                        ui.selectable_value(&mut self.adopt_target, Some(id.clone()), label);
+                    }
+                });
+            });
+            ui.label("纳管时运行参数只还原名称、镜像与端口映射，环境变量、卷等需在容器定义中补充，否则重新启动后会丢失。");
+        }
+        egui::Grid::new("reconcile_orphans").striped(true).show(ui, |ui| {
+            ui.strong("主机");
+            ui.strong("容器");
+            ui.strong("镜像");
+            ui.strong("状态");
+            ui.strong("原因");
+            ui.strong("");
+            ui.end_row();
+            for (index, orphan) in report.orphans.iter().enumerate() {
+                ui.label(&orphan.host_name);
+                ui.label(&orphan.container.name);
+                ui.label(&orphan.container.image);
+                ui.label(&orphan.container.status);
+                ui.label(orphan.reason.label());
+                ui.horizontal(|ui| {
+                    if orphan.adoptable() && ui.add_enabled(self.adopt_target.is_some(), egui::Button::new("纳管")).clicked() {
+                        adopt = Some(index);
+                    }
+                    if ui.add_enabled(!self.container_action_service.is_busy(&orphan.container.name), egui::Button::new("删除容器")).clicked() {
+                        remove = Some(index);
+                    }
+                });
+                ui.end_row();
+            }
+        });
+        
+        let mut restart = None;
+        let mut forget = None;
+        ui.label(RichText::new(format!("漂移 ({})", report.drifts.len())).strong());
+        egui::Grid::new("reconcile_drifts").striped(true).show(ui, |ui| {
+            ui.strong("类型");
+            ui.strong("名称");
+            ui.strong("主机");
+            ui.strong("配置状态");
+            ui.strong("缺失的容器");
+            ui.strong("");
+            ui.end_row();
+            for (index, drift) in report.drifts.iter().enumerate() {
+                ui.label(drift.kind.label());
+                ui.label(&drift.name);
+                ui.label(&drift.host_name);
+                ui.label(Self::get_container_status_text(&drift.status));
+                ui.label(drift.missing.join(", "));
+                ui.horizontal(|ui| {
+                    if ui.button("重新启动").clicked() {
+                        restart = Some(index);
+                    }
+                    if ui.button("从配置删除").clicked() {
+                        forget = Some(index);
+                    }
+                });
+                ui.end_row();
+            }
+        });
+        
+        if let Some(index) = adopt {
+            self.adopt_orphan(index);
+        }
+        if let Some(index) = remove {
+            self.remove_orphan(index);
+        }
+        if let Some(index) = restart {
+            self.restart_drift(index);
+        }
+        if let Some(index) = forget {
+            self.forget_drift(index);
+        }
+    }
+    
+    /// 将孤儿容器纳管到选定的业务组或中间层
+    fn adopt_orphan(&mut self, index: usize) {
+        let (Some(report), Some((group_id, middleware_id))) = (&self.reconcile_report, self.adopt_target.clone()) else {
+            return;
+        };
+        let Some(orphan) = report.orphans.get(index).cloned() else {
+            return;
+        };
+        match self.backend_service.adopt_orphan(&GroupId::from(&group_id), middleware_id.as_deref().map(MiddlewareId::from).as_ref(), &orphan) {
+            Ok(backend) => {
+                let action = format!("纳管{}上的容器 {} 为后端", orphan.host_name, orphan.container.name);
+                self.push_log(LogEntry::new("Docker", &action));
+                self.record_audit(&action, Some(EntityKind::Backend), Some(&backend.id));
+                self.drop_orphan(index);
+                self.load_business_groups();
+            }
+            Err(e) => self.push_log(LogEntry::new("Docker", &format!("纳管容器 {} 失败: {:#}", orphan.container.name, e))),
+        }
+    }
+    
+    /// 在后台停止并删除孤儿容器，完成后由 [`Self::finish_remove_orphan`] 处理结果
+    fn remove_orphan(&mut self, index: usize) {
+        let Some(orphan) = self.reconcile_report.as_ref().and_then(|r| r.orphans.get(index)).cloned() else {
+            return;
+        };
+        self.container_action_service.submit(ContainerAction::RemoveOrphan { orphan: Box::new(orphan) });
+    }
+
+    /// 孤儿容器删除完成；列表可能已重新扫描，按主机与容器名称而非位置移除
+    fn finish_remove_orphan(&mut self, orphan: &Orphan, result: anyhow::Result<()>) {
+        match result {
+            Ok(()) => {
+                let action = format!("删除{}上的孤儿容器 {}", orphan.host_name, orphan.container.name);
+                self.push_log(LogEntry::new("Docker", &action));
+                self.record_audit(&action, None, None);
+                if let Some(report) = &mut self.reconcile_report {
+                    report.orphans.retain(|o| o.host_id != orphan.host_id || o.container.name != orphan.container.name);
+                }
+            }
+            Err(e) => self.push_log(LogEntry::new("Docker", &format!("删除容器 {} 失败: {:#}", orphan.container.name, e))),
+        }
+    }
+    
+    fn drop_orphan(&mut self, index: usize) {
+        if let Some(report) = &mut self.reconcile_report
+            && index < report.orphans.len()
+        {
+            report.orphans.remove(index);
+        }
+    }
+    
+    fn take_drift(&mut self, index: usize) -> Option<Drift> {
+        let report = self.reconcile_report.as_mut()?;
+        (index < report.drifts.len()).then(|| report.drifts.remove(index))
+    }
+    
+    /// 按配置重新创建漂移的容器
+    fn restart_drift(&mut self, index: usize) {
+        let Some(drift) = self.take_drift(index) else {
+            return;
+        };
+        match drift.kind {
+            EntityKind::Middleware => {
+                let exists = self.business_groups
+                    .iter()
+                    .flat_map(|g| g.middlewares.iter())
+                    .any(|m| m.id == drift.id);
+                if !exists {
+                    return;
+                }
+                self.container_action_service.submit(ContainerAction::StartMiddleware { group_id: drift.group_id, middleware_id: drift.id });
+            }
+            _ => {
+                self.container_action_service.submit(ContainerAction::StartBackend {
+                    group_id: drift.group_id,
+                    middleware_id: drift.middleware_id,
+                    backend_id: drift.id,
+                });
+            }
+        }
+    }
+    
+    /// 从配置中删除漂移的容器
+    fn forget_drift(&mut self, index: usize) {
+        let Some(drift) = self.take_drift(index) else {
+            return;
+        };
+        let result = match drift.kind {
+            EntityKind::Middleware => self.middleware_service.delete_middleware(&GroupId::from(&drift.group_id), &MiddlewareId::from(&drift.id)),
+            _ => self.backend_service.delete_backend(&GroupId::from(&drift.group_id), drift.middleware_id.as_deref().map(MiddlewareId::from).as_ref(), &BackendId::from(&drift.id)),
+        };
+        match result {
+            Ok(()) => {
+                let action = format!("从配置中删除{} {}（{}上的容器已不存在）", drift.kind.label(), drift.name, drift.host_name);
+                self.push_log(LogEntry::new("Docker", &action));
+                self.record_audit(&action, Some(drift.kind), Some(&drift.id));
+                self.load_business_groups();
+            }
+            Err(e) => self.push_log(LogEntry::new("Docker", &format!("删除{} {} 失败: {:#}", drift.kind.label(), drift.name, e))),
+        }
+    }
+    
+    /// 渲染网络配置管理标签页
+    fn render_network_tab(&mut self, ui: &mut egui::Ui) {
+        ui.heading("网络配置");
+        ui.label("每个业务组可以指定一组网络配置，该组下中间层与后端的接口请求都使用其中的代理、DNS、TLS与超时设置。");
+        ui.separator();
+        
+        let profiles = self.network_service.get_profiles();
+        let mut edit = None;
+        let mut delete = None;
+        egui::Grid::new("network_profiles").striped(true).show(ui, |ui| {
+            ui.strong("名称");
+            ui.strong("代理");
+            ui.strong("DNS覆盖");
+            ui.strong("TLS");
+            ui.strong("使用的业务组");
+            ui.strong("");
+            ui.end_row();
+            for profile in &profiles {
+                ui.label(&profile.name);
+                ui.label(if profile.proxy.is_empty() { "无" } else { &profile.proxy });
+                ui.label(profile.dns_overrides.len().to_string());
+                let tls = if profile.accept_invalid_certs {
+                    "跳过校验"
+                } else if profile.ca_cert_path.is_empty() {
+                    "系统证书"
+                } else {
+                    "自定义CA"
+                };
+                ui.label(tls);
+                let groups: Vec<&str> = self.business_groups
+                    .iter()
+                    .filter(|g| g.network_profile_id.as_deref() == Some(profile.id.as_str()))
+                    .map(|g| g.name.as_str())
+                    .collect();
+                ui.label(groups.join(", "));
+                ui.horizontal(|ui| {
+                    if ui.button("编辑").clicked() {
+                        edit = Some(profile.clone());
+                    }
+                    if ui.button("删除").clicked() {
+                        delete = Some(profile.id.clone());
+                    }
+                });
+                ui.end_row();
+            }
+        });
+        if profiles.is_empty() {
+            ui.label("暂无网络配置，所有请求使用默认网络设置");
+        }
+        if let Some(profile) = edit {
+            self.network_profile_form = profile;
+        }
+        if let Some(profile_id) = delete {
+            self.report_error(self.network_service.delete_profile(&profile_id));
+        }
+        
+        ui.separator();
+        let editing = profiles.iter().any(|p| p.id == self.network_profile_form.id);
+        ui.strong(if editing { "编辑网络配置" } else { "添加网络配置" });
+        let form = &mut self.network_profile_form;
+        egui::Grid::new("network_profile_form").num_columns(2).show(ui, |ui| {
+            ui.label("名称:");
+            ui.text_edit_singleline(&mut form.name);
+            ui.end_row();
+            ui.label("代理:");
+            ui.add(egui::TextEdit::singleline(&mut form.proxy).hint_text("http://proxy:3128，留空不使用代理"));
+            ui.end_row();
+            ui.label("不经代理:");
+            ui.add(egui::TextEdit::singleline(&mut form.no_proxy).hint_text("localhost,.internal"));
+            ui.end_row();
+            ui.label("CA证书:");
+            ui.add(egui::TextEdit::singleline(&mut form.ca_cert_path).hint_text("PEM文件路径，留空使用系统证书"));
+            ui.end_row();
+            ui.label("");
+            ui.checkbox(&mut form.accept_invalid_certs, "跳过证书校验（仅限实验环境）");
+            ui.end_row();
+            ui.label("连接超时(ms):");
+            ui.add(egui::DragValue::new(&mut form.connect_timeout_ms).speed(100).clamp_range(0..=600_000));
+            ui.end_row();
+            ui.label("请求超时(ms):");
+            ui.add(egui::DragValue::new(&mut form.request_timeout_ms).speed(100).clamp_range(0..=600_000));
+            ui.end_row();
+            ui.label("DNS覆盖:");
+            ui.vertical(|ui| {
+                let mut remove = None;
+                for (index, entry) in form.dns_overrides.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.add(egui::TextEdit::singleline(&mut entry.host).hint_text("主机名").desired_width(160.0));
+                        ui.label("→");
+                        ui.add(egui::TextEdit::singleline(&mut entry.address).hint_text("IP地址").desired_width(120.0));
+                        if ui.small_button("删除").clicked() {
+                            remove = Some(index);
+                        }
+                    });
+                }
+                if let Some(index) = remove {
+                    form.dns_overrides.remove(index);
+                }
+                if ui.small_button("添加").clicked() {
+                    form.dns_overrides.push(DnsOverride { host: String::new(), address: String::new() });
+                }
+            });
+            ui.end_row();
+        });
+        self.render_hosts_import(ui);
+        let form = &self.network_profile_form;
+        
+        let invalid_dns = form.dns_overrides
+            .iter()
+            .find(|d| d.host.trim().is_empty() || d.address.trim().parse::<std::net::IpAddr>().is_err());
+        if let Some(entry) = invalid_dns {
+            ui.colored_label(Color32::RED, format!("DNS覆盖无效: {} → {}", entry.host, entry.address));
+        }
+        let valid = !form.name.trim().is_empty() && invalid_dns.is_none();
+        ui.horizontal(|ui| {
+            if ui.add_enabled(valid, egui::Button::new(if editing { "保存" } else { "添加" })).clicked() {
+                let profile = self.network_profile_form.clone();
+                let result = if editing {
+                    self.network_service.update_profile(profile)
+                } else {
+                    self.network_service.add_profile(profile)
+                };
+                if result.is_ok() {
+                    self.network_profile_form = NetworkProfile::new("");
+                }
+                self.report_error(result);
+            }
+            if editing && ui.button("取消").clicked() {
+                self.network_profile_form = NetworkProfile::new("");
+            }
+        });
+    }
+    
+    /// 渲染hosts格式的解析覆盖导入导出，便于从跳板机沿用生产主机名而无需修改系统hosts文件
+    fn render_hosts_import(&mut self, ui: &mut egui::Ui) {
+        CollapsingHeader::new("hosts格式导入导出").show(ui, |ui| {
+            ui.label("每行为IP地址后跟一个或多个主机名，与系统hosts文件格式相同；已存在的主机名会被替换。");
+            ui.add(
+                egui::TextEdit::multiline(&mut self.network_hosts_text)
+                    .code_editor()
+                    .desired_rows(4)
+                    .hint_text("10.0.0.12  crypto-mw.prod.internal"),
+            );
+            let mut imported = None;
+            ui.horizontal(|ui| {
+                if ui.button("导入文本").clicked() {
+                    imported = Some(NetworkProfile::parse_hosts(&self.network_hosts_text));
+                }
+                if ui.button("导出为文本").clicked() {
+                    self.network_hosts_text = self.network_profile_form.hosts_text();
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label("文件:");
+                ui.add(egui::TextEdit::singleline(&mut self.network_hosts_path).hint_text("hosts文件路径"));
+                if ui.add_enabled(!self.network_hosts_path.trim().is_empty(), egui::Button::new("从文件导入")).clicked() {
+                    let path = self.network_hosts_path.trim();
+                    imported = Some(
+                        std::fs::read_to_string(path)
+                            .map_err(|e| anyhow::anyhow!("无法读取hosts文件 {}: {}", path, e))
+                            .and_then(|text| NetworkProfile::parse_hosts(&text)),
+                    );
+                }
+            });
+            match imported {
+                Some(Ok(entries)) => {
+                    let total = entries.len();
+                    let added = self.network_profile_form.merge_hosts(entries);
+                    self.push_log(LogEntry::new("网络配置", &format!("导入 {} 条解析覆盖，其中新增 {} 条", total, added)));
+                }
+                Some(Err(e)) => self.push_log(LogEntry::new("网络配置", &format!("导入解析覆盖失败: {:#}", e))),
+                None => {}
+            }
+        });
+    }
+    
+    /// 渲染容器控制台标签页
+    fn render_console_tab(&mut self, ui: &mut egui::Ui) {
+        ui.heading("容器控制台");
+        ui.label(format!(
+            "通过Docker exec在运行中的容器内执行命令（sh -c），每条命令独立执行。仅授权操作人可用，当前操作人: {}",
+            self.audit_service.actor()
+        ));
+        ui.separator();
+        
+        let targets = self.console_service.targets();
+        if targets.is_empty() {
+            ui.label("没有正在运行且由Docker管理的容器");
+            return;
+        }
+        if self.console_target.as_ref().is_none_or(|id| !targets.iter().any(|t| &t.id == id)) {
+            self.console_target = targets.first().map(|t| t.id.clone());
+        }
+        let Some(target) = self.console_target.as_ref().and_then(|id| targets.iter().find(|t| &t.id == id)).cloned() else {
+            return;
+        };
+        let running = self.console_service.running().map(str::to_string);
+        
+        ui.horizontal(|ui| {
+            ui.label("容器:");
+            egui::ComboBox::from_id_source("console_target")
+                .selected_text(&target.label)
+                .width(320.0)
+                .show_ui(ui, |ui| {
+                    for t in &targets {
+                        ui.selectable_value(&mut self.console_target, Some(t.id.clone()), &t.label);
+                    }
+                });
+            ui.label("工作目录:");
+            ui.add(egui::TextEdit::singleline(&mut self.console_working_dir).hint_text("镜像默认").desired_width(160.0));
+            if ui.button("清空输出").clicked() {
+                self.console_service.clear(&target.id);
+            }
+        });
+        
+        let input_height = ui.spacing().interact_size.y * 2.0;
+        ScrollArea::vertical()
+            .id_source("console_scrollback")
+            .max_height((ui.available_height() - input_height).max(120.0))
+            .stick_to_bottom(true)
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                for entry in self.console_service.scrollback(&target.id) {
+                    let text = egui::RichText::new(entry.text.trim_end_matches('\n')).monospace();
+                    let text = match entry.stream {
+                        ConsoleStream::Command => text.strong(),
+                        ConsoleStream::Output => text,
+                        ConsoleStream::Status => text.color(Color32::GRAY),
+                        ConsoleStream::Error => text.color(Color32::RED),
+                    };
+                    ui.label(text);
+                }
+            });
+        
+        ui.horizontal(|ui| {
+            ui.label("$");
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut self.console_input)
+                    .font(egui::TextStyle::Monospace)
+                    .desired_width(ui.available_width() - 120.0)
+                    .hint_text("输入命令后回车执行"),
+            );
+            if response.has_focus() && !self.console_history.is_empty() {
+                let (up, down) = ui.input(|i| (i.key_pressed(egui::Key::ArrowUp), i.key_pressed(egui::Key::ArrowDown)));
+                let last = self.console_history.len() - 1;
+                if up {
+                    let index = self.console_history_index.map_or(last, |i| i.saturating_sub(1));
+                    self.console_history_index = Some(index);
+                    self.console_input = self.console_history[index].clone();
+                } else if down {
+                    self.console_history_index = self.console_history_index.filter(|i| *i < last).map(|i| i + 1);
+                    self.console_input = self.console_history_index.map(|i| self.console_history[i].clone()).unwrap_or_default();
+                }
+            }
+            let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            
+            if let Some(running_id) = &running {
+                ui.spinner();
+                if ui.button("中止").clicked() {
+                    self.console_service.cancel();
+                }
+                if running_id != &target.id {
+                    ui.weak("其他容器正在执行");
+                }
+            } else if (ui.button("执行").clicked() || submitted) && !self.console_input.trim().is_empty() {
+                let command = self.console_input.trim().to_string();
+                let actor = self.audit_service.actor().to_string();
+                match self.console_service.run(&target.id, &command, &self.console_working_dir, &actor) {
+                    Ok(()) => {
+                        self.record_audit(&format!("容器控制台执行: {}", command), Some(target.kind), Some(&target.id));
+                        if self.console_history.last() != Some(&command) {
+                            self.console_history.push(command);
+                        }
+                        self.console_history_index = None;
+                        self.console_input.clear();
+                    }
+                    Err(e) => self.push_log(LogEntry::new("容器控制台", &format!("{:#}", e))),
+                }
+                response.request_focus();
+            }
+        });
+    }
+    
+    /// 渲染中间层迁移标签页
+    fn render_migration_tab(&mut self, ui: &mut egui::Ui) {
+        ui.heading("中间层迁移");
+        ui.label("将一个中间层的配置与全部后端迁移到另一个中间层，每一步都可以回滚。");
+        ui.separator();
+        
+        let middlewares: Vec<(String, MiddlewareContainer, String)> = self.business_groups
+            .iter()
+            .flat_map(|g| g.middlewares.iter().map(move |m| (g.id.clone(), m.clone(), format!("{} / {}", g.name, m.name))))
+            .collect();
+        let name_of = |id: &str| {
+            middlewares
+                .iter()
+                .find(|(_, m, _)| m.id == id)
+                .map(|(_, _, name)| name.clone())
+                .unwrap_or_else(|| id.to_string())
+        };
+        
+        let Some(migration) = self.migration_service.current().cloned() else {
+            egui::Grid::new("migration_select").show(ui, |ui| {
+                for (label, selected) in [("源中间层:", &mut self.migration_source), ("目标中间层:", &mut self.migration_target)] {
+                    ui.label(label);
+                    let text = selected.as_ref().map(|(_, id)| name_of(id)).unwrap_or_else(|| "选择中间层".to_string());
+                    egui::ComboBox::from_id_source(label).selected_text(text).width(240.0).show_ui(ui, |ui| {
+                        for (group_id, middleware, name) in &middlewares {
+                            ui.selectable_value(selected, Some((group_id.clone(), middleware.id.clone())), name);
+                        }
+                    });
+                    ui.end_row();
+                }
+            });
+            
+            let ready = self.migration_source.is_some() && self.migration_target.is_some();
+            if ui.add_enabled(ready, egui::Button::new("开始迁移向导")).clicked()
+                && let (Some((source_group, source)), Some((target_group, target))) = (&self.migration_source, &self.migration_target)
+            {
+                let migration = Migration::new(source_group, source, target_group, target);
+                let (trace_id, source_id) = (migration.trace_id.clone(), migration.source_id.clone());
+                let result = self.migration_service.begin(migration);
+                if result.is_ok() {
+                    let action = format!("开始迁移向导: {} → {}", name_of(&source_id), name_of(target));
+                    self.record_traced_audit(&action, Some(EntityKind::Middleware), Some(&source_id), &trace_id);
+                }
+                self.report_error(result);
+            }
+            return;
+        };
+        
+        ui.label(format!("{} → {}", name_of(&migration.source_id), name_of(&migration.target_id)));
+        ui.label(format!("追踪ID: {}", migration.trace_id));
+        ui.add_space(6.0);
+        
+        let next = migration.next_step();
+        for step in MigrationStep::ALL {
+            ui.horizontal(|ui| {
+                if migration.completed.contains(&step) {
+                    ui.colored_label(Color32::GREEN, "✔");
+                } else if Some(step) == next {
+                    ui.colored_label(Color32::YELLOW, "▶");
+                } else {
+                    ui.label("·");
+                }
+                ui.strong(step.label());
+                ui.label(step.description());
+            });
+        }
+        if let Some(error) = &migration.error {
+            ui.colored_label(Color32::RED, error);
+        }
+        
+        ui.add_space(6.0);
+        ui.horizontal(|ui| {
+            let running = self.migration_service.is_running();
+            if ui.add_enabled(!running && next.is_some(), egui::Button::new("执行下一步")).clicked() {
+                self.migration_service.run_next();
+            }
+            if ui.add_enabled(!running && migration.last_completed().is_some(), egui::Button::new("回滚上一步")).clicked() {
+                self.migration_service.rollback_last();
+            }
+            if ui.add_enabled(!running, egui::Button::new("结束向导")).clicked() {
+                self.migration_service.close();
+            }
+            if running {
+                ui.spinner();
+            } else if migration.is_finished() {
+                ui.colored_label(Color32::GREEN, "迁移已完成");
+            }
+        });
+    }
+    
+    /// 写入审计日志并刷新界面
+    fn record_audit(&mut self, action: &str, entity_kind: Option<EntityKind>, entity_id: Option<&str>) {
+        match self.audit_service.record(action, entity_kind, entity_id) {
+            Ok(()) => self.audit_entries = self.audit_service.get_entries().unwrap_or_default(),
+            Err(e) => tracing::error!("写入审计日志失败: {:#}", e),
+        }
+    }
+    
+    /// 写入带追踪ID的审计日志
+    fn record_traced_audit(&mut self, action: &str, entity_kind: Option<EntityKind>, entity_id: Option<&str>, trace_id: &str) {
+        match self.audit_service.record_traced(action, entity_kind, entity_id, trace_id) {
+            Ok(()) => self.audit_entries = self.audit_service.get_entries().unwrap_or_default(),
+            Err(e) => tracing::error!("写入审计日志失败: {:#}", e),
+        }
+    }
+    
+    /// 渲染中间层的远程命令面板
+    fn render_remote_commands(&mut self, ui: &mut egui::Ui, middleware: &MiddlewareContainer) {
+        if self.command_allowlist.is_empty() {
+            ui.label("命令白名单为空，请在配置页添加。");
+            return;
+        }
+        
+        ui.horizontal(|ui| {
+            let selected = self.command_allowlist
+                .iter()
+                .find(|c| Some(&c.id) == self.command_selected.as_ref())
+                .map(|c| c.name.clone())
+                .unwrap_or_else(|| "选择命令".to_string());
+            egui::ComboBox::from_id_source(("remote_command", &middleware.id)).selected_text(selected).show_ui(ui, |ui| {
+                for command in &self.command_allowlist {
+                    ui.selectable_value(&mut self.command_selected, Some(command.id.clone()), &command.name)
+                        .on_hover_text(&command.command);
+                }
+            });
+            
+            let running = self.command_service.is_running();
+            if ui.add_enabled(!running && self.command_selected.is_some(), egui::Button::new("执行")).clicked()
+                && let Some(command_id) = self.command_selected.clone()
+            {
+                let actor = self.audit_service.actor().to_string();
+                let trace_id = api::new_trace_id();
+                match self.command_service.run(middleware, &command_id, &actor, &trace_id) {
+                    Ok(command) => {
+                        self.command_output = Some((middleware.id.clone(), "执行中...".to_string()));
+                        let action = format!("在 {} 上发起远程命令: {}", middleware.name, command.command);
+                        self.record_traced_audit(&action, Some(EntityKind::Middleware), Some(&middleware.id), &trace_id);
+                    }
+                    Err(e) => {
+                        self.push_log(LogEntry::new(&middleware.name, &format!("{:#}", e)));
+                        let action = format!("在 {} 上执行远程命令被拒绝: {:#}", middleware.name, e);
+                        self.record_audit(&action, Some(EntityKind::Middleware), Some(&middleware.id));
+                    }
+                }
+            }
+            if running {
+                ui.spinner();
+            }
+        });
+        
+        if let Some((middleware_id, output)) = &self.command_output
+            && *middleware_id == middleware.id
+        {
+            let mut output = output.clone();
+            ui.add(egui::TextEdit::multiline(&mut output).code_editor().interactive(false).desired_width(f32::INFINITY));
+        }
+    }
+    
+    /// 收取远程命令执行结果并写入审计日志
+    fn poll_remote_command(&mut self) {
+        let Some(run) = self.command_service.poll() else {
+            return;
+        };
+        let (summary, output) = match &run.result {
+            Ok(response) => (
+                format!("退出码 {}", response.exit_code),
+                format!("退出码: {}\n{}{}", response.exit_code, response.stdout, response.stderr),
+            ),
+            Err(e) => (format!("失败: {:#}", e), format!("执行失败: {:#}", e)),
+        };
+        let action = format!("在 {} 上执行远程命令 {}: {}", run.middleware_name, run.command.name, summary);
+        self.push_log(LogEntry::new(&run.middleware_name, &action));
+        self.record_audit(&action, Some(EntityKind::Middleware), Some(&run.middleware_id));
+        self.command_output = Some((run.middleware_id, output));
+    }
+    
+    /// 收取Agent操作结果，安装结果写入审计日志
+    fn poll_agent_runs(&mut self) {
+        let runs = self.agent_service.poll();
+        if runs.is_empty() {
+            return;
+        }
+        for run in runs {
+            let action = match &run.result {
+                Ok(message) => format!("{} {}: {}", run.action.label(), run.middleware_name, message),
+                Err(e) => format!("{} {} 失败: {:#}", run.action.label(), run.middleware_name, e),
+            };
+            self.push_log(LogEntry::new(&run.middleware_name, &action));
+            if run.action == AgentAction::Install {
+                self.record_audit(&action, Some(EntityKind::Middleware), Some(&run.middleware_id));
+            }
+        }
+        self.load_business_groups();
+    }
+    
+    /// 渲染中间层的Agent状态与安装、检查操作
+    fn render_agent_status(&mut self, ui: &mut egui::Ui, group_id: &str, middleware: &MiddlewareContainer) {
+        ui.horizontal(|ui| {
+            ui.label("Agent状态:");
+            ui.label(if middleware.agent_installed { "已安装" } else { "未安装" });
+            match &middleware.agent_health {
+                Some(HealthStatus::Healthy) => {
+                    ui.colored_label(Color32::GREEN, "响应正常");
+                }
+                Some(HealthStatus::Unhealthy) => {
+                    ui.colored_label(Color32::RED, "未响应");
+                }
+                _ => {}
+            }
+            if self.agent_service.is_busy(&MiddlewareId::from(&middleware.id)) {
+                ui.spinner();
+                return;
+            }
+            let install = if middleware.agent_installed { "重新安装" } else { "安装" };
+            if ui.small_button(install)
+                .on_hover_text("由Docker管理的中间层复制到容器内启动，其余经SSH复制到主机启动")
+                .clicked()
+            {
+                let result = self.agent_service.run(&GroupId::from(group_id), &MiddlewareId::from(&middleware.id), AgentAction::Install);
+                self.report_error(result);
+            }
+            if ui.small_button("检查").clicked() {
+                let result = self.agent_service.run(&GroupId::from(group_id), &MiddlewareId::from(&middleware.id), AgentAction::Check);
+                self.report_error(result);
+            }
+        });
+    }
+    
+    /// 渲染Agent部署设置
+    fn render_agent_settings(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Agent部署");
+        ui.label("安装时将本机的Agent程序复制到中间层并在后台启动，随后在中间层地址的Agent端口上确认响应。");
+        let settings = &mut self.agent_settings;
+        egui::Grid::new("agent_settings").num_columns(2).show(ui, |ui| {
+            ui.label("Agent程序:");
+            ui.text_edit_singleline(&mut settings.binary_path);
+            ui.end_row();
+            ui.label("安装目录:");
+            ui.text_edit_singleline(&mut settings.install_dir);
+            ui.end_row();
+            ui.label("监听端口:");
+            ui.add(egui::DragValue::new(&mut settings.port).clamp_range(1..=65535));
+            ui.end_row();
+            ui.label("启动参数:");
+            ui.add(egui::TextEdit::singleline(&mut settings.args).hint_text("{port} 替换为监听端口"));
+            ui.end_row();
+            ui.label("SSH用户:");
+            ui.text_edit_singleline(&mut settings.ssh_user);
+            ui.end_row();
+            ui.label("SSH端口:");
+            ui.add(egui::DragValue::new(&mut settings.ssh_port).clamp_range(1..=65535));
+            ui.end_row();
+            ui.label("SSH私钥:");
+            ui.add(egui::TextEdit::singleline(&mut settings.ssh_key).hint_text("为空时使用ssh默认配置"));
+            ui.end_row();
+        });
+        ui.horizontal(|ui| {
+            if ui.button("保存").clicked() {
+                let result = self.agent_service.set_settings(self.agent_settings.clone());
+                if result.is_ok() {
+                    self.record_audit("修改Agent部署设置", None, None);
+                }
+                self.report_error(result);
+            }
+            if ui.button("恢复默认").clicked() {
+                self.agent_settings = AgentSettings::default();
+            }
+        });
+    }
+    
+    /// 渲染远程命令白名单设置
+    fn render_command_allowlist(&mut self, ui: &mut egui::Ui) {
+        ui.heading("远程命令白名单");
+        ui.label(format!("当前操作人: {}", self.audit_service.actor()));
+        let editable = self.ui_profile.can_manage_roles();
+        if !editable {
+            ui.weak("只有完整视图可以修改白名单与授权操作人");
+        }
+        
+        let mut delete = None;
+        egui::Grid::new("command_allowlist").striped(true).show(ui, |ui| {
+            for command in &self.command_allowlist {
+                ui.label(&command.name);
+                ui.monospace(&command.command);
+                if editable && ui.small_button("删除").clicked() {
+                    delete = Some(command.id.clone());
+                }
+                ui.end_row();
+            }
+        });
+        if !editable {
+            return;
+        }
+        if let Some(command_id) = delete {
+            match self.command_service.delete_command(&command_id, self.audit_service.actor()) {
+                Ok(command) => self.record_audit(&format!("删除白名单命令: {}", command.command), None, None),
+                Err(e) => self.report_error(Err(e)),
+            }
+            self.command_allowlist = self.command_service.get_allowlist().unwrap_or_default();
+        }
+        
+        ui.horizontal(|ui| {
+            ui.label("名称:");
+            ui.text_edit_singleline(&mut self.new_command_name);
+            ui.label("命令:");
+            ui.text_edit_singleline(&mut self.new_command_text);
+            let valid = !self.new_command_name.trim().is_empty() && !self.new_command_text.trim().is_empty();
+            if ui.add_enabled(valid, egui::Button::new("添加")).clicked() {
+                let command = AllowedCommand::new(self.new_command_name.trim(), self.new_command_text.trim());
+                let action = format!("添加白名单命令: {}", command.command);
+                let result = self.command_service.add_command(command, self.audit_service.actor());
+                if result.is_ok() {
+                    self.record_audit(&action, None, None);
+                }
+                self.report_error(result);
+                self.new_command_name.clear();
+                self.new_command_text.clear();
+                self.command_allowlist = self.command_service.get_allowlist().unwrap_or_default();
+            }
+        });
+        
+        ui.horizontal(|ui| {
+            ui.label("授权操作人（逗号分隔）:");
+            ui.text_edit_singleline(&mut self.command_operators_text);
+            if ui.button("保存").clicked() {
+                let operators: Vec<String> = self.command_operators_text
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|o| !o.is_empty())
+                    .map(str::to_string)
+                    .collect();
+                let action = format!("设置远程命令授权操作人: {}", operators.join(", "));
+                let result = self.command_service.set_operators(operators, self.audit_service.actor());
+                if result.is_ok() {
+                    self.record_audit(&action, None, None);
+                }
+                self.report_error(result);
+            }
+        });
+    }
+    
+    /// 切换界面配置，不能超过当前操作人可使用的范围
+    fn switch_ui_profile(&mut self, profile: UiProfile) {
+        if !profile.within(self.ui_profile_limit) {
+            self.push_log(LogEntry::new("界面", &format!("当前操作人不能使用{}", profile.label())));
+            return;
+        }
+        self.ui_profile = profile;
+        if !self.current_tab.visible_in(profile) {
+            self.current_tab = AppTab::Monitor;
+        }
+        self.push_log(LogEntry::new("界面", &format!("已切换到{}", profile.label())));
+    }
+    
+    /// 渲染界面角色设置：为操作人分配界面配置，只有完整视图可以修改
+    fn render_ui_roles(&mut self, ui: &mut egui::Ui) {
+        ui.heading("界面角色");
+        ui.label(format!(
+            "当前操作人 {} 可使用{}，正在使用{}。",
+            self.audit_service.actor(),
+            self.ui_profile_limit.label(),
+            self.ui_profile.label(),
+        ));
+        let editable = self.ui_profile.can_manage_roles();
+        if !editable {
+            ui.weak("只有完整视图可以分配界面角色");
+        }
+        
+        let access = self.state_store.access();
+        if !access.groups.is_empty() {
+            ui.label(format!("只能管理业务组: {}，其他业务组只读。", self.group_names(&access.groups)));
+        }
+        
+        let mut unassign = None;
+        egui::Grid::new("ui_roles").striped(true).show(ui, |ui| {
+            for assignment in &self.ui_roles {
+                ui.label(&assignment.actor);
+                ui.label(assignment.profile.label()).on_hover_text(assignment.profile.description());
+                if assignment.groups.is_empty() {
+                    ui.label("全部业务组");
+                } else {
+                    ui.label(self.group_names(&assignment.groups));
+                }
+                if editable && ui.small_button("移除").clicked() {
+                    unassign = Some(assignment.actor.clone());
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(actor) = unassign {
+            let result = self.role_service.unassign(&actor, self.audit_service.actor());
+            if result.is_ok() {
+                self.record_audit(&format!("移除操作人 {} 的界面角色", actor), None, None);
+            }
+            self.report_error(result);
+            self.reload_ui_roles();
+        }
+        if !editable {
+            return;
+        }
+        
+        ui.horizontal(|ui| {
+            ui.label("操作人:");
+            ui.text_edit_singleline(&mut self.new_role_actor);
+            egui::ComboBox::from_id_source("new_role_profile")
+                .selected_text(self.new_role_profile.label())
+                .show_ui(ui, |ui| {
+                    for profile in UiProfile::ALL {
+                        ui.selectable_value(&mut self.new_role_profile, profile, profile.label())
+                            .on_hover_text(profile.description());
+                    }
+                });
+            let scope = match self.new_role_groups.len() {
+                0 => "全部业务组".to_string(),
+                n => format!("{} 个业务组", n),
+            };
+            ui.menu_button(scope, |ui| {
+                ui.weak("不选时可管理所有业务组，选中后其他业务组只读");
+                for group in &self.business_groups {
+                    let mut selected = self.new_role_groups.contains(&group.id);
+                    if ui.checkbox(&mut selected, &group.name).changed() {
+                        if selected {
+                            self.new_role_groups.push(group.id.clone());
+                        } else {
+                            self.new_role_groups.retain(|id| id != &group.id);
+                        }
+                    }
+                }
+            });
+            if ui.add_enabled(!self.new_role_actor.trim().is_empty(), egui::Button::new("分配")).clicked() {
+                let actor = self.new_role_actor.trim().to_string();
+                let scope = if self.new_role_groups.is_empty() {
+                    String::new()
+                } else {
+                    format!("，限业务组 {}", self.group_names(&self.new_role_groups))
+                };
+                let result = self.role_service.assign(&actor, self.new_role_profile, self.new_role_groups.clone(), self.audit_service.actor());
+                if result.is_ok() {
+                    self.record_audit(&format!("为操作人 {} 分配{}{}", actor, self.new_role_profile.label(), scope), None, None);
+                    self.new_role_actor.clear();
+                    self.new_role_groups.clear();
+                }
+                self.report_error(result);
+                self.reload_ui_roles();
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("未分配的操作人使用:");
+            let mut default = self.default_ui_profile;
+            egui::ComboBox::from_id_source("default_ui_profile")
+                .selected_text(default.label())
+                .show_ui(ui, |ui| {
+                    for profile in UiProfile::ALL {
+                        ui.selectable_value(&mut default, profile, profile.label());
+                    }
+                });
+            if default != self.default_ui_profile {
+                let result = self.role_service.set_default(default, self.audit_service.actor());
+                if result.is_ok() {
+                    self.record_audit(&format!("未分配界面角色的操作人改用{}", default.label()), None, None);
+                }
+                self.report_error(result);
+                self.reload_ui_roles();
+            }
+        });
+    }
+    
+    /// 按间隔更新会话心跳；会话被强制登出或超过最长时长时保存修改并退出
+    fn tick_session(&mut self, ctx: &egui::Context) {
+        if self.session_ended.is_some() || self.last_heartbeat.is_some_and(|t| t.elapsed() < SESSION_HEARTBEAT_INTERVAL) {
+            return;
+        }
+        self.last_heartbeat = Some(Instant::now());
+        let mut reason = match self.session_service.heartbeat(self.last_input) {
+            Ok(SessionCheck::Active) => None,
+            Ok(SessionCheck::Revoked(by)) => Some(format!("已被 {} 强制登出", by)),
+            Err(e) => {
+                tracing::warn!("更新会话心跳失败: {:#}", e);
+                None
+            }
+        };
+        let minutes = self.session_service.current().duration(Utc::now()).num_minutes();
+        if reason.is_none() && self.max_session_minutes > 0 && minutes >= self.max_session_minutes as i64 {
+            reason = Some(format!("已超过会话最长时长 {} 分钟", self.max_session_minutes));
+        }
+        let Some(reason) = reason else {
+            if self.ui_profile.can_manage_roles() {
+                self.sessions = self.session_service.list().unwrap_or_default();
+            }
+            return;
+        };
+        
+        self.push_log(LogEntry::new("会话", &format!("会话结束: {}", reason)));
+        self.record_audit(&format!("会话结束: {}", reason), None, None);
+        if let Err(e) = self.state_store.flush() {
+            self.push_log(LogEntry::new("配置", &format!("退出前保存失败: {:#}", e)));
+        }
+        self.session_ended = Some(reason);
+        self.exit_confirmed = true;
+        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+    }
+    
+    /// 渲染活动会话列表与会话最长时长，只有完整视图可以强制登出与修改时长
+    fn render_sessions(&mut self, ui: &mut egui::Ui) {
+        ui.heading("会话");
+        let editable = self.ui_profile.can_manage_roles();
+        if !editable {
+            ui.weak("只有完整视图可以查看与管理会话");
+            return;
+        }
+        ui.horizontal(|ui| {
+            ui.label("会话最长时长 (分钟):");
+            let response = ui.add(egui::DragValue::new(&mut self.max_session_minutes).clamp_range(0..=10080));
+            ui.weak("0为不限，超过后实例保存修改并退出");
+            if response.drag_stopped() || response.lost_focus() {
+                let minutes = self.max_session_minutes;
+                let result = self.config_manager.update_if(|config| {
+                    if config.max_session_minutes == minutes {
+                        return Ok(false);
+                    }
+                    config.max_session_minutes = minutes;
+                    Ok(true)
+                });
+                match result {
+                    Ok(true) => self.record_audit(&format!("会话最长时长改为 {} 分钟", minutes), None, None),
+                    Ok(false) => {}
+                    Err(e) => self.push_log(LogEntry::new("配置", &format!("保存会话最长时长失败: {:#}", e))),
+                }
+            }
+        });
+        
+        let now = Utc::now();
+        let current_id = self.session_service.current().id.clone();
+        let mut revoke = None;
+        egui::Grid::new("ui_sessions").striped(true).show(ui, |ui| {
+            ui.strong("操作人");
+            ui.strong("主机");
+            ui.strong("登录时间");
+            ui.strong("已登录");
+            ui.strong("空闲");
+            ui.label("");
+            ui.end_row();
+            for session in &self.sessions {
+                ui.label(&session.actor);
+                ui.label(&session.host);
+                ui.label(session.started_at.with_timezone(&chrono::Local).format("%m-%d %H:%M").to_string());
+                ui.label(Self::format_span(session.duration(now)));
+                ui.label(Self::format_span(session.idle(now)));
+                if session.id == current_id {
+                    ui.weak("当前会话");
+                } else if session.revoked_by.is_some() {
+                    ui.weak("正在登出");
+                } else if ui.small_button("强制登出").clicked() {
+                    revoke = Some(session.clone());
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(session) = revoke {
+            let result = self.session_service.revoke(&session.id, self.audit_service.actor());
+            if result.is_ok() {
+                self.record_audit(&format!("强制登出 {} 在 {} 上的会话", session.actor, session.host), None, None);
+                self.sessions = self.session_service.list().unwrap_or_default();
+            }
+            self.report_error(result);
+        }
+    }
+    
+    /// 渲染登录界面；密码须修改时先设置新密码再进入
+    fn render_login(&mut self, ctx: &egui::Context) {
+        let mut accepted = None;
+        egui::CentralPanel::default().show(ctx, |ui| {
+            let Some(form) = self.login.as_mut() else {
+                return;
+            };
+            ui.vertical_centered(|ui| {
+                ui.add_space(ui.available_height() / 4.0);
+                ui.heading("登录加密服务管理器");
+                ui.add_space(12.0);
+                egui::Grid::new("login_form").num_columns(2).show(ui, |ui| {
+                    ui.label("用户名:");
+                    ui.add_enabled(form.change_reason.is_none(), egui::TextEdit::singleline(&mut form.name));
+                    ui.end_row();
+                    ui.label("密码:");
+                    ui.add_enabled(form.change_reason.is_none(), egui::TextEdit::singleline(&mut form.password).password(true));
+                    ui.end_row();
+                    if form.change_reason.is_some() {
+                        ui.label("新密码:");
+                        ui.add(egui::TextEdit::singleline(&mut form.new_password).password(true));
+                        ui.end_row();
+                        ui.label("确认新密码:");
+                        ui.add(egui::TextEdit::singleline(&mut form.confirm).password(true));
+                        ui.end_row();
+                    }
+                });
+                
+                let submit = ui.input(|i| i.key_pressed(egui::Key::Enter));
+                match form.change_reason.clone() {
+                    None => {
+                        if ui.button("登录").clicked() || submit {
+                            match self.user_service.authenticate(form.name.trim(), &form.password) {
+                                Ok(LoginOutcome::Accepted) => accepted = Some(form.name.trim().to_string()),
+                                Ok(LoginOutcome::MustChange(reason)) => {
+                                    form.change_reason = Some(reason);
+                                    form.error = None;
+                                }
+                                Err(e) => {
+                                    form.error = Some(format!("{:#}", e));
+                                    form.password.clear();
+                                    let _ = self.audit_service.record_as(form.name.trim(), "登录失败", None, None);
+                                }
+                            }
+                        }
+                    }
+                    Some(reason) => {
+                        ui.colored_label(egui::Color32::from_rgb(220, 160, 0), reason);
+                        for violation in self.password_policy.violations(&form.new_password) {
+                            ui.weak(violation);
+                        }
+                        if ui.button("修改密码并登录").clicked() || submit {
+                            let result = if form.new_password != form.confirm {
+                                Err(anyhow::anyhow!("两次输入的新密码不一致"))
+                            } else {
+                                self.user_service.change_password(form.name.trim(), &form.password, &form.new_password)
+                            };
+                            match result {
+                                Ok(()) => {
+                                    let _ = self.audit_service.record_as(form.name.trim(), "修改本地用户密码", None, None);
+                                    accepted = Some(form.name.trim().to_string());
+                                }
+                                Err(e) => form.error = Some(format!("{:#}", e)),
+                            }
+                        }
+                    }
+                }
+                if let Some(error) = &form.error {
+                    ui.colored_label(egui::Color32::RED, error);
+                }
+            });
+        });
+        if let Some(name) = accepted {
+            self.login = None;
+            self.audit_service.set_actor(&name);
+            self.session_service.set_actor(&name);
+            self.reload_ui_roles();
+            self.record_audit("登录", None, None);
+        }
+    }
+    
+    /// 重新读取本地用户与密码策略
+    fn reload_local_users(&mut self) {
+        match self.user_service.get_users() {
+            Ok((users, policy)) => {
+                self.local_users = users;
+                self.password_policy = policy;
+            }
+            Err(e) => self.push_log(LogEntry::new("配置", &format!("读取本地用户失败: {:#}", e))),
+        }
+    }
+    
+    /// 渲染本地用户与密码策略，只有完整视图可以管理
+    fn render_local_users(&mut self, ui: &mut egui::Ui) {
+        ui.heading("本地用户");
+        if !self.ui_profile.can_manage_roles() {
+            ui.weak("只有完整视图可以管理本地用户");
+            return;
+        }
+        ui.weak("配置了本地用户后启动时须登录，用户名作为操作人参与界面角色分配与审计");
+        
+        ui.collapsing("密码策略", |ui| {
+            let policy = &mut self.password_policy;
+            ui.horizontal(|ui| {
+                ui.label("最小长度:");
+                ui.add(egui::DragValue::new(&mut policy.min_length).clamp_range(1..=128));
+                ui.label("有效天数:");
+                ui.add(egui::DragValue::new(&mut policy.expiry_days).clamp_range(0..=3650));
+                ui.weak("0为不过期");
+            });
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut policy.require_uppercase, "大写字母");
+                ui.checkbox(&mut policy.require_lowercase, "小写字母");
+                ui.checkbox(&mut policy.require_digit, "数字");
+                ui.checkbox(&mut policy.require_symbol, "符号");
+            });
+            if ui.button("保存策略").clicked() {
+                let result = self.user_service.set_policy(self.password_policy.clone(), self.audit_service.actor());
+                if result.is_ok() {
+                    self.record_audit("修改密码策略", None, None);
+                }
+                self.report_error(result);
+                self.reload_local_users();
+            }
+        });
+        
+        let now = Utc::now();
+        let actor = self.audit_service.actor().to_string();
+        let mut reset = None;
+        let mut delete = None;
+        egui::Grid::new("local_users").striped(true).show(ui, |ui| {
+            ui.strong("用户名");
+            ui.strong("密码修改时间");
+            ui.strong("状态");
+            ui.strong("摘要");
+            ui.label("");
+            ui.end_row();
+            for user in &self.local_users {
+                ui.label(&user.name);
+                ui.label(user.password_changed_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string());
+                if user.must_change {
+                    ui.colored_label(egui::Color32::from_rgb(220, 160, 0), "待修改密码");
+                } else if self.password_policy.is_expired(user.password_changed_at, now) {
+                    ui.colored_label(egui::Color32::from_rgb(220, 160, 0), "密码已过期");
+                } else {
+                    ui.label("正常");
+                }
+                ui.label(password::scheme(&user.password_hash).label());
+                ui.horizontal(|ui| {
+                    if ui.small_button("重置密码").clicked() {
+                        reset = Some(user.name.clone());
+                    }
+                    if ui.add_enabled(user.name != actor, egui::Button::new("删除").small())
+                        .on_disabled_hover_text("不能删除当前登录的用户")
+                        .clicked()
+                    {
+                        delete = Some(user.name.clone());
+                    }
+                });
+                ui.end_row();
+            }
+        });
+        if let Some(name) = reset {
+            self.password_reset = Some((name, String::new()));
+        }
+        if let Some(name) = delete {
+            let result = self.user_service.delete(&name, &actor);
+            if result.is_ok() {
+                self.record_audit(&format!("删除本地用户 {}", name), None, None);
+            }
+            self.report_error(result);
+            self.reload_local_users();
+        }
+        
+        let mut reset_done = false;
+        if let Some((name, temp)) = &mut self.password_reset {
+            let name = name.clone();
+            let mut confirmed = false;
+            ui.horizontal(|ui| {
+                ui.label(format!("{} 的临时密码:", name));
+                ui.add(egui::TextEdit::singleline(temp).password(true));
+                confirmed = ui.button("重置").clicked();
+                reset_done = ui.button("取消").clicked();
+            });
+            if confirmed {
+                let result = self.user_service.reset_password(&name, temp, &actor);
+                if result.is_ok() {
+                    reset_done = true;
+                    self.record_audit(&format!("重置本地用户 {} 的密码", name), None, None);
+                }
+                self.report_error(result);
+                self.reload_local_users();
+            }
+        }
+        if reset_done {
+            self.password_reset = None;
+        }
+        
+        ui.horizontal(|ui| {
+            ui.label("用户名:");
+            ui.text_edit_singleline(&mut self.new_user_name);
+            ui.label("临时密码:");
+            ui.add(egui::TextEdit::singleline(&mut self.new_user_password).password(true));
+            if ui.button("添加").clicked() {
+                let name = self.new_user_name.trim().to_string();
+                let result = self.user_service.create(&name, &self.new_user_password, &actor);
+                if result.is_ok() {
+                    self.record_audit(&format!("新建本地用户 {}", name), None, None);
+                    self.new_user_name.clear();
+                    self.new_user_password.clear();
+                }
+                self.report_error(result);
+                self.reload_local_users();
+            }
+        });
+        ui.weak("新建或重置密码的用户须在首次登录时设置新密码");
+    }
+    
+    /// 业务组ID对应的名称，已删除的显示ID
+    fn group_names(&self, group_ids: &[String]) -> String {
+        group_ids
+            .iter()
+            .map(|id| self.business_groups.iter().find(|g| &g.id == id).map_or(id.as_str(), |g| g.name.as_str()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+    
+    /// 重新读取界面角色，当前操作人的范围收窄时随之切换
+    fn reload_ui_roles(&mut self) {
+        let (roles, default) = match self.role_service.get_roles() {
+            Ok(roles) => roles,
+            Err(e) => {
+                self.push_log(LogEntry::new("配置", &format!("读取界面角色失败: {:#}", e)));
+                return;
+            }
+        };
+        self.ui_profile_limit = ui_profile_for(&roles, default, self.audit_service.actor());
+        self.state_store.set_access(GroupAccess::for_actor(&roles, default, self.audit_service.actor()));
+        self.ui_roles = roles;
+        self.default_ui_profile = default;
+        if !self.ui_profile.within(self.ui_profile_limit) {
+            self.switch_ui_profile(self.ui_profile_limit);
+        }
+    }
+    
+    /// Webhook操作目标的显示名称
+    fn webhook_target_name(&self, action: &WebhookAction) -> String {
+        let name = match action {
+            WebhookAction::RestartGroup { group_id } => self.business_groups
+                .iter()
+                .find(|g| &g.id == group_id)
+                .map(|g| g.name.clone()),
+            WebhookAction::PushConfig { middleware_id } => self.business_groups
+                .iter()
+                .flat_map(|g| g.middlewares.iter())
+                .find(|m| &m.id == middleware_id)
+                .map(|m| m.name.clone()),
+        };
+        name.unwrap_or_else(|| "（已删除）".to_string())
+    }
+    
+    /// 收取Webhook请求，校验通过的提交为后台任务，并全部写入审计日志
+    fn poll_webhooks(&mut self) {
+        let Some(server) = &self.webhook_server else {
+            return;
+        };
+        for event in server.poll() {
+            match event {
+                WebhookEvent::Triggered { hook, remote } => {
+                    let actor = format!("webhook:{}", hook.name);
+                    let (kind, entity) = match &hook.action {
+                        WebhookAction::RestartGroup { group_id } => (
+                            self.business_groups.iter().find(|g| &g.id == group_id).map(|g| JobKind::RestartGroup {
+                                group_id: g.id.clone(),
+                                group_name: g.name.clone(),
+                                mode: RestartMode::AllAtOnce,
+                            }),
+                            (EntityKind::Group, group_id.clone()),
+                        ),
+                        WebhookAction::PushConfig { middleware_id } => (
+                            self.business_groups
+                                .iter()
+                                .flat_map(|g| g.middlewares.iter())
+                                .find(|m| &m.id == middleware_id)
+                                .map(|m| JobKind::PushConfig {
+                                    middleware_id: m.id.clone(),
+                                    middleware_name: m.name.clone(),
+                                }),
+                            (EntityKind::Middleware, middleware_id.clone()),
+                        ),
+                    };
+                    // 以Webhook所有者而非界面当前操作人的权限判断与执行
+                    let owner = GroupAccess::for_actor(&self.ui_roles, self.default_ui_profile, &hook.owner);
+                    let permitted = self.webhook_service.target_group(&hook.action).is_some_and(|g| owner.can_manage(&g));
+                    let action = match kind {
+                        Some(kind) if permitted => {
+                            let action = format!("来自 {} 的Webhook触发: {}", remote, kind.describe());
+                            self.enqueue_job_as(kind, Some(&hook.owner));
+                            action
+                        }
+                        Some(_) => format!("来自 {} 的Webhook触发被拒绝: 所有者 {} 无权管理{}的目标", remote, hook.owner, hook.action.label()),
+                        None => format!("来自 {} 的Webhook触发失败: {}的目标不存在", remote, hook.action.label()),
+                    };
+                    self.push_log(LogEntry::new(&actor, &action));
+                    let result = self.audit_service.record_as(&actor, &action, Some(entity.0), Some(&entity.1));
+                    self.report_error(result);
+                }
+                WebhookEvent::Rejected { hook_id, remote, reason } => {
+                    let action = format!("拒绝来自 {} 的Webhook请求 {}: {}", remote, hook_id, reason);
+                    self.push_log(LogEntry::new("webhook", &action));
+                    let result = self.audit_service.record_as("webhook", &action, None, None);
+                    self.report_error(result);
+                }
+            }
+            self.audit_entries = self.audit_service.get_entries().unwrap_or_default();
+        }
+    }
+    
+    /// 渲染Webhook设置
+    fn render_webhooks(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Webhook");
+        ui.label(format!(
+            "外部系统向 http://<监听地址>{}<ID> 发送POST请求触发操作：请求头 {} 携带当前Unix时间戳（秒），\
+             {} 携带用密钥对“时间戳.请求体”计算的 sha256=<HMAC>。时间戳与本机相差超过5分钟或重复发送的请求会被拒绝。",
+            webhook::PATH_PREFIX,
+            webhook::TIMESTAMP_HEADER,
+            webhook::SIGNATURE_HEADER,
+        ));
+        
+        ui.horizontal(|ui| {
+            ui.label("监听地址:");
+            ui.add_enabled(self.webhook_server.is_none(), egui::TextEdit::singleline(&mut self.webhook_listen));
+            match &self.webhook_server {
+                Some(server) => {
+                    ui.colored_label(egui::Color32::GREEN, format!("监听中: {}", server.listen()));
+                    if ui.button("停止").clicked() {
+                        self.webhook_server = None;
+                        let result = self.webhook_service.set_listener(false, &self.webhook_listen);
+                        self.report_error(result);
+                        self.record_audit("停止Webhook监听", None, None);
+                    }
+                }
+                None => {
+                    if ui.button("启动").clicked() {
+                        let listen = self.webhook_listen.trim().to_string();
+                        match WebhookServer::start(&listen, self.config_manager.clone()) {
+                            Ok(server) => {
+                                self.webhook_server = Some(server);
+                                let result = self.webhook_service.set_listener(true, &listen);
+                                self.report_error(result);
+                                self.record_audit(&format!("启动Webhook监听: {}", listen), None, None);
+                            }
+                            Err(e) => self.push_log(LogEntry::new("webhook", &format!("{:#}", e))),
+                        }
+                    }
+                }
+            }
+        });
+        
+        let mut delete = None;
+        let mut toggle = None;
+        egui::Grid::new("webhooks").striped(true).show(ui, |ui| {
+            for hook in &self.webhooks {
+                let mut enabled = hook.enabled;
+                if ui.checkbox(&mut enabled, &hook.name).changed() {
+                    toggle = Some((hook.id.clone(), enabled));
+                }
+                ui.label(format!("{}: {}", hook.action.label(), self.webhook_target_name(&hook.action)));
+                ui.monospace(format!("{}{}", webhook::PATH_PREFIX, hook.id));
+                if ui.small_button("复制密钥").clicked() {
+                    ui.output_mut(|o| o.copied_text = hook.secret.clone());
+                }
+                if ui.small_button("复制测试命令").on_hover_text("以当前时间签名的curl命令，5分钟内有效且只能发送一次").clicked() {
+                    let timestamp = Utc::now().timestamp().to_string();
+                    let command = format!(
+                        "curl -X POST -H '{}: {}' -H '{}: {}' http://{}{}{}",
+                        webhook::TIMESTAMP_HEADER,
+                        timestamp,
+                        webhook::SIGNATURE_HEADER,
+                        webhook::sign(&hook.secret, &timestamp, b""),
+                        self.webhook_listen.trim(),
+                        webhook::PATH_PREFIX,
+                        hook.id,
+                    );
+                    ui.output_mut(|o| o.copied_text = command);
+                }
+                if ui.small_button("删除").clicked() {
+                    delete = Some(hook.clone());
+                }
+                ui.end_row();
+            }
+        });
+        if let Some((hook_id, enabled)) = toggle {
+            let result = self.webhook_service.set_webhook_enabled(&hook_id, enabled);
+            if result.is_ok() {
+                let action = format!("{}Webhook {}", if enabled { "启用" } else { "停用" }, hook_id);
+                self.record_audit(&action, None, None);
+            }
+            self.report_error(result);
+            self.webhooks = self.webhook_service.get_webhooks().unwrap_or_default();
+        }
+        if let Some(hook) = delete {
+            let result = self.webhook_service.delete_webhook(&hook.id);
+            if result.is_ok() {
+                self.record_audit(&format!("删除Webhook: {}", hook.name), None, None);
+            }
+            self.report_error(result);
+            self.webhooks = self.webhook_service.get_webhooks().unwrap_or_default();
+        }
+        
+        ui.horizontal(|ui| {
+            ui.label("名称:");
+            ui.text_edit_singleline(&mut self.new_webhook_name);
+            if ui.radio_value(&mut self.new_webhook_push_config, false, "重启业务组").changed()
+                | ui.radio_value(&mut self.new_webhook_push_config, true, "推送中间层配置").changed()
+            {
+                self.new_webhook_target = None;
+            }
+            
+            let targets: Vec<(String, String)> = if self.new_webhook_push_config {
+                self.business_groups
+                    .iter()
+                    .flat_map(|g| g.middlewares.iter().map(move |m| (m.id.clone(), format!("{} / {}", g.name, m.name))))
+                    .collect()
+            } else {
+                self.business_groups.iter().map(|g| (g.id.clone(), g.name.clone())).collect()
+            };
+            let selected = targets
+                .iter()
+                .find(|(id, _)| Some(id) == self.new_webhook_target.as_ref())
+                .map(|(_, name)| name.clone())
+                .unwrap_or_else(|| "选择目标".to_string());
+            egui::ComboBox::from_id_source("new_webhook_target").selected_text(selected).show_ui(ui, |ui| {
+                for (id, name) in &targets {
+                    ui.selectable_value(&mut self.new_webhook_target, Some(id.clone()), name);
+                }
+            });
+            
+            let valid = !self.new_webhook_name.trim().is_empty() && self.new_webhook_target.is_some();
+            if ui.add_enabled(valid, egui::Button::new("添加")).clicked()
+                && let Some(target) = self.new_webhook_target.take()
+            {
+                let action = if self.new_webhook_push_config {
+                    WebhookAction::PushConfig { middleware_id: target }
+                } else {
+                    WebhookAction::RestartGroup { group_id: target }
+                };
+                let hook = Webhook::new(self.new_webhook_name.trim(), action);
+                let audit = format!("添加Webhook: {}（{}）", hook.name, hook.action.label());
+                let result = self.webhook_service.add_webhook(hook);
+                if result.is_ok() {
+                    self.record_audit(&audit, None, None);
+                }
+                self.report_error(result);
+                self.new_webhook_name.clear();
+                self.webhooks = self.webhook_service.get_webhooks().unwrap_or_default();
+            }
+        });
+    }
+    
+    /// 渲染systemd单元文件生成面板
+    fn render_systemd_units(&mut self, ui: &mut egui::Ui) {
+        ui.heading("systemd 单元文件");
+        
+        ui.horizontal(|ui| {
+            ui.label("业务组:");
+            let selected = self.business_groups
+                .iter()
+                .find(|g| Some(&g.id) == self.systemd_group_id.as_ref())
+                .map(|g| g.name.clone())
+                .unwrap_or_else(|| "选择业务组".to_string());
+            egui::ComboBox::from_id_source("systemd_group").selected_text(selected).show_ui(ui, |ui| {
+                for group in &self.business_groups {
+                    ui.selectable_value(&mut self.systemd_group_id, Some(group.id.clone()), &group.name);
+                }
+            });
+        });
+        
+        let options = &mut self.systemd_options;
+        egui::Grid::new("systemd_options").show(ui, |ui| {
+            ui.label("中间层可执行文件:");
+            ui.text_edit_singleline(&mut options.middleware_exec);
+            ui.end_row();
+            ui.label("后端可执行文件:");
+            ui.text_edit_singleline(&mut options.backend_exec);
+            ui.end_row();
+            ui.label("运行用户:");
+            ui.text_edit_singleline(&mut options.user);
+            ui.end_row();
+            ui.label("重启策略:");
+            ui.horizontal(|ui| {
+                for policy in RestartPolicy::ALL {
+                    ui.radio_value(&mut options.restart, policy, policy.value());
+                }
+                ui.label("间隔 (秒):");
+                ui.add(egui::DragValue::new(&mut options.restart_sec).clamp_range(0..=3600));
+            });
+            ui.end_row();
+        });
+        
+        let Some(group) = self.business_groups.iter().find(|g| Some(&g.id) == self.systemd_group_id.as_ref()).cloned() else {
+            return;
+        };
+        let units = systemd::group_units(&group, &self.systemd_options);
+        
+        let mut install = None;
+        ui.horizontal(|ui| {
+            if ui.button("导出到 systemd 目录").clicked() {
+                let result = std::fs::create_dir_all("systemd").and_then(|_| {
+                    units.iter().try_for_each(|unit| std::fs::write(format!("systemd/{}", unit.name), &unit.content))
+                });
+                let entry = match result {
+                    Ok(()) => LogEntry::new("systemd", &format!("已导出 {} 个单元文件到 systemd 目录", units.len())),
+                    Err(e) => LogEntry::new("systemd", &format!("导出单元文件失败: {}", e)),
+                };
+                self.push_log(entry);
+            }
+            for middleware in group.middlewares.iter().filter(|m| m.agent_installed) {
+                if ui.add_enabled(!self.command_service.is_running(), egui::Button::new(format!("通过Agent安装到 {}", middleware.name))).clicked() {
+                    install = Some(middleware.clone());
+                }
+            }
+        });
+        
+        for unit in &units {
+            CollapsingHeader::new(&unit.name).id_source(("systemd_unit", &unit.name)).show(ui, |ui| {
+                let mut content = unit.content.clone();
+                ui.add(egui::TextEdit::multiline(&mut content).code_editor().interactive(false).desired_width(f32::INFINITY));
+            });
+        }
+        
+        // 安装中间层及其下属后端的单元文件
+        if let Some(middleware) = install {
+            let mut units = vec![systemd::middleware_unit(&middleware, &self.systemd_options)];
+            units.extend(middleware.backend_containers.iter().map(|b| systemd::backend_unit(b, &self.systemd_options)));
+            let actor = self.audit_service.actor().to_string();
+            let trace_id = api::new_trace_id();
+            let action = match self.command_service.install_units(&middleware, &units, &actor, &trace_id) {
+                Ok(()) => format!("在 {} 上安装 {} 个systemd单元", middleware.name, units.len()),
+                Err(e) => {
+                    self.push_log(LogEntry::new(&middleware.name, &format!("{:#}", e)));
+                    format!("在 {} 上安装systemd单元被拒绝: {:#}", middleware.name, e)
+                }
+            };
+            self.record_traced_audit(&action, Some(EntityKind::Middleware), Some(&middleware.id), &trace_id);
+        }
+    }
+    
+    /// 渲染Terraform导出面板
+    fn render_terraform_export(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Terraform 导出");
+        
+        ui.horizontal(|ui| {
+            ui.label("业务组:");
+            let selected = self.business_groups
+                .iter()
+                .find(|g| Some(&g.id) == self.terraform_group_id.as_ref())
+                .map(|g| g.name.clone())
+                .unwrap_or_else(|| "选择业务组".to_string());
+            egui::ComboBox::from_id_source("terraform_group").selected_text(selected).show_ui(ui, |ui| {
+                for group in &self.business_groups {
+                    ui.selectable_value(&mut self.terraform_group_id, Some(group.id.clone()), &group.name);
+                }
+            });
+            ui.label("提供方:");
+            for provider in [TerraformProvider::Docker, TerraformProvider::Kubernetes] {
+                ui.radio_value(&mut self.terraform_provider, provider, provider.label());
+            }
+        });
+        
+        let Some(group) = self.business_groups.iter().find(|g| Some(&g.id) == self.terraform_group_id.as_ref()) else {
+            return;
+        };
+        let hcl = self.terraform_provider.render(group);
+        let group_name = group.name.clone();
+        
+        if ui.button("导出 .tf 文件").clicked() {
+            let path = format!("terraform_{}_{}.tf", self.terraform_provider.label().to_lowercase(), Utc::now().format("%Y%m%d_%H%M%S"));
+            let entry = match std::fs::write(&path, &hcl) {
+                Ok(()) => LogEntry::new("terraform", &format!("已导出业务组 {} 到 {}", group_name, path)),
+                Err(e) => LogEntry::new("terraform", &format!("导出Terraform失败: {}", e)),
+            };
+            self.push_log(entry);
+        }
+        
+        let mut preview = hcl;
+        ScrollArea::vertical().id_source("terraform_preview").max_height(300.0).show(ui, |ui| {
+            ui.add(egui::TextEdit::multiline(&mut preview).code_editor().interactive(false).desired_width(f32::INFINITY));
+        });
+    }
+    
+    /// 渲染Kubernetes导入面板
+    fn render_kubernetes_import(&mut self, ui: &mut egui::Ui) {
+        ui.heading("从 Kubernetes 导入");
+        ui.label(format!(
+            "发现带有 {} 标签（middleware / backend）的Deployment，按 {} 标签归入业务组，后端可用 {} 标签指定所属中间层。",
+            kubernetes::ROLE_LABEL,
+            kubernetes::GROUP_LABEL,
+            kubernetes::MIDDLEWARE_LABEL,
+        ));
+        
+        ui.horizontal(|ui| {
+            ui.label("命名空间:");
+            ui.text_edit_singleline(&mut self.kubernetes_namespace);
+            let discovering = self.kubernetes_service.is_discovering();
+            if ui.add_enabled(!discovering && !self.kubernetes_namespace.trim().is_empty(), egui::Button::new("发现")).clicked() {
+                let namespace = self.kubernetes_namespace.trim().to_string();
+                let result = self.kubernetes_service.set_namespace(&namespace);
+                self.report_error(result);
+                self.kubernetes_service.discover(&namespace);
+            }
+            if discovering {
+                ui.spinner();
+            }
+        });
+        
+        CollapsingHeader::new("发现后端的存活期限").show(ui, |ui| {
+            self.render_discovery_ttl(ui);
+        });
+        
+        let Some((namespace, workloads)) = &self.kubernetes_workloads else {
+            return;
+        };
+        if workloads.is_empty() {
+            ui.label(format!("命名空间 {} 中没有带标签的工作负载", namespace));
+            return;
+        }
+        
+        egui::Grid::new("kubernetes_workloads").striped(true).show(ui, |ui| {
+            ui.label("业务组");
+            ui.label("Deployment");
+            ui.label("角色");
+            ui.label("就绪");
+            ui.label("Pod");
+            ui.end_row();
+            for workload in workloads {
+                ui.label(&workload.group);
+                ui.label(&workload.deployment);
+                ui.label(workload.role.label());
+                ui.label(format!("{}/{}", workload.ready_replicas, workload.replicas));
+                ui.label(workload.pods.join(", "));
+                ui.end_row();
+            }
+        });
+        
+        if ui.button("导入为业务组").clicked() {
+            let (namespace, workloads) = (namespace.clone(), workloads.clone());
+            match self.kubernetes_service.import(&namespace, &workloads) {
+                Ok(count) => {
+                    let action = format!("从Kubernetes命名空间 {} 导入 {} 个工作负载", namespace, count);
+                    self.push_log(LogEntry::new("kubernetes", &action));
+                    if count > 0 {
+                        self.record_audit(&action, None, None);
+                    }
+                }
+                Err(e) => self.push_log(LogEntry::new("kubernetes", &format!("导入失败: {:#}", e))),
+            }
+        }
+    }
+    
+    /// 渲染发现后端的存活期限设置与待移除列表
+    fn render_discovery_ttl(&mut self, ui: &mut egui::Ui) {
+        let mut ttl = self.discovery_ttl.clone();
+        ui.horizontal(|ui| {
+            ui.label("集群超过");
+            ui.add(egui::DragValue::new(&mut ttl.ttl_secs).clamp_range(60..=604800));
+            ui.label("秒未再报告的后端标记为失联");
+        });
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut ttl.auto_remove, "自动移除，失联后等待");
+            ui.add_enabled(ttl.auto_remove, egui::DragValue::new(&mut ttl.remove_after_secs).clamp_range(0..=2592000));
+            ui.label("秒");
+        });
+        if ttl != self.discovery_ttl {
+            let result = self.kubernetes_service.set_discovery_ttl(ttl.clone());
+            self.report_error(result);
+            self.discovery_ttl = ttl;
+        }
+        
+        let stale = self.kubernetes_service.stale_backends(&self.discovery_ttl);
+        if stale.is_empty() {
+            ui.weak("没有失联的发现后端");
+            return;
+        }
+        ui.label("待移除列表：确认仍需要的后端可保留，保留后不再按存活期限跟踪");
+        let time = |t: DateTime<Utc>| t.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S").to_string();
+        let mut keep = None;
+        let mut remove = None;
+        egui::Grid::new("stale_backends").striped(true).show(ui, |ui| {
+            ui.label("业务组");
+            ui.label("后端");
+            ui.label("最后报告");
+            ui.label("自动移除");
+            ui.label("");
+            ui.end_row();
+            for backend in &stale {
+                ui.label(&backend.group_name);
+                ui.label(&backend.backend_name);
+                ui.label(time(backend.last_seen));
+                ui.label(backend.removal_at.map(time).unwrap_or_else(|| "未开启".to_string()));
+                ui.horizontal(|ui| {
+                    if ui.small_button("保留").clicked() {
+                        keep = Some(backend.clone());
+                    }
+                    if ui.small_button("立即移除").clicked() {
+                        remove = Some(backend.clone());
+                    }
+                });
+                ui.end_row();
+            }
+        });
+        
+        if let Some(backend) = keep {
+            match self.kubernetes_service.keep_backend(&BackendId::from(&backend.backend_id)) {
+                Ok(()) => {
+                    let action = format!("保留失联的发现后端 {}（业务组 {}）", backend.backend_name, backend.group_name);
+                    self.push_log(LogEntry::new("kubernetes", &action));
+                    self.record_audit(&action, Some(EntityKind::Backend), Some(&backend.backend_id));
+                }
+                Err(e) => self.report_error(Err(e)),
+            }
+            self.load_business_groups();
+        }
+        if let Some(backend) = remove {
+            match self.backend_service.delete_backend(&GroupId::from(&backend.group_id), backend.middleware_id.as_deref().map(MiddlewareId::from).as_ref(), &BackendId::from(&backend.backend_id)) {
+                Ok(()) => {
+                    let action = format!("移除失联的发现后端 {}（业务组 {}）", backend.backend_name, backend.group_name);
+                    self.push_log(LogEntry::new("kubernetes", &action));
+                    self.record_audit(&action, Some(EntityKind::Backend), Some(&backend.backend_id));
+                }
+                Err(e) => self.report_error(Err(e)),
+            }
+            self.load_business_groups();
+        }
+    }
+    
+    /// 渲染docker-compose导入面板
+    fn render_compose_import(&mut self, ui: &mut egui::Ui) {
+        ui.heading("从 docker-compose 导入");
+        ui.label(format!(
+            "每个服务生成一个容器：可用 {} 标签指定角色，未标注时声明了 depends_on 且不被其他服务依赖的作为中间层，其依赖作为其后端。",
+            kubernetes::ROLE_LABEL,
+        ));
+        
+        ui.horizontal(|ui| {
+            ui.label("文件:");
+            ui.text_edit_singleline(&mut self.compose_path);
+            if ui.add_enabled(!self.compose_path.trim().is_empty(), egui::Button::new("导入为业务组")).clicked() {
+                let path = self.compose_path.trim().to_string();
+                match self.business_group_service.import_compose(&path) {
+                    Ok(group) => {
+                        let backends = group.backend_containers.len()
+                            + group.middlewares.iter().map(|m| m.backend_containers.len()).sum::<usize>();
+                        let action = format!(
+                            "从 {} 导入业务组 {}（{} 个中间层，{} 个后端）",
+                            path,
+                            group.name,
+                            group.middlewares.len(),
+                            backends,
+                        );
+                        self.push_log(LogEntry::new("配置", &action));
+                        self.record_audit(&action, Some(EntityKind::Group), Some(&group.id));
+                    }
+                    Err(e) => self.push_log(LogEntry::new("配置", &format!("导入docker-compose失败: {:#}", e))),
+                }
+            }
+        });
+    }
+    
+    /// 渲染由快照恢复业务组的面板
+    fn render_snapshot_restore(&mut self, ui: &mut egui::Ui) {
+        ui.heading("从快照恢复业务组");
+        ui.label("由业务组快照重新创建业务组，组内由Docker管理的容器改在选定的主机上运行，恢复后为停止状态。原业务组仍存在时按副本恢复。");
+        
+        let hosts = self.docker_service.get_hosts();
+        let form = &mut self.snapshot_restore;
+        egui::Grid::new("snapshot_restore").num_columns(2).show(ui, |ui| {
+            ui.label("快照文件:");
+            ui.text_edit_singleline(&mut form.path);
+            ui.end_row();
+            ui.label("目标主机:");
+            let selected = form.host_id
+                .as_ref()
+                .and_then(|id| hosts.iter().find(|h| &h.id == id))
+                .map_or("本机", |h| h.name.as_str());
+            egui::ComboBox::from_id_source("snapshot_restore_host").selected_text(selected).show_ui(ui, |ui| {
+                ui.selectable_value(&mut form.host_id, None, "本机");
+                for host in &hosts {
+                    ui.selectable_value(&mut form.host_id, Some(host.id.clone()), &host.name);
+                }
+            });
+            ui.end_row();
+            ui.label("业务组名称:");
+            ui.add(egui::TextEdit::singleline(&mut form.name).hint_text("留空使用快照中的名称"));
+            ui.end_row();
+        });
+        ui.checkbox(&mut form.restore_volumes, "恢复命名卷数据")
+            .on_hover_text("目标主机上已存在同名卷时拒绝恢复，不会覆盖已有数据");
+        if ui.add_enabled(!form.path.trim().is_empty(), egui::Button::new("恢复")).clicked() {
+            let host_name = form.host_id
+                .as_ref()
+                .and_then(|id| hosts.iter().find(|h| &h.id == id))
+                .map_or_else(|| "本机".to_string(), |h| h.name.clone());
+            let kind = JobKind::RestoreGroup {
+                path: form.path.trim().to_string(),
+                host_id: form.host_id.clone(),
+                host_name,
+                name: form.name.trim().to_string(),
+                restore_volumes: form.restore_volumes,
+            };
+            self.enqueue_job(kind);
+        }
+    }
+    
+    /// 渲染审计日志筛选条件，返回解析后的筛选条件，日期格式错误时返回None并提示
+    fn render_audit_filter(&mut self, ui: &mut egui::Ui) -> Option<AuditFilter> {
+        let form = &mut self.audit_export;
+        ui.horizontal(|ui| {
+            ui.label("日期:");
+            ui.add(egui::TextEdit::singleline(&mut form.from).hint_text("YYYY-MM-DD").desired_width(90.0));
+            ui.label("至");
+            ui.add(egui::TextEdit::singleline(&mut form.to).hint_text("YYYY-MM-DD").desired_width(90.0));
+            ui.label("操作人:");
+            ui.add(egui::TextEdit::singleline(&mut form.actor).desired_width(80.0));
+            ui.label("实体:");
+            ui.add(egui::TextEdit::singleline(&mut form.entity).hint_text("ID或类型").desired_width(100.0));
+            ui.label("操作:");
+            ui.add(egui::TextEdit::singleline(&mut form.action).desired_width(100.0));
+        });
+        let filter = AuditFilter {
+            actor: form.actor.clone(),
+            entity: form.entity.clone(),
+            action: form.action.clone(),
+            ..AuditFilter::default()
+        };
+        match filter.with_days(&form.from, &form.to) {
+            Ok(filter) => Some(filter),
+            Err(e) => {
+                ui.colored_label(Color32::RED, e.to_string());
+                None
+            }
+        }
+    }
+    
+    /// 渲染审计日志导出与导出文件校验，导出使用当前的筛选条件
+    fn render_audit_export(&mut self, ui: &mut egui::Ui, filter: Option<&AuditFilter>) {
+        let form = &mut self.audit_export;
+        egui::Grid::new("audit_export").num_columns(2).show(ui, |ui| {
+            ui.label("文件:");
+            ui.text_edit_singleline(&mut form.path);
+            ui.end_row();
+            ui.label("格式:");
+            ui.horizontal(|ui| {
+                for format in AuditExportFormat::ALL {
+                    if ui.radio(form.format == format, format.label()).clicked() && form.format != format {
+                        form.format = format;
+                        if let Some((stem, _)) = form.path.rsplit_once('.') {
+                            form.path = format!("{}.{}", stem, format.extension());
+                        }
+                    }
+                }
+            });
+            ui.end_row();
+            ui.label("防篡改:");
+            egui::ComboBox::from_id_source("audit_integrity")
+                .selected_text(form.integrity.label())
+                .show_ui(ui, |ui| {
+                    for integrity in AuditIntegrity::ALL {
+                        ui.selectable_value(&mut form.integrity, integrity, integrity.label());
+                    }
+                });
+            ui.end_row();
+            ui.label("签名密钥:");
+            ui.add(egui::TextEdit::singleline(&mut form.key).password(true).hint_text("HMAC签名与校验时使用，不会保存"));
+            ui.end_row();
+        });
+        ui.label(RichText::new("哈希链中每条记录的哈希包含前一条的哈希，删改、重排或截断都会被发现；校验只支持JSON导出文件。").small().weak());
+        
+        let path = form.path.trim().to_string();
+        let (export, verify) = ui.horizontal(|ui| {
+            let export = ui.add_enabled(filter.is_some() && !path.is_empty(), egui::Button::new("导出")).clicked();
+            let verify = ui.add_enabled(!path.is_empty(), egui::Button::new("校验导出文件")).clicked();
+            (export, verify)
+        }).inner;
+        
+        if export && let Some(filter) = filter {
+            let result = self.audit_service.export(&path, form.format, filter, form.integrity, &form.key);
+            match result {
+                Ok(count) => {
+                    let action = format!("导出 {} 条审计日志到 {}（{}，{}）", count, path, form.format.label(), form.integrity.label());
+                    self.push_log(LogEntry::new("审计", &action));
+                    self.record_audit(&action, None, None);
+                }
+                Err(e) => self.push_log(LogEntry::new("审计", &format!("导出审计日志失败: {:#}", e))),
+            }
+        }
+        if verify {
+            match self.audit_service.verify_export(&path, &self.audit_export.key) {
+                Ok(count) => self.push_log(LogEntry::new("审计", &format!("{} 校验通过，共 {} 条记录", path, count))),
+                Err(e) => self.push_log(LogEntry::new("审计", &format!("{} 校验失败: {:#}", path, e))),
+            }
+        }
+    }
+    
+    /// 渲染存储的审计日志完整性校验
+    fn render_audit_integrity(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if ui.button("验证审计完整性").on_hover_text("按哈希链逐条校验存储的审计日志，发现被修改或删除的历史记录").clicked() {
+                let result = self.audit_service.verify_integrity().map_err(|e| format!("{:#}", e));
+                match &result {
+                    Ok(status) => self.push_log(LogEntry::new("审计", &format!("审计日志完整性校验通过，共 {} 条记录", status.verified))),
+                    Err(e) => self.push_log(LogEntry::new("审计", &format!("审计日志完整性校验失败: {}", e))),
+                }
+                self.audit_integrity = Some(result);
+            }
+            match &self.audit_integrity {
+                Some(Ok(status)) => {
+                    let mut text = format!("✔ {} 条记录完整", status.verified);
+                    if status.legacy > 0 {
+                        text.push_str(&format!("，另有开头 {} 条旧记录由起点校验", status.legacy));
+                    }
+                    ui.colored_label(Color32::GREEN, text);
+                }
+                Some(Err(e)) => {
+                    ui.colored_label(Color32::RED, format!("✖ {}", e));
+                }
+                None => {}
+            }
+        });
+        if let Some(Ok(AuditChainStatus { head: Some(head), .. })) = &self.audit_integrity {
+            ui.horizontal(|ui| {
+                ui.label(RichText::new("末条哈希:").small().weak());
+                ui.monospace(&head[..16]).on_hover_text(head);
+                if ui.small_button("复制").on_hover_text("另行保存末条哈希，可发现末尾记录被删除").clicked() {
+                    ui.output_mut(|o| o.copied_text = head.clone());
+                }
+            });
+        }
+    }
+    
+    /// 渲染业务组快照对话框
+    fn render_snapshot_dialog(&mut self, ctx: &egui::Context) {
+        let Some(draft) = &mut self.snapshot_draft else {
+            return;
+        };
+        let mut open = true;
+        let mut create = false;
+        let mut cancel = false;
+        Window::new(format!("创建快照 - {}", draft.group_name))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label("快照包含业务组的容器定义与中间层配置，保存为gzip压缩的tar归档。");
+                ui.horizontal(|ui| {
+                    ui.label("文件:");
+                    ui.text_edit_singleline(&mut draft.path);
+                });
+                if draft.volumes.is_empty() {
+                    ui.weak("组内容器没有挂载命名卷");
+                } else {
+                    ui.checkbox(&mut draft.include_volumes, format!("包含命名卷数据: {}", draft.volumes.join(", ")))
+                        .on_hover_text("通过以容器镜像创建、不启动的临时容器读取卷内容，卷较大时耗时较长");
+                }
+                ui.horizontal(|ui| {
+                    create = ui.add_enabled(!draft.path.trim().is_empty(), egui::Button::new("创建")).clicked();
+                    cancel = ui.button("取消").clicked();
+                });
+            });
+        if !open || cancel {
+            self.snapshot_draft = None;
+        } else if create && let Some(draft) = self.snapshot_draft.take() {
+            self.enqueue_job(JobKind::SnapshotGroup {
+                group_id: draft.group_id,
+                group_name: draft.group_name,
+                path: draft.path.trim().to_string(),
+                include_volumes: draft.include_volumes && !draft.volumes.is_empty(),
+            });
+        }
+    }
+    
+    /// 收取Kubernetes发现结果
+    fn poll_kubernetes_discovery(&mut self) {
+        let Some((namespace, result)) = self.kubernetes_service.poll_discovery() else {
+            return;
+        };
+        match result {
+            Ok(workloads) => {
+                self.push_log(LogEntry::new("kubernetes", &format!("在命名空间 {} 中发现 {} 个工作负载", namespace, workloads.len())));
+                self.kubernetes_workloads = Some((namespace, workloads));
+            }
+            Err(e) => self.push_log(LogEntry::new("kubernetes", &format!("发现失败: {:#}", e))),
+        }
+    }
+    
+    /// 渲染日志标签页
+    /// 渲染由Docker读取的容器输出
+    fn render_container_logs(&mut self, ui: &mut egui::Ui) {
+        let available = self.log_stream_service.containers();
+        let containers: Vec<(String, String)> = self.business_groups
+            .iter()
+            .flat_map(|group| {
+                let middlewares = group.middlewares.iter().flat_map(move |m| {
+                    std::iter::once((m.id.clone(), format!("{} / {}", group.name, m.name)))
+                        .chain(m.backend_containers.iter().map(move |b| (b.id.clone(), format!("{} / {} / {}", group.name, m.name, b.name))))
+                });
+                let backends = group.backend_containers.iter().map(move |b| (b.id.clone(), format!("{} / {}", group.name, b.name)));
+                middlewares.chain(backends)
+            })
+            .filter(|(id, _)| available.contains(id))
+            .collect();
+        if containers.is_empty() {
+            ui.label("暂无容器日志，运行中的Docker容器的输出会自动读取到这里");
+            return;
+        }
+        
+        let selected_label = self.container_log_id
+            .as_ref()
+            .and_then(|id| containers.iter().find(|(c, _)| c == id))
+            .map(|(_, label)| label.clone())
+            .unwrap_or_else(|| "选择容器".to_string());
+        ui.horizontal(|ui| {
+            ui.label("容器:");
+            egui::ComboBox::from_id_source("container_log_picker")
+                .selected_text(selected_label)
+                .show_ui(ui, |ui| {
+                    for (id, label) in &containers {
+                        ui.selectable_value(&mut self.container_log_id, Some(id.clone()), label);
+                    }
+                });
+            ui.label("筛选:");
+            ui.text_edit_singleline(&mut self.container_log_filter);
+        });
+        let Some(id) = self.container_log_id.clone() else {
+            return;
+        };
+        
+        let lines = self.log_stream_service.lines(&id);
+        let filter = self.container_log_filter.trim().to_lowercase();
+        ui.horizontal(|ui| {
+            if self.log_stream_service.is_streaming(&id) {
+                ui.colored_label(Color32::GREEN, "读取中");
+            } else {
+                ui.colored_label(Color32::GRAY, "已停止读取");
+            }
+            ui.label(format!("共 {} 行", lines.len()));
+            if ui.small_button("清空").clicked() {
+                self.log_stream_service.clear(&id);
+            }
+        });
+        ScrollArea::vertical().id_source("container_log_lines").max_height(300.0).stick_to_bottom(true).show(ui, |ui| {
+            for line in lines.iter().filter(|l| filter.is_empty() || l.text.to_lowercase().contains(&filter)) {
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new(line.timestamp.with_timezone(&chrono::Local).format("%H:%M:%S").to_string()).color(Color32::GRAY));
+                    ui.monospace(&line.text);
+                });
+            }
+        });
+    }
+    
+    fn render_logs_tab(&mut self, ui: &mut egui::Ui) {
+        ui.vertical(|ui| {
+            ui.heading("日志中心");
+            ui.separator();
+            
+            CollapsingHeader::new("异常检测规则").show(ui, |ui| {
+                self.render_anomaly_rules(ui);
+            });
+            
+            CollapsingHeader::new("审计日志").show(ui, |ui| {
+                let filter = self.render_audit_filter(ui);
+                let mut trace = None;
+                ScrollArea::vertical().id_source("audit_log").max_height(200.0).show(ui, |ui| {
+                    egui::Grid::new("audit_log_grid").striped(true).show(ui, |ui| {
+                        for entry in self.audit_entries.iter().rev().filter(|e| filter.as_ref().is_none_or(|f| f.matches(e))) {
+                            ui.label(entry.timestamp.format("%Y-%m-%d %H:%M:%S").to_string());
+                            ui.label(&entry.actor);
+                            ui.label(&entry.action);
+                            match &entry.trace_id {
+                                Some(trace_id) => {
+                                    if ui.small_button(Self::short_trace_id(trace_id)).on_hover_text(trace_id).clicked() {
+                                        trace = Some(trace_id.clone());
+                                    }
+                                }
+                                None => {
+                                    ui.label("");
+                                }
+                            }
+                            ui.end_row();
+                        }
+                    });
+                });
+                if let Some(trace_id) = trace {
+                    self.search_trace(&trace_id);
+                }
+                
+                self.render_audit_integrity(ui);
+                
+                CollapsingHeader::new("导出与校验").show(ui, |ui| {
+                    self.render_audit_export(ui, filter.as_ref());
+                });
+            });
+            
+            CollapsingHeader::new("链路追踪").default_open(self.trace_search.is_some()).show(ui, |ui| {
+                self.render_trace_search(ui);
+            });
+            
+            CollapsingHeader::new("容器日志").default_open(self.container_log_id.is_some()).show(ui, |ui| {
+                self.render_container_logs(ui);
+            });
+            
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut self.show_only_anomalies, "只显示异常日志");
+                ui.label(format!("异常日志: {}", self.anomaly_detector.hit_count));
+            });
+            ui.separator();
+            
+            if !self.selected_log_indices.is_empty() {
+                self.render_evidence_panel(ui);
+                ui.separator();
+            }
+            
+            ScrollArea::vertical().stick_to_bottom(true).show(ui, |ui| {
+                for (index, log) in self.logs.iter().enumerate() {
+                    let rule = self.anomaly_detector.matches(&log.message);
+                    if rule.is_none() && self.show_only_anomalies {
+                        continue;
+                    }
+                    
+                    ui.horizontal(|ui| {
+                        let mut selected = self.selected_log_indices.contains(&index);
+                        if ui.checkbox(&mut selected, "").changed() {
+                            if selected {
+                                self.selected_log_indices.insert(index);
+                            } else {
+                                self.selected_log_indices.remove(&index);
+                            }
+                        }
+                        match rule {
+                            Some(rule) => ui.label(RichText::new(log.to_string()).color(Self::get_alert_severity_color(&rule.severity))),
+                            None => ui.label(log.to_string()),
+                        };
+                    });
+                }
+            });
+        });
+    }
+    
+    /// 追踪ID的简短显示形式
+    fn short_trace_id(trace_id: &str) -> &str {
+        trace_id.get(..8).unwrap_or(trace_id)
+    }
+    
+    /// 按追踪ID检索中间层日志
+    fn search_trace(&mut self, trace_id: &str) {
+        self.trace_query = trace_id.to_string();
+        self.trace_search = None;
+        self.trace_service.search(trace_id);
+    }
+    
+    /// 渲染按追踪ID检索审计日志、调试请求与中间层日志的面板
+    fn render_trace_search(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("追踪ID:");
+            ui.text_edit_singleline(&mut self.trace_query);
+            let searching = self.trace_service.is_searching();
+            let query = self.trace_query.trim().to_string();
+            if ui.add_enabled(!searching && !query.is_empty(), egui::Button::new("检索中间层日志")).clicked() {
+                self.search_trace(&query);
+            }
+            if searching {
+                ui.spinner();
+            }
+        });
+        ui.label(format!("界面发起的操作会在请求头 {} 中携带追踪ID，中间层日志需记录该请求头才能检索到。", api::TRACE_HEADER));
+        
+        let query = self.trace_query.trim();
+        if query.is_empty() {
+            return;
+        }
+        
+        let audits: Vec<&AuditEntry> = self.audit_entries
+            .iter()
+            .filter(|e| e.trace_id.as_deref() == Some(query))
+            .collect();
+        let requests: Vec<&PlaygroundHistoryEntry> = self.playground_history
+            .iter()
+            .filter(|e| e.trace_id.as_deref() == Some(query))
+            .collect();
+        let jobs: Vec<&JobRecord> = self.job_history
+            .iter()
+            .chain(self.job_service.active_jobs())
+            .filter(|j| j.trace_id.as_deref() == Some(query))
+            .collect();
+        
+        ui.strong(format!("审计日志 ({})", audits.len()));
+        for entry in audits {
+            ui.label(format!("{} {} {}", entry.timestamp.format("%Y-%m-%d %H:%M:%S"), entry.actor, entry.action));
+        }
+        ui.strong(format!("调试请求 ({})", requests.len()));
+        for entry in requests {
+            let result = match (entry.status, &entry.error) {
+                (Some(status), _) => status.to_string(),
+                (None, error) => error.clone().unwrap_or_default(),
+            };
+            ui.label(format!(
+                "{} {} {} {} → {}",
+                entry.timestamp.format("%Y-%m-%d %H:%M:%S"),
+                entry.middleware_name,
+                entry.request.method.label(),
+                entry.request.path,
+                result,
+            ));
+        }
+        ui.strong(format!("后台任务 ({})", jobs.len()));
+        for job in jobs {
+            ui.label(format!("{} {}: {}", job.kind.describe(), job.status.label(), job.message));
+        }
+        
+        let Some(search) = self.trace_search.as_ref().filter(|s| s.trace_id == query) else {
+            return;
+        };
+        ui.strong(format!("中间层日志 ({})", search.lines.len()));
+        ScrollArea::vertical().id_source("trace_log_lines").max_height(240.0).show(ui, |ui| {
+            for line in &search.lines {
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new(&line.middleware_name).strong());
+                    ui.label(RichText::new(&line.line).monospace());
+                });
+            }
+        });
+        for (name, error) in &search.errors {
+            ui.colored_label(Color32::RED, format!("{} 日志获取失败: {}", name, error));
+        }
+    }
+    
+    /// 渲染异常检测规则编辑器
+    fn render_anomaly_rules(&mut self, ui: &mut egui::Ui) {
+        let mut removed = None;
+        
+        egui::Grid::new("anomaly_rules").striped(true).show(ui, |ui| {
+            ui.label("启用");
+            ui.label("名称");
+            ui.label("关键字/正则");
+            ui.label("正则");
+            ui.label("级别");
+            ui.label("告警");
+            ui.end_row();
+            
+            for (index, rule) in self.anomaly_rules.iter_mut().enumerate() {
+                ui.checkbox(&mut rule.enabled, "");
+                ui.text_edit_singleline(&mut rule.name);
+                ui.text_edit_singleline(&mut rule.pattern);
+                ui.checkbox(&mut rule.is_regex, "");
+                egui::ComboBox::from_id_source(format!("anomaly_severity_{}", rule.id))
+                    .selected_text(Self::get_alert_severity_text(&rule.severity))
+                    .show_ui(ui, |ui| {
+                        for severity in [AlertSeverity::Info, AlertSeverity::Warning, AlertSeverity::Critical] {
+                            ui.selectable_value(&mut rule.severity, severity, Self::get_alert_severity_text(&severity));
+                        }
+                    });
+                ui.checkbox(&mut rule.raise_alert, "");
+                if ui.button("删除").clicked() {
+                    removed = Some(index);
+                }
+                ui.end_row();
+            }
+        });
+        
+        if let Some(index) = removed {
+            self.anomaly_rules.remove(index);
+        }
+        
+        ui.horizontal(|ui| {
+            ui.label("激增阈值 (每分钟, 0为关闭):");
+            ui.add(egui::DragValue::new(&mut self.anomaly_spike_threshold));
+        });
+        
+        ui.horizontal(|ui| {
+            if ui.button("添加规则").clicked() {
+                self.anomaly_rules.push(AnomalyRule::default());
+            }
+            if ui.button("保存规则").clicked() {
+                self.save_anomaly_rules();
+            }
+        });
+        
+        for error in &self.anomaly_rule_errors {
+            ui.label(RichText::new(error).color(Color32::RED));
+        }
+    }
+    
+    /// 渲染日志证据附加面板
+    fn render_evidence_panel(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(format!("已选择 {} 行日志", self.selected_log_indices.len()));
+            if ui.button("清除选择").clicked() {
+                self.selected_log_indices.clear();
+            }
+        });
+        
+        ui.horizontal(|ui| {
+            ui.label("附加到:");
+            let selected_text = match &self.evidence_alert_id {
+                Some(alert_id) => self.alerts
+                    .iter()
+                    .find(|a| a.id == *alert_id)
+                    .map(|a| format!("{} {}", a.created_at.format("%m-%d %H:%M"), a.message))
+                    .unwrap_or_else(|| "新建事件".to_string()),
+                None => "新建事件".to_string(),
+            };
+            egui::ComboBox::from_id_source("evidence_alert")
+                .selected_text(selected_text)
+                .width(300.0)
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.evidence_alert_id, None, "新建事件");
+                    for alert in self.alerts.iter().rev() {
+                        ui.selectable_value(
+                            &mut self.evidence_alert_id,
+                            Some(alert.id.clone()),
+                            format!("{} [{}] {}", alert.created_at.format("%m-%d %H:%M"), alert.source, alert.message),
+                        );
+                    }
+                });
+        });
+        
+        ui.horizontal(|ui| {
+            ui.label("关联实体:");
+            ui.add(egui::TextEdit::singleline(&mut self.evidence_entity).hint_text("默认取日志来源"));
+        });
+        
+        ui.horizontal(|ui| {
+            ui.label("备注:");
+            ui.text_edit_multiline(&mut self.evidence_note);
+        });
+        
+        if ui.button("附加证据").clicked() {
+            self.attach_selected_logs();
+        }
     }
     
-    /// 渲染配置标签页
-    fn render_config_tab(&mut self, ui: &mut egui::Ui) {
-        ui.vertical(|ui| {
-            ui.heading("配置管理");
-            ui.separator();
-            
+    /// 渲染告警列表
+    fn render_alerts_section(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.heading("告警");
+            if ui.button("清除已确认").clicked() {
+                self.report_error(self.alert_service.clear_acknowledged());
+                self.load_alerts();
+            }
+        });
+        
+        CollapsingHeader::new("去重与抖动抑制").show(ui, |ui| {
+            self.render_alert_policy(ui);
+        });
+        
+        CollapsingHeader::new("监控策略导出与导入").show(ui, |ui| {
+            self.render_monitoring_policy_transfer(ui);
+        });
+        
+        let mut acknowledged = None;
+        for alert in self.alerts.iter().rev() {
             ui.horizontal(|ui| {
-                if ui.button("保存配置").clicked() {
-                    let config = Config {
-                        app_state: self.business_group_service.config_manager.load_config().unwrap().app_state,
-                        last_opened: Utc::now().to_string(),
-                        theme: "dark".to_string(),
-                        auto_save: true,
-                        save_interval: 30,
-                    };
-                    self.config_manager.save_config(&config).unwrap();
+                ui.label(RichText::new(Self::get_alert_severity_text(&alert.severity)).color(Self::get_alert_severity_color(&alert.severity)));
+                ui.label(alert.created_at.format("%Y-%m-%d %H:%M:%S").to_string());
+                ui.label(&alert.source);
+                ui.label(&alert.message);
+                if alert.occurrences > 1 {
+                    let last_seen = alert.last_seen.unwrap_or(alert.created_at);
+                    ui.label(RichText::new(format!("×{}", alert.occurrences)).strong())
+                        .on_hover_text(format!("最近一次: {}", last_seen.format("%Y-%m-%d %H:%M:%S")));
                 }
-                if ui.button("导入配置").clicked() {
-                    // TODO: 实现导入配置功能
-                }
-                if ui.button("导出配置").clicked() {
-                    // TODO: 实现导出配置功能
+                if alert.acknowledged {
+                    ui.label(RichText::new("已确认").color(Color32::GRAY));
+                } else if ui.button("确认").clicked() {
+                    acknowledged = Some(alert.id.clone());
                 }
             });
             
-            ui.separator();
-            
-            ui.heading("应用配置");
-            ScrollArea::vertical().show(ui, |ui| {
-                ui.label("这里显示应用配置详情");
-            });
-        });
-    }
-    
-    /// 渲染监控标签页
-    fn render_monitor_tab(&mut self, ui: &mut egui::Ui) {
-        ui.vertical(|ui| {
-            ui.heading("监控中心");
-            ui.separator();
-            
-            ui.heading("业务组状态");
-            ScrollArea::vertical().show(ui, |ui| {
-                for group in &self.business_groups {
-                    ui.collapsing(&group.name, |ui| {
-                        ui.horizontal(|ui| {
-                            ui.label("状态:");
-                            ui.label(Self::get_status_text(&group.status));
-                        });
-                        
-                        for middleware in &group.middlewares {
-                            ui.collapsing(&middleware.name, |ui| {
-                                ui.horizontal(|ui| {
-                                    ui.label("状态:");
-                                    ui.label(Self::get_container_status_text(&middleware.status));
-                                    ui.label("健康状态:");
-                                    ui.label(Self::get_health_status_text(&middleware.health));
-                                });
-                                
-                                for backend in &middleware.backend_containers {
-                                    ui.horizontal(|ui| {
-                                        ui.label("  - ");
-                                        ui.label(&backend.name);
-                                        ui.label(":");
-                                        ui.label(Self::get_container_status_text(&backend.status));
-                                        ui.label(Self::get_health_status_text(&backend.health));
-                                    });
-                                }
-                            });
+            if !alert.evidence.is_empty() {
+                CollapsingHeader::new(format!("事件证据 ({})", alert.evidence.len()))
+                    .id_source(format!("alert_evidence_{}", alert.id))
+                    .show(ui, |ui| {
+                        let mut evidence: Vec<_> = alert.evidence.iter().collect();
+                        evidence.sort_by_key(|e| e.from);
+                        for item in evidence {
+                            ui.label(RichText::new(format!(
+                                "{} ~ {}  实体: {}",
+                                item.from.format("%Y-%m-%d %H:%M:%S"),
+                                item.to.format("%H:%M:%S"),
+                                item.entity,
+                            )).strong());
+                            if !item.note.is_empty() {
+                                ui.label(format!("备注: {}", item.note));
+                            }
+                            for entry in &item.entries {
+                                ui.label(RichText::new(entry.to_string()).monospace());
+                            }
+                            ui.separator();
                         }
                     });
+            }
+        }
+        
+        if let Some(alert_id) = acknowledged {
+            self.report_error(self.alert_service.acknowledge_alert(&alert_id));
+            self.load_alerts();
+        }
+    }
+    
+    /// 渲染选中业务组最近一年每天告警数的日历热力图，点击日期显示当天的事件时间线
+    fn render_incident_calendar(&mut self, ui: &mut egui::Ui) {
+        ui.heading("事件日历");
+        let group = self.selected_group_id
+            .as_ref()
+            .and_then(|id| self.business_groups.iter().find(|g| &g.id == id));
+        let names = group.map(|g| g.entity_names());
+        let alerts: Vec<&Alert> = self.alerts
+            .iter()
+            .filter(|a| names.as_ref().is_none_or(|names| names.contains(a.source.as_str())))
+            .collect();
+        match group {
+            Some(group) => ui.label(format!("业务组 {} 每天的告警数，点击日期查看当天的事件时间线", group.name)),
+            None => ui.label("全部告警每天的数量，选择业务组后只统计其中间层与后端；点击日期查看当天的事件时间线"),
+        };
+        
+        let mut counts: HashMap<NaiveDate, usize> = HashMap::new();
+        for alert in &alerts {
+            *counts.entry(alert.local_day()).or_insert(0) += 1;
+        }
+        let max = counts.values().copied().max().unwrap_or(0);
+        let today = Local::now().date_naive();
+        let first = today - chrono::Duration::days(i64::from(today.weekday().num_days_from_monday()) + (INCIDENT_CALENDAR_WEEKS - 1) * 7);
+        
+        let step = INCIDENT_CELL_SIZE + INCIDENT_CELL_GAP;
+        let size = egui::vec2(INCIDENT_CALENDAR_WEEKS as f32 * step, 7.0 * step);
+        let (response, painter) = ui.allocate_painter(size, egui::Sense::click());
+        let day_at = |pos: egui::Pos2| {
+            let offset = pos - response.rect.min;
+            let (week, weekday) = ((offset.x / step) as i64, (offset.y / step) as i64);
+            let day = first + chrono::Duration::days(week * 7 + weekday);
+            (offset.x >= 0.0 && offset.y >= 0.0 && week < INCIDENT_CALENDAR_WEEKS && weekday < 7 && day <= today).then_some(day)
+        };
+        for week in 0..INCIDENT_CALENDAR_WEEKS {
+            for weekday in 0..7 {
+                let day = first + chrono::Duration::days(week * 7 + weekday);
+                if day > today {
+                    continue;
                 }
-            });
+                let min = response.rect.min + egui::vec2(week as f32 * step, weekday as f32 * step);
+                let rect = egui::Rect::from_min_size(min, egui::vec2(INCIDENT_CELL_SIZE, INCIDENT_CELL_SIZE));
+                let count = counts.get(&day).copied().unwrap_or(0);
+                let color = if count == 0 {
+                    ui.visuals().faint_bg_color
+                } else {
+                    INCIDENT_LEVEL_COLORS[(count * INCIDENT_LEVEL_COLORS.len()).div_ceil(max) - 1]
+                };
+                painter.rect_filled(rect, 2.0, color);
+                if self.incident_day == Some(day) {
+                    painter.rect_stroke(rect.expand(1.0), 2.0, ui.visuals().selection.stroke);
+                }
+            }
+        }
+        let hovered = response.hover_pos().and_then(day_at);
+        if response.clicked()
+            && let Some(day) = response.interact_pointer_pos().and_then(day_at)
+        {
+            self.incident_day = (self.incident_day != Some(day)).then_some(day);
+        }
+        if let Some(day) = hovered {
+            response.on_hover_text(format!("{}: {} 条告警", day.format("%Y-%m-%d"), counts.get(&day).copied().unwrap_or(0)));
+        }
+        ui.horizontal(|ui| {
+            ui.weak("少");
+            for color in INCIDENT_LEVEL_COLORS {
+                ui.label(RichText::new("■").color(color));
+            }
+            ui.weak("多");
+        });
+        
+        let Some(day) = self.incident_day else {
+            return;
+        };
+        let mut timeline: Vec<&&Alert> = alerts.iter().filter(|a| a.local_day() == day).collect();
+        timeline.sort_by_key(|a| a.created_at);
+        let mut close = false;
+        ui.horizontal(|ui| {
+            ui.strong(format!("{} 的事件时间线（{} 条）", day.format("%Y-%m-%d"), timeline.len()));
+            close = ui.small_button("关闭").clicked();
+        });
+        if timeline.is_empty() {
+            ui.label("当天没有告警");
+        }
+        egui::Grid::new("incident_timeline").striped(true).show(ui, |ui| {
+            for alert in timeline {
+                ui.label(alert.created_at.with_timezone(&Local).format("%H:%M:%S").to_string());
+                ui.label(RichText::new(Self::get_alert_severity_text(&alert.severity)).color(Self::get_alert_severity_color(&alert.severity)));
+                ui.label(&alert.source);
+                ui.label(&alert.message);
+                if alert.occurrences > 1 {
+                    ui.label(RichText::new(format!("×{}", alert.occurrences)).strong());
+                } else {
+                    ui.label("");
+                }
+                ui.end_row();
+            }
         });
+        if close {
+            self.incident_day = None;
+        }
     }
     
-    /// 渲染日志标签页
-    fn render_logs_tab(&mut self, ui: &mut egui::Ui) {
-        ui.vertical(|ui| {
-            ui.heading("日志中心");
-            ui.separator();
-            
-            ScrollArea::vertical().show(ui, |ui| {
-                for log in &self.logs {
-                    ui.label(log);
-                }
-            });
+    /// 渲染告警抑制设置
+    fn render_alert_policy(&mut self, ui: &mut egui::Ui) {
+        let policy = &mut self.alert_policy;
+        egui::Grid::new("alert_policy").show(ui, |ui| {
+            ui.label("连续失败次数:");
+            ui.add(egui::DragValue::new(&mut policy.failure_threshold).clamp_range(1..=20));
+            ui.end_row();
+            ui.label("连续恢复次数:");
+            ui.add(egui::DragValue::new(&mut policy.recovery_threshold).clamp_range(1..=20));
+            ui.end_row();
+            ui.label("去重窗口 (秒):");
+            ui.add(egui::DragValue::new(&mut policy.dedup_window_secs).clamp_range(0..=86400));
+            ui.end_row();
+            ui.label("抖动切换次数 (0为不检测):");
+            ui.add(egui::DragValue::new(&mut policy.flap_threshold).clamp_range(0..=50));
+            ui.end_row();
+            ui.label("抖动检测窗口 (秒):");
+            ui.add(egui::DragValue::new(&mut policy.flap_window_secs).clamp_range(10..=86400));
+            ui.end_row();
+        });
+        if ui.button("保存").clicked() {
+            let result = self.alert_service.set_policy(self.alert_policy.clone());
+            if result.is_ok() {
+                self.record_audit("修改告警抑制设置", None, None);
+            }
+            self.report_error(result);
+        }
+    }
+    
+    /// 渲染监控策略的导出与导入，策略包含告警抑制设置与日志异常检测规则，不含业务组
+    fn render_monitoring_policy_transfer(&mut self, ui: &mut egui::Ui) {
+        let mut import = None;
+        let mut export = false;
+        ui.horizontal(|ui| {
+            ui.label("文件:");
+            ui.text_edit_singleline(&mut self.monitoring_policy_path);
+            let enabled = !self.monitoring_policy_path.trim().is_empty();
+            export = ui.add_enabled(enabled, egui::Button::new("导出")).clicked();
+            if ui.add_enabled(enabled, egui::Button::new("导入并合并")).on_hover_text("同ID的规则以导入的为准，其余规则保留").clicked() {
+                import = Some(PolicyImportMode::Merge);
+            }
+            if ui.add_enabled(enabled, egui::Button::new("导入并替换")).on_hover_text("以导入的规则替换全部现有规则").clicked() {
+                import = Some(PolicyImportMode::Replace);
+            }
         });
+        let path = self.monitoring_policy_path.trim().to_string();
+        
+        if export {
+            match self.alert_service.export_monitoring_policy(&path) {
+                Ok(policy) => self.push_log(LogEntry::new("告警", &format!("已导出监控策略（{} 条异常检测规则）到 {}", policy.anomaly_rules.len(), path))),
+                Err(e) => self.push_log(LogEntry::new("告警", &format!("导出监控策略失败: {:#}", e))),
+            }
+        }
+        if let Some(mode) = import {
+            match self.alert_service.import_monitoring_policy(&path, mode) {
+                Ok(policy) => {
+                    self.alert_policy = policy.alert_policy;
+                    self.anomaly_rules = policy.anomaly_rules;
+                    self.anomaly_spike_threshold = policy.anomaly_spike_threshold;
+                    self.anomaly_rule_errors = self.anomaly_detector.set_rules(&self.anomaly_rules, self.anomaly_spike_threshold);
+                    let action = format!("从 {} 导入监控策略（{} 条异常检测规则）", path, self.anomaly_rules.len());
+                    self.push_log(LogEntry::new("告警", &action));
+                    self.record_audit(&action, None, None);
+                }
+                Err(e) => self.push_log(LogEntry::new("告警", &format!("导入监控策略失败: {:#}", e))),
+            }
+        }
     }
     
     /// 渲染新建业务组对话框
@@ -722,11 +9902,31 @@ impl App {
                         ui.text_edit_multiline(&mut self.new_group.description);
                     });
                     
+                    let mut isolated = self.new_group.docker_network.is_some();
+                    if ui.checkbox(&mut isolated, "创建独立的Docker桥接网络").changed() {
+                        self.new_group.docker_network = isolated.then(|| GroupDockerNetwork::for_group(&self.new_group.id));
+                    }
+                    let mut error = None;
+                    if let Some(network) = &mut self.new_group.docker_network {
+                        egui::Grid::new("new_group_network").num_columns(2).show(ui, |ui| {
+                            ui.label("网络名称:");
+                            ui.text_edit_singleline(&mut network.name);
+                            ui.end_row();
+                            ui.label("子网:");
+                            ui.add(egui::TextEdit::singleline(&mut network.subnet).hint_text("留空由Docker分配，例如 172.30.0.0/24"));
+                            ui.end_row();
+                        });
+                        ui.label("组内未指定网络的容器启动时接入此网络。");
+                        error = network.validate().err();
+                    }
+                    if let Some(error) = &error {
+                        ui.colored_label(Color32::RED, error.to_string());
+                    }
+                    
                     ui.horizontal(|ui| {
-                        if ui.button("确定").clicked() {
-                            self.business_group_service.add_business_group(self.new_group.clone()).unwrap();
-                            self.load_business_groups();
-                            self.new_group = BusinessGroup::default();
+                        if ui.add_enabled(error.is_none(), egui::Button::new("确定")).clicked() {
+                            let group = std::mem::take(&mut self.new_group);
+                            self.container_action_service.submit(ContainerAction::CreateGroup { group: Box::new(group) });
                             self.show_new_group_dialog = false;
                         }
                         if ui.button("取消").clicked() {
@@ -768,13 +9968,24 @@ impl App {
                             ui.text_edit_multiline(&mut self.new_middleware.docker_run_params);
                         });
                         
+                        ui.label("附加端口:");
+                        let ports_valid = Self::edit_port_mappings(ui, &mut self.new_middleware.ports).is_ok();
+                        let conflicts = self.port_conflicts(&self.new_middleware.id, &self.new_middleware.docker_host_id, &self.new_middleware.docker_run_params, &self.new_middleware.ports);
+                        Self::show_port_conflicts(ui, &conflicts);
+                        
+                        ui.label("附加挂载:");
+                        let volumes_valid = Self::edit_volume_mounts(ui, &mut self.new_middleware.volumes).is_ok();
+                        
+                        ui.label("资源上限:");
+                        let valid = Self::edit_resource_limits(ui, &mut self.new_middleware.limits).is_ok() && volumes_valid && ports_valid;
+                        
                         ui.horizontal(|ui| {
                             ui.checkbox(&mut self.new_middleware.agent_installed, "是否安装Agent");
                         });
                         
                         ui.horizontal(|ui| {
-                            if ui.button("确定").clicked() {
-                                self.middleware_service.add_middleware_to_group(group_id, self.new_middleware.clone()).unwrap();
+                            if ui.add_enabled(valid, egui::Button::new(if conflicts.is_empty() { "确定" } else { "仍然创建" })).clicked() {
+                                self.report_error(self.middleware_service.add_middleware_to_group(&GroupId::from(group_id), self.new_middleware.clone()));
                                 self.load_business_groups();
                                 self.new_middleware = MiddlewareContainer::default();
                                 self.show_new_middleware_dialog = false;
@@ -856,16 +10067,32 @@ impl App {
                             ui.text_edit_singleline(&mut self.new_backend.retries.to_string());
                         });
                         
+                        ui.vertical(|ui| {
+                            ui.label("Docker Run参数:");
+                            ui.text_edit_multiline(&mut self.new_backend.docker_run_params);
+                        });
+                        
+                        ui.label("附加端口:");
+                        let ports_valid = Self::edit_port_mappings(ui, &mut self.new_backend.ports).is_ok();
+                        let conflicts = self.port_conflicts(&self.new_backend.id, &self.new_backend.docker_host_id, &self.new_backend.docker_run_params, &self.new_backend.ports);
+                        Self::show_port_conflicts(ui, &conflicts);
+                        
+                        ui.label("附加挂载:");
+                        let volumes_valid = Self::edit_volume_mounts(ui, &mut self.new_backend.volumes).is_ok();
+                        
+                        ui.label("资源上限:");
+                        let valid = Self::edit_resource_limits(ui, &mut self.new_backend.limits).is_ok() && volumes_valid && ports_valid;
+                        
                         ui.horizontal(|ui| {
-                            if ui.button("确定").clicked() {
+                            if ui.add_enabled(valid, egui::Button::new(if conflicts.is_empty() { "确定" } else { "仍然创建" })).clicked() {
                                 if add_to_middleware {
                                     // 添加到中间层
                                     if let Some(middleware_id) = &selected_middleware_id {
-                                        self.backend_service.add_backend_to_middleware(group_id, middleware_id, self.new_backend.clone()).unwrap();
+                                        self.report_error(self.backend_service.add_backend_to_middleware(&GroupId::from(group_id), &MiddlewareId::from(middleware_id), self.new_backend.clone()));
                                     }
                                 } else {
                                     // 直接添加到业务组
-                                    self.backend_service.add_backend_to_group(group_id, self.new_backend.clone()).unwrap();
+                                    self.report_error(self.backend_service.add_backend_to_group(&GroupId::from(group_id), self.new_backend.clone()));
                                 }
                                 self.load_business_groups();
                                 self.new_backend = BackendContainer::default();
@@ -889,75 +10116,511 @@ impl App {
         self.show_new_backend_dialog = show_dialog;
     }
     
+    /// 业务组状态颜色
+    fn group_status_color(status: &GroupStatus) -> Color32 {
+        match status {
+            GroupStatus::Running => Color32::GREEN,
+            GroupStatus::Stopped => Color32::GRAY,
+            GroupStatus::Starting => Color32::YELLOW,
+            GroupStatus::Stopping => Color32::from_rgb(255, 165, 0),
+            GroupStatus::Error => Color32::RED,
+            GroupStatus::Degraded => Color32::from_rgb(255, 120, 0),
+        }
+    }
+    
     /// 获取状态文本
     fn get_status_text(status: &GroupStatus) -> RichText {
+        RichText::new(status.label()).color(Self::group_status_color(status))
+    }
+    
+    /// 容器状态颜色
+    fn container_status_color(status: &ContainerStatus) -> Color32 {
         match status {
-            GroupStatus::Running => RichText::new("运行中").color(Color32::GREEN),
-            GroupStatus::Stopped => RichText::new("已停止").color(Color32::GRAY),
-            GroupStatus::Starting => RichText::new("启动中").color(Color32::YELLOW),
-            GroupStatus::Stopping => RichText::new("停止中").color(Color32::from_rgb(255, 165, 0)),
-            GroupStatus::Error => RichText::new("错误").color(Color32::RED),
+            ContainerStatus::Running => Color32::GREEN,
+            ContainerStatus::Stopped => Color32::GRAY,
+            ContainerStatus::Starting => Color32::YELLOW,
+            ContainerStatus::Stopping => Color32::from_rgb(255, 165, 0),
+            ContainerStatus::Error => Color32::RED,
         }
     }
     
     /// 获取容器状态文本
     fn get_container_status_text(status: &ContainerStatus) -> RichText {
-        match status {
-            ContainerStatus::Running => RichText::new("运行中").color(Color32::GREEN),
-            ContainerStatus::Stopped => RichText::new("已停止").color(Color32::GRAY),
-            ContainerStatus::Starting => RichText::new("启动中").color(Color32::YELLOW),
-            ContainerStatus::Stopping => RichText::new("停止中").color(Color32::from_rgb(255, 165, 0)),
-            ContainerStatus::Error => RichText::new("错误").color(Color32::RED),
+        RichText::new(status.label()).color(Self::container_status_color(status))
+    }
+    
+    /// 获取告警级别文本
+    fn get_alert_severity_text(severity: &AlertSeverity) -> &'static str {
+        match severity {
+            AlertSeverity::Info => "信息",
+            AlertSeverity::Warning => "警告",
+            AlertSeverity::Critical => "严重",
+        }
+    }
+    
+    /// 获取告警级别颜色
+    fn get_alert_severity_color(severity: &AlertSeverity) -> Color32 {
+        match severity {
+            AlertSeverity::Info => Color32::LIGHT_BLUE,
+            AlertSeverity::Warning => Color32::from_rgb(255, 165, 0),
+            AlertSeverity::Critical => Color32::RED,
         }
     }
     
     /// 获取健康状态文本
     fn get_health_status_text(status: &HealthStatus) -> RichText {
+        RichText::new(status.label()).color(Self::health_status_color(status))
+    }
+    
+    /// 健康状态颜色
+    fn health_status_color(status: &HealthStatus) -> Color32 {
         match status {
-            HealthStatus::Healthy => RichText::new("健康").color(Color32::GREEN),
-            HealthStatus::Unhealthy => RichText::new("不健康").color(Color32::RED),
-            HealthStatus::Unknown => RichText::new("未知").color(Color32::GRAY),
-            HealthStatus::Checking => RichText::new("检查中").color(Color32::YELLOW),
+            HealthStatus::Healthy => Color32::GREEN,
+            HealthStatus::Unhealthy => Color32::RED,
+            HealthStatus::Unknown => Color32::GRAY,
+            HealthStatus::Checking => Color32::YELLOW,
+        }
+    }
+    
+    /// 健康状态图标
+    fn health_glyph(status: &HealthStatus) -> RichText {
+        let glyph = match status {
+            HealthStatus::Healthy => "✔",
+            HealthStatus::Unhealthy => "✖",
+            HealthStatus::Unknown => "?",
+            HealthStatus::Checking => "…",
+        };
+        RichText::new(glyph).color(Self::health_status_color(status))
+    }
+    
+    /// 侧边栏中容器的状态与健康图标
+    fn container_glyphs(ui: &mut egui::Ui, status: &ContainerStatus, health: &HealthStatus) {
+        ui.label(RichText::new("●").color(Self::container_status_color(status)))
+            .on_hover_text(status.label());
+        ui.label(Self::health_glyph(health)).on_hover_text(health.label());
+    }
+    
+    /// 显示运行中中间层的服务状态，经轮询协调器获取，同一中间层在多个面板中只请求一次
+    fn render_service_status(&self, ui: &mut egui::Ui, panel: &'static str, middleware: &MiddlewareContainer) {
+        if middleware.status != ContainerStatus::Running {
+            return;
+        }
+        let mut subscriptions = self.poll_subscriptions.borrow_mut();
+        let (subscription, last_used) = subscriptions
+            .entry((panel, middleware.id.clone()))
+            .or_insert_with(|| (self.poll_coordinator.subscribe(&middleware.id, PollEndpoint::Status), Instant::now()));
+        *last_used = Instant::now();
+        self.poll_coordinator.poll(subscription);
+        
+        let Some(polled) = subscription.latest() else {
+            ui.spinner();
+            return;
+        };
+        let age = format!("{}秒前", polled.fetched_at.elapsed().as_secs());
+        match polled.result.as_ref() {
+            Ok(PollResponse::Status(status)) => {
+                ui.label(format!("服务 {} ({})", status.status, status.service_role))
+                    .on_hover_text(format!("服务ID: {}\n服务时间: {}\n{}更新", status.service_id, status.timestamp, age));
+            }
+            Err(e) => {
+                ui.colored_label(Color32::RED, "服务状态获取失败").on_hover_text(format!("{}\n{}更新", e, age));
+            }
+        }
+    }
+    
+    /// 释放超过 [`POLL_SUBSCRIPTION_IDLE`] 未渲染的面板的轮询订阅
+    fn prune_poll_subscriptions(&self) {
+        self.poll_subscriptions
+            .borrow_mut()
+            .retain(|_, (_, last_used)| last_used.elapsed() < POLL_SUBSCRIPTION_IDLE);
+    }
+    
+    /// 显示容器最近探测的紧凑徽标：延迟、错误率与距上次检查的时间，尚未探测时不显示
+    fn render_probe_badge(&self, ui: &mut egui::Ui, entity_id: &str) {
+        let Some(stats) = self.health_service.probe_stats(entity_id) else {
+            return;
+        };
+        let latency = match stats.latency {
+            Some(latency) => {
+                let ms = latency.as_millis();
+                let color = if ms >= 1000 {
+                    Color32::RED
+                } else if ms >= 300 {
+                    Color32::YELLOW
+                } else {
+                    Color32::GRAY
+                };
+                RichText::new(format!("{}ms", ms)).color(color)
+            }
+            None => RichText::new("--ms").weak(),
+        };
+        let error_percent = stats.error_percent();
+        let error_color = if stats.last_failed() || error_percent >= 50.0 {
+            Color32::RED
+        } else if error_percent > 0.0 {
+            Color32::YELLOW
+        } else {
+            Color32::GRAY
+        };
+        let age = stats.last_checked
+            .map(|t| Self::format_age(Utc::now() - t))
+            .unwrap_or_default();
+        ui.label(latency.small())
+            .on_hover_text("最近一次成功探测的耗时");
+        ui.label(RichText::new(format!("{:.0}%", error_percent)).small().color(error_color))
+            .on_hover_text("最近探测的失败比例");
+        ui.label(RichText::new(age).small().weak())
+            .on_hover_text(stats.last_checked.map(|t| format!("上次检查: {}", t.format("%Y-%m-%d %H:%M:%S"))).unwrap_or_default());
+    }
+    
+    /// 将时间间隔格式化为紧凑的“多久之前”
+    fn format_span(span: chrono::Duration) -> String {
+        let secs = span.num_seconds().max(0);
+        if secs < 60 {
+            format!("{}秒", secs)
+        } else if secs < 3600 {
+            format!("{}分", secs / 60)
+        } else {
+            format!("{}时{}分", secs / 3600, secs % 3600 / 60)
+        }
+    }
+    
+    fn format_age(age: chrono::Duration) -> String {
+        let secs = age.num_seconds().max(0);
+        if secs < 60 {
+            format!("{}秒前", secs)
+        } else if secs < 3600 {
+            format!("{}分前", secs / 60)
+        } else {
+            format!("{}时前", secs / 3600)
         }
     }
 }
 
 impl eframe::App for App {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        repaint::frame_started();
+        let _frame_timer = diagnostics::FrameTimer::start();
+        
+        // 配置了本地用户时先登录，登录前不运行后台任务
+        if self.login.is_some() {
+            self.render_login(ctx);
+            return;
+        }
+        
+        // 处理界面截图
+        self.handle_screenshot(ctx);
+        
+        // 收取服务在后台提交的API请求结果
+        for entry in self.api_service.poll() {
+            self.push_log(entry);
+        }
+        
+        // 收取后台指标采集结果
+        for error in self.metrics_service.poll() {
+            self.push_log(error);
+        }
+        if self.metrics_auto_scrape && !self.background_paused {
+            let interval = Duration::from_secs(self.metrics_scrape_interval);
+            if self.last_metrics_scrape.is_none_or(|t| t.elapsed() >= interval) {
+                self.scrape_metrics();
+            }
+            ctx.request_repaint_after(interval);
+        }
+        
+        // 收取自动保存的写入错误，再同步其他实例对配置文件的修改
+        for error in self.auto_saver.take_errors() {
+            self.push_log(LogEntry::new("配置", &format!("自动保存失败: {}", error)));
+        }
+        self.sync_with_peers();
+        
+        // 记录界面操作时间，按间隔更新会话心跳，被强制登出或超时后退出
+        if ctx.input(|i| !i.events.is_empty()) {
+            self.last_input = Utc::now();
+        }
+        self.tick_session(ctx);
+        
+        // 释放不再显示的面板的轮询订阅
+        self.prune_poll_subscriptions();
+        ctx.request_repaint_after(CONFIG_SYNC_INTERVAL);
+        
+        // 收取立即检查的结果，不受后台任务暂停影响
+        let observations = self.health_service.poll_group_check();
+        if let Err(e) = self.alert_service.observe_health(&observations) {
+            tracing::error!("处理健康告警失败: {:#}", e);
+        } else if !observations.is_empty() {
+            self.load_alerts();
+        }
+        
+        // 按各中间层的设置轮询健康状态，并让关联Kubernetes或Docker的容器状态跟随实际运行情况
+        if !self.background_paused {
+            let observations = self.health_service.tick();
+            match self.alert_service.observe_health(&observations) {
+                Ok(0) => {}
+                Ok(_) => self.load_alerts(),
+                Err(e) => tracing::error!("处理健康告警失败: {:#}", e),
+            }
+            let removed = self.kubernetes_service.tick();
+            for stale in &removed {
+                let action = format!(
+                    "自动移除失联的发现后端 {}（业务组 {}，最后报告于 {}）",
+                    stale.backend_name,
+                    stale.group_name,
+                    stale.last_seen.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S"),
+                );
+                self.push_log(LogEntry::new("kubernetes", &action));
+                self.record_audit(&action, Some(EntityKind::Backend), Some(&stale.backend_id));
+            }
+            if !removed.is_empty() {
+                self.load_business_groups();
+            }
+            let exits = self.docker_service.tick();
+            for entry in self.supervisor_service.observe(exits) {
+                self.push_log(entry);
+            }
+            for event in self.supervisor_service.tick() {
+                self.push_log(event.log);
+                if let Some((group_id, middleware)) = event.warmup {
+                    self.warmup_service.begin(&GroupId::from(&group_id), middleware);
+                }
+            }
+            if !self.supervisor_service.pending().is_empty() {
+                ctx.request_repaint_after(Duration::from_secs(1));
+            }
+            self.stats_service.tick();
+            self.weight_service.tick();
+            for entry in self.log_stream_service.tick() {
+                self.push_log(entry);
+            }
+            if self.log_stream_service.is_active() {
+                ctx.request_repaint_after(Duration::from_secs(1));
+            }
+            let upgradable = self.image_update_service.tick();
+            if upgradable > 0 {
+                self.push_log(LogEntry::new("镜像", &format!("发现 {} 个容器的镜像可升级", upgradable)));
+            }
+            if self.image_update_service.is_checking() {
+                ctx.request_repaint_after(Duration::from_secs(1));
+            }
+        }
+        
+        // 维持SSH隧道，暂停后台任务时手动操作仍需经隧道访问
+        for entry in self.tunnel_service.tick() {
+            self.push_log(entry);
+        }
+        if self.tunnel_service.is_active() {
+            ctx.request_repaint_after(Duration::from_secs(1));
+        }
+        self.poll_image_pull();
+        self.poll_container_actions();
+        self.console_service.poll();
+        if self.console_service.running().is_some() {
+            ctx.request_repaint_after(Duration::from_millis(100));
+        }
+        if self.image_service.is_pulling() {
+            ctx.request_repaint_after(Duration::from_millis(100));
+        }
+        self.poll_weight_adjustments();
+        if self.weight_service.is_busy() {
+            ctx.request_repaint_after(Duration::from_millis(500));
+        }
+        self.poll_kubernetes_discovery();
+        if self.kubernetes_service.is_discovering() {
+            ctx.request_repaint_after(Duration::from_millis(200));
+        }
+        
+        // 收取启动预热结果
+        for entry in self.warmup_service.poll() {
+            self.push_log(entry);
+        }
+        if self.warmup_service.is_warming() {
+            ctx.request_repaint_after(Duration::from_millis(500));
+        }
+        
+        // 执行外部Webhook触发的操作
+        self.poll_webhooks();
+        if self.webhook_server.is_some() {
+            ctx.request_repaint_after(Duration::from_millis(500));
+        }
+        
+        // 共享状态变更后刷新界面数据
+        self.process_model_events();
+        
+        // 收取远程命令结果
+        self.poll_remote_command();
+        
+        // 收取Agent部署与检查结果
+        self.poll_agent_runs();
+        if self.agent_service.is_running() {
+            ctx.request_repaint_after(Duration::from_millis(200));
+        }
+        
+        // 收取迁移步骤结果
+        if let Some(entry) = self.migration_service.poll() {
+            self.push_log(entry);
+        }
+        if self.migration_service.is_running() {
+            ctx.request_repaint_after(Duration::from_millis(200));
+        }
+        
+        // 收取数据校验报告
+        if let Some(report) = self.verification_service.poll() {
+            self.push_log(LogEntry::new("数据校验", &format!("校验完成: 通过 {}，失败 {}", report.passed(), report.failed())));
+            self.verify_report = Some(report);
+        }
+        
+        // 收取链路追踪检索结果
+        if let Some(search) = self.trace_service.poll() {
+            self.trace_search = Some(search);
+        }
+        
+        // 收取接口调试结果
+        self.poll_playground();
+        // 收取大数据加解密的进度与结果
+        if let Some(result) = self.payload_service.poll() {
+            if let Err(e) = &result {
+                self.push_log(LogEntry::new("大数据加解密", &format!("处理失败: {:#}", e)));
+            }
+            self.payload_result = Some(result.map_err(|e| format!("{:#}", e)));
+        }
+        if self.payload_service.is_running() {
+            ctx.request_repaint_after(Duration::from_millis(100));
+        }
+        
+        // 收取后台任务结果
+        self.poll_jobs();
+        if self.job_service.has_active_jobs() {
+            ctx.request_repaint_after(Duration::from_millis(200));
+        }
+        
+        // 重试写入排队的配置；关闭窗口前先尝试写入，仍失败时请用户确认
+        if self.config_manager.pending_save().is_some() {
+            self.retry_pending_save(false);
+            ctx.request_repaint_after(Duration::from_secs(1));
+        }
+        if ctx.input(|i| i.viewport().close_requested())
+            && let Err(e) = self.state_store.flush()
+        {
+            self.push_log(LogEntry::new("配置", &format!("退出前保存失败: {:#}", e)));
+        }
+        if ctx.input(|i| i.viewport().close_requested())
+            && let Err(e) = self.session_service.end()
+        {
+            tracing::warn!("注销会话失败: {:#}", e);
+        }
+        if ctx.input(|i| i.viewport().close_requested())
+            && !self.exit_confirmed
+            && matches!(self.config_manager.retry_pending(true), Some(Err(_)))
+        {
+            ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+            self.show_unsaved_exit_dialog = true;
+        }
+        
         // 顶部菜单栏
         TopBottomPanel::top("menu_bar").show(ctx, |ui| {
             self.render_menu_bar(ui);
         });
         
+        // 配置写入失败提示
+        if self.config_manager.pending_save().is_some() {
+            TopBottomPanel::top("pending_save_banner").show(ctx, |ui| {
+                self.render_pending_save_banner(ui);
+            });
+        }
+        
+        // 配置加载失败时的恢复提示
+        if self.config_manager.is_read_only() {
+            TopBottomPanel::top("recovery_banner").show(ctx, |ui| {
+                self.render_recovery_banner(ui);
+            });
+        }
+        
+        // 后台任务暂停提示
+        if self.background_paused {
+            TopBottomPanel::top("paused_banner").show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.colored_label(Color32::YELLOW, "后台任务已暂停：健康轮询、指标自动采集与集群状态同步均已停止。");
+                    if ui.button("恢复").clicked() {
+                        self.set_background_paused(false);
+                    }
+                });
+            });
+        }
+        
+        // 状态筛选栏
+        TopBottomPanel::top("filter_bar").show(ctx, |ui| {
+            self.render_filter_bar(ui);
+        });
+        
         // 左侧导航面板
         SidePanel::left("side_panel").show(ctx, |ui| {
             self.render_side_panel(ui);
         });
         
+        // 其他入口可能跳转到当前界面配置隐藏的页面
+        if !self.current_tab.visible_in(self.ui_profile) {
+            self.current_tab = AppTab::Monitor;
+        }
+        
         // 主内容区域
         CentralPanel::default().show(ctx, |ui| {
             match self.current_tab {
+                AppTab::Dashboard => self.render_dashboard_tab(ui),
                 AppTab::BusinessGroups => self.render_business_groups_tab(ui),
                 AppTab::Middleware => self.render_middleware_tab(ui),
                 AppTab::Backend => self.render_backend_tab(ui),
                 AppTab::Config => self.render_config_tab(ui),
                 AppTab::Monitor => self.render_monitor_tab(ui),
                 AppTab::Logs => self.render_logs_tab(ui),
+                AppTab::Jobs => self.render_jobs_tab(ui),
+                AppTab::Playground => self.render_playground_tab(ui),
+                AppTab::Verify => self.render_verify_tab(ui),
+                AppTab::Migration => self.render_migration_tab(ui),
+                AppTab::DockerHosts => self.render_docker_hosts_tab(ui),
+                AppTab::Network => self.render_network_tab(ui),
+                AppTab::Console => self.render_console_tab(ui),
             }
         });
         
         // 底部状态栏
         TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
             ui.horizontal(|ui| {
-                ui.label(format!("当前选中: {:?}", self.current_tab));
+                ui.label(format!("当前选中: {}", self.current_tab.label()));
+                ui.add_space(10.0);
+                ui.label(self.ui_profile.label()).on_hover_text(self.ui_profile.description());
                 ui.add_space(10.0);
                 ui.label(format!("业务组数量: {}", self.business_groups.len()));
+                ui.add_space(10.0);
+                let anomaly_text = format!("异常日志: {}", self.anomaly_detector.hit_count);
+                if self.anomaly_detector.hit_count > 0 {
+                    ui.label(RichText::new(anomaly_text).color(Color32::from_rgb(255, 165, 0)));
+                } else {
+                    ui.label(anomaly_text);
+                }
+                ui.add_space(10.0);
+                let unacknowledged = self.alerts.iter().filter(|a| !a.acknowledged).count();
+                let alert_text = format!("未确认告警: {}", unacknowledged);
+                if unacknowledged > 0 {
+                    ui.label(RichText::new(alert_text).color(Color32::RED));
+                } else {
+                    ui.label(alert_text);
+                }
             });
         });
         
         // 对话框
         self.render_new_group_dialog(ctx);
+        self.render_unsaved_exit_dialog(ctx);
         self.render_new_middleware_dialog(ctx);
         self.render_new_backend_dialog(ctx);
+        self.render_config_propagation_dialog(ctx);
+        self.render_diagnostics_overlay(ctx);
+        self.render_tuning_dialog(ctx);
+        self.render_scale_out_dialog(ctx);
+        self.render_scale_in_dialog(ctx);
+        self.render_plan_dialog(ctx);
+        self.render_snapshot_dialog(ctx);
+        self.render_image_pull_dialog(ctx);
+        self.render_group_check_dialog(ctx);
     }
 }
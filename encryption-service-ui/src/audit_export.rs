@@ -0,0 +1,419 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, NaiveDate, TimeZone, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt::Write as _;
+
+use crate::models::{AuditChainAnchor, AuditEntry};
+
+/// 哈希链首条记录的前一哈希
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// 审计日志导出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditExportFormat {
+    Csv,
+    Json,
+}
+
+impl AuditExportFormat {
+    /// 所有导出格式
+    pub const ALL: [AuditExportFormat; 2] = [AuditExportFormat::Csv, AuditExportFormat::Json];
+
+    /// 显示名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            AuditExportFormat::Csv => "CSV",
+            AuditExportFormat::Json => "JSON",
+        }
+    }
+
+    /// 文件扩展名
+    pub fn extension(&self) -> &'static str {
+        match self {
+            AuditExportFormat::Csv => "csv",
+            AuditExportFormat::Json => "json",
+        }
+    }
+}
+
+/// 导出记录的防篡改方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditIntegrity {
+    /// 不附加校验信息
+    None,
+    /// 每条记录附加SHA-256哈希，哈希包含前一条记录的哈希，删改或重排任一条都会使之后的哈希失配
+    HashChain,
+    /// 与哈希链相同，但以签名密钥计算HMAC-SHA256，没有密钥无法重新生成整条链
+    HmacSha256,
+}
+
+impl AuditIntegrity {
+    /// 所有防篡改方式
+    pub const ALL: [AuditIntegrity; 3] = [AuditIntegrity::None, AuditIntegrity::HashChain, AuditIntegrity::HmacSha256];
+
+    /// 显示名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            AuditIntegrity::None => "无",
+            AuditIntegrity::HashChain => "哈希链",
+            AuditIntegrity::HmacSha256 => "HMAC签名链",
+        }
+    }
+
+    /// 是否需要签名密钥
+    pub fn needs_key(&self) -> bool {
+        *self == AuditIntegrity::HmacSha256
+    }
+}
+
+/// 审计日志筛选条件，空字段不筛选
+#[derive(Debug, Clone, Default)]
+pub struct AuditFilter {
+    /// 起始时间（含）
+    pub from: Option<DateTime<Utc>>,
+    /// 截止时间（不含）
+    pub to: Option<DateTime<Utc>>,
+    /// 操作人，精确匹配
+    pub actor: String,
+    /// 实体ID包含该文本，或等于实体类型名称
+    pub entity: String,
+    /// 操作描述包含该文本，不区分大小写
+    pub action: String,
+}
+
+impl AuditFilter {
+    /// 以本地日期解析时间范围，截止日期当天包含在内；日期格式为 `YYYY-MM-DD`，留空不限
+    pub fn with_days(mut self, from: &str, to: &str) -> Result<Self> {
+        self.from = parse_day(from)?;
+        self.to = parse_day(to)?.map(|day| day + chrono::Duration::days(1));
+        Ok(self)
+    }
+
+    /// 条目是否符合筛选条件
+    pub fn matches(&self, entry: &AuditEntry) -> bool {
+        let actor = self.actor.trim();
+        let entity = self.entity.trim();
+        let action = self.action.trim().to_lowercase();
+        self.from.is_none_or(|from| entry.timestamp >= from)
+            && self.to.is_none_or(|to| entry.timestamp < to)
+            && (actor.is_empty() || entry.actor == actor)
+            && (entity.is_empty()
+                || entry.entity_id.as_deref().is_some_and(|id| id.contains(entity))
+                || entry.entity_kind.is_some_and(|kind| kind.label() == entity))
+            && (action.is_empty() || entry.action.to_lowercase().contains(&action))
+    }
+}
+
+/// 解析本地日期为当天零点
+fn parse_day(text: &str) -> Result<Option<DateTime<Utc>>> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(None);
+    }
+    let day = NaiveDate::parse_from_str(text, "%Y-%m-%d").map_err(|_| anyhow::anyhow!("日期格式应为 YYYY-MM-DD: {}", text))?;
+    let midnight = day.and_hms_opt(0, 0, 0).expect("零点总是有效的时间");
+    let local = Local
+        .from_local_datetime(&midnight)
+        .earliest()
+        .ok_or_else(|| anyhow::anyhow!("本地时间不存在: {}", text))?;
+    Ok(Some(local.with_timezone(&Utc)))
+}
+
+/// 导出的一条审计记录，附带防篡改哈希
+#[derive(Debug, Serialize, Deserialize)]
+struct ExportedEntry {
+    #[serde(flatten)]
+    entry: AuditEntry,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    prev_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    hash: Option<String>,
+}
+
+/// JSON导出文件
+#[derive(Debug, Serialize, Deserialize)]
+struct AuditExport {
+    exported_at: DateTime<Utc>,
+    exported_by: String,
+    integrity: AuditIntegrity,
+    /// 最后一条记录的哈希，单独保存它即可发现导出文件被截断
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    head_hash: Option<String>,
+    entries: Vec<ExportedEntry>,
+}
+
+/// 计算一条记录的链式哈希：对前一哈希与记录的JSON计算SHA-256或HMAC-SHA256
+fn chain_hash(integrity: AuditIntegrity, key: &str, prev_hash: &str, entry: &AuditEntry) -> Result<String> {
+    let json = serde_json::to_string(entry).context("无法序列化审计日志")?;
+    let hash = match integrity {
+        AuditIntegrity::None => return Ok(String::new()),
+        AuditIntegrity::HashChain => {
+            let mut hasher = Sha256::new();
+            hasher.update(prev_hash.as_bytes());
+            hasher.update(json.as_bytes());
+            hex::encode(hasher.finalize())
+        }
+        AuditIntegrity::HmacSha256 => {
+            let mut mac = Hmac::<Sha256>::new_from_slice(key.as_bytes()).expect("HMAC接受任意长度的密钥");
+            mac.update(prev_hash.as_bytes());
+            mac.update(json.as_bytes());
+            hex::encode(mac.finalize().into_bytes())
+        }
+    };
+    Ok(hash)
+}
+
+/// 按时间顺序为条目生成导出记录
+fn chain(entries: Vec<AuditEntry>, integrity: AuditIntegrity, key: &str) -> Result<Vec<ExportedEntry>> {
+    if integrity.needs_key() && key.is_empty() {
+        anyhow::bail!("{}需要签名密钥", integrity.label());
+    }
+    let mut prev_hash = GENESIS_HASH.to_string();
+    let mut exported = Vec::with_capacity(entries.len());
+    for entry in entries {
+        if integrity == AuditIntegrity::None {
+            exported.push(ExportedEntry { entry, prev_hash: None, hash: None });
+            continue;
+        }
+        let hash = chain_hash(integrity, key, &prev_hash, &entry)?;
+        exported.push(ExportedEntry {
+            entry,
+            prev_hash: Some(std::mem::replace(&mut prev_hash, hash.clone())),
+            hash: Some(hash),
+        });
+    }
+    Ok(exported)
+}
+
+/// 存储的审计日志哈希链的校验结果
+#[derive(Debug, Clone)]
+pub struct AuditChainStatus {
+    /// 校验通过的条数
+    pub verified: usize,
+    /// 日志开头没有哈希的旧记录条数，由哈希链起点整体校验
+    pub legacy: usize,
+    /// 末条记录的哈希，另行保存后可发现末尾记录被删除
+    pub head: Option<String>,
+}
+
+/// 计算存储的审计记录的链式哈希，哈希不包含记录自身的哈希字段
+fn stored_hash(prev_hash: &str, entry: &AuditEntry) -> Result<String> {
+    let unsealed = AuditEntry { chain_hash: None, ..entry.clone() };
+    chain_hash(AuditIntegrity::HashChain, "", prev_hash, &unsealed)
+}
+
+/// 将旧记录依次折叠为哈希链的起始哈希，旧记录因此同样受链保护
+fn fold_legacy(legacy: &[AuditEntry]) -> Result<String> {
+    legacy.iter().try_fold(GENESIS_HASH.to_string(), |prev_hash, entry| stored_hash(&prev_hash, entry))
+}
+
+/// 为新条目计算链式哈希并追加到审计日志末尾；第一次追加时把已有的记录作为旧记录记入链的起点
+///
+/// 已有哈希但没有起点说明起点被删除，此时不重新建立起点，校验仍然失败
+pub fn append(log: &mut Vec<AuditEntry>, anchor: &mut Option<AuditChainAnchor>, mut entry: AuditEntry) -> Result<()> {
+    let prev_hash = match (log.last().and_then(|e| e.chain_hash.clone()), anchor.as_ref()) {
+        (Some(hash), _) => hash,
+        (None, Some(anchor)) => anchor.genesis.clone(),
+        (None, None) if log.iter().any(|e| e.chain_hash.is_some()) => GENESIS_HASH.to_string(),
+        (None, None) => {
+            let genesis = fold_legacy(log)?;
+            *anchor = Some(AuditChainAnchor { legacy: log.len(), genesis: genesis.clone() });
+            genesis
+        }
+    };
+    entry.chain_hash = Some(stored_hash(&prev_hash, &entry)?);
+    log.push(entry);
+    Ok(())
+}
+
+/// 校验存储的审计日志哈希链，发现修改、删除或重排历史记录时返回错误
+///
+/// 只有起点记录的开头若干条旧记录可以没有哈希，它们的内容由起始哈希校验
+pub fn verify_log(log: &[AuditEntry], anchor: Option<&AuditChainAnchor>) -> Result<AuditChainStatus> {
+    let Some(anchor) = anchor else {
+        if let Some(index) = log.iter().position(|e| e.chain_hash.is_some()) {
+            anyhow::bail!("第 {} 条记录有哈希但哈希链起点缺失，起点被删除", index + 1);
+        }
+        return Ok(AuditChainStatus { verified: 0, legacy: log.len(), head: None });
+    };
+    let legacy = anchor.legacy;
+    if legacy > log.len() {
+        anyhow::bail!("哈希链起点记录了 {} 条旧记录，日志只有 {} 条，记录被删除", legacy, log.len());
+    }
+    if let Some(index) = log[..legacy].iter().position(|e| e.chain_hash.is_some()) {
+        anyhow::bail!("第 {} 条旧记录带有哈希，记录被重排或替换", index + 1);
+    }
+    if fold_legacy(&log[..legacy])? != anchor.genesis {
+        anyhow::bail!("开头 {} 条旧记录与哈希链起点不符，旧记录被修改或删除", legacy);
+    }
+    let mut prev_hash = anchor.genesis.clone();
+    for (index, entry) in log.iter().enumerate().skip(legacy) {
+        let Some(recorded) = &entry.chain_hash else {
+            anyhow::bail!("第 {} 条记录（{}）缺少哈希，记录被修改", index + 1, entry.action);
+        };
+        let hash = stored_hash(&prev_hash, entry)?;
+        if *recorded != hash {
+            anyhow::bail!("第 {} 条记录（{}）的哈希不符，该记录被修改或其前面的记录被删除", index + 1, entry.action);
+        }
+        prev_hash = hash;
+    }
+    Ok(AuditChainStatus {
+        verified: log.len() - legacy,
+        legacy,
+        head: log.last().and_then(|e| e.chain_hash.clone()),
+    })
+}
+
+/// 转义CSV字段
+fn escape_csv(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// 将审计日志按格式渲染为文本，条目按时间排序后生成哈希链
+pub fn render(mut entries: Vec<AuditEntry>, format: AuditExportFormat, integrity: AuditIntegrity, key: &str, exported_by: &str) -> Result<String> {
+    entries.sort_by_key(|e| e.timestamp);
+    let exported = chain(entries, integrity, key)?;
+    match format {
+        AuditExportFormat::Json => {
+            let export = AuditExport {
+                exported_at: Utc::now(),
+                exported_by: exported_by.to_string(),
+                integrity,
+                head_hash: exported.last().and_then(|e| e.hash.clone()),
+                entries: exported,
+            };
+            serde_json::to_string_pretty(&export).context("无法序列化审计日志")
+        }
+        AuditExportFormat::Csv => {
+            let mut out = String::from("id,timestamp,actor,action,entity_kind,entity_id,trace_id,prev_hash,hash\n");
+            for record in &exported {
+                let entry = &record.entry;
+                let fields = [
+                    entry.id.clone(),
+                    entry.timestamp.to_rfc3339(),
+                    entry.actor.clone(),
+                    entry.action.clone(),
+                    entry.entity_kind.map(|k| k.label().to_string()).unwrap_or_default(),
+                    entry.entity_id.clone().unwrap_or_default(),
+                    entry.trace_id.clone().unwrap_or_default(),
+                    record.prev_hash.clone().unwrap_or_default(),
+                    record.hash.clone().unwrap_or_default(),
+                ];
+                let line: Vec<String> = fields.iter().map(|f| escape_csv(f)).collect();
+                let _ = writeln!(out, "{}", line.join(","));
+            }
+            Ok(out)
+        }
+    }
+}
+
+/// 校验JSON导出文件的哈希链，返回校验通过的条数；HMAC签名链需要导出时的签名密钥
+pub fn verify(content: &str, key: &str) -> Result<usize> {
+    let export: AuditExport = serde_json::from_str(content).context("不是有效的审计日志JSON导出文件")?;
+    if export.integrity == AuditIntegrity::None {
+        anyhow::bail!("导出文件未附加防篡改信息，无法校验");
+    }
+    if export.integrity.needs_key() && key.is_empty() {
+        anyhow::bail!("{}需要签名密钥", export.integrity.label());
+    }
+    let mut prev_hash = GENESIS_HASH.to_string();
+    for (index, record) in export.entries.iter().enumerate() {
+        if record.prev_hash.as_deref() != Some(prev_hash.as_str()) {
+            anyhow::bail!("第 {} 条记录的前一哈希不符，记录被删除或重排", index + 1);
+        }
+        let hash = chain_hash(export.integrity, key, &prev_hash, &record.entry)?;
+        if record.hash.as_deref() != Some(hash.as_str()) {
+            anyhow::bail!("第 {} 条记录的哈希不符，记录被修改{}", index + 1, if export.integrity.needs_key() { "或密钥错误" } else { "" });
+        }
+        prev_hash = hash;
+    }
+    let head = export.entries.last().and_then(|e| e.hash.as_deref());
+    if export.head_hash.as_deref() != head {
+        anyhow::bail!("末条哈希与文件记录的不符，导出文件被截断");
+    }
+    Ok(export.entries.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(action: &str) -> AuditEntry {
+        AuditEntry::new("tester", action, None, None)
+    }
+
+    /// 两条旧记录之后追加三条带哈希的记录
+    fn sealed_log() -> (Vec<AuditEntry>, Option<AuditChainAnchor>) {
+        let mut log = vec![entry("旧记录1"), entry("旧记录2")];
+        let mut anchor = None;
+        for action in ["操作1", "操作2", "操作3"] {
+            append(&mut log, &mut anchor, entry(action)).unwrap();
+        }
+        (log, anchor)
+    }
+
+    #[test]
+    fn append_folds_legacy_entries_into_genesis() {
+        let (log, anchor) = sealed_log();
+        let anchor = anchor.unwrap();
+        assert_eq!(anchor.legacy, 2);
+        assert_eq!(anchor.genesis, fold_legacy(&log[..2]).unwrap());
+
+        let status = verify_log(&log, Some(&anchor)).unwrap();
+        assert_eq!((status.verified, status.legacy), (3, 2));
+        assert_eq!(status.head, log[4].chain_hash);
+    }
+
+    #[test]
+    fn verify_log_detects_tampering() {
+        let (log, anchor) = sealed_log();
+        let anchor = anchor.unwrap();
+
+        let mut modified = log.clone();
+        modified[3].action = "篡改".to_string();
+        assert!(verify_log(&modified, Some(&anchor)).is_err());
+
+        let mut legacy_modified = log.clone();
+        legacy_modified[0].actor = "someone".to_string();
+        assert!(verify_log(&legacy_modified, Some(&anchor)).is_err());
+
+        let mut removed = log.clone();
+        removed.remove(2);
+        assert!(verify_log(&removed, Some(&anchor)).is_err());
+
+        let mut reordered = log.clone();
+        reordered.swap(3, 4);
+        assert!(verify_log(&reordered, Some(&anchor)).is_err());
+
+        // 删除起点后不能把带哈希的记录当作旧记录
+        assert!(verify_log(&log, None).is_err());
+    }
+
+    #[test]
+    fn append_without_anchor_keeps_chain_broken() {
+        let (mut log, _) = sealed_log();
+        let mut anchor = None;
+        append(&mut log, &mut anchor, entry("操作4")).unwrap();
+        assert!(anchor.is_none());
+        assert!(verify_log(&log, None).is_err());
+    }
+
+    #[test]
+    fn exported_chain_verifies_and_detects_truncation() {
+        let (log, _) = sealed_log();
+        let content = render(log, AuditExportFormat::Json, AuditIntegrity::HmacSha256, "secret", "tester").unwrap();
+        assert_eq!(verify(&content, "secret").unwrap(), 5);
+        assert!(verify(&content, "wrong").is_err());
+
+        let mut export: AuditExport = serde_json::from_str(&content).unwrap();
+        export.entries.pop();
+        assert!(verify(&serde_json::to_string(&export).unwrap(), "secret").is_err());
+    }
+}
@@ -0,0 +1,393 @@
+use crate::metrics::{EndpointKind, MetricsStore};
+use crate::models::{BackendContainer, ContainerStatus, SchedulerStrategy};
+
+/// M/M/1模型中响应时间的P95为均值的 ln(20) 倍
+const P95_FACTOR: f64 = 2.995_732_273_553_991;
+
+/// 每种类型推荐的实例数上限，超出时说明单实例容量或目标利用率的输入不合理
+pub const MAX_PLANNED_INSTANCES: u32 = 1000;
+
+/// 容量规划的输入
+#[derive(Debug, Clone, PartialEq)]
+pub struct CapacityInput {
+    /// 目标每秒请求数
+    pub target_qps: f64,
+    /// 读请求占比 (0.0 - 1.0)
+    pub read_ratio: f64,
+    /// 单实例的最大吞吐（每秒请求数）
+    pub instance_capacity: f64,
+    /// 无排队时单个请求的延迟（毫秒）
+    pub base_latency_ms: f64,
+    /// 规划时单实例的目标利用率 (0.0 - 1.0)，留出余量应对突发
+    pub target_utilization: f64,
+    pub strategy: SchedulerStrategy,
+}
+
+impl CapacityInput {
+    fn read_qps(&self) -> f64 {
+        self.target_qps * self.read_ratio.clamp(0.0, 1.0)
+    }
+
+    fn write_qps(&self) -> f64 {
+        self.target_qps - self.read_qps()
+    }
+}
+
+/// 参与模拟的一组相同实例，同组的实例承担相同的负载
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimInstance {
+    pub name: String,
+    /// read、write 或 mixed
+    pub instance_type: String,
+    /// 单个实例的权重
+    pub weight: u32,
+    /// 实例数，多副本的后端为副本数
+    pub count: u32,
+}
+
+impl SimInstance {
+    /// 由后端生成，每个后端为一组，副本数即实例数
+    pub fn from_backends(backends: &[BackendContainer]) -> Vec<Self> {
+        backends
+            .iter()
+            .map(|b| SimInstance {
+                name: b.name.clone(),
+                instance_type: b.instance_type.clone(),
+                weight: b.weight,
+                count: b.replicas.max(1),
+            })
+            .collect()
+    }
+}
+
+/// 一组实例在目标负载下的估算，负载与延迟均为单个实例的值
+#[derive(Debug, Clone, PartialEq)]
+pub struct InstanceEstimate {
+    pub name: String,
+    pub instance_type: String,
+    pub count: u32,
+    pub load_qps: f64,
+    pub utilization: f64,
+    /// 平均与P95延迟（毫秒），过载时为空
+    pub mean_latency_ms: Option<f64>,
+    pub p95_latency_ms: Option<f64>,
+}
+
+/// 读写与混合实例的数量
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Topology {
+    pub read: u32,
+    pub write: u32,
+    pub mixed: u32,
+}
+
+impl Topology {
+    /// 统计实例的类型
+    pub fn of(instances: &[SimInstance]) -> Self {
+        let mut topology = Self::default();
+        for instance in instances {
+            let count = match instance.instance_type.as_str() {
+                "read" => &mut topology.read,
+                "write" => &mut topology.write,
+                _ => &mut topology.mixed,
+            };
+            *count = count.saturating_add(instance.count);
+        }
+        topology
+    }
+
+    /// 生成模拟用的实例组，每种类型一组，权重相同
+    pub fn instances(&self) -> Vec<SimInstance> {
+        [("read", "读实例", self.read), ("write", "写实例", self.write), ("mixed", "混合实例", self.mixed)]
+            .into_iter()
+            .filter(|(_, _, count)| *count > 0)
+            .map(|(kind, name, count)| SimInstance {
+                name: name.to_string(),
+                instance_type: kind.to_string(),
+                weight: 1,
+                count,
+            })
+            .collect()
+    }
+
+    /// 实例总数
+    pub fn total(&self) -> u32 {
+        self.read + self.write + self.mixed
+    }
+}
+
+/// 推荐的拓扑与其模拟结果
+#[derive(Debug, Clone)]
+pub struct CapacityPlan {
+    pub topology: Topology,
+    pub estimates: Vec<InstanceEstimate>,
+    /// 所选策略无法满足目标等需要提示的问题
+    pub warnings: Vec<String>,
+}
+
+/// 按M/M/1排队模型估算实例的延迟：利用率为ρ时平均延迟为基础延迟的 1/(1-ρ) 倍
+fn estimate(instance: &SimInstance, count: u32, load_qps: f64, input: &CapacityInput) -> InstanceEstimate {
+    let utilization = if input.instance_capacity > 0.0 { load_qps / input.instance_capacity } else { f64::INFINITY };
+    let mean = (utilization < 1.0).then(|| input.base_latency_ms / (1.0 - utilization));
+    InstanceEstimate {
+        name: instance.name.clone(),
+        instance_type: instance.instance_type.clone(),
+        count,
+        load_qps,
+        utilization,
+        mean_latency_ms: mean,
+        p95_latency_ms: mean.map(|m| m * P95_FACTOR),
+    }
+}
+
+/// 模拟目标负载按策略在实例间的分配：单容器模式全部发往第一个实例；
+/// 读写分离按请求类型在可处理的实例间轮询；负载均衡按权重分配。
+/// 每组实例一行估算，单容器模式下第一组中未分到负载的其余实例另起一行
+pub fn simulate(input: &CapacityInput, instances: &[SimInstance]) -> Vec<InstanceEstimate> {
+    let mut loads = vec![0.0; instances.len()];
+    match input.strategy {
+        SchedulerStrategy::Single => {
+            let mut estimates = Vec::with_capacity(instances.len() + 1);
+            for (index, instance) in instances.iter().enumerate() {
+                if index == 0 {
+                    estimates.push(estimate(instance, 1, input.target_qps, input));
+                    if instance.count > 1 {
+                        estimates.push(estimate(instance, instance.count - 1, 0.0, input));
+                    }
+                } else {
+                    estimates.push(estimate(instance, instance.count, 0.0, input));
+                }
+            }
+            return estimates;
+        }
+        SchedulerStrategy::ReadWriteSplit => {
+            for (qps, accepted) in [(input.read_qps(), ["read", "mixed"]), (input.write_qps(), ["write", "mixed"])] {
+                let accepts = |i: &SimInstance| accepted.contains(&i.instance_type.as_str());
+                let serving: f64 = instances.iter().filter(|i| accepts(i)).map(|i| i.count as f64).sum();
+                for (load, instance) in loads.iter_mut().zip(instances) {
+                    if accepts(instance) {
+                        *load += qps / serving;
+                    }
+                }
+            }
+        }
+        SchedulerStrategy::LoadBalance => {
+            let total: f64 = instances.iter().map(|i| i.weight as f64 * i.count as f64).sum();
+            let count: f64 = instances.iter().map(|i| i.count as f64).sum();
+            for (load, instance) in loads.iter_mut().zip(instances) {
+                *load = if total > 0.0 {
+                    input.target_qps * instance.weight as f64 / total
+                } else {
+                    input.target_qps / count
+                };
+            }
+        }
+    }
+    instances.iter().zip(loads).map(|(instance, load)| estimate(instance, instance.count, load, input)).collect()
+}
+
+/// 请求没有可处理的实例时的提示，如读写分离下缺少写实例
+pub fn unserved(input: &CapacityInput, instances: &[SimInstance]) -> Option<String> {
+    if instances.is_empty() {
+        return Some("没有后端实例".to_string());
+    }
+    if input.strategy != SchedulerStrategy::ReadWriteSplit {
+        return None;
+    }
+    let topology = Topology::of(instances);
+    if input.read_qps() > 0.0 && topology.read + topology.mixed == 0 {
+        return Some("没有可处理读请求的实例".to_string());
+    }
+    if input.write_qps() > 0.0 && topology.write + topology.mixed == 0 {
+        return Some("没有可处理写请求的实例".to_string());
+    }
+    None
+}
+
+/// 按所选策略推荐使每个实例的利用率不超过目标值的最少实例数，每种类型不超过 [`MAX_PLANNED_INSTANCES`]
+pub fn plan(input: &CapacityInput) -> CapacityPlan {
+    let per_instance = input.instance_capacity * input.target_utilization.clamp(0.01, 1.0);
+    let needed = |qps: f64| if qps <= 0.0 { 0 } else { (qps / per_instance).ceil() as u32 };
+    let mut warnings = Vec::new();
+    let mut topology = match input.strategy {
+        SchedulerStrategy::Single => {
+            if needed(input.target_qps) > 1 {
+                warnings.push(format!(
+                    "单容器模式下所有请求由一个实例处理，目标负载需要 {} 个实例的容量，建议改用读写分离或负载均衡",
+                    needed(input.target_qps),
+                ));
+            }
+            Topology { mixed: 1, ..Topology::default() }
+        }
+        SchedulerStrategy::ReadWriteSplit => Topology {
+            read: needed(input.read_qps()).max(1),
+            write: needed(input.write_qps()).max(1),
+            mixed: 0,
+        },
+        SchedulerStrategy::LoadBalance => Topology { mixed: needed(input.target_qps).max(1), ..Topology::default() },
+    };
+    for count in [&mut topology.read, &mut topology.write, &mut topology.mixed] {
+        if *count > MAX_PLANNED_INSTANCES {
+            warnings.push(format!(
+                "目标负载需要 {} 个同类实例，超过规划上限 {}，请检查单实例容量与目标利用率",
+                count, MAX_PLANNED_INSTANCES,
+            ));
+            *count = MAX_PLANNED_INSTANCES;
+        }
+    }
+    let estimates = simulate(input, &topology.instances());
+    CapacityPlan { topology, estimates, warnings }
+}
+
+/// 观测到的单实例吞吐：各次采集中中间层所有接口的每秒请求数峰值，按策略除以处理请求的运行中实例数；
+/// 是负载而非容量的下限估计，没有采集到请求时为空
+pub fn observed_instance_throughput(store: &MetricsStore, middleware_id: &str, strategy: &SchedulerStrategy, backends: &[BackendContainer]) -> Option<f64> {
+    let ids = [middleware_id.to_string()];
+    let peak = EndpointKind::ALL
+        .iter()
+        .map(|kind| store.group_rate_series(&ids, *kind))
+        .fold(Vec::<f64>::new(), |mut totals, series| {
+            for (index, point) in series.iter().enumerate() {
+                match totals.get_mut(index) {
+                    Some(total) => *total += point.qps,
+                    None => totals.push(point.qps),
+                }
+            }
+            totals
+        })
+        .into_iter()
+        .fold(0.0, f64::max);
+    if peak <= 0.0 {
+        return None;
+    }
+    let serving = match strategy {
+        SchedulerStrategy::Single => 1,
+        _ => backends
+            .iter()
+            .filter(|b| b.status == ContainerStatus::Running)
+            .map(|b| b.replicas.max(1))
+            .sum::<u32>()
+            .max(1),
+    };
+    Some(peak / serving as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(strategy: SchedulerStrategy) -> CapacityInput {
+        CapacityInput {
+            target_qps: 1000.0,
+            read_ratio: 0.8,
+            instance_capacity: 200.0,
+            base_latency_ms: 10.0,
+            target_utilization: 0.5,
+            strategy,
+        }
+    }
+
+    fn instance(instance_type: &str, weight: u32, count: u32) -> SimInstance {
+        SimInstance { name: instance_type.to_string(), instance_type: instance_type.to_string(), weight, count }
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn latency_follows_queueing_model() {
+        let input = input(SchedulerStrategy::LoadBalance);
+        let half = estimate(&instance("mixed", 1, 1), 1, 100.0, &input);
+        assert_close(half.utilization, 0.5);
+        assert_close(half.mean_latency_ms.unwrap(), 20.0);
+        assert_close(half.p95_latency_ms.unwrap(), 20.0 * 20f64.ln());
+
+        let idle = estimate(&instance("mixed", 1, 1), 1, 0.0, &input);
+        assert_close(idle.mean_latency_ms.unwrap(), 10.0);
+
+        // 满载与过载时没有稳定的延迟
+        assert_eq!(estimate(&instance("mixed", 1, 1), 1, 200.0, &input).mean_latency_ms, None);
+        let overloaded = estimate(&instance("mixed", 1, 1), 1, 300.0, &input);
+        assert_close(overloaded.utilization, 1.5);
+        assert_eq!(overloaded.p95_latency_ms, None);
+
+        let no_capacity = CapacityInput { instance_capacity: 0.0, ..input };
+        assert_eq!(estimate(&instance("mixed", 1, 1), 1, 1.0, &no_capacity).mean_latency_ms, None);
+    }
+
+    #[test]
+    fn load_balance_splits_by_weight() {
+        let input = input(SchedulerStrategy::LoadBalance);
+        let estimates = simulate(&input, &[instance("mixed", 3, 1), instance("mixed", 1, 2)]);
+        assert_close(estimates[0].load_qps, 600.0);
+        assert_close(estimates[1].load_qps, 200.0);
+        assert_eq!(estimates[1].count, 2);
+
+        // 权重全为零时平均分配
+        let estimates = simulate(&input, &[instance("mixed", 0, 1), instance("mixed", 0, 3)]);
+        assert_close(estimates[0].load_qps, 250.0);
+        assert_close(estimates[1].load_qps, 250.0);
+    }
+
+    #[test]
+    fn read_write_split_routes_by_request_type() {
+        let input = input(SchedulerStrategy::ReadWriteSplit);
+        let estimates = simulate(&input, &[instance("read", 1, 2), instance("write", 1, 1), instance("mixed", 1, 2)]);
+        // 读请求由4个读与混合实例分担，写请求由3个写与混合实例分担
+        assert_close(estimates[0].load_qps, 800.0 / 4.0);
+        assert_close(estimates[1].load_qps, 200.0 / 3.0);
+        assert_close(estimates[2].load_qps, 800.0 / 4.0 + 200.0 / 3.0);
+    }
+
+    #[test]
+    fn single_sends_everything_to_the_first_instance() {
+        let input = input(SchedulerStrategy::Single);
+        let estimates = simulate(&input, &[instance("mixed", 1, 3), instance("read", 1, 1)]);
+        let loads: Vec<(u32, f64)> = estimates.iter().map(|e| (e.count, e.load_qps)).collect();
+        assert_eq!(loads, [(1, 1000.0), (2, 0.0), (1, 0.0)]);
+    }
+
+    #[test]
+    fn plan_meets_target_utilization() {
+        let balanced = plan(&input(SchedulerStrategy::LoadBalance));
+        assert_eq!(balanced.topology, Topology { mixed: 10, ..Topology::default() });
+        assert!(balanced.warnings.is_empty());
+        assert_close(balanced.estimates[0].utilization, 0.5);
+
+        let split = plan(&input(SchedulerStrategy::ReadWriteSplit));
+        assert_eq!(split.topology, Topology { read: 8, write: 2, mixed: 0 });
+        assert!(split.estimates.iter().all(|e| e.utilization <= 0.5 + 1e-9));
+
+        // 只读负载也保留一个写实例
+        let read_only = plan(&CapacityInput { read_ratio: 1.0, ..input(SchedulerStrategy::ReadWriteSplit) });
+        assert_eq!(read_only.topology, Topology { read: 10, write: 1, mixed: 0 });
+
+        let idle = plan(&CapacityInput { target_qps: 0.0, ..input(SchedulerStrategy::LoadBalance) });
+        assert_eq!(idle.topology.total(), 1);
+    }
+
+    #[test]
+    fn plan_warns_when_single_or_over_limit() {
+        let single = plan(&input(SchedulerStrategy::Single));
+        assert_eq!(single.topology, Topology { mixed: 1, ..Topology::default() });
+        assert_eq!(single.warnings.len(), 1);
+        assert_eq!(single.estimates[0].mean_latency_ms, None);
+
+        let huge = plan(&CapacityInput { instance_capacity: 0.001, ..input(SchedulerStrategy::LoadBalance) });
+        assert_eq!(huge.topology.mixed, MAX_PLANNED_INSTANCES);
+        assert_eq!(huge.warnings.len(), 1);
+    }
+
+    #[test]
+    fn unserved_requests_are_reported() {
+        let split = input(SchedulerStrategy::ReadWriteSplit);
+        assert!(unserved(&split, &[]).is_some());
+        assert!(unserved(&split, &[instance("read", 1, 1)]).unwrap().contains("写请求"));
+        assert!(unserved(&split, &[instance("write", 1, 1)]).unwrap().contains("读请求"));
+        assert_eq!(unserved(&split, &[instance("mixed", 1, 1)]), None);
+        let writes_only = CapacityInput { read_ratio: 0.0, ..split };
+        assert_eq!(unserved(&writes_only, &[instance("write", 1, 1)]), None);
+        assert_eq!(unserved(&input(SchedulerStrategy::LoadBalance), &[instance("read", 1, 1)]), None);
+    }
+}
@@ -0,0 +1,206 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::api::ApiClient;
+
+/// 大数据处理方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PayloadMode {
+    Encrypt,
+    Decrypt,
+}
+
+impl PayloadMode {
+    /// 显示名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            PayloadMode::Encrypt => "加密",
+            PayloadMode::Decrypt => "解密",
+        }
+    }
+}
+
+/// 清单格式版本；第1版保存明文摘要，可被用来验证对明文的猜测，已不再读取
+const MANIFEST_VERSION: u32 = 2;
+
+/// 分块加密结果的清单，序列化为单行JSON作为整体密文
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChunkManifest {
+    /// 清单格式版本，用于识别分块密文
+    pub chunked: u32,
+    /// 明文总字节数
+    pub total_bytes: usize,
+    /// 各块密文按顺序计算的SHA-256摘要，解密前校验分块未被修改或重排；不含明文信息
+    pub chunks_sha256: String,
+    /// 按顺序排列的各块密文
+    pub chunks: Vec<String>,
+}
+
+impl ChunkManifest {
+    /// 由明文总字节数与按顺序排列的各块密文组装清单
+    fn new(total_bytes: usize, chunks: Vec<String>) -> Self {
+        Self {
+            chunked: MANIFEST_VERSION,
+            total_bytes,
+            chunks_sha256: chunks_digest(&chunks),
+            chunks,
+        }
+    }
+
+    /// 校验各块密文未被修改或重排
+    fn check_digest(&self) -> Result<()> {
+        if chunks_digest(&self.chunks) != self.chunks_sha256 {
+            anyhow::bail!("分块摘要不符，分块可能被篡改或顺序错误");
+        }
+        Ok(())
+    }
+
+    /// 尝试将密文解析为清单，普通密文返回空
+    pub fn parse(payload: &str) -> Option<Self> {
+        if !payload.trim_start().starts_with('{') {
+            return None;
+        }
+        serde_json::from_str::<Self>(payload).ok().filter(|m| m.chunked == MANIFEST_VERSION)
+    }
+}
+
+/// 按字节上限切分文本，切分点落在字符边界上
+pub fn split(data: &str, chunk_bytes: usize) -> Vec<&str> {
+    let chunk_bytes = chunk_bytes.max(4);
+    let mut chunks = Vec::new();
+    let mut rest = data;
+    while rest.len() > chunk_bytes {
+        let mut end = chunk_bytes;
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        let (chunk, tail) = rest.split_at(end);
+        chunks.push(chunk);
+        rest = tail;
+    }
+    chunks.push(rest);
+    chunks
+}
+
+/// 各块密文的摘要，每块前加长度，块的切分与顺序变化时摘要随之变化
+fn chunks_digest(chunks: &[String]) -> String {
+    let mut hasher = Sha256::new();
+    for chunk in chunks {
+        hasher.update((chunk.len() as u64).to_be_bytes());
+        hasher.update(chunk.as_bytes());
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// 加密数据，超过块大小时逐块加密并组装清单；每完成一块回调一次（已完成, 总块数）
+pub async fn encrypt(client: &ApiClient, data: &str, chunk_bytes: usize, mut progress: impl FnMut(usize, usize)) -> Result<String> {
+    if data.len() <= chunk_bytes {
+        let encrypted = client.encrypt(data).await?;
+        progress(1, 1);
+        return Ok(encrypted);
+    }
+
+    let parts = split(data, chunk_bytes);
+    let mut chunks = Vec::with_capacity(parts.len());
+    for (index, part) in parts.iter().enumerate() {
+        let encrypted = client
+            .encrypt(part)
+            .await
+            .with_context(|| format!("第 {}/{} 块加密失败", index + 1, parts.len()))?;
+        chunks.push(encrypted);
+        progress(index + 1, parts.len());
+    }
+    let manifest = ChunkManifest::new(data.len(), chunks);
+    serde_json::to_string(&manifest).context("无法序列化分块清单")
+}
+
+/// 解密数据，分块密文先校验摘要，再逐块解密后拼接并校验长度
+///
+/// 清单来自不可信的输入，明文长度只用于校验，不按它预先分配内存
+pub async fn decrypt(client: &ApiClient, payload: &str, mut progress: impl FnMut(usize, usize)) -> Result<String> {
+    let Some(manifest) = ChunkManifest::parse(payload) else {
+        let decrypted = client.decrypt(payload).await?;
+        progress(1, 1);
+        return Ok(decrypted);
+    };
+    manifest.check_digest()?;
+
+    let total = manifest.chunks.len();
+    let mut data = String::new();
+    for (index, chunk) in manifest.chunks.iter().enumerate() {
+        let decrypted = client
+            .decrypt(chunk)
+            .await
+            .with_context(|| format!("第 {}/{} 块解密失败", index + 1, total))?;
+        if data.len() + decrypted.len() > manifest.total_bytes {
+            anyhow::bail!("解密后长度超过清单记录的 {} 字节", manifest.total_bytes);
+        }
+        data.push_str(&decrypted);
+        progress(index + 1, total);
+    }
+    if data.len() != manifest.total_bytes {
+        anyhow::bail!("解密后长度不符: 期望 {} 字节，实际 {} 字节", manifest.total_bytes, data.len());
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 以反转文本代替加密，组装成序列化后的清单
+    fn assemble(data: &str, chunk_bytes: usize) -> String {
+        let chunks = split(data, chunk_bytes).into_iter().map(|c| c.chars().rev().collect()).collect();
+        serde_json::to_string(&ChunkManifest::new(data.len(), chunks)).unwrap()
+    }
+
+    #[test]
+    fn split_respects_char_boundaries() {
+        let data = "加密服务abc分块";
+        let chunks = split(data, 5);
+        assert!(chunks.iter().all(|c| c.len() <= 5));
+        assert_eq!(chunks.concat(), data);
+        assert_eq!(split("", 16), [""]);
+    }
+
+    #[test]
+    fn manifest_round_trips() {
+        let data = "0123456789".repeat(10);
+        let manifest = ChunkManifest::parse(&assemble(&data, 32)).unwrap();
+        manifest.check_digest().unwrap();
+        assert_eq!(manifest.chunks.len(), 4);
+        assert_eq!(manifest.total_bytes, data.len());
+        let restored: String = manifest.chunks.iter().map(|c| c.chars().rev().collect::<String>()).collect();
+        assert_eq!(restored, data);
+        // 摘要只针对密文，不含明文的摘要
+        assert!(!assemble(&data, 32).contains(&hex::encode(Sha256::digest(data.as_bytes()))));
+    }
+
+    #[test]
+    fn manifest_detects_reordered_or_modified_chunks() {
+        let payload = assemble(&"0123456789".repeat(8), 16);
+
+        let mut reordered = ChunkManifest::parse(&payload).unwrap();
+        reordered.chunks.swap(0, 1);
+        assert!(reordered.check_digest().is_err());
+
+        let mut modified = ChunkManifest::parse(&payload).unwrap();
+        modified.chunks[2].push('x');
+        assert!(modified.check_digest().is_err());
+
+        // 移动块的切分点后拼接内容不变，摘要仍不同
+        let mut resplit = ChunkManifest::parse(&payload).unwrap();
+        let moved = resplit.chunks[1].remove(0);
+        resplit.chunks[0].push(moved);
+        assert!(resplit.check_digest().is_err());
+    }
+
+    #[test]
+    fn plain_ciphertext_and_old_manifests_are_not_parsed() {
+        assert!(ChunkManifest::parse("U2FsdGVkX19hYmNkZWZn").is_none());
+        let mut old = ChunkManifest::parse(&assemble(&"x".repeat(40), 16)).unwrap();
+        old.chunked = 1;
+        assert!(ChunkManifest::parse(&serde_json::to_string(&old).unwrap()).is_none());
+    }
+}
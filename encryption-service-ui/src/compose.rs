@@ -0,0 +1,594 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+
+use crate::kubernetes::ROLE_LABEL;
+use crate::models::{BackendContainer, BusinessGroup, ContainerRestartPolicy, ContainerSpec, EnvVar, format_memory, MiddlewareContainer, NANO_CPUS_PER_CPU, parse_memory, PortMapping, VolumeMount};
+
+/// 未映射端口时中间层与后端使用的默认端口
+const COMPOSE_MIDDLEWARE_PORT: u16 = 9999;
+const COMPOSE_BACKEND_PORT: u16 = 8000;
+
+/// docker-compose文件中用到的部分
+#[derive(Debug, Serialize, Deserialize)]
+struct ComposeFile {
+    /// 项目名称，作为业务组名称
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(default)]
+    services: BTreeMap<String, ComposeService>,
+}
+
+/// docker-compose中的服务定义
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct ComposeService {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    image: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    container_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    command: Option<ComposeCommand>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    ports: Vec<ComposePort>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    environment: Option<ComposeKeyValues>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    labels: Option<ComposeKeyValues>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    volumes: Vec<ComposeVolume>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    depends_on: Option<ComposeDependsOn>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    restart: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    network_mode: Option<String>,
+    /// CPU核数，数字或字符串
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cpus: Option<serde_yaml::Value>,
+    /// 内存上限，带单位的字符串或字节数
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mem_limit: Option<serde_yaml::Value>,
+}
+
+/// 启动命令，字符串或列表
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum ComposeCommand {
+    Shell(String),
+    List(Vec<String>),
+}
+
+/// 端口，短格式或长格式
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum ComposePort {
+    Number(u16),
+    Short(String),
+    Long {
+        target: u16,
+        #[serde(default)]
+        published: Option<serde_yaml::Value>,
+        #[serde(default)]
+        host_ip: Option<String>,
+        #[serde(default)]
+        protocol: Option<String>,
+    },
+}
+
+/// 环境变量与标签，`KEY=VALUE` 列表或映射
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum ComposeKeyValues {
+    List(Vec<String>),
+    Map(BTreeMap<String, Option<serde_yaml::Value>>),
+}
+
+/// 卷，短格式或长格式
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum ComposeVolume {
+    Short(String),
+    Long {
+        #[serde(default)]
+        source: Option<String>,
+        target: String,
+        #[serde(default)]
+        read_only: bool,
+    },
+}
+
+/// 依赖的服务，列表或带条件的映射
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum ComposeDependsOn {
+    List(Vec<String>),
+    Map(BTreeMap<String, serde_yaml::Value>),
+}
+
+/// 将YAML标量转换为字符串，空值为空字符串
+fn yaml_scalar(value: &serde_yaml::Value) -> String {
+    match value {
+        serde_yaml::Value::String(s) => s.clone(),
+        serde_yaml::Value::Number(n) => n.to_string(),
+        serde_yaml::Value::Bool(b) => b.to_string(),
+        _ => String::new(),
+    }
+}
+
+impl ComposeKeyValues {
+    fn pairs(&self) -> Vec<(String, String)> {
+        match self {
+            ComposeKeyValues::List(items) => items
+                .iter()
+                .map(|item| {
+                    let (key, value) = item.split_once('=').unwrap_or((item, ""));
+                    (key.to_string(), value.to_string())
+                })
+                .collect(),
+            ComposeKeyValues::Map(map) => map
+                .iter()
+                .map(|(key, value)| (key.clone(), value.as_ref().map(yaml_scalar).unwrap_or_default()))
+                .collect(),
+        }
+    }
+}
+
+impl ComposeDependsOn {
+    fn services(&self) -> Vec<&str> {
+        match self {
+            ComposeDependsOn::List(items) => items.iter().map(String::as_str).collect(),
+            ComposeDependsOn::Map(map) => map.keys().map(String::as_str).collect(),
+        }
+    }
+}
+
+impl ComposePort {
+    /// 转换为端口映射；只声明容器端口时宿主机端口与容器端口相同，端口范围不支持
+    fn mapping(&self) -> Result<PortMapping> {
+        match self {
+            ComposePort::Number(port) => Ok(PortMapping {
+                host_port: *port,
+                container_port: *port,
+                ..PortMapping::default()
+            }),
+            ComposePort::Short(value) => {
+                let (ports, _) = value.split_once('/').unwrap_or((value, ""));
+                if ports.contains('-') {
+                    anyhow::bail!("不支持端口范围: {}", value);
+                }
+                if ports.contains(':') {
+                    PortMapping::parse(value)
+                } else {
+                    PortMapping::parse(&format!("{}:{}", ports, value))
+                }
+            }
+            ComposePort::Long { target, published, host_ip, protocol } => {
+                let published = published.as_ref().map(yaml_scalar).unwrap_or_default();
+                Ok(PortMapping {
+                    host_ip: host_ip.clone().unwrap_or_default(),
+                    host_port: published.parse().unwrap_or(*target),
+                    container_port: *target,
+                    protocol: protocol.clone().unwrap_or_else(|| "tcp".to_string()),
+                })
+            }
+        }
+    }
+}
+
+impl ComposeVolume {
+    fn mount(&self) -> Result<VolumeMount> {
+        match self {
+            ComposeVolume::Short(value) => VolumeMount::parse(value),
+            ComposeVolume::Long { source: Some(source), target, read_only } => Ok(VolumeMount {
+                source: source.clone(),
+                target: target.clone(),
+                read_only: *read_only,
+            }),
+            ComposeVolume::Long { target, .. } => anyhow::bail!("匿名卷没有来源: {}", target),
+        }
+    }
+}
+
+impl ComposeService {
+    /// 由服务定义生成容器定义，没有镜像（仅构建）的服务返回空
+    fn spec(&self, service: &str) -> Result<Option<ContainerSpec>> {
+        let Some(image) = &self.image else {
+            return Ok(None);
+        };
+        let restart = match self.restart.as_deref() {
+            None => ContainerRestartPolicy::No,
+            Some(value) => {
+                let name = value.split(':').next().unwrap_or_default();
+                ContainerRestartPolicy::ALL
+                    .into_iter()
+                    .find(|p| p.as_arg() == name)
+                    .ok_or_else(|| anyhow::anyhow!("服务 {} 的重启策略不支持: {}", service, value))?
+            }
+        };
+        let command = match &self.command {
+            None => Vec::new(),
+            Some(ComposeCommand::List(words)) => words.clone(),
+            Some(ComposeCommand::Shell(line)) => shlex::split(line)
+                .ok_or_else(|| anyhow::anyhow!("服务 {} 的启动命令中引号不匹配", service))?,
+        };
+        Ok(Some(ContainerSpec {
+            name: self.container_name.clone().unwrap_or_default(),
+            image: image.clone(),
+            command,
+            ports: self
+                .ports
+                .iter()
+                .map(ComposePort::mapping)
+                .collect::<Result<_>>()
+                .with_context(|| format!("服务 {} 的端口无法解析", service))?,
+            env: self
+                .environment
+                .as_ref()
+                .map(|e| e.pairs().into_iter().map(|(key, value)| EnvVar { key, value }).collect())
+                .unwrap_or_default(),
+            volumes: self
+                .volumes
+                .iter()
+                .map(ComposeVolume::mount)
+                .collect::<Result<_>>()
+                .with_context(|| format!("服务 {} 的卷无法解析", service))?,
+            network: self.network_mode.clone().unwrap_or_default(),
+            restart,
+            nano_cpus: self.nano_cpus(service)?,
+            memory: self.memory(service)?,
+        }))
+    }
+
+    /// `cpus` 换算的纳核数
+    fn nano_cpus(&self, service: &str) -> Result<Option<u64>> {
+        let Some(value) = &self.cpus else {
+            return Ok(None);
+        };
+        let cpus = match value {
+            serde_yaml::Value::Number(n) => n.as_f64(),
+            serde_yaml::Value::String(s) => s.trim().parse().ok(),
+            _ => None,
+        }
+        .filter(|c: &f64| *c > 0.0 && c.is_finite())
+        .ok_or_else(|| anyhow::anyhow!("服务 {} 的CPU上限无效: {:?}", service, value))?;
+        Ok(Some((cpus * NANO_CPUS_PER_CPU).round() as u64))
+    }
+
+    /// `mem_limit` 换算的字节数
+    fn memory(&self, service: &str) -> Result<Option<u64>> {
+        match &self.mem_limit {
+            None => Ok(None),
+            Some(serde_yaml::Value::Number(n)) => n
+                .as_u64()
+                .map(Some)
+                .ok_or_else(|| anyhow::anyhow!("服务 {} 的内存上限无效: {}", service, n)),
+            Some(serde_yaml::Value::String(s)) => parse_memory(s)
+                .map(Some)
+                .with_context(|| format!("服务 {} 的内存上限无效", service)),
+            Some(value) => anyhow::bail!("服务 {} 的内存上限无效: {:?}", service, value),
+        }
+    }
+
+    /// 服务通过角色标签声明的角色
+    fn role(&self) -> Option<String> {
+        self.labels
+            .as_ref()?
+            .pairs()
+            .into_iter()
+            .find(|(key, _)| key == ROLE_LABEL)
+            .map(|(_, value)| value)
+    }
+
+    fn depends_on(&self) -> Vec<&str> {
+        self.depends_on.as_ref().map(ComposeDependsOn::services).unwrap_or_default()
+    }
+}
+
+/// 由服务生成访问地址与容器端口：有宿主机端口映射时经本机访问，否则按服务名访问
+fn compose_endpoint(service: &str, spec: Option<&ContainerSpec>, default_port: u16) -> (String, u16) {
+    match spec.and_then(|s| s.ports.iter().find(|p| p.protocol == "tcp")) {
+        Some(port) => (format!("http://localhost:{}", port.host_port), port.container_port),
+        None => (format!("http://{}:{}", service, default_port), default_port),
+    }
+}
+
+/// 读取docker-compose文件并生成业务组
+///
+/// 带有角色标签（middleware / backend）的服务按标签归类；未标注的服务中，
+/// 声明了 depends_on 且不被其他服务依赖的作为中间层，其余作为后端。
+/// 后端归入第一个依赖它的中间层，没有中间层依赖的后端直接属于业务组。
+pub fn read(path: &str) -> Result<BusinessGroup> {
+    let content = fs::read_to_string(path).context(format!("无法读取docker-compose文件: {}", path))?;
+    // 没有 name 字段时项目名称取文件所在目录名
+    let dir_name = fs::canonicalize(path)
+        .ok()
+        .and_then(|p| p.parent()?.file_name().map(|n| n.to_string_lossy().to_string()));
+    parse(&content, path, dir_name)
+}
+
+/// 解析docker-compose文件内容，`path` 只用于提示与描述，`dir_name` 为没有 name 字段时的项目名称
+fn parse(content: &str, path: &str, dir_name: Option<String>) -> Result<BusinessGroup> {
+    let compose: ComposeFile = serde_yaml::from_str(content).context(format!("无法解析docker-compose文件: {}", path))?;
+    if compose.services.is_empty() {
+        anyhow::bail!("docker-compose文件中没有服务: {}", path);
+    }
+    for (name, service) in &compose.services {
+        if let Some(missing) = service.depends_on().into_iter().find(|d| !compose.services.contains_key(*d)) {
+            anyhow::bail!("服务 {} 依赖的服务不存在: {}", name, missing);
+        }
+    }
+
+    let depended: HashSet<&str> = compose.services.values().flat_map(ComposeService::depends_on).collect();
+    let is_middleware = |name: &str, service: &ComposeService| match service.role().as_deref() {
+        Some("middleware") => true,
+        Some(_) => false,
+        None => !service.depends_on().is_empty() && !depended.contains(name),
+    };
+
+    // 项目名称依次取 name 字段、文件所在目录名
+    let name = compose
+        .name
+        .clone()
+        .or(dir_name)
+        .unwrap_or_else(|| "docker-compose".to_string());
+    let mut group = BusinessGroup {
+        name,
+        description: format!("从docker-compose文件 {} 导入", path),
+        ..BusinessGroup::default()
+    };
+
+    let mut backends: BTreeMap<&str, BackendContainer> = BTreeMap::new();
+    let mut middlewares = Vec::new();
+    for (name, service) in &compose.services {
+        let spec = service.spec(name)?;
+        let params = spec.as_ref().map(ContainerSpec::to_params).unwrap_or_default();
+        if is_middleware(name, service) {
+            let (url, port) = compose_endpoint(name, spec.as_ref(), COMPOSE_MIDDLEWARE_PORT);
+            let mut middleware = MiddlewareContainer {
+                name: name.clone(),
+                url,
+                docker_run_params: params,
+                ..MiddlewareContainer::default()
+            };
+            middleware.config.server.port = port;
+            middlewares.push((middleware, service.depends_on()));
+        } else {
+            let (url, _) = compose_endpoint(name, spec.as_ref(), COMPOSE_BACKEND_PORT);
+            let backend = BackendContainer {
+                name: name.clone(),
+                url,
+                docker_run_params: params,
+                ..BackendContainer::default()
+            };
+            backends.insert(name, backend);
+        }
+    }
+
+    for (mut middleware, depends_on) in middlewares {
+        for dependency in depends_on {
+            if let Some(backend) = backends.remove(dependency) {
+                middleware.backend_containers.push(backend);
+            }
+        }
+        middleware.config.crud_api.instances = middleware.crud_instances();
+        group.middlewares.push(middleware);
+    }
+    group.backend_containers.extend(backends.into_values());
+    Ok(group)
+}
+
+/// compose中的名称只允许小写字母、数字、`-` 与 `_`，其余字符替换为 `-`
+pub fn normalize_name(name: &str) -> String {
+    let name: String = name
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '-' })
+        .collect();
+    let name = name.trim_matches('-');
+    if name.is_empty() { "service".to_string() } else { name.to_string() }
+}
+
+/// 由容器定义生成compose服务
+fn compose_service(spec: ContainerSpec, role: &str, depends_on: Vec<String>) -> ComposeService {
+    ComposeService {
+        image: Some(spec.image),
+        container_name: (!spec.name.is_empty()).then_some(spec.name),
+        command: (!spec.command.is_empty()).then_some(ComposeCommand::List(spec.command)),
+        ports: spec.ports.iter().map(|p| ComposePort::Short(p.to_arg())).collect(),
+        environment: (!spec.env.is_empty())
+            .then(|| ComposeKeyValues::List(spec.env.iter().map(|e| format!("{}={}", e.key, e.value)).collect())),
+        labels: Some(ComposeKeyValues::List(vec![format!("{}={}", ROLE_LABEL, role)])),
+        volumes: spec.volumes.iter().map(|v| ComposeVolume::Short(v.to_arg())).collect(),
+        depends_on: (!depends_on.is_empty()).then_some(ComposeDependsOn::List(depends_on)),
+        restart: (spec.restart != ContainerRestartPolicy::No).then(|| spec.restart.as_arg().to_string()),
+        network_mode: (!spec.network.is_empty()).then_some(spec.network),
+        cpus: spec.nano_cpus.map(|n| serde_yaml::Value::Number((n as f64 / NANO_CPUS_PER_CPU).into())),
+        mem_limit: spec.memory.map(|m| serde_yaml::Value::String(format_memory(m))),
+    }
+}
+
+/// 将业务组渲染为docker-compose文件，与 [`read`] 互逆
+///
+/// 服务带有角色标签，中间层依赖其下属后端；没有Docker运行参数的容器由外部管理，
+/// 不生成服务，在文件头部注释中列出
+pub fn render(group: &BusinessGroup) -> Result<String> {
+    let mut services = BTreeMap::new();
+    let mut skipped = Vec::new();
+    let service_name = |name: &str, services: &BTreeMap<String, ComposeService>| {
+        let base = normalize_name(name);
+        let mut candidate = base.clone();
+        let mut suffix = 2;
+        while services.contains_key(&candidate) {
+            candidate = format!("{}-{}", base, suffix);
+            suffix += 1;
+        }
+        candidate
+    };
+    let mut spec_of = |name: &str, params: &str, spec: Result<ContainerSpec>| -> Result<Option<ContainerSpec>> {
+        if params.trim().is_empty() {
+            skipped.push(name.to_string());
+            return Ok(None);
+        }
+        spec.map(Some).with_context(|| format!("容器 {} 的Docker运行参数无法解析", name))
+    };
+
+    for middleware in &group.middlewares {
+        let mut depends_on = Vec::new();
+        for backend in &middleware.backend_containers {
+            if let Some(spec) = spec_of(&backend.name, &backend.docker_run_params, backend.container_spec())? {
+                let name = service_name(&backend.name, &services);
+                services.insert(name.clone(), compose_service(spec, "backend", Vec::new()));
+                depends_on.push(name);
+            }
+        }
+        if let Some(spec) = spec_of(&middleware.name, &middleware.docker_run_params, middleware.container_spec())? {
+            let name = service_name(&middleware.name, &services);
+            services.insert(name, compose_service(spec, "middleware", depends_on));
+        }
+    }
+    for backend in &group.backend_containers {
+        if let Some(spec) = spec_of(&backend.name, &backend.docker_run_params, backend.container_spec())? {
+            let name = service_name(&backend.name, &services);
+            services.insert(name, compose_service(spec, "backend", Vec::new()));
+        }
+    }
+
+    let compose = ComposeFile {
+        name: Some(normalize_name(&group.name)),
+        services,
+    };
+    let mut content = format!("# 由业务组 {} 导出\n", group.name);
+    if !skipped.is_empty() {
+        content.push_str(&format!("# 以下容器由外部管理，未包含: {}\n", skipped.join(", ")));
+    }
+    content.push_str(&serde_yaml::to_string(&compose).context("无法序列化docker-compose文件")?);
+    Ok(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn backend(name: &str, params: &str) -> BackendContainer {
+        BackendContainer {
+            name: name.to_string(),
+            docker_run_params: params.to_string(),
+            ..BackendContainer::default()
+        }
+    }
+
+    fn group() -> BusinessGroup {
+        let middleware = MiddlewareContainer {
+            name: "gateway".to_string(),
+            docker_run_params: "--name gateway -p 9999:9999 -e MODE=prod encryption/middleware:1.2".to_string(),
+            backend_containers: vec![backend("db-a", "-p 8001:8000 -v data:/var/lib/data --restart always crud:1 serve --port 8000")],
+            ..MiddlewareContainer::default()
+        };
+        BusinessGroup {
+            name: "payments".to_string(),
+            middlewares: vec![middleware],
+            backend_containers: vec![
+                backend("cache", "-e SIZE=64 --memory 256m --cpus 0.5 redis:7"),
+                backend("external", ""),
+            ],
+            ..BusinessGroup::default()
+        }
+    }
+
+    #[test]
+    fn render_then_parse_round_trips() {
+        let group = group();
+        let rendered = render(&group).unwrap();
+        let parsed = parse(&rendered, "docker-compose.yml", None).unwrap();
+
+        assert_eq!(parsed.name, "payments");
+        assert_eq!(parsed.middlewares.len(), 1);
+        let (middleware, original) = (&parsed.middlewares[0], &group.middlewares[0]);
+        assert_eq!(middleware.name, "gateway");
+        assert_eq!(middleware.container_spec().unwrap(), original.container_spec().unwrap());
+        assert_eq!(middleware.backend_containers.len(), 1);
+        assert_eq!(middleware.backend_containers[0].name, "db-a");
+        assert_eq!(
+            middleware.backend_containers[0].container_spec().unwrap(),
+            original.backend_containers[0].container_spec().unwrap(),
+        );
+        // 由外部管理的容器不导出
+        assert_eq!(parsed.backend_containers.iter().map(|b| b.name.as_str()).collect::<Vec<_>>(), ["cache"]);
+        assert_eq!(parsed.backend_containers[0].container_spec().unwrap(), group.backend_containers[0].container_spec().unwrap());
+
+        // 再次渲染只少了外部容器的提示
+        let skipped_note = "# 以下容器由外部管理，未包含: external\n";
+        assert_eq!(render(&parsed).unwrap(), rendered.replace(skipped_note, ""));
+    }
+
+    #[test]
+    fn parse_derives_endpoints_from_ports() {
+        let parsed = parse(&render(&group()).unwrap(), "docker-compose.yml", Some("ignored".to_string())).unwrap();
+        let middleware = &parsed.middlewares[0];
+        assert_eq!(middleware.url, "http://localhost:9999");
+        assert_eq!(middleware.config.server.port, 9999);
+        assert_eq!(middleware.backend_containers[0].url, "http://localhost:8001");
+        // 未映射端口的服务按服务名访问
+        assert_eq!(parsed.backend_containers[0].url, "http://cache:8000");
+    }
+
+    /// 渲染后按YAML读取的服务定义
+    fn rendered_service(group: &BusinessGroup, service: &str) -> serde_yaml::Value {
+        let rendered: serde_yaml::Value = serde_yaml::from_str(&render(group).unwrap()).unwrap();
+        rendered["services"][service].clone()
+    }
+
+    fn strings(value: &serde_yaml::Value) -> Vec<&str> {
+        value.as_sequence().unwrap().iter().map(|v| v.as_str().unwrap()).collect()
+    }
+
+    #[test]
+    fn render_writes_ports_in_short_syntax() {
+        let group = BusinessGroup {
+            backend_containers: vec![backend("dns", "-p 8053:53 -p 127.0.0.1:5353:53/udp -p 0:9100 coredns:1")],
+            ..BusinessGroup::default()
+        };
+        assert_eq!(strings(&rendered_service(&group, "dns")["ports"]), ["8053:53", "127.0.0.1:5353:53/udp", "0:9100"]);
+
+        let group = BusinessGroup {
+            backend_containers: vec![backend("plain", "redis:7")],
+            ..BusinessGroup::default()
+        };
+        assert!(rendered_service(&group, "plain").get("ports").is_none());
+    }
+
+    #[test]
+    fn render_writes_volumes_with_mode() {
+        let group = BusinessGroup {
+            backend_containers: vec![backend("db", "-v data:/var/lib/data -v ./conf:/etc/conf:ro postgres:16")],
+            ..BusinessGroup::default()
+        };
+        assert_eq!(strings(&rendered_service(&group, "db")["volumes"]), ["data:/var/lib/data", "./conf:/etc/conf:ro"]);
+    }
+
+    #[test]
+    fn render_writes_environment_and_role_labels() {
+        let group = BusinessGroup {
+            middlewares: vec![MiddlewareContainer {
+                name: "Gateway API".to_string(),
+                docker_run_params: "-e MODE=prod -e 'GREETING=hello world' -e EMPTY= -e URL=http://db:8000/?a=b mw:1".to_string(),
+                backend_containers: vec![backend("db", "crud:1"), backend("db", "crud:1")],
+                ..MiddlewareContainer::default()
+            }],
+            ..BusinessGroup::default()
+        };
+        let gateway = rendered_service(&group, "gateway-api");
+        assert_eq!(strings(&gateway["environment"]), ["MODE=prod", "GREETING=hello world", "EMPTY=", "URL=http://db:8000/?a=b"]);
+        assert_eq!(strings(&gateway["labels"]), [format!("{}=middleware", ROLE_LABEL)]);
+        // 同名服务加序号，中间层依赖其下属后端
+        assert_eq!(strings(&gateway["depends_on"]), ["db", "db-2"]);
+        assert!(rendered_service(&group, "db").get("environment").is_none());
+
+        // 环境变量在解析后保持原样
+        let parsed = parse(&render(&group).unwrap(), "docker-compose.yml", None).unwrap();
+        let env = parsed.middlewares[0].container_spec().unwrap().env;
+        assert_eq!(env, group.middlewares[0].container_spec().unwrap().env);
+    }
+}
@@ -1,19 +1,152 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::fs::{self, File};
+use std::collections::BTreeSet;
+use std::fs::{self, File, OpenOptions};
 use std::io::{Read, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use chrono::{DateTime, Utc};
+use std::time::{Duration, Instant, SystemTime};
 
-use crate::models::AppState;
+use crate::diagnostics::{self, Metric};
+use crate::models::{AgentSettings, Alert, AlertPolicy, AlertSeverity, AllowedCommand, AnomalyRule, AppConfig, AppState, AuditChainAnchor, AuditEntry, BackendContainer, BusinessGroup, ConfigPreset, ConnectionPoolSettings, CostSettings, DashboardWidget, DashboardWidgetKind, DiscoveryTtl, DockerConnection, DockerHost, HistoryRedaction, LocalUser, PasswordPolicy, UiProfile, UiRoleAssignment, UiTheme, JobRecord, MiddlewareContainer, NetworkProfile, OtlpSettings, PlaygroundHistoryEntry, RequestCollection, RetryPolicy, StatusTransition, WeightAdjustment, Webhook};
 
 /// 应用配置
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Config {
     pub app_state: AppState,
+    /// 旧版本保存在共享配置中的界面偏好，只读取用于迁移到用户偏好文件，不再写入
+    #[serde(default, skip_serializing)]
     pub last_opened: String,
+    #[serde(default, skip_serializing)]
     pub theme: String,
     pub auto_save: bool,
     pub save_interval: u64,
+    /// 日志异常检测规则
+    #[serde(default = "default_anomaly_rules")]
+    pub anomaly_rules: Vec<AnomalyRule>,
+    /// 异常日志激增阈值（每分钟命中次数，0表示不检测）
+    #[serde(default = "default_anomaly_spike_threshold")]
+    pub anomaly_spike_threshold: u32,
+    /// 告警记录
+    #[serde(default)]
+    pub alerts: Vec<Alert>,
+    /// 健康告警的迟滞、去重与抖动抑制设置
+    #[serde(default)]
+    pub alert_policy: AlertPolicy,
+    /// 旧版本的仪表盘组件布局，已移至用户偏好文件，只读取用于迁移
+    #[serde(default = "default_dashboard_widgets", skip_serializing)]
+    pub dashboard_widgets: Vec<DashboardWidget>,
+    /// 旧版本的仪表盘网格列数，已移至用户偏好文件，只读取用于迁移
+    #[serde(default = "default_dashboard_columns", skip_serializing)]
+    pub dashboard_columns: u32,
+    /// 新建后台任务的重试策略
+    #[serde(default)]
+    pub job_retry_policy: RetryPolicy,
+    /// 已结束的后台任务记录
+    #[serde(default)]
+    pub job_history: Vec<JobRecord>,
+    /// 未结束的后台任务，重启后恢复执行
+    #[serde(default)]
+    pub active_jobs: Vec<JobRecord>,
+    /// 接口客户端的连接池与保活设置
+    #[serde(default)]
+    pub connection_pool: ConnectionPoolSettings,
+    /// 管理器自身链路的OTLP导出设置
+    #[serde(default)]
+    pub otlp: OtlpSettings,
+    /// 用户保存的中间层配置模板
+    #[serde(default)]
+    pub config_presets: Vec<ConfigPreset>,
+    /// 审计日志
+    #[serde(default)]
+    pub audit_log: Vec<AuditEntry>,
+    /// 审计日志哈希链的起点，尚未开始计算哈希时为空
+    #[serde(default)]
+    pub audit_chain: Option<AuditChainAnchor>,
+    /// 各容器的状态变化记录
+    #[serde(default)]
+    pub status_history: Vec<StatusTransition>,
+    /// 接口调试保存的请求集合
+    #[serde(default)]
+    pub request_collections: Vec<RequestCollection>,
+    /// 超过该字节数的数据分块加解密
+    #[serde(default = "default_payload_chunk_bytes")]
+    pub payload_chunk_bytes: usize,
+    /// 同时进行的健康探测数上限，超出的排队等待
+    #[serde(default = "default_health_concurrency")]
+    pub health_concurrency: usize,
+    /// 后台状态变化触发的两次重绘之间的最短间隔（毫秒）
+    #[serde(default = "default_min_repaint_interval_ms")]
+    pub min_repaint_interval_ms: u64,
+    /// 会话最长时长（分钟），超过后实例自动退出，0表示不限
+    #[serde(default)]
+    pub max_session_minutes: u64,
+    /// 成本估算设置
+    #[serde(default)]
+    pub cost: CostSettings,
+    /// 本地用户，不为空时启动后须登录
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub local_users: Vec<LocalUser>,
+    /// 本地用户的密码策略
+    #[serde(default)]
+    pub password_policy: PasswordPolicy,
+    /// 接口调试历史
+    #[serde(default)]
+    pub playground_history: Vec<PlaygroundHistoryEntry>,
+    /// 接口调试历史中请求内容的保存方式
+    #[serde(default)]
+    pub playground_history_redaction: HistoryRedaction,
+    /// 允许通过Agent远程执行的命令
+    #[serde(default)]
+    pub command_allowlist: Vec<AllowedCommand>,
+    /// 允许执行远程命令的操作人
+    #[serde(default)]
+    pub command_operators: Vec<String>,
+    /// 分配给操作人的界面配置
+    #[serde(default)]
+    pub ui_roles: Vec<UiRoleAssignment>,
+    /// 未分配界面配置的操作人使用的配置
+    #[serde(default)]
+    pub default_ui_profile: UiProfile,
+    /// Agent部署设置
+    #[serde(default)]
+    pub agent: AgentSettings,
+    /// 导入Kubernetes工作负载的命名空间
+    #[serde(default = "default_kubernetes_namespace")]
+    pub kubernetes_namespace: String,
+    /// 发现导入的后端的存活期限与自动移除设置
+    #[serde(default)]
+    pub discovery_ttl: DiscoveryTtl,
+    /// 外部触发操作的Webhook
+    #[serde(default)]
+    pub webhooks: Vec<Webhook>,
+    /// 是否启动Webhook监听
+    #[serde(default)]
+    pub webhook_enabled: bool,
+    /// Webhook监听地址
+    #[serde(default = "default_webhook_listen")]
+    pub webhook_listen: String,
+    /// 自适应权重调整记录
+    #[serde(default)]
+    pub weight_history: Vec<WeightAdjustment>,
+    /// 是否暂停健康轮询、指标采集与集群同步等后台任务，维护期间避免与现场操作冲突
+    #[serde(default)]
+    pub background_paused: bool,
+    /// 分文件保存：每个业务组保存在配置文件旁目录中各自的文件里，只有内容变化的业务组才写入，
+    /// 主配置文件不含业务组，只记录其顺序
+    #[serde(default)]
+    pub split_storage: bool,
+    /// 分文件保存时业务组的顺序，读取时按此顺序组装
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub group_order: Vec<String>,
+    /// 配置修订号，每次写入递增，供多个实例判断文件是否被他人修改
+    #[serde(default)]
+    pub revision: u64,
+    /// 最近一次写入配置的实例ID
+    #[serde(default)]
+    pub last_writer: String,
 }
 
 impl Default for Config {
@@ -24,14 +157,178 @@ impl Default for Config {
             theme: "dark".to_string(),
             auto_save: true,
             save_interval: 30,
+            anomaly_rules: default_anomaly_rules(),
+            anomaly_spike_threshold: default_anomaly_spike_threshold(),
+            alerts: Vec::new(),
+            alert_policy: AlertPolicy::default(),
+            dashboard_widgets: default_dashboard_widgets(),
+            dashboard_columns: default_dashboard_columns(),
+            job_retry_policy: RetryPolicy::default(),
+            job_history: Vec::new(),
+            active_jobs: Vec::new(),
+            connection_pool: ConnectionPoolSettings::default(),
+            otlp: OtlpSettings::default(),
+            config_presets: Vec::new(),
+            audit_log: Vec::new(),
+            audit_chain: None,
+            status_history: Vec::new(),
+            request_collections: Vec::new(),
+            payload_chunk_bytes: default_payload_chunk_bytes(),
+            health_concurrency: default_health_concurrency(),
+            min_repaint_interval_ms: default_min_repaint_interval_ms(),
+            max_session_minutes: 0,
+            cost: CostSettings::default(),
+            local_users: Vec::new(),
+            password_policy: PasswordPolicy::default(),
+            playground_history: Vec::new(),
+            playground_history_redaction: HistoryRedaction::default(),
+            command_allowlist: Vec::new(),
+            command_operators: Vec::new(),
+            ui_roles: Vec::new(),
+            default_ui_profile: UiProfile::default(),
+            agent: AgentSettings::default(),
+            kubernetes_namespace: default_kubernetes_namespace(),
+            discovery_ttl: DiscoveryTtl::default(),
+            webhooks: Vec::new(),
+            webhook_enabled: false,
+            webhook_listen: default_webhook_listen(),
+            weight_history: Vec::new(),
+            background_paused: false,
+            split_storage: false,
+            group_order: Vec::new(),
+            revision: 0,
+            last_writer: String::new(),
         }
     }
 }
 
+impl Config {
+    /// 业务组、中间层与后端等嵌套结构都有一个元素的示例配置，原始JSON编辑器据此推断字段
+    pub fn schema_sample() -> Self {
+        let mut middleware = MiddlewareContainer {
+            config: AppConfig::schema_sample(),
+            ..Default::default()
+        };
+        middleware.backend_containers.push(BackendContainer::default());
+        let mut group = BusinessGroup::default();
+        group.middlewares.push(middleware);
+        group.backend_containers.push(BackendContainer::default());
+        let mut config = Config::default();
+        config.app_state.business_groups.push(group);
+        config.app_state.docker_hosts.push(DockerHost::new("", DockerConnection::Local, ""));
+        config.app_state.network_profiles.push(NetworkProfile::new(""));
+        config.command_allowlist.push(AllowedCommand::new("", ""));
+        config
+    }
+}
+
+/// 默认的日志异常检测规则
+fn default_anomaly_rules() -> Vec<AnomalyRule> {
+    vec![
+        AnomalyRule::keyword("ERROR", AlertSeverity::Warning),
+        AnomalyRule::keyword("panic", AlertSeverity::Critical),
+        AnomalyRule::keyword("timeout", AlertSeverity::Warning),
+    ]
+}
+
+/// 默认的异常日志激增阈值
+fn default_anomaly_spike_threshold() -> u32 {
+    20
+}
+
+/// 默认的仪表盘布局
+fn default_dashboard_widgets() -> Vec<DashboardWidget> {
+    vec![
+        DashboardWidget::new(DashboardWidgetKind::UptimeCard, 0, 0, 1),
+        DashboardWidget::new(DashboardWidgetKind::QuickActions, 0, 1, 1),
+        DashboardWidget::new(DashboardWidgetKind::AlertList, 1, 0, 2),
+    ]
+}
+
+/// 默认的仪表盘网格列数
+fn default_dashboard_columns() -> u32 {
+    2
+}
+
+/// 默认的Kubernetes命名空间
+fn default_kubernetes_namespace() -> String {
+    "default".to_string()
+}
+
+/// 默认的Webhook监听地址
+fn default_webhook_listen() -> String {
+    "127.0.0.1:8787".to_string()
+}
+
+fn default_payload_chunk_bytes() -> usize {
+    256 * 1024
+}
+
+fn default_health_concurrency() -> usize {
+    16
+}
+
+fn default_min_repaint_interval_ms() -> u64 {
+    50
+}
+
+/// 连续加载失败多少次后进入只读恢复模式
+const RECOVERY_THRESHOLD: u32 = 3;
+
+/// 写入失败后首次重试的等待时间，之后逐次翻倍
+const SAVE_RETRY_BASE: Duration = Duration::from_secs(2);
+/// 写入重试的最长等待时间
+const SAVE_RETRY_MAX: Duration = Duration::from_secs(120);
+
+/// 写入失败、排队等待重试的配置；它不是已保存的配置，读取仍以文件为准
+struct PendingSave {
+    /// 待写入的配置，修订号为写入后的修订号
+    config: Config,
+    /// 已失败的写入次数
+    attempts: u32,
+    since: DateTime<Utc>,
+    next_retry: Instant,
+    last_error: String,
+}
+
+/// 待写入配置的状态，供界面提示
+#[derive(Debug, Clone)]
+pub struct PendingSaveStatus {
+    pub attempts: u32,
+    /// 首次写入失败的时间
+    pub since: DateTime<Utc>,
+    pub last_error: String,
+    /// 距下次自动重试的时间
+    pub retry_in: Duration,
+}
+
 /// 配置管理器
 #[derive(Clone)]
 pub struct ConfigManager {
     config_path: String,
+    /// 连续加载失败次数，在所有克隆间共享
+    load_failures: Arc<AtomicU32>,
+    /// 最近一次加载失败的原因
+    last_error: Arc<Mutex<Option<String>>>,
+    /// 写入失败、等待重试的配置
+    pending: Arc<Mutex<Option<PendingSave>>>,
+    /// 配置的读取-修改-写入在该锁内串行执行，在所有克隆间共享
+    write_lock: Arc<Mutex<()>>,
+    /// 本实例ID，写入配置时记录，用于区分其他实例的修改
+    instance_id: String,
+    /// 本实例写入的修订号，据此判断两个修订号之间是否有其他实例的写入
+    own_revisions: Arc<Mutex<BTreeSet<u64>>>,
+}
+
+/// 记录的本实例修订号上限，超出时丢弃最早的
+const OWN_REVISIONS_LIMIT: usize = 4096;
+
+/// 配置写锁的守卫：同时持有进程内的互斥锁与锁文件上的排他锁，
+/// 共用同一配置文件的其他进程在释放前无法写入
+pub struct WriteGuard<'a> {
+    /// 先于进程内的锁释放
+    _file: Option<File>,
+    _guard: MutexGuard<'a, ()>,
 }
 
 impl ConfigManager {
@@ -39,7 +336,106 @@ impl ConfigManager {
     pub fn new(config_path: String) -> Self {
         Self {
             config_path,
+            load_failures: Arc::new(AtomicU32::new(0)),
+            last_error: Arc::new(Mutex::new(None)),
+            pending: Arc::new(Mutex::new(None)),
+            write_lock: Arc::new(Mutex::new(())),
+            instance_id: uuid::Uuid::new_v4().to_string(),
+            own_revisions: Arc::default(),
+        }
+    }
+    
+    /// 本实例ID
+    pub fn instance_id(&self) -> &str {
+        &self.instance_id
+    }
+    
+    /// 记录本实例写入的修订号
+    fn record_own_revision(&self, revision: u64) {
+        let mut own = self.own_revisions.lock().unwrap_or_else(|e| e.into_inner());
+        own.insert(revision);
+        while own.len() > OWN_REVISIONS_LIMIT {
+            own.pop_first();
+        }
+    }
+    
+    /// 修订号从 `base` 变为 `current` 期间是否有其他实例写入：其间每个修订号都由本实例写入时为否。
+    /// 最近一次写入者不能说明问题，其他实例写入后本实例又写入审计等内容时它仍是本实例
+    pub fn peer_modified(&self, base: u64, current: u64) -> bool {
+        if current <= base {
+            // 修订号倒退说明文件被其他实例恢复或替换
+            return current < base;
+        }
+        let own = self.own_revisions.lock().unwrap_or_else(|e| e.into_inner());
+        own.range(base + 1..=current).count() as u64 != current - base
+    }
+    
+    /// 配置文件最后修改时间，文件不存在时返回None
+    pub fn modified_time(&self) -> Option<SystemTime> {
+        fs::metadata(&self.config_path).and_then(|m| m.modified()).ok()
+    }
+    
+    /// 是否处于只读恢复模式
+    pub fn is_read_only(&self) -> bool {
+        self.load_failures.load(Ordering::Relaxed) >= RECOVERY_THRESHOLD
+    }
+    
+    /// 连续加载失败次数
+    pub fn load_failures(&self) -> u32 {
+        self.load_failures.load(Ordering::Relaxed)
+    }
+    
+    /// 最近一次加载失败的原因
+    pub fn last_error(&self) -> Option<String> {
+        self.last_error.lock().ok().and_then(|e| e.clone())
+    }
+    
+    /// 自动备份文件路径
+    fn auto_backup_path(&self) -> String {
+        format!("{}.bak", self.config_path)
+    }
+    
+    /// 最近一次完好的配置，即每次写入前保存的自动备份；配置文件损坏时用于确定登录与界面权限
+    pub fn last_good_config(&self) -> Result<Config> {
+        self.import_config(&self.auto_backup_path())
+    }
+    
+    /// 分文件保存时业务组文件所在的目录，与配置文件同级，如 `config.json` 对应 `config.groups`
+    pub fn groups_dir(&self) -> PathBuf {
+        let path = Path::new(&self.config_path);
+        let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+        path.with_file_name(format!("{}.groups", stem))
+    }
+    
+    /// 会话文件路径，与配置文件同级，如 `config.json` 对应 `config.sessions.json`；
+    /// 心跳频繁写入，与配置分开保存以免每次心跳都递增配置修订号
+    pub fn sessions_path(&self) -> PathBuf {
+        let path = Path::new(&self.config_path);
+        let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+        path.with_file_name(format!("{}.sessions.json", stem))
+    }
+    
+    /// 业务组文件路径：ID中的小写字母、数字、`-` 与 `_` 原样保留，其余字节编码为 `%XX`，
+    /// 不同的ID总是对应不同的文件名，在不区分大小写的文件系统上也不冲突
+    fn group_path(dir: &Path, group_id: &str) -> PathBuf {
+        let mut name = String::with_capacity(group_id.len());
+        for byte in group_id.bytes() {
+            if byte.is_ascii_lowercase() || byte.is_ascii_digit() || byte == b'-' || byte == b'_' {
+                name.push(byte as char);
+            } else {
+                name.push_str(&format!("%{:02X}", byte));
+            }
         }
+        dir.join(format!("{}.json", name))
+    }
+    
+    /// 旧版本把路径字符替换为下划线生成的业务组文件路径，不同ID可能相同，只用于读取尚未迁移的文件
+    fn legacy_group_path(dir: &Path, group_id: &str) -> PathBuf {
+        let name: String = group_id
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+            .collect();
+        dir.join(format!("{}.json", name))
     }
     
     /// 获取默认配置路径
@@ -49,8 +445,80 @@ impl ConfigManager {
         path.to_string_lossy().to_string()
     }
     
-    /// 加载配置
+    /// 写入失败、等待重试的配置状态
+    pub fn pending_save(&self) -> Option<PendingSaveStatus> {
+        let pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        pending.as_ref().map(|p| PendingSaveStatus {
+            attempts: p.attempts,
+            since: p.since,
+            last_error: p.last_error.clone(),
+            retry_in: p.next_retry.saturating_duration_since(Instant::now()),
+        })
+    }
+    
+    /// 重试写入排队的配置，未到重试时间且未强制时跳过；没有排队的配置时返回None
+    ///
+    /// 排队后配置文件已被其他实例写入时不再写入，以免覆盖对方的修改，
+    /// 排队的配置被丢弃并返回错误，未保存的修改由调用方重新提交
+    pub fn retry_pending(&self, force: bool) -> Option<Result<()>> {
+        let _guard = self.lock_writes();
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        let save = pending.as_mut()?;
+        if !force && Instant::now() < save.next_retry {
+            return None;
+        }
+        let result = match self.read_config() {
+            Ok(current) if current.revision + 1 != save.config.revision => {
+                *pending = None;
+                return Some(Err(anyhow::anyhow!("配置文件已被其他实例修改（修订号 {}），排队的修改未写入", current.revision)));
+            }
+            Ok(_) => self.write_config(&save.config),
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(()) => {
+                self.record_own_revision(save.config.revision);
+                *pending = None;
+                Some(Ok(()))
+            }
+            Err(e) => {
+                save.attempts += 1;
+                save.last_error = format!("{:#}", e);
+                save.next_retry = Instant::now() + Self::retry_delay(save.attempts);
+                Some(Err(e))
+            }
+        }
+    }
+    
+    /// 第 `attempts` 次失败后的重试等待时间
+    fn retry_delay(attempts: u32) -> Duration {
+        SAVE_RETRY_BASE
+            .saturating_mul(2u32.saturating_pow(attempts.saturating_sub(1)))
+            .min(SAVE_RETRY_MAX)
+    }
+    
+    /// 加载配置，记录连续失败次数；总是读取文件，写入失败排队中的配置不视为已保存
     pub fn load_config(&self) -> Result<Config> {
+        let result = self.read_config();
+        match &result {
+            Ok(_) => {
+                self.load_failures.store(0, Ordering::Relaxed);
+                if let Ok(mut last_error) = self.last_error.lock() {
+                    *last_error = None;
+                }
+            }
+            Err(e) => {
+                self.load_failures.fetch_add(1, Ordering::Relaxed);
+                if let Ok(mut last_error) = self.last_error.lock() {
+                    *last_error = Some(format!("{:#}", e));
+                }
+            }
+        }
+        result
+    }
+    
+    /// 读取配置文件
+    fn read_config(&self) -> Result<Config> {
         let path = Path::new(&self.config_path);
         
         // 如果配置文件不存在，返回默认配置
@@ -65,32 +533,229 @@ impl ConfigManager {
         file.read_to_string(&mut content)
             .context(format!("无法读取配置文件: {}", self.config_path))?;
         
-        let config: Config = serde_json::from_str(&content)
+        let mut config: Config = serde_json::from_str(&content)
             .context(format!("无法解析配置文件: {}", self.config_path))?;
+        if config.split_storage {
+            self.read_groups(&mut config, false)?;
+        }
         
         Ok(config)
     }
     
-    /// 保存配置
-    pub fn save_config(&self, config: &Config) -> Result<()> {
+    /// 按主配置记录的顺序读取各业务组文件，组装到状态中；`skip_missing` 时跳过已删除的业务组文件
+    fn read_groups(&self, config: &mut Config, skip_missing: bool) -> Result<()> {
+        let dir = self.groups_dir();
+        let mut groups = Vec::with_capacity(config.group_order.len());
+        for group_id in &config.group_order {
+            let mut path = Self::group_path(&dir, group_id);
+            if !path.exists() {
+                let legacy = Self::legacy_group_path(&dir, group_id);
+                if legacy.exists() {
+                    path = legacy;
+                }
+            }
+            if skip_missing && !path.exists() {
+                tracing::warn!("业务组文件已删除，跳过: {}", path.display());
+                continue;
+            }
+            let content = fs::read_to_string(&path)
+                .context(format!("无法读取业务组文件: {}", path.display()))?;
+            let group: BusinessGroup = serde_json::from_str(&content)
+                .context(format!("无法解析业务组文件: {}", path.display()))?;
+            if &group.id != group_id {
+                anyhow::bail!("业务组文件 {} 属于业务组 {}，而不是 {}", path.display(), group.id, group_id);
+            }
+            groups.push(group);
+        }
+        config.app_state.business_groups = groups.into();
+        Ok(())
+    }
+    
+    /// 将内容有变化的业务组写入各自的文件，返回不含业务组、只记录其顺序的主配置
+    fn write_groups(&self, config: &Config) -> Result<Config> {
+        let dir = self.groups_dir();
+        fs::create_dir_all(&dir)
+            .context(format!("无法创建业务组目录: {}", dir.display()))?;
+        let paths: Vec<PathBuf> = config.app_state.business_groups.iter().map(|g| Self::group_path(&dir, &g.id)).collect();
+        for (group, path) in config.app_state.business_groups.iter().zip(&paths) {
+            let content = serde_json::to_string_pretty(group)
+                .context("无法序列化业务组")?;
+            if fs::read_to_string(path).is_ok_and(|current| current == content) {
+                continue;
+            }
+            let temp_path = path.with_extension("json.tmp");
+            fs::write(&temp_path, content.as_bytes())
+                .context(format!("无法写入业务组文件: {}", temp_path.display()))?;
+            fs::rename(&temp_path, path)
+                .context(format!("无法替换业务组文件: {}", path.display()))?;
+        }
+        // 迁移后删除旧命名的文件，旧文件名恰好是其他业务组的新文件名时保留
+        for group in &config.app_state.business_groups {
+            let legacy = Self::legacy_group_path(&dir, &group.id);
+            if !paths.contains(&legacy) && legacy.exists() && let Err(e) = fs::remove_file(&legacy) {
+                tracing::warn!("无法删除旧的业务组文件 {}: {}", legacy.display(), e);
+            }
+        }
+        let mut main = config.clone();
+        main.group_order = config.app_state.business_groups.iter().map(|g| g.id.clone()).collect();
+        main.app_state.business_groups.clear();
+        Ok(main)
+    }
+    
+    /// 主配置文件当前记录的业务组顺序，读取失败时为空
+    fn stored_group_order(&self) -> Vec<String> {
+        fs::read_to_string(&self.config_path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<Config>(&content).ok())
+            .filter(|config| config.split_storage)
+            .map(|config| config.group_order)
+            .unwrap_or_default()
+    }
+    
+    /// 获取写锁。配置与会话文件等同目录下共用的文件都在该锁内读取-修改-写入，
+    /// 持有期间不能调用本管理器的 `update`
+    ///
+    /// 除进程内的互斥锁外还锁定配置文件旁的锁文件，其他进程中的实例因此也不会同时写入；
+    /// 锁文件无法打开或锁定时只记录警告，仍在进程内串行
+    pub fn lock_writes(&self) -> WriteGuard<'_> {
+        let guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let path = self.lock_path();
+        if let Some(parent) = Path::new(&path).parent().filter(|p| !p.as_os_str().is_empty()) {
+            let _ = fs::create_dir_all(parent);
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .and_then(|file| file.lock().map(|_| file));
+        let file = match file {
+            Ok(file) => Some(file),
+            Err(e) => {
+                tracing::warn!("无法锁定配置锁文件 {}，只在进程内串行写入: {}", path, e);
+                None
+            }
+        };
+        WriteGuard { _file: file, _guard: guard }
+    }
+    
+    /// 跨进程写锁所用的锁文件
+    fn lock_path(&self) -> String {
+        format!("{}.lock", self.config_path)
+    }
+    
+    /// 读取最新配置、修改并保存，整个过程持有写锁，并发的修改不会互相覆盖
+    ///
+    /// 修改失败时不写入。修改中不能再调用本管理器的 `update`
+    pub fn update<R>(&self, f: impl FnOnce(&mut Config) -> Result<R>) -> Result<R> {
+        self.update_revision(f).map(|(result, _)| result)
+    }
+    
+    /// 同 [`ConfigManager::update`]，修改返回 `false` 时表示没有变化，不写入；返回是否写入
+    pub fn update_if(&self, f: impl FnOnce(&mut Config) -> Result<bool>) -> Result<bool> {
+        let _guard = self.lock_writes();
+        if self.is_read_only() {
+            anyhow::bail!("配置处于只读恢复模式，修改未保存");
+        }
+        let mut config = self.load_config()?;
+        if !f(&mut config)? {
+            return Ok(false);
+        }
+        self.write_revision(&config)?;
+        Ok(true)
+    }
+    
+    /// 同 [`ConfigManager::update`]，同时返回写入后的修订号
+    pub fn update_revision<R>(&self, f: impl FnOnce(&mut Config) -> Result<R>) -> Result<(R, u64)> {
+        let _guard = self.lock_writes();
+        if self.is_read_only() {
+            anyhow::bail!("配置处于只读恢复模式，修改未保存");
+        }
+        let mut config = self.load_config()?;
+        let result = f(&mut config)?;
+        let revision = self.write_revision(&config)?;
+        Ok((result, revision))
+    }
+    
+    /// 在写锁内保存配置并返回写入后的修订号
+    ///
+    /// 写入失败时返回错误，修改未保存；配置进入重试队列，由 `retry_pending` 按退避间隔重试，
+    /// 之后的读取仍以文件为准。排队期间的保存照常写入，成功后取代队列中的配置
+    fn write_revision(&self, config: &Config) -> Result<u64> {
+        let config = Config {
+            revision: config.revision + 1,
+            last_writer: self.instance_id.clone(),
+            ..config.clone()
+        };
+        let revision = config.revision;
+        
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        match diagnostics::timed(Metric::ConfigSave, || self.write_config(&config)) {
+            Ok(()) => {
+                *pending = None;
+                self.record_own_revision(revision);
+                Ok(revision)
+            }
+            Err(e) => {
+                tracing::warn!("写入配置失败，已加入重试队列: {:#}", e);
+                let (attempts, since) = pending.as_ref().map_or((1, Utc::now()), |p| (p.attempts + 1, p.since));
+                *pending = Some(PendingSave {
+                    config,
+                    attempts,
+                    since,
+                    next_retry: Instant::now() + Self::retry_delay(attempts),
+                    last_error: format!("{:#}", e),
+                });
+                Err(e.context("写入配置失败，修改未保存"))
+            }
+        }
+    }
+    
+    /// 写入配置文件：先保留上一份完好的文件作为自动备份，再经临时文件替换
+    fn write_config(&self, config: &Config) -> Result<()> {
         let path = Path::new(&self.config_path);
         
         // 如果目录不存在，创建目录
-        if let Some(parent) = path.parent() {
-            if !parent.exists() {
-                fs::create_dir_all(parent)
-                    .context(format!("无法创建配置目录: {:?}", parent))?;
-            }
+        if let Some(parent) = path.parent()
+            && !parent.exists()
+        {
+            fs::create_dir_all(parent)
+                .context(format!("无法创建配置目录: {:?}", parent))?;
         }
         
-        let content = serde_json::to_string_pretty(config)
-            .context("无法序列化配置")?;
+        // 分文件保存时先写业务组文件再写主配置，最后删除不再记录的业务组文件，
+        // 其他实例读取时主配置记录的业务组文件总是存在
+        let (content, removed) = if config.split_storage {
+            let previous = self.stored_group_order();
+            let main = self.write_groups(config)?;
+            let removed: Vec<String> = previous.into_iter().filter(|id| !main.group_order.contains(id)).collect();
+            (serde_json::to_string_pretty(&main).context("无法序列化配置")?, removed)
+        } else {
+            (serde_json::to_string_pretty(config).context("无法序列化配置")?, Vec::new())
+        };
         
-        let mut file = File::create(path)
-            .context(format!("无法创建配置文件: {}", self.config_path))?;
+        if self.load_failures() == 0 && path.exists() {
+            fs::copy(path, self.auto_backup_path())
+                .context(format!("无法备份配置文件: {}", self.config_path))?;
+        }
+        
+        let temp_path = format!("{}.tmp", self.config_path);
+        let mut file = File::create(&temp_path)
+            .context(format!("无法创建配置文件: {}", temp_path))?;
         
         file.write_all(content.as_bytes())
-            .context(format!("无法写入配置文件: {}", self.config_path))?;
+            .context(format!("无法写入配置文件: {}", temp_path))?;
+        
+        fs::rename(&temp_path, path)
+            .context(format!("无法替换配置文件: {}", self.config_path))?;
+        
+        let dir = self.groups_dir();
+        for group_id in removed {
+            let group_path = Self::group_path(&dir, &group_id);
+            if let Err(e) = fs::remove_file(&group_path) {
+                tracing::warn!("无法删除业务组文件 {}: {}", group_path.display(), e);
+            }
+        }
         
         Ok(())
     }
@@ -106,8 +771,12 @@ impl ConfigManager {
         file.read_to_string(&mut content)
             .context(format!("无法读取导入文件: {}", import_path))?;
         
-        let config: Config = serde_json::from_str(&content)
+        let mut config: Config = serde_json::from_str(&content)
             .context(format!("无法解析导入文件: {}", import_path))?;
+        // 分文件保存的主配置备份不含业务组，业务组取自当前的业务组文件，之后删除的业务组无法恢复
+        if config.split_storage && config.app_state.business_groups.is_empty() {
+            self.read_groups(&mut config, true)?;
+        }
         
         Ok(config)
     }
@@ -138,10 +807,378 @@ impl ConfigManager {
         Ok(backup_path)
     }
     
-    /// 恢复配置
+    /// 恢复配置，成功后退出只读恢复模式
     pub fn restore_config(&self, backup_path: &str) -> Result<Config> {
-        let config = self.import_config(backup_path)?;
-        self.save_config(&config)?;
-        Ok(config)
+        let _guard = self.lock_writes();
+        let current_revision = self.read_config().map(|c| c.revision).unwrap_or_default();
+        let config = Config {
+            revision: current_revision + 1,
+            last_writer: self.instance_id.clone(),
+            ..self.import_config(backup_path)?
+        };
+        self.write_config(&config)?;
+        self.record_own_revision(config.revision);
+        // 恢复的备份取代排队中尚未写入的配置
+        *self.pending.lock().unwrap_or_else(|e| e.into_inner()) = None;
+        self.load_config()
+    }
+    
+    /// 列出可用的备份文件，最新的在前
+    pub fn list_backups(&self) -> Result<Vec<String>> {
+        let mut backups = Vec::new();
+        
+        let auto_backup = self.auto_backup_path();
+        if Path::new(&auto_backup).exists() {
+            backups.push(auto_backup);
+        }
+        
+        let mut manual: Vec<String> = fs::read_dir(".")
+            .context("无法读取备份目录")?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .filter(|name| name.starts_with("config_backup_") && name.ends_with(".json"))
+            .collect();
+        manual.sort_by(|a, b| b.cmp(a));
+        backups.extend(manual);
+        
+        Ok(backups)
+    }
+}
+
+/// egui默认的正文字号
+pub const DEFAULT_FONT_SIZE: f32 = 12.5;
+
+fn default_font_size() -> f32 {
+    DEFAULT_FONT_SIZE
+}
+
+/// 每个用户各自的界面偏好，与共享的拓扑配置分开保存，
+/// 使通过git等方式同步的配置不会因外观调整而频繁变动
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UserPreferences {
+    #[serde(default)]
+    pub theme: UiTheme,
+    /// 正文字号，其他文字按相同比例缩放
+    #[serde(default = "default_font_size")]
+    pub font_size: f32,
+    #[serde(default = "default_dashboard_widgets")]
+    pub dashboard_widgets: Vec<DashboardWidget>,
+    #[serde(default = "default_dashboard_columns")]
+    pub dashboard_columns: u32,
+    /// 最近一次打开的时间
+    #[serde(default)]
+    pub last_opened: String,
+    /// 是否显示诊断浮层
+    #[serde(default)]
+    pub show_diagnostics: bool,
+}
+
+impl Default for UserPreferences {
+    fn default() -> Self {
+        Self {
+            theme: UiTheme::default(),
+            font_size: DEFAULT_FONT_SIZE,
+            dashboard_widgets: default_dashboard_widgets(),
+            dashboard_columns: default_dashboard_columns(),
+            last_opened: String::new(),
+            show_diagnostics: false,
+        }
+    }
+}
+
+impl UserPreferences {
+    /// 由旧版本共享配置中的界面设置生成偏好
+    fn from_legacy(config: &Config) -> Self {
+        Self {
+            theme: if config.theme == "light" { UiTheme::Light } else { UiTheme::Dark },
+            dashboard_widgets: config.dashboard_widgets.clone(),
+            dashboard_columns: config.dashboard_columns,
+            last_opened: config.last_opened.clone(),
+            ..Self::default()
+        }
+    }
+}
+
+/// 用户偏好文件管理器
+#[derive(Clone)]
+pub struct PreferencesManager {
+    path: String,
+}
+
+impl PreferencesManager {
+    /// 创建新的偏好管理器
+    pub fn new(path: String) -> Self {
+        Self { path }
+    }
+    
+    /// 偏好文件路径
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+    
+    /// 获取默认偏好路径：位于用户配置目录，无法确定时位于当前目录
+    pub fn default_path() -> String {
+        let base = if cfg!(windows) {
+            std::env::var("APPDATA").ok()
+        } else {
+            std::env::var("XDG_CONFIG_HOME")
+                .ok()
+                .filter(|dir| !dir.is_empty())
+                .or_else(|| std::env::var("HOME").ok().map(|home| format!("{}/.config", home)))
+        };
+        match base.filter(|dir| !dir.is_empty()) {
+            Some(dir) => Path::new(&dir).join("encryption-service-ui").join("preferences.json").to_string_lossy().to_string(),
+            None => "preferences.json".to_string(),
+        }
+    }
+    
+    /// 加载偏好，偏好文件不存在时由共享配置中的旧设置迁移
+    pub fn load(&self, legacy: &Config) -> Result<UserPreferences> {
+        let path = Path::new(&self.path);
+        if !path.exists() {
+            return Ok(UserPreferences::from_legacy(legacy));
+        }
+        let content = fs::read_to_string(path).context(format!("无法读取偏好文件: {}", self.path))?;
+        serde_json::from_str(&content).context(format!("无法解析偏好文件: {}", self.path))
+    }
+    
+    /// 保存偏好
+    pub fn save(&self, preferences: &UserPreferences) -> Result<()> {
+        let path = Path::new(&self.path);
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).context(format!("无法创建偏好目录: {}", dir.display()))?;
+        }
+        let content = serde_json::to_string_pretty(preferences).context("无法序列化偏好")?;
+        fs::write(path, content).context(format!("无法写入偏好文件: {}", self.path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试用的临时配置目录，结束时删除
+    struct TempConfig {
+        dir: PathBuf,
+    }
+
+    impl TempConfig {
+        fn new() -> Self {
+            let dir = std::env::temp_dir().join(format!("encryption-service-ui-test-{}", uuid::Uuid::new_v4()));
+            fs::create_dir_all(&dir).unwrap();
+            Self { dir }
+        }
+
+        fn manager(&self) -> ConfigManager {
+            ConfigManager::new(self.dir.join("config.json").to_string_lossy().to_string())
+        }
+    }
+
+    impl Drop for TempConfig {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.dir);
+        }
+    }
+
+    fn group(id: &str) -> BusinessGroup {
+        BusinessGroup { id: id.to_string(), name: format!("group {}", id), ..BusinessGroup::default() }
+    }
+
+    #[test]
+    fn group_file_names_are_distinct() {
+        let dir = Path::new("groups");
+        let ids = ["a/b", "a_b", "a?b", "a b", "A", "a", "a%2Fb", "组", ".."];
+        let paths: std::collections::HashSet<PathBuf> = ids.iter().map(|id| ConfigManager::group_path(dir, id)).collect();
+        assert_eq!(paths.len(), ids.len());
+        assert!(paths.iter().all(|p| p.parent() == Some(dir)));
+        assert_eq!(ConfigManager::group_path(dir, "a/b"), dir.join("a%2Fb.json"));
+        assert_eq!(ConfigManager::group_path(dir, ".."), dir.join("%2E%2E.json"));
+        // UUID形式的ID文件名不变
+        let id = "0b9c6a52-3f4e-4d8a-9a1e-2f6c1b7d8e90";
+        assert_eq!(ConfigManager::group_path(dir, id), dir.join(format!("{}.json", id)));
+    }
+
+    #[test]
+    fn split_storage_keeps_groups_with_similar_ids_apart() {
+        let temp = TempConfig::new();
+        let manager = temp.manager();
+        manager
+            .update(|config| {
+                config.split_storage = true;
+                config.app_state.business_groups = vec![group("a/b"), group("a_b"), group("A_B")].into();
+                Ok(())
+            })
+            .unwrap();
+        let loaded = temp.manager().load_config().unwrap();
+        let ids: Vec<&str> = loaded.app_state.business_groups.iter().map(|g| g.id.as_str()).collect();
+        assert_eq!(ids, ["a/b", "a_b", "A_B"]);
+        assert_eq!(fs::read_dir(manager.groups_dir()).unwrap().count(), 3);
+    }
+
+    #[test]
+    fn legacy_group_files_are_read_and_migrated() {
+        let temp = TempConfig::new();
+        let manager = temp.manager();
+        let dir = manager.groups_dir();
+        fs::create_dir_all(&dir).unwrap();
+        let legacy = dir.join("team_payments.json");
+        fs::write(&legacy, serde_json::to_string(&group("team/payments")).unwrap()).unwrap();
+        let main = Config { split_storage: true, group_order: vec!["team/payments".to_string()], ..Config::default() };
+        fs::write(dir.with_file_name("config.json"), serde_json::to_string(&main).unwrap()).unwrap();
+
+        let loaded = manager.load_config().unwrap();
+        assert_eq!(loaded.app_state.business_groups[0].id, "team/payments");
+        manager.update(|_| Ok(())).unwrap();
+        assert!(!legacy.exists());
+        assert!(dir.join("team%2Fpayments.json").exists());
+        assert_eq!(manager.load_config().unwrap().app_state.business_groups.len(), 1);
+    }
+
+    #[test]
+    fn group_file_of_another_group_is_rejected() {
+        let temp = TempConfig::new();
+        let manager = temp.manager();
+        let dir = manager.groups_dir();
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("a_b.json"), serde_json::to_string(&group("a_b")).unwrap()).unwrap();
+        let main = Config { split_storage: true, group_order: vec!["a/b".to_string()], ..Config::default() };
+        fs::write(dir.with_file_name("config.json"), serde_json::to_string(&main).unwrap()).unwrap();
+        assert!(manager.load_config().is_err());
+    }
+
+    /// 在临时文件位置放置目录，使配置写入失败
+    fn block_writes(manager: &ConfigManager) -> PathBuf {
+        let blocker = PathBuf::from(format!("{}.tmp", manager.config_path));
+        fs::create_dir_all(&blocker).unwrap();
+        blocker
+    }
+
+    fn set_interval(manager: &ConfigManager, secs: u64) -> Result<()> {
+        manager.update(|config| {
+            config.save_interval = secs;
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn failed_write_is_reported_and_retried() {
+        let temp = TempConfig::new();
+        let manager = temp.manager();
+        set_interval(&manager, 1).unwrap();
+
+        let blocker = block_writes(&manager);
+        assert!(set_interval(&manager, 2).is_err());
+        let pending = manager.pending_save().unwrap();
+        assert_eq!(pending.attempts, 1);
+        // 排队的配置不视为已保存
+        let stored = manager.load_config().unwrap();
+        assert_eq!((stored.save_interval, stored.revision), (1, 1));
+
+        // 未到重试时间时不重试
+        assert!(manager.retry_pending(false).is_none());
+        assert!(matches!(manager.retry_pending(true), Some(Err(_))));
+        assert_eq!(manager.pending_save().unwrap().attempts, 2);
+
+        fs::remove_dir(&blocker).unwrap();
+        assert!(matches!(manager.retry_pending(true), Some(Ok(()))));
+        assert!(manager.pending_save().is_none());
+        let stored = manager.load_config().unwrap();
+        assert_eq!((stored.save_interval, stored.revision), (2, 2));
+        assert!(manager.retry_pending(true).is_none());
+    }
+
+    #[test]
+    fn retry_does_not_overwrite_peer_changes() {
+        let temp = TempConfig::new();
+        let manager = temp.manager();
+        let peer = temp.manager();
+        set_interval(&manager, 1).unwrap();
+
+        let blocker = block_writes(&manager);
+        assert!(set_interval(&manager, 2).is_err());
+        fs::remove_dir(&blocker).unwrap();
+        set_interval(&peer, 7).unwrap();
+
+        assert!(matches!(manager.retry_pending(true), Some(Err(_))));
+        assert!(manager.pending_save().is_none());
+        let stored = manager.load_config().unwrap();
+        assert_eq!((stored.save_interval, stored.last_writer.as_str()), (7, peer.instance_id()));
+    }
+
+    #[test]
+    fn later_write_supersedes_queued_save() {
+        let temp = TempConfig::new();
+        let manager = temp.manager();
+        let blocker = block_writes(&manager);
+        assert!(set_interval(&manager, 2).is_err());
+        assert!(set_interval(&manager, 3).is_err());
+        let pending = manager.pending_save().unwrap();
+        assert_eq!(pending.attempts, 2);
+
+        fs::remove_dir(&blocker).unwrap();
+        set_interval(&manager, 4).unwrap();
+        assert!(manager.pending_save().is_none());
+        assert_eq!(manager.load_config().unwrap().save_interval, 4);
+    }
+
+    #[test]
+    fn retry_delay_backs_off_up_to_limit() {
+        let delays: Vec<u64> = [1, 2, 3, 4, 7, 8, 100].iter().map(|a| ConfigManager::retry_delay(*a).as_secs()).collect();
+        assert_eq!(delays, [2, 4, 8, 16, 120, 120, 120]);
+    }
+
+    #[test]
+    fn corrupt_config_enters_read_only_mode_until_restored() {
+        let temp = TempConfig::new();
+        let manager = temp.manager();
+        let backup = temp.dir.join("backup.json");
+        manager.export_config(&Config { save_interval: 9, ..Config::default() }, &backup.to_string_lossy()).unwrap();
+        fs::write(&manager.config_path, "{ not json").unwrap();
+
+        for _ in 0..RECOVERY_THRESHOLD {
+            assert!(!manager.is_read_only());
+            assert!(manager.load_config().is_err());
+        }
+        assert!(manager.is_read_only());
+        assert!(manager.last_error().is_some());
+        assert!(set_interval(&manager, 1).is_err());
+        // 损坏的文件不覆盖自动备份
+        assert!(!Path::new(&manager.auto_backup_path()).exists());
+
+        let restored = manager.restore_config(&backup.to_string_lossy()).unwrap();
+        assert_eq!(restored.save_interval, 9);
+        assert!(!manager.is_read_only());
+        set_interval(&manager, 1).unwrap();
+        assert!(Path::new(&manager.auto_backup_path()).exists());
+    }
+
+    #[test]
+    fn peer_writes_are_found_by_revision_lineage() {
+        let temp = TempConfig::new();
+        let (own, peer) = (temp.manager(), temp.manager());
+        set_interval(&own, 1).unwrap();
+        set_interval(&own, 2).unwrap();
+        assert!(!own.peer_modified(0, 2));
+        set_interval(&peer, 3).unwrap();
+        // 本实例随后的写入使最近写入者变回本实例，其他实例的写入仍能发现
+        set_interval(&own, 4).unwrap();
+        assert_eq!(own.load_config().unwrap().last_writer, own.instance_id());
+        assert!(own.peer_modified(2, 4));
+        assert!(!own.peer_modified(3, 4));
+        assert!(peer.peer_modified(3, 4));
+        // 修订号倒退说明文件被替换
+        assert!(own.peer_modified(4, 1));
+    }
+
+    #[test]
+    fn write_lock_excludes_other_processes() {
+        let temp = TempConfig::new();
+        let manager = temp.manager();
+        let guard = manager.lock_writes();
+        // 另行打开的文件描述与其他进程一样受锁文件约束
+        let other = File::open(manager.lock_path()).unwrap();
+        assert!(other.try_lock().is_err());
+        drop(guard);
+        other.try_lock().unwrap();
     }
 }
@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+
+use crate::models::{BackendContainer, BusinessGroup, ContainerStatus, CostSettings, DockerHost};
+
+/// 一个业务组的月成本估算
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct GroupCost {
+    /// 运行中与启动中的实例数，多副本按副本数计
+    pub instances: u32,
+    /// 按角色计费的实例的费用
+    pub containers: f64,
+    /// 分摊到的主机费用
+    pub hosts: f64,
+}
+
+impl GroupCost {
+    /// 月成本合计
+    pub fn total(&self) -> f64 {
+        self.containers + self.hosts
+    }
+}
+
+/// 月成本估算：只计运行中与启动中的实例，停止或出错的实例不产生费用；
+/// 部署在有月费用的主机上的实例平均分摊主机费用，其余实例按角色计费
+pub struct CostEstimator<'a> {
+    settings: &'a CostSettings,
+    /// 有月费用的主机：ID到（月费用, 所有业务组在其上的实例数）
+    hosts: HashMap<&'a str, (f64, u32)>,
+}
+
+impl<'a> CostEstimator<'a> {
+    /// 按所有业务组的部署统计各主机上计费的实例数
+    pub fn new(settings: &'a CostSettings, hosts: &'a [DockerHost], groups: &[BusinessGroup]) -> Self {
+        let mut estimator = Self {
+            settings,
+            hosts: hosts
+                .iter()
+                .filter(|h| h.monthly_cost > 0.0)
+                .map(|h| (h.id.as_str(), (h.monthly_cost, 0)))
+                .collect(),
+        };
+        for group in groups {
+            for (host_id, replicas, _) in Self::instances(group) {
+                if let Some(host_id) = host_id
+                    && let Some((_, count)) = estimator.hosts.get_mut(host_id)
+                {
+                    *count += replicas;
+                }
+            }
+        }
+        estimator
+    }
+    
+    /// 业务组中计费的实例：（所在主机, 副本数, 是否为中间层）
+    fn instances(group: &BusinessGroup) -> Vec<(Option<&str>, u32, bool)> {
+        fn billed(status: &ContainerStatus) -> bool {
+            matches!(status, ContainerStatus::Running | ContainerStatus::Starting)
+        }
+        fn backend(b: &BackendContainer) -> Option<(Option<&str>, u32, bool)> {
+            billed(&b.status).then(|| (b.docker_host_id.as_deref(), b.replicas.max(1), false))
+        }
+        group.middlewares
+            .iter()
+            .filter(|m| billed(&m.status))
+            .map(|m| (m.docker_host_id.as_deref(), m.replicas.max(1), true))
+            .chain(group.backend_containers.iter().filter_map(backend))
+            .chain(group.middlewares.iter().flat_map(|m| m.backend_containers.iter().filter_map(backend)))
+            .collect()
+    }
+    
+    /// 是否设置了任何费用，未设置时界面不显示成本
+    pub fn is_configured(&self) -> bool {
+        self.settings.middleware_monthly > 0.0 || self.settings.backend_monthly > 0.0 || !self.hosts.is_empty()
+    }
+    
+    /// 是否在有月费用的主机上，此时实例分摊主机费用
+    fn on_priced_host(&self, host_id: Option<&str>) -> bool {
+        host_id.is_some_and(|id| self.hosts.contains_key(id))
+    }
+    
+    /// 业务组的月成本
+    pub fn group_cost(&self, group: &BusinessGroup) -> GroupCost {
+        let mut cost = GroupCost::default();
+        for (host_id, replicas, is_middleware) in Self::instances(group) {
+            cost.instances += replicas;
+            match host_id.and_then(|id| self.hosts.get(id)) {
+                Some((monthly, count)) => cost.hosts += monthly * replicas as f64 / (*count).max(1) as f64,
+                None => {
+                    let rate = if is_middleware { self.settings.middleware_monthly } else { self.settings.backend_monthly };
+                    cost.containers += rate * replicas as f64;
+                }
+            }
+        }
+        cost
+    }
+    
+    /// 增加或移除后端后总月成本的变化：按角色计费的实例增减其费用，
+    /// 有月费用的主机上的实例只改变分摊比例，不改变总成本
+    pub fn backend_delta(&self, added: &[BackendContainer], removed: &[&BackendContainer]) -> f64 {
+        let cost = |b: &BackendContainer| {
+            if self.on_priced_host(b.docker_host_id.as_deref()) {
+                0.0
+            } else {
+                self.settings.backend_monthly * b.replicas.max(1) as f64
+            }
+        };
+        added.iter().map(cost).sum::<f64>() - removed.iter().map(|b| cost(b)).sum::<f64>()
+    }
+    
+    /// 后端是否分摊主机费用，用于说明成本变化为0的原因
+    pub fn shares_host(&self, backend: &BackendContainer) -> bool {
+        self.on_priced_host(backend.docker_host_id.as_deref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{DockerConnection, MiddlewareContainer};
+
+    fn settings() -> CostSettings {
+        CostSettings { middleware_monthly: 100.0, backend_monthly: 10.0, ..CostSettings::default() }
+    }
+
+    fn backend(status: ContainerStatus, replicas: u32, host_id: Option<&str>) -> BackendContainer {
+        BackendContainer {
+            status,
+            replicas,
+            docker_host_id: host_id.map(str::to_string),
+            ..BackendContainer::default()
+        }
+    }
+
+    #[test]
+    fn stopped_instances_are_not_billed() {
+        let settings = settings();
+        let group = BusinessGroup {
+            middlewares: vec![MiddlewareContainer {
+                status: ContainerStatus::Running,
+                backend_containers: vec![
+                    backend(ContainerStatus::Running, 2, None),
+                    backend(ContainerStatus::Stopped, 3, None),
+                ],
+                ..MiddlewareContainer::default()
+            }],
+            backend_containers: vec![backend(ContainerStatus::Starting, 1, None), backend(ContainerStatus::Error, 1, None)],
+            ..BusinessGroup::default()
+        };
+        let estimator = CostEstimator::new(&settings, &[], std::slice::from_ref(&group));
+
+        let cost = estimator.group_cost(&group);
+        assert_eq!(cost.instances, 4);
+        assert_eq!(cost.containers, 100.0 + 3.0 * 10.0);
+        assert_eq!(cost.hosts, 0.0);
+    }
+
+    #[test]
+    fn host_cost_is_shared_by_running_instances_only() {
+        let settings = settings();
+        let host = DockerHost { monthly_cost: 90.0, ..DockerHost::new("主机", DockerConnection::Tcp, "10.0.0.1:2375") };
+        let host_id = Some(host.id.as_str());
+        let first = BusinessGroup {
+            backend_containers: vec![backend(ContainerStatus::Running, 1, host_id)],
+            ..BusinessGroup::default()
+        };
+        let second = BusinessGroup {
+            backend_containers: vec![
+                backend(ContainerStatus::Running, 2, host_id),
+                backend(ContainerStatus::Stopped, 4, host_id),
+            ],
+            ..BusinessGroup::default()
+        };
+        let groups = [first, second];
+        let estimator = CostEstimator::new(&settings, std::slice::from_ref(&host), &groups);
+
+        assert_eq!(estimator.group_cost(&groups[0]).hosts, 30.0);
+        let cost = estimator.group_cost(&groups[1]);
+        assert_eq!(cost.instances, 2);
+        assert_eq!(cost.hosts, 60.0);
+        assert_eq!(cost.containers, 0.0);
+    }
+}
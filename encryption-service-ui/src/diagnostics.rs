@@ -0,0 +1,154 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// 汇总的时间窗口
+pub const WINDOW: Duration = Duration::from_secs(5);
+/// 样本按该宽度分桶汇总，记录时不保留单个样本
+const BUCKET: Duration = Duration::from_secs(1);
+
+/// 诊断浮层显示的耗时指标
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    /// 界面一帧的处理耗时
+    Frame,
+    /// 等待共享状态读写锁的时间
+    LockWait,
+    /// 写入配置文件的耗时
+    ConfigSave,
+}
+
+impl Metric {
+    /// 所有指标
+    pub const ALL: [Metric; 3] = [Metric::Frame, Metric::LockWait, Metric::ConfigSave];
+
+    /// 显示名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            Metric::Frame => "帧耗时",
+            Metric::LockWait => "状态锁等待",
+            Metric::ConfigSave => "配置保存",
+        }
+    }
+}
+
+/// 一项指标在时间窗口内的汇总
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MetricSummary {
+    pub count: u64,
+    pub average: Duration,
+    pub max: Duration,
+    /// 最近一次的耗时，窗口内没有样本时仍保留
+    pub last: Option<Duration>,
+}
+
+/// 一个时间桶内的样本汇总
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    start: Instant,
+    count: u64,
+    total: Duration,
+    max: Duration,
+}
+
+struct Series {
+    buckets: VecDeque<Bucket>,
+    last: Option<Duration>,
+}
+
+impl Series {
+    const fn new() -> Self {
+        Self { buckets: VecDeque::new(), last: None }
+    }
+
+    fn record(&mut self, now: Instant, elapsed: Duration) {
+        self.last = Some(elapsed);
+        match self.buckets.back_mut() {
+            Some(bucket) if now < bucket.start + BUCKET => {
+                bucket.count += 1;
+                bucket.total += elapsed;
+                bucket.max = bucket.max.max(elapsed);
+            }
+            _ => self.buckets.push_back(Bucket { start: now, count: 1, total: elapsed, max: elapsed }),
+        }
+        self.prune(now);
+    }
+
+    fn prune(&mut self, now: Instant) {
+        while self.buckets.front().is_some_and(|b| now.saturating_duration_since(b.start) > WINDOW) {
+            self.buckets.pop_front();
+        }
+    }
+
+    fn summary(&mut self, now: Instant) -> MetricSummary {
+        self.prune(now);
+        let count: u64 = self.buckets.iter().map(|b| b.count).sum();
+        let total: Duration = self.buckets.iter().map(|b| b.total).sum();
+        MetricSummary {
+            count,
+            average: if count > 0 { total / count as u32 } else { Duration::ZERO },
+            max: self.buckets.iter().map(|b| b.max).max().unwrap_or_default(),
+            last: self.last,
+        }
+    }
+}
+
+/// 只有显示诊断浮层时才记录，关闭时记录点只读取这一标志
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// 服务与状态存储没有界面的引用，耗时汇总在进程内，由界面读取
+static RECORDER: Mutex<[Series; Metric::ALL.len()]> = Mutex::new([const { Series::new() }; Metric::ALL.len()]);
+
+fn recorder() -> MutexGuard<'static, [Series; Metric::ALL.len()]> {
+    RECORDER.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// 开启或关闭记录，开启时清空之前的汇总
+pub fn set_enabled(enabled: bool) {
+    if enabled && !ENABLED.load(Ordering::Relaxed) {
+        *recorder() = [const { Series::new() }; Metric::ALL.len()];
+    }
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// 记录一次耗时
+pub fn record(metric: Metric, elapsed: Duration) {
+    if ENABLED.load(Ordering::Relaxed) {
+        recorder()[metric as usize].record(Instant::now(), elapsed);
+    }
+}
+
+/// 执行并记录耗时，未开启时直接执行
+pub fn timed<T>(metric: Metric, f: impl FnOnce() -> T) -> T {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return f();
+    }
+    let started = Instant::now();
+    let result = f();
+    record(metric, started.elapsed());
+    result
+}
+
+/// 各指标在时间窗口内的汇总，顺序同 [`Metric::ALL`]
+pub fn summaries() -> [MetricSummary; Metric::ALL.len()] {
+    let now = Instant::now();
+    let mut recorder = recorder();
+    Metric::ALL.map(|metric| recorder[metric as usize].summary(now))
+}
+
+/// 在丢弃时记录一帧的耗时，界面在每帧开始时创建，提前返回的帧同样记录
+pub struct FrameTimer(Instant);
+
+impl FrameTimer {
+    /// 开始计时
+    pub fn start() -> Self {
+        Self(Instant::now())
+    }
+}
+
+impl Drop for FrameTimer {
+    fn drop(&mut self) {
+        record(Metric::Frame, self.0.elapsed());
+    }
+}
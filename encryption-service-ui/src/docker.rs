@@ -0,0 +1,839 @@
+use anyhow::{Context, Result};
+use futures_util::StreamExt;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use sha2::{Digest, Sha256};
+
+use bollard::{API_DEFAULT_VERSION, Docker, body_full};
+use bollard::errors::Error as DockerError;
+use bollard::exec::StartExecResults;
+use bollard::models::{ContainerCreateBody, ContainerStateStatusEnum, ContainerSummaryStateEnum, ContainerStatsResponse, ExecConfig, HostConfig, Ipam, IpamConfig, NetworkCreateRequest, PortBinding, RestartPolicyNameEnum};
+use bollard::query_parameters::{CreateContainerOptionsBuilder, CreateImageOptionsBuilder, DownloadFromContainerOptionsBuilder, ListContainersOptionsBuilder, LogsOptionsBuilder, RemoveContainerOptionsBuilder, StatsOptionsBuilder, StopContainerOptionsBuilder, UploadToContainerOptionsBuilder};
+
+use crate::models::{ContainerEngine, ContainerSpec, ContainerStatus, ContainerRestartPolicy, DockerConnection, DockerHost, GroupDockerNetwork, PortMapping};
+use crate::tasks;
+
+/// 标记由本工具创建的容器的标签，取值为模型ID
+pub const MANAGED_LABEL: &str = "encryption-service/id";
+/// 记录创建请求摘要的标签，启动时据此判断已有容器的定义是否变化
+const SPEC_LABEL: &str = "encryption-service/spec";
+/// 连接远程主机的超时秒数
+const CONNECT_TIMEOUT_SECS: u64 = 30;
+/// 停止容器时等待优雅退出的秒数
+const STOP_TIMEOUT_SECS: i32 = 10;
+/// 读写命名卷的临时容器中卷的挂载点，卷归档中的路径以此目录名开头
+const VOLUME_MOUNT: &str = "/volume";
+
+/// 由容器定义生成创建请求，模型注入的环境变量在前，定义中显式指定的可覆盖
+fn create_body(spec: ContainerSpec, id: &str, env: Vec<(&'static str, String)>) -> ContainerCreateBody {
+    let mut all_env: Vec<String> = env.into_iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+    all_env.extend(spec.env.iter().map(|e| format!("{}={}", e.key, e.value)));
+
+    let mut port_bindings: HashMap<String, Option<Vec<PortBinding>>> = HashMap::new();
+    for port in &spec.ports {
+        let key = format!("{}/{}", port.container_port, port.protocol);
+        port_bindings.entry(key).or_default().get_or_insert_default().push(PortBinding {
+            host_ip: (!port.host_ip.is_empty()).then(|| port.host_ip.clone()),
+            host_port: Some(port.host_port.to_string()),
+        });
+    }
+
+    let restart = match spec.restart {
+        ContainerRestartPolicy::No => RestartPolicyNameEnum::NO,
+        ContainerRestartPolicy::Always => RestartPolicyNameEnum::ALWAYS,
+        ContainerRestartPolicy::UnlessStopped => RestartPolicyNameEnum::UNLESS_STOPPED,
+        ContainerRestartPolicy::OnFailure => RestartPolicyNameEnum::ON_FAILURE,
+    };
+
+    ContainerCreateBody {
+        image: Some(spec.image),
+        cmd: (!spec.command.is_empty()).then_some(spec.command),
+        env: Some(all_env),
+        exposed_ports: Some(port_bindings.keys().cloned().collect()),
+        labels: Some(HashMap::from([(MANAGED_LABEL.to_string(), id.to_string())])),
+        host_config: Some(HostConfig {
+            binds: (!spec.volumes.is_empty()).then(|| spec.volumes.iter().map(|v| v.to_arg()).collect()),
+            network_mode: (!spec.network.is_empty()).then_some(spec.network),
+            port_bindings: Some(port_bindings),
+            nano_cpus: spec.nano_cpus.map(|n| n as i64),
+            memory: spec.memory.map(|m| m as i64),
+            restart_policy: Some(bollard::models::RestartPolicy {
+                name: Some(restart),
+                maximum_retry_count: None,
+            }),
+            ..HostConfig::default()
+        }),
+        ..ContainerCreateBody::default()
+    }
+}
+
+/// 创建请求的摘要；转为JSON值后键按字母序排列，端口等无序集合的顺序不影响结果
+fn spec_digest(body: &ContainerCreateBody) -> String {
+    let canonical = serde_json::to_value(body).map(|value| value.to_string()).unwrap_or_default();
+    hex::encode(Sha256::digest(canonical.as_bytes()))
+}
+
+/// 模型对应的容器名称，容器定义中未指定名称时按ID生成
+pub fn container_name(spec: &ContainerSpec, id: &str) -> String {
+    if spec.name.is_empty() {
+        format!("encryption-service-{}", id)
+    } else {
+        spec.name.clone()
+    }
+}
+
+/// 连接Docker主机，未指定时连接本机；Podman主机通过其Docker兼容API连接
+fn connect(host: Option<&DockerHost>) -> Result<Docker> {
+    let Some(host) = host.filter(|h| h.connection != DockerConnection::Local || h.engine == ContainerEngine::Podman) else {
+        return Docker::connect_with_local_defaults().context("无法连接本机Docker守护进程");
+    };
+    let url = host.url();
+    let docker = match host.connection {
+        DockerConnection::Local => Docker::connect_with_socket(&url, CONNECT_TIMEOUT_SECS, API_DEFAULT_VERSION),
+        DockerConnection::Tcp => Docker::connect_with_http(&url, CONNECT_TIMEOUT_SECS, API_DEFAULT_VERSION),
+        DockerConnection::Ssh if host.engine == ContainerEngine::Podman => anyhow::bail!(
+            "Podman主机不支持SSH连接，请在主机上运行 `podman system service tcp:<地址>:<端口>` 后以TCP连接: {}",
+            host.name,
+        ),
+        #[cfg(unix)]
+        DockerConnection::Ssh => {
+            let key = (!host.ssh_key.trim().is_empty()).then(|| host.ssh_key.trim().to_string());
+            Docker::connect_with_ssh(&url, CONNECT_TIMEOUT_SECS, API_DEFAULT_VERSION, key)
+        }
+        #[cfg(not(unix))]
+        DockerConnection::Ssh => anyhow::bail!("当前平台不支持通过SSH连接Docker主机: {}", host.name),
+    };
+    docker.with_context(|| format!("无法连接{}主机 {} ({})", host.engine.label(), host.name, url))
+}
+
+/// 测试主机连接，返回容器引擎版本
+pub fn ping(host: Option<&DockerHost>) -> Result<String> {
+    let engine = host.map(|h| h.engine).unwrap_or_default();
+    tasks::block_on(async {
+        let version = connect(host)?.version().await.with_context(|| format!("无法获取{}版本", engine.label()))?;
+        Ok(format!(
+            "{} {}（{}/{}）",
+            engine.label(),
+            version.version.unwrap_or_default(),
+            version.os.unwrap_or_default(),
+            version.arch.unwrap_or_default(),
+        ))
+    })
+}
+
+/// 启动容器时所需镜像在主机上不存在
+#[derive(Debug)]
+pub struct MissingImage {
+    pub image: String,
+}
+
+impl std::fmt::Display for MissingImage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "镜像 {} 不存在，请先拉取", self.image)
+    }
+}
+
+impl std::error::Error for MissingImage {}
+
+fn is_not_found(error: &DockerError) -> bool {
+    matches!(error, DockerError::DockerResponseServerError { status_code: 404, .. })
+}
+
+/// 按容器定义启动容器：已有的同名容器定义与镜像都未变化时直接启动，
+/// 否则删除后按最新定义重新创建
+///
+/// 容器定义未指定网络时接入业务组网络，网络不存在则先创建
+pub fn start(host: Option<&DockerHost>, id: &str, mut spec: ContainerSpec, env: Vec<(&'static str, String)>, network: Option<&GroupDockerNetwork>) -> Result<()> {
+    let name = container_name(&spec, id);
+    let image = spec.image.clone();
+    let network = network.filter(|_| spec.network.is_empty());
+    if let Some(network) = network {
+        spec.network = network.name.clone();
+    }
+    let mut body = create_body(spec, id, env);
+    let digest = spec_digest(&body);
+    body.labels.get_or_insert_default().insert(SPEC_LABEL.to_string(), digest.clone());
+    tasks::block_on(async {
+        let docker = connect(host)?;
+        if let Some(network) = network {
+            create_network_if_missing(&docker, network).await?;
+        }
+        match docker.inspect_container(&name, None).await {
+            Ok(existing) => {
+                let label = existing.config.and_then(|c| c.labels).and_then(|mut l| l.remove(SPEC_LABEL));
+                // 镜像标签已指向新拉取的镜像时也重新创建
+                let current_image = docker.inspect_image(&image).await.ok().and_then(|i| i.id);
+                if label.as_deref() == Some(digest.as_str()) && (current_image.is_none() || existing.image == current_image) {
+                    return start_existing(&docker, &name).await;
+                }
+                remove(&docker, &name).await?;
+            }
+            Err(e) if is_not_found(&e) => {}
+            Err(e) => return Err(e).with_context(|| format!("无法查询容器 {}", name)),
+        }
+        let options = CreateContainerOptionsBuilder::new().name(&name).build();
+        match docker.create_container(Some(options), body).await {
+            Err(e) if is_not_found(&e) => return Err(MissingImage { image }.into()),
+            result => result.with_context(|| format!("无法创建容器 {}", name))?,
+        };
+        start_existing(&docker, &name).await
+    })
+}
+
+/// 启动已创建的容器，容器已在运行时视为成功
+async fn start_existing(docker: &Docker, name: &str) -> Result<()> {
+    match docker.start_container(name, None).await {
+        // 304 表示容器已在运行
+        Err(DockerError::DockerResponseServerError { status_code: 304, .. }) | Ok(()) => Ok(()),
+        Err(e) => Err(e).with_context(|| format!("无法启动容器 {}", name)),
+    }
+}
+
+/// 停止容器并保留，下次启动时定义未变化则直接启动；容器不存在时视为成功
+pub fn stop(host: Option<&DockerHost>, id: &str, spec: &ContainerSpec) -> Result<()> {
+    let name = container_name(spec, id);
+    tasks::block_on(async { halt(&connect(host)?, &name).await.map(|_| ()) })
+}
+
+/// 按名称停止并删除容器，容器不存在时视为成功
+pub fn remove_container(host: Option<&DockerHost>, name: &str) -> Result<()> {
+    tasks::block_on(async { remove(&connect(host)?, name).await })
+}
+
+/// 主机上带有管理标签的容器
+#[derive(Debug, Clone)]
+pub struct LabeledContainer {
+    /// 标签中的模型ID
+    pub model_id: String,
+    pub name: String,
+    pub image: String,
+    pub running: bool,
+    /// Docker给出的状态说明，如 `Up 3 hours`
+    pub status: String,
+    /// 已发布到宿主机的端口
+    pub ports: Vec<PortMapping>,
+}
+
+/// 列出主机上由本工具创建的所有容器，包括已退出的
+pub fn list_labeled(host: Option<&DockerHost>) -> Result<Vec<LabeledContainer>> {
+    tasks::block_on(async {
+        let filters = HashMap::from([("label", vec![MANAGED_LABEL])]);
+        let options = ListContainersOptionsBuilder::new().all(true).filters(&filters).build();
+        let containers = connect(host)?
+            .list_containers(Some(options))
+            .await
+            .context("无法列出容器")?;
+        Ok(containers
+            .into_iter()
+            .filter_map(|container| {
+                let model_id = container.labels.as_ref()?.get(MANAGED_LABEL)?.clone();
+                let name = container.names.as_ref()?.first()?.trim_start_matches('/').to_string();
+                // 同时监听IPv4与IPv6时同一端口会列出两次
+                let mut ports: Vec<PortMapping> = container.ports
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter_map(|port| Some(PortMapping {
+                        host_ip: port.ip.filter(|ip| ip != "0.0.0.0" && ip != "::").unwrap_or_default(),
+                        host_port: port.public_port?,
+                        container_port: port.private_port,
+                        protocol: port.typ.map(|t| t.to_string()).unwrap_or_else(|| "tcp".to_string()),
+                    }))
+                    .collect();
+                ports.sort_by(|a, b| (a.host_port, a.container_port, &a.protocol).cmp(&(b.host_port, b.container_port, &b.protocol)));
+                ports.dedup();
+                Some(LabeledContainer {
+                    model_id,
+                    name,
+                    image: container.image.unwrap_or_default(),
+                    running: container.state == Some(ContainerSummaryStateEnum::RUNNING),
+                    status: container.status.unwrap_or_default(),
+                    ports,
+                })
+            })
+            .collect())
+    })
+}
+
+/// 确保业务组网络存在，不存在时创建为桥接网络
+pub fn ensure_network(host: Option<&DockerHost>, network: &GroupDockerNetwork) -> Result<()> {
+    tasks::block_on(async { create_network_if_missing(&connect(host)?, network).await })
+}
+
+/// 删除业务组网络，网络不存在时视为成功
+pub fn remove_network(host: Option<&DockerHost>, name: &str) -> Result<()> {
+    tasks::block_on(async {
+        match connect(host)?.remove_network(name).await {
+            Err(e) if is_not_found(&e) => Ok(()),
+            result => result.with_context(|| format!("无法删除网络 {}（可能仍有容器接入）", name)),
+        }
+    })
+}
+
+async fn create_network_if_missing(docker: &Docker, network: &GroupDockerNetwork) -> Result<()> {
+    match docker.inspect_network(&network.name, None).await {
+        Ok(_) => return Ok(()),
+        Err(e) if is_not_found(&e) => {}
+        Err(e) => return Err(e).with_context(|| format!("无法查询网络 {}", network.name)),
+    }
+    let subnet = network.subnet.trim();
+    let request = NetworkCreateRequest {
+        name: network.name.clone(),
+        driver: Some("bridge".to_string()),
+        ipam: (!subnet.is_empty()).then(|| Ipam {
+            config: Some(vec![IpamConfig {
+                subnet: Some(subnet.to_string()),
+                ..IpamConfig::default()
+            }]),
+            ..Ipam::default()
+        }),
+        ..NetworkCreateRequest::default()
+    };
+    docker
+        .create_network(request)
+        .await
+        .with_context(|| format!("无法创建网络 {}", network.name))?;
+    Ok(())
+}
+
+/// 停止容器，返回容器是否存在
+async fn halt(docker: &Docker, name: &str) -> Result<bool> {
+    let stop = StopContainerOptionsBuilder::new().t(STOP_TIMEOUT_SECS).build();
+    match docker.stop_container(name, Some(stop)).await {
+        Err(e) if is_not_found(&e) => Ok(false),
+        // 304 表示容器本就未运行
+        Err(DockerError::DockerResponseServerError { status_code: 304, .. }) | Ok(()) => Ok(true),
+        Err(e) => Err(e).with_context(|| format!("无法停止容器 {}", name)),
+    }
+}
+
+async fn remove(docker: &Docker, name: &str) -> Result<()> {
+    if !halt(docker, name).await? {
+        return Ok(());
+    }
+    let options = RemoveContainerOptionsBuilder::new().force(true).build();
+    match docker.remove_container(name, Some(options)).await {
+        Err(e) if is_not_found(&e) => Ok(()),
+        result => result.with_context(|| format!("无法删除容器 {}", name)),
+    }
+}
+
+/// 在运行中的容器内通过 `sh -c` 执行命令，输出到达时逐段回调；`cancel` 置位后停止接收并返回空退出码
+pub fn exec(
+    host: Option<&DockerHost>,
+    id: &str,
+    params: &str,
+    command: &str,
+    working_dir: &str,
+    cancel: &AtomicBool,
+    mut output: impl FnMut(&str),
+) -> Result<Option<i64>> {
+    let spec = ContainerSpec::parse(params)?;
+    let name = container_name(&spec, id);
+    tasks::block_on(async {
+        let docker = connect(host)?;
+        let config = ExecConfig {
+            attach_stdout: Some(true),
+            attach_stderr: Some(true),
+            cmd: Some(vec!["sh".to_string(), "-c".to_string(), command.to_string()]),
+            working_dir: (!working_dir.trim().is_empty()).then(|| working_dir.trim().to_string()),
+            ..Default::default()
+        };
+        let created = match docker.create_exec(&name, config).await {
+            Err(e) if is_not_found(&e) => anyhow::bail!("容器 {} 不存在或未运行", name),
+            result => result.with_context(|| format!("无法在容器 {} 中创建执行", name))?,
+        };
+        let StartExecResults::Attached { output: mut stream, .. } = docker
+            .start_exec(&created.id, None)
+            .await
+            .with_context(|| format!("无法在容器 {} 中执行命令", name))?
+        else {
+            anyhow::bail!("执行未附加输出");
+        };
+        loop {
+            if cancel.load(Ordering::Relaxed) {
+                return Ok(None);
+            }
+            // 定期醒来检查取消，长时间无输出的命令也能中止
+            let Ok(chunk) = tokio::time::timeout(Duration::from_millis(200), stream.next()).await else {
+                continue;
+            };
+            let Some(chunk) = chunk else {
+                break;
+            };
+            let chunk = chunk.context("读取执行输出失败")?;
+            output(&String::from_utf8_lossy(&chunk.into_bytes()));
+        }
+        let inspect = docker.inspect_exec(&created.id).await.context("无法查询执行结果")?;
+        Ok(inspect.exit_code)
+    })
+}
+
+/// 持续读取容器的标准输出与错误输出，每个完整的行回调一次；容器退出或 `cancel` 置位后返回
+///
+/// `since` 为Unix秒时，只读取该时间之后的输出，用于中断后接续；为空时先读取最后 `tail` 行
+pub fn follow_logs(
+    host: Option<&DockerHost>,
+    id: &str,
+    params: &str,
+    tail: usize,
+    since: Option<i64>,
+    cancel: &AtomicBool,
+    mut line: impl FnMut(&str),
+) -> Result<()> {
+    let spec = ContainerSpec::parse(params)?;
+    let name = container_name(&spec, id);
+    tasks::block_on(async {
+        let docker = connect(host)?;
+        let mut options = LogsOptionsBuilder::new().follow(true).stdout(true).stderr(true);
+        options = match since {
+            Some(since) => options.since(i32::try_from(since).unwrap_or(i32::MAX)),
+            None => options.tail(&tail.to_string()),
+        };
+        let mut stream = docker.logs(&name, Some(options.build()));
+        // 一次输出可能包含多行或只有半行，未结束的行留到下次输出
+        let mut partial = String::new();
+        loop {
+            if cancel.load(Ordering::Relaxed) {
+                return Ok(());
+            }
+            // 定期醒来检查取消，长时间无输出的容器也能停止读取
+            let Ok(chunk) = tokio::time::timeout(Duration::from_millis(500), stream.next()).await else {
+                continue;
+            };
+            let Some(chunk) = chunk else {
+                break;
+            };
+            let chunk = match chunk {
+                Err(e) if is_not_found(&e) => anyhow::bail!("容器 {} 不存在", name),
+                result => result.with_context(|| format!("读取容器 {} 的日志失败", name))?,
+            };
+            partial.push_str(&String::from_utf8_lossy(&chunk.into_bytes()));
+            while let Some(end) = partial.find('\n') {
+                line(partial[..end].trim_end_matches('\r'));
+                partial.drain(..=end);
+            }
+        }
+        if !partial.is_empty() {
+            line(&partial);
+        }
+        Ok(())
+    })
+}
+
+/// 将单个文件复制到运行中容器内的目录
+pub fn upload(host: Option<&DockerHost>, id: &str, params: &str, dir: &str, file_name: &str, contents: &[u8], mode: u32) -> Result<()> {
+    let spec = ContainerSpec::parse(params)?;
+    let name = container_name(&spec, id);
+    let archive = tar_file(file_name, contents, mode)?;
+    tasks::block_on(async {
+        let docker = connect(host)?;
+        let options = UploadToContainerOptionsBuilder::default().path(dir).build();
+        match docker.upload_to_container(&name, Some(options), body_full(archive.into())).await {
+            Err(e) if is_not_found(&e) => anyhow::bail!("容器 {} 不存在或目录 {} 不存在", name, dir),
+            result => result.with_context(|| format!("无法复制文件到容器 {}", name)),
+        }
+    })
+}
+
+/// 命名卷是否已存在于主机
+pub fn volume_exists(host: Option<&DockerHost>, volume: &str) -> Result<bool> {
+    tasks::block_on(async {
+        match connect(host)?.inspect_volume(volume).await {
+            Ok(_) => Ok(true),
+            Err(e) if is_not_found(&e) => Ok(false),
+            Err(e) => Err(e).with_context(|| format!("无法查询卷 {}", volume)),
+        }
+    })
+}
+
+/// 创建挂载命名卷但不启动的临时容器，卷不存在时由Docker创建；返回容器名称
+async fn create_volume_container(docker: &Docker, image: &str, volume: &str) -> Result<String> {
+    let name = format!("encryption-service-volume-{}", uuid::Uuid::new_v4());
+    let options = CreateContainerOptionsBuilder::new().name(&name).build();
+    let body = ContainerCreateBody {
+        image: Some(image.to_string()),
+        host_config: Some(HostConfig {
+            binds: Some(vec![format!("{}:{}", volume, VOLUME_MOUNT)]),
+            ..HostConfig::default()
+        }),
+        ..ContainerCreateBody::default()
+    };
+    match docker.create_container(Some(options), body).await {
+        Err(e) if is_not_found(&e) => Err(MissingImage { image: image.to_string() }.into()),
+        result => result.map(|_| name).with_context(|| format!("无法创建挂载卷 {} 的临时容器", volume)),
+    }
+}
+
+/// 将命名卷的内容导出为tar归档，借助以 `image` 创建、不启动的临时容器读取
+pub fn export_volume(host: Option<&DockerHost>, image: &str, volume: &str) -> Result<Vec<u8>> {
+    tasks::block_on(async {
+        let docker = connect(host)?;
+        let name = create_volume_container(&docker, image, volume).await?;
+        let options = DownloadFromContainerOptionsBuilder::new().path(VOLUME_MOUNT).build();
+        let mut stream = docker.download_from_container(&name, Some(options));
+        let mut archive = Vec::new();
+        let mut result = Ok(());
+        while let Some(chunk) = stream.next().await {
+            match chunk {
+                Ok(chunk) => archive.extend_from_slice(&chunk),
+                Err(e) => {
+                    result = Err(e).with_context(|| format!("无法读取卷 {}", volume));
+                    break;
+                }
+            }
+        }
+        drop(stream);
+        if let Err(e) = remove(&docker, &name).await {
+            tracing::warn!("{:#}", e);
+        }
+        result.map(|_| archive)
+    })
+}
+
+/// 将 [`export_volume`] 导出的归档写入命名卷，卷不存在时创建
+pub fn import_volume(host: Option<&DockerHost>, image: &str, volume: &str, archive: Vec<u8>) -> Result<()> {
+    tasks::block_on(async {
+        let docker = connect(host)?;
+        let name = create_volume_container(&docker, image, volume).await?;
+        let options = UploadToContainerOptionsBuilder::default().path("/").build();
+        let result = docker
+            .upload_to_container(&name, Some(options), body_full(archive.into()))
+            .await
+            .with_context(|| format!("无法写入卷 {}", volume));
+        if let Err(e) = remove(&docker, &name).await {
+            tracing::warn!("{:#}", e);
+        }
+        result
+    })
+}
+
+/// 将单个文件打包为ustar格式的tar归档，供上传到容器
+fn tar_file(file_name: &str, contents: &[u8], mode: u32) -> Result<Vec<u8>> {
+    if file_name.is_empty() || file_name.len() > 99 {
+        anyhow::bail!("文件名为空或过长: {}", file_name);
+    }
+    let octal = |field: &mut [u8], value: u64| {
+        let text = format!("{:0width$o}\0", value, width = field.len() - 1);
+        field.copy_from_slice(text.as_bytes());
+    };
+    let mut header = [0u8; 512];
+    header[..file_name.len()].copy_from_slice(file_name.as_bytes());
+    octal(&mut header[100..108], u64::from(mode));
+    octal(&mut header[108..116], 0);
+    octal(&mut header[116..124], 0);
+    octal(&mut header[124..136], contents.len() as u64);
+    octal(&mut header[136..148], chrono::Utc::now().timestamp().max(0) as u64);
+    header[156] = b'0';
+    header[257..263].copy_from_slice(b"ustar\0");
+    header[263..265].copy_from_slice(b"00");
+    // 校验和按校验和字段为空格计算
+    header[148..156].fill(b' ');
+    let checksum: u32 = header.iter().map(|&b| u32::from(b)).sum();
+    header[148..156].copy_from_slice(format!("{:06o}\0 ", checksum).as_bytes());
+
+    let padding = (512 - contents.len() % 512) % 512;
+    let mut archive = Vec::with_capacity(512 + contents.len() + padding + 1024);
+    archive.extend_from_slice(&header);
+    archive.extend_from_slice(contents);
+    archive.resize(archive.len() + padding + 1024, 0);
+    Ok(archive)
+}
+
+/// 需要查询状态的容器
+pub struct InspectTarget {
+    pub id: String,
+    pub params: String,
+    pub host: Option<DockerHost>,
+}
+
+/// 查询容器的实际状态，返回以模型ID为键的状态
+///
+/// 容器不存在时为已停止，非零退出或异常终止为错误；无法连接的主机上的容器不出现在结果中
+pub fn inspect(targets: &[InspectTarget]) -> Result<HashMap<String, ContainerStatus>> {
+    tasks::block_on(async {
+        let mut clients: HashMap<Option<String>, Option<Docker>> = HashMap::new();
+        let mut statuses = HashMap::new();
+        for target in targets {
+            let Ok(spec) = ContainerSpec::parse(&target.params) else {
+                continue;
+            };
+            let host_id = target.host.as_ref().map(|h| h.id.clone());
+            let docker = clients.entry(host_id).or_insert_with(|| {
+                connect(target.host.as_ref())
+                    .inspect_err(|e| tracing::warn!("{:#}", e))
+                    .ok()
+            });
+            let Some(docker) = docker else {
+                continue;
+            };
+            let name = container_name(&spec, &target.id);
+            let status = match docker.inspect_container(&name, None).await {
+                Ok(response) => {
+                    let state = response.state.unwrap_or_default();
+                    match state.status {
+                        Some(ContainerStateStatusEnum::RUNNING) => ContainerStatus::Running,
+                        Some(ContainerStateStatusEnum::CREATED | ContainerStateStatusEnum::RESTARTING) => ContainerStatus::Starting,
+                        Some(ContainerStateStatusEnum::EXITED) if state.exit_code == Some(0) => ContainerStatus::Stopped,
+                        Some(ContainerStateStatusEnum::PAUSED | ContainerStateStatusEnum::REMOVING) => ContainerStatus::Stopped,
+                        _ => ContainerStatus::Error,
+                    }
+                }
+                Err(e) if is_not_found(&e) => ContainerStatus::Stopped,
+                Err(e) => {
+                    tracing::warn!("无法查询容器 {}: {:#}", name, e);
+                    continue;
+                }
+            };
+            statuses.insert(target.id.clone(), status);
+        }
+        Ok(statuses)
+    })
+}
+
+/// 容器的一次资源统计
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ContainerStats {
+    /// CPU占用百分比，多核时可超过100
+    pub cpu_percent: f64,
+    /// 内存用量，不含可回收的页缓存
+    pub memory_bytes: u64,
+    /// 内存上限，未限制时为主机内存
+    pub memory_limit: u64,
+    /// 各网卡累计接收字节
+    pub rx_bytes: u64,
+    /// 各网卡累计发送字节
+    pub tx_bytes: u64,
+}
+
+impl ContainerStats {
+    /// 按 `docker stats` 的算法换算统计结果
+    fn from_response(response: ContainerStatsResponse) -> Self {
+        let cpu = response.cpu_stats.unwrap_or_default();
+        let precpu = response.precpu_stats.unwrap_or_default();
+        let usage = |stats: &bollard::models::ContainerCpuStats| stats.cpu_usage.as_ref().and_then(|u| u.total_usage).unwrap_or(0);
+        let cpu_delta = usage(&cpu).saturating_sub(usage(&precpu));
+        let system_delta = cpu.system_cpu_usage.unwrap_or(0).saturating_sub(precpu.system_cpu_usage.unwrap_or(0));
+        let cpus = cpu
+            .online_cpus
+            .map(u64::from)
+            .or_else(|| cpu.cpu_usage.as_ref().and_then(|u| u.percpu_usage.as_ref()).map(|p| p.len() as u64))
+            .unwrap_or(1);
+        let cpu_percent = if system_delta > 0 {
+            cpu_delta as f64 / system_delta as f64 * cpus as f64 * 100.0
+        } else {
+            0.0
+        };
+
+        let memory = response.memory_stats.unwrap_or_default();
+        // cgroup v1 与 v2 的页缓存字段名不同
+        let cache = memory
+            .stats
+            .as_ref()
+            .and_then(|s| s.get("inactive_file").or_else(|| s.get("total_inactive_file")).copied())
+            .unwrap_or(0);
+        let (rx_bytes, tx_bytes) = response
+            .networks
+            .unwrap_or_default()
+            .values()
+            .fold((0, 0), |(rx, tx), n| (rx + n.rx_bytes.unwrap_or(0), tx + n.tx_bytes.unwrap_or(0)));
+        Self {
+            cpu_percent,
+            memory_bytes: memory.usage.unwrap_or(0).saturating_sub(cache),
+            memory_limit: memory.limit.unwrap_or(0),
+            rx_bytes,
+            tx_bytes,
+        }
+    }
+}
+
+/// 查询容器的资源统计，返回以模型ID为键的结果
+///
+/// 各容器并发查询；未运行、不存在或所在主机无法连接的容器不出现在结果中
+pub fn stats(targets: &[InspectTarget]) -> Result<HashMap<String, ContainerStats>> {
+    tasks::block_on(async {
+        let mut clients: HashMap<Option<String>, Option<Docker>> = HashMap::new();
+        let mut queries = Vec::new();
+        for target in targets {
+            let Ok(spec) = ContainerSpec::parse(&target.params) else {
+                continue;
+            };
+            let host_id = target.host.as_ref().map(|h| h.id.clone());
+            let docker = clients.entry(host_id).or_insert_with(|| {
+                connect(target.host.as_ref())
+                    .inspect_err(|e| tracing::warn!("{:#}", e))
+                    .ok()
+            });
+            let Some(docker) = docker.clone() else {
+                continue;
+            };
+            let name = container_name(&spec, &target.id);
+            let id = target.id.clone();
+            queries.push(async move {
+                // 非一次性查询时守护进程会等待一个采样周期，返回的数据带有上一周期的CPU用量
+                let options = StatsOptionsBuilder::new().stream(false).one_shot(false).build();
+                let response = docker.stats(&name, Some(options)).next().await?;
+                match response {
+                    Ok(response) => Some((id, ContainerStats::from_response(response))),
+                    Err(e) => {
+                        if !is_not_found(&e) {
+                            tracing::debug!("无法查询容器 {} 的资源统计: {:#}", name, e);
+                        }
+                        None
+                    }
+                }
+            });
+        }
+        Ok(futures_util::future::join_all(queries).await.into_iter().flatten().collect())
+    })
+}
+
+/// 镜像拉取进度，按层汇总
+#[derive(Debug, Clone, Default)]
+pub struct PullProgress {
+    /// 最近一条状态信息
+    pub status: String,
+    /// 各层的（已下载字节, 总字节, 是否完成）
+    layers: BTreeMap<String, (u64, u64, bool)>,
+}
+
+impl PullProgress {
+    /// 已完成层数与总层数
+    pub fn layer_counts(&self) -> (usize, usize) {
+        let completed = self.layers.values().filter(|(_, _, done)| *done).count();
+        (completed, self.layers.len())
+    }
+
+    /// 已下载字节与总字节，只统计已知大小的层
+    pub fn bytes(&self) -> (u64, u64) {
+        self.layers
+            .values()
+            .filter(|(_, total, _)| *total > 0)
+            .fold((0, 0), |(current, total), (c, t, done)| {
+                (current + if *done { *t } else { (*c).min(*t) }, total + t)
+            })
+    }
+
+    /// 总体完成比例，尚无层信息时为空
+    pub fn fraction(&self) -> Option<f32> {
+        let (completed, layers) = self.layer_counts();
+        if layers == 0 {
+            return None;
+        }
+        let (current, total) = self.bytes();
+        if total == 0 {
+            return Some(completed as f32 / layers as f32);
+        }
+        Some(current as f32 / total as f32)
+    }
+
+    fn update(&mut self, id: Option<String>, status: &str, current: Option<i64>, total: Option<i64>) {
+        self.status = match &id {
+            Some(id) => format!("{}: {}", id, status),
+            None => status.to_string(),
+        };
+        // 不带层ID的信息（如摘要、最终状态）只更新状态文字
+        let Some(id) = id else {
+            return;
+        };
+        let layer = self.layers.entry(id).or_default();
+        match status {
+            "Download complete" | "Pull complete" | "Already exists" => layer.2 = true,
+            "Downloading" => {
+                layer.0 = current.unwrap_or_default().max(0) as u64;
+                layer.1 = total.unwrap_or_default().max(0) as u64;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// 拆分镜像引用为仓库与标签，未指定标签时为 latest；带摘要的引用整体作为仓库
+fn split_reference(image: &str) -> (&str, &str) {
+    if image.contains('@') {
+        return (image, "");
+    }
+    let name_start = image.rfind('/').map(|i| i + 1).unwrap_or(0);
+    match image[name_start..].rfind(':') {
+        Some(i) => (&image[..name_start + i], &image[name_start + i + 1..]),
+        None => (image, "latest"),
+    }
+}
+
+/// 镜像在主机上与仓库中的摘要
+#[derive(Debug, Clone, Default)]
+pub struct ImageDigests {
+    /// 主机上镜像拉取时的摘要，镜像不存在或为本地构建时为空
+    pub local: Option<String>,
+    /// 仓库中同一标签当前的摘要
+    pub remote: Option<String>,
+}
+
+/// 查询镜像在主机上与仓库中的摘要，由守护进程访问仓库，沿用其登录凭据
+pub fn image_digests(host: Option<&DockerHost>, image: &str) -> Result<ImageDigests> {
+    let (repo, _) = split_reference(image);
+    tasks::block_on(async {
+        let docker = connect(host)?;
+        let local = match docker.inspect_image(image).await {
+            Ok(inspect) => {
+                let digests = inspect.repo_digests.unwrap_or_default();
+                digests
+                    .iter()
+                    .find(|d| d.split_once('@').is_some_and(|(r, _)| r == repo))
+                    .or(digests.first())
+                    .and_then(|d| d.split_once('@'))
+                    .map(|(_, digest)| digest.to_string())
+            }
+            Err(e) if is_not_found(&e) => None,
+            Err(e) => return Err(e).with_context(|| format!("无法查询镜像 {}", image)),
+        };
+        let remote = docker
+            .inspect_registry_image(image, None)
+            .await
+            .with_context(|| format!("无法查询仓库中的镜像 {}", image))?
+            .descriptor
+            .digest;
+        Ok(ImageDigests { local, remote })
+    })
+}
+
+/// 拉取镜像，每收到一条进度信息回调一次；`cancel` 置位后中止
+pub fn pull(host: Option<&DockerHost>, image: &str, cancel: &AtomicBool, mut progress: impl FnMut(&PullProgress)) -> Result<()> {
+    let (repo, tag) = split_reference(image);
+    tasks::block_on(async {
+        let docker = connect(host)?;
+        let options = CreateImageOptionsBuilder::new().from_image(repo).tag(tag).build();
+        let mut stream = docker.create_image(Some(options), None, None);
+        let mut state = PullProgress::default();
+        while let Some(info) = stream.next().await {
+            if cancel.load(Ordering::Relaxed) {
+                anyhow::bail!("已取消拉取镜像 {}", image);
+            }
+            let info = info.with_context(|| format!("拉取镜像 {} 失败", image))?;
+            let detail = info.progress_detail.unwrap_or_default();
+            state.update(info.id, info.status.as_deref().unwrap_or_default(), detail.current, detail.total);
+            progress(&state);
+        }
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digest(params: &str) -> String {
+        spec_digest(&create_body(ContainerSpec::parse(params).unwrap(), "b-1", vec![("SERVICE_ID", "b-1".to_string())]))
+    }
+
+    #[test]
+    fn spec_digest_tracks_definition_changes() {
+        let base = digest("-d -p 8080:80 -p 9090:90 -e MODE=prod crud-api:1.0");
+        assert_eq!(base, digest("-d -p 8080:80 -p 9090:90 -e MODE=prod crud-api:1.0"));
+        // 端口映射的书写顺序不影响摘要
+        assert_eq!(base, digest("-d -p 9090:90 -p 8080:80 -e MODE=prod crud-api:1.0"));
+        assert_ne!(base, digest("-d -p 8080:80 -p 9090:90 -e MODE=prod crud-api:1.1"));
+        assert_ne!(base, digest("-d -p 8080:80 -p 9090:90 -e MODE=dev crud-api:1.0"));
+        assert_ne!(base, digest("-d -p 8081:80 -p 9090:90 -e MODE=prod crud-api:1.0"));
+    }
+}
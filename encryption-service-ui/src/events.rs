@@ -0,0 +1,210 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use serde::{Deserialize, Serialize};
+
+use crate::models::{AppState, BusinessGroup, BusinessGroups, ContainerStatus, GroupStatus, HealthStatus};
+
+/// 实体类型
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum EntityKind {
+    Group,
+    Middleware,
+    Backend,
+}
+
+impl EntityKind {
+    /// 显示名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            EntityKind::Group => "业务组",
+            EntityKind::Middleware => "中间层",
+            EntityKind::Backend => "后端",
+        }
+    }
+}
+
+/// 模型变更事件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModelEvent {
+    Created { kind: EntityKind, id: String, name: String },
+    Updated { kind: EntityKind, id: String, name: String },
+    Deleted { kind: EntityKind, id: String, name: String },
+    StatusChanged { kind: EntityKind, id: String, name: String, from: String, to: String },
+    HealthChanged { kind: EntityKind, id: String, name: String, from: HealthStatus, to: HealthStatus },
+    /// 从配置文件整体重新加载
+    Reloaded,
+}
+
+impl ModelEvent {
+    /// 事件涉及的实体
+    pub fn entity(&self) -> Option<(EntityKind, &str)> {
+        match self {
+            ModelEvent::Created { kind, id, .. }
+            | ModelEvent::Updated { kind, id, .. }
+            | ModelEvent::Deleted { kind, id, .. }
+            | ModelEvent::StatusChanged { kind, id, .. }
+            | ModelEvent::HealthChanged { kind, id, .. } => Some((*kind, id.as_str())),
+            ModelEvent::Reloaded => None,
+        }
+    }
+
+    /// 事件描述
+    pub fn describe(&self) -> String {
+        match self {
+            ModelEvent::Created { kind, name, .. } => format!("创建{} {}", kind.label(), name),
+            ModelEvent::Updated { kind, name, .. } => format!("更新{} {}", kind.label(), name),
+            ModelEvent::Deleted { kind, name, .. } => format!("删除{} {}", kind.label(), name),
+            ModelEvent::StatusChanged { kind, name, from, to, .. } => format!("{} {} 状态: {} → {}", kind.label(), name, from, to),
+            ModelEvent::HealthChanged { kind, name, from, to, .. } => format!("{} {} 健康状态: {} → {}", kind.label(), name, from.label(), to.label()),
+            ModelEvent::Reloaded => "重新加载配置".to_string(),
+        }
+    }
+}
+
+/// 进程内事件总线，订阅者各自持有接收端
+#[derive(Clone, Default)]
+pub struct EventBus {
+    subscribers: Arc<Mutex<Vec<Sender<ModelEvent>>>>,
+}
+
+impl EventBus {
+    /// 创建新的事件总线
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 订阅事件
+    pub fn subscribe(&self) -> Receiver<ModelEvent> {
+        let (sender, receiver) = mpsc::channel();
+        self.subscribers.lock().unwrap_or_else(|e| e.into_inner()).push(sender);
+        receiver
+    }
+
+    /// 发布事件，移除已断开的订阅者
+    pub fn publish(&self, events: &[ModelEvent]) {
+        if events.is_empty() {
+            return;
+        }
+        let mut subscribers = self.subscribers.lock().unwrap_or_else(|e| e.into_inner());
+        subscribers.retain(|sender| events.iter().all(|event| sender.send(event.clone()).is_ok()));
+    }
+}
+
+/// 用于比较的实体快照
+struct EntitySnapshot {
+    kind: EntityKind,
+    name: String,
+    status: String,
+    health: Option<HealthStatus>,
+    /// 除状态与子实体外的内容
+    body: String,
+}
+
+/// 展开状态中除未修改业务组外的所有实体，按ID索引并保持原有顺序
+fn snapshot(state: &AppState, unchanged: &HashSet<*const BusinessGroup>) -> (Vec<String>, HashMap<String, EntitySnapshot>) {
+    let mut order = Vec::new();
+    let mut entities = HashMap::new();
+    let mut insert = |id: &str, entity: EntitySnapshot| {
+        order.push(id.to_string());
+        entities.insert(id.to_string(), entity);
+    };
+
+    for group in state.business_groups.iter().filter(|g| !BusinessGroups::is_shared(unchanged, g)) {
+        let mut body = group.clone();
+        body.middlewares.clear();
+        body.backend_containers.clear();
+        body.status = GroupStatus::Stopped;
+        insert(&group.id, EntitySnapshot {
+            kind: EntityKind::Group,
+            name: group.name.clone(),
+            status: group.status.label().to_string(),
+            health: None,
+            body: serde_json::to_string(&body).unwrap_or_default(),
+        });
+
+        for middleware in &group.middlewares {
+            let mut body = middleware.clone();
+            body.backend_containers.clear();
+            body.status = ContainerStatus::Stopped;
+            body.health = HealthStatus::Unknown;
+            insert(&middleware.id, EntitySnapshot {
+                kind: EntityKind::Middleware,
+                name: middleware.name.clone(),
+                status: middleware.status.label().to_string(),
+                health: Some(middleware.health.clone()),
+                body: serde_json::to_string(&body).unwrap_or_default(),
+            });
+        }
+
+        let backends = group.middlewares
+            .iter()
+            .flat_map(|m| m.backend_containers.iter())
+            .chain(group.backend_containers.iter());
+        for backend in backends {
+            let mut body = backend.clone();
+            body.status = ContainerStatus::Stopped;
+            body.health = HealthStatus::Unknown;
+            insert(&backend.id, EntitySnapshot {
+                kind: EntityKind::Backend,
+                name: backend.name.clone(),
+                status: backend.status.label().to_string(),
+                health: Some(backend.health.clone()),
+                body: serde_json::to_string(&body).unwrap_or_default(),
+            });
+        }
+    }
+
+    (order, entities)
+}
+
+/// 比较前后两份状态，生成变更事件
+///
+/// 两份状态仍共享存储的业务组未被修改，跳过其中的实体，只比较修改过的业务组
+pub fn diff(old: &AppState, new: &AppState) -> Vec<ModelEvent> {
+    let unchanged = old.business_groups.shared_with(&new.business_groups);
+    let (old_order, old_entities) = snapshot(old, &unchanged);
+    let (new_order, new_entities) = snapshot(new, &unchanged);
+    let mut events = Vec::new();
+
+    for id in &old_order {
+        if !new_entities.contains_key(id) {
+            let entity = &old_entities[id];
+            events.push(ModelEvent::Deleted { kind: entity.kind, id: id.clone(), name: entity.name.clone() });
+        }
+    }
+
+    for id in &new_order {
+        let entity = &new_entities[id];
+        let Some(previous) = old_entities.get(id) else {
+            events.push(ModelEvent::Created { kind: entity.kind, id: id.clone(), name: entity.name.clone() });
+            continue;
+        };
+
+        if previous.body != entity.body {
+            events.push(ModelEvent::Updated { kind: entity.kind, id: id.clone(), name: entity.name.clone() });
+        }
+        if previous.status != entity.status {
+            events.push(ModelEvent::StatusChanged {
+                kind: entity.kind,
+                id: id.clone(),
+                name: entity.name.clone(),
+                from: previous.status.clone(),
+                to: entity.status.clone(),
+            });
+        }
+        if let (Some(from), Some(to)) = (&previous.health, &entity.health)
+            && from != to
+        {
+            events.push(ModelEvent::HealthChanged {
+                kind: entity.kind,
+                id: id.clone(),
+                name: entity.name.clone(),
+                from: from.clone(),
+                to: to.clone(),
+            });
+        }
+    }
+
+    events
+}
@@ -0,0 +1,186 @@
+use anyhow::{Context, Result};
+use chrono::Utc;
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::BufWriter;
+
+use crate::metrics::{EndpointKind, RatePoint};
+use crate::topology::{self, TopologyLayout};
+
+/// 图表宽度
+const CHART_WIDTH: f32 = 640.0;
+/// 单个图表高度
+const CHART_HEIGHT: f32 = 200.0;
+/// 图表内边距
+const CHART_PADDING: f32 = 40.0;
+/// 曲线配色
+const SERIES_COLORS: [&str; 4] = ["#1e88e5", "#43a047", "#fb8c00", "#8e24aa"];
+
+/// 图像导出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,
+    Svg,
+}
+
+impl ImageFormat {
+    /// 文件扩展名
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ImageFormat::Png => "png",
+            ImageFormat::Svg => "svg",
+        }
+    }
+}
+
+/// 生成带时间戳的导出文件名
+pub fn export_file_name(prefix: &str, format: ImageFormat) -> String {
+    format!("{}_{}.{}", prefix, Utc::now().format("%Y%m%d_%H%M%S"), format.extension())
+}
+
+/// 转义XML文本
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// 绘制单个折线图，`top` 为图表在画布中的纵向偏移
+fn write_line_chart(
+    out: &mut String,
+    top: f32,
+    title: &str,
+    series: &[(EndpointKind, Vec<RatePoint>)],
+    value: fn(&RatePoint) -> f64,
+) {
+    let now = Utc::now();
+    let left = CHART_PADDING;
+    let right = CHART_WIDTH - CHART_PADDING / 2.0;
+    let plot_top = top + CHART_PADDING;
+    let plot_bottom = top + CHART_HEIGHT - CHART_PADDING / 2.0;
+
+    let points: Vec<(f64, f64)> = series
+        .iter()
+        .flat_map(|(_, points)| points.iter())
+        .map(|p| ((p.timestamp - now).num_milliseconds() as f64 / 1000.0, value(p)))
+        .collect();
+    let min_x = points.iter().map(|p| p.0).fold(f64::INFINITY, f64::min).min(-1.0);
+    let max_y = points.iter().map(|p| p.1).fold(0.0, f64::max).max(1.0) * 1.1;
+
+    let scale_x = |x: f64| left as f64 + (x - min_x) / (0.0 - min_x) * (right - left) as f64;
+    let scale_y = |y: f64| plot_bottom as f64 - y / max_y * (plot_bottom - plot_top) as f64;
+
+    let _ = writeln!(out, r#"<text x="{}" y="{}" font-size="14" font-weight="bold">{}</text>"#, left, top + 20.0, escape_xml(title));
+    let _ = writeln!(
+        out,
+        r##"<rect x="{}" y="{}" width="{}" height="{}" fill="none" stroke="#9e9e9e"/>"##,
+        left, plot_top, right - left, plot_bottom - plot_top,
+    );
+    let _ = writeln!(out, r#"<text x="{}" y="{}" font-size="10" text-anchor="end">{:.2}</text>"#, left - 4.0, plot_top + 10.0, max_y);
+    let _ = writeln!(out, r#"<text x="{}" y="{}" font-size="10" text-anchor="end">0</text>"#, left - 4.0, plot_bottom);
+    let _ = writeln!(out, r#"<text x="{}" y="{}" font-size="10">{:.0}s</text>"#, left, plot_bottom + 12.0, min_x);
+    let _ = writeln!(out, r#"<text x="{}" y="{}" font-size="10" text-anchor="end">0s</text>"#, right, plot_bottom + 12.0);
+
+    for (index, (kind, points)) in series.iter().enumerate() {
+        let color = SERIES_COLORS[index % SERIES_COLORS.len()];
+        let path: Vec<String> = points
+            .iter()
+            .map(|p| {
+                let x = (p.timestamp - now).num_milliseconds() as f64 / 1000.0;
+                format!("{:.1},{:.1}", scale_x(x), scale_y(value(p)))
+            })
+            .collect();
+        if !path.is_empty() {
+            let _ = writeln!(
+                out,
+                r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="2"/>"#,
+                path.join(" "),
+                color,
+            );
+        }
+
+        let legend_x = right - 70.0 * (series.len() - index) as f32;
+        let _ = writeln!(out, r#"<rect x="{}" y="{}" width="10" height="10" fill="{}"/>"#, legend_x, top + 10.0, color);
+        let _ = writeln!(out, r#"<text x="{}" y="{}" font-size="12">{}</text>"#, legend_x + 14.0, top + 19.0, kind.label());
+    }
+}
+
+/// 将QPS与错误率图表渲染为SVG
+pub fn rate_charts_svg(title: &str, series: &[(EndpointKind, Vec<RatePoint>)]) -> String {
+    let height = CHART_HEIGHT * 2.0 + 30.0;
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}" font-family="sans-serif">"#,
+        CHART_WIDTH, height, CHART_WIDTH, height,
+    );
+    let _ = writeln!(out, r#"<rect width="100%" height="100%" fill="white"/>"#);
+    let _ = writeln!(out, r#"<text x="{}" y="20" font-size="16" font-weight="bold">{}</text>"#, CHART_PADDING, escape_xml(title));
+    write_line_chart(&mut out, 30.0, "QPS", series, |p| p.qps);
+    write_line_chart(&mut out, 30.0 + CHART_HEIGHT, "错误率 (%)", series, |p| p.error_rate * 100.0);
+    let _ = writeln!(out, "</svg>");
+    out
+}
+
+/// 将拓扑布局渲染为SVG
+pub fn topology_svg(layout: &TopologyLayout) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}" font-family="sans-serif">"#,
+        layout.width, layout.height, layout.width, layout.height,
+    );
+    let _ = writeln!(out, r#"<rect width="100%" height="100%" fill="white"/>"#);
+
+    for edge in &layout.edges {
+        let from = &layout.nodes[edge.from];
+        let to = &layout.nodes[edge.to];
+        let _ = writeln!(
+            out,
+            r##"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="#616161" stroke-width="1.5"{}/>"##,
+            from.x + from.width,
+            from.y + from.height / 2.0,
+            to.x,
+            to.y + to.height / 2.0,
+            if edge.dashed { r#" stroke-dasharray="6 4""# } else { "" },
+        );
+    }
+
+    for node in &layout.nodes {
+        let _ = writeln!(
+            out,
+            r##"<rect x="{}" y="{}" width="{}" height="{}" rx="6" fill="{}" stroke="#616161"/>"##,
+            node.x, node.y, node.width, node.height, topology::hex(node.fill),
+        );
+        for (index, line) in node.lines.iter().enumerate() {
+            let _ = writeln!(
+                out,
+                r#"<text x="{}" y="{}" font-size="{}"{}>{}</text>"#,
+                node.x + 8.0,
+                node.y + 16.0 + index as f32 * 15.0,
+                if index == 0 { 13 } else { 11 },
+                if index == 0 { r#" font-weight="bold""# } else { "" },
+                escape_xml(line),
+            );
+        }
+    }
+
+    let _ = writeln!(out, "</svg>");
+    out
+}
+
+/// 将截取的界面图像保存为PNG
+pub fn save_png(path: &str, image: &egui::ColorImage) -> Result<()> {
+    let file = File::create(path).context(format!("无法创建图像文件: {}", path))?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), image.size[0] as u32, image.size[1] as u32);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let data: Vec<u8> = image.pixels.iter().flat_map(|p| p.to_srgba_unmultiplied()).collect();
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(&data))
+        .context(format!("无法写入图像文件: {}", path))?;
+    Ok(())
+}
@@ -0,0 +1,683 @@
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::fs::{File, OpenOptions};
+use std::io::{Seek, SeekFrom, Write};
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::Sender;
+use std::time::{Duration, Instant};
+
+use crate::api::{ApiClient, ApiClientConfig};
+use crate::chunking;
+use crate::models::{BackendContainer, BackendStartOrder, BusinessGroup, GroupId, HealthStatus, JobCheckpoint, JobKind, MiddlewareId, RestartMode, StartItem};
+use crate::scheduler;
+use crate::services::{BackendService, BusinessGroupService};
+use crate::state::StateStore;
+use crate::tasks;
+
+/// 压测请求使用的样例数据
+const BENCHMARK_PAYLOAD: &str = "benchmark-payload";
+/// 批量解密的最大并发数
+pub const MAX_BULK_WORKERS: u32 = 32;
+/// 批量解密每批包含的记录数（按每个工作线程计），每批完成后保存检查点
+const BULK_BATCH_PER_WORKER: usize = 16;
+/// 上报检查点的最小间隔
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(2);
+
+/// 任务进度检查点的上报器，同时提供上次保存的检查点供任务从中断处继续
+pub struct Checkpointer {
+    job_id: String,
+    resume: Option<JobCheckpoint>,
+    sender: Sender<(String, JobCheckpoint)>,
+    last_sent: Cell<Option<Instant>>,
+}
+
+impl Checkpointer {
+    /// 创建上报器
+    pub fn new(job_id: String, resume: Option<JobCheckpoint>, sender: Sender<(String, JobCheckpoint)>) -> Self {
+        Self {
+            job_id,
+            resume,
+            sender,
+            last_sent: Cell::new(None),
+        }
+    }
+
+    /// 上次保存的检查点
+    pub fn resume(&self) -> Option<&JobCheckpoint> {
+        self.resume.as_ref()
+    }
+
+    /// 上报检查点，距上次上报不足间隔时跳过
+    pub fn save(&self, completed: u64, total: u64, data: impl Serialize) {
+        if self.last_sent.get().is_some_and(|t| t.elapsed() < CHECKPOINT_INTERVAL) {
+            return;
+        }
+        self.save_now(completed, total, data);
+    }
+
+    /// 立即上报检查点，用于阶段切换等不应被跳过的进度
+    pub fn save_now(&self, completed: u64, total: u64, data: impl Serialize) {
+        self.last_sent.set(Some(Instant::now()));
+        let checkpoint = JobCheckpoint {
+            completed,
+            total,
+            data: serde_json::to_value(data).unwrap_or_default(),
+            updated_at: Utc::now(),
+        };
+        let _ = self.sender.send((self.job_id.clone(), checkpoint));
+    }
+
+    /// 上次保存的中间结果，没有检查点或格式不符时为默认值
+    fn resume_data<T: for<'de> Deserialize<'de> + Default>(&self) -> (u64, T) {
+        match &self.resume {
+            Some(checkpoint) => (
+                checkpoint.completed,
+                serde_json::from_value(checkpoint.data.clone()).unwrap_or_default(),
+            ),
+            None => (0, T::default()),
+        }
+    }
+}
+
+/// 执行一次任务，返回结果描述；`cancel` 置位后尽快退出，长任务通过 `checkpoint` 保存与恢复进度
+#[tracing::instrument(name = "job.run", skip_all, fields(job = %kind.describe(), trace_id = trace_id), err(level = "debug"))]
+pub fn run_job(kind: &JobKind, cancel: &AtomicBool, state: &StateStore, checkpoint: &Checkpointer, trace_id: Option<&str>) -> Result<String> {
+    match kind {
+        JobKind::StartGroup { group_id, .. } => start_group(group_id, cancel, state, checkpoint, trace_id),
+        JobKind::StopGroup { group_id, .. } => {
+            BusinessGroupService::new(state.clone()).stop_business_group(&GroupId::from(group_id))?;
+            Ok("业务组已停止".to_string())
+        }
+        JobKind::PullImage { image } => pull_image(image, cancel),
+        JobKind::Benchmark { middleware_id, url, timeout, requests, .. } => {
+            let config = ApiClientConfig {
+                base_url: url.clone(),
+                timeout: *timeout,
+                network: state.read(|s| s.network_for(middleware_id)),
+                trace_id: trace_id.map(str::to_string),
+            };
+            benchmark(config, *requests, cancel, checkpoint)
+        }
+        JobKind::RestartGroup { group_id, mode: RestartMode::AllAtOnce, .. } => {
+            BusinessGroupService::new(state.clone()).stop_business_group(&GroupId::from(group_id))?;
+            start_group(group_id, cancel, state, checkpoint, trace_id)
+        }
+        JobKind::RestartGroup { group_id, mode: RestartMode::Rolling, .. } => rolling_restart(group_id, cancel, state, checkpoint),
+        JobKind::PushConfig { middleware_id, .. } => push_config(middleware_id, state, trace_id),
+        JobKind::BulkDecrypt { middleware_id, input, output, workers, .. } => {
+            bulk_decrypt(middleware_id, input, output, *workers, cancel, state, checkpoint, trace_id)
+        }
+        JobKind::ScaleOutReads { group_id, middleware_id, backend_ids, .. } => {
+            scale_out_reads(group_id, middleware_id, backend_ids, cancel, state, checkpoint)
+        }
+        JobKind::ScaleInReads { group_id, middleware_id, backend_ids, .. } => {
+            let total = backend_ids.len() as u64;
+            let removed = BackendService::new(state.clone()).scale_in_reads(group_id, middleware_id, backend_ids, cancel, |removed| {
+                checkpoint.save_now(removed as u64, total, ());
+            })?;
+            Ok(format!("{} 个读实例已移出实例列表并删除", removed))
+        }
+        JobKind::UpgradeGroup { group_id, targets, .. } => upgrade_group(group_id, targets, cancel, state, checkpoint),
+        JobKind::SnapshotGroup { group_id, path, include_volumes, .. } => {
+            let manifest = BusinessGroupService::new(state.clone()).snapshot_group(group_id, path, *include_volumes, cancel, |done, total| {
+                checkpoint.save_now(done as u64, total as u64, ());
+            })?;
+            Ok(format!("快照已保存到 {}（{} 个卷）", path, manifest.volumes.len()))
+        }
+        JobKind::RestoreGroup { path, host_id, name, restore_volumes, .. } => {
+            let group = BusinessGroupService::new(state.clone()).restore_group(path, host_id.as_deref(), name, *restore_volumes, cancel, |done, total| {
+                checkpoint.save_now(done as u64, total as u64, ());
+            })?;
+            Ok(format!("已恢复业务组 {}（{} 个中间层）", group.name, group.middlewares.len()))
+        }
+    }
+}
+
+/// 任务被取消时的错误
+pub fn cancelled() -> anyhow::Error {
+    anyhow::anyhow!("任务已取消")
+}
+
+/// 启动业务组的进度，随检查点上报，界面据此显示后端的启动顺序
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct StartProgress {
+    /// 启动顺序的名称
+    pub order: String,
+    /// 按顺序排列的批次（批次名称, 后端名称）
+    pub stages: Vec<(String, Vec<String>)>,
+    /// 正在启动的批次
+    pub current: usize,
+}
+
+/// 启动业务组：按依赖与启动顺序分批启动后端与中间层，再确认所有中间层健康
+fn start_group(group_id: &str, cancel: &AtomicBool, state: &StateStore, checkpoint: &Checkpointer, trace_id: Option<&str>) -> Result<String> {
+    let service = BusinessGroupService::new(state.clone());
+    let group = service
+        .get_business_group(&GroupId::from(group_id))?
+        .ok_or_else(|| anyhow::anyhow!("业务组不存在: {}", group_id))?;
+    let started = start_members(&group, cancel, state, checkpoint)?;
+    // 容器均已启动，此处只更新业务组状态
+    service.start_business_group(&GroupId::from(group_id))?;
+
+    let mut unhealthy = Vec::new();
+    for middleware in &group.middlewares {
+        if cancel.load(Ordering::Relaxed) {
+            return Err(cancelled());
+        }
+        let health = ApiClient::new(ApiClientConfig {
+            base_url: middleware.url.clone(),
+            timeout: middleware.config.crud_api.timeout,
+            network: state.read(|s| s.network_for(&group.id)),
+            trace_id: trace_id.map(str::to_string),
+        })
+        .and_then(|client| tasks::block_on(client.health_check()));
+        if !matches!(health, Ok(HealthStatus::Healthy)) {
+            unhealthy.push(middleware.name.clone());
+        }
+    }
+
+    if !unhealthy.is_empty() {
+        anyhow::bail!("以下中间层未就绪: {}", unhealthy.join(", "));
+    }
+    Ok(format!(
+        "业务组已启动，{} 个容器按依赖与{}启动，{} 个中间层健康",
+        started,
+        group.backend_start_order.label(),
+        group.middlewares.len(),
+    ))
+}
+
+/// 按业务组的启动顺序分批启动容器，已运行的容器跳过；启动顺序中相邻且属于同一批次的容器为一批，
+/// 依赖跨越批次时同一批次可能出现多次。写实例优先时前一批后端通过健康探测后才启动下一批
+fn start_members(group: &BusinessGroup, cancel: &AtomicBool, state: &StateStore, checkpoint: &Checkpointer) -> Result<usize> {
+    let order = group.backend_start_order;
+    let mut stages: Vec<(&str, Vec<StartItem>)> = Vec::new();
+    for item in group.start_order()? {
+        let label = match item {
+            StartItem::Middleware(_) => "中间层",
+            StartItem::Backend(backend, _) => order.stage_of(backend).1,
+        };
+        match stages.last_mut() {
+            Some((last, items)) if *last == label => items.push(item),
+            _ => stages.push((label, vec![item])),
+        }
+    }
+    let mut progress = StartProgress {
+        order: order.label().to_string(),
+        stages: stages
+            .iter()
+            .map(|(label, items)| (label.to_string(), items.iter().map(|i| i.name().to_string()).collect()))
+            .collect(),
+        current: 0,
+    };
+    let total = stages.iter().map(|(_, items)| items.len()).sum::<usize>() as u64;
+    let service = BusinessGroupService::new(state.clone());
+    let mut started = 0;
+    for (index, (label, items)) in stages.iter().enumerate() {
+        progress.current = index;
+        checkpoint.save_now(started as u64, total, &progress);
+        for item in items {
+            if cancel.load(Ordering::Relaxed) {
+                return Err(cancelled());
+            }
+            service.start_member(&GroupId::from(&group.id), *item).with_context(|| format!("{}未全部启动", label))?;
+            started += 1;
+        }
+        let backends: Vec<&BackendContainer> = items
+            .iter()
+            .filter_map(|item| match item {
+                StartItem::Backend(backend, _) => Some(*backend),
+                StartItem::Middleware(_) => None,
+            })
+            .collect();
+        if order == BackendStartOrder::WritesFirst && index + 1 < stages.len() && !backends.is_empty() {
+            BackendService::new(state.clone())
+                .wait_ready(group, &backends, cancel)
+                .with_context(|| format!("{}未就绪，未启动后续批次", label))?;
+        }
+    }
+    checkpoint.save_now(total, total, &progress);
+    Ok(started)
+}
+
+/// 滚动重启的进度，随检查点上报，界面据此显示正在重启的后端
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RollingProgress {
+    /// 按重启顺序排列的后端名称
+    pub backends: Vec<String>,
+    /// 正在重启的后端
+    pub current: usize,
+}
+
+/// 滚动重启业务组，从检查点记录的已完成数继续
+fn rolling_restart(group_id: &str, cancel: &AtomicBool, state: &StateStore, checkpoint: &Checkpointer) -> Result<String> {
+    let (done, _) = checkpoint.resume_data::<RollingProgress>();
+    let restarted = BusinessGroupService::new(state.clone()).rolling_restart_business_group(group_id, done as usize, cancel, |current, backends| {
+        let progress = RollingProgress {
+            backends: backends.iter().map(|b| b.name.clone()).collect(),
+            current,
+        };
+        checkpoint.save_now(current as u64, backends.len() as u64, &progress);
+    })?;
+    Ok(format!("业务组已滚动重启，{} 个后端逐个通过健康探测", restarted))
+}
+
+/// 按启动顺序升级业务组内容器的镜像，已升级的容器在恢复时跳过
+fn upgrade_group(group_id: &str, targets: &[(String, String)], cancel: &AtomicBool, state: &StateStore, checkpoint: &Checkpointer) -> Result<String> {
+    let (done, _) = checkpoint.resume_data::<()>();
+    let upgraded = BusinessGroupService::new(state.clone()).upgrade_business_group(group_id, targets, done as usize, cancel, |upgraded, total| {
+        checkpoint.save_now(upgraded as u64, total as u64, ());
+    })?;
+    Ok(format!("{} 个容器已升级镜像", upgraded))
+}
+
+/// 扩容读实例，已启动的实例在恢复时跳过
+fn scale_out_reads(
+    group_id: &str,
+    middleware_id: &str,
+    backend_ids: &[String],
+    cancel: &AtomicBool,
+    state: &StateStore,
+    checkpoint: &Checkpointer,
+) -> Result<String> {
+    let total = backend_ids.len() as u64;
+    let started = BackendService::new(state.clone()).scale_out_reads(group_id, middleware_id, backend_ids, cancel, |started| {
+        checkpoint.save_now(started as u64, total, ());
+    })?;
+    Ok(format!("{} 个读实例已通过健康探测并加入实例列表", started))
+}
+
+/// 将中间层已保存的配置推送到服务
+fn push_config(middleware_id: &str, state: &StateStore, trace_id: Option<&str>) -> Result<String> {
+    state.authorize_entity(middleware_id)?;
+    let middleware = state
+        .read(|s| s.find_middleware(&MiddlewareId::from(middleware_id)).map(|(_, m)| m.clone()))
+        .ok_or_else(|| anyhow::anyhow!("中间层容器不存在: {}", middleware_id))?;
+    let client = ApiClient::new(ApiClientConfig {
+        base_url: middleware.api_base_url(),
+        timeout: middleware.config.crud_api.timeout,
+        network: state.read(|s| s.network_for(middleware_id)),
+        trace_id: trace_id.map(str::to_string),
+    })?;
+    tasks::block_on(client.update_config(&middleware.config)).context("推送配置失败")?;
+    Ok(format!("配置已推送到 {}", middleware.name))
+}
+
+/// 通过docker命令拉取镜像
+fn pull_image(image: &str, cancel: &AtomicBool) -> Result<String> {
+    let mut child = Command::new("docker")
+        .args(["pull", image])
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("无法执行docker命令")?;
+
+    loop {
+        if cancel.load(Ordering::Relaxed) {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(cancelled());
+        }
+        if child.try_wait()?.is_some() {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(200));
+    }
+
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        anyhow::bail!("拉取镜像失败: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(format!("镜像 {} 拉取完成", image))
+}
+
+/// 压测的中间结果
+#[derive(Default, Serialize, Deserialize)]
+struct BenchmarkProgress {
+    failures: u32,
+    elapsed_secs: f64,
+}
+
+/// 串行请求加密接口，统计平均耗时与失败数
+fn benchmark(config: ApiClientConfig, requests: u32, cancel: &AtomicBool, checkpoint: &Checkpointer) -> Result<String> {
+    let client = ApiClient::new(config)?;
+    let (done, mut progress) = checkpoint.resume_data::<BenchmarkProgress>();
+    let done = (done as u32).min(requests);
+    let started = Instant::now();
+
+    for completed in done + 1..=requests {
+        if cancel.load(Ordering::Relaxed) {
+            return Err(cancelled());
+        }
+        if tasks::block_on(client.encrypt(BENCHMARK_PAYLOAD)).is_err() {
+            progress.failures += 1;
+        }
+        let saved = BenchmarkProgress {
+            failures: progress.failures,
+            elapsed_secs: progress.elapsed_secs + started.elapsed().as_secs_f64(),
+        };
+        checkpoint.save(completed as u64, requests as u64, saved);
+    }
+
+    if progress.failures == requests {
+        anyhow::bail!("压测请求全部失败");
+    }
+    let elapsed = progress.elapsed_secs + started.elapsed().as_secs_f64();
+    Ok(format!(
+        "{} 次请求，失败 {} 次，平均耗时 {:.1} ms，吞吐 {:.1} 次/秒",
+        requests,
+        progress.failures,
+        elapsed * 1000.0 / requests.max(1) as f64,
+        requests as f64 / elapsed.max(f64::EPSILON),
+    ))
+}
+
+/// 批量解密成功的记录
+#[derive(Serialize)]
+struct DecryptedRecord<'a> {
+    line: usize,
+    plaintext: &'a str,
+}
+
+/// 批量解密失败的记录
+#[derive(Serialize)]
+struct FailedRecord<'a> {
+    line: usize,
+    backend: &'a str,
+    error: &'a str,
+}
+
+/// 单条记录的解密结果，失败时附带处理实例的序号
+type RecordResult = Result<String, (usize, String)>;
+
+/// 批量解密的中间结果，恢复时将文件截断到已确认的长度
+#[derive(Default, Serialize, Deserialize)]
+struct BulkDecryptProgress {
+    failed: usize,
+    output_bytes: u64,
+    report_bytes: u64,
+}
+
+/// 批量解密的错误报告路径，与结果文件并列
+pub fn error_report_path(output: &str) -> String {
+    format!("{}.errors.jsonl", output)
+}
+
+/// 打开输出文件，从检查点恢复时截断到已确认的长度并追加，否则清空
+fn open_output(path: &str, resume_bytes: Option<u64>) -> Result<File> {
+    let Some(bytes) = resume_bytes else {
+        return File::create(path).with_context(|| format!("无法创建文件: {}", path));
+    };
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(false)
+        .open(path)
+        .with_context(|| format!("无法打开文件: {}", path))?;
+    file.set_len(bytes).with_context(|| format!("无法截断文件: {}", path))?;
+    file.seek(SeekFrom::End(0))?;
+    Ok(file)
+}
+
+/// 从文件读取密文，在中间层调度策略下的读实例间轮询分发，由有界工作线程池并发解密
+///
+/// 按批执行，每批结果按输入顺序逐行写入JSON，失败记录连同行号与处理实例写入错误报告；
+/// 每批写入后保存检查点，恢复时跳过已完成的批次
+#[allow(clippy::too_many_arguments)]
+fn bulk_decrypt(
+    middleware_id: &str,
+    input: &str,
+    output: &str,
+    workers: u32,
+    cancel: &AtomicBool,
+    state: &StateStore,
+    checkpoint: &Checkpointer,
+    trace_id: Option<&str>,
+) -> Result<String> {
+    let middleware = state
+        .read(|s| s.find_middleware(&MiddlewareId::from(middleware_id)).map(|(_, m)| m.clone()))
+        .ok_or_else(|| anyhow::anyhow!("中间层容器不存在: {}", middleware_id))?;
+    let targets = scheduler::read_targets(&middleware.config.crud_api.strategy, &middleware.backend_containers);
+    if targets.is_empty() {
+        anyhow::bail!("{} 没有可处理读请求的运行中后端", middleware.name);
+    }
+    let network = state.read(|s| s.network_for(middleware_id));
+    let clients = targets
+        .iter()
+        .map(|b| {
+            let client = ApiClient::new(ApiClientConfig {
+                base_url: b.url.clone(),
+                timeout: b.timeout,
+                network: network.clone(),
+                trace_id: trace_id.map(str::to_string),
+            })?;
+            Ok((b.name.as_str(), client))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let text = std::fs::read_to_string(input).with_context(|| format!("无法读取密文文件: {}", input))?;
+    let records: Vec<(usize, &str)> = text
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .collect();
+    if records.is_empty() {
+        anyhow::bail!("密文文件中没有记录: {}", input);
+    }
+
+    let (done, mut progress) = checkpoint.resume_data::<BulkDecryptProgress>();
+    let done = (done as usize).min(records.len());
+    let resuming = checkpoint.resume().is_some();
+    let report = error_report_path(output);
+    let mut decrypted = open_output(output, resuming.then_some(progress.output_bytes))?;
+    let mut failures = open_output(&report, resuming.then_some(progress.report_bytes))?;
+
+    let workers = workers.clamp(1, MAX_BULK_WORKERS) as usize;
+    let batch_size = workers * BULK_BATCH_PER_WORKER;
+    let mut start = done;
+    while start < records.len() {
+        let batch = &records[start..(start + batch_size).min(records.len())];
+        let next = AtomicUsize::new(0);
+        let results: Mutex<Vec<Option<RecordResult>>> = Mutex::new(vec![None; batch.len()]);
+        std::thread::scope(|scope| {
+            for _ in 0..workers.min(batch.len()) {
+                scope.spawn(|| {
+                    loop {
+                        if cancel.load(Ordering::Relaxed) {
+                            return;
+                        }
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some((_, ciphertext)) = batch.get(index) else {
+                            return;
+                        };
+                        let target = (start + index) % clients.len();
+                        let result = tasks::block_on(chunking::decrypt(&clients[target].1, ciphertext, |_, _| {}))
+                            .map_err(|e| (target, format!("{:#}", e)));
+                        results.lock().unwrap()[index] = Some(result);
+                    }
+                });
+            }
+        });
+        // 取消时丢弃未完成的批次，检查点仍停留在上一批
+        if cancel.load(Ordering::Relaxed) {
+            return Err(cancelled());
+        }
+
+        let mut decrypted_lines = String::new();
+        let mut failed_lines = String::new();
+        for ((line, _), result) in batch.iter().zip(results.into_inner().unwrap()) {
+            match result {
+                Some(Ok(plaintext)) => {
+                    decrypted_lines.push_str(&serde_json::to_string(&DecryptedRecord { line: *line, plaintext: &plaintext })?);
+                    decrypted_lines.push('\n');
+                }
+                Some(Err((target, error))) => {
+                    let record = FailedRecord { line: *line, backend: clients[target].0, error: &error };
+                    failed_lines.push_str(&serde_json::to_string(&record)?);
+                    failed_lines.push('\n');
+                    progress.failed += 1;
+                }
+                None => {}
+            }
+        }
+        decrypted.write_all(decrypted_lines.as_bytes()).with_context(|| format!("无法写入结果文件: {}", output))?;
+        failures.write_all(failed_lines.as_bytes()).with_context(|| format!("无法写入错误报告: {}", report))?;
+        progress.output_bytes += decrypted_lines.len() as u64;
+        progress.report_bytes += failed_lines.len() as u64;
+
+        start += batch.len();
+        checkpoint.save(start as u64, records.len() as u64, &progress);
+    }
+    drop(failures);
+
+    if progress.failed == 0 {
+        // 没有失败记录时不保留空报告
+        let _ = std::fs::remove_file(&report);
+        return Ok(format!("{} 条记录全部解密成功，经 {} 个读实例，结果已写入 {}", records.len(), clients.len(), output));
+    }
+    if progress.failed == records.len() {
+        anyhow::bail!("{} 条记录全部解密失败，错误报告已写入 {}", records.len(), report);
+    }
+    Ok(format!(
+        "{} 条记录，成功 {} 条，失败 {} 条，结果已写入 {}，错误报告已写入 {}",
+        records.len(),
+        records.len() - progress.failed,
+        progress.failed,
+        output,
+        report,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ConfigManager;
+    use crate::events::EventBus;
+    use crate::models::{ContainerStatus, JobStatus, MiddlewareContainer, RetryPolicy};
+    use crate::services::JobService;
+    use std::path::PathBuf;
+    use std::sync::mpsc;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("encryption-service-ui-{}-{}", name, uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn store(dir: &std::path::Path) -> (ConfigManager, StateStore) {
+        let config_manager = ConfigManager::new(dir.join("config.json").to_string_lossy().to_string());
+        let (state, loaded) = StateStore::load(config_manager.clone(), EventBus::new());
+        loaded.unwrap();
+        (config_manager, state)
+    }
+
+    /// 添加一个只有一个运行中后端的中间层，后端地址不可连接，每条记录都解密失败并写入错误报告
+    fn add_middleware(state: &StateStore) -> String {
+        let backend = BackendContainer {
+            url: "http://127.0.0.1:1".to_string(),
+            status: ContainerStatus::Running,
+            ..BackendContainer::default()
+        };
+        let middleware = MiddlewareContainer { backend_containers: vec![backend], ..MiddlewareContainer::default() };
+        let middleware_id = middleware.id.clone();
+        let group = BusinessGroup { id: "g".to_string(), name: "g".to_string(), middlewares: vec![middleware], ..BusinessGroup::default() };
+        state.update(|s| {
+            s.business_groups.push(group);
+            Ok(())
+        }).unwrap();
+        middleware_id
+    }
+
+    fn bulk_decrypt_job(dir: &std::path::Path, middleware_id: &str) -> (JobKind, String) {
+        let input = dir.join("input.txt");
+        std::fs::write(&input, "c1\nc2\nc3\n").unwrap();
+        let output = dir.join("output.jsonl").to_string_lossy().to_string();
+        let kind = JobKind::BulkDecrypt {
+            middleware_id: middleware_id.to_string(),
+            middleware_name: "m".to_string(),
+            input: input.to_string_lossy().to_string(),
+            output: output.clone(),
+            workers: 1,
+        };
+        (kind, output)
+    }
+
+    fn failed_lines(output: &str) -> Vec<u64> {
+        std::fs::read_to_string(error_report_path(output))
+            .unwrap_or_default()
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap()["line"].as_u64().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn failed_job_is_retried_up_to_the_limit_then_failed() {
+        let dir = temp_dir("jobs-retry");
+        let (config_manager, state) = store(&dir);
+        let mut service = JobService::new(config_manager, state);
+        service.set_retry_policy(RetryPolicy { max_attempts: 3, backoff_secs: 0 }).unwrap();
+        let kind = JobKind::StartGroup { group_id: "missing".to_string(), group_name: "missing".to_string() };
+        service.enqueue(kind, "trace", None).unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let finished = loop {
+            let finished = service.poll();
+            if !finished.is_empty() {
+                break finished;
+            }
+            assert!(Instant::now() < deadline, "任务未在限定时间内结束");
+            std::thread::sleep(Duration::from_millis(10));
+        };
+        assert_eq!(finished.len(), 1);
+        assert_eq!(finished[0].status, JobStatus::Failed);
+        assert_eq!(finished[0].attempts, 3);
+        assert!(finished[0].message.contains("业务组不存在"));
+        assert!(!service.has_active_jobs());
+        assert_eq!(service.get_history().unwrap().len(), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn cancelled_job_does_not_run_remaining_steps() {
+        let dir = temp_dir("jobs-cancel");
+        let (_, state) = store(&dir);
+        let (kind, output) = bulk_decrypt_job(&dir, &add_middleware(&state));
+        let (sender, receiver) = mpsc::channel();
+        let checkpoint = Checkpointer::new("job".to_string(), None, sender);
+
+        let result = run_job(&kind, &AtomicBool::new(true), &state, &checkpoint, None);
+        assert_eq!(result.unwrap_err().to_string(), "任务已取消");
+        // 没有记录被处理，检查点也未前进
+        assert!(failed_lines(&output).is_empty());
+        assert!(receiver.try_recv().is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn resumed_job_skips_completed_steps() {
+        let dir = temp_dir("jobs-resume");
+        let (_, state) = store(&dir);
+        let (kind, output) = bulk_decrypt_job(&dir, &add_middleware(&state));
+        let resume = JobCheckpoint {
+            completed: 2,
+            total: 3,
+            data: serde_json::to_value(BulkDecryptProgress::default()).unwrap(),
+            updated_at: Utc::now(),
+        };
+        let (sender, receiver) = mpsc::channel();
+        let checkpoint = Checkpointer::new("job".to_string(), Some(resume), sender);
+
+        let message = run_job(&kind, &AtomicBool::new(false), &state, &checkpoint, None).unwrap();
+        assert!(message.contains("失败 1 条"), "{}", message);
+        // 只处理了检查点之后的第3条记录
+        assert_eq!(failed_lines(&output), [3]);
+        let (_, saved) = receiver.try_recv().unwrap();
+        assert_eq!((saved.completed, saved.total), (3, 3));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
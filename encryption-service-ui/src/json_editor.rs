@@ -0,0 +1,1071 @@
+use eframe::egui::{self, Color32, FontId, Key, Modifiers, RichText, Stroke};
+use eframe::egui::text::{CCursor, CCursorRange, LayoutJob, TextFormat};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::Range;
+
+/// 补全列表最多显示的字段数
+const MAX_COMPLETIONS: usize = 8;
+/// 工具栏中列出的可折叠区域的最大层级
+const MAX_FOLD_MENU_DEPTH: usize = 3;
+
+/// 由示例值推断出的JSON结构，用于补全字段名与显示字段类型
+#[derive(Debug, Clone, Default)]
+pub enum SchemaNode {
+    /// 示例中没有值，如空数组的元素
+    #[default]
+    Unknown,
+    Object(BTreeMap<String, SchemaNode>),
+    Array(Box<SchemaNode>),
+    /// 标量及其类型名称
+    Scalar(&'static str),
+}
+
+impl SchemaNode {
+    /// 合并多个示例值的结构，同名字段的结构逐层合并
+    pub fn from_samples(samples: &[Value]) -> Self {
+        let mut node = SchemaNode::Unknown;
+        for sample in samples {
+            node.merge(sample);
+        }
+        node
+    }
+
+    fn merge(&mut self, value: &Value) {
+        match value {
+            Value::Object(map) => {
+                if !matches!(self, SchemaNode::Object(_)) {
+                    *self = SchemaNode::Object(BTreeMap::new());
+                }
+                if let SchemaNode::Object(fields) = self {
+                    for (key, value) in map {
+                        fields.entry(key.clone()).or_default().merge(value);
+                    }
+                }
+            }
+            Value::Array(items) => {
+                if !matches!(self, SchemaNode::Array(_)) {
+                    *self = SchemaNode::Array(Box::default());
+                }
+                if let SchemaNode::Array(element) = self {
+                    for item in items {
+                        element.merge(item);
+                    }
+                }
+            }
+            // 可为空的字段以其他示例中的类型为准
+            Value::Null => {
+                if matches!(self, SchemaNode::Unknown) {
+                    *self = SchemaNode::Scalar("null");
+                }
+            }
+            Value::Bool(_) => *self = SchemaNode::Scalar("布尔"),
+            Value::Number(_) => *self = SchemaNode::Scalar("数字"),
+            Value::String(_) => *self = SchemaNode::Scalar("字符串"),
+        }
+    }
+
+    /// 类型名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            SchemaNode::Unknown => "任意",
+            SchemaNode::Object(_) => "对象",
+            SchemaNode::Array(_) => "数组",
+            SchemaNode::Scalar(label) => label,
+        }
+    }
+
+    fn lookup(&self, path: &[PathSegment]) -> Option<&SchemaNode> {
+        path.iter().try_fold(self, |node, segment| match (node, segment) {
+            (SchemaNode::Object(fields), PathSegment::Key(key)) => fields.get(key),
+            (SchemaNode::Array(element), PathSegment::Index(_)) => Some(element.as_ref()),
+            _ => None,
+        })
+    }
+
+    /// 补全字段名时插入的值骨架，返回（插入文本, 插入后光标相对插入起点的位置）
+    fn snippet(&self, key: &str) -> (String, usize) {
+        let key = serde_json::to_string(key).unwrap_or_default();
+        let (value, inside) = match self {
+            SchemaNode::Object(_) => ("{}", 1),
+            SchemaNode::Array(_) => ("[]", 1),
+            SchemaNode::Scalar("字符串") => ("\"\"", 1),
+            _ => ("", 0),
+        };
+        let text = format!("{}: {}", key, value);
+        let cursor = text.len() - value.len() + inside;
+        (text, cursor)
+    }
+}
+
+/// JSON路径中的一段
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+fn path_string(path: &[PathSegment]) -> String {
+    let mut text = String::new();
+    for segment in path {
+        match segment {
+            PathSegment::Key(key) if text.is_empty() => text.push_str(key),
+            PathSegment::Key(key) => {
+                text.push('.');
+                text.push_str(key);
+            }
+            PathSegment::Index(index) => text.push_str(&format!("[{}]", index)),
+        }
+    }
+    text
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenKind {
+    Key,
+    String,
+    Number,
+    /// `true`、`false` 与 `null`
+    Literal,
+    Punct,
+    Whitespace,
+    Invalid,
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    range: Range<usize>,
+    /// 字符串缺少结尾的引号
+    unterminated: bool,
+}
+
+/// 按JSON词法切分文本，不检查语法；后跟冒号的字符串视为字段名
+fn tokenize(text: &str) -> Vec<Token> {
+    let bytes = text.as_bytes();
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let start = i;
+        let mut unterminated = false;
+        let kind = match bytes[i] {
+            b'"' => {
+                i += 1;
+                loop {
+                    match bytes.get(i) {
+                        None | Some(b'\n') => {
+                            unterminated = true;
+                            break;
+                        }
+                        Some(b'\\') => i += 2,
+                        Some(b'"') => {
+                            i += 1;
+                            break;
+                        }
+                        Some(_) => i += 1,
+                    }
+                }
+                i = i.min(bytes.len());
+                TokenKind::String
+            }
+            b'{' | b'}' | b'[' | b']' | b':' | b',' => {
+                i += 1;
+                TokenKind::Punct
+            }
+            b if b.is_ascii_whitespace() => {
+                while bytes.get(i).is_some_and(|b| b.is_ascii_whitespace()) {
+                    i += 1;
+                }
+                TokenKind::Whitespace
+            }
+            b'-' | b'0'..=b'9' => {
+                while bytes.get(i).is_some_and(|b| b.is_ascii_digit() || matches!(b, b'-' | b'+' | b'.' | b'e' | b'E')) {
+                    i += 1;
+                }
+                TokenKind::Number
+            }
+            b if b.is_ascii_alphabetic() => {
+                while bytes.get(i).is_some_and(|b| b.is_ascii_alphabetic()) {
+                    i += 1;
+                }
+                match &text[start..i] {
+                    "true" | "false" | "null" => TokenKind::Literal,
+                    _ => TokenKind::Invalid,
+                }
+            }
+            _ => {
+                i += text[i..].chars().next().map(char::len_utf8).unwrap_or(1);
+                TokenKind::Invalid
+            }
+        };
+        tokens.push(Token { kind, range: start..i, unterminated });
+    }
+    // 字符串之后（跳过空白）是冒号时为字段名
+    for index in 0..tokens.len() {
+        if tokens[index].kind == TokenKind::String
+            && tokens[index + 1..]
+                .iter()
+                .find(|t| t.kind != TokenKind::Whitespace)
+                .is_some_and(|t| &text[t.range.clone()] == ":")
+        {
+            tokens[index].kind = TokenKind::Key;
+        }
+    }
+    tokens
+}
+
+/// 可折叠的对象或数组
+#[derive(Debug, Clone)]
+pub struct FoldRegion {
+    /// 字段路径，如 `app_state.business_groups[0]`，折叠状态按路径保存
+    pub path: String,
+    pub depth: usize,
+    /// 开括号的位置
+    open: usize,
+    /// 闭括号的位置
+    close: usize,
+}
+
+/// 光标处可补全的字段名
+#[derive(Debug, Clone)]
+struct KeyContext {
+    path: Vec<PathSegment>,
+    /// 已输入的部分字段名
+    prefix: String,
+    /// 补全时替换的范围：正在输入的字段名字符串，未输入引号时为光标处
+    replace: Range<usize>,
+    /// 替换范围之后已有冒号，补全时只插入字段名
+    has_colon: bool,
+    /// 同一对象中已有的其他字段
+    existing: BTreeSet<String>,
+}
+
+struct Frame {
+    object: bool,
+    path: Vec<PathSegment>,
+    open: usize,
+    expect_key: bool,
+    pending_key: Option<String>,
+    index: usize,
+    /// 已出现的字段名
+    keys: BTreeSet<String>,
+    /// 用于判断光标所在的对象是否已结束
+    serial: usize,
+}
+
+/// 遍历文本的结构，返回可折叠的区域与 `cursor` 处可补全的字段名；
+/// `force` 为真时光标不在字符串中也补全，用于手动触发
+fn analyze(text: &str, tokens: &[Token], cursor: Option<usize>, force: bool) -> (Vec<FoldRegion>, Option<KeyContext>) {
+    let mut regions = Vec::new();
+    let mut stack: Vec<Frame> = Vec::new();
+    let mut context: Option<(KeyContext, usize, Option<usize>)> = None;
+    let mut serial = 0;
+    let mut cursor = cursor;
+
+    for (position, token) in tokens.iter().enumerate() {
+        // 光标位于本记号之前或字符串内部时确定补全位置
+        if let Some(at) = cursor
+            && (at < token.range.end || (token.unterminated && at == token.range.end))
+        {
+            cursor = None;
+            if let Some(frame) = stack.last().filter(|f| f.object && f.expect_key) {
+                let is_string = matches!(token.kind, TokenKind::String | TokenKind::Key) && token.range.start < at;
+                if is_string || force {
+                    let (replace, prefix) = if is_string {
+                        (token.range.clone(), text[token.range.start + 1..at].to_string())
+                    } else {
+                        (at..at, String::new())
+                    };
+                    let has_colon = tokens[position + usize::from(is_string)..]
+                        .iter()
+                        .find(|t| t.kind != TokenKind::Whitespace)
+                        .is_some_and(|t| &text[t.range.clone()] == ":");
+                    let current = is_string.then_some(token.range.start);
+                    context = Some((
+                        KeyContext { path: frame.path.clone(), prefix, replace, has_colon, existing: frame.keys.clone() },
+                        frame.serial,
+                        current,
+                    ));
+                }
+            }
+        }
+
+        let value = &text[token.range.clone()];
+        match (token.kind, value) {
+            (TokenKind::Punct, "{" | "[") => {
+                let mut path = stack.last().map(|f| f.path.clone()).unwrap_or_default();
+                if let Some(parent) = stack.last_mut() {
+                    if parent.object {
+                        if let Some(key) = parent.pending_key.take() {
+                            path.push(PathSegment::Key(key));
+                        }
+                    } else {
+                        path.push(PathSegment::Index(parent.index));
+                    }
+                }
+                serial += 1;
+                stack.push(Frame {
+                    object: value == "{",
+                    path,
+                    open: token.range.start,
+                    expect_key: true,
+                    pending_key: None,
+                    index: 0,
+                    keys: BTreeSet::new(),
+                    serial,
+                });
+            }
+            (TokenKind::Punct, "}" | "]") => {
+                if let Some(frame) = stack.pop() {
+                    if text[frame.open..token.range.start].contains('\n') {
+                        regions.push(FoldRegion {
+                            path: path_string(&frame.path),
+                            depth: frame.path.len(),
+                            open: frame.open,
+                            close: token.range.start,
+                        });
+                    }
+                    if let Some((_, context_serial, _)) = &mut context
+                        && *context_serial == frame.serial
+                    {
+                        *context_serial = 0;
+                    }
+                }
+            }
+            (TokenKind::Punct, ":") => {
+                if let Some(frame) = stack.last_mut() {
+                    frame.expect_key = false;
+                }
+            }
+            (TokenKind::Punct, ",") => {
+                if let Some(frame) = stack.last_mut() {
+                    if frame.object {
+                        frame.expect_key = true;
+                        frame.pending_key = None;
+                    } else {
+                        frame.index += 1;
+                    }
+                }
+            }
+            (TokenKind::String | TokenKind::Key, _) => {
+                if let Some(frame) = stack.last_mut()
+                    && frame.object
+                    && frame.expect_key
+                {
+                    let key = serde_json::from_str::<String>(value).unwrap_or_else(|_| value.trim_matches('"').to_string());
+                    if let Some((context, context_serial, current)) = &mut context
+                        && *context_serial == frame.serial
+                        && *current != Some(token.range.start)
+                    {
+                        context.existing.insert(key.clone());
+                    }
+                    frame.keys.insert(key.clone());
+                    frame.pending_key = Some(key);
+                    frame.expect_key = false;
+                }
+            }
+            _ => {}
+        }
+    }
+    // 光标在文本末尾
+    if let Some(at) = cursor
+        && force
+        && let Some(frame) = stack.last().filter(|f| f.object && f.expect_key)
+    {
+        context = Some((
+            KeyContext { path: frame.path.clone(), prefix: String::new(), replace: at..at, has_colon: false, existing: frame.keys.clone() },
+            frame.serial,
+            None,
+        ));
+    }
+    regions.sort_by_key(|r| r.open);
+    (regions, context.map(|(context, _, _)| context))
+}
+
+/// 折叠后显示的文本中的一段
+#[derive(Debug, Clone)]
+struct Span {
+    source: Range<usize>,
+    view: Range<usize>,
+    folded: bool,
+}
+
+/// 折叠后显示的文本：折叠区域的内容替换为占位文本，编辑在原文上进行
+#[derive(Debug, Clone, Default)]
+struct Projection {
+    text: String,
+    spans: Vec<Span>,
+}
+
+impl Projection {
+    /// `hidden` 为按起点排序、互不重叠的隐藏范围
+    fn new(source: &str, hidden: &[Range<usize>]) -> Self {
+        let mut text = String::with_capacity(source.len());
+        let mut spans = Vec::new();
+        let mut visible_start = 0;
+        for range in hidden {
+            if range.start > visible_start {
+                let view_start = text.len();
+                text.push_str(&source[visible_start..range.start]);
+                spans.push(Span { source: visible_start..range.start, view: view_start..text.len(), folded: false });
+            }
+            let lines = source[range.clone()].matches('\n').count().saturating_sub(1).max(1);
+            let view_start = text.len();
+            text.push_str(&format!("⋯ {} 行", lines));
+            spans.push(Span { source: range.clone(), view: view_start..text.len(), folded: true });
+            visible_start = range.end;
+        }
+        let view_start = text.len();
+        text.push_str(&source[visible_start..]);
+        spans.push(Span { source: visible_start..source.len(), view: view_start..text.len(), folded: false });
+        Self { text, spans }
+    }
+
+    /// 显示位置对应的原文位置，位于占位文本内部时为空
+    fn to_source(&self, pos: usize) -> Option<usize> {
+        self.spans.iter().find_map(|span| {
+            if span.view.start > pos || pos > span.view.end {
+                None
+            } else if !span.folded {
+                Some(span.source.start + pos - span.view.start)
+            } else if pos == span.view.start {
+                Some(span.source.start)
+            } else if pos == span.view.end {
+                Some(span.source.end)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// 显示位置对应的原文位置，位于占位文本内部时取折叠区域的起点
+    fn to_source_floor(&self, pos: usize) -> usize {
+        self.to_source(pos).unwrap_or_else(|| {
+            self.spans
+                .iter()
+                .find(|s| s.view.start <= pos && pos <= s.view.end)
+                .map(|s| s.source.start)
+                .unwrap_or(0)
+        })
+    }
+
+    /// 原文位置对应的显示位置，位于折叠区域内时为占位文本的起点
+    fn to_view(&self, pos: usize) -> usize {
+        self.spans
+            .iter()
+            .find(|span| span.source.start <= pos && pos <= span.source.end)
+            .map(|span| if span.folded { span.view.start } else { span.view.start + pos - span.source.start })
+            .unwrap_or(self.text.len())
+    }
+
+    /// 占位文本在显示文本中的范围
+    fn placeholders(&self) -> impl Iterator<Item = &Range<usize>> {
+        self.spans.iter().filter(|s| s.folded).map(|s| &s.view)
+    }
+}
+
+/// 解析失败的位置与原因
+#[derive(Debug, Clone)]
+pub struct JsonError {
+    /// 行号，从1起
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+    /// 在原文中的位置
+    offset: usize,
+}
+
+impl JsonError {
+    fn new(text: &str, error: &serde_json::Error) -> Self {
+        let line_start: usize = text.split_inclusive('\n').take(error.line().saturating_sub(1)).map(str::len).sum();
+        let offset = text[line_start..]
+            .char_indices()
+            .nth(error.column().saturating_sub(1))
+            .map(|(i, _)| line_start + i)
+            .unwrap_or(text.len());
+        // serde_json的错误信息末尾附带位置，已单独显示
+        let message = error.to_string();
+        let message = message.split(" at line ").next().unwrap_or(&message).to_string();
+        Self { line: error.line(), column: error.column(), message, offset }
+    }
+}
+
+/// 高亮结果的缓存，文本与错误位置不变时复用
+struct Highlight {
+    text: String,
+    error: Option<usize>,
+    dark: bool,
+    job: LayoutJob,
+}
+
+/// 原始JSON编辑器：语法高亮、折叠、按结构补全字段名并在输入时校验
+pub struct JsonEditor {
+    text: String,
+    /// 已折叠区域的路径
+    folded: BTreeSet<String>,
+    regions: Vec<FoldRegion>,
+    view: Projection,
+    /// 绑定到文本框的显示文本
+    view_text: String,
+    schema: SchemaNode,
+    validate: fn(&str) -> serde_json::Result<()>,
+    error: Option<JsonError>,
+    highlight: Option<Highlight>,
+    /// 当前的补全及选中项
+    completion: Option<(KeyContext, Vec<(String, &'static str)>)>,
+    selected: usize,
+    /// 按Esc关闭补全时的光标位置，光标移动后重新显示
+    dismissed_at: Option<usize>,
+    /// 上次分析补全时的光标位置
+    completion_cursor: Option<usize>,
+    modified: bool,
+}
+
+impl JsonEditor {
+    /// `validate` 按目标类型解析文本，输入时调用
+    pub fn new(text: String, schema: SchemaNode, validate: fn(&str) -> serde_json::Result<()>) -> Self {
+        let mut editor = Self {
+            text: String::new(),
+            folded: BTreeSet::new(),
+            regions: Vec::new(),
+            view: Projection::default(),
+            view_text: String::new(),
+            schema,
+            validate,
+            error: None,
+            highlight: None,
+            completion: None,
+            selected: 0,
+            dismissed_at: None,
+            completion_cursor: None,
+            modified: false,
+        };
+        editor.set_text(text);
+        editor.modified = false;
+        editor
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// 替换全部文本，保留仍存在的折叠区域
+    pub fn set_text(&mut self, text: String) {
+        self.text = text;
+        self.modified = true;
+        self.completion_cursor = None;
+        self.error = (self.validate)(&self.text).err().map(|e| JsonError::new(&self.text, &e));
+        self.regions = analyze(&self.text, &tokenize(&self.text), None, false).0;
+        self.refresh_view();
+    }
+
+    pub fn error(&self) -> Option<&JsonError> {
+        self.error.as_ref()
+    }
+
+    /// 打开后是否编辑过
+    pub fn is_modified(&self) -> bool {
+        self.modified
+    }
+
+    /// 折叠指定层级的所有区域，层级从1起；0为全部展开
+    pub fn fold_depth(&mut self, depth: usize) {
+        self.folded = self.regions.iter().filter(|r| r.depth == depth).map(|r| r.path.clone()).collect();
+        self.refresh_view();
+    }
+
+    fn refresh_view(&mut self) {
+        let mut hidden: Vec<Range<usize>> = Vec::new();
+        for region in self.regions.iter().filter(|r| self.folded.contains(&r.path)) {
+            // 已被外层折叠的区域不再单独处理
+            if hidden.last().is_some_and(|h| region.open < h.end) {
+                continue;
+            }
+            hidden.push(region.open + 1..region.close);
+        }
+        self.folded.retain(|path| self.regions.iter().any(|r| &r.path == path));
+        self.view = Projection::new(&self.text, &hidden);
+        self.view_text = self.view.text.clone();
+    }
+
+    /// 将文本框中的修改映射回原文，修改落在占位文本内部时展开该区域并撤销修改；返回修改后光标的原文位置
+    fn apply_view_edit(&mut self) -> Option<usize> {
+        let old = &self.view.text;
+        let new = &self.view_text;
+        let prefix = old
+            .char_indices()
+            .zip(new.chars())
+            .find(|((_, a), b)| a != b)
+            .map(|((i, _), _)| i)
+            .unwrap_or(old.len().min(new.len()));
+        let max_suffix = (old.len() - prefix).min(new.len() - prefix);
+        let suffix = old[prefix..]
+            .chars()
+            .rev()
+            .zip(new[prefix..].chars().rev())
+            .take_while(|(a, b)| a == b)
+            .map(|(c, _)| c.len_utf8())
+            .sum::<usize>()
+            .min(max_suffix);
+        let inserted = new[prefix..new.len() - suffix].to_string();
+        let (start, end) = (prefix, old.len() - suffix);
+        match (self.view.to_source(start), self.view.to_source(end)) {
+            (Some(source_start), Some(source_end)) => {
+                let mut text = std::mem::take(&mut self.text);
+                text.replace_range(source_start..source_end, &inserted);
+                self.set_text(text);
+                Some(source_start + inserted.len())
+            }
+            _ => {
+                let touched: Vec<String> = self.regions
+                    .iter()
+                    .filter(|r| self.folded.contains(&r.path) && r.open < self.view.to_source_floor(end) && self.view.to_source_floor(start) <= r.close)
+                    .map(|r| r.path.clone())
+                    .collect();
+                for path in touched {
+                    self.folded.remove(&path);
+                }
+                let cursor = self.view.to_source_floor(start);
+                self.refresh_view();
+                Some(cursor)
+            }
+        }
+    }
+
+    /// 渲染工具栏、编辑区、补全列表与校验结果
+    pub fn show(&mut self, ui: &mut egui::Ui, id_source: &str) {
+        let id = ui.make_persistent_id(id_source);
+        self.show_toolbar(ui);
+
+        // 补全列表显示时由编辑器处理方向键、Tab与Esc，不交给文本框
+        let mut accept = false;
+        let focused = ui.memory(|m| m.has_focus(id));
+        if focused && let Some((_, items)) = &self.completion {
+            let count = items.len();
+            ui.input_mut(|input| {
+                if input.consume_key(Modifiers::NONE, Key::ArrowDown) {
+                    self.selected = (self.selected + 1) % count;
+                }
+                if input.consume_key(Modifiers::NONE, Key::ArrowUp) {
+                    self.selected = (self.selected + count - 1) % count;
+                }
+                accept = input.consume_key(Modifiers::NONE, Key::Tab);
+            });
+            if ui.input_mut(|input| input.consume_key(Modifiers::NONE, Key::Escape)) {
+                self.dismissed_at = self.completion.as_ref().map(|(c, _)| c.replace.end);
+                self.completion = None;
+            }
+        }
+        let force = focused && ui.input_mut(|input| input.consume_key(Modifiers::CTRL, Key::Space));
+        if force {
+            self.dismissed_at = None;
+        }
+
+        let error = self.error.as_ref().map(|e| self.view.to_view(e.offset));
+        let placeholders: Vec<Range<usize>> = self.view.placeholders().cloned().collect();
+        let expected = self.view.text.clone();
+        let highlight = &mut self.highlight;
+        let mut layouter = |ui: &egui::Ui, text: &str, wrap_width: f32| {
+            let dark = ui.visuals().dark_mode;
+            let font = egui::TextStyle::Monospace.resolve(ui.style());
+            // 同一帧内文本框已修改时占位与错误位置尚未更新
+            let (error, placeholders) = if text == expected { (error, placeholders.as_slice()) } else { (None, &[][..]) };
+            if !highlight.as_ref().is_some_and(|h| h.text == text && h.error == error && h.dark == dark) {
+                *highlight = Some(Highlight {
+                    text: text.to_string(),
+                    error,
+                    dark,
+                    job: highlight_job(text, placeholders, error, dark, font),
+                });
+            }
+            let mut job = highlight.as_ref().map(|h| h.job.clone()).unwrap_or_default();
+            job.wrap.max_width = wrap_width;
+            ui.fonts(|f| f.layout_job(job))
+        };
+        let output = egui::ScrollArea::vertical()
+            .id_source(id.with("scroll"))
+            .max_height(480.0)
+            .show(ui, |ui| {
+                egui::TextEdit::multiline(&mut self.view_text)
+                    .id(id)
+                    .code_editor()
+                    .desired_width(f32::INFINITY)
+                    .desired_rows(24)
+                    .lock_focus(true)
+                    .layouter(&mut layouter)
+                    .show(ui)
+            })
+            .inner;
+
+        let mut cursor = output.cursor_range.filter(|c| c.is_empty()).and_then(|c| {
+            let byte = char_to_byte(&self.view_text, c.primary.ccursor.index);
+            self.view.to_source(byte)
+        });
+        if self.view_text != self.view.text {
+            cursor = self.apply_view_edit();
+            self.set_cursor(ui.ctx(), id, cursor);
+        } else if accept && let Some(accepted) = self.accept_completion() {
+            cursor = Some(accepted);
+            self.set_cursor(ui.ctx(), id, cursor);
+        }
+
+        self.update_completion(output.response.has_focus().then_some(cursor).flatten(), force);
+        if let (Some((_, items)), Some(range)) = (&self.completion, output.cursor_range) {
+            let anchor = output.galley_pos + output.galley.pos_from_cursor(&range.primary).left_bottom().to_vec2();
+            let mut clicked = None;
+            egui::Area::new(id.with("completion"))
+                .order(egui::Order::Foreground)
+                .fixed_pos(anchor)
+                .show(ui.ctx(), |ui| {
+                    egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        for (index, (key, kind)) in items.iter().enumerate() {
+                            let text = RichText::new(format!("{}  {}", key, kind)).monospace();
+                            if ui.selectable_label(index == self.selected, text).clicked() {
+                                clicked = Some(index);
+                            }
+                        }
+                        ui.label(RichText::new("Tab 插入，Esc 关闭").small().weak());
+                    });
+                });
+            if let Some(index) = clicked {
+                self.selected = index;
+                let cursor = self.accept_completion();
+                self.set_cursor(ui.ctx(), id, cursor);
+                output.response.request_focus();
+            }
+        }
+
+        match &self.error {
+            Some(error) => {
+                ui.colored_label(Color32::RED, format!("第 {} 行第 {} 列: {}", error.line, error.column, error.message));
+            }
+            None => {
+                ui.colored_label(Color32::GREEN, "格式正确");
+            }
+        }
+    }
+
+    /// 插入选中的补全项，返回插入后光标的原文位置
+    fn accept_completion(&mut self) -> Option<usize> {
+        let (context, items) = self.completion.take()?;
+        let (key, _) = items.get(self.selected)?;
+        let node = match self.schema.lookup(&context.path) {
+            Some(SchemaNode::Object(fields)) => fields.get(key),
+            _ => None,
+        };
+        let (snippet, offset) = match node {
+            Some(node) if !context.has_colon => node.snippet(key),
+            _ => {
+                let key = serde_json::to_string(key).unwrap_or_default();
+                let len = key.len();
+                (key, len)
+            }
+        };
+        let mut text = std::mem::take(&mut self.text);
+        text.replace_range(context.replace.clone(), &snippet);
+        self.set_text(text);
+        Some(context.replace.start + offset)
+    }
+
+    /// 将文本框的光标移到原文位置
+    fn set_cursor(&self, ctx: &egui::Context, id: egui::Id, cursor: Option<usize>) {
+        let Some(cursor) = cursor else {
+            return;
+        };
+        let view_cursor = byte_to_char(&self.view.text, self.view.to_view(cursor));
+        let mut state = egui::TextEdit::load_state(ctx, id).unwrap_or_default();
+        state.cursor.set_char_range(Some(CCursorRange::one(CCursor::new(view_cursor))));
+        state.store(ctx, id);
+    }
+
+    fn show_toolbar(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if ui.button("折叠第一层").clicked() {
+                self.fold_depth(1);
+            }
+            if ui.button("折叠第二层").clicked() {
+                self.fold_depth(2);
+            }
+            if ui.button("全部展开").clicked() {
+                self.fold_depth(0);
+            }
+            let mut toggled = None;
+            ui.menu_button("折叠…", |ui| {
+                egui::ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                    for region in self.regions.iter().filter(|r| (1..=MAX_FOLD_MENU_DEPTH).contains(&r.depth)) {
+                        let mut folded = self.folded.contains(&region.path);
+                        let name = region.path.rsplit(['.']).next().unwrap_or(&region.path);
+                        let label = format!("{}{}", "    ".repeat(region.depth - 1), name);
+                        if ui.checkbox(&mut folded, label).on_hover_text(&region.path).changed() {
+                            toggled = Some((region.path.clone(), folded));
+                        }
+                    }
+                });
+            });
+            if let Some((path, folded)) = toggled {
+                if folded {
+                    self.folded.insert(path);
+                } else {
+                    self.folded.remove(&path);
+                }
+                self.refresh_view();
+            }
+            ui.label(RichText::new("Ctrl+Space 补全字段名").small().weak());
+        });
+    }
+
+    /// 按光标位置更新补全列表，光标不在对象的字段名处时关闭
+    fn update_completion(&mut self, cursor: Option<usize>, force: bool) {
+        let Some(cursor) = cursor.filter(|c| force || self.dismissed_at != Some(*c)) else {
+            self.completion = None;
+            self.completion_cursor = None;
+            return;
+        };
+        self.dismissed_at = None;
+        // 光标与文本未变时沿用上次的结果，避免每帧重新分析整个文本
+        if !force && self.completion_cursor == Some(cursor) {
+            return;
+        }
+        self.completion_cursor = Some(cursor);
+        let (_, context) = analyze(&self.text, &tokenize(&self.text), Some(cursor), force || self.completion.is_some());
+        let items = context.as_ref().and_then(|context| match self.schema.lookup(&context.path) {
+            Some(SchemaNode::Object(fields)) => {
+                let prefix = context.prefix.to_lowercase();
+                let mut items: Vec<(bool, String, &'static str)> = fields
+                    .iter()
+                    .filter(|(key, _)| !context.existing.contains(*key) && key.to_lowercase().contains(&prefix))
+                    .map(|(key, node)| (!key.to_lowercase().starts_with(&prefix), key.clone(), node.label()))
+                    .collect();
+                items.sort();
+                let items: Vec<(String, &'static str)> = items.into_iter().take(MAX_COMPLETIONS).map(|(_, key, label)| (key, label)).collect();
+                // 已完整输入唯一匹配的字段名时不再提示
+                (!items.is_empty() && (force || items.len() > 1 || items[0].0 != context.prefix)).then_some(items)
+            }
+            _ => None,
+        });
+        match (context, items) {
+            (Some(context), Some(items)) => {
+                if self.selected >= items.len() {
+                    self.selected = 0;
+                }
+                self.completion = Some((context, items));
+            }
+            _ => self.completion = None,
+        }
+    }
+}
+
+/// 按记号类型着色，占位文本显示为灰色，解析错误所在的记号加红色下划线
+fn highlight_job(text: &str, placeholders: &[Range<usize>], error: Option<usize>, dark: bool, font: FontId) -> LayoutJob {
+    let color = |kind: TokenKind| match (kind, dark) {
+        (TokenKind::Key, true) => Color32::from_rgb(156, 220, 254),
+        (TokenKind::Key, false) => Color32::from_rgb(0, 80, 160),
+        (TokenKind::String, true) => Color32::from_rgb(206, 145, 120),
+        (TokenKind::String, false) => Color32::from_rgb(163, 21, 21),
+        (TokenKind::Number, true) => Color32::from_rgb(181, 206, 168),
+        (TokenKind::Number, false) => Color32::from_rgb(9, 134, 88),
+        (TokenKind::Literal, true) => Color32::from_rgb(86, 156, 214),
+        (TokenKind::Literal, false) => Color32::from_rgb(0, 0, 255),
+        (TokenKind::Invalid, _) => Color32::RED,
+        (_, true) => Color32::LIGHT_GRAY,
+        (_, false) => Color32::DARK_GRAY,
+    };
+    let mut job = LayoutJob::default();
+    let append = |job: &mut LayoutJob, range: Range<usize>, mut format: TextFormat| {
+        if error.is_some_and(|e| range.start <= e && (e < range.end || (e == range.end && e == text.len()))) {
+            format.underline = Stroke::new(1.5, Color32::RED);
+        }
+        job.append(&text[range], 0.0, format);
+    };
+    let mut visible_start = 0;
+    let pieces = placeholders.iter().map(Some).chain(std::iter::once(None));
+    for placeholder in pieces {
+        let visible_end = placeholder.map(|p| p.start).unwrap_or(text.len());
+        for token in tokenize(&text[visible_start..visible_end]) {
+            let range = visible_start + token.range.start..visible_start + token.range.end;
+            append(&mut job, range, TextFormat::simple(font.clone(), color(token.kind)));
+        }
+        if let Some(placeholder) = placeholder {
+            let format = TextFormat {
+                font_id: font.clone(),
+                color: Color32::GRAY,
+                background: if dark { Color32::from_gray(50) } else { Color32::from_gray(220) },
+                italics: true,
+                ..Default::default()
+            };
+            append(&mut job, placeholder.clone(), format);
+            visible_start = placeholder.end;
+        }
+    }
+    job
+}
+
+fn char_to_byte(text: &str, index: usize) -> usize {
+    text.char_indices().nth(index).map(|(i, _)| i).unwrap_or(text.len())
+}
+
+fn byte_to_char(text: &str, index: usize) -> usize {
+    text[..index.min(text.len())].chars().count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn validate(text: &str) -> serde_json::Result<()> {
+        serde_json::from_str::<Value>(text).map(|_| ())
+    }
+
+    fn schema() -> SchemaNode {
+        SchemaNode::from_samples(&[
+            json!({"name": "a", "port": 80, "tags": [], "server": {"host": "h", "https": false}}),
+            json!({"name": "b", "note": null, "tags": [{"key": "k"}], "server": {"port": 1}}),
+        ])
+    }
+
+    fn editor(text: &str) -> JsonEditor {
+        JsonEditor::new(text.to_string(), schema(), validate)
+    }
+
+    /// 在 `|` 处放置光标并更新补全，返回候选字段名
+    fn complete(editor: &mut JsonEditor, text: &str, force: bool) -> Vec<String> {
+        let cursor = text.find('|').unwrap();
+        editor.set_text(text.replace('|', ""));
+        editor.update_completion(Some(cursor), force);
+        editor.completion.iter().flat_map(|(_, items)| items.iter().map(|(key, _)| key.clone())).collect()
+    }
+
+    #[test]
+    fn schema_merges_samples() {
+        let schema = schema();
+        let root = |key: &str| schema.lookup(&[PathSegment::Key(key.to_string())]).map(SchemaNode::label);
+        assert_eq!(root("name"), Some("字符串"));
+        assert_eq!(root("note"), Some("null"));
+        assert_eq!(root("tags"), Some("数组"));
+        let server = [PathSegment::Key("server".to_string())];
+        let SchemaNode::Object(fields) = schema.lookup(&server).unwrap() else {
+            panic!("server 应为对象");
+        };
+        assert_eq!(fields.keys().collect::<Vec<_>>(), ["host", "https", "port"]);
+        let tag_key = [PathSegment::Key("tags".to_string()), PathSegment::Index(3), PathSegment::Key("key".to_string())];
+        assert_eq!(schema.lookup(&tag_key).map(SchemaNode::label), Some("字符串"));
+        assert!(schema.lookup(&[PathSegment::Index(0)]).is_none());
+        // 可为空的字段以其他示例的类型为准
+        let nullable = SchemaNode::from_samples(&[json!({"a": null}), json!({"a": 1})]);
+        assert_eq!(nullable.lookup(&[PathSegment::Key("a".to_string())]).map(SchemaNode::label), Some("数字"));
+    }
+
+    #[test]
+    fn tokens_distinguish_keys_from_values() {
+        let text = r#"{"a" : "b", "c": [1, true, nul"#;
+        let kinds: Vec<(TokenKind, &str)> = tokenize(text)
+            .into_iter()
+            .filter(|t| t.kind != TokenKind::Whitespace)
+            .map(|t| (t.kind, &text[t.range]))
+            .collect();
+        assert_eq!(kinds, [
+            (TokenKind::Punct, "{"),
+            (TokenKind::Key, "\"a\""),
+            (TokenKind::Punct, ":"),
+            (TokenKind::String, "\"b\""),
+            (TokenKind::Punct, ","),
+            (TokenKind::Key, "\"c\""),
+            (TokenKind::Punct, ":"),
+            (TokenKind::Punct, "["),
+            (TokenKind::Number, "1"),
+            (TokenKind::Punct, ","),
+            (TokenKind::Literal, "true"),
+            (TokenKind::Punct, ","),
+            (TokenKind::Invalid, "nul"),
+        ]);
+        let unterminated = tokenize("\"ab\\\"c");
+        assert!(unterminated[0].unterminated);
+    }
+
+    #[test]
+    fn completion_offers_missing_fields_by_prefix() {
+        let mut editor = editor("{}");
+        assert_eq!(complete(&mut editor, r#"{"name": "x", "|"}"#, false), ["note", "port", "server", "tags"]);
+        // 前缀匹配的字段排在包含匹配之前
+        assert_eq!(complete(&mut editor, r#"{"t|"}"#, false), ["tags", "note", "port"]);
+        assert_eq!(complete(&mut editor, r#"{"server": {"ho|"}}"#, false), ["host"]);
+        assert_eq!(complete(&mut editor, r#"{"tags": [{|}]}"#, true), ["key"]);
+        // 已完整输入唯一匹配的字段名时不提示
+        assert!(complete(&mut editor, r#"{"host|"}"#, false).is_empty());
+        // 光标在值上或未手动触发时不提示
+        assert!(complete(&mut editor, r#"{"name": "|"}"#, false).is_empty());
+        assert!(complete(&mut editor, r#"{|}"#, false).is_empty());
+        assert!(complete(&mut editor, r#"{"unknown": {"|"}}"#, false).is_empty());
+    }
+
+    #[test]
+    fn accepting_completion_inserts_value_skeleton() {
+        let mut editor = editor("{}");
+        assert_eq!(complete(&mut editor, r#"{"serv|"}"#, false), ["server"]);
+        let cursor = editor.accept_completion().unwrap();
+        assert_eq!(editor.text(), r#"{"server": {}}"#);
+        assert_eq!(&editor.text()[cursor..], "}}");
+
+        complete(&mut editor, r#"{"na|"}"#, false);
+        let cursor = editor.accept_completion().unwrap();
+        assert_eq!(editor.text(), r#"{"name": ""}"#);
+        assert_eq!(&editor.text()[cursor..], "\"}");
+
+        // 已有冒号时只替换字段名
+        complete(&mut editor, r#"{"po|": 1}"#, false);
+        let cursor = editor.accept_completion().unwrap();
+        assert_eq!(editor.text(), r#"{"port": 1}"#);
+        assert_eq!(&editor.text()[cursor..], ": 1}");
+    }
+
+    #[test]
+    fn validation_reports_error_position() {
+        let mut editor = editor(r#"{"name": "a"}"#);
+        assert!(editor.error().is_none());
+        assert!(!editor.is_modified());
+
+        editor.set_text("{\n  \"name\": \"a\",\n  \"port\": 8o\n}".to_string());
+        assert!(editor.is_modified());
+        let error = editor.error().unwrap();
+        assert_eq!((error.line, error.column), (3, 12));
+        assert!(!error.message.contains(" at line "));
+        assert_eq!(&editor.text()[error.offset..error.offset + 1], "o");
+
+        // 校验函数按目标类型解析
+        let typed = JsonEditor::new("[1]".to_string(), SchemaNode::Unknown, |text| {
+            serde_json::from_str::<BTreeMap<String, u16>>(text).map(|_| ())
+        });
+        assert_eq!(typed.error().map(|e| e.line), Some(1));
+    }
+
+    #[test]
+    fn folding_hides_regions_and_maps_positions() {
+        let text = "{\n  \"a\": {\n    \"b\": 1\n  },\n  \"c\": [\n    2\n  ]\n}";
+        let mut editor = editor(text);
+        let regions: Vec<(&str, usize)> = editor.regions.iter().map(|r| (r.path.as_str(), r.depth)).collect();
+        assert_eq!(regions, [("", 0), ("a", 1), ("c", 1)]);
+
+        editor.fold_depth(1);
+        assert_eq!(editor.view.text, "{\n  \"a\": {⋯ 1 行},\n  \"c\": [⋯ 1 行]\n}");
+        let c = editor.view.text.find("\"c\"").unwrap();
+        assert_eq!(editor.view.to_source(c), text.find("\"c\""));
+        let inside = editor.view.text.find('⋯').unwrap() + '⋯'.len_utf8();
+        assert_eq!(editor.view.to_source(inside), None);
+        assert_eq!(editor.view.to_view(text.find("\"b\"").unwrap()), editor.view.text.find('⋯').unwrap());
+
+        // 在折叠区域外的编辑写回原文并保留折叠
+        editor.view_text = editor.view.text.replacen("\"c\"", "\"d\"", 1);
+        editor.apply_view_edit();
+        assert!(editor.text().contains("\"d\": [\n    2\n  ]"));
+        assert!(editor.folded.contains("a"));
+        assert!(!editor.folded.contains("c"));
+
+        // 编辑落在占位文本内部时展开该区域而不修改原文
+        let before = editor.text().to_string();
+        editor.view_text = editor.view.text.replacen('⋯', "x", 1);
+        editor.apply_view_edit();
+        assert_eq!(editor.text(), before);
+        assert!(editor.folded.is_empty());
+    }
+}
@@ -0,0 +1,424 @@
+use anyhow::{Context, Result};
+use chrono::Utc;
+use std::collections::BTreeMap;
+
+use k8s_openapi::api::apps::v1::Deployment;
+use k8s_openapi::api::core::v1::{Pod, Service};
+use kube::api::{Api, ListParams, Patch, PatchParams};
+use serde_json::{Value, json};
+
+use crate::models::{BackendContainer, BusinessGroup, ContainerSpec, ContainerStatus, GroupStatus, KubernetesLink, MiddlewareContainer, RuntimeKind};
+use crate::tasks;
+
+/// 标记工作负载所属业务组的标签
+pub const GROUP_LABEL: &str = "encryption-service/group";
+/// 标记工作负载角色的标签，取值为 middleware 或 backend
+pub const ROLE_LABEL: &str = "encryption-service/role";
+/// 标记后端所属中间层Deployment的标签，可选
+pub const MIDDLEWARE_LABEL: &str = "encryption-service/middleware";
+
+/// Pod选择器使用的标签，取值为Deployment名称
+const APP_LABEL: &str = "app.kubernetes.io/name";
+/// 写入集群时使用的字段管理者名称
+const FIELD_MANAGER: &str = "encryption-service-ui";
+
+/// 未声明容器端口时使用的默认端口
+const DEFAULT_MIDDLEWARE_PORT: u16 = 9999;
+const DEFAULT_BACKEND_PORT: u16 = 8000;
+
+/// 工作负载角色
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkloadRole {
+    Middleware,
+    Backend,
+}
+
+impl WorkloadRole {
+    /// 显示名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            WorkloadRole::Middleware => "中间层",
+            WorkloadRole::Backend => "后端",
+        }
+    }
+
+    /// 角色标签的取值
+    pub fn label_value(&self) -> &'static str {
+        match self {
+            WorkloadRole::Middleware => "middleware",
+            WorkloadRole::Backend => "backend",
+        }
+    }
+}
+
+/// 集群中带有加密服务标签的Deployment
+#[derive(Debug, Clone)]
+pub struct Workload {
+    pub deployment: String,
+    pub group: String,
+    pub role: WorkloadRole,
+    /// 后端所属中间层的Deployment名称
+    pub middleware: Option<String>,
+    pub port: Option<u16>,
+    pub replicas: i32,
+    pub ready_replicas: i32,
+    /// 属于该Deployment的Pod名称
+    pub pods: Vec<String>,
+}
+
+impl Workload {
+    /// 按副本就绪情况换算的容器状态
+    pub fn status(&self) -> ContainerStatus {
+        if self.replicas == 0 {
+            ContainerStatus::Stopped
+        } else if self.ready_replicas >= self.replicas {
+            ContainerStatus::Running
+        } else {
+            ContainerStatus::Starting
+        }
+    }
+
+    /// 集群内访问地址
+    fn url(&self, namespace: &str) -> String {
+        let default_port = match self.role {
+            WorkloadRole::Middleware => DEFAULT_MIDDLEWARE_PORT,
+            WorkloadRole::Backend => DEFAULT_BACKEND_PORT,
+        };
+        format!("http://{}.{}.svc:{}", self.deployment, namespace, self.port.unwrap_or(default_port))
+    }
+
+    fn link(&self, namespace: &str) -> KubernetesLink {
+        KubernetesLink {
+            namespace: namespace.to_string(),
+            deployment: self.deployment.clone(),
+        }
+    }
+}
+
+/// 列出命名空间中带有角色标签的Deployment及其Pod
+pub fn discover(namespace: &str) -> Result<Vec<Workload>> {
+    tasks::block_on(list_workloads(namespace))
+}
+
+async fn list_workloads(namespace: &str) -> Result<Vec<Workload>> {
+    let client = kube::Client::try_default().await.context("无法连接Kubernetes集群")?;
+    let params = ListParams::default().labels(ROLE_LABEL);
+    let deployments = Api::<Deployment>::namespaced(client.clone(), namespace)
+        .list(&params)
+        .await
+        .context(format!("无法列出命名空间 {} 中的Deployment", namespace))?;
+    let pods = Api::<Pod>::namespaced(client, namespace)
+        .list(&params)
+        .await
+        .context(format!("无法列出命名空间 {} 中的Pod", namespace))?;
+
+    let mut workloads = Vec::new();
+    for deployment in deployments.items {
+        let labels = deployment.metadata.labels.clone().unwrap_or_default();
+        let role = match labels.get(ROLE_LABEL).map(String::as_str) {
+            Some("middleware") => WorkloadRole::Middleware,
+            Some("backend") => WorkloadRole::Backend,
+            _ => continue,
+        };
+        let Some(name) = deployment.metadata.name.clone() else {
+            continue;
+        };
+        let spec = deployment.spec.unwrap_or_default();
+
+        // Pod标签需包含Deployment选择器中的全部标签
+        let selector = spec.selector.match_labels.unwrap_or_default();
+        let pods = pods
+            .items
+            .iter()
+            .filter(|pod| {
+                let pod_labels = pod.metadata.labels.as_ref();
+                !selector.is_empty() && selector.iter().all(|(k, v)| pod_labels.and_then(|l| l.get(k)) == Some(v))
+            })
+            .filter_map(|pod| pod.metadata.name.clone())
+            .collect();
+
+        let port = spec
+            .template
+            .spec
+            .and_then(|s| s.containers.into_iter().next())
+            .and_then(|c| c.ports)
+            .and_then(|ports| ports.first().map(|p| p.container_port))
+            .and_then(|port| u16::try_from(port).ok());
+        let status = deployment.status.unwrap_or_default();
+
+        workloads.push(Workload {
+            group: labels.get(GROUP_LABEL).cloned().unwrap_or_else(|| namespace.to_string()),
+            middleware: labels.get(MIDDLEWARE_LABEL).cloned(),
+            deployment: name,
+            role,
+            port,
+            replicas: spec.replicas.unwrap_or(1),
+            ready_replicas: status.ready_replicas.unwrap_or(0),
+            pods,
+        });
+    }
+    Ok(workloads)
+}
+
+/// 由容器名称与ID生成Deployment名称：只含小写字母、数字与 `-`，不超过63个字符
+pub fn deployment_name(name: &str, id: &str) -> String {
+    let mut base = String::new();
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            base.push(c.to_ascii_lowercase());
+        } else if !base.is_empty() && !base.ends_with('-') {
+            base.push('-');
+        }
+    }
+    base.truncate(54);
+    let base = base.trim_end_matches('-');
+    let suffix: String = id.chars().filter(char::is_ascii_alphanumeric).take(8).collect();
+    if base.is_empty() {
+        format!("encryption-service-{}", suffix)
+    } else {
+        format!("{}-{}", base, suffix)
+    }
+}
+
+/// 业务组标签的取值：标签值只允许字母、数字与 `-_.`，名称不合法时使用业务组ID
+pub fn group_label(group: &BusinessGroup) -> String {
+    let valid = |c: char| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.');
+    let name = group.name.trim();
+    if !name.is_empty() && name.len() <= 63 && name.chars().all(valid) {
+        name.to_string()
+    } else {
+        group.id.chars().filter(|c| valid(*c)).take(63).collect()
+    }
+}
+
+/// 部署到集群的工作负载
+pub struct DeploymentRequest {
+    pub link: KubernetesLink,
+    /// 业务组标签的取值
+    pub group: String,
+    pub role: WorkloadRole,
+    /// 后端所属中间层的Deployment名称
+    pub middleware: Option<String>,
+    pub replicas: u32,
+    pub spec: ContainerSpec,
+    /// 模型注入的环境变量，容器定义中同名的变量优先
+    pub env: Vec<(&'static str, String)>,
+}
+
+impl DeploymentRequest {
+    fn labels(&self) -> BTreeMap<String, String> {
+        let mut labels = BTreeMap::from([
+            (APP_LABEL.to_string(), self.link.deployment.clone()),
+            (GROUP_LABEL.to_string(), self.group.clone()),
+            (ROLE_LABEL.to_string(), self.role.label_value().to_string()),
+        ]);
+        if let Some(middleware) = &self.middleware {
+            labels.insert(MIDDLEWARE_LABEL.to_string(), middleware.clone());
+        }
+        labels
+    }
+
+    /// Deployment清单：绝对路径挂载为hostPath，卷名挂载为同名的PersistentVolumeClaim
+    fn deployment(&self) -> Value {
+        let spec = &self.spec;
+        let mut env: Vec<Value> = self
+            .env
+            .iter()
+            .filter(|(key, _)| !spec.env.iter().any(|e| e.key == *key))
+            .map(|(key, value)| json!({ "name": key, "value": value }))
+            .collect();
+        env.extend(spec.env.iter().map(|e| json!({ "name": e.key, "value": e.value })));
+        let ports: Vec<Value> = spec
+            .ports
+            .iter()
+            .map(|p| json!({ "containerPort": p.container_port, "protocol": p.protocol.to_uppercase() }))
+            .collect();
+        let mounts: Vec<Value> = spec
+            .volumes
+            .iter()
+            .enumerate()
+            .map(|(i, v)| json!({ "name": format!("volume-{}", i), "mountPath": v.target, "readOnly": v.read_only }))
+            .collect();
+        let volumes: Vec<Value> = spec
+            .volumes
+            .iter()
+            .enumerate()
+            .map(|(i, v)| {
+                let name = format!("volume-{}", i);
+                if v.source.starts_with(['/', '.', '~']) || v.source.contains(['\\', ':']) {
+                    json!({ "name": name, "hostPath": { "path": v.source } })
+                } else {
+                    json!({ "name": name, "persistentVolumeClaim": { "claimName": v.source } })
+                }
+            })
+            .collect();
+
+        let mut container = json!({
+            "name": "app",
+            "image": spec.image,
+            "env": env,
+            "ports": ports,
+            "volumeMounts": mounts,
+        });
+        if !spec.command.is_empty() {
+            container["args"] = json!(spec.command);
+        }
+        // CPU按千分之一核、内存按字节设置上限
+        let mut limits = serde_json::Map::new();
+        if let Some(nano_cpus) = spec.nano_cpus {
+            limits.insert("cpu".to_string(), json!(format!("{}m", (nano_cpus / 1_000_000).max(1))));
+        }
+        if let Some(memory) = spec.memory {
+            limits.insert("memory".to_string(), json!(memory.to_string()));
+        }
+        if !limits.is_empty() {
+            container["resources"] = json!({ "limits": limits });
+        }
+        let labels = self.labels();
+        json!({
+            "apiVersion": "apps/v1",
+            "kind": "Deployment",
+            "metadata": { "name": self.link.deployment, "namespace": self.link.namespace, "labels": labels },
+            "spec": {
+                "replicas": self.replicas,
+                "selector": { "matchLabels": { APP_LABEL: self.link.deployment } },
+                "template": {
+                    "metadata": { "labels": labels },
+                    "spec": { "containers": [container], "volumes": volumes },
+                },
+            },
+        })
+    }
+
+    /// 与Deployment同名的Service清单，未声明容器端口时为空
+    fn service(&self) -> Option<Value> {
+        if self.spec.ports.is_empty() {
+            return None;
+        }
+        let ports: Vec<Value> = self
+            .spec
+            .ports
+            .iter()
+            .map(|p| {
+                json!({
+                    "name": format!("{}-{}", p.protocol.to_lowercase(), p.container_port),
+                    "port": p.container_port,
+                    "targetPort": p.container_port,
+                    "protocol": p.protocol.to_uppercase(),
+                })
+            })
+            .collect();
+        Some(json!({
+            "apiVersion": "v1",
+            "kind": "Service",
+            "metadata": { "name": self.link.deployment, "namespace": self.link.namespace, "labels": self.labels() },
+            "spec": { "selector": { APP_LABEL: self.link.deployment }, "ports": ports },
+        }))
+    }
+}
+
+/// 以服务端应用方式创建或更新Deployment，声明了容器端口时同时创建同名Service供集群内访问
+pub fn apply(request: &DeploymentRequest) -> Result<()> {
+    let link = &request.link;
+    tasks::block_on(async {
+        let client = kube::Client::try_default().await.context("无法连接Kubernetes集群")?;
+        let params = PatchParams::apply(FIELD_MANAGER).force();
+        Api::<Deployment>::namespaced(client.clone(), &link.namespace)
+            .patch(&link.deployment, &params, &Patch::Apply(request.deployment()))
+            .await
+            .with_context(|| format!("无法部署Deployment {}/{}", link.namespace, link.deployment))?;
+        if let Some(service) = request.service() {
+            Api::<Service>::namespaced(client, &link.namespace)
+                .patch(&link.deployment, &params, &Patch::Apply(service))
+                .await
+                .with_context(|| format!("无法部署Service {}/{}", link.namespace, link.deployment))?;
+        }
+        Ok(())
+    })
+}
+
+/// 调整Deployment的副本数，Deployment不存在且副本数为0时视为成功
+pub fn scale(link: &KubernetesLink, replicas: u32) -> Result<()> {
+    tasks::block_on(async {
+        let client = kube::Client::try_default().await.context("无法连接Kubernetes集群")?;
+        let patch = json!({ "spec": { "replicas": replicas } });
+        let result = Api::<Deployment>::namespaced(client, &link.namespace)
+            .patch(&link.deployment, &PatchParams::default(), &Patch::Merge(patch))
+            .await;
+        match result {
+            Err(kube::Error::Api(e)) if e.code == 404 && replicas == 0 => Ok(()),
+            result => result
+                .map(|_| ())
+                .with_context(|| format!("无法调整Deployment {}/{} 的副本数", link.namespace, link.deployment)),
+        }
+    })
+}
+
+/// 业务组内所有容器的集群关联
+pub fn group_links(group: &BusinessGroup) -> Vec<&KubernetesLink> {
+    let mut links = Vec::new();
+    for middleware in &group.middlewares {
+        links.extend(middleware.kubernetes.as_ref());
+        links.extend(middleware.backend_containers.iter().filter_map(|b| b.kubernetes.as_ref()));
+    }
+    links.extend(group.backend_containers.iter().filter_map(|b| b.kubernetes.as_ref()));
+    links
+}
+
+/// 将工作负载按业务组标签映射为业务组
+pub fn to_groups(namespace: &str, workloads: &[Workload]) -> Vec<BusinessGroup> {
+    let mut by_group: BTreeMap<&str, Vec<&Workload>> = BTreeMap::new();
+    for workload in workloads {
+        by_group.entry(&workload.group).or_default().push(workload);
+    }
+
+    by_group
+        .into_iter()
+        .map(|(name, workloads)| {
+            let mut group = BusinessGroup {
+                name: name.to_string(),
+                description: format!("从Kubernetes命名空间 {} 导入", namespace),
+                ..BusinessGroup::default()
+            };
+
+            for workload in workloads.iter().filter(|w| w.role == WorkloadRole::Middleware) {
+                let mut middleware = MiddlewareContainer {
+                    name: workload.deployment.clone(),
+                    url: workload.url(namespace),
+                    status: workload.status(),
+                    kubernetes: Some(workload.link(namespace)),
+                    runtime: RuntimeKind::Kubernetes { namespace: namespace.to_string() },
+                    replicas: u32::try_from(workload.replicas).unwrap_or(1).max(1),
+                    ..MiddlewareContainer::default()
+                };
+                middleware.config.server.port = workload.port.unwrap_or(DEFAULT_MIDDLEWARE_PORT);
+                group.middlewares.push(middleware);
+            }
+
+            for workload in workloads.iter().filter(|w| w.role == WorkloadRole::Backend) {
+                let backend = BackendContainer {
+                    name: workload.deployment.clone(),
+                    url: workload.url(namespace),
+                    status: workload.status(),
+                    kubernetes: Some(workload.link(namespace)),
+                    replicas: u32::try_from(workload.replicas).unwrap_or(1).max(1),
+                    last_seen: Some(Utc::now()),
+                    ..BackendContainer::default()
+                };
+                let parent = workload
+                    .middleware
+                    .as_ref()
+                    .and_then(|m| group.middlewares.iter_mut().find(|mw| &mw.name == m));
+                match parent {
+                    Some(middleware) => middleware.backend_containers.push(backend),
+                    None => group.backend_containers.push(backend),
+                }
+            }
+
+            if group.middlewares.iter().any(|m| m.status == ContainerStatus::Running) {
+                group.status = GroupStatus::Running;
+            }
+            group
+        })
+        .collect()
+}
@@ -1,14 +1,58 @@
 use eframe::NativeOptions;
+use tracing_subscriber::filter::{self, LevelFilter};
+use tracing_subscriber::prelude::*;
 
 mod app;
 mod models;
 mod api;
 mod services;
 mod config;
+mod compose;
+mod metrics;
+mod anomaly;
+mod chunking;
+mod alerting;
+mod topology;
+mod image_export;
+mod jobs;
+mod kubernetes;
+mod docker;
+mod state;
+mod events;
+mod scheduler;
+mod warmup;
+mod verification;
+mod webhook;
+mod weights;
+mod migration;
+mod systemd;
+mod terraform;
+mod telemetry;
+mod probe;
+mod runtime;
+mod tunnel;
+mod agent;
+mod registry;
+mod json_editor;
+mod upstream;
+mod reconcile;
+mod snapshot;
+mod tasks;
+mod audit_export;
+mod password;
+mod tuning;
+mod polling;
+mod repaint;
+mod capacity;
+mod cost;
+mod diagnostics;
 
 fn main() -> Result<(), eframe::Error> {
-    // 初始化日志
-    tracing_subscriber::fmt::init();
+    // 初始化日志，本程序的span另外交给OTLP导出层，导出在设置中启用后生效
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::fmt::layer().with_filter(LevelFilter::INFO))
+        .with(telemetry::layer().with_filter(filter::filter_fn(telemetry::captures)))
+        .init();
     
     let options = NativeOptions {
         viewport: egui::ViewportBuilder::default()
@@ -0,0 +1,354 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use chrono::{DateTime, Utc};
+
+/// 每个中间层保留的采样点数量
+const MAX_SAMPLES: usize = 120;
+
+/// 接口类别枚举
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EndpointKind {
+    Encrypt,
+    Decrypt,
+    Config,
+    Other,
+}
+
+impl EndpointKind {
+    /// 所有接口类别
+    pub const ALL: [EndpointKind; 4] = [
+        EndpointKind::Encrypt,
+        EndpointKind::Decrypt,
+        EndpointKind::Config,
+        EndpointKind::Other,
+    ];
+
+    /// 根据接口路径归类
+    pub fn from_endpoint(endpoint: &str) -> Self {
+        let endpoint = endpoint.to_lowercase();
+        if endpoint.contains("decrypt") {
+            EndpointKind::Decrypt
+        } else if endpoint.contains("encrypt") {
+            EndpointKind::Encrypt
+        } else if endpoint.contains("config") {
+            EndpointKind::Config
+        } else {
+            EndpointKind::Other
+        }
+    }
+
+    /// 显示名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            EndpointKind::Encrypt => "加密",
+            EndpointKind::Decrypt => "解密",
+            EndpointKind::Config => "配置",
+            EndpointKind::Other => "其他",
+        }
+    }
+}
+
+/// 接口累计计数
+#[derive(Debug, Clone, Default)]
+pub struct EndpointCounter {
+    pub requests: f64,
+    pub errors: f64,
+}
+
+/// 一次指标采集样本
+#[derive(Debug, Clone)]
+pub struct MetricsSample {
+    /// 采集轮次，同一轮采集的样本用于业务组聚合
+    pub round: u64,
+    pub timestamp: DateTime<Utc>,
+    pub counters: HashMap<EndpointKind, EndpointCounter>,
+    /// 指标接口的响应耗时（毫秒）
+    pub latency_ms: f64,
+}
+
+/// 速率数据点
+#[derive(Debug, Clone)]
+pub struct RatePoint {
+    pub round: u64,
+    pub timestamp: DateTime<Utc>,
+    /// 每秒请求数
+    pub qps: f64,
+    /// 错误请求占比 (0.0 - 1.0)
+    pub error_rate: f64,
+}
+
+/// 解析Prometheus文本格式的请求计数器
+///
+/// 识别 `*requests_total` 与 `*errors_total` 计数器，接口取自
+/// `endpoint`/`path`/`handler`/`route` 标签。接口有错误计数器时错误数取自错误计数器，
+/// 否则取请求计数器中 `status`/`code` 标签不小于400的部分；同一接口只按一种规则计数，
+/// 两种指标都导出的服务不会把同一批错误计两次。
+pub fn parse_metrics(text: &str) -> HashMap<EndpointKind, EndpointCounter> {
+    let mut counters: HashMap<EndpointKind, EndpointCounter> = HashMap::new();
+    // 各接口按状态码识别的错误数，接口没有错误计数器时才采用
+    let mut status_errors: HashMap<EndpointKind, f64> = HashMap::new();
+    let mut has_error_metric: HashSet<EndpointKind> = HashSet::new();
+
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let name_end = line.find(|c: char| c == '{' || c.is_whitespace()).unwrap_or(line.len());
+        let name = &line[..name_end];
+        let (labels, rest) = match line[name_end..].strip_prefix('{') {
+            Some(labels) => match parse_labels(labels) {
+                Some(parsed) => parsed,
+                None => continue,
+            },
+            None => (HashMap::new(), &line[name_end..]),
+        };
+
+        let value: f64 = match rest.split_whitespace().next().and_then(|v| v.parse().ok()) {
+            Some(value) => value,
+            None => continue,
+        };
+
+        let is_requests = name.ends_with("requests_total");
+        let is_errors = name.ends_with("errors_total");
+        if !is_requests && !is_errors {
+            continue;
+        }
+
+        let endpoint = ["endpoint", "path", "handler", "route"]
+            .iter()
+            .find_map(|key| labels.get(*key))
+            .map(String::as_str)
+            .unwrap_or("");
+        let kind = EndpointKind::from_endpoint(endpoint);
+        let counter = counters.entry(kind).or_default();
+
+        if is_errors {
+            counter.errors += value;
+            has_error_metric.insert(kind);
+        } else {
+            counter.requests += value;
+            let status = labels.get("status").or_else(|| labels.get("code"));
+            if status.and_then(|s| s.parse::<u16>().ok()).is_some_and(|s| s >= 400) {
+                *status_errors.entry(kind).or_default() += value;
+            }
+        }
+    }
+
+    for (kind, errors) in status_errors {
+        if !has_error_metric.contains(&kind) {
+            counters.entry(kind).or_default().errors += errors;
+        }
+    }
+    counters
+}
+
+/// 解析 `{` 之后 `key="value",key2="value2"}` 形式的标签，返回标签与 `}` 之后的部分
+///
+/// 标签值按引号界定，可包含逗号与花括号，`\\`、`\"`、`\n` 按转义处理；格式错误时返回空
+fn parse_labels(input: &str) -> Option<(HashMap<String, String>, &str)> {
+    let mut labels = HashMap::new();
+    let mut rest = input.trim_start();
+    loop {
+        if let Some(after) = rest.strip_prefix('}') {
+            return Some((labels, after));
+        }
+        let (key, after_key) = rest.split_once('=')?;
+        let key = key.trim();
+        if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return None;
+        }
+
+        let quoted = after_key.trim_start().strip_prefix('"')?;
+        let mut value = String::new();
+        let mut chars = quoted.char_indices();
+        let end = loop {
+            match chars.next()? {
+                (i, '"') => break i,
+                (_, '\\') => match chars.next()?.1 {
+                    'n' => value.push('\n'),
+                    c => value.push(c),
+                },
+                (_, c) => value.push(c),
+            }
+        };
+        labels.insert(key.to_string(), value);
+
+        rest = quoted[end + 1..].trim_start();
+        if let Some(after) = rest.strip_prefix(',') {
+            rest = after.trim_start();
+        } else if !rest.starts_with('}') {
+            return None;
+        }
+    }
+}
+
+/// 指标存储，按中间层ID保存滚动窗口内的采样
+#[derive(Debug, Default)]
+pub struct MetricsStore {
+    samples: HashMap<String, VecDeque<MetricsSample>>,
+}
+
+impl MetricsStore {
+    /// 创建新的指标存储
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 记录一次采样
+    pub fn record(&mut self, middleware_id: &str, sample: MetricsSample) {
+        let samples = self.samples.entry(middleware_id.to_string()).or_default();
+        samples.push_back(sample);
+        while samples.len() > MAX_SAMPLES {
+            samples.pop_front();
+        }
+    }
+
+    /// 获取中间层最新的采样
+    pub fn latest(&self, middleware_id: &str) -> Option<&MetricsSample> {
+        self.samples.get(middleware_id).and_then(|s| s.back())
+    }
+
+    /// 计算中间层某类接口的速率序列
+    pub fn rate_series(&self, middleware_id: &str, kind: EndpointKind) -> Vec<RatePoint> {
+        let samples = match self.samples.get(middleware_id) {
+            Some(samples) => samples,
+            None => return Vec::new(),
+        };
+
+        samples
+            .iter()
+            .zip(samples.iter().skip(1))
+            .filter_map(|(prev, next)| {
+                let elapsed = (next.timestamp - prev.timestamp).num_milliseconds() as f64 / 1000.0;
+                if elapsed <= 0.0 {
+                    return None;
+                }
+
+                let before = prev.counters.get(&kind).cloned().unwrap_or_default();
+                let after = next.counters.get(&kind).cloned().unwrap_or_default();
+                // 计数器被重置（服务重启）时，以当前值作为增量
+                let requests = if after.requests >= before.requests { after.requests - before.requests } else { after.requests };
+                let errors = if after.errors >= before.errors { after.errors - before.errors } else { after.errors };
+
+                Some(RatePoint {
+                    round: next.round,
+                    timestamp: next.timestamp,
+                    qps: requests / elapsed,
+                    error_rate: if requests > 0.0 { (errors / requests).min(1.0) } else { 0.0 },
+                })
+            })
+            .collect()
+    }
+
+    /// 获取中间层的响应延迟序列
+    pub fn latency_series(&self, middleware_id: &str) -> Vec<(DateTime<Utc>, f64)> {
+        self.samples
+            .get(middleware_id)
+            .map(|samples| samples.iter().map(|s| (s.timestamp, s.latency_ms)).collect())
+            .unwrap_or_default()
+    }
+
+    /// 按采集轮次聚合多个中间层的速率序列
+    pub fn group_rate_series(&self, middleware_ids: &[String], kind: EndpointKind) -> Vec<RatePoint> {
+        let mut rounds: Vec<(u64, DateTime<Utc>, f64, f64)> = Vec::new();
+
+        for middleware_id in middleware_ids {
+            for point in self.rate_series(middleware_id, kind) {
+                let errors = point.qps * point.error_rate;
+                match rounds.iter_mut().find(|(round, ..)| *round == point.round) {
+                    Some(entry) => {
+                        entry.2 += point.qps;
+                        entry.3 += errors;
+                    }
+                    None => rounds.push((point.round, point.timestamp, point.qps, errors)),
+                }
+            }
+        }
+
+        rounds.sort_by_key(|(round, ..)| *round);
+        rounds
+            .into_iter()
+            .map(|(round, timestamp, qps, errors)| RatePoint {
+                round,
+                timestamp,
+                qps,
+                error_rate: if qps > 0.0 { (errors / qps).min(1.0) } else { 0.0 },
+            })
+            .collect()
+    }
+
+    /// 移除中间层的采样
+    pub fn remove(&mut self, middleware_id: &str) {
+        self.samples.remove(middleware_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(input: &str) -> HashMap<String, String> {
+        parse_labels(input).unwrap().0
+    }
+
+    #[test]
+    fn labels_keep_quoted_commas_and_braces() {
+        let (parsed, rest) = parse_labels(r#"path="/v1/encrypt,batch", note="a}b" } 12"#).unwrap();
+        assert_eq!(parsed["path"], "/v1/encrypt,batch");
+        assert_eq!(parsed["note"], "a}b");
+        assert_eq!(rest, " 12");
+        // 末尾逗号与空标签集合
+        assert_eq!(labels(r#"code="500",}"#)["code"], "500");
+        assert!(labels("}").is_empty());
+    }
+
+    #[test]
+    fn labels_unescape_values() {
+        let parsed = labels(r#"msg="say \"hi\"\nback\\slash"}"#);
+        assert_eq!(parsed["msg"], "say \"hi\"\nback\\slash");
+    }
+
+    #[test]
+    fn malformed_labels_are_rejected() {
+        assert!(parse_labels(r#"path="/encrypt""#).is_none());
+        assert!(parse_labels(r#"path=/encrypt}"#).is_none());
+        assert!(parse_labels(r#"path="/encrypt" code="200"}"#).is_none());
+        assert!(parse_labels(r#"="x"}"#).is_none());
+    }
+
+    #[test]
+    fn error_counter_takes_precedence_over_status_labels() {
+        let text = r#"
+# HELP http_requests_total Total requests
+# TYPE http_requests_total counter
+http_requests_total{path="/encrypt",status="200"} 90
+http_requests_total{path="/encrypt",status="500"} 10
+http_errors_total{path="/encrypt"} 7
+http_requests_total{path="/decrypt",code="503"} 4
+http_requests_total{path="/decrypt",code="200"} 16
+"#;
+        let counters = parse_metrics(text);
+        let encrypt = &counters[&EndpointKind::Encrypt];
+        assert_eq!(encrypt.requests, 100.0);
+        assert_eq!(encrypt.errors, 7.0);
+        let decrypt = &counters[&EndpointKind::Decrypt];
+        assert_eq!(decrypt.requests, 20.0);
+        assert_eq!(decrypt.errors, 4.0);
+    }
+
+    #[test]
+    fn quoted_label_values_do_not_break_lines() {
+        let text = concat!(
+            "requests_total{route=\"/config\",detail=\"a,b}c\"} 5 1700000000\n",
+            "requests_total 3\n",
+            "requests_total{route=\"/config\" 9\n",
+            "requests_total{route=\"/encrypt\"} not-a-number\n",
+            "process_cpu_seconds_total 1.5\n",
+        );
+        let counters = parse_metrics(text);
+        assert_eq!(counters[&EndpointKind::Config].requests, 5.0);
+        assert_eq!(counters[&EndpointKind::Other].requests, 3.0);
+        assert!(!counters.contains_key(&EndpointKind::Encrypt));
+    }
+}
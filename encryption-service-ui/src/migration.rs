@@ -0,0 +1,207 @@
+use anyhow::{Context, Result};
+
+use crate::api::{self, ApiClient, ApiClientConfig};
+use crate::models::{AppConfig, ContainerStatus, GroupId, HealthStatus, MiddlewareContainer, MiddlewareId};
+use crate::services::MiddlewareService;
+use crate::state::StateStore;
+use crate::tasks;
+
+/// 合成事务使用的样例数据
+const SYNTHETIC_PAYLOAD: &str = "migration-probe";
+
+/// 迁移步骤，按顺序执行
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrationStep {
+    PushConfig,
+    MoveBackends,
+    VerifyHealth,
+    SyntheticTransaction,
+    RetireSource,
+}
+
+impl MigrationStep {
+    /// 所有步骤
+    pub const ALL: [MigrationStep; 5] = [
+        MigrationStep::PushConfig,
+        MigrationStep::MoveBackends,
+        MigrationStep::VerifyHealth,
+        MigrationStep::SyntheticTransaction,
+        MigrationStep::RetireSource,
+    ];
+
+    /// 显示名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            MigrationStep::PushConfig => "推送配置",
+            MigrationStep::MoveBackends => "迁移后端",
+            MigrationStep::VerifyHealth => "健康检查",
+            MigrationStep::SyntheticTransaction => "合成事务",
+            MigrationStep::RetireSource => "下线源中间层",
+        }
+    }
+
+    /// 步骤说明
+    pub fn description(&self) -> &'static str {
+        match self {
+            MigrationStep::PushConfig => "将源中间层的配置写入目标中间层（保留目标的监听地址），并推送到目标服务。",
+            MigrationStep::MoveBackends => "把源中间层下的全部后端容器移到目标中间层。",
+            MigrationStep::VerifyHealth => "确认目标中间层健康检查通过。",
+            MigrationStep::SyntheticTransaction => "在目标中间层上执行一次加密解密往返。",
+            MigrationStep::RetireSource => "停止源中间层。",
+        }
+    }
+}
+
+/// 一次中间层迁移的进度及回滚所需的数据
+#[derive(Debug, Clone)]
+pub struct Migration {
+    pub source_group_id: String,
+    pub source_id: String,
+    pub target_group_id: String,
+    pub target_id: String,
+    /// 整个迁移过程共用的追踪ID，各步骤的请求均携带此ID
+    pub trace_id: String,
+    /// 已完成的步骤
+    pub completed: Vec<MigrationStep>,
+    /// 最近一次失败的原因
+    pub error: Option<String>,
+    /// 推送前目标中间层的配置
+    previous_target_config: Option<AppConfig>,
+    /// 已转移的后端ID
+    moved_backends: Vec<String>,
+    /// 下线前源中间层的状态
+    previous_source_status: Option<ContainerStatus>,
+}
+
+impl Migration {
+    /// 创建迁移
+    pub fn new(source_group_id: &str, source_id: &str, target_group_id: &str, target_id: &str) -> Self {
+        Self {
+            source_group_id: source_group_id.to_string(),
+            source_id: source_id.to_string(),
+            target_group_id: target_group_id.to_string(),
+            target_id: target_id.to_string(),
+            trace_id: api::new_trace_id(),
+            completed: Vec::new(),
+            error: None,
+            previous_target_config: None,
+            moved_backends: Vec::new(),
+            previous_source_status: None,
+        }
+    }
+
+    /// 下一个待执行的步骤
+    pub fn next_step(&self) -> Option<MigrationStep> {
+        MigrationStep::ALL.get(self.completed.len()).copied()
+    }
+
+    /// 最近完成、可回滚的步骤
+    pub fn last_completed(&self) -> Option<MigrationStep> {
+        self.completed.last().copied()
+    }
+
+    /// 是否已全部完成
+    pub fn is_finished(&self) -> bool {
+        self.next_step().is_none()
+    }
+
+    /// 执行下一步
+    pub fn run_next(&mut self, state: &StateStore) -> Result<MigrationStep> {
+        let step = self.next_step().context("迁移已全部完成")?;
+        let result = self.run_step(step, state);
+        self.error = result.as_ref().err().map(|e| format!("{:#}", e));
+        result?;
+        self.completed.push(step);
+        Ok(step)
+    }
+
+    /// 回滚最近完成的一步
+    pub fn rollback_last(&mut self, state: &StateStore) -> Result<MigrationStep> {
+        let step = self.last_completed().context("没有可回滚的步骤")?;
+        let result = self.rollback_step(step, state);
+        self.error = result.as_ref().err().map(|e| format!("回滚失败: {:#}", e));
+        result?;
+        self.completed.pop();
+        Ok(step)
+    }
+
+    fn find(&self, state: &StateStore, id: &str) -> Result<MiddlewareContainer> {
+        state
+            .read(|s| s.find_middleware(&MiddlewareId::from(id)).map(|(_, m)| m.clone()))
+            .ok_or_else(|| anyhow::anyhow!("中间层容器不存在: {}", id))
+    }
+
+    fn client(&self, state: &StateStore, middleware: &MiddlewareContainer) -> Result<ApiClient> {
+        ApiClient::new(ApiClientConfig {
+            base_url: middleware.api_base_url(),
+            timeout: middleware.config.crud_api.timeout,
+            network: state.read(|s| s.network_for(&middleware.id)),
+            trace_id: Some(self.trace_id.clone()),
+        })
+    }
+
+    fn run_step(&mut self, step: MigrationStep, state: &StateStore) -> Result<()> {
+        let service = MiddlewareService::new(state.clone());
+        let source = self.find(state, &self.source_id)?;
+        let target = self.find(state, &self.target_id)?;
+
+        match step {
+            MigrationStep::PushConfig => {
+                let mut config = source.config.clone();
+                config.server = target.config.server.clone();
+                tasks::block_on(self.client(state, &target)?.update_config(&config)).context("推送配置到目标服务失败")?;
+
+                self.previous_target_config = Some(target.config.clone());
+                let mut updated = target;
+                updated.config = config;
+                service.update_middleware(&GroupId::from(&self.target_group_id), updated)
+            }
+            MigrationStep::MoveBackends => {
+                let ids: Vec<String> = source.backend_containers.iter().map(|b| b.id.clone()).collect();
+                service.transfer_backends(&self.source_id, &self.target_id, &ids)?;
+                self.moved_backends = ids;
+                Ok(())
+            }
+            MigrationStep::VerifyHealth => match tasks::block_on(self.client(state, &target)?.health_check())? {
+                HealthStatus::Healthy => Ok(()),
+                health => anyhow::bail!("目标中间层健康状态为{}", health.label()),
+            },
+            MigrationStep::SyntheticTransaction => {
+                let client = self.client(state, &target)?;
+                let encrypted = tasks::block_on(client.encrypt(SYNTHETIC_PAYLOAD))?;
+                if tasks::block_on(client.decrypt(&encrypted))? != SYNTHETIC_PAYLOAD {
+                    anyhow::bail!("解密结果与原文不一致");
+                }
+                Ok(())
+            }
+            MigrationStep::RetireSource => {
+                self.previous_source_status = Some(source.status.clone());
+                service.stop_middleware(&GroupId::from(&self.source_group_id), &MiddlewareId::from(&self.source_id))
+            }
+        }
+    }
+
+    fn rollback_step(&mut self, step: MigrationStep, state: &StateStore) -> Result<()> {
+        let service = MiddlewareService::new(state.clone());
+        match step {
+            MigrationStep::PushConfig => {
+                let previous = self.previous_target_config.clone().context("缺少目标中间层的原配置")?;
+                let mut target = self.find(state, &self.target_id)?;
+                tasks::block_on(self.client(state, &target)?.update_config(&previous)).context("恢复目标服务配置失败")?;
+                target.config = previous;
+                service.update_middleware(&GroupId::from(&self.target_group_id), target)
+            }
+            MigrationStep::MoveBackends => {
+                service.transfer_backends(&self.target_id, &self.source_id, &self.moved_backends)?;
+                self.moved_backends.clear();
+                Ok(())
+            }
+            // 只读检查，无需回滚
+            MigrationStep::VerifyHealth | MigrationStep::SyntheticTransaction => Ok(()),
+            MigrationStep::RetireSource => {
+                let status = self.previous_source_status.clone().unwrap_or(ContainerStatus::Running);
+                service.set_middleware_status(&GroupId::from(&self.source_group_id), &MiddlewareId::from(&self.source_id), status)
+            }
+        }
+    }
+}
@@ -1,7 +1,11 @@
 use serde::{Deserialize, Serialize};
-use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+use chrono::{DateTime, Local, NaiveDate, Utc};
 use uuid::Uuid;
 
+use crate::events::EntityKind;
+
 /// 业务组状态枚举
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub enum GroupStatus {
@@ -10,6 +14,8 @@ pub enum GroupStatus {
     Starting,
     Stopping,
     Error,
+    /// 部分中间层或后端故障，仅由 `BusinessGroup::rollup_status` 计算得出
+    Degraded,
 }
 
 /// 容器状态枚举
@@ -31,6 +37,45 @@ pub enum HealthStatus {
     Checking,
 }
 
+impl GroupStatus {
+    /// 显示名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            GroupStatus::Running => "运行中",
+            GroupStatus::Stopped => "已停止",
+            GroupStatus::Starting => "启动中",
+            GroupStatus::Stopping => "停止中",
+            GroupStatus::Error => "错误",
+            GroupStatus::Degraded => "降级",
+        }
+    }
+}
+
+impl ContainerStatus {
+    /// 显示名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            ContainerStatus::Running => "运行中",
+            ContainerStatus::Stopped => "已停止",
+            ContainerStatus::Starting => "启动中",
+            ContainerStatus::Stopping => "停止中",
+            ContainerStatus::Error => "错误",
+        }
+    }
+}
+
+impl HealthStatus {
+    /// 显示名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            HealthStatus::Healthy => "健康",
+            HealthStatus::Unhealthy => "不健康",
+            HealthStatus::Unknown => "未知",
+            HealthStatus::Checking => "检查中",
+        }
+    }
+}
+
 /// 调度策略枚举
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub enum SchedulerStrategy {
@@ -42,6 +87,35 @@ pub enum SchedulerStrategy {
     LoadBalance,
 }
 
+impl SchedulerStrategy {
+    /// 所有调度策略
+    pub const ALL: [SchedulerStrategy; 3] = [
+        SchedulerStrategy::Single,
+        SchedulerStrategy::ReadWriteSplit,
+        SchedulerStrategy::LoadBalance,
+    ];
+
+    /// 显示名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            SchedulerStrategy::Single => "单容器模式",
+            SchedulerStrategy::ReadWriteSplit => "读写分离模式",
+            SchedulerStrategy::LoadBalance => "负载均衡模式",
+        }
+    }
+
+    /// 策略说明
+    pub fn description(&self) -> &'static str {
+        match self {
+            SchedulerStrategy::Single => "所有请求都发往第一个可用的后端实例，其余实例仅作备用。",
+            SchedulerStrategy::ReadWriteSplit => {
+                "读请求在读实例与混合实例间轮询，写请求在写实例与混合实例间轮询，两类请求各自计数。"
+            }
+            SchedulerStrategy::LoadBalance => "不区分请求类型，所有请求在全部可用实例间依次轮询。",
+        }
+    }
+}
+
 /// CRUD API实例配置
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CrudApiInstance {
@@ -50,6 +124,17 @@ pub struct CrudApiInstance {
     pub instance_type: String,
     pub timeout: u64,
     pub retries: u32,
+    /// 负载均衡权重
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+}
+
+fn default_weight() -> u32 {
+    100
+}
+
+fn default_replicas() -> u32 {
+    1
 }
 
 /// 服务器配置
@@ -104,6 +189,230 @@ pub struct AppConfig {
     pub crud_api: CrudApiConfig,
 }
 
+impl AppConfig {
+    /// 嵌套结构都有一个元素的示例配置，原始JSON编辑器据此推断字段
+    pub fn schema_sample() -> Self {
+        let mut config = MiddlewareContainer::default().config;
+        config.crud_api.instances.push(CrudApiInstance {
+            id: String::new(),
+            url: String::new(),
+            instance_type: "read".to_string(),
+            timeout: 0,
+            retries: 0,
+            weight: default_weight(),
+        });
+        config
+    }
+}
+
+/// 可批量下发的AppConfig字段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AppConfigField {
+    ServerHost,
+    ServerPort,
+    ServerHttps,
+    JwtExpiresIn,
+    JwtRefreshIn,
+    EncryptionAlgorithm,
+    EncryptionKeyLength,
+    EncryptionIterations,
+    HealthCheckInterval,
+    CrudTimeout,
+    CrudRetries,
+}
+
+impl AppConfigField {
+    /// 所有可编辑字段
+    pub const ALL: [AppConfigField; 11] = [
+        AppConfigField::ServerHost,
+        AppConfigField::ServerPort,
+        AppConfigField::ServerHttps,
+        AppConfigField::JwtExpiresIn,
+        AppConfigField::JwtRefreshIn,
+        AppConfigField::EncryptionAlgorithm,
+        AppConfigField::EncryptionKeyLength,
+        AppConfigField::EncryptionIterations,
+        AppConfigField::HealthCheckInterval,
+        AppConfigField::CrudTimeout,
+        AppConfigField::CrudRetries,
+    ];
+
+    /// 字段路径
+    pub fn label(&self) -> &'static str {
+        match self {
+            AppConfigField::ServerHost => "server.host",
+            AppConfigField::ServerPort => "server.port",
+            AppConfigField::ServerHttps => "server.https",
+            AppConfigField::JwtExpiresIn => "jwt.expires_in",
+            AppConfigField::JwtRefreshIn => "jwt.refresh_in",
+            AppConfigField::EncryptionAlgorithm => "encryption.algorithm",
+            AppConfigField::EncryptionKeyLength => "encryption.key_length",
+            AppConfigField::EncryptionIterations => "encryption.iterations",
+            AppConfigField::HealthCheckInterval => "crud_api.health_check_interval",
+            AppConfigField::CrudTimeout => "crud_api.timeout",
+            AppConfigField::CrudRetries => "crud_api.retries",
+        }
+    }
+
+    /// 读取字段值
+    pub fn get(&self, config: &AppConfig) -> String {
+        match self {
+            AppConfigField::ServerHost => config.server.host.clone(),
+            AppConfigField::ServerPort => config.server.port.to_string(),
+            AppConfigField::ServerHttps => config.server.https.to_string(),
+            AppConfigField::JwtExpiresIn => config.jwt.expires_in.to_string(),
+            AppConfigField::JwtRefreshIn => config.jwt.refresh_in.to_string(),
+            AppConfigField::EncryptionAlgorithm => config.encryption.algorithm.clone(),
+            AppConfigField::EncryptionKeyLength => config.encryption.key_length.to_string(),
+            AppConfigField::EncryptionIterations => config.encryption.iterations.to_string(),
+            AppConfigField::HealthCheckInterval => config.crud_api.health_check_interval.to_string(),
+            AppConfigField::CrudTimeout => config.crud_api.timeout.to_string(),
+            AppConfigField::CrudRetries => config.crud_api.retries.to_string(),
+        }
+    }
+
+    /// 是否属于加密设置
+    pub fn is_encryption(&self) -> bool {
+        matches!(self, AppConfigField::EncryptionAlgorithm | AppConfigField::EncryptionKeyLength | AppConfigField::EncryptionIterations)
+    }
+
+    /// 解析并写入字段值
+    pub fn set(&self, config: &mut AppConfig, value: &str) -> anyhow::Result<()> {
+        let value = value.trim();
+        let invalid = || anyhow::anyhow!("字段 {} 的值无效: {}", self.label(), value);
+        match self {
+            AppConfigField::ServerHost => config.server.host = value.to_string(),
+            AppConfigField::ServerPort => config.server.port = value.parse().map_err(|_| invalid())?,
+            AppConfigField::ServerHttps => config.server.https = value.parse().map_err(|_| invalid())?,
+            AppConfigField::JwtExpiresIn => config.jwt.expires_in = value.parse().map_err(|_| invalid())?,
+            AppConfigField::JwtRefreshIn => config.jwt.refresh_in = value.parse().map_err(|_| invalid())?,
+            AppConfigField::EncryptionAlgorithm => config.encryption.algorithm = value.to_string(),
+            AppConfigField::EncryptionKeyLength => config.encryption.key_length = value.parse().map_err(|_| invalid())?,
+            AppConfigField::EncryptionIterations => config.encryption.iterations = value.parse().map_err(|_| invalid())?,
+            AppConfigField::HealthCheckInterval => config.crud_api.health_check_interval = value.parse().map_err(|_| invalid())?,
+            AppConfigField::CrudTimeout => config.crud_api.timeout = value.parse().map_err(|_| invalid())?,
+            AppConfigField::CrudRetries => config.crud_api.retries = value.parse().map_err(|_| invalid())?,
+        }
+        Ok(())
+    }
+}
+
+/// 中间层配置模板，一键填入JWT、加密与调度相关字段；地址、密钥、盐值与实例列表不受影响
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConfigPreset {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// 内置模板随程序提供，不保存到配置文件，不可删除
+    #[serde(skip)]
+    pub builtin: bool,
+    pub jwt_expires_in: i64,
+    pub jwt_refresh_in: i64,
+    pub encryption_algorithm: String,
+    pub encryption_key_length: u32,
+    pub encryption_iterations: u32,
+    /// 服务角色，为空时保持原值
+    #[serde(default)]
+    pub service_role: String,
+    pub strategy: SchedulerStrategy,
+    pub health_check_interval: u64,
+    pub timeout: u64,
+    pub retries: u32,
+}
+
+impl ConfigPreset {
+    /// 内置模板
+    pub fn builtin() -> Vec<ConfigPreset> {
+        vec![
+            ConfigPreset {
+                id: "builtin-high-security".to_string(),
+                name: "高安全".to_string(),
+                description: "短令牌有效期、高迭代次数的AES-256-GCM，读写分离".to_string(),
+                builtin: true,
+                jwt_expires_in: 900,
+                jwt_refresh_in: 3600,
+                encryption_algorithm: "aes-256-gcm".to_string(),
+                encryption_key_length: 32,
+                encryption_iterations: 600000,
+                service_role: String::new(),
+                strategy: SchedulerStrategy::ReadWriteSplit,
+                health_check_interval: 15,
+                timeout: 5000,
+                retries: 1,
+            },
+            ConfigPreset {
+                id: "builtin-low-latency".to_string(),
+                name: "低延迟".to_string(),
+                description: "较少的密钥派生迭代与较短超时，负载均衡分散请求".to_string(),
+                builtin: true,
+                jwt_expires_in: 3600,
+                jwt_refresh_in: 86400,
+                encryption_algorithm: "aes-128-gcm".to_string(),
+                encryption_key_length: 16,
+                encryption_iterations: 10000,
+                service_role: String::new(),
+                strategy: SchedulerStrategy::LoadBalance,
+                health_check_interval: 10,
+                timeout: 1000,
+                retries: 2,
+            },
+            ConfigPreset {
+                id: "builtin-read-replica".to_string(),
+                name: "只读副本".to_string(),
+                description: "只读服务角色，单容器转发，适合作为查询副本".to_string(),
+                builtin: true,
+                jwt_expires_in: 3600,
+                jwt_refresh_in: 86400,
+                encryption_algorithm: "aes-256-gcm".to_string(),
+                encryption_key_length: 32,
+                encryption_iterations: 100000,
+                service_role: "read".to_string(),
+                strategy: SchedulerStrategy::Single,
+                health_check_interval: 30,
+                timeout: 5000,
+                retries: 3,
+            },
+        ]
+    }
+
+    /// 由现有配置生成用户模板
+    pub fn from_config(name: &str, config: &AppConfig) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            name: name.trim().to_string(),
+            description: String::new(),
+            builtin: false,
+            jwt_expires_in: config.jwt.expires_in,
+            jwt_refresh_in: config.jwt.refresh_in,
+            encryption_algorithm: config.encryption.algorithm.clone(),
+            encryption_key_length: config.encryption.key_length,
+            encryption_iterations: config.encryption.iterations,
+            service_role: config.service.role.clone(),
+            strategy: config.crud_api.strategy.clone(),
+            health_check_interval: config.crud_api.health_check_interval,
+            timeout: config.crud_api.timeout,
+            retries: config.crud_api.retries,
+        }
+    }
+
+    /// 将模板字段写入配置
+    pub fn apply(&self, config: &mut AppConfig) {
+        config.jwt.expires_in = self.jwt_expires_in;
+        config.jwt.refresh_in = self.jwt_refresh_in;
+        config.encryption.algorithm = self.encryption_algorithm.clone();
+        config.encryption.key_length = self.encryption_key_length;
+        config.encryption.iterations = self.encryption_iterations;
+        if !self.service_role.is_empty() {
+            config.service.role = self.service_role.clone();
+        }
+        config.crud_api.strategy = self.strategy.clone();
+        config.crud_api.health_check_interval = self.health_check_interval;
+        config.crud_api.timeout = self.timeout;
+        config.crud_api.retries = self.retries;
+    }
+}
+
 /// 后端容器模型
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct BackendContainer {
@@ -113,8 +422,51 @@ pub struct BackendContainer {
     pub instance_type: String,
     pub timeout: u64,
     pub retries: u32,
+    /// 创建容器使用的 `docker run` 参数，为空时容器由外部管理
+    #[serde(default)]
+    pub docker_run_params: String,
+    /// 容器所在的Docker主机，为空时使用本机
+    #[serde(default)]
+    pub docker_host_id: Option<String>,
     pub status: ContainerStatus,
     pub health: HealthStatus,
+    /// 从Kubernetes导入时关联的工作负载
+    #[serde(default)]
+    pub kubernetes: Option<KubernetesLink>,
+    /// 负载均衡权重
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+    /// 是否手动固定权重，固定后不参与自适应调整
+    #[serde(default)]
+    pub weight_pinned: bool,
+    /// 后台健康探测设置
+    #[serde(default)]
+    pub health_probe: HealthProbe,
+    /// 容器意外退出后的自动重启策略
+    #[serde(default)]
+    pub restart_policy: AutoRestartPolicy,
+    /// 创建容器时附加的卷与目录挂载，与运行参数中容器路径相同的挂载以此为准
+    #[serde(default)]
+    pub volumes: Vec<VolumeMount>,
+    /// 创建容器时附加的端口映射，与运行参数中容器端口相同的映射以此为准
+    #[serde(default)]
+    pub ports: Vec<PortMapping>,
+    /// 运行的副本数：Kubernetes中为Deployment的副本数；
+    /// Docker中每个副本是单独的容器，第N个副本（从0起）的宿主机端口与地址端口加N，分别注册到中间层的实例列表
+    #[serde(default = "default_replicas")]
+    pub replicas: u32,
+    /// 由发现导入时来源最后一次报告的时间，手动创建或已确认保留的后端为空
+    #[serde(default)]
+    pub last_seen: Option<DateTime<Utc>>,
+    /// 只能经跳板机访问时使用的SSH隧道
+    #[serde(default)]
+    pub tunnel: Option<SshTunnel>,
+    /// 容器的CPU与内存上限，与运行参数中的 `--cpus`、`--memory` 同时存在时以此为准
+    #[serde(default)]
+    pub limits: ResourceLimits,
+    /// 启动业务组时须先于本容器启动的同组容器ID，已不在组内的ID忽略
+    #[serde(default)]
+    pub depends_on: Vec<String>,
 }
 
 impl Default for BackendContainer {
@@ -126,9 +478,103 @@ impl Default for BackendContainer {
             instance_type: "mixed".to_string(),
             timeout: 5000,
             retries: 3,
+            docker_run_params: String::new(),
+            docker_host_id: None,
             status: ContainerStatus::Stopped,
             health: HealthStatus::Unknown,
+            kubernetes: None,
+            weight: default_weight(),
+            weight_pinned: false,
+            health_probe: HealthProbe::default(),
+            restart_policy: AutoRestartPolicy::default(),
+            volumes: Vec::new(),
+            ports: Vec::new(),
+            replicas: default_replicas(),
+            last_seen: None,
+            tunnel: None,
+            limits: ResourceLimits::default(),
+            depends_on: Vec::new(),
+        }
+    }
+}
+
+impl BackendContainer {
+    /// 复制为新实体：重新生成ID并重置运行状态
+    pub fn duplicate(mut self) -> Self {
+        self.id = Uuid::new_v4().to_string();
+        self.status = ContainerStatus::Stopped;
+        self.health = HealthStatus::Unknown;
+        self.kubernetes = None;
+        self.last_seen = None;
+        self
+    }
+
+    /// 是否处于故障状态（运行出错或健康检查失败）
+    pub fn is_unhealthy(&self) -> bool {
+        self.status == ContainerStatus::Error || self.health == HealthStatus::Unhealthy
+    }
+
+    /// 创建容器使用的定义：运行参数合并单独配置的端口、挂载与资源上限
+    pub fn container_spec(&self) -> anyhow::Result<ContainerSpec> {
+        container_spec(&self.docker_run_params, &self.ports, &self.volumes, &self.limits)
+    }
+
+    /// 克隆为新后端：重新生成ID并重置运行状态，地址端口与已映射的宿主机端口加 `offset`，
+    /// 运行参数中指定了容器名称时改为 `container_name`
+    pub fn clone_as(&self, name: &str, container_name: &str, offset: u16) -> anyhow::Result<Self> {
+        let mut clone = self.clone().duplicate();
+        clone.name = name.to_string();
+        clone.url = self.replica_url(offset as u32);
+        for port in clone.ports.iter_mut().filter(|p| p.host_port != 0) {
+            port.host_port = port.host_port.saturating_add(offset);
+        }
+        if !self.docker_run_params.trim().is_empty() {
+            let mut spec = ContainerSpec::parse(&self.docker_run_params)?;
+            spec.ports = spec.replica_ports(offset as u32);
+            if !spec.name.is_empty() {
+                spec.name = container_name.to_string();
+            }
+            clone.docker_run_params = spec.to_params();
+        }
+        Ok(clone)
+    }
+
+    /// 第 `index` 个副本（从0起）注册到中间层的实例ID，第0个副本沿用后端ID
+    pub fn replica_id(&self, index: u32) -> String {
+        match index {
+            0 => self.id.clone(),
+            _ => format!("{}-{}", self.id, index + 1),
+        }
+    }
+
+    /// 第 `index` 个副本（从0起）的访问地址：端口加 `index`，地址无法解析时沿用原地址
+    pub fn replica_url(&self, index: u32) -> String {
+        if index == 0 {
+            return self.url.clone();
+        }
+        let Ok(mut url) = reqwest::Url::parse(&self.url) else {
+            return self.url.clone();
+        };
+        let port = url.port_or_known_default().unwrap_or(80).saturating_add(index as u16);
+        if url.set_port(Some(port)).is_err() {
+            return self.url.clone();
         }
+        let mut replica = url.to_string();
+        // 原地址没有路径时不添加结尾的斜杠
+        if !self.url.ends_with('/') && url.path() == "/" {
+            replica.pop();
+        }
+        replica
+    }
+
+    /// 部署时注入的环境变量
+    pub fn environment(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("LISTEN_URL", self.url.clone()),
+            ("INSTANCE_TYPE", self.instance_type.clone()),
+            ("TIMEOUT", self.timeout.to_string()),
+            ("RETRIES", self.retries.to_string()),
+        ]
     }
 }
 
@@ -139,106 +585,3770 @@ pub struct MiddlewareContainer {
     pub name: String,
     pub url: String,
     pub docker_run_params: String,
+    /// 容器所在的Docker主机，为空时使用本机
+    #[serde(default)]
+    pub docker_host_id: Option<String>,
     pub config: AppConfig,
     pub backend_containers: Vec<BackendContainer>,
     pub status: ContainerStatus,
     pub health: HealthStatus,
     pub logs: Vec<String>,
     pub agent_installed: bool,
+    /// Agent最近一次检查的结果，未检查时为空
+    #[serde(default)]
+    pub agent_health: Option<HealthStatus>,
+    /// 后台健康探测设置
+    #[serde(default, alias = "polling")]
+    pub health_probe: HealthProbe,
+    /// 启动后的预热检查设置
+    #[serde(default)]
+    pub warmup: WarmupConfig,
+    /// 从Kubernetes导入时关联的工作负载
+    #[serde(default)]
+    pub kubernetes: Option<KubernetesLink>,
+    /// 负载均衡模式下按延迟自动调整后端权重的设置
+    #[serde(default)]
+    pub adaptive_weights: AdaptiveWeightConfig,
+    /// 容器意外退出后的自动重启策略
+    #[serde(default)]
+    pub restart_policy: AutoRestartPolicy,
+    /// 创建容器时附加的卷与目录挂载，与运行参数中容器路径相同的挂载以此为准
+    #[serde(default)]
+    pub volumes: Vec<VolumeMount>,
+    /// 创建容器时附加的端口映射，与运行参数中容器端口相同的映射以此为准
+    #[serde(default)]
+    pub ports: Vec<PortMapping>,
+    /// 中间层及其下属后端使用的容器运行时
+    #[serde(default)]
+    pub runtime: RuntimeKind,
+    /// 部署到Kubernetes时的副本数
+    #[serde(default = "default_replicas")]
+    pub replicas: u32,
+    /// 只能经跳板机访问时使用的SSH隧道
+    #[serde(default)]
+    pub tunnel: Option<SshTunnel>,
+    /// 容器的CPU与内存上限，与运行参数中的 `--cpus`、`--memory` 同时存在时以此为准
+    #[serde(default)]
+    pub limits: ResourceLimits,
+    /// 启动业务组时须先于本容器启动的同组容器ID，已不在组内的ID忽略
+    #[serde(default)]
+    pub depends_on: Vec<String>,
 }
 
-impl Default for MiddlewareContainer {
+/// 容器运行时类型
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
+pub enum RuntimeKind {
+    /// 由Docker创建容器
+    #[default]
+    Docker,
+    /// 在Kubernetes命名空间中部署为Deployment
+    Kubernetes { namespace: String },
+}
+
+impl RuntimeKind {
+    /// 显示名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            RuntimeKind::Docker => "Docker",
+            RuntimeKind::Kubernetes { .. } => "Kubernetes",
+        }
+    }
+}
+
+/// 容器退出后由管理器执行的自动重启方式
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AutoRestartMode {
+    #[default]
+    Never,
+    /// 仅在非零退出或异常终止时重启
+    OnFailure,
+    /// 任何退出都重启
+    Always,
+}
+
+impl AutoRestartMode {
+    pub const ALL: [AutoRestartMode; 3] = [AutoRestartMode::Never, AutoRestartMode::OnFailure, AutoRestartMode::Always];
+
+    /// 显示名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            AutoRestartMode::Never => "不重启",
+            AutoRestartMode::OnFailure => "失败时重启",
+            AutoRestartMode::Always => "总是重启",
+        }
+    }
+}
+
+/// 自动重启策略，由管理器监视容器退出并重新启动，与Docker自身的重启策略相互独立
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct AutoRestartPolicy {
+    pub mode: AutoRestartMode,
+    /// 连续重启次数上限，0表示不限
+    pub max_retries: u32,
+    /// 首次重启前等待秒数，之后每次翻倍
+    pub backoff_secs: u64,
+    /// 等待秒数上限
+    pub max_backoff_secs: u64,
+}
+
+impl Default for AutoRestartPolicy {
     fn default() -> Self {
-        let default_config = AppConfig {
-            server: ServerConfig {
-                host: "0.0.0.0".to_string(),
-                port: 9999,
-                https: false,
-            },
-            jwt: JwtConfig {
-                secret: "default_jwt_secret_123456".to_string(),
-                expires_in: 3600,
-                refresh_in: 86400,
-            },
-            encryption: EncryptionConfig {
-                algorithm: "aes-256-gcm".to_string(),
-                key_length: 32,
-                iterations: 100000,
-                salt: "default_salt".to_string(),
-            },
-            service: ServiceRoleConfig {
-                role: "mixed".to_string(),
-                id: "encryption-01".to_string(),
-            },
-            crud_api: CrudApiConfig {
-                instances: Vec::new(),
-                strategy: SchedulerStrategy::ReadWriteSplit,
-                health_check_interval: 30,
-                timeout: 5000,
-                retries: 3,
-            },
-        };
-        
         Self {
-            id: Uuid::new_v4().to_string(),
-            name: "新中间层容器".to_string(),
-            url: "http://localhost:9999".to_string(),
-            docker_run_params: "".to_string(),
-            config: default_config,
-            backend_containers: Vec::new(),
-            status: ContainerStatus::Stopped,
-            health: HealthStatus::Unknown,
-            logs: Vec::new(),
-            agent_installed: false,
+            mode: AutoRestartMode::Never,
+            max_retries: 5,
+            backoff_secs: 5,
+            max_backoff_secs: 300,
         }
     }
 }
 
-/// 业务组模型
+impl AutoRestartPolicy {
+    /// 按退出方式判断是否需要重启
+    pub fn applies(&self, failed: bool) -> bool {
+        match self.mode {
+            AutoRestartMode::Never => false,
+            AutoRestartMode::OnFailure => failed,
+            AutoRestartMode::Always => true,
+        }
+    }
+
+    /// 第几次重启（从0开始）前的等待秒数
+    pub fn delay_secs(&self, attempt: u32) -> u64 {
+        self.backoff_secs
+            .saturating_mul(1u64 << attempt.min(20))
+            .min(self.max_backoff_secs.max(self.backoff_secs))
+    }
+}
+
+/// 自适应权重设置
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct AdaptiveWeightConfig {
+    pub enabled: bool,
+    /// 调整间隔（秒）
+    pub interval_secs: u64,
+    /// 每次调整对每个后端的探测次数
+    pub probes: u32,
+}
+
+impl Default for AdaptiveWeightConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_secs: 60,
+            probes: 3,
+        }
+    }
+}
+
+/// 一次权重调整中单个后端的测量结果
 #[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct BusinessGroup {
+pub struct WeightEntry {
+    pub backend_id: String,
+    pub backend_name: String,
+    /// 成功探测的平均延迟（毫秒），全部失败时为空
+    pub latency_ms: Option<f64>,
+    pub error_rate: f64,
+    pub weight: u32,
+    pub pinned: bool,
+}
+
+/// 权重调整记录
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WeightAdjustment {
     pub id: String,
-    pub name: String,
-    pub description: String,
-    pub middlewares: Vec<MiddlewareContainer>,
-    pub backend_containers: Vec<BackendContainer>,
-    pub status: GroupStatus,
-    pub created_at: DateTime<Utc>,
-    pub updated_at: DateTime<Utc>,
+    pub middleware_id: String,
+    pub middleware_name: String,
+    pub timestamp: DateTime<Utc>,
+    pub entries: Vec<WeightEntry>,
+    /// 是否由操作人手动触发
+    pub manual: bool,
+    /// 推送失败的原因
+    pub error: Option<String>,
+    /// 手动触发时生成的追踪ID
+    #[serde(default)]
+    pub trace_id: Option<String>,
 }
 
-impl Default for BusinessGroup {
+/// 经SSH跳板机访问实体：在本机建立到实体地址的端口转发，健康检查与接口请求改经本机端口
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct SshTunnel {
+    /// 跳板机，`用户@主机` 或ssh配置中的主机别名
+    pub bastion: String,
+    /// 跳板机的SSH端口
+    pub port: u16,
+    /// 私钥路径，为空时使用ssh的默认配置
+    #[serde(default)]
+    pub ssh_key: String,
+    /// 本机监听端口，0表示自动选择
+    #[serde(default)]
+    pub local_port: u16,
+}
+
+impl Default for SshTunnel {
     fn default() -> Self {
-        let now = Utc::now();
+        Self {
+            bastion: String::new(),
+            port: 22,
+            ssh_key: String::new(),
+            local_port: 0,
+        }
+    }
+}
+
+/// 与Kubernetes工作负载的关联，状态随集群同步
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct KubernetesLink {
+    pub namespace: String,
+    pub deployment: String,
+}
+
+/// Docker主机连接方式
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DockerConnection {
+    /// 本机的Unix套接字或Windows命名管道
+    #[default]
+    Local,
+    /// tcp://主机:端口
+    Tcp,
+    /// ssh://用户@主机
+    Ssh,
+}
+
+impl DockerConnection {
+    /// 显示名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            DockerConnection::Local => "本机",
+            DockerConnection::Tcp => "TCP",
+            DockerConnection::Ssh => "SSH",
+        }
+    }
+}
+
+/// 主机上运行的容器引擎，两者都通过Docker兼容的API管理
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ContainerEngine {
+    #[default]
+    Docker,
+    /// 通过 `podman system service` 提供的兼容API管理，支持rootless模式
+    Podman,
+}
+
+impl ContainerEngine {
+    /// 所有容器引擎
+    pub const ALL: [ContainerEngine; 2] = [ContainerEngine::Docker, ContainerEngine::Podman];
+
+    /// 显示名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            ContainerEngine::Docker => "Docker",
+            ContainerEngine::Podman => "Podman",
+        }
+    }
+
+    /// 该引擎支持的连接方式，Podman不提供SSH所需的 `docker system dial-stdio`
+    pub fn connections(&self) -> &'static [DockerConnection] {
+        match self {
+            ContainerEngine::Docker => &[DockerConnection::Tcp, DockerConnection::Ssh],
+            ContainerEngine::Podman => &[DockerConnection::Local, DockerConnection::Tcp],
+        }
+    }
+}
+
+/// 本机Podman的默认API套接字：rootless时位于 `$XDG_RUNTIME_DIR/podman/podman.sock`
+fn podman_socket() -> String {
+    if cfg!(windows) {
+        return "npipe:////./pipe/podman-machine-default".to_string();
+    }
+    match std::env::var("XDG_RUNTIME_DIR") {
+        Ok(dir) if !dir.is_empty() => format!("unix://{}/podman/podman.sock", dir.trim_end_matches('/')),
+        _ => "unix:///run/podman/podman.sock".to_string(),
+    }
+}
+
+/// 可管理容器的Docker或Podman主机
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct DockerHost {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub engine: ContainerEngine,
+    pub connection: DockerConnection,
+    /// TCP为 `主机:端口`，SSH为 `用户@主机[:端口]`；本机Podman为API套接字路径，为空时使用默认套接字
+    pub address: String,
+    /// SSH私钥路径，为空时使用默认密钥
+    #[serde(default)]
+    pub ssh_key: String,
+    /// 主机的月费用，大于0时由主机上的容器平均分摊，取代按容器计费
+    #[serde(default)]
+    pub monthly_cost: f64,
+}
+
+impl DockerHost {
+    /// 创建主机
+    pub fn new(name: &str, connection: DockerConnection, address: &str) -> Self {
         Self {
             id: Uuid::new_v4().to_string(),
-            name: "新业务组".to_string(),
-            description: "".to_string(),
-            middlewares: Vec::new(),
-            backend_containers: Vec::new(),
-            status: GroupStatus::Stopped,
-            created_at: now,
-            updated_at: now,
+            name: name.to_string(),
+            engine: ContainerEngine::Docker,
+            connection,
+            address: address.to_string(),
+            ssh_key: String::new(),
+            monthly_cost: 0.0,
+        }
+    }
+
+    /// 连接地址
+    pub fn url(&self) -> String {
+        let address = self.address.trim();
+        match self.connection {
+            DockerConnection::Local if self.engine == ContainerEngine::Podman => {
+                if address.is_empty() {
+                    podman_socket()
+                } else if address.contains("://") {
+                    address.to_string()
+                } else {
+                    format!("unix://{}", address)
+                }
+            }
+            DockerConnection::Local => "本机".to_string(),
+            DockerConnection::Tcp => format!("tcp://{}", address.trim_start_matches("tcp://")),
+            DockerConnection::Ssh => format!("ssh://{}", address.trim_start_matches("ssh://")),
         }
     }
 }
 
-/// 应用状态模型
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct AppState {
-    pub business_groups: Vec<BusinessGroup>,
-    pub selected_group_id: Option<String>,
-    pub selected_middleware_id: Option<String>,
-    pub selected_backend_id: Option<String>,
+/// 成本估算设置：未部署在有月费用的主机上的容器按角色计费，多副本按副本数计
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CostSettings {
+    /// 货币符号
+    pub currency: String,
+    /// 每个中间层实例的月费用
+    pub middleware_monthly: f64,
+    /// 每个后端实例的月费用
+    pub backend_monthly: f64,
 }
 
-impl Default for AppState {
+impl Default for CostSettings {
     fn default() -> Self {
         Self {
-            business_groups: Vec::new(),
-            selected_group_id: None,
-            selected_middleware_id: None,
-            selected_backend_id: None,
+            currency: "¥".to_string(),
+            middleware_monthly: 0.0,
+            backend_monthly: 0.0,
+        }
+    }
+}
+
+impl CostSettings {
+    /// 格式化金额，如 `¥1,234.50`
+    pub fn format(&self, amount: f64) -> String {
+        let sign = if amount < 0.0 { "-" } else { "" };
+        let cents = (amount.abs() * 100.0).round() as u64;
+        let digits = (cents / 100).to_string();
+        let mut grouped = String::new();
+        for (i, c) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                grouped.push(',');
+            }
+            grouped.push(c);
+        }
+        format!("{}{}{}.{:02}", sign, self.currency, grouped, cents % 100)
+    }
+}
+
+/// 主机名解析覆盖
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
+pub struct DnsOverride {
+    pub host: String,
+    /// 解析到的IP地址
+    pub address: String,
+}
+
+/// 业务组专用的Docker桥接网络
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct GroupDockerNetwork {
+    pub name: String,
+    /// 子网（CIDR），为空时由Docker分配
+    #[serde(default)]
+    pub subnet: String,
+}
+
+impl GroupDockerNetwork {
+    /// 按业务组ID生成默认网络
+    pub fn for_group(group_id: &str) -> Self {
+        Self {
+            name: format!("encryption-service-net-{}", group_id.get(..8).unwrap_or(group_id)),
+            subnet: String::new(),
+        }
+    }
+
+    /// 校验网络名称与子网格式
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let name = self.name.trim();
+        if name.is_empty() {
+            anyhow::bail!("网络名称不能为空");
         }
+        if !name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-')) {
+            anyhow::bail!("网络名称只能包含字母、数字、下划线、点和连字符: {}", name);
+        }
+        let subnet = self.subnet.trim();
+        if subnet.is_empty() {
+            return Ok(());
+        }
+        let (address, prefix) = subnet
+            .split_once('/')
+            .ok_or_else(|| anyhow::anyhow!("子网应为CIDR格式，例如 172.30.0.0/24: {}", subnet))?;
+        let address: std::net::IpAddr = address
+            .parse()
+            .map_err(|_| anyhow::anyhow!("子网地址无效: {}", subnet))?;
+        let max_prefix = if address.is_ipv4() { 32 } else { 128 };
+        match prefix.parse::<u8>() {
+            Ok(prefix) if prefix <= max_prefix => Ok(()),
+            _ => anyhow::bail!("子网前缀长度无效: {}", subnet),
+        }
+    }
+}
+
+/// 一组网络配置，例如生产与实验环境分别位于不同的代理与CA之后
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct NetworkProfile {
+    pub id: String,
+    pub name: String,
+    /// 代理地址，如 `http://proxy:3128`，为空时不使用代理
+    #[serde(default)]
+    pub proxy: String,
+    /// 不经代理的主机，逗号分隔
+    #[serde(default)]
+    pub no_proxy: String,
+    #[serde(default)]
+    pub dns_overrides: Vec<DnsOverride>,
+    /// 额外信任的CA证书（PEM）路径
+    #[serde(default)]
+    pub ca_cert_path: String,
+    /// 是否跳过证书校验，仅用于实验环境
+    #[serde(default)]
+    pub accept_invalid_certs: bool,
+    /// 建立连接的超时毫秒数，0表示不单独限制
+    #[serde(default)]
+    pub connect_timeout_ms: u64,
+    /// 请求超时毫秒数，非0时覆盖各容器配置的超时
+    #[serde(default)]
+    pub request_timeout_ms: u64,
+}
+
+impl NetworkProfile {
+    /// 创建不含任何设置的网络配置
+    pub fn new(name: &str) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            name: name.to_string(),
+            proxy: String::new(),
+            no_proxy: String::new(),
+            dns_overrides: Vec::new(),
+            ca_cert_path: String::new(),
+            accept_invalid_certs: false,
+            connect_timeout_ms: 0,
+            request_timeout_ms: 0,
+        }
+    }
+
+    /// 解析hosts文件格式的文本，每行为IP地址后跟一个或多个主机名，`#`之后为注释；
+    /// 与hosts文件一样，同一主机名出现多次时以第一次为准
+    pub fn parse_hosts(text: &str) -> anyhow::Result<Vec<DnsOverride>> {
+        let mut entries: Vec<DnsOverride> = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default();
+            let mut fields = line.split_whitespace();
+            let Some(address) = fields.next() else {
+                continue;
+            };
+            if address.parse::<std::net::IpAddr>().is_err() {
+                anyhow::bail!("第 {} 行的IP地址无效: {}", index + 1, address);
+            }
+            let hosts: Vec<&str> = fields.collect();
+            if hosts.is_empty() {
+                anyhow::bail!("第 {} 行缺少主机名", index + 1);
+            }
+            for host in hosts {
+                let host = host.to_ascii_lowercase();
+                if !entries.iter().any(|e| e.host == host) {
+                    entries.push(DnsOverride { host, address: address.to_string() });
+                }
+            }
+        }
+        Ok(entries)
+    }
+
+    /// 合并解析覆盖，主机名已存在时替换其地址，返回新增的条数
+    pub fn merge_hosts(&mut self, entries: Vec<DnsOverride>) -> usize {
+        let mut added = 0;
+        for entry in entries {
+            match self.dns_overrides.iter_mut().find(|d| d.host.trim().eq_ignore_ascii_case(&entry.host)) {
+                Some(existing) => existing.address = entry.address,
+                None => {
+                    self.dns_overrides.push(entry);
+                    added += 1;
+                }
+            }
+        }
+        added
+    }
+
+    /// 以hosts文件格式输出解析覆盖
+    pub fn hosts_text(&self) -> String {
+        self.dns_overrides
+            .iter()
+            .map(|d| format!("{}\t{}\n", d.address.trim(), d.host.trim()))
+            .collect()
+    }
+
+    /// 查找主机名的解析覆盖
+    pub fn resolve(&self, host: &str) -> Option<&str> {
+        self.dns_overrides
+            .iter()
+            .find(|d| d.host.trim().eq_ignore_ascii_case(host))
+            .map(|d| d.address.trim())
+    }
+}
+
+/// 接口客户端的HTTP协议版本偏好
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HttpVersionPreference {
+    /// HTTPS时经ALPN协商，明文时使用HTTP/1.1
+    #[default]
+    Auto,
+    Http1Only,
+    /// 不经协商直接使用HTTP/2，服务端须支持明文HTTP/2
+    Http2PriorKnowledge,
+}
+
+impl HttpVersionPreference {
+    pub const ALL: [HttpVersionPreference; 3] = [
+        HttpVersionPreference::Auto,
+        HttpVersionPreference::Http1Only,
+        HttpVersionPreference::Http2PriorKnowledge,
+    ];
+
+    /// 显示名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            HttpVersionPreference::Auto => "自动协商",
+            HttpVersionPreference::Http1Only => "仅HTTP/1.1",
+            HttpVersionPreference::Http2PriorKnowledge => "HTTP/2（直连）",
+        }
+    }
+}
+
+/// 接口客户端的连接池与保活设置，对所有中间层与后端生效
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct ConnectionPoolSettings {
+    /// 每个主机保留的空闲连接上限，0表示不限
+    #[serde(default)]
+    pub max_idle_per_host: usize,
+    /// 空闲连接的回收秒数，0表示不回收
+    #[serde(default)]
+    pub idle_timeout_secs: u64,
+    /// TCP保活探测间隔秒数，0表示关闭
+    #[serde(default)]
+    pub tcp_keepalive_secs: u64,
+    #[serde(default)]
+    pub tcp_nodelay: bool,
+    #[serde(default)]
+    pub http_version: HttpVersionPreference,
+    /// HTTP/2按带宽时延积自动调整流控窗口
+    #[serde(default)]
+    pub http2_adaptive_window: bool,
+}
+
+impl Default for ConnectionPoolSettings {
+    fn default() -> Self {
+        Self {
+            max_idle_per_host: 0,
+            idle_timeout_secs: 90,
+            tcp_keepalive_secs: 0,
+            tcp_nodelay: true,
+            http_version: HttpVersionPreference::Auto,
+            http2_adaptive_window: false,
+        }
+    }
+}
+
+/// 管理器自身链路（接口调用、健康检查、任务执行）的OTLP导出设置
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct OtlpSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// 收集器的OTLP/HTTP地址，例如 http://localhost:4318
+    pub endpoint: String,
+    pub service_name: String,
+    /// 随导出请求发送的请求头，例如收集器的认证信息
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+}
+
+impl Default for OtlpSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: "http://localhost:4318".to_string(),
+            service_name: "encryption-service-ui".to_string(),
+            headers: Vec::new(),
+        }
+    }
+}
+
+impl OtlpSettings {
+    /// 链路数据的导出地址，地址未带路径时补全 `/v1/traces`
+    pub fn traces_url(&self) -> String {
+        let endpoint = self.endpoint.trim().trim_end_matches('/');
+        if endpoint.ends_with("/v1/traces") {
+            endpoint.to_string()
+        } else {
+            format!("{}/v1/traces", endpoint)
+        }
+    }
+}
+
+/// 容器重启策略
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ContainerRestartPolicy {
+    #[default]
+    No,
+    Always,
+    UnlessStopped,
+    OnFailure,
+}
+
+impl ContainerRestartPolicy {
+    /// 所有重启策略
+    pub const ALL: [ContainerRestartPolicy; 4] = [
+        ContainerRestartPolicy::No,
+        ContainerRestartPolicy::Always,
+        ContainerRestartPolicy::UnlessStopped,
+        ContainerRestartPolicy::OnFailure,
+    ];
+
+    /// `--restart` 的取值
+    pub fn as_arg(&self) -> &'static str {
+        match self {
+            ContainerRestartPolicy::No => "no",
+            ContainerRestartPolicy::Always => "always",
+            ContainerRestartPolicy::UnlessStopped => "unless-stopped",
+            ContainerRestartPolicy::OnFailure => "on-failure",
+        }
+    }
+
+    /// 显示名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            ContainerRestartPolicy::No => "不重启",
+            ContainerRestartPolicy::Always => "总是重启",
+            ContainerRestartPolicy::UnlessStopped => "除非手动停止",
+            ContainerRestartPolicy::OnFailure => "失败时重启",
+        }
+    }
+}
+
+/// 端口映射
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct PortMapping {
+    /// 绑定的宿主机地址，为空时绑定所有地址
+    pub host_ip: String,
+    pub host_port: u16,
+    pub container_port: u16,
+    /// tcp 或 udp
+    pub protocol: String,
+}
+
+impl Default for PortMapping {
+    fn default() -> Self {
+        Self {
+            host_ip: String::new(),
+            host_port: 8000,
+            container_port: 8000,
+            protocol: "tcp".to_string(),
+        }
+    }
+}
+
+/// 环境变量
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
+pub struct EnvVar {
+    pub key: String,
+    pub value: String,
+}
+
+/// 卷挂载
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
+pub struct VolumeMount {
+    /// 宿主机路径或卷名
+    pub source: String,
+    /// 容器内路径
+    pub target: String,
+    pub read_only: bool,
+}
+
+/// 由 `docker run` 参数解析出的结构化容器定义，可与参数字符串互相转换
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
+pub struct ContainerSpec {
+    /// 容器名称，为空时按模型ID生成
+    pub name: String,
+    pub image: String,
+    /// 镜像之后的启动命令
+    pub command: Vec<String>,
+    pub ports: Vec<PortMapping>,
+    pub env: Vec<EnvVar>,
+    pub volumes: Vec<VolumeMount>,
+    /// 网络名称，为空时使用默认网络
+    pub network: String,
+    pub restart: ContainerRestartPolicy,
+    /// CPU上限（纳核，1核为10^9）
+    #[serde(default)]
+    pub nano_cpus: Option<u64>,
+    /// 内存上限（字节）
+    #[serde(default)]
+    pub memory: Option<u64>,
+}
+
+impl ContainerSpec {
+    /// 第 `index` 个副本（从0起）的容器定义：名称加序号后缀，已映射的宿主机端口加 `index`，第0个副本即原容器
+    pub fn replica(&self, name: &str, index: u32) -> ContainerSpec {
+        let mut spec = self.clone();
+        if index == 0 {
+            return spec;
+        }
+        spec.name = format!("{}-{}", name, index + 1);
+        spec.ports = self.replica_ports(index);
+        spec
+    }
+
+    /// 第 `index` 个副本（从0起）的端口映射，未指定宿主机端口的映射不变
+    pub fn replica_ports(&self, index: u32) -> Vec<PortMapping> {
+        let mut ports = self.ports.clone();
+        for port in ports.iter_mut().filter(|p| p.host_port != 0) {
+            port.host_port = port.host_port.saturating_add(index as u16);
+        }
+        ports
+    }
+
+    /// 解析 `docker run` 参数，可省略开头的 `docker run`，镜像之后的内容作为启动命令
+    pub fn parse(params: &str) -> anyhow::Result<Self> {
+        let words = shlex::split(params).ok_or_else(|| anyhow::anyhow!("Docker运行参数中的引号不匹配"))?;
+        let mut words = words.into_iter().peekable();
+        if words.peek().is_some_and(|w| w == "docker") {
+            words.next();
+        }
+        if words.peek().is_some_and(|w| w == "run") {
+            words.next();
+        }
+
+        let mut spec = ContainerSpec::default();
+        while let Some(word) = words.next() {
+            if !word.starts_with('-') {
+                spec.image = word;
+                spec.command = words.collect();
+                break;
+            }
+            let (flag, inline) = match word.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value.to_string())),
+                _ => (word.clone(), None),
+            };
+            let mut value = || {
+                inline
+                    .clone()
+                    .or_else(|| words.next())
+                    .ok_or_else(|| anyhow::anyhow!("参数 {} 缺少取值", flag))
+            };
+            match flag.as_str() {
+                "-d" | "--detach" | "--rm" | "-i" | "-t" | "-it" | "--interactive" | "--tty" => {}
+                "--name" => spec.name = value()?,
+                "-e" | "--env" => {
+                    let value = value()?;
+                    let (key, value) = value.split_once('=').unwrap_or((&value, ""));
+                    spec.env.push(EnvVar {
+                        key: key.to_string(),
+                        value: value.to_string(),
+                    });
+                }
+                "-v" | "--volume" => spec.volumes.push(VolumeMount::parse(&value()?)?),
+                "-p" | "--publish" => spec.ports.push(PortMapping::parse(&value()?)?),
+                "--network" | "--net" => spec.network = value()?,
+                "--restart" => {
+                    let value = value()?;
+                    // on-failure:N 的重试次数不保留
+                    let name = value.split(':').next().unwrap_or_default();
+                    spec.restart = ContainerRestartPolicy::ALL
+                        .into_iter()
+                        .find(|p| p.as_arg() == name)
+                        .ok_or_else(|| anyhow::anyhow!("不支持的重启策略: {}", value))?;
+                }
+                "--cpus" => {
+                    let value = value()?;
+                    let cpus: f64 = value.parse().map_err(|_| anyhow::anyhow!("无效的CPU上限: {}", value))?;
+                    if !(cpus > 0.0 && cpus.is_finite()) {
+                        anyhow::bail!("无效的CPU上限: {}", value);
+                    }
+                    spec.nano_cpus = Some((cpus * NANO_CPUS_PER_CPU).round() as u64);
+                }
+                "-m" | "--memory" => spec.memory = Some(parse_memory(&value()?)?),
+                _ => anyhow::bail!("不支持的Docker运行参数: {}", flag),
+            }
+        }
+        if spec.image.is_empty() {
+            anyhow::bail!("Docker运行参数中没有指定镜像");
+        }
+        Ok(spec)
+    }
+
+    /// 合并容器上单独配置的挂载，容器路径相同时替换运行参数中的挂载
+    pub fn merge_volumes(&mut self, volumes: &[VolumeMount]) {
+        for volume in volumes {
+            match self.volumes.iter_mut().find(|v| v.target == volume.target) {
+                Some(existing) => *existing = volume.clone(),
+                None => self.volumes.push(volume.clone()),
+            }
+        }
+    }
+
+    /// 合并容器上单独配置的端口映射，容器端口与协议相同时替换运行参数中的映射
+    pub fn merge_ports(&mut self, ports: &[PortMapping]) {
+        for port in ports {
+            match self.ports.iter_mut().find(|p| p.container_port == port.container_port && p.protocol == port.protocol) {
+                Some(existing) => *existing = port.clone(),
+                None => self.ports.push(port.clone()),
+            }
+        }
+    }
+
+    /// 合并容器上单独配置的资源上限，已设置的一项替换运行参数中的取值
+    pub fn merge_limits(&mut self, limits: &ResourceLimits) {
+        if let Some(cpus) = limits.cpus {
+            self.nano_cpus = Some((cpus * NANO_CPUS_PER_CPU).round() as u64);
+        }
+        if let Some(memory) = limits.memory_mb {
+            self.memory = Some(memory.saturating_mul(BYTES_PER_MB));
+        }
+    }
+
+    /// CPU上限的核数
+    pub fn cpus(&self) -> Option<f64> {
+        self.nano_cpus.map(|n| n as f64 / NANO_CPUS_PER_CPU)
+    }
+
+    /// 转换回 `docker run` 参数（不含开头的 `docker run`）
+    pub fn to_params(&self) -> String {
+        let mut words: Vec<String> = Vec::new();
+        if !self.name.is_empty() {
+            words.extend(["--name".to_string(), self.name.clone()]);
+        }
+        for port in &self.ports {
+            words.extend(["-p".to_string(), port.to_arg()]);
+        }
+        for env in &self.env {
+            words.extend(["-e".to_string(), format!("{}={}", env.key, env.value)]);
+        }
+        for volume in &self.volumes {
+            words.extend(["-v".to_string(), volume.to_arg()]);
+        }
+        if !self.network.is_empty() {
+            words.extend(["--network".to_string(), self.network.clone()]);
+        }
+        if self.restart != ContainerRestartPolicy::No {
+            words.extend(["--restart".to_string(), self.restart.as_arg().to_string()]);
+        }
+        if let Some(cpus) = self.cpus() {
+            words.extend(["--cpus".to_string(), cpus.to_string()]);
+        }
+        if let Some(memory) = self.memory {
+            words.extend(["--memory".to_string(), format_memory(memory)]);
+        }
+        words.push(self.image.clone());
+        words.extend(self.command.iter().cloned());
+        shlex::try_join(words.iter().map(String::as_str)).unwrap_or_else(|_| words.join(" "))
+    }
+}
+
+impl PortMapping {
+    /// 解析 `-p` 的取值：`[IP:]宿主机端口:容器端口[/协议]`，IPv6地址须带方括号
+    pub fn parse(value: &str) -> anyhow::Result<Self> {
+        let invalid = || anyhow::anyhow!("无法解析端口映射: {}", value);
+        let (ports, protocol) = value.split_once('/').unwrap_or((value, "tcp"));
+        let (host_ip, host_port, container_port) = match ports.strip_prefix('[') {
+            Some(rest) => {
+                let (ip, rest) = rest.split_once("]:").ok_or_else(invalid)?;
+                let (host, container) = rest.split_once(':').ok_or_else(invalid)?;
+                (ip, host, container)
+            }
+            None => match ports.split(':').collect::<Vec<_>>().as_slice() {
+                [host, container] => ("", *host, *container),
+                [ip, host, container] => (*ip, *host, *container),
+                _ => return Err(invalid()),
+            },
+        };
+        if protocol.is_empty() {
+            return Err(invalid());
+        }
+        Ok(Self {
+            host_ip: host_ip.to_string(),
+            host_port: host_port.parse().map_err(|_| invalid())?,
+            container_port: container_port.parse().map_err(|_| invalid())?,
+            protocol: protocol.to_string(),
+        })
+    }
+
+    /// 是否与另一映射占用同一宿主机端口；宿主机端口为0时由Docker随机分配，不会冲突
+    pub fn conflicts_with(&self, other: &PortMapping) -> bool {
+        let any_address = |ip: &str| ip.is_empty() || ip == "0.0.0.0" || ip == "::";
+        self.host_port != 0
+            && self.host_port == other.host_port
+            && self.protocol == other.protocol
+            && (any_address(&self.host_ip) || any_address(&other.host_ip) || self.host_ip == other.host_ip)
+    }
+
+    /// `-p` 的取值
+    pub fn to_arg(&self) -> String {
+        let mut arg = format!("{}:{}", self.host_port, self.container_port);
+        if self.host_ip.contains(':') {
+            arg = format!("[{}]:{}", self.host_ip, arg);
+        } else if !self.host_ip.is_empty() {
+            arg = format!("{}:{}", self.host_ip, arg);
+        }
+        if self.protocol != "tcp" {
+            arg = format!("{}/{}", arg, self.protocol);
+        }
+        arg
+    }
+}
+
+impl VolumeMount {
+    /// 解析 `-v` 的取值：`来源:容器路径[:ro|rw]`
+    ///
+    /// 来源可带Windows盘符，如 `C:\data:/data`，来源带盘符时容器路径也可带盘符；
+    /// 单个字母的来源后只有容器路径时按卷名处理，其余含冒号、无法确定如何拆分的取值视为错误
+    pub fn parse(value: &str) -> anyhow::Result<Self> {
+        let invalid = || anyhow::anyhow!("无法解析卷挂载: {}", value);
+        let mut segments: Vec<&str> = value.split(':').collect();
+        let read_only = match segments.last() {
+            Some(&mode @ ("ro" | "rw")) if segments.len() > 2 => {
+                segments.pop();
+                mode == "ro"
+            }
+            _ => false,
+        };
+
+        let mut parts: Vec<String> = Vec::new();
+        let mut index = 0;
+        while index < segments.len() {
+            let segment = segments[index];
+            let is_drive = segment.len() == 1
+                && segment.chars().all(|c| c.is_ascii_alphabetic())
+                && segments.get(index + 1).is_some_and(|next| next.starts_with(['\\', '/']))
+                && match index {
+                    0 => segments.len() > 2 || segments[1].starts_with('\\'),
+                    _ => parts.len() == 1 && parts[0].as_bytes().get(1) == Some(&b':'),
+                };
+            if is_drive {
+                parts.push(format!("{}:{}", segment, segments[index + 1]));
+                index += 2;
+            } else {
+                parts.push(segment.to_string());
+                index += 1;
+            }
+        }
+        match <[String; 2]>::try_from(parts) {
+            Ok([source, target]) if !source.is_empty() && !target.is_empty() => Ok(Self { source, target, read_only }),
+            _ => Err(invalid()),
+        }
+    }
+
+    /// `-v` 的取值
+    pub fn to_arg(&self) -> String {
+        if self.read_only {
+            format!("{}:{}:ro", self.source, self.target)
+        } else {
+            format!("{}:{}", self.source, self.target)
+        }
+    }
+}
+
+/// 解析运行参数并合并单独配置的端口、挂载与资源上限
+fn container_spec(params: &str, ports: &[PortMapping], volumes: &[VolumeMount], limits: &ResourceLimits) -> anyhow::Result<ContainerSpec> {
+    let mut spec = ContainerSpec::parse(params)?;
+    spec.merge_ports(ports);
+    spec.merge_volumes(volumes);
+    spec.merge_limits(limits);
+    Ok(spec)
+}
+
+/// 容器的CPU与内存上限，未设置的一项不限制
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct ResourceLimits {
+    /// 可使用的CPU核数，可为小数，如 `1.5`
+    #[serde(default)]
+    pub cpus: Option<f64>,
+    /// 内存上限（MiB）
+    #[serde(default)]
+    pub memory_mb: Option<u64>,
+}
+
+impl ResourceLimits {
+    /// 是否未设置任何上限
+    pub fn is_empty(&self) -> bool {
+        self.cpus.is_none() && self.memory_mb.is_none()
+    }
+
+    /// 校验上限：CPU核数须大于0，内存不能低于Docker允许的最小值
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if let Some(cpus) = self.cpus
+            && !(cpus > 0.0 && cpus.is_finite())
+        {
+            anyhow::bail!("CPU上限必须大于0: {}", cpus);
+        }
+        if let Some(memory) = self.memory_mb
+            && memory < MIN_MEMORY_MB
+        {
+            anyhow::bail!("内存上限不能低于 {} MiB: {}", MIN_MEMORY_MB, memory);
+        }
+        Ok(())
+    }
+
+    /// 显示文本，如 `1.5核 / 512 MiB`
+    pub fn label(&self) -> String {
+        let cpus = self.cpus.map(|c| format!("{}核", c)).unwrap_or_else(|| "CPU不限".to_string());
+        let memory = self.memory_mb.map(|m| format!("{} MiB", m)).unwrap_or_else(|| "内存不限".to_string());
+        format!("{} / {}", cpus, memory)
+    }
+}
+
+/// Docker允许的最小内存上限（MiB）
+const MIN_MEMORY_MB: u64 = 6;
+/// 每个CPU核对应的纳核数
+pub const NANO_CPUS_PER_CPU: f64 = 1e9;
+/// 每MiB的字节数
+pub const BYTES_PER_MB: u64 = 1024 * 1024;
+
+/// 解析 `--memory` 的取值：数字后可跟单位b、k、m、g（不区分大小写），无单位时为字节
+pub fn parse_memory(value: &str) -> anyhow::Result<u64> {
+    let lower = value.trim().to_ascii_lowercase();
+    let (number, unit) = match lower.find(|c: char| !c.is_ascii_digit()) {
+        Some(i) => lower.split_at(i),
+        None => (lower.as_str(), ""),
+    };
+    let multiplier = match unit.strip_suffix('b').unwrap_or(unit) {
+        "" => 1,
+        "k" => 1024,
+        "m" => BYTES_PER_MB,
+        "g" => 1024 * BYTES_PER_MB,
+        _ => anyhow::bail!("无效的内存大小: {}", value),
+    };
+    let number: u64 = number.parse().map_err(|_| anyhow::anyhow!("无效的内存大小: {}", value))?;
+    number
+        .checked_mul(multiplier)
+        .ok_or_else(|| anyhow::anyhow!("内存大小超出范围: {}", value))
+}
+
+/// 内存字节数转换为 `--memory` 的取值，能整除时使用较大的单位
+pub fn format_memory(bytes: u64) -> String {
+    const UNITS: [(u64, &str); 3] = [(1024 * BYTES_PER_MB, "g"), (BYTES_PER_MB, "m"), (1024, "k")];
+    UNITS
+        .iter()
+        .find(|(size, _)| bytes != 0 && bytes.is_multiple_of(*size))
+        .map(|(size, unit)| format!("{}{}", bytes / size, unit))
+        .unwrap_or_else(|| bytes.to_string())
+}
+
+/// 校验端口映射列表：容器端口不能为0，协议须为tcp或udp，宿主机端口不能重复
+pub fn validate_ports(ports: &[PortMapping]) -> anyhow::Result<()> {
+    for (index, port) in ports.iter().enumerate() {
+        if port.container_port == 0 {
+            anyhow::bail!("容器端口不能为0");
+        }
+        if port.protocol != "tcp" && port.protocol != "udp" {
+            anyhow::bail!("协议必须为tcp或udp: {}", port.protocol);
+        }
+        if ports[..index].iter().any(|p| p.conflicts_with(port)) {
+            anyhow::bail!("宿主机端口重复: {}", port.to_arg());
+        }
+    }
+    Ok(())
+}
+
+/// 校验挂载列表：来源与容器路径不能为空，容器路径须为绝对路径且不能重复
+pub fn validate_volumes(volumes: &[VolumeMount]) -> anyhow::Result<()> {
+    for (index, volume) in volumes.iter().enumerate() {
+        if volume.source.trim().is_empty() || volume.target.trim().is_empty() {
+            anyhow::bail!("卷挂载的来源和容器路径不能为空");
+        }
+        if !volume.target.trim().starts_with('/') {
+            anyhow::bail!("容器路径必须为绝对路径: {}", volume.target);
+        }
+        if volumes[..index].iter().any(|v| v.target.trim() == volume.target.trim()) {
+            anyhow::bail!("容器路径重复: {}", volume.target);
+        }
+    }
+    Ok(())
+}
+
+/// 启动预热检查设置
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct WarmupConfig {
+    /// 需要连续健康的探测次数
+    pub required_successes: u32,
+    /// 探测间隔（毫秒）
+    pub probe_interval_ms: u64,
+    /// 预热超时（秒）
+    pub timeout_secs: u64,
+    /// 是否执行加密解密往返测试
+    pub round_trip: bool,
+}
+
+impl Default for WarmupConfig {
+    fn default() -> Self {
+        Self {
+            required_successes: 3,
+            probe_interval_ms: 1000,
+            timeout_secs: 60,
+            round_trip: false,
+        }
+    }
+}
+
+/// 健康探测方式
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProbeKind {
+    /// 请求HTTP路径，返回2xx视为健康
+    #[default]
+    Http,
+    /// 能建立TCP连接视为健康
+    Tcp,
+    /// 在容器内执行命令，退出码为0视为健康
+    Exec,
+}
+
+impl ProbeKind {
+    pub const ALL: [ProbeKind; 3] = [ProbeKind::Http, ProbeKind::Tcp, ProbeKind::Exec];
+
+    /// 显示名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            ProbeKind::Http => "HTTP",
+            ProbeKind::Tcp => "TCP",
+            ProbeKind::Exec => "执行命令",
+        }
+    }
+}
+
+/// 容器后台健康探测设置，兼容旧版只有开关与间隔的轮询设置
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct HealthProbe {
+    pub enabled: bool,
+    pub kind: ProbeKind,
+    /// HTTP探测请求的路径
+    pub path: String,
+    /// 在容器内通过 `sh -c` 执行的探测命令
+    pub command: String,
+    /// 探测间隔（秒）
+    pub interval_secs: u64,
+    /// 单次探测超时（毫秒）
+    pub timeout_ms: u64,
+    /// 连续失败达到该次数才判定为不健康
+    pub failure_threshold: u32,
+}
+
+impl Default for HealthProbe {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            kind: ProbeKind::Http,
+            path: "/health".to_string(),
+            command: String::new(),
+            interval_secs: 30,
+            timeout_ms: 5000,
+            failure_threshold: 3,
+        }
+    }
+}
+
+impl Default for MiddlewareContainer {
+    fn default() -> Self {
+        let default_config = AppConfig {
+            server: ServerConfig {
+                host: "0.0.0.0".to_string(),
+                port: 9999,
+                https: false,
+            },
+            jwt: JwtConfig {
+                secret: "default_jwt_secret_123456".to_string(),
+                expires_in: 3600,
+                refresh_in: 86400,
+            },
+            encryption: EncryptionConfig {
+                algorithm: "aes-256-gcm".to_string(),
+                key_length: 32,
+                iterations: 100000,
+                salt: "default_salt".to_string(),
+            },
+            service: ServiceRoleConfig {
+                role: "mixed".to_string(),
+                id: "encryption-01".to_string(),
+            },
+            crud_api: CrudApiConfig {
+                instances: Vec::new(),
+                strategy: SchedulerStrategy::ReadWriteSplit,
+                health_check_interval: 30,
+                timeout: 5000,
+                retries: 3,
+            },
+        };
+        
+        Self {
+            id: Uuid::new_v4().to_string(),
+            name: "新中间层容器".to_string(),
+            url: "http://localhost:9999".to_string(),
+            docker_run_params: "".to_string(),
+            docker_host_id: None,
+            config: default_config,
+            backend_containers: Vec::new(),
+            status: ContainerStatus::Stopped,
+            health: HealthStatus::Unknown,
+            logs: Vec::new(),
+            agent_installed: false,
+            agent_health: None,
+            health_probe: HealthProbe::default(),
+            warmup: WarmupConfig::default(),
+            kubernetes: None,
+            adaptive_weights: AdaptiveWeightConfig::default(),
+            restart_policy: AutoRestartPolicy::default(),
+            volumes: Vec::new(),
+            ports: Vec::new(),
+            runtime: RuntimeKind::default(),
+            replicas: default_replicas(),
+            tunnel: None,
+            limits: ResourceLimits::default(),
+            depends_on: Vec::new(),
+        }
+    }
+}
+
+/// 按旧ID到新ID的映射改写依赖列表
+fn remap(depends_on: &mut [String], ids: &HashMap<String, String>) {
+    for id in depends_on {
+        if let Some(new_id) = ids.get(id.as_str()) {
+            *id = new_id.clone();
+        }
+    }
+}
+
+impl MiddlewareContainer {
+    /// 复制为新实体：重新生成自身及后端的ID并重置运行状态，指向被复制实体的依赖改为指向副本
+    pub fn duplicate(self) -> Self {
+        let old_ids = self.entity_ids();
+        let mut copy = self.duplicate_entities();
+        let ids: HashMap<String, String> = old_ids.into_iter().zip(copy.entity_ids()).collect();
+        copy.remap_dependencies(&ids);
+        copy
+    }
+
+    /// 重新生成自身及后端的ID并重置运行状态，不改写依赖
+    fn duplicate_entities(mut self) -> Self {
+        self.id = Uuid::new_v4().to_string();
+        self.status = ContainerStatus::Stopped;
+        self.health = HealthStatus::Unknown;
+        self.logs.clear();
+        self.kubernetes = None;
+        self.agent_health = None;
+        self.backend_containers = self.backend_containers.into_iter().map(BackendContainer::duplicate).collect();
+        self
+    }
+
+    /// 自身及下属后端的ID，按固定顺序排列
+    fn entity_ids(&self) -> Vec<String> {
+        std::iter::once(self.id.clone())
+            .chain(self.backend_containers.iter().map(|b| b.id.clone()))
+            .collect()
+    }
+
+    /// 按旧ID到新ID的映射改写自身及下属后端的依赖，映射外的依赖保持不变
+    fn remap_dependencies(&mut self, ids: &HashMap<String, String>) {
+        remap(&mut self.depends_on, ids);
+        for backend in &mut self.backend_containers {
+            remap(&mut backend.depends_on, ids);
+        }
+    }
+
+    /// 是否处于故障状态（运行出错或健康检查失败）
+    pub fn is_unhealthy(&self) -> bool {
+        self.status == ContainerStatus::Error || self.health == HealthStatus::Unhealthy
+    }
+
+    /// 创建容器使用的定义：运行参数合并单独配置的端口、挂载与资源上限
+    pub fn container_spec(&self) -> anyhow::Result<ContainerSpec> {
+        container_spec(&self.docker_run_params, &self.ports, &self.volumes, &self.limits)
+    }
+
+    /// 部署时注入的环境变量（不含密钥等敏感配置）
+    pub fn environment(&self) -> Vec<(&'static str, String)> {
+        let config = &self.config;
+        vec![
+            ("SERVER_HOST", config.server.host.clone()),
+            ("SERVER_PORT", config.server.port.to_string()),
+            ("SERVER_HTTPS", config.server.https.to_string()),
+            ("JWT_EXPIRES_IN", config.jwt.expires_in.to_string()),
+            ("JWT_REFRESH_IN", config.jwt.refresh_in.to_string()),
+            ("ENCRYPTION_ALGORITHM", config.encryption.algorithm.clone()),
+            ("ENCRYPTION_KEY_LENGTH", config.encryption.key_length.to_string()),
+            ("ENCRYPTION_ITERATIONS", config.encryption.iterations.to_string()),
+            ("SERVICE_ROLE", config.service.role.clone()),
+            ("SERVICE_ID", config.service.id.clone()),
+            (
+                "CRUD_API_URLS",
+                self.crud_instances().into_iter().map(|i| i.url).collect::<Vec<_>>().join(","),
+            ),
+            ("CRUD_API_TIMEOUT", config.crud_api.timeout.to_string()),
+            ("CRUD_API_RETRIES", config.crud_api.retries.to_string()),
+        ]
+    }
+
+    /// 由下属后端生成推送给服务的调度实例列表，Docker运行时的多副本后端每个副本为一个实例
+    pub fn crud_instances(&self) -> Vec<CrudApiInstance> {
+        self.backend_containers
+            .iter()
+            .flat_map(|b| {
+                let replicas = match self.runtime {
+                    RuntimeKind::Docker => b.replicas.max(1),
+                    // Kubernetes的副本由Service统一负载均衡
+                    RuntimeKind::Kubernetes { .. } => 1,
+                };
+                (0..replicas).map(move |index| CrudApiInstance {
+                    id: b.replica_id(index),
+                    url: b.replica_url(index),
+                    instance_type: b.instance_type.clone(),
+                    timeout: b.timeout,
+                    retries: b.retries,
+                    weight: b.weight,
+                })
+            })
+            .collect()
+    }
+
+    /// 访问中间层接口的地址，启用HTTPS时自动使用https协议
+    pub fn api_base_url(&self) -> String {
+        match self.url.strip_prefix("http://") {
+            Some(rest) if self.config.server.https => format!("https://{}", rest),
+            _ => self.url.clone(),
+        }
+    }
+}
+
+/// 业务组模型
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BusinessGroup {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub middlewares: Vec<MiddlewareContainer>,
+    pub backend_containers: Vec<BackendContainer>,
+    pub status: GroupStatus,
+    /// 访问组内服务使用的网络配置，为空时使用系统默认
+    #[serde(default)]
+    pub network_profile_id: Option<String>,
+    /// 组专用的Docker桥接网络，组内未指定网络的容器启动时接入
+    #[serde(default)]
+    pub docker_network: Option<GroupDockerNetwork>,
+    /// 启动业务组时后端的启动顺序
+    #[serde(default)]
+    pub backend_start_order: BackendStartOrder,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 启动业务组时后端的启动顺序
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BackendStartOrder {
+    /// 先写实例、再混合实例、最后读实例，前一批就绪后才启动下一批，读副本通常依赖主库先启动
+    #[default]
+    WritesFirst,
+    /// 按定义顺序一次启动，不等待就绪
+    Declared,
+}
+
+impl BackendStartOrder {
+    pub const ALL: [BackendStartOrder; 2] = [BackendStartOrder::WritesFirst, BackendStartOrder::Declared];
+
+    /// 显示名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            BackendStartOrder::WritesFirst => "写实例优先",
+            BackendStartOrder::Declared => "定义顺序",
+        }
+    }
+
+    /// 后端所在批次的（序号, 批次名称），序号小的批次先启动
+    pub fn stage_of(&self, backend: &BackendContainer) -> (usize, &'static str) {
+        match self {
+            BackendStartOrder::WritesFirst => match backend.instance_type.as_str() {
+                "write" => (0, "写实例"),
+                "read" => (2, "读实例"),
+                _ => (1, "混合实例"),
+            },
+            BackendStartOrder::Declared => (0, "全部后端"),
+        }
+    }
+
+    /// 将后端分为按顺序启动的批次，返回（批次名称, 后端），空批次省略
+    pub fn stages<'a>(&self, backends: impl IntoIterator<Item = &'a BackendContainer>) -> Vec<(&'static str, Vec<&'a BackendContainer>)> {
+        let mut stages: BTreeMap<usize, (&'static str, Vec<&'a BackendContainer>)> = BTreeMap::new();
+        for backend in backends {
+            let (rank, label) = self.stage_of(backend);
+            stages.entry(rank).or_insert_with(|| (label, Vec::new())).1.push(backend);
+        }
+        stages.into_values().collect()
+    }
+}
+
+impl Default for BusinessGroup {
+    fn default() -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            name: "新业务组".to_string(),
+            description: "".to_string(),
+            middlewares: Vec::new(),
+            backend_containers: Vec::new(),
+            status: GroupStatus::Stopped,
+            network_profile_id: None,
+            docker_network: None,
+            backend_start_order: BackendStartOrder::default(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+/// 业务组启动顺序中的一个容器
+#[derive(Debug, Clone, Copy)]
+pub enum StartItem<'a> {
+    Middleware(&'a MiddlewareContainer),
+    /// 后端及其所属中间层的ID，直属业务组的后端为空
+    Backend(&'a BackendContainer, Option<&'a str>),
+}
+
+impl<'a> StartItem<'a> {
+    pub fn id(&self) -> &'a str {
+        match self {
+            StartItem::Middleware(m) => &m.id,
+            StartItem::Backend(b, _) => &b.id,
+        }
+    }
+
+    pub fn name(&self) -> &'a str {
+        match self {
+            StartItem::Middleware(m) => &m.name,
+            StartItem::Backend(b, _) => &b.name,
+        }
+    }
+
+    pub fn status(&self) -> &'a ContainerStatus {
+        match self {
+            StartItem::Middleware(m) => &m.status,
+            StartItem::Backend(b, _) => &b.status,
+        }
+    }
+
+    pub fn kind(&self) -> EntityKind {
+        match self {
+            StartItem::Middleware(_) => EntityKind::Middleware,
+            StartItem::Backend(..) => EntityKind::Backend,
+        }
+    }
+
+    pub fn docker_run_params(&self) -> &'a str {
+        match self {
+            StartItem::Middleware(m) => &m.docker_run_params,
+            StartItem::Backend(b, _) => &b.docker_run_params,
+        }
+    }
+
+    pub fn docker_host_id(&self) -> &'a Option<String> {
+        match self {
+            StartItem::Middleware(m) => &m.docker_host_id,
+            StartItem::Backend(b, _) => &b.docker_host_id,
+        }
+    }
+
+    /// 须先启动的容器：显式声明的依赖，中间层还依赖其下属后端
+    fn dependencies(&self) -> Vec<&'a str> {
+        match self {
+            StartItem::Middleware(m) => m.depends_on
+                .iter()
+                .map(String::as_str)
+                .chain(m.backend_containers.iter().map(|b| b.id.as_str()))
+                .collect(),
+            StartItem::Backend(b, _) => b.depends_on.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+impl BusinessGroup {
+    /// 组内容器的启动顺序：依赖先于依赖方启动，中间层在其下属后端之后；
+    /// 没有依赖关系的容器按后端启动顺序的批次与定义顺序排列，中间层排在后端之后。依赖存在循环时报错
+    pub fn start_order(&self) -> anyhow::Result<Vec<StartItem<'_>>> {
+        let mut ranked: Vec<(usize, StartItem)> = self.middlewares
+            .iter()
+            .flat_map(|m| m.backend_containers.iter().map(move |b| (b, Some(m.id.as_str()))))
+            .chain(self.backend_containers.iter().map(|b| (b, None)))
+            .map(|(b, owner)| (self.backend_start_order.stage_of(b).0, StartItem::Backend(b, owner)))
+            .chain(self.middlewares.iter().map(|m| (usize::MAX, StartItem::Middleware(m))))
+            .collect();
+        ranked.sort_by_key(|(rank, _)| *rank);
+        let items: Vec<StartItem> = ranked.into_iter().map(|(_, item)| item).collect();
+        let position: HashMap<&str, usize> = items.iter().enumerate().map(|(index, item)| (item.id(), index)).collect();
+        let dependencies: Vec<Vec<usize>> = items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| {
+                item.dependencies()
+                    .into_iter()
+                    .filter_map(|id| position.get(id).copied())
+                    .filter(|&dependency| dependency != index)
+                    .collect()
+            })
+            .collect();
+
+        // 每次取所有依赖都已排入的第一个容器，保持无依赖关系时的原有顺序
+        let mut placed = vec![false; items.len()];
+        let mut order = Vec::with_capacity(items.len());
+        while order.len() < items.len() {
+            let next = (0..items.len()).find(|&index| !placed[index] && dependencies[index].iter().all(|&d| placed[d]));
+            let Some(next) = next else {
+                let cycle = Self::dependency_cycle(&dependencies, &placed);
+                let names: Vec<&str> = cycle.iter().map(|&index| items[index].name()).collect();
+                anyhow::bail!("启动依赖存在循环: {}", names.join(" → "));
+            };
+            placed[next] = true;
+            order.push(items[next]);
+        }
+        Ok(order)
+    }
+
+    /// 未排入的容器中的一个依赖循环，首尾为同一容器
+    fn dependency_cycle(dependencies: &[Vec<usize>], placed: &[bool]) -> Vec<usize> {
+        // 未排入的容器都至少有一个未排入的依赖，沿依赖前进必然回到走过的容器
+        let mut path: Vec<usize> = Vec::new();
+        let mut current = (0..placed.len()).find(|&index| !placed[index]).unwrap_or_default();
+        while !path.contains(&current) {
+            path.push(current);
+            current = dependencies[current].iter().copied().find(|&d| !placed[d]).unwrap_or(current);
+        }
+        let start = path.iter().position(|&index| index == current).unwrap_or_default();
+        let mut cycle = path.split_off(start);
+        cycle.push(current);
+        // 依赖方向与启动方向相反，按启动方向显示
+        cycle.reverse();
+        cycle
+    }
+
+    /// 组内是否包含该ID的中间层或后端
+    pub fn contains(&self, entity_id: &str) -> bool {
+        self.middlewares.iter().any(|m| {
+            m.id == entity_id || m.backend_containers.iter().any(|b| b.id == entity_id)
+        }) || self.backend_containers.iter().any(|b| b.id == entity_id)
+    }
+
+    /// 组使用的网络配置
+    pub fn network_profile<'a>(&self, profiles: &'a [NetworkProfile]) -> Option<&'a NetworkProfile> {
+        let id = self.network_profile_id.as_ref()?;
+        profiles.iter().find(|p| &p.id == id)
+    }
+
+    /// 复制为新实体：重新生成所有层级的ID并重置运行状态，组内的依赖改为指向对应的副本
+    pub fn duplicate(mut self) -> Self {
+        let old_ids = self.entity_ids();
+        let now = Utc::now();
+        self.id = Uuid::new_v4().to_string();
+        self.status = GroupStatus::Stopped;
+        self.created_at = now;
+        self.updated_at = now;
+        self.middlewares = self.middlewares.into_iter().map(MiddlewareContainer::duplicate_entities).collect();
+        self.backend_containers = self.backend_containers.into_iter().map(BackendContainer::duplicate).collect();
+        let ids: HashMap<String, String> = old_ids.into_iter().zip(self.entity_ids()).collect();
+        for middleware in &mut self.middlewares {
+            middleware.remap_dependencies(&ids);
+        }
+        for backend in &mut self.backend_containers {
+            remap(&mut backend.depends_on, &ids);
+        }
+        // 网络按新ID重新命名，子网不复制以免冲突
+        if self.docker_network.is_some() {
+            self.docker_network = Some(GroupDockerNetwork::for_group(&self.id));
+        }
+        self
+    }
+
+    /// 组内所有中间层与后端的ID，按固定顺序排列
+    fn entity_ids(&self) -> Vec<String> {
+        self.middlewares
+            .iter()
+            .flat_map(MiddlewareContainer::entity_ids)
+            .chain(self.backend_containers.iter().map(|b| b.id.clone()))
+            .collect()
+    }
+
+    /// 由子实体汇总的业务组状态：运行中的组在部分中间层或后端故障时为降级，
+    /// 中间层或后端全部故障时为错误；其他状态按组自身状态显示
+    pub fn rollup_status(&self) -> GroupStatus {
+        if self.status != GroupStatus::Running {
+            return self.status.clone();
+        }
+        let backends: Vec<&BackendContainer> = self.middlewares
+            .iter()
+            .flat_map(|m| m.backend_containers.iter())
+            .chain(self.backend_containers.iter())
+            .collect();
+        let all_down = |count: usize, down: usize| count > 0 && down == count;
+        let middlewares_down = self.middlewares.iter().filter(|m| m.is_unhealthy()).count();
+        let backends_down = backends.iter().filter(|b| b.is_unhealthy()).count();
+        
+        if all_down(self.middlewares.len(), middlewares_down) || all_down(backends.len(), backends_down) {
+            GroupStatus::Error
+        } else if middlewares_down + backends_down > 0 {
+            GroupStatus::Degraded
+        } else {
+            GroupStatus::Running
+        }
+    }
+
+    /// 故障的中间层与后端数量，包括中间层下属的后端
+    pub fn unhealthy_count(&self) -> usize {
+        let middlewares = self.middlewares.iter().map(|m| {
+            usize::from(m.is_unhealthy()) + m.backend_containers.iter().filter(|b| b.is_unhealthy()).count()
+        });
+        middlewares.sum::<usize>() + self.backend_containers.iter().filter(|b| b.is_unhealthy()).count()
+    }
+    
+    /// 业务组及其中间层与后端的名称，用于按告警来源归属业务组
+    pub fn entity_names(&self) -> HashSet<&str> {
+        let mut names = HashSet::from([self.name.as_str()]);
+        for middleware in &self.middlewares {
+            names.insert(middleware.name.as_str());
+            names.extend(middleware.backend_containers.iter().map(|b| b.name.as_str()));
+        }
+        names.extend(self.backend_containers.iter().map(|b| b.name.as_str()));
+        names
+    }
+}
+
+/// 以JSON形式复制/粘贴的实体
+#[derive(Debug, Clone)]
+pub enum EntityJson {
+    Group(Box<BusinessGroup>),
+    Middleware(Box<MiddlewareContainer>),
+    Backend(Box<BackendContainer>),
+}
+
+impl EntityJson {
+    /// 解析JSON，依次尝试业务组、中间层、后端
+    pub fn parse(text: &str) -> Option<Self> {
+        let value: serde_json::Value = serde_json::from_str(text.trim()).ok()?;
+        if let Ok(group) = serde_json::from_value(value.clone()) {
+            return Some(EntityJson::Group(Box::new(group)));
+        }
+        if let Ok(middleware) = serde_json::from_value(value.clone()) {
+            return Some(EntityJson::Middleware(Box::new(middleware)));
+        }
+        serde_json::from_value(value).ok().map(|backend| EntityJson::Backend(Box::new(backend)))
+    }
+}
+
+/// 定义实体ID的新类型，可直接以 `&str` 作为哈希表的查询键
+///
+/// 用于 [`EntityIndex`] 的键与状态、服务的查找接口，避免三类ID混用；
+/// 实体字段与配置文件格式仍使用字符串，序列化结果与字符串相同
+macro_rules! entity_id {
+    ($(#[$doc:meta])* $name:ident) => {
+        $(#[$doc])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+        #[serde(transparent)]
+        pub struct $name(String);
+
+        impl $name {
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(id: &str) -> Self {
+                Self(id.to_string())
+            }
+        }
+
+        impl From<&String> for $name {
+            fn from(id: &String) -> Self {
+                Self(id.clone())
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(id: String) -> Self {
+                Self(id)
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(id: $name) -> Self {
+                id.0
+            }
+        }
+
+        impl std::ops::Deref for $name {
+            type Target = str;
+
+            fn deref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl std::borrow::Borrow<str> for $name {
+            fn borrow(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl PartialEq<str> for $name {
+            fn eq(&self, other: &str) -> bool {
+                self.0 == other
+            }
+        }
+
+        impl PartialEq<$name> for String {
+            fn eq(&self, other: &$name) -> bool {
+                *self == other.0
+            }
+        }
+
+        impl PartialEq<&$name> for String {
+            fn eq(&self, other: &&$name) -> bool {
+                *self == other.0
+            }
+        }
+
+        impl PartialEq<$name> for str {
+            fn eq(&self, other: &$name) -> bool {
+                self == other.0
+            }
+        }
+    };
+}
+
+entity_id!(
+    /// 业务组ID
+    GroupId
+);
+entity_id!(
+    /// 中间层ID
+    MiddlewareId
+);
+entity_id!(
+    /// 后端ID
+    BackendId
+);
+
+/// 中间层在状态中的位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct MiddlewareSlot {
+    group: usize,
+    middleware: usize,
+}
+
+/// 后端在状态中的位置，直属业务组的后端没有中间层
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct BackendSlot {
+    group: usize,
+    middleware: Option<usize>,
+    backend: usize,
+}
+
+/// 业务组、中间层与后端ID到所在位置的索引
+///
+/// 状态每次被替换时重建；同一次修改中增删实体后索引可能过期，
+/// 查找时先核对索引位置上的ID，不符再逐个查找，因此结果总是正确的
+#[derive(Debug, Clone, Default)]
+pub struct EntityIndex {
+    groups: HashMap<GroupId, usize>,
+    middlewares: HashMap<MiddlewareId, MiddlewareSlot>,
+    backends: HashMap<BackendId, BackendSlot>,
+}
+
+impl EntityIndex {
+    /// 为业务组列表建立索引
+    fn build(groups: &BusinessGroups) -> Self {
+        let mut index = Self::default();
+        for (g, group) in groups.iter().enumerate() {
+            index.insert_group(g, group);
+        }
+        index
+    }
+
+    /// 加入位于 `g` 的业务组及其中的实体
+    fn insert_group(&mut self, g: usize, group: &BusinessGroup) {
+        self.groups.insert(GroupId::from(group.id.as_str()), g);
+        for (m, middleware) in group.middlewares.iter().enumerate() {
+            self.middlewares.insert(MiddlewareId::from(middleware.id.as_str()), MiddlewareSlot { group: g, middleware: m });
+            for (b, backend) in middleware.backend_containers.iter().enumerate() {
+                self.backends.insert(BackendId::from(backend.id.as_str()), BackendSlot { group: g, middleware: Some(m), backend: b });
+            }
+        }
+        for (b, backend) in group.backend_containers.iter().enumerate() {
+            self.backends.insert(BackendId::from(backend.id.as_str()), BackendSlot { group: g, middleware: None, backend: b });
+        }
+    }
+
+    /// 移除业务组及其中的实体
+    fn remove_group(&mut self, group: &BusinessGroup) {
+        self.groups.remove(group.id.as_str());
+        for middleware in &group.middlewares {
+            self.middlewares.remove(middleware.id.as_str());
+        }
+        let backends = group.middlewares.iter().flat_map(|m| m.backend_containers.iter()).chain(group.backend_containers.iter());
+        for backend in backends {
+            self.backends.remove(backend.id.as_str());
+        }
+    }
+}
+
+/// 业务组列表，各业务组共享存储、写时复制
+///
+/// 复制列表只增加引用计数；通过可变访问修改某个业务组时才复制该业务组，
+/// 因此状态修改前的副本开销与业务组数量无关，前后两个列表中仍共享的业务组未被修改
+#[derive(Debug, Clone, Default)]
+pub struct BusinessGroups(Vec<Arc<BusinessGroup>>);
+
+impl BusinessGroups {
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&BusinessGroup> {
+        self.0.get(index).map(|g| g.as_ref())
+    }
+
+    /// 可变访问，业务组仍被其他副本共享时先复制
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut BusinessGroup> {
+        self.0.get_mut(index).map(Arc::make_mut)
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &BusinessGroup> + ExactSizeIterator {
+        self.0.iter().map(|g| g.as_ref())
+    }
+
+    /// 逐个可变访问，只复制实际迭代到的业务组；只修改其中一个时应先按ID定位
+    pub fn iter_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut BusinessGroup> + ExactSizeIterator {
+        self.0.iter_mut().map(Arc::make_mut)
+    }
+
+    /// 可变访问满足条件的业务组，只复制这些业务组；批量修改少数业务组时使用
+    pub fn matching_mut(&mut self, f: impl Fn(&BusinessGroup) -> bool) -> impl Iterator<Item = &mut BusinessGroup> {
+        self.0.iter_mut().filter(move |g| f(g)).map(Arc::make_mut)
+    }
+
+    /// 与另一列表共享存储的业务组，即两者之间未被修改的业务组
+    pub fn shared_with(&self, other: &BusinessGroups) -> HashSet<*const BusinessGroup> {
+        let own: HashSet<*const BusinessGroup> = self.0.iter().map(Arc::as_ptr).collect();
+        other.0.iter().map(Arc::as_ptr).filter(|g| own.contains(g)).collect()
+    }
+
+    /// 是否与另一列表共享该业务组的存储
+    pub fn is_shared(shared: &HashSet<*const BusinessGroup>, group: &BusinessGroup) -> bool {
+        shared.contains(&(group as *const BusinessGroup))
+    }
+
+    pub fn push(&mut self, group: BusinessGroup) {
+        self.0.push(Arc::new(group));
+    }
+
+    pub fn retain(&mut self, mut f: impl FnMut(&BusinessGroup) -> bool) {
+        self.0.retain(|g| f(g));
+    }
+
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    /// 复制为普通列表
+    pub fn to_vec(&self) -> Vec<BusinessGroup> {
+        self.iter().cloned().collect()
+    }
+}
+
+impl std::ops::Index<usize> for BusinessGroups {
+    type Output = BusinessGroup;
+
+    fn index(&self, index: usize) -> &BusinessGroup {
+        &self.0[index]
+    }
+}
+
+impl std::ops::IndexMut<usize> for BusinessGroups {
+    fn index_mut(&mut self, index: usize) -> &mut BusinessGroup {
+        Arc::make_mut(&mut self.0[index])
+    }
+}
+
+impl From<Vec<BusinessGroup>> for BusinessGroups {
+    fn from(groups: Vec<BusinessGroup>) -> Self {
+        Self(groups.into_iter().map(Arc::new).collect())
+    }
+}
+
+impl FromIterator<BusinessGroup> for BusinessGroups {
+    fn from_iter<I: IntoIterator<Item = BusinessGroup>>(iter: I) -> Self {
+        Self(iter.into_iter().map(Arc::new).collect())
+    }
+}
+
+impl Extend<BusinessGroup> for BusinessGroups {
+    fn extend<I: IntoIterator<Item = BusinessGroup>>(&mut self, iter: I) {
+        self.0.extend(iter.into_iter().map(Arc::new));
+    }
+}
+
+impl IntoIterator for BusinessGroups {
+    type Item = BusinessGroup;
+    type IntoIter = std::iter::Map<std::vec::IntoIter<Arc<BusinessGroup>>, fn(Arc<BusinessGroup>) -> BusinessGroup>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter().map(Arc::unwrap_or_clone)
+    }
+}
+
+impl<'a> IntoIterator for &'a BusinessGroups {
+    type Item = &'a BusinessGroup;
+    type IntoIter = std::iter::Map<std::slice::Iter<'a, Arc<BusinessGroup>>, fn(&'a Arc<BusinessGroup>) -> &'a BusinessGroup>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter().map(|g| g.as_ref())
+    }
+}
+
+impl<'a> IntoIterator for &'a mut BusinessGroups {
+    type Item = &'a mut BusinessGroup;
+    type IntoIter = std::iter::Map<std::slice::IterMut<'a, Arc<BusinessGroup>>, fn(&'a mut Arc<BusinessGroup>) -> &'a mut BusinessGroup>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter_mut().map(Arc::make_mut)
+    }
+}
+
+impl Serialize for BusinessGroups {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de> Deserialize<'de> for BusinessGroups {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<BusinessGroup>::deserialize(deserializer).map(Self::from)
+    }
+}
+
+/// 应用状态模型
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct AppState {
+    pub business_groups: BusinessGroups,
+    /// 可管理容器的Docker主机
+    #[serde(default)]
+    pub docker_hosts: Vec<DockerHost>,
+    /// 可供业务组选用的网络配置
+    #[serde(default)]
+    pub network_profiles: Vec<NetworkProfile>,
+    pub selected_group_id: Option<String>,
+    pub selected_middleware_id: Option<String>,
+    pub selected_backend_id: Option<String>,
+    /// 实体ID索引，不保存到配置文件；状态副本共享索引，修改后更新时才复制
+    #[serde(skip)]
+    pub(crate) index: Arc<EntityIndex>,
+}
+
+impl AppState {
+    /// 重建实体ID索引，状态整体替换后调用
+    pub fn reindex(&mut self) {
+        self.index = Arc::new(EntityIndex::build(&self.business_groups));
+    }
+
+    /// 以修改前的状态为基础更新实体ID索引，只重建修改过的业务组的条目；
+    /// 业务组有增删或换位时整体重建
+    pub fn refresh_index(&mut self, previous: AppState) {
+        let AppState { business_groups: old, index, .. } = previous;
+        // 先丢弃修改前状态持有的索引，本状态通常持有唯一引用，增量更新无需复制
+        drop(index);
+        let (old, new) = (&old.0, &self.business_groups.0);
+        if old.len() != new.len() || old.iter().zip(new).any(|(o, n)| o.id != n.id) {
+            self.reindex();
+            return;
+        }
+        let changed: Vec<usize> = (0..new.len()).filter(|&g| !Arc::ptr_eq(&old[g], &new[g])).collect();
+        if changed.is_empty() {
+            return;
+        }
+        let index = Arc::make_mut(&mut self.index);
+        for &g in &changed {
+            index.remove_group(&old[g]);
+        }
+        for &g in &changed {
+            index.insert_group(g, &new[g]);
+        }
+    }
+    
+    /// 业务组的位置
+    fn group_slot(&self, group_id: &str) -> Option<usize> {
+        match self.index.groups.get(group_id) {
+            Some(&g) if self.business_groups.get(g).is_some_and(|group| group.id == group_id) => Some(g),
+            _ => self.business_groups.iter().position(|g| g.id == group_id),
+        }
+    }
+    
+    /// 中间层的位置
+    fn middleware_slot(&self, middleware_id: &str) -> Option<MiddlewareSlot> {
+        let at = |slot: &MiddlewareSlot| self.business_groups.get(slot.group).and_then(|g| g.middlewares.get(slot.middleware));
+        match self.index.middlewares.get(middleware_id) {
+            Some(slot) if at(slot).is_some_and(|m| m.id == middleware_id) => Some(*slot),
+            _ => self.business_groups.iter().enumerate().find_map(|(g, group)| {
+                let m = group.middlewares.iter().position(|m| m.id == middleware_id)?;
+                Some(MiddlewareSlot { group: g, middleware: m })
+            }),
+        }
+    }
+    
+    /// 后端的位置
+    fn backend_slot(&self, backend_id: &str) -> Option<BackendSlot> {
+        if let Some(slot) = self.index.backends.get(backend_id)
+            && self.backend_at(*slot).is_some_and(|b| b.id == backend_id)
+        {
+            return Some(*slot);
+        }
+        self.business_groups.iter().enumerate().find_map(|(g, group)| {
+            let under_middleware = group.middlewares.iter().enumerate().find_map(|(m, middleware)| {
+                let b = middleware.backend_containers.iter().position(|b| b.id == backend_id)?;
+                Some(BackendSlot { group: g, middleware: Some(m), backend: b })
+            });
+            under_middleware.or_else(|| {
+                let b = group.backend_containers.iter().position(|b| b.id == backend_id)?;
+                Some(BackendSlot { group: g, middleware: None, backend: b })
+            })
+        })
+    }
+    
+    /// 位置上的后端
+    fn backend_at(&self, slot: BackendSlot) -> Option<&BackendContainer> {
+        let group = self.business_groups.get(slot.group)?;
+        let backends = match slot.middleware {
+            Some(m) => &group.middlewares.get(m)?.backend_containers,
+            None => &group.backend_containers,
+        };
+        backends.get(slot.backend)
+    }
+    
+    /// 按ID查找业务组
+    pub fn group(&self, group_id: &GroupId) -> anyhow::Result<&BusinessGroup> {
+        let g = self.group_slot(group_id).ok_or_else(|| anyhow::anyhow!("业务组不存在: {}", group_id))?;
+        Ok(&self.business_groups[g])
+    }
+    
+    /// 按ID查找业务组以便修改
+    pub fn group_mut(&mut self, group_id: &GroupId) -> anyhow::Result<&mut BusinessGroup> {
+        let g = self.group_slot(group_id).ok_or_else(|| anyhow::anyhow!("业务组不存在: {}", group_id))?;
+        Ok(&mut self.business_groups[g])
+    }
+    
+    /// 业务组中中间层的位置，业务组或中间层不存在时分别报错
+    fn scoped_middleware_slot(&self, group_id: &GroupId, middleware_id: &MiddlewareId) -> anyhow::Result<MiddlewareSlot> {
+        let g = self.group_slot(group_id).ok_or_else(|| anyhow::anyhow!("业务组不存在: {}", group_id))?;
+        self.middleware_slot(middleware_id)
+            .filter(|slot| slot.group == g)
+            .ok_or_else(|| anyhow::anyhow!("中间层容器不存在: {}", middleware_id))
+    }
+    
+    /// 查找业务组中的中间层，返回（业务组, 中间层）
+    pub fn middleware(&self, group_id: &GroupId, middleware_id: &MiddlewareId) -> anyhow::Result<(&BusinessGroup, &MiddlewareContainer)> {
+        let slot = self.scoped_middleware_slot(group_id, middleware_id)?;
+        let group = &self.business_groups[slot.group];
+        Ok((group, &group.middlewares[slot.middleware]))
+    }
+    
+    /// 查找业务组中的中间层以便修改
+    pub fn middleware_mut(&mut self, group_id: &GroupId, middleware_id: &MiddlewareId) -> anyhow::Result<&mut MiddlewareContainer> {
+        let slot = self.scoped_middleware_slot(group_id, middleware_id)?;
+        Ok(&mut self.business_groups[slot.group].middlewares[slot.middleware])
+    }
+    
+    /// 不限业务组按ID查找中间层，返回（业务组, 中间层）
+    pub fn find_middleware(&self, middleware_id: &MiddlewareId) -> Option<(&BusinessGroup, &MiddlewareContainer)> {
+        let slot = self.middleware_slot(middleware_id)?;
+        let group = &self.business_groups[slot.group];
+        Some((group, &group.middlewares[slot.middleware]))
+    }
+    
+    /// 不限业务组按ID查找中间层以便修改
+    pub fn find_middleware_mut(&mut self, middleware_id: &MiddlewareId) -> Option<&mut MiddlewareContainer> {
+        let slot = self.middleware_slot(middleware_id)?;
+        Some(&mut self.business_groups[slot.group].middlewares[slot.middleware])
+    }
+    
+    /// 业务组或其中间层下的后端列表
+    pub fn backends_mut(&mut self, group_id: &GroupId, middleware_id: Option<&MiddlewareId>) -> anyhow::Result<&mut Vec<BackendContainer>> {
+        match middleware_id {
+            Some(middleware_id) => Ok(&mut self.middleware_mut(group_id, middleware_id)?.backend_containers),
+            None => Ok(&mut self.group_mut(group_id)?.backend_containers),
+        }
+    }
+    
+    /// 业务组或其中间层下后端的位置，业务组、中间层或后端不存在时分别报错
+    fn scoped_backend_slot(&self, group_id: &GroupId, middleware_id: Option<&MiddlewareId>, backend_id: &BackendId) -> anyhow::Result<BackendSlot> {
+        let (g, m) = match middleware_id {
+            Some(middleware_id) => {
+                let slot = self.scoped_middleware_slot(group_id, middleware_id)?;
+                (slot.group, Some(slot.middleware))
+            }
+            None => (self.group_slot(group_id).ok_or_else(|| anyhow::anyhow!("业务组不存在: {}", group_id))?, None),
+        };
+        self.backend_slot(backend_id)
+            .filter(|slot| slot.group == g && slot.middleware == m)
+            .ok_or_else(|| anyhow::anyhow!("后端容器不存在: {}", backend_id))
+    }
+    
+    /// 查找业务组或其中间层下的后端，返回（业务组, 所属中间层, 后端）
+    pub fn backend(&self, group_id: &GroupId, middleware_id: Option<&MiddlewareId>, backend_id: &BackendId) -> anyhow::Result<(&BusinessGroup, Option<&MiddlewareContainer>, &BackendContainer)> {
+        let slot = self.scoped_backend_slot(group_id, middleware_id, backend_id)?;
+        let group = &self.business_groups[slot.group];
+        let middleware = slot.middleware.map(|m| &group.middlewares[m]);
+        let backends = middleware.map_or(&group.backend_containers, |m| &m.backend_containers);
+        Ok((group, middleware, &backends[slot.backend]))
+    }
+    
+    /// 查找业务组或其中间层下的后端以便修改
+    pub fn backend_mut(&mut self, group_id: &GroupId, middleware_id: Option<&MiddlewareId>, backend_id: &BackendId) -> anyhow::Result<&mut BackendContainer> {
+        let slot = self.scoped_backend_slot(group_id, middleware_id, backend_id)?;
+        Ok(self.backend_at_mut(slot))
+    }
+    
+    /// 不限位置按ID查找后端
+    pub fn find_backend(&self, backend_id: &BackendId) -> Option<&BackendContainer> {
+        self.backend_slot(backend_id).and_then(|slot| self.backend_at(slot))
+    }
+    
+    /// 不限位置按ID查找后端以便修改
+    pub fn find_backend_mut(&mut self, backend_id: &BackendId) -> Option<&mut BackendContainer> {
+        let slot = self.backend_slot(backend_id)?;
+        Some(self.backend_at_mut(slot))
+    }
+    
+    /// 位置上的后端，位置须有效
+    fn backend_at_mut(&mut self, slot: BackendSlot) -> &mut BackendContainer {
+        let group = &mut self.business_groups[slot.group];
+        let backends = match slot.middleware {
+            Some(m) => &mut group.middlewares[m].backend_containers,
+            None => &mut group.backend_containers,
+        };
+        &mut backends[slot.backend]
+    }
+    
+    /// 实体所在的业务组，实体可以是业务组、中间层或后端
+    pub fn group_of(&self, entity_id: &str) -> Option<&BusinessGroup> {
+        let g = self.group_slot(entity_id)
+            .or_else(|| self.middleware_slot(entity_id).map(|slot| slot.group))
+            .or_else(|| self.backend_slot(entity_id).map(|slot| slot.group))?;
+        Some(&self.business_groups[g])
+    }
+    
+    /// 实体所在业务组使用的网络配置，实体可以是业务组、中间层或后端
+    pub fn network_for(&self, entity_id: &str) -> Option<NetworkProfile> {
+        self.group_of(entity_id)?
+            .network_profile(&self.network_profiles)
+            .cloned()
+    }
+
+    /// 查找所有业务组中与给定端口映射冲突的其他容器
+    ///
+    /// 只比较同一Docker主机上由运行参数创建的容器，外部管理的容器不占用端口
+    pub fn port_conflicts(&self, entity_id: &str, host_id: &Option<String>, ports: &[PortMapping]) -> Vec<PortConflict> {
+        let mut conflicts = Vec::new();
+        for group in &self.business_groups {
+            let containers = group.middlewares
+                .iter()
+                .map(|m| (&m.id, &m.name, &m.docker_host_id, &m.docker_run_params, m.container_spec(), &m.status))
+                .chain(
+                    group.middlewares
+                        .iter()
+                        .flat_map(|m| m.backend_containers.iter())
+                        .chain(group.backend_containers.iter())
+                        .map(|b| (&b.id, &b.name, &b.docker_host_id, &b.docker_run_params, b.container_spec(), &b.status)),
+                );
+            for (id, name, other_host_id, params, spec, status) in containers {
+                if id == entity_id || other_host_id != host_id || params.trim().is_empty() {
+                    continue;
+                }
+                let Ok(spec) = spec else {
+                    continue;
+                };
+                for port in ports {
+                    if spec.ports.iter().any(|p| p.conflicts_with(port)) {
+                        conflicts.push(PortConflict {
+                            port: port.clone(),
+                            container_name: name.clone(),
+                            group_name: group.name.clone(),
+                            running: matches!(status, ContainerStatus::Running | ContainerStatus::Starting),
+                        });
+                    }
+                }
+            }
+        }
+        conflicts
+    }
+
+    /// 所有业务组中超过存活期限未被来源报告的发现后端
+    pub fn stale_backends(&self, ttl: &DiscoveryTtl, now: DateTime<Utc>) -> Vec<StaleBackend> {
+        let mut stale = Vec::new();
+        for group in &self.business_groups {
+            let backends = group.middlewares
+                .iter()
+                .flat_map(|m| m.backend_containers.iter().map(move |b| (Some(&m.id), b)))
+                .chain(group.backend_containers.iter().map(|b| (None, b)));
+            for (middleware_id, backend) in backends {
+                let Some(last_seen) = backend.last_seen else {
+                    continue;
+                };
+                if ttl.stale_at(last_seen) > now {
+                    continue;
+                }
+                stale.push(StaleBackend {
+                    group_id: group.id.clone(),
+                    group_name: group.name.clone(),
+                    middleware_id: middleware_id.cloned(),
+                    backend_id: backend.id.clone(),
+                    backend_name: backend.name.clone(),
+                    last_seen,
+                    removal_at: ttl.removal_at(last_seen),
+                });
+            }
+        }
+        stale
+    }
+}
+
+/// 发现导入的后端的存活期限：来源超过期限未再报告时标记为失联，
+/// 开启自动移除后再经过宽限期移除，宽限期内可在待移除列表中保留或立即移除
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct DiscoveryTtl {
+    /// 未再报告多少秒后标记为失联
+    pub ttl_secs: u64,
+    #[serde(default)]
+    pub auto_remove: bool,
+    /// 标记失联后等待多少秒自动移除
+    pub remove_after_secs: u64,
+}
+
+impl Default for DiscoveryTtl {
+    fn default() -> Self {
+        Self {
+            ttl_secs: 600,
+            auto_remove: false,
+            remove_after_secs: 3600,
+        }
+    }
+}
+
+impl DiscoveryTtl {
+    /// 标记为失联的时间
+    pub fn stale_at(&self, last_seen: DateTime<Utc>) -> DateTime<Utc> {
+        last_seen + chrono::Duration::seconds(self.ttl_secs.min(i64::MAX as u64) as i64)
+    }
+
+    /// 自动移除的时间，未开启自动移除时为空
+    pub fn removal_at(&self, last_seen: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.auto_remove
+            .then(|| self.stale_at(last_seen) + chrono::Duration::seconds(self.remove_after_secs.min(i64::MAX as u64) as i64))
+    }
+}
+
+/// 来源已不再报告的发现后端
+#[derive(Debug, Clone)]
+pub struct StaleBackend {
+    pub group_id: String,
+    pub group_name: String,
+    pub middleware_id: Option<String>,
+    pub backend_id: String,
+    pub backend_name: String,
+    pub last_seen: DateTime<Utc>,
+    /// 自动移除的时间，未开启自动移除时为空
+    pub removal_at: Option<DateTime<Utc>>,
+}
+
+impl StaleBackend {
+    /// 是否已到自动移除时间
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.removal_at.is_some_and(|at| at <= now)
+    }
+}
+
+/// 端口冲突：另一个容器映射了相同的宿主机端口
+#[derive(Debug, Clone)]
+pub struct PortConflict {
+    pub port: PortMapping,
+    pub container_name: String,
+    pub group_name: String,
+    /// 冲突的容器是否正在运行，运行中时端口已被占用
+    pub running: bool,
+}
+
+impl std::fmt::Display for PortConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "宿主机端口 {}/{} 已由业务组 {} 的容器 {} 映射", self.port.host_port, self.port.protocol, self.group_name, self.container_name)?;
+        if self.running {
+            write!(f, "（运行中）")?;
+        }
+        Ok(())
+    }
+}
+
+/// 告警级别枚举
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum AlertSeverity {
+    Info,
+    Warning,
+    Critical,
+}
+
+/// 日志条目
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LogEntry {
+    pub timestamp: DateTime<Utc>,
+    /// 日志来源（实体名称或子系统）
+    pub source: String,
+    pub message: String,
+}
+
+impl LogEntry {
+    /// 创建当前时间的日志条目
+    pub fn new(source: &str, message: &str) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            source: source.to_string(),
+            message: message.to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for LogEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}] [{}] {}", self.timestamp.format("%Y-%m-%d %H:%M:%S"), self.source, self.message)
+    }
+}
+
+/// 附加到告警的日志证据
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LogEvidence {
+    pub id: String,
+    /// 关联实体
+    pub entity: String,
+    pub entries: Vec<LogEntry>,
+    /// 证据覆盖的时间范围
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    /// 操作员备注
+    pub note: String,
+    pub attached_at: DateTime<Utc>,
+}
+
+impl LogEvidence {
+    /// 根据选中的日志创建证据，时间范围取自日志时间戳
+    pub fn new(entity: &str, entries: Vec<LogEntry>, note: &str) -> Self {
+        let now = Utc::now();
+        let from = entries.iter().map(|e| e.timestamp).min().unwrap_or(now);
+        let to = entries.iter().map(|e| e.timestamp).max().unwrap_or(now);
+        Self {
+            id: Uuid::new_v4().to_string(),
+            entity: entity.to_string(),
+            entries,
+            from,
+            to,
+            note: note.to_string(),
+            attached_at: now,
+        }
+    }
+}
+
+/// 告警模型
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Alert {
+    pub id: String,
+    /// 告警来源（实体名称或子系统）
+    pub source: String,
+    pub message: String,
+    pub severity: AlertSeverity,
+    pub created_at: DateTime<Utc>,
+    pub acknowledged: bool,
+    /// 排查过程中附加的日志证据
+    #[serde(default)]
+    pub evidence: Vec<LogEvidence>,
+    /// 去重后合并的触发次数
+    #[serde(default = "default_occurrences")]
+    pub occurrences: u32,
+    /// 最近一次触发时间
+    #[serde(default)]
+    pub last_seen: Option<DateTime<Utc>>,
+}
+
+fn default_occurrences() -> u32 {
+    1
+}
+
+impl Alert {
+    /// 告警首次触发的本地日期
+    pub fn local_day(&self) -> NaiveDate {
+        self.created_at.with_timezone(&Local).date_naive()
+    }
+    
+    /// 创建新的告警
+    pub fn new(source: &str, message: &str, severity: AlertSeverity) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            source: source.to_string(),
+            message: message.to_string(),
+            severity,
+            created_at: Utc::now(),
+            acknowledged: false,
+            evidence: Vec::new(),
+            occurrences: 1,
+            last_seen: None,
+        }
+    }
+}
+
+/// 健康告警的迟滞、去重与抖动抑制设置
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct AlertPolicy {
+    /// 连续失败多少次才告警
+    pub failure_threshold: u32,
+    /// 连续成功多少次才视为恢复
+    pub recovery_threshold: u32,
+    /// 相同告警在该时间内未确认时合并（秒）
+    pub dedup_window_secs: u64,
+    /// 窗口内状态切换达到该次数视为抖动，0表示不检测
+    pub flap_threshold: u32,
+    /// 抖动检测窗口（秒）
+    pub flap_window_secs: u64,
+}
+
+impl Default for AlertPolicy {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 3,
+            recovery_threshold: 2,
+            dedup_window_secs: 600,
+            flap_threshold: 4,
+            flap_window_secs: 600,
+        }
+    }
+}
+
+/// 监控策略文件的格式版本
+pub const MONITORING_POLICY_VERSION: u32 = 1;
+
+/// 与拓扑配置分开导出的监控策略，可在不同配置与团队之间共享而不复制业务组
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MonitoringPolicy {
+    pub version: u32,
+    pub alert_policy: AlertPolicy,
+    pub anomaly_rules: Vec<AnomalyRule>,
+    pub anomaly_spike_threshold: u32,
+}
+
+/// 导入监控策略的方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolicyImportMode {
+    /// 按ID合并异常检测规则，同ID的以导入的为准，其余保留
+    Merge,
+    /// 以导入的规则替换现有规则
+    Replace,
+}
+
+/// 界面主题
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UiTheme {
+    #[default]
+    Dark,
+    Light,
+}
+
+impl UiTheme {
+    /// 所有主题
+    pub const ALL: [UiTheme; 2] = [UiTheme::Dark, UiTheme::Light];
+
+    /// 显示名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            UiTheme::Dark => "深色",
+            UiTheme::Light => "浅色",
+        }
+    }
+}
+
+/// 日志异常检测规则
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AnomalyRule {
+    pub id: String,
+    pub name: String,
+    /// 关键字或正则表达式
+    pub pattern: String,
+    pub is_regex: bool,
+    pub enabled: bool,
+    pub severity: AlertSeverity,
+    /// 命中时是否触发告警
+    pub raise_alert: bool,
+}
+
+impl AnomalyRule {
+    /// 创建关键字规则
+    pub fn keyword(pattern: &str, severity: AlertSeverity) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            name: pattern.to_string(),
+            pattern: pattern.to_string(),
+            is_regex: false,
+            enabled: true,
+            severity,
+            raise_alert: false,
+        }
+    }
+}
+
+impl Default for AnomalyRule {
+    fn default() -> Self {
+        Self::keyword("", AlertSeverity::Warning)
+    }
+}
+
+/// 仪表盘组件类型
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub enum DashboardWidgetKind {
+    /// 运行概况卡片
+    UptimeCard,
+    /// 未确认告警列表
+    AlertList,
+    /// 指定中间层的延迟曲线
+    LatencyChart { middleware_id: Option<String> },
+    /// 快捷操作
+    QuickActions,
+    /// 各业务组的月成本估算
+    CostSummary,
+}
+
+impl DashboardWidgetKind {
+    /// 组件目录
+    pub fn catalog() -> Vec<DashboardWidgetKind> {
+        vec![
+            DashboardWidgetKind::UptimeCard,
+            DashboardWidgetKind::AlertList,
+            DashboardWidgetKind::LatencyChart { middleware_id: None },
+            DashboardWidgetKind::QuickActions,
+            DashboardWidgetKind::CostSummary,
+        ]
+    }
+
+    /// 显示名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            DashboardWidgetKind::UptimeCard => "运行概况",
+            DashboardWidgetKind::AlertList => "告警列表",
+            DashboardWidgetKind::LatencyChart { .. } => "延迟曲线",
+            DashboardWidgetKind::QuickActions => "快捷操作",
+            DashboardWidgetKind::CostSummary => "成本估算",
+        }
+    }
+}
+
+/// 仪表盘组件及其在网格中的位置
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DashboardWidget {
+    pub id: String,
+    pub kind: DashboardWidgetKind,
+    pub row: u32,
+    pub column: u32,
+    /// 占用的列数
+    pub span: u32,
+}
+
+impl DashboardWidget {
+    /// 创建新组件
+    pub fn new(kind: DashboardWidgetKind, row: u32, column: u32, span: u32) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            kind,
+            row,
+            column,
+            span,
+        }
+    }
+}
+
+/// 重启业务组的方式
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RestartMode {
+    /// 先停止再启动全部后端
+    #[default]
+    AllAtOnce,
+    /// 逐个重启运行中的后端，前一个通过健康探测后才重启下一个
+    Rolling,
+}
+
+/// 计划对单个容器执行的操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlannedAction {
+    /// 按容器定义启动，主机上不存在或定义有变化的容器先（重新）创建
+    Create,
+    /// 启动已有的工作负载
+    Start,
+    /// 停止运行
+    Stop,
+    /// 删除后按新的定义重新创建
+    Recreate,
+    /// 只修改容器定义，下次启动时生效
+    Update,
+    /// 容器由外部管理，只更新记录的状态
+    StatusOnly,
+    /// 无需操作
+    Skip,
+}
+
+impl PlannedAction {
+    /// 显示名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            PlannedAction::Create => "创建",
+            PlannedAction::Start => "启动",
+            PlannedAction::Stop => "停止",
+            PlannedAction::Recreate => "重新创建",
+            PlannedAction::Update => "更新定义",
+            PlannedAction::StatusOnly => "仅更新状态",
+            PlannedAction::Skip => "跳过",
+        }
+    }
+}
+
+/// 计划中的一项：按执行顺序排列的单个容器的操作
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedChange {
+    pub kind: EntityKind,
+    pub id: String,
+    pub name: String,
+    pub action: PlannedAction,
+    /// 操作的具体内容，如镜像与副本数
+    pub detail: String,
+}
+
+/// 业务组操作的执行计划，执行前列出每个容器将被如何处理
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperationPlan {
+    /// 操作描述
+    pub title: String,
+    pub changes: Vec<PlannedChange>,
+}
+
+impl OperationPlan {
+    /// 是否有需要执行的操作
+    pub fn has_changes(&self) -> bool {
+        self.changes.iter().any(|c| c.action != PlannedAction::Skip)
+    }
+
+    /// 各类操作的数量，如 `创建 2，停止 1`，无操作的类别省略
+    pub fn summary(&self) -> String {
+        let mut counts: Vec<(PlannedAction, usize)> = Vec::new();
+        for change in &self.changes {
+            match counts.iter_mut().find(|(action, _)| *action == change.action) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((change.action, 1)),
+            }
+        }
+        counts
+            .iter()
+            .map(|(action, count)| format!("{} {}", action.label(), count))
+            .collect::<Vec<_>>()
+            .join("，")
+    }
+}
+
+/// 一次扩容最多新建的读实例数
+pub const MAX_SCALE_OUT: u32 = 20;
+
+/// 扩容读实例的计划：名称、地址与运行参数中的 `{index}` 替换为序号（从1起），`{port}` 替换为起始端口加序号减1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadScaleOut {
+    pub count: u32,
+    pub name_prefix: String,
+    pub url_pattern: String,
+    pub start_port: u16,
+    /// 为空时新实例由外部管理，启动只更新状态
+    pub docker_run_params: String,
+}
+
+impl Default for ReadScaleOut {
+    fn default() -> Self {
+        Self {
+            count: 2,
+            name_prefix: "read".to_string(),
+            url_pattern: "http://localhost:{port}".to_string(),
+            start_port: 8001,
+            docker_run_params: String::new(),
+        }
+    }
+}
+
+impl ReadScaleOut {
+    /// 按计划生成中间层下的读实例，地址与已有后端或彼此重复时报错；
+    /// 超时与重试次数取中间层的调度配置，Docker主机与中间层相同
+    pub fn backends(&self, middleware: &MiddlewareContainer) -> anyhow::Result<Vec<BackendContainer>> {
+        if self.count == 0 || self.count > MAX_SCALE_OUT {
+            anyhow::bail!("扩容数量须在1到{}之间", MAX_SCALE_OUT);
+        }
+        if self.count > 1 && !self.url_pattern.contains("{port}") && !self.url_pattern.contains("{index}") {
+            anyhow::bail!("扩容多个实例时地址模板须包含 {{port}} 或 {{index}}");
+        }
+        if u32::from(self.start_port) + self.count - 1 > u32::from(u16::MAX) {
+            anyhow::bail!("端口超出范围: {} 起的 {} 个端口", self.start_port, self.count);
+        }
+        let mut backends: Vec<BackendContainer> = Vec::new();
+        for index in 1..=self.count {
+            let port = (self.start_port + (index - 1) as u16).to_string();
+            let fill = |pattern: &str| pattern.replace("{index}", &index.to_string()).replace("{port}", &port);
+            let url = fill(&self.url_pattern);
+            reqwest::Url::parse(&url).map_err(|e| anyhow::anyhow!("无效的地址 {}: {}", url, e))?;
+            if let Some(existing) = middleware.backend_containers.iter().chain(backends.iter()).find(|b| b.url == url) {
+                anyhow::bail!("地址 {} 已被后端 {} 使用", url, existing.name);
+            }
+            let docker_run_params = fill(&self.docker_run_params);
+            if !docker_run_params.trim().is_empty() {
+                ContainerSpec::parse(&docker_run_params).map_err(|e| anyhow::anyhow!("第 {} 个实例的运行参数无效: {:#}", index, e))?;
+            }
+            backends.push(BackendContainer {
+                name: format!("{}-{}", self.name_prefix.trim(), index),
+                url,
+                instance_type: "read".to_string(),
+                timeout: middleware.config.crud_api.timeout,
+                retries: middleware.config.crud_api.retries,
+                docker_run_params,
+                docker_host_id: middleware.docker_host_id.clone(),
+                ..BackendContainer::default()
+            });
+        }
+        Ok(backends)
+    }
+}
+
+/// 缩容时选择读实例的方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScaleInSelection {
+    /// 负载（CPU占用）最低的优先
+    #[default]
+    LeastLoaded,
+    /// 最后加入的优先
+    MostRecent,
+}
+
+impl ScaleInSelection {
+    pub const ALL: [ScaleInSelection; 2] = [ScaleInSelection::LeastLoaded, ScaleInSelection::MostRecent];
+
+    /// 显示名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            ScaleInSelection::LeastLoaded => "负载最低",
+            ScaleInSelection::MostRecent => "最近加入",
+        }
+    }
+
+    /// 从中间层的后端中选出 `count` 个待缩容的读实例，写实例与混合实例不参与选择；
+    /// 后端按加入顺序排列，`load` 为后端当前的负载
+    pub fn pick<'a>(&self, backends: &'a [BackendContainer], count: usize, load: impl Fn(&BackendContainer) -> f64) -> Vec<&'a BackendContainer> {
+        let mut reads: Vec<&BackendContainer> = backends.iter().filter(|b| b.instance_type == "read").collect();
+        match self {
+            ScaleInSelection::LeastLoaded => reads.sort_by(|a, b| load(a).total_cmp(&load(b))),
+            ScaleInSelection::MostRecent => reads.reverse(),
+        }
+        reads.truncate(count);
+        reads
+    }
+}
+
+/// 后台任务类型
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub enum JobKind {
+    /// 启动业务组，按启动顺序分批启动后端并检查中间层健康状态
+    StartGroup { group_id: String, group_name: String },
+    /// 停止业务组的所有容器
+    StopGroup { group_id: String, group_name: String },
+    /// 拉取Docker镜像
+    PullImage { image: String },
+    /// 对中间层加密接口进行压测
+    Benchmark {
+        /// 用于选用所在业务组的网络配置，旧记录中为空
+        #[serde(default)]
+        middleware_id: String,
+        middleware_name: String,
+        url: String,
+        timeout: u64,
+        requests: u32,
+    },
+    /// 重启业务组：一次重启后检查中间层健康状态，或逐个滚动重启后端
+    RestartGroup {
+        group_id: String,
+        group_name: String,
+        /// 旧记录中为空，视为一次重启
+        #[serde(default)]
+        mode: RestartMode,
+    },
+    /// 将已保存的配置推送到中间层服务
+    PushConfig { middleware_id: String, middleware_name: String },
+    /// 从文件读取密文，按调度策略分发到读实例并发解密
+    BulkDecrypt { middleware_id: String, middleware_name: String, input: String, output: String, workers: u32 },
+    /// 启动新建的读实例，全部通过健康探测后加入中间层的实例列表；失败或取消时停止并删除这些实例
+    ScaleOutReads { group_id: String, middleware_id: String, middleware_name: String, backend_ids: Vec<String> },
+    /// 将选中的读实例移出中间层的实例列表，等待进行中的请求完成后停止并删除
+    ScaleInReads { group_id: String, middleware_id: String, middleware_name: String, backend_ids: Vec<String> },
+    /// 升级业务组内容器的镜像：修改容器定义并拉取新镜像，运行中的容器按启动顺序重新创建
+    UpgradeGroup {
+        group_id: String,
+        group_name: String,
+        /// （容器ID, 目标镜像）
+        targets: Vec<(String, String)>,
+    },
+    /// 为业务组创建快照文件，可选包含命名卷的数据
+    SnapshotGroup { group_id: String, group_name: String, path: String, include_volumes: bool },
+    /// 由快照文件恢复业务组到指定主机
+    RestoreGroup {
+        path: String,
+        /// 目标Docker主机，本机为空
+        host_id: Option<String>,
+        host_name: String,
+        /// 恢复后的名称，为空时沿用快照中的名称
+        name: String,
+        restore_volumes: bool,
+    },
+}
+
+impl JobKind {
+    /// 任务描述
+    pub fn describe(&self) -> String {
+        match self {
+            JobKind::StartGroup { group_name, .. } => format!("启动业务组 {}", group_name),
+            JobKind::StopGroup { group_name, .. } => format!("停止业务组 {}", group_name),
+            JobKind::PullImage { image } => format!("拉取镜像 {}", image),
+            JobKind::Benchmark { middleware_name, requests, .. } => format!("压测 {} ({} 次请求)", middleware_name, requests),
+            JobKind::RestartGroup { group_name, mode: RestartMode::AllAtOnce, .. } => format!("重启业务组 {}", group_name),
+            JobKind::RestartGroup { group_name, mode: RestartMode::Rolling, .. } => format!("滚动重启业务组 {}", group_name),
+            JobKind::PushConfig { middleware_name, .. } => format!("推送配置到 {}", middleware_name),
+            JobKind::BulkDecrypt { middleware_name, input, workers, .. } => {
+                format!("批量解密 {} 经 {} ({} 个并发)", input, middleware_name, workers)
+            }
+            JobKind::ScaleOutReads { middleware_name, backend_ids, .. } => {
+                format!("扩容 {} 的读实例 ({} 个)", middleware_name, backend_ids.len())
+            }
+            JobKind::ScaleInReads { middleware_name, backend_ids, .. } => {
+                format!("缩容 {} 的读实例 ({} 个)", middleware_name, backend_ids.len())
+            }
+            JobKind::UpgradeGroup { group_name, targets, .. } => format!("升级业务组 {} 的镜像 ({} 个容器)", group_name, targets.len()),
+            JobKind::SnapshotGroup { group_name, path, .. } => format!("为业务组 {} 创建快照 {}", group_name, path),
+            JobKind::RestoreGroup { path, host_name, .. } => format!("从快照 {} 恢复业务组到{}", path, host_name),
+        }
+    }
+}
+
+/// 后台任务状态
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    /// 显示名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            JobStatus::Queued => "排队中",
+            JobStatus::Running => "运行中",
+            JobStatus::Succeeded => "成功",
+            JobStatus::Failed => "失败",
+            JobStatus::Cancelled => "已取消",
+        }
+    }
+
+}
+
+/// 任务失败后的自动重试策略
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// 最多执行次数（含首次）
+    pub max_attempts: u32,
+    /// 重试等待秒数，按已执行次数线性递增
+    pub backoff_secs: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            backoff_secs: 5,
+        }
+    }
+}
+
+/// 后台任务记录
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct JobRecord {
+    pub id: String,
+    pub kind: JobKind,
+    pub status: JobStatus,
+    pub policy: RetryPolicy,
+    /// 已执行次数
+    pub attempts: u32,
+    pub created_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    /// 最近一次执行的结果
+    pub message: String,
+    /// 最近保存的进度检查点，重新执行时从此处继续
+    #[serde(default)]
+    pub checkpoint: Option<JobCheckpoint>,
+    /// 提交任务时生成的追踪ID，任务发出的请求均携带此ID
+    #[serde(default)]
+    pub trace_id: Option<String>,
+    /// 以该操作人的业务组权限执行，如Webhook的所有者；为空时使用界面当前操作人的权限
+    #[serde(default)]
+    pub actor: Option<String>,
+}
+
+impl JobRecord {
+    /// 创建排队中的任务
+    pub fn new(kind: JobKind, policy: RetryPolicy) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            kind,
+            status: JobStatus::Queued,
+            policy,
+            attempts: 0,
+            created_at: Utc::now(),
+            finished_at: None,
+            message: String::new(),
+            checkpoint: None,
+            trace_id: None,
+            actor: None,
+        }
+    }
+}
+
+/// 长任务的进度检查点
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct JobCheckpoint {
+    /// 已完成的工作量
+    pub completed: u64,
+    pub total: u64,
+    /// 任务继续执行所需的中间结果，格式由任务类型决定
+    #[serde(default)]
+    pub data: serde_json::Value,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 状态变化的类别
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum TransitionField {
+    Status,
+    Health,
+}
+
+impl TransitionField {
+    /// 显示名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            TransitionField::Status => "状态",
+            TransitionField::Health => "健康状态",
+        }
+    }
+}
+
+/// 容器运行状态或健康状态的一次变化，取值为显示名称
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StatusTransition {
+    pub timestamp: DateTime<Utc>,
+    pub entity_id: String,
+    pub field: TransitionField,
+    pub from: String,
+    pub to: String,
+}
+
+/// 审计日志哈希链的起点，在开始为记录计算哈希时写入
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct AuditChainAnchor {
+    /// 开始计算哈希时已有的旧记录条数，只有日志开头的这些记录可以没有哈希
+    pub legacy: usize,
+    /// 旧记录依次折叠得到的哈希，作为第一条有哈希的记录的前一哈希
+    pub genesis: String,
+}
+
+/// 审计日志条目
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AuditEntry {
+    pub id: String,
+    pub timestamp: DateTime<Utc>,
+    /// 操作人
+    pub actor: String,
+    pub action: String,
+    pub entity_kind: Option<EntityKind>,
+    pub entity_id: Option<String>,
+    /// 界面发起操作时生成的追踪ID
+    #[serde(default)]
+    pub trace_id: Option<String>,
+    /// 存储时计算的链式哈希，包含前一条记录的哈希；旧版本写入的记录没有
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chain_hash: Option<String>,
+}
+
+impl AuditEntry {
+    /// 创建审计日志条目
+    pub fn new(actor: &str, action: &str, entity_kind: Option<EntityKind>, entity_id: Option<&str>) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            timestamp: Utc::now(),
+            actor: actor.to_string(),
+            action: action.to_string(),
+            entity_kind,
+            entity_id: entity_id.map(str::to_string),
+            trace_id: None,
+            chain_hash: None,
+        }
+    }
+}
+
+/// HTTP请求方法
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum HttpMethod {
+    Get,
+    Post,
+    Put,
+    Patch,
+    Delete,
+}
+
+impl HttpMethod {
+    /// 所有请求方法
+    pub const ALL: [HttpMethod; 5] = [
+        HttpMethod::Get,
+        HttpMethod::Post,
+        HttpMethod::Put,
+        HttpMethod::Patch,
+        HttpMethod::Delete,
+    ];
+
+    /// 显示名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            HttpMethod::Get => "GET",
+            HttpMethod::Post => "POST",
+            HttpMethod::Put => "PUT",
+            HttpMethod::Patch => "PATCH",
+            HttpMethod::Delete => "DELETE",
+        }
+    }
+}
+
+/// 已保存的调试请求
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SavedRequest {
+    pub id: String,
+    pub name: String,
+    pub method: HttpMethod,
+    /// 相对于中间层地址的路径
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Default for SavedRequest {
+    fn default() -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            name: "新请求".to_string(),
+            method: HttpMethod::Get,
+            path: "/health".to_string(),
+            headers: vec![("Content-Type".to_string(), "application/json".to_string())],
+            body: String::new(),
+        }
+    }
+}
+
+impl SavedRequest {
+    /// 是否为可能携带凭据的请求头
+    pub fn is_sensitive_header(name: &str) -> bool {
+        let name = name.to_lowercase();
+        ["authorization", "cookie", "token", "key", "secret"].iter().any(|s| name.contains(s))
+    }
+}
+
+/// 调试请求集合
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RequestCollection {
+    pub id: String,
+    pub name: String,
+    pub requests: Vec<SavedRequest>,
+}
+
+impl RequestCollection {
+    /// 创建空集合
+    pub fn new(name: &str) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            name: name.to_string(),
+            requests: Vec::new(),
+        }
+    }
+}
+
+/// 调试历史中请求内容的保存方式
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HistoryRedaction {
+    /// 保存原文，可直接重新发送
+    Plain,
+    /// 保存SHA-256摘要，可比对但无法还原
+    #[default]
+    Hashed,
+    /// 只保存长度
+    Redacted,
+}
+
+impl HistoryRedaction {
+    /// 所有保存方式
+    pub const ALL: [HistoryRedaction; 3] = [
+        HistoryRedaction::Plain,
+        HistoryRedaction::Hashed,
+        HistoryRedaction::Redacted,
+    ];
+
+    /// 显示名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            HistoryRedaction::Plain => "保存原文",
+            HistoryRedaction::Hashed => "保存摘要",
+            HistoryRedaction::Redacted => "完全隐藏",
+        }
+    }
+}
+
+/// 接口调试的历史记录
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PlaygroundHistoryEntry {
+    pub id: String,
+    pub timestamp: DateTime<Utc>,
+    pub middleware_id: String,
+    pub middleware_name: String,
+    /// 按保存方式处理过请求体与敏感请求头的请求
+    pub request: SavedRequest,
+    /// 请求内容是否为原文，可直接重新发送
+    pub replayable: bool,
+    pub status: Option<u16>,
+    pub elapsed_ms: Option<f64>,
+    /// 响应体，过长时截断
+    pub response: String,
+    pub error: Option<String>,
+    /// 请求携带的追踪ID
+    #[serde(default)]
+    pub trace_id: Option<String>,
+}
+
+impl PlaygroundHistoryEntry {
+    /// 是否匹配搜索关键字（不区分大小写）
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return true;
+        }
+        [
+            self.middleware_name.as_str(),
+            self.request.name.as_str(),
+            self.request.method.label(),
+            self.request.path.as_str(),
+            self.request.body.as_str(),
+            self.response.as_str(),
+            self.error.as_deref().unwrap_or_default(),
+            self.trace_id.as_deref().unwrap_or_default(),
+        ]
+        .iter()
+        .any(|field| field.to_lowercase().contains(&query))
+    }
+}
+
+/// 白名单中允许通过Agent执行的命令
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AllowedCommand {
+    pub id: String,
+    pub name: String,
+    pub command: String,
+}
+
+impl AllowedCommand {
+    /// 创建白名单命令
+    pub fn new(name: &str, command: &str) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            name: name.to_string(),
+            command: command.to_string(),
+        }
+    }
+}
+
+/// 界面配置，按角色隐藏无关的页面与操作
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UiProfile {
+    /// 所有页面与操作
+    #[default]
+    Full,
+    /// 除加密设置与角色管理外的所有页面与操作
+    Operator,
+    /// 只能查看监控、日志与审计日志
+    Auditor,
+}
+
+impl UiProfile {
+    /// 所有界面配置，由宽到严排列
+    pub const ALL: [UiProfile; 3] = [
+        UiProfile::Full,
+        UiProfile::Operator,
+        UiProfile::Auditor,
+    ];
+
+    /// 显示名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            UiProfile::Full => "完整视图",
+            UiProfile::Operator => "运维视图",
+            UiProfile::Auditor => "审计视图",
+        }
+    }
+
+    /// 说明
+    pub fn description(&self) -> &'static str {
+        match self {
+            UiProfile::Full => "显示所有页面与操作",
+            UiProfile::Operator => "隐藏中间层的加密设置、原始配置编辑与角色管理",
+            UiProfile::Auditor => "只显示监控与日志页面，审计日志在日志页面中",
+        }
+    }
+
+    /// 可见范围不超过另一个配置，角色只能切换到其分配的配置或更严的配置
+    pub fn within(&self, limit: UiProfile) -> bool {
+        let rank = |p: UiProfile| match p {
+            UiProfile::Full => 2,
+            UiProfile::Operator => 1,
+            UiProfile::Auditor => 0,
+        };
+        rank(*self) <= rank(limit)
+    }
+
+    /// 能否修改中间层的加密设置，包括加密字段、配置模板与原始配置
+    pub fn can_edit_encryption(&self) -> bool {
+        *self == UiProfile::Full
+    }
+
+    /// 能否分配界面角色
+    pub fn can_manage_roles(&self) -> bool {
+        *self == UiProfile::Full
+    }
+}
+
+/// 分配给操作人的界面配置
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct UiRoleAssignment {
+    pub actor: String,
+    pub profile: UiProfile,
+    /// 可管理的业务组ID，为空时可管理所有业务组；其他业务组只读
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<String>,
+}
+
+/// 操作人可使用的最宽界面配置，未分配的操作人使用默认配置
+pub fn ui_profile_for(assignments: &[UiRoleAssignment], default: UiProfile, actor: &str) -> UiProfile {
+    assignments
+        .iter()
+        .find(|a| a.actor == actor)
+        .map_or(default, |a| a.profile)
+}
+
+/// 操作人对业务组的管理权限，由界面角色分配得出
+#[derive(Debug, Clone, Default)]
+pub struct GroupAccess {
+    pub actor: String,
+    pub profile: UiProfile,
+    /// 可管理的业务组ID，为空时不限
+    pub groups: Vec<String>,
+}
+
+impl GroupAccess {
+    /// 操作人的权限，未分配的操作人使用默认配置且不限业务组
+    pub fn for_actor(assignments: &[UiRoleAssignment], default: UiProfile, actor: &str) -> Self {
+        let assignment = assignments.iter().find(|a| a.actor == actor);
+        Self {
+            actor: actor.to_string(),
+            profile: assignment.map_or(default, |a| a.profile),
+            groups: assignment.map(|a| a.groups.clone()).unwrap_or_default(),
+        }
+    }
+
+    /// 能否修改业务组及其中的中间层与后端，审计视图对所有业务组只读
+    pub fn can_manage(&self, group_id: &str) -> bool {
+        self.profile != UiProfile::Auditor && (self.groups.is_empty() || self.groups.iter().any(|g| g == group_id))
+    }
+}
+
+/// 一个运行中的管理器实例的会话，启动时登录，退出时注销
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct UiSession {
+    /// 实例ID
+    pub id: String,
+    pub actor: String,
+    /// 实例所在的主机名
+    pub host: String,
+    pub started_at: DateTime<Utc>,
+    /// 最近一次界面操作的时间
+    pub last_input: DateTime<Utc>,
+    /// 最近一次心跳的时间，长时间未更新的会话视为已退出
+    pub heartbeat: DateTime<Utc>,
+    /// 被强制登出时记录执行的操作人，实例在下次心跳时退出
+    #[serde(default)]
+    pub revoked_by: Option<String>,
+}
+
+impl UiSession {
+    /// 已登录的时长
+    pub fn duration(&self, now: DateTime<Utc>) -> chrono::Duration {
+        now - self.started_at
+    }
+
+    /// 距最近一次界面操作的空闲时长
+    pub fn idle(&self, now: DateTime<Utc>) -> chrono::Duration {
+        now - self.last_input
+    }
+}
+
+/// 本地用户的密码策略
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub require_uppercase: bool,
+    pub require_lowercase: bool,
+    pub require_digit: bool,
+    /// 至少包含一个字母与数字以外的字符
+    pub require_symbol: bool,
+    /// 密码有效天数，过期后登录时须修改，0表示不过期
+    pub expiry_days: u32,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            min_length: 12,
+            require_uppercase: true,
+            require_lowercase: true,
+            require_digit: true,
+            require_symbol: false,
+            expiry_days: 90,
+        }
+    }
+}
+
+impl PasswordPolicy {
+    /// 密码不满足的各项要求，为空时满足策略
+    pub fn violations(&self, password: &str) -> Vec<String> {
+        let mut violations = Vec::new();
+        if password.chars().count() < self.min_length {
+            violations.push(format!("长度至少 {} 个字符", self.min_length));
+        }
+        if self.require_uppercase && !password.chars().any(|c| c.is_uppercase()) {
+            violations.push("须包含大写字母".to_string());
+        }
+        if self.require_lowercase && !password.chars().any(|c| c.is_lowercase()) {
+            violations.push("须包含小写字母".to_string());
+        }
+        if self.require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+            violations.push("须包含数字".to_string());
+        }
+        if self.require_symbol && !password.chars().any(|c| !c.is_alphanumeric()) {
+            violations.push("须包含符号".to_string());
+        }
+        violations
+    }
+
+    /// 在某时间修改的密码是否已过期
+    pub fn is_expired(&self, changed_at: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+        self.expiry_days > 0 && now - changed_at >= chrono::Duration::days(self.expiry_days as i64)
+    }
+}
+
+/// 本地用户，配置了本地用户后启动时须登录，用户名作为操作人参与界面角色分配与审计
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct LocalUser {
+    pub name: String,
+    /// 密码摘要，Argon2的PHC字符串
+    pub password_hash: String,
+    pub password_changed_at: DateTime<Utc>,
+    /// 新建或重置密码后须在首次登录时修改
+    #[serde(default)]
+    pub must_change: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Agent部署设置，对所有中间层生效
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct AgentSettings {
+    /// 本机上的Agent程序路径
+    pub binary_path: String,
+    /// 复制到中间层容器或主机上的目录
+    pub install_dir: String,
+    /// Agent监听的端口，部署后在中间层地址的此端口上检查响应
+    pub port: u16,
+    /// 启动参数，`{port}` 替换为监听端口
+    pub args: String,
+    /// 经SSH部署到主机时的登录用户
+    pub ssh_user: String,
+    pub ssh_port: u16,
+    /// SSH私钥路径，为空时使用ssh的默认配置
+    pub ssh_key: String,
+}
+
+impl Default for AgentSettings {
+    fn default() -> Self {
+        Self {
+            binary_path: String::new(),
+            install_dir: "/usr/local/bin".to_string(),
+            port: 9100,
+            args: "--port {port}".to_string(),
+            ssh_user: "root".to_string(),
+            ssh_port: 22,
+            ssh_key: String::new(),
+        }
+    }
+}
+
+/// Webhook触发的操作
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub enum WebhookAction {
+    /// 重启业务组
+    RestartGroup { group_id: String },
+    /// 将已保存的配置推送到中间层
+    PushConfig { middleware_id: String },
+}
+
+impl WebhookAction {
+    /// 操作类型名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            WebhookAction::RestartGroup { .. } => "重启业务组",
+            WebhookAction::PushConfig { .. } => "推送中间层配置",
+        }
+    }
+}
+
+/// 外部系统触发操作的Webhook，请求体需用独立密钥签名
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Webhook {
+    pub id: String,
+    pub name: String,
+    /// HMAC-SHA256签名密钥
+    pub secret: String,
+    pub action: WebhookAction,
+    pub enabled: bool,
+    /// 创建Webhook的操作人，触发的任务以其业务组权限执行；旧记录为空，按未分配操作人的默认配置判断
+    #[serde(default)]
+    pub owner: String,
+}
+
+impl Webhook {
+    /// 创建Webhook并生成随机密钥，所有者在添加时记录
+    pub fn new(name: &str, action: WebhookAction) -> Self {
+        let secret: [u8; 32] = rand::random();
+        Self {
+            id: Uuid::new_v4().simple().to_string(),
+            name: name.to_string(),
+            secret: hex::encode(secret),
+            action,
+            enabled: true,
+            owner: String::new(),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn backend(id: &str, instance_type: &str, depends_on: &[&str]) -> BackendContainer {
+        BackendContainer {
+            id: id.to_string(),
+            name: id.to_string(),
+            instance_type: instance_type.to_string(),
+            depends_on: depends_on.iter().map(|d| d.to_string()).collect(),
+            ..BackendContainer::default()
+        }
+    }
+
+    fn start_ids(group: &BusinessGroup) -> anyhow::Result<Vec<String>> {
+        Ok(group.start_order()?.iter().map(|item| item.id().to_string()).collect())
+    }
+
+    #[test]
+    fn password_policy_lists_each_violation() {
+        let policy = PasswordPolicy::default();
+        assert_eq!(policy.violations("short").len(), 3);
+        assert!(policy.violations("Correct-Horse-7").is_empty());
+
+        let symbol = PasswordPolicy { require_symbol: true, ..PasswordPolicy::default() };
+        assert_eq!(symbol.violations("CorrectHorse7x"), vec!["须包含符号".to_string()]);
+        // 长度按字符而非字节计算
+        let short = PasswordPolicy { min_length: 4, require_uppercase: false, require_digit: false, ..PasswordPolicy::default() };
+        assert!(!short.violations("密码").is_empty());
+    }
+
+    #[test]
+    fn password_expires_after_policy_days() {
+        let changed = Utc::now();
+        let policy = PasswordPolicy { expiry_days: 90, ..PasswordPolicy::default() };
+        assert!(!policy.is_expired(changed, changed + chrono::Duration::days(89)));
+        assert!(policy.is_expired(changed, changed + chrono::Duration::days(90)));
+
+        let never = PasswordPolicy { expiry_days: 0, ..PasswordPolicy::default() };
+        assert!(!never.is_expired(changed, changed + chrono::Duration::days(3650)));
+    }
+
+    #[test]
+    fn start_order_puts_dependencies_first() {
+        let group = BusinessGroup {
+            middlewares: vec![MiddlewareContainer {
+                id: "mw".to_string(),
+                backend_containers: vec![backend("owned", "mixed", &["db"])],
+                ..MiddlewareContainer::default()
+            }],
+            backend_containers: vec![backend("cache", "mixed", &["cache"]), backend("db", "mixed", &[])],
+            ..BusinessGroup::default()
+        };
+        // 自身依赖被忽略；中间层排在其下属后端之后
+        assert_eq!(start_ids(&group).unwrap(), ["cache", "db", "owned", "mw"]);
+    }
+
+    #[test]
+    fn start_order_reports_dependency_cycle() {
+        let group = BusinessGroup {
+            backend_containers: vec![
+                backend("a", "mixed", &[]),
+                backend("b", "mixed", &["c"]),
+                backend("c", "mixed", &["d"]),
+                backend("d", "mixed", &["b"]),
+            ],
+            ..BusinessGroup::default()
+        };
+        let error = start_ids(&group).unwrap_err().to_string();
+        assert!(error.starts_with("启动依赖存在循环"), "{}", error);
+        // 按启动方向显示：被依赖的容器在前
+        assert!(error.contains("b → d → c → b"), "{}", error);
+        assert!(!error.contains('a'), "{}", error);
+    }
+
+    #[test]
+    fn scale_in_picks_only_read_instances() {
+        let backends = vec![
+            backend("r1", "read", &[]),
+            backend("w", "write", &[]),
+            backend("r2", "read", &[]),
+            backend("m", "mixed", &[]),
+            backend("r3", "read", &[]),
+        ];
+        let load = |b: &BackendContainer| match b.id.as_str() {
+            "r1" => 0.5,
+            "r2" => 0.1,
+            "r3" => 0.9,
+            _ => 0.0,
+        };
+        let ids = |picked: Vec<&BackendContainer>| picked.iter().map(|b| b.id.clone()).collect::<Vec<_>>();
+
+        assert_eq!(ids(ScaleInSelection::LeastLoaded.pick(&backends, 2, load)), ["r2", "r1"]);
+        assert_eq!(ids(ScaleInSelection::MostRecent.pick(&backends, 2, load)), ["r3", "r2"]);
+        assert_eq!(ids(ScaleInSelection::MostRecent.pick(&backends, 10, load)), ["r3", "r2", "r1"]);
+        assert!(ScaleInSelection::LeastLoaded.pick(&backends, 0, load).is_empty());
+    }
+
+    #[test]
+    fn container_spec_parses_run_params() {
+        let spec = ContainerSpec::parse(
+            "docker run -d --rm --name api -p 8080:80 -p 127.0.0.1:5353:53/udp -e A=1 --env=B=x=y -v data:/data:ro \
+             --network=net1 --restart on-failure:3 --cpus 1.5 -m 512m nginx:1.25 nginx -g 'daemon off;'",
+        )
+        .unwrap();
+        assert_eq!(spec.name, "api");
+        assert_eq!(spec.image, "nginx:1.25");
+        assert_eq!(spec.command, ["nginx", "-g", "daemon off;"]);
+        assert_eq!(spec.ports.iter().map(PortMapping::to_arg).collect::<Vec<_>>(), ["8080:80", "127.0.0.1:5353:53/udp"]);
+        assert_eq!(spec.env, [EnvVar { key: "A".to_string(), value: "1".to_string() }, EnvVar { key: "B".to_string(), value: "x=y".to_string() }]);
+        assert_eq!(spec.volumes, [VolumeMount { source: "data".to_string(), target: "/data".to_string(), read_only: true }]);
+        assert_eq!(spec.network, "net1");
+        assert_eq!(spec.restart, ContainerRestartPolicy::OnFailure);
+        assert_eq!(spec.nano_cpus, Some(1_500_000_000));
+        assert_eq!(spec.memory, Some(512 * BYTES_PER_MB));
+
+        // 转换回参数后解析得到相同的定义
+        assert_eq!(ContainerSpec::parse(&spec.to_params()).unwrap(), spec);
+        assert_eq!(ContainerSpec::parse("redis:7").unwrap(), ContainerSpec { image: "redis:7".to_string(), ..ContainerSpec::default() });
+    }
+
+    #[test]
+    fn container_spec_rejects_bad_params() {
+        for params in [
+            "",
+            "docker run -d --name api",
+            "--privileged nginx",
+            "--name",
+            "-e A=1 'nginx",
+            "--restart sometimes nginx",
+            "--cpus 0 nginx",
+            "--cpus many nginx",
+            "-m 1t nginx",
+            "-p 80 nginx",
+            "-v /data nginx",
+        ] {
+            assert!(ContainerSpec::parse(params).is_err(), "{}", params);
+        }
+    }
+
+    #[test]
+    fn port_mapping_parses_and_round_trips() {
+        for (value, host_ip, host_port, container_port, protocol) in [
+            ("8080:80", "", 8080, 80, "tcp"),
+            ("0:9100", "", 0, 9100, "tcp"),
+            ("127.0.0.1:5353:53/udp", "127.0.0.1", 5353, 53, "udp"),
+            ("[::1]:8443:443", "::1", 8443, 443, "tcp"),
+        ] {
+            let port = PortMapping::parse(value).unwrap();
+            assert_eq!(
+                (port.host_ip.as_str(), port.host_port, port.container_port, port.protocol.as_str()),
+                (host_ip, host_port, container_port, protocol),
+                "{}",
+                value,
+            );
+            assert_eq!(port.to_arg(), value);
+        }
+    }
+
+    #[test]
+    fn port_mapping_rejects_bad_ports() {
+        for value in ["80", "70000:80", "8080:-1", "http:80", "8080:", ":80", "1.2.3.4:5:6:7", "[::1:8080:80", "[::1]:8080", "8080:80/"] {
+            assert!(PortMapping::parse(value).is_err(), "{}", value);
+        }
+    }
+
+    #[test]
+    fn volume_mount_parses_paths_with_colons() {
+        let mount = |source: &str, target: &str, read_only: bool| VolumeMount { source: source.to_string(), target: target.to_string(), read_only };
+        for (value, expected) in [
+            ("data:/data", mount("data", "/data", false)),
+            ("./conf:/etc/conf:ro", mount("./conf", "/etc/conf", true)),
+            ("/srv:/srv:rw", mount("/srv", "/srv", false)),
+            (r"C:\data:/data", mount(r"C:\data", "/data", false)),
+            (r"C:\data:C:\app:ro", mount(r"C:\data", r"C:\app", true)),
+            ("d:/projects:/work", mount("d:/projects", "/work", false)),
+            // 单个字母的卷名
+            ("a:/data", mount("a", "/data", false)),
+            ("a:/data:ro", mount("a", "/data", true)),
+        ] {
+            let parsed = VolumeMount::parse(value).unwrap();
+            assert_eq!(parsed, expected, "{}", value);
+            assert_eq!(VolumeMount::parse(&parsed.to_arg()).unwrap(), parsed, "{}", value);
+        }
+    }
+
+    #[test]
+    fn volume_mount_rejects_ambiguous_or_incomplete_values() {
+        for value in ["/data", "/tmp/a:b:/data", ":/data", "data:", "data:/x:readonly", "data:/x:ro:rw", r"C:\data"] {
+            assert!(VolumeMount::parse(value).is_err(), "{}", value);
+        }
+    }
+
+    fn hosts(entries: &[DnsOverride]) -> Vec<(&str, &str)> {
+        entries.iter().map(|e| (e.host.as_str(), e.address.as_str())).collect()
+    }
+
+    #[test]
+    fn hosts_import_skips_comments_and_reads_ipv6() {
+        let text = "# 本地解析\n\n127.0.0.1\tlocalhost api.local  # 本机\n::1 ip6-localhost\n   # 缩进的注释\nfd00::10 DB.internal cache.internal\n";
+        let entries = NetworkProfile::parse_hosts(text).unwrap();
+        assert_eq!(hosts(&entries), [
+            ("localhost", "127.0.0.1"),
+            ("api.local", "127.0.0.1"),
+            ("ip6-localhost", "::1"),
+            ("db.internal", "fd00::10"),
+            ("cache.internal", "fd00::10"),
+        ]);
+    }
+
+    #[test]
+    fn hosts_import_keeps_first_duplicate() {
+        let entries = NetworkProfile::parse_hosts("10.0.0.1 db\n10.0.0.2 DB other\n10.0.0.3 db\n").unwrap();
+        assert_eq!(hosts(&entries), [("db", "10.0.0.1"), ("other", "10.0.0.2")]);
+
+        // 合并时已有的主机名替换地址，不重复添加
+        let mut profile = NetworkProfile::new("测试");
+        profile.dns_overrides.push(DnsOverride { host: "Other".to_string(), address: "192.168.0.9".to_string() });
+        assert_eq!(profile.merge_hosts(entries), 1);
+        assert_eq!(hosts(&profile.dns_overrides), [("Other", "10.0.0.2"), ("db", "10.0.0.1")]);
+        assert_eq!(profile.resolve("DB"), Some("10.0.0.1"));
+    }
+
+    #[test]
+    fn hosts_import_rejects_bad_lines() {
+        for text in ["10.0.0.1\n", "db 10.0.0.1\n", "10.0.0.256 db\n", "fe80::1%eth0 db\n", "127.0.0.1 ok\n10.0.0 bad\n"] {
+            assert!(NetworkProfile::parse_hosts(text).is_err(), "{:?}", text);
+        }
+        let error = NetworkProfile::parse_hosts("127.0.0.1 ok\n# 注释\n10.0.0.1\n").unwrap_err().to_string();
+        assert!(error.contains("第 3 行"), "{}", error);
+    }
+
+    #[test]
+    fn typed_lookups_check_scope() {
+        let mut state = AppState::default();
+        state.business_groups.push(BusinessGroup {
+            id: "g-1".to_string(),
+            middlewares: vec![MiddlewareContainer { id: "mw".to_string(), backend_containers: vec![backend("b-mw", "mixed", &[])], ..MiddlewareContainer::default() }],
+            backend_containers: vec![backend("b-group", "mixed", &[])],
+            ..BusinessGroup::default()
+        });
+        state.reindex();
+        let (group, middleware) = (GroupId::from("g-1"), MiddlewareId::from("mw"));
+        assert!(state.middleware(&group, &middleware).is_ok());
+        assert!(state.backend(&group, Some(&middleware), &BackendId::from("b-mw")).is_ok());
+        assert!(state.backend(&group, None, &BackendId::from("b-group")).is_ok());
+        // 后端不在所给的中间层下时按不存在处理
+        assert!(state.backend(&group, Some(&middleware), &BackendId::from("b-group")).is_err());
+        assert!(state.group(&GroupId::from("mw")).is_err());
+
+        // 新类型与字符串的序列化结果相同
+        assert_eq!(serde_json::to_string(&group).unwrap(), "\"g-1\"");
+        assert_eq!(serde_json::from_str::<BackendId>("\"b-mw\"").unwrap(), BackendId::from("b-mw"));
     }
 }
@@ -0,0 +1,114 @@
+use anyhow::Result;
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::{Algorithm, Argon2, Params};
+use std::sync::OnceLock;
+
+/// 密码摘要的算法，用于在用户列表中提示无法识别的摘要
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashScheme {
+    Argon2,
+    Unknown,
+}
+
+impl HashScheme {
+    /// 显示名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            HashScheme::Argon2 => "Argon2id",
+            HashScheme::Unknown => "无法识别",
+        }
+    }
+}
+
+/// 以Argon2id与随机盐计算密码摘要，结果为PHC字符串
+pub fn hash(password: &str) -> Result<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| anyhow::anyhow!("计算密码摘要失败: {}", e))
+}
+
+/// 以Argon2摘要校验密码
+pub fn verify(stored: &str, password: &str) -> Result<bool> {
+    let parsed = PasswordHash::new(stored).map_err(|e| anyhow::anyhow!("密码摘要格式错误: {}", e))?;
+    Ok(Argon2::default().verify_password(password.as_bytes(), &parsed).is_ok())
+}
+
+/// 对固定的摘要校验一次密码并丢弃结果，用户不存在时调用，使其耗时与密码错误相同，
+/// 不能据响应时间判断用户是否存在
+pub fn verify_dummy(password: &str) {
+    static DUMMY: OnceLock<String> = OnceLock::new();
+    let dummy = DUMMY.get_or_init(|| hash("dummy-password").unwrap_or_default());
+    let _ = verify(dummy, password);
+}
+
+/// 摘要的算法
+pub fn scheme(stored: &str) -> HashScheme {
+    match PasswordHash::new(stored) {
+        Ok(parsed) if parsed.algorithm.as_str().starts_with("argon2") => HashScheme::Argon2,
+        _ => HashScheme::Unknown,
+    }
+}
+
+/// 摘要是否需要在登录成功后重新计算：非Argon2id变体或参数低于当前默认值
+pub fn needs_upgrade(stored: &str) -> bool {
+    let Ok(parsed) = PasswordHash::new(stored) else {
+        return true;
+    };
+    if parsed.algorithm != Algorithm::Argon2id.ident() {
+        return true;
+    }
+    let Ok(params) = Params::try_from(&parsed) else {
+        return true;
+    };
+    let current = Params::default();
+    params.m_cost() < current.m_cost() || params.t_cost() < current.t_cost() || params.p_cost() < current.p_cost()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use argon2::Version;
+
+    /// 以指定算法与参数计算摘要，模拟旧版本写入的摘要
+    fn hash_with(algorithm: Algorithm, params: Params, password: &str) -> String {
+        let salt = SaltString::generate(&mut OsRng);
+        Argon2::new(algorithm, Version::V0x13, params)
+            .hash_password(password.as_bytes(), &salt)
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn hash_round_trips_with_random_salt() {
+        let first = hash("Correct-Horse-7").unwrap();
+        let second = hash("Correct-Horse-7").unwrap();
+        assert_ne!(first, second);
+        assert!(verify(&first, "Correct-Horse-7").unwrap());
+        assert!(verify(&second, "Correct-Horse-7").unwrap());
+        assert!(!verify(&first, "correct-horse-7").unwrap());
+        assert_eq!(scheme(&first), HashScheme::Argon2);
+        assert!(!needs_upgrade(&first));
+    }
+
+    #[test]
+    fn malformed_hash_is_rejected() {
+        assert!(verify("5f4dcc3b5aa765d61d8327deb882cf99", "password").is_err());
+        assert_eq!(scheme("5f4dcc3b5aa765d61d8327deb882cf99"), HashScheme::Unknown);
+        assert!(needs_upgrade("5f4dcc3b5aa765d61d8327deb882cf99"));
+    }
+
+    #[test]
+    fn weaker_hashes_need_upgrade() {
+        let weak = Params::new(Params::MIN_M_COST, 1, 1, None).unwrap();
+        let weak_id = hash_with(Algorithm::Argon2id, weak, "Correct-Horse-7");
+        assert!(verify(&weak_id, "Correct-Horse-7").unwrap());
+        assert!(needs_upgrade(&weak_id));
+
+        let argon2i = hash_with(Algorithm::Argon2i, Params::default(), "Correct-Horse-7");
+        assert_eq!(scheme(&argon2i), HashScheme::Argon2);
+        assert!(needs_upgrade(&argon2i));
+    }
+}
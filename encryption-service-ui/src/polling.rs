@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use crate::api::{ApiClient, ApiClientConfig, HealthCheckResponse};
+use crate::models::{ContainerStatus, MiddlewareId};
+use crate::repaint::{self, RepaintSource};
+use crate::state::StateStore;
+use crate::tasks;
+
+/// 轮询结果的缓存有效期，有效期内的轮询直接使用上次的响应
+pub const POLL_TTL: Duration = Duration::from_secs(5);
+
+/// 可轮询的中间层接口
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PollEndpoint {
+    /// `GET /health` 返回的服务状态
+    Status,
+}
+
+/// 中间层接口的响应
+#[derive(Debug)]
+pub enum PollResponse {
+    Status(HealthCheckResponse),
+}
+
+/// 一次轮询的结果，多个订阅共享同一份
+#[derive(Debug, Clone)]
+pub struct Polled {
+    pub fetched_at: Instant,
+    pub result: Arc<Result<PollResponse, String>>,
+}
+
+/// 轮询的统计：实际发出的请求、因相同请求进行中而合并的轮询、命中缓存的轮询
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PollStats {
+    pub requests: u64,
+    pub coalesced: u64,
+    pub cache_hits: u64,
+}
+
+/// 轮询的目标：（中间层ID, 接口）
+type PollKey = (String, PollEndpoint);
+
+struct PollEntry {
+    subscribers: usize,
+    in_flight: bool,
+    last: Option<Polled>,
+}
+
+#[derive(Default)]
+struct Shared {
+    entries: HashMap<PollKey, PollEntry>,
+    stats: PollStats,
+}
+
+fn lock(shared: &Mutex<Shared>) -> MutexGuard<'_, Shared> {
+    shared.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// 轮询协调器：多个界面面板订阅同一中间层接口时，进行中的相同请求只发出一次，
+/// 最近的响应在 [`POLL_TTL`] 内直接复用，面板数量不会成倍增加中间层的负载。
+/// 请求在共享的异步运行时上执行，不阻塞界面线程；没有订阅的接口不再缓存。
+#[derive(Clone)]
+pub struct PollCoordinator {
+    state: StateStore,
+    shared: Arc<Mutex<Shared>>,
+    ttl: Duration,
+}
+
+/// 对一个中间层接口的订阅，释放后不再计入订阅数
+pub struct Subscription {
+    key: PollKey,
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl Subscription {
+    /// 最近一次轮询的结果，尚未返回时为空
+    pub fn latest(&self) -> Option<Polled> {
+        lock(&self.shared).entries.get(&self.key).and_then(|e| e.last.clone())
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        let mut shared = lock(&self.shared);
+        if let Some(entry) = shared.entries.get_mut(&self.key) {
+            entry.subscribers = entry.subscribers.saturating_sub(1);
+            // 进行中的请求返回时再移除
+            if entry.subscribers == 0 && !entry.in_flight {
+                shared.entries.remove(&self.key);
+            }
+        }
+    }
+}
+
+impl PollCoordinator {
+    /// 创建轮询协调器
+    pub fn new(state: StateStore) -> Self {
+        Self {
+            state,
+            shared: Arc::new(Mutex::new(Shared::default())),
+            ttl: POLL_TTL,
+        }
+    }
+    
+    /// 订阅中间层接口，之后由 [`Self::poll`] 按需刷新
+    pub fn subscribe(&self, middleware_id: &str, endpoint: PollEndpoint) -> Subscription {
+        let key = (middleware_id.to_string(), endpoint);
+        lock(&self.shared)
+            .entries
+            .entry(key.clone())
+            .or_insert(PollEntry { subscribers: 0, in_flight: false, last: None })
+            .subscribers += 1;
+        Subscription { key, shared: self.shared.clone() }
+    }
+    
+    /// 刷新订阅的接口：缓存未过期或相同请求进行中时不再发出请求
+    pub fn poll(&self, subscription: &Subscription) {
+        if !self.begin(&subscription.key) {
+            return;
+        }
+        
+        let (middleware_id, endpoint) = subscription.key.clone();
+        let config = self.state.read(|s| {
+            s.find_middleware(&MiddlewareId::from(&middleware_id))
+                .filter(|(_, m)| m.status == ContainerStatus::Running)
+                .map(|(_, m)| ApiClientConfig {
+                    base_url: m.api_base_url(),
+                    timeout: m.config.crud_api.timeout,
+                    network: s.network_for(&middleware_id),
+                    trace_id: None,
+                })
+        });
+        let shared = self.shared.clone();
+        let key = subscription.key.clone();
+        tasks::spawn(async move {
+            let result = match config {
+                None => Err("中间层未运行".to_string()),
+                Some(config) => {
+                    let response = match ApiClient::new(config) {
+                        Ok(client) => match endpoint {
+                            PollEndpoint::Status => client.get_status().await.map(PollResponse::Status),
+                        },
+                        Err(e) => Err(e),
+                    };
+                    response.map_err(|e| format!("{:#}", e))
+                }
+            };
+            Self::finish(&shared, &key, result);
+            repaint::request(RepaintSource::Poll);
+        });
+    }
+    
+    /// 登记一次轮询，需要发出请求时标记为进行中并返回 `true`
+    fn begin(&self, key: &PollKey) -> bool {
+        let mut shared = lock(&self.shared);
+        let Shared { entries, stats } = &mut *shared;
+        let Some(entry) = entries.get_mut(key) else {
+            return false;
+        };
+        if entry.in_flight {
+            stats.coalesced += 1;
+            return false;
+        }
+        if entry.last.as_ref().is_some_and(|last| last.fetched_at.elapsed() < self.ttl) {
+            stats.cache_hits += 1;
+            return false;
+        }
+        entry.in_flight = true;
+        stats.requests += 1;
+        true
+    }
+    
+    /// 记录请求的结果，订阅在请求期间全部释放时移除该接口
+    fn finish(shared: &Mutex<Shared>, key: &PollKey, result: Result<PollResponse, String>) {
+        let mut shared = lock(shared);
+        if let Some(entry) = shared.entries.get_mut(key) {
+            entry.in_flight = false;
+            entry.last = Some(Polled { fetched_at: Instant::now(), result: Arc::new(result) });
+            if entry.subscribers == 0 {
+                shared.entries.remove(key);
+            }
+        }
+    }
+    
+    /// 轮询统计与当前被订阅的接口数
+    pub fn stats(&self) -> (PollStats, usize) {
+        let shared = lock(&self.shared);
+        (shared.stats, shared.entries.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ConfigManager;
+    use crate::events::EventBus;
+
+    fn coordinator() -> PollCoordinator {
+        let path = std::env::temp_dir().join(format!("encryption-service-ui-polling-{}.json", uuid::Uuid::new_v4()));
+        let (state, _) = StateStore::load(ConfigManager::new(path.to_string_lossy().to_string()), EventBus::new());
+        PollCoordinator::new(state)
+    }
+
+    fn answer() -> Result<PollResponse, String> {
+        Err("中间层未运行".to_string())
+    }
+
+    #[test]
+    fn polls_during_a_request_are_coalesced() {
+        let polls = coordinator();
+        let subscription = polls.subscribe("mw", PollEndpoint::Status);
+        assert!(polls.begin(&subscription.key));
+        assert!(!polls.begin(&subscription.key));
+        let (stats, _) = polls.stats();
+        assert_eq!((stats.requests, stats.coalesced, stats.cache_hits), (1, 1, 0));
+    }
+
+    #[test]
+    fn polls_within_ttl_use_the_cached_response() {
+        let polls = coordinator();
+        let subscription = polls.subscribe("mw", PollEndpoint::Status);
+        assert!(polls.begin(&subscription.key));
+        PollCoordinator::finish(&polls.shared, &subscription.key, answer());
+        assert!(subscription.latest().is_some());
+        assert!(!polls.begin(&subscription.key));
+        let (stats, _) = polls.stats();
+        assert_eq!((stats.requests, stats.coalesced, stats.cache_hits), (1, 0, 1));
+    }
+
+    #[test]
+    fn entry_outlives_last_subscriber_until_request_returns() {
+        let polls = coordinator();
+        let subscription = polls.subscribe("mw", PollEndpoint::Status);
+        let key = subscription.key.clone();
+        assert!(polls.begin(&key));
+        drop(subscription);
+        assert_eq!(polls.stats().1, 1);
+        PollCoordinator::finish(&polls.shared, &key, answer());
+        assert_eq!(polls.stats().1, 0);
+    }
+}
@@ -0,0 +1,122 @@
+use anyhow::{Context, Result};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use crate::api::{ApiClient, ApiClientConfig};
+use crate::docker;
+use crate::models::{AppState, BackendContainer, BusinessGroup, DockerHost, HealthProbe, MiddlewareContainer, NetworkProfile, ProbeKind};
+use crate::tasks;
+use crate::tunnel;
+
+/// 在容器内执行探测命令所需的信息
+pub struct ExecTarget {
+    pub host: Option<DockerHost>,
+    /// 模型ID，用于生成容器名称
+    pub id: String,
+    pub params: String,
+}
+
+/// 探测目标
+pub struct ProbeTarget {
+    pub base_url: String,
+    pub network: Option<NetworkProfile>,
+    /// 由Docker管理的容器才可执行命令探测
+    pub exec: Option<ExecTarget>,
+}
+
+impl ProbeTarget {
+    /// 中间层的探测目标
+    pub fn middleware(state: &AppState, group: &BusinessGroup, middleware: &MiddlewareContainer) -> Self {
+        Self {
+            base_url: middleware.api_base_url(),
+            network: group.network_profile(&state.network_profiles).cloned(),
+            exec: ExecTarget::new(state, &middleware.id, &middleware.docker_run_params, &middleware.docker_host_id),
+        }
+    }
+
+    /// 后端的探测目标
+    pub fn backend(state: &AppState, group: &BusinessGroup, backend: &BackendContainer) -> Self {
+        Self {
+            base_url: backend.url.clone(),
+            network: group.network_profile(&state.network_profiles).cloned(),
+            exec: ExecTarget::new(state, &backend.id, &backend.docker_run_params, &backend.docker_host_id),
+        }
+    }
+}
+
+impl ExecTarget {
+    /// 未配置运行参数或主机已不存在时为空
+    fn new(state: &AppState, id: &str, params: &str, host_id: &Option<String>) -> Option<Self> {
+        if params.trim().is_empty() {
+            return None;
+        }
+        let host = match host_id {
+            Some(host_id) => Some(state.docker_hosts.iter().find(|h| &h.id == host_id)?.clone()),
+            None => None,
+        };
+        Some(Self {
+            host,
+            id: id.to_string(),
+            params: params.to_string(),
+        })
+    }
+}
+
+/// 按探测设置执行一次健康探测，探测未通过时返回原因
+pub fn run(probe: &HealthProbe, target: &ProbeTarget) -> Result<()> {
+    let timeout = Duration::from_millis(probe.timeout_ms.max(1));
+    match probe.kind {
+        ProbeKind::Http => ApiClient::new(ApiClientConfig {
+            base_url: target.base_url.clone(),
+            timeout: probe.timeout_ms,
+            network: target.network.clone(),
+            trace_id: None,
+        })
+        .and_then(|client| tasks::block_on(client.probe(&probe.path))),
+        ProbeKind::Tcp => tcp(&target.base_url, target.network.as_ref(), timeout),
+        ProbeKind::Exec => {
+            let exec_target = target.exec.as_ref().context("容器未由Docker管理，无法在容器内执行探测命令")?;
+            exec(exec_target, &probe.command, timeout)
+        }
+    }
+}
+
+/// 连接地址中的主机与端口，已建立SSH隧道的目标改连本机端口，网络配置中的解析覆盖优先
+fn tcp(base_url: &str, network: Option<&NetworkProfile>, timeout: Duration) -> Result<()> {
+    let base_url = &tunnel::route(base_url);
+    let url = reqwest::Url::parse(base_url).with_context(|| format!("无效的地址: {}", base_url))?;
+    let host = url.host_str().with_context(|| format!("地址中没有主机: {}", base_url))?;
+    let port = url.port_or_known_default().with_context(|| format!("地址中没有端口: {}", base_url))?;
+    let host = network.and_then(|n| n.resolve(host)).unwrap_or(host);
+    let mut last_error = None;
+    for address in (host, port).to_socket_addrs().with_context(|| format!("无法解析 {}", host))? {
+        match TcpStream::connect_timeout(&address, timeout) {
+            Ok(_) => return Ok(()),
+            Err(e) => last_error = Some(e),
+        }
+    }
+    match last_error {
+        Some(e) => Err(e).with_context(|| format!("无法连接 {}:{}", host, port)),
+        None => anyhow::bail!("{} 没有可用的地址", host),
+    }
+}
+
+/// 在容器内执行探测命令，超时后停止等待
+fn exec(target: &ExecTarget, command: &str, timeout: Duration) -> Result<()> {
+    if command.trim().is_empty() {
+        anyhow::bail!("未配置探测命令");
+    }
+    let cancel = Arc::new(AtomicBool::new(false));
+    let timer = cancel.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(timeout);
+        timer.store(true, Ordering::Relaxed);
+    });
+    match docker::exec(target.host.as_ref(), &target.id, &target.params, command, "", &cancel, |_| {})? {
+        Some(0) => Ok(()),
+        Some(code) => anyhow::bail!("探测命令退出码为 {}", code),
+        None => anyhow::bail!("探测命令在 {} 毫秒内未结束", timeout.as_millis()),
+    }
+}
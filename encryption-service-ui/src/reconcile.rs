@@ -0,0 +1,283 @@
+use chrono::{DateTime, Utc};
+use std::collections::HashSet;
+
+use crate::docker::{self, LabeledContainer};
+use crate::events::EntityKind;
+use crate::models::{AppState, BackendContainer, ContainerSpec, ContainerStatus, DockerHost, RuntimeKind};
+
+/// 一台主机的扫描结果
+pub struct HostScan {
+    /// 本机为空
+    pub host: Option<DockerHost>,
+    pub containers: anyhow::Result<Vec<LabeledContainer>>,
+}
+
+/// 孤儿容器的成因
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OrphanReason {
+    /// 配置中没有标签所指的容器
+    NotInConfig,
+    /// 配置中的容器指定在另一台主机上运行
+    OtherHost(String),
+    /// 配置中的容器存在，但名称不属于其当前的副本，多为缩容或修改名称后遗留
+    StaleReplica,
+}
+
+impl OrphanReason {
+    /// 显示说明
+    pub fn label(&self) -> String {
+        match self {
+            OrphanReason::NotInConfig => "配置中不存在".to_string(),
+            OrphanReason::OtherHost(host) => format!("配置指定在 {} 上运行", host),
+            OrphanReason::StaleReplica => "不属于当前的副本".to_string(),
+        }
+    }
+}
+
+/// 主机上带有管理标签，却不对应配置中任何容器的孤儿容器
+#[derive(Debug, Clone)]
+pub struct Orphan {
+    /// 所在主机，本机为空
+    pub host_id: Option<String>,
+    pub host_name: String,
+    /// 主机的地址，用于推断纳管后的访问地址
+    pub host_address: String,
+    pub container: LabeledContainer,
+    pub reason: OrphanReason,
+}
+
+impl Orphan {
+    /// 是否可以纳管，仅配置中不存在的容器可以纳管
+    pub fn adoptable(&self) -> bool {
+        self.reason == OrphanReason::NotInConfig
+    }
+
+    /// 纳管为后端：沿用标签中的ID以继续对应该容器，运行参数只还原名称、镜像与端口映射，
+    /// 访问地址取第一个发布的端口
+    pub fn to_backend(&self) -> BackendContainer {
+        let container = &self.container;
+        let default_name = format!("encryption-service-{}", container.model_id);
+        let spec = ContainerSpec {
+            name: if container.name == default_name { String::new() } else { container.name.clone() },
+            image: container.image.clone(),
+            ports: container.ports.clone(),
+            ..ContainerSpec::default()
+        };
+        let mut backend = BackendContainer {
+            id: container.model_id.clone(),
+            name: container.name.clone(),
+            docker_run_params: spec.to_params(),
+            docker_host_id: self.host_id.clone(),
+            ports: container.ports.clone(),
+            status: if container.running { ContainerStatus::Running } else { ContainerStatus::Stopped },
+            ..BackendContainer::default()
+        };
+        if let Some(port) = container.ports.first() {
+            let host = if port.host_ip.is_empty() { &self.host_address } else { &port.host_ip };
+            backend.url = if host.contains(':') {
+                format!("http://[{}]:{}", host, port.host_port)
+            } else {
+                format!("http://{}:{}", host, port.host_port)
+            };
+        }
+        backend
+    }
+}
+
+/// 配置为运行中，主机上却不存在对应容器的漂移
+#[derive(Debug, Clone)]
+pub struct Drift {
+    pub kind: EntityKind,
+    pub group_id: String,
+    /// 中间层自身或后端所属的中间层，业务组直属后端为空
+    pub middleware_id: Option<String>,
+    pub id: String,
+    pub name: String,
+    pub host_name: String,
+    /// 缺失的容器名称
+    pub missing: Vec<String>,
+    pub status: ContainerStatus,
+}
+
+/// 一次对账扫描的结果
+#[derive(Debug, Clone)]
+pub struct ReconcileReport {
+    pub scanned_at: DateTime<Utc>,
+    /// 每台主机的扫描结果：（主机名称, 带管理标签的容器数或错误）
+    pub hosts: Vec<(String, Result<usize, String>)>,
+    pub orphans: Vec<Orphan>,
+    pub drifts: Vec<Drift>,
+}
+
+/// 配置中由Docker管理的容器及其应有的容器名称
+struct Expected {
+    kind: EntityKind,
+    group_id: String,
+    middleware_id: Option<String>,
+    id: String,
+    name: String,
+    host_id: Option<String>,
+    status: ContainerStatus,
+    containers: Vec<String>,
+}
+
+/// 配置中所有由Docker管理的容器，运行时为Kubernetes或运行参数无法解析的除外；
+/// 中间层下的后端跟随中间层的运行时，直属业务组的后端使用Docker
+fn expected(state: &AppState) -> Vec<Expected> {
+    let mut expected = Vec::new();
+    for group in &state.business_groups {
+        let mut push = |kind, middleware_id: Option<&str>, id: &str, name: &str, params: &str, host_id: &Option<String>, runtime: &RuntimeKind, status: &ContainerStatus, replicas: u32| {
+            if params.trim().is_empty() || matches!(runtime, RuntimeKind::Kubernetes { .. }) {
+                return;
+            }
+            let Ok(spec) = ContainerSpec::parse(params) else {
+                return;
+            };
+            let base = docker::container_name(&spec, id);
+            expected.push(Expected {
+                kind,
+                group_id: group.id.clone(),
+                middleware_id: middleware_id.map(str::to_string),
+                id: id.to_string(),
+                name: name.to_string(),
+                host_id: host_id.clone(),
+                status: status.clone(),
+                containers: (0..replicas).map(|index| docker::container_name(&spec.replica(&base, index), id)).collect(),
+            });
+        };
+        for middleware in &group.middlewares {
+            push(EntityKind::Middleware, Some(&middleware.id), &middleware.id, &middleware.name, &middleware.docker_run_params, &middleware.docker_host_id, &middleware.runtime, &middleware.status, 1);
+            for backend in &middleware.backend_containers {
+                push(EntityKind::Backend, Some(&middleware.id), &backend.id, &backend.name, &backend.docker_run_params, &backend.docker_host_id, &middleware.runtime, &backend.status, backend.replicas.max(1));
+            }
+        }
+        for backend in &group.backend_containers {
+            push(EntityKind::Backend, None, &backend.id, &backend.name, &backend.docker_run_params, &backend.docker_host_id, &RuntimeKind::Docker, &backend.status, backend.replicas.max(1));
+        }
+    }
+    expected
+}
+
+/// 主机的显示名称与地址
+fn describe(host: Option<&DockerHost>) -> (String, String) {
+    let Some(host) = host else {
+        return ("本机".to_string(), "localhost".to_string());
+    };
+    let address = reqwest::Url::parse(&host.url())
+        .ok()
+        .and_then(|url| url.host_str().map(|h| h.trim_matches(['[', ']']).to_string()))
+        .unwrap_or_else(|| "localhost".to_string());
+    (host.name.clone(), address)
+}
+
+/// 对比扫描结果与配置：主机上不对应配置的带标签容器为孤儿，
+/// 配置为运行中、启动中的容器在其主机上不存在为漂移；无法连接的主机不参与对比
+pub fn reconcile(state: &AppState, scans: Vec<HostScan>) -> ReconcileReport {
+    let expected = expected(state);
+    let known: HashSet<&str> = state.business_groups
+        .iter()
+        .flat_map(|g| {
+            g.middlewares.iter().flat_map(|m| std::iter::once(m.id.as_str()).chain(m.backend_containers.iter().map(|b| b.id.as_str())))
+                .chain(g.backend_containers.iter().map(|b| b.id.as_str()))
+        })
+        .collect();
+    let host_name = |host_id: &Option<String>| match host_id {
+        Some(id) => state.docker_hosts.iter().find(|h| &h.id == id).map_or_else(|| id.clone(), |h| h.name.clone()),
+        None => "本机".to_string(),
+    };
+
+    let mut report = ReconcileReport {
+        scanned_at: Utc::now(),
+        hosts: Vec::new(),
+        orphans: Vec::new(),
+        drifts: Vec::new(),
+    };
+    for scan in scans {
+        let host_id = scan.host.as_ref().map(|h| h.id.clone());
+        let (name, address) = describe(scan.host.as_ref());
+        let containers = match scan.containers {
+            Ok(containers) => containers,
+            Err(e) => {
+                report.hosts.push((name, Err(format!("{:#}", e))));
+                continue;
+            }
+        };
+        report.hosts.push((name.clone(), Ok(containers.len())));
+        let present: HashSet<&str> = containers.iter().map(|c| c.name.as_str()).collect();
+
+        for entry in expected.iter().filter(|e| e.host_id == host_id) {
+            let missing: Vec<String> = entry.containers
+                .iter()
+                .filter(|c| !present.contains(c.as_str()))
+                .cloned()
+                .collect();
+            if !missing.is_empty() && matches!(entry.status, ContainerStatus::Running | ContainerStatus::Starting) {
+                report.drifts.push(Drift {
+                    kind: entry.kind,
+                    group_id: entry.group_id.clone(),
+                    middleware_id: entry.middleware_id.clone(),
+                    id: entry.id.clone(),
+                    name: entry.name.clone(),
+                    host_name: name.clone(),
+                    missing,
+                    status: entry.status.clone(),
+                });
+            }
+        }
+
+        for container in &containers {
+            let owners: Vec<&Expected> = expected.iter().filter(|e| e.id == container.model_id).collect();
+            let reason = if owners.iter().any(|e| e.host_id == host_id && e.containers.contains(&container.name)) {
+                continue;
+            } else if !known.contains(container.model_id.as_str()) {
+                OrphanReason::NotInConfig
+            } else if let Some(owner) = owners.iter().find(|e| e.host_id != host_id) {
+                OrphanReason::OtherHost(host_name(&owner.host_id))
+            } else {
+                OrphanReason::StaleReplica
+            };
+            report.orphans.push(Orphan {
+                host_id: host_id.clone(),
+                host_name: name.clone(),
+                host_address: address.clone(),
+                container: container.clone(),
+                reason,
+            });
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{BusinessGroup, KubernetesLink, MiddlewareContainer};
+
+    fn backend(id: &str) -> BackendContainer {
+        BackendContainer { id: id.to_string(), docker_run_params: "-d crud-api:latest".to_string(), ..BackendContainer::default() }
+    }
+
+    #[test]
+    fn expected_containers_follow_runtime_kind() {
+        let kubernetes = RuntimeKind::Kubernetes { namespace: "prod".to_string() };
+        let link = KubernetesLink { namespace: "prod".to_string(), deployment: "old".to_string() };
+        let middleware = |id: &str, runtime: RuntimeKind, backends: Vec<BackendContainer>| MiddlewareContainer {
+            id: id.to_string(),
+            docker_run_params: "-d encryption-service:latest".to_string(),
+            runtime,
+            backend_containers: backends,
+            ..MiddlewareContainer::default()
+        };
+        // 曾部署到Kubernetes、已改回Docker的实体仍保留关联，按Docker核对
+        let mut returned = middleware("mw-docker", RuntimeKind::Docker, vec![BackendContainer { kubernetes: Some(link.clone()), ..backend("b-docker") }]);
+        returned.kubernetes = Some(link);
+        let mut state = AppState::default();
+        state.business_groups.push(BusinessGroup {
+            middlewares: vec![returned, middleware("mw-k8s", kubernetes, vec![backend("b-k8s")])],
+            backend_containers: vec![backend("b-group")],
+            ..BusinessGroup::default()
+        });
+        let ids: Vec<String> = expected(&state).into_iter().map(|e| e.id).collect();
+        assert_eq!(ids, ["mw-docker", "b-docker", "b-group"]);
+    }
+}
@@ -0,0 +1,272 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::cmp::Ordering;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Docker Hub的仓库地址，镜像名不含仓库地址时使用
+const DOCKER_HUB_REGISTRY: &str = "registry-1.docker.io";
+/// 查询仓库的请求超时
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+/// 每页请求的标签数量
+const TAGS_PER_PAGE: u32 = 1000;
+/// 最多读取的标签页数
+const MAX_TAG_PAGES: usize = 10;
+
+/// 拆分后的镜像名称
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageReference {
+    /// 仓库地址
+    pub registry: String,
+    /// 仓库中的镜像路径，Docker Hub的官方镜像带 `library/` 前缀
+    pub repository: String,
+    pub tag: String,
+}
+
+impl ImageReference {
+    /// 按Docker的规则拆分镜像名称，未指定标签时为 `latest`；按摘要引用的镜像无法比较标签
+    pub fn parse(image: &str) -> Result<Self> {
+        let image = image.trim();
+        if image.is_empty() {
+            anyhow::bail!("镜像名称为空");
+        }
+        if image.contains('@') {
+            anyhow::bail!("按摘要引用的镜像没有标签: {}", image);
+        }
+        let name_start = image.rfind('/').map(|i| i + 1).unwrap_or(0);
+        let (name, tag) = match image[name_start..].rfind(':') {
+            Some(i) => (&image[..name_start + i], &image[name_start + i + 1..]),
+            None => (image, "latest"),
+        };
+        // 第一段含有点、端口或为localhost时是仓库地址
+        let (registry, repository) = match name.split_once('/') {
+            Some((first, rest)) if first.contains('.') || first.contains(':') || first == "localhost" => {
+                (first.to_string(), rest.to_string())
+            }
+            _ if name.contains('/') => (DOCKER_HUB_REGISTRY.to_string(), name.to_string()),
+            _ => (DOCKER_HUB_REGISTRY.to_string(), format!("library/{}", name)),
+        };
+        Ok(Self {
+            registry: match registry.as_str() {
+                "docker.io" | "index.docker.io" => DOCKER_HUB_REGISTRY.to_string(),
+                _ => registry,
+            },
+            repository,
+            tag: tag.to_string(),
+        })
+    }
+}
+
+/// 读取仓库中镜像的所有标签，需要认证时匿名获取令牌
+pub async fn list_tags(reference: &ImageReference) -> Result<Vec<String>> {
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .context("无法创建仓库客户端")?;
+    let base = format!("https://{}", reference.registry);
+    let mut next = Some(format!("{}/v2/{}/tags/list?n={}", base, reference.repository, TAGS_PER_PAGE));
+    let mut token: Option<String> = None;
+    let mut tags = Vec::new();
+    for _ in 0..MAX_TAG_PAGES {
+        let Some(url) = next.take() else {
+            break;
+        };
+        let send = |token: Option<String>| {
+            let mut request = client.get(&url);
+            if let Some(token) = token {
+                request = request.bearer_auth(token);
+            }
+            async move { request.send().await.with_context(|| format!("无法访问仓库 {}", reference.registry)) }
+        };
+        let mut response = send(token.clone()).await?;
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED && token.is_none() {
+            token = Some(anonymous_token(&client, &response).await?);
+            response = send(token.clone()).await?;
+        }
+        if !response.status().is_success() {
+            anyhow::bail!("仓库返回 {}，无法读取 {} 的标签", response.status(), reference.repository);
+        }
+        next = next_page(&base, &response);
+        let body: serde_json::Value = response.json().await.context("仓库返回的标签列表格式错误")?;
+        tags.extend(
+            body["tags"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|t| t.as_str().map(str::to_string)),
+        );
+    }
+    Ok(tags)
+}
+
+/// 按 `WWW-Authenticate` 中的认证地址匿名获取拉取令牌
+async fn anonymous_token(client: &reqwest::Client, response: &reqwest::Response) -> Result<String> {
+    static PARAM: OnceLock<Regex> = OnceLock::new();
+    let challenge = response
+        .headers()
+        .get(reqwest::header::WWW_AUTHENTICATE)
+        .and_then(|h| h.to_str().ok())
+        .context("仓库要求认证但未说明认证方式")?;
+    let Some(params) = challenge.strip_prefix("Bearer ") else {
+        anyhow::bail!("不支持的仓库认证方式: {}", challenge);
+    };
+    let pattern = PARAM.get_or_init(|| Regex::new(r#"(\w+)="([^"]*)""#).expect("认证参数正则有效"));
+    let mut realm = None;
+    let mut query = Vec::new();
+    for capture in pattern.captures_iter(params) {
+        match &capture[1] {
+            "realm" => realm = Some(capture[2].to_string()),
+            key => query.push((key.to_string(), capture[2].to_string())),
+        }
+    }
+    let realm = realm.context("仓库认证缺少认证地址")?;
+    let body: serde_json::Value = client
+        .get(&realm)
+        .query(&query)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .with_context(|| format!("无法从 {} 获取令牌", realm))?
+        .json()
+        .await
+        .context("认证服务返回的令牌格式错误")?;
+    body["token"]
+        .as_str()
+        .or_else(|| body["access_token"].as_str())
+        .map(str::to_string)
+        .context("认证服务未返回令牌")
+}
+
+/// 分页时 `Link` 头中下一页的地址
+fn next_page(base: &str, response: &reqwest::Response) -> Option<String> {
+    let link = response.headers().get(reqwest::header::LINK)?.to_str().ok()?;
+    let path = link.split(';').next()?.trim().trim_start_matches('<').trim_end_matches('>');
+    Some(if path.starts_with("http") { path.to_string() } else { format!("{}{}", base, path) })
+}
+
+/// 版本形式的标签：可选的 `v` 前缀、点分隔的数字与其后的后缀，如 `v1.2.3-alpine`；
+/// `-rc1`、`-beta.2` 这类后缀视为预发布版本
+#[derive(Debug, PartialEq, Eq)]
+struct VersionTag<'a> {
+    prefix: &'a str,
+    numbers: Vec<u64>,
+    suffix: &'a str,
+    /// 预发布阶段与序号
+    pre: Option<(&'a str, u64)>,
+}
+
+impl<'a> VersionTag<'a> {
+    fn parse(tag: &'a str) -> Option<Self> {
+        let prefix = if tag.starts_with('v') { "v" } else { "" };
+        let rest = &tag[prefix.len()..];
+        let end = rest.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(rest.len());
+        let (version, suffix) = rest.split_at(end);
+        let numbers = version
+            .split('.')
+            .map(|part| part.parse().ok())
+            .collect::<Option<Vec<u64>>>()?;
+        match prerelease(suffix) {
+            Some(pre) => Some(Self { prefix, numbers, suffix: "", pre: Some(pre) }),
+            None => Some(Self { prefix, numbers, suffix, pre: None }),
+        }
+    }
+
+    /// 前缀、数字段数与后缀相同的标签才可比较，避免从 `1.2-alpine` 升级到 `1.3` 这类不同变体；
+    /// 数字相同时正式版本高于预发布版本
+    fn compare(&self, other: &VersionTag) -> Option<Ordering> {
+        (self.prefix == other.prefix && self.suffix == other.suffix && self.numbers.len() == other.numbers.len()).then(|| {
+            self.numbers.cmp(&other.numbers).then(match (self.pre, other.pre) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(a), Some(b)) => a.cmp(&b),
+            })
+        })
+    }
+}
+
+/// 解析 `-alpha`、`-beta.2`、`-rc1` 形式的预发布后缀，阶段名按字母序即为先后顺序
+fn prerelease(suffix: &str) -> Option<(&str, u64)> {
+    let rest = suffix.strip_prefix('-')?;
+    let end = rest.find(|c: char| !c.is_ascii_lowercase()).unwrap_or(rest.len());
+    let (stage, number) = rest.split_at(end);
+    if !["alpha", "beta", "pre", "rc"].contains(&stage) {
+        return None;
+    }
+    let number = number.strip_prefix('.').unwrap_or(number);
+    Some((stage, if number.is_empty() { 0 } else { number.parse().ok()? }))
+}
+
+/// 标签列表中与当前标签同一变体且版本最高的更新标签，当前标签不是版本形式时为空；
+/// 当前为正式版本时不推荐预发布版本
+pub fn newer_tag(current: &str, tags: &[String]) -> Option<String> {
+    let current = VersionTag::parse(current)?;
+    tags.iter()
+        .filter_map(|tag| VersionTag::parse(tag).map(|version| (tag, version)))
+        .filter(|(_, version)| current.pre.is_some() || version.pre.is_none())
+        .filter(|(_, version)| version.compare(&current) == Some(Ordering::Greater))
+        .max_by(|(_, a), (_, b)| a.compare(b).unwrap_or(Ordering::Equal))
+        .map(|(tag, _)| tag.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn newer(current: &str, tags: &[&str]) -> Option<String> {
+        newer_tag(current, &tags.iter().map(|t| t.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn picks_highest_semver_of_same_shape() {
+        let tags = ["1.2.3", "1.10.0", "1.9.9", "2.0", "v3.0.0", "1.2.2"];
+        assert_eq!(newer("1.2.3", &tags).as_deref(), Some("1.10.0"));
+        assert_eq!(newer("2.0", &tags), None);
+        assert_eq!(newer("1.10.0", &tags), None);
+        assert_eq!(newer("v2.9.0", &tags).as_deref(), Some("v3.0.0"));
+    }
+
+    #[test]
+    fn variants_are_kept() {
+        let tags = ["1.25-alpine", "1.26", "1.26-alpine", "1.27-bookworm"];
+        assert_eq!(newer("1.25-alpine", &tags).as_deref(), Some("1.26-alpine"));
+        assert_eq!(newer("1.25", &tags).as_deref(), Some("1.26"));
+        assert_eq!(newer("1.26-alpine", &tags), None);
+    }
+
+    #[test]
+    fn prereleases_order_before_releases() {
+        let tags = ["1.3.0-rc1", "1.3.0-rc.2", "1.3.0-beta", "1.3.0", "1.4.0-alpha.1"];
+        // 正式版本不升级到预发布版本
+        assert_eq!(newer("1.2.0", &tags).as_deref(), Some("1.3.0"));
+        assert_eq!(newer("1.3.0", &tags), None);
+        // 预发布版本可升级到更新的预发布或正式版本
+        assert_eq!(newer("1.3.0-beta", &["1.3.0-rc1", "1.3.0-alpha"]).as_deref(), Some("1.3.0-rc1"));
+        assert_eq!(newer("1.3.0-rc1", &["1.3.0-rc.2", "1.3.0"]).as_deref(), Some("1.3.0"));
+        assert_eq!(newer("1.3.0-rc1", &tags).as_deref(), Some("1.4.0-alpha.1"));
+        assert_eq!(prerelease("-rc"), Some(("rc", 0)));
+        assert_eq!(prerelease("-rc1x"), None);
+        assert_eq!(prerelease("-alpine"), None);
+    }
+
+    #[test]
+    fn non_version_tags_are_ignored() {
+        let tags = ["latest", "stable", "main-abc123", "1.2.x", "2.0.0"];
+        assert_eq!(newer("latest", &tags), None);
+        assert_eq!(newer("sha-1a2b3c", &tags), None);
+        assert_eq!(newer("1.0.0", &tags).as_deref(), Some("2.0.0"));
+        assert_eq!(newer("1.0.0", &[]), None);
+    }
+
+    #[test]
+    fn image_references_split_registry_and_tag() {
+        let nginx = ImageReference::parse("nginx").unwrap();
+        assert_eq!((nginx.registry.as_str(), nginx.repository.as_str(), nginx.tag.as_str()), (DOCKER_HUB_REGISTRY, "library/nginx", "latest"));
+        let local = ImageReference::parse("localhost:5000/team/api:1.2").unwrap();
+        assert_eq!((local.registry.as_str(), local.repository.as_str(), local.tag.as_str()), ("localhost:5000", "team/api", "1.2"));
+        let hub = ImageReference::parse("docker.io/bitnami/redis:7.2").unwrap();
+        assert_eq!((hub.registry.as_str(), hub.repository.as_str()), (DOCKER_HUB_REGISTRY, "bitnami/redis"));
+        assert!(ImageReference::parse("nginx@sha256:abc").is_err());
+        assert!(ImageReference::parse(" ").is_err());
+    }
+}
@@ -0,0 +1,117 @@
+use std::sync::mpsc::Receiver;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use crate::events::ModelEvent;
+
+/// 已请求的重绘超过该时长仍未开始时视为丢失，允许重新请求
+const SCHEDULE_LOST_AFTER: Duration = Duration::from_secs(1);
+
+/// 请求重绘的来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepaintSource {
+    /// 共享状态的模型变更
+    Model,
+    /// 健康探测返回
+    Health,
+    /// 任务池中的任务或服务内部提交的请求完成
+    Task,
+    /// 轮询协调器收到响应
+    Poll,
+}
+
+impl RepaintSource {
+    /// 所有来源
+    pub const ALL: [RepaintSource; 4] = [RepaintSource::Model, RepaintSource::Health, RepaintSource::Task, RepaintSource::Poll];
+
+    /// 显示名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            RepaintSource::Model => "模型变更",
+            RepaintSource::Health => "健康探测",
+            RepaintSource::Task => "后台任务",
+            RepaintSource::Poll => "状态轮询",
+        }
+    }
+}
+
+/// 重绘请求的统计：各来源的请求数与因已有重绘待执行而合并的请求数
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RepaintStats {
+    pub requested: [u64; RepaintSource::ALL.len()],
+    pub coalesced: u64,
+}
+
+struct Scheduler {
+    ctx: Option<egui::Context>,
+    min_interval: Duration,
+    /// 上一帧开始的时间
+    last_frame: Option<Instant>,
+    /// 已向界面请求、尚未开始的重绘时间
+    scheduled: Option<Instant>,
+    stats: RepaintStats,
+}
+
+/// 服务没有界面上下文，重绘请求汇总在进程内，由界面在创建时接入
+static SCHEDULER: Mutex<Scheduler> = Mutex::new(Scheduler {
+    ctx: None,
+    min_interval: Duration::ZERO,
+    last_frame: None,
+    scheduled: None,
+    stats: RepaintStats { requested: [0; RepaintSource::ALL.len()], coalesced: 0 },
+});
+
+fn scheduler() -> MutexGuard<'static, Scheduler> {
+    SCHEDULER.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// 接入界面上下文，之后的重绘请求才会生效
+pub fn attach(ctx: &egui::Context) {
+    scheduler().ctx = Some(ctx.clone());
+}
+
+/// 设置两次由后台请求的重绘之间的最短间隔，用户操作引起的重绘不受限制
+pub fn set_min_interval(interval: Duration) {
+    scheduler().min_interval = interval;
+}
+
+/// 每帧开始时调用，之后的请求重新安排重绘
+pub fn frame_started() {
+    let mut scheduler = scheduler();
+    scheduler.last_frame = Some(Instant::now());
+    scheduler.scheduled = None;
+}
+
+/// 后台状态变化后请求重绘：已有重绘待执行时合并，距上一帧不足最短间隔时推迟到间隔结束
+pub fn request(source: RepaintSource) {
+    let mut scheduler = scheduler();
+    let Some(ctx) = scheduler.ctx.clone() else {
+        return;
+    };
+    scheduler.stats.requested[source as usize] += 1;
+    let now = Instant::now();
+    if scheduler.scheduled.is_some_and(|at| now < at + SCHEDULE_LOST_AFTER) {
+        scheduler.stats.coalesced += 1;
+        return;
+    }
+    let delay = scheduler
+        .last_frame
+        .map_or(Duration::ZERO, |frame| (frame + scheduler.min_interval).saturating_duration_since(now));
+    scheduler.scheduled = Some(now + delay);
+    drop(scheduler);
+    ctx.request_repaint_after(delay);
+}
+
+/// 把模型变更事件转为重绘请求，在后台线程中运行直到事件总线关闭
+pub fn forward(events: Receiver<ModelEvent>) {
+    std::thread::spawn(move || {
+        for _ in events {
+            request(RepaintSource::Model);
+        }
+    });
+}
+
+/// 重绘请求的统计
+pub fn stats() -> RepaintStats {
+    scheduler().stats
+}
@@ -0,0 +1,274 @@
+use anyhow::Result;
+
+use crate::docker;
+use crate::kubernetes::{self, DeploymentRequest, WorkloadRole};
+use crate::models::{AppState, BackendContainer, BusinessGroup, ContainerSpec, DockerHost, GroupDockerNetwork, KubernetesLink, MiddlewareContainer, PlannedAction, PortMapping, RuntimeKind};
+
+/// 容器运行时：启动、停止与扩缩容单个中间层或后端容器
+pub trait ContainerRuntime {
+    /// 显示名称
+    fn name(&self) -> &'static str;
+    /// 按容器定义创建或更新并启动
+    fn start(&self) -> Result<()>;
+    /// 停止运行
+    fn stop(&self) -> Result<()>;
+    /// 调整运行的副本数
+    fn scale(&self, replicas: u32) -> Result<()>;
+    /// 停止并删除所有副本，删除容器模型前调用
+    fn remove(&self) -> Result<()>;
+    /// 启动时将执行的操作及其说明，不访问运行时
+    fn plan_start(&self) -> (PlannedAction, String);
+    /// 停止时将执行的操作及其说明，不访问运行时
+    fn plan_stop(&self) -> (PlannedAction, String);
+    /// 启动时占用的宿主机端口，用于检查端口冲突
+    fn host_ports(&self) -> &[PortMapping] {
+        &[]
+    }
+    /// 启动后关联的集群工作负载，状态随集群同步
+    fn kubernetes_link(&self) -> Option<KubernetesLink> {
+        None
+    }
+}
+
+/// 在Docker或Podman主机上运行容器，两种引擎使用相同的兼容API；每个副本是单独的容器
+pub struct DockerRuntime {
+    host: Option<DockerHost>,
+    id: String,
+    spec: ContainerSpec,
+    env: Vec<(&'static str, String)>,
+    network: Option<GroupDockerNetwork>,
+    /// 当前的副本数，中间层固定为1
+    replicas: u32,
+    /// 所有副本映射的宿主机端口
+    ports: Vec<PortMapping>,
+}
+
+impl DockerRuntime {
+    /// 第 `index` 个副本（从0起）的容器定义
+    fn replica(&self, index: u32) -> ContainerSpec {
+        self.spec.replica(&docker::container_name(&self.spec, &self.id), index)
+    }
+
+    fn start_replica(&self, index: u32) -> Result<()> {
+        let mut env = self.env.clone();
+        env.push(("REPLICA_INDEX", index.to_string()));
+        docker::start(self.host.as_ref(), &self.id, self.replica(index), env, self.network.as_ref())
+    }
+
+    fn stop_replica(&self, index: u32) -> Result<()> {
+        docker::stop(self.host.as_ref(), &self.id, &self.replica(index))
+    }
+
+    fn remove_replica(&self, index: u32) -> Result<()> {
+        docker::remove_container(self.host.as_ref(), &docker::container_name(&self.replica(index), &self.id))
+    }
+}
+
+impl ContainerRuntime for DockerRuntime {
+    fn name(&self) -> &'static str {
+        self.host.as_ref().map(|h| h.engine).unwrap_or_default().label()
+    }
+
+    fn start(&self) -> Result<()> {
+        (0..self.replicas).try_for_each(|index| self.start_replica(index))
+    }
+
+    fn stop(&self) -> Result<()> {
+        (0..self.replicas).try_for_each(|index| self.stop_replica(index))
+    }
+
+    /// 启动新增的副本、删除多出的副本，已运行的副本不受影响
+    fn scale(&self, replicas: u32) -> Result<()> {
+        if replicas == 0 {
+            anyhow::bail!("{}运行时至少运行一个副本，请改用停止", self.name());
+        }
+        (self.replicas..replicas).try_for_each(|index| self.start_replica(index))?;
+        (replicas..self.replicas).rev().try_for_each(|index| self.remove_replica(index))
+    }
+
+    fn remove(&self) -> Result<()> {
+        (0..self.replicas).rev().try_for_each(|index| self.remove_replica(index))
+    }
+
+    /// 定义与镜像未变化的已有容器直接启动，其余按镜像重新创建，不访问主机无法区分
+    fn plan_start(&self) -> (PlannedAction, String) {
+        let host = self.host.as_ref().map(|h| h.name.as_str()).unwrap_or("本机");
+        (PlannedAction::Create, format!("在{}上启动 {} 个容器，不存在或定义有变化的按镜像 {} 重新创建", host, self.replicas, self.spec.image))
+    }
+
+    fn plan_stop(&self) -> (PlannedAction, String) {
+        (PlannedAction::Stop, format!("停止 {} 个容器，保留容器供下次启动", self.replicas))
+    }
+
+    fn host_ports(&self) -> &[PortMapping] {
+        &self.ports
+    }
+}
+
+/// 以Deployment形式在Kubernetes中运行，停止即缩容到0
+pub struct KubernetesRuntime {
+    link: KubernetesLink,
+    /// 部署清单，未配置容器定义时为空，此时只调整已有Deployment的副本数
+    request: Option<DeploymentRequest>,
+    replicas: u32,
+}
+
+impl ContainerRuntime for KubernetesRuntime {
+    fn name(&self) -> &'static str {
+        "Kubernetes"
+    }
+
+    fn start(&self) -> Result<()> {
+        match &self.request {
+            Some(request) => kubernetes::apply(request),
+            None => kubernetes::scale(&self.link, self.replicas),
+        }
+    }
+
+    fn stop(&self) -> Result<()> {
+        kubernetes::scale(&self.link, 0)
+    }
+
+    fn scale(&self, replicas: u32) -> Result<()> {
+        kubernetes::scale(&self.link, replicas)
+    }
+
+    /// Deployment可能由集群中的其他清单管理，只缩容到0而不删除
+    fn remove(&self) -> Result<()> {
+        kubernetes::scale(&self.link, 0)
+    }
+
+    fn plan_start(&self) -> (PlannedAction, String) {
+        let target = format!("{}/{}", self.link.namespace, self.link.deployment);
+        match &self.request {
+            Some(request) => (PlannedAction::Start, format!("应用Deployment {}，镜像 {}，{} 个副本", target, request.spec.image, self.replicas)),
+            None => (PlannedAction::Start, format!("将Deployment {} 扩容到 {} 个副本", target, self.replicas)),
+        }
+    }
+
+    fn plan_stop(&self) -> (PlannedAction, String) {
+        (PlannedAction::Stop, format!("将Deployment {}/{} 缩容到0", self.link.namespace, self.link.deployment))
+    }
+
+    fn kubernetes_link(&self) -> Option<KubernetesLink> {
+        Some(self.link.clone())
+    }
+}
+
+/// 运行时管理的容器
+struct Subject<'a> {
+    role: WorkloadRole,
+    id: &'a str,
+    name: &'a str,
+    /// 容器定义，未配置运行参数时为空
+    spec: Option<ContainerSpec>,
+    env: Vec<(&'static str, String)>,
+    docker_host_id: &'a Option<String>,
+    link: &'a Option<KubernetesLink>,
+    replicas: u32,
+}
+
+/// 中间层使用的运行时；选择Docker且未配置运行参数时由外部管理，返回空
+pub fn for_middleware(state: &AppState, group: &BusinessGroup, middleware: &MiddlewareContainer) -> Result<Option<Box<dyn ContainerRuntime>>> {
+    let subject = Subject {
+        role: WorkloadRole::Middleware,
+        id: &middleware.id,
+        name: &middleware.name,
+        spec: configured(&middleware.docker_run_params).then(|| middleware.container_spec()).transpose()?,
+        env: middleware.environment(),
+        docker_host_id: &middleware.docker_host_id,
+        link: &middleware.kubernetes,
+        replicas: middleware.replicas,
+    };
+    build(state, group, &middleware.runtime, subject, None)
+}
+
+/// 后端使用的运行时，中间层下的后端跟随中间层的运行时，直属业务组的后端使用Docker
+pub fn for_backend(state: &AppState, group: &BusinessGroup, middleware: Option<&MiddlewareContainer>, backend: &BackendContainer) -> Result<Option<Box<dyn ContainerRuntime>>> {
+    let subject = Subject {
+        role: WorkloadRole::Backend,
+        id: &backend.id,
+        name: &backend.name,
+        spec: configured(&backend.docker_run_params).then(|| backend.container_spec()).transpose()?,
+        env: backend.environment(),
+        docker_host_id: &backend.docker_host_id,
+        link: &backend.kubernetes,
+        replicas: backend.replicas,
+    };
+    let kind = middleware.map(|m| m.runtime.clone()).unwrap_or_default();
+    let middleware_deployment = middleware.map(|m| match &m.kubernetes {
+        Some(link) => link.deployment.clone(),
+        None => kubernetes::deployment_name(&m.name, &m.id),
+    });
+    build(state, group, &kind, subject, middleware_deployment)
+}
+
+fn configured(params: &str) -> bool {
+    !params.trim().is_empty()
+}
+
+fn build(state: &AppState, group: &BusinessGroup, kind: &RuntimeKind, subject: Subject, middleware_deployment: Option<String>) -> Result<Option<Box<dyn ContainerRuntime>>> {
+    match kind {
+        RuntimeKind::Docker => {
+            let Some(spec) = subject.spec else {
+                return Ok(None);
+            };
+            let host = match subject.docker_host_id {
+                Some(host_id) => Some(
+                    state.docker_hosts
+                        .iter()
+                        .find(|h| &h.id == host_id)
+                        .cloned()
+                        .ok_or_else(|| anyhow::anyhow!("Docker主机不存在: {}", host_id))?,
+                ),
+                None => None,
+            };
+            let replicas = match subject.role {
+                WorkloadRole::Backend => subject.replicas.max(1),
+                WorkloadRole::Middleware => 1,
+            };
+            let ports = (0..replicas).flat_map(|index| spec.replica_ports(index)).collect();
+            Ok(Some(Box::new(DockerRuntime {
+                host,
+                id: subject.id.to_string(),
+                spec,
+                env: subject.env,
+                network: group.docker_network.clone(),
+                replicas,
+                ports,
+            })))
+        }
+        RuntimeKind::Kubernetes { namespace } => {
+            let namespace = namespace.trim();
+            if namespace.is_empty() {
+                anyhow::bail!("{} 未指定Kubernetes命名空间", subject.name);
+            }
+            // 已关联的工作负载在同一命名空间时沿用，切换命名空间后部署为新的Deployment
+            let link = subject
+                .link
+                .clone()
+                .filter(|l| l.namespace == namespace)
+                .unwrap_or_else(|| KubernetesLink {
+                    namespace: namespace.to_string(),
+                    deployment: kubernetes::deployment_name(subject.name, subject.id),
+                });
+            if subject.spec.is_none() && subject.link.as_ref() != Some(&link) {
+                anyhow::bail!("{} 未配置容器定义，无法部署到Kubernetes", subject.name);
+            }
+            let request = subject.spec.map(|spec| DeploymentRequest {
+                link: link.clone(),
+                group: kubernetes::group_label(group),
+                role: subject.role,
+                middleware: middleware_deployment,
+                replicas: subject.replicas,
+                spec,
+                env: subject.env,
+            });
+            Ok(Some(Box::new(KubernetesRuntime {
+                link,
+                request,
+                replicas: subject.replicas,
+            })))
+        }
+    }
+}
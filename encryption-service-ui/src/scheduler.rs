@@ -0,0 +1,120 @@
+use crate::models::{BackendContainer, ContainerStatus, SchedulerStrategy};
+
+/// 示例请求类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestKind {
+    Read,
+    Write,
+}
+
+impl RequestKind {
+    /// 显示名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            RequestKind::Read => "读请求",
+            RequestKind::Write => "写请求",
+        }
+    }
+
+    /// 该实例类型能否处理此类请求
+    fn accepts(&self, instance_type: &str) -> bool {
+        match self {
+            RequestKind::Read => instance_type == "read" || instance_type == "mixed",
+            RequestKind::Write => instance_type == "write" || instance_type == "mixed",
+        }
+    }
+}
+
+/// 示例请求序列，读多写少以贴近常见负载
+const EXAMPLE_REQUESTS: [(RequestKind, &str); 6] = [
+    (RequestKind::Read, "GET /records/1"),
+    (RequestKind::Read, "GET /records?page=2"),
+    (RequestKind::Write, "POST /records"),
+    (RequestKind::Read, "GET /records/7"),
+    (RequestKind::Write, "PUT /records/7"),
+    (RequestKind::Read, "GET /records/9"),
+];
+
+/// 单个示例请求的调度结果
+#[derive(Debug, Clone)]
+pub struct RouteExample {
+    pub kind: RequestKind,
+    pub request: &'static str,
+    /// 处理请求的后端名称，为None时表示没有可用实例
+    pub backend: Option<String>,
+}
+
+/// 按策略生成的调度示例
+#[derive(Debug, Clone)]
+pub struct StrategyExample {
+    pub routes: Vec<RouteExample>,
+    /// 当前没有运行中的后端时，示例假设所有后端均在运行
+    pub assumed_running: bool,
+}
+
+/// 按策略筛选可处理此类请求的后端
+fn candidates<'a>(strategy: &SchedulerStrategy, backends: &[&'a BackendContainer], kind: RequestKind) -> Vec<&'a BackendContainer> {
+    match strategy {
+        SchedulerStrategy::Single => backends.first().copied().into_iter().collect(),
+        SchedulerStrategy::ReadWriteSplit => backends
+            .iter()
+            .copied()
+            .filter(|b| kind.accepts(&b.instance_type))
+            .collect(),
+        SchedulerStrategy::LoadBalance => backends.to_vec(),
+    }
+}
+
+/// 按策略为请求选择后端，`seq` 为同类候选实例间轮询的序号
+fn route<'a>(
+    strategy: &SchedulerStrategy,
+    backends: &[&'a BackendContainer],
+    kind: RequestKind,
+    seq: usize,
+) -> Option<&'a BackendContainer> {
+    let candidates = candidates(strategy, backends, kind);
+    if candidates.is_empty() {
+        None
+    } else {
+        Some(candidates[seq % candidates.len()])
+    }
+}
+
+/// 用实际的后端列表生成调度示例
+pub fn examples(strategy: &SchedulerStrategy, backends: &[BackendContainer]) -> StrategyExample {
+    let running: Vec<&BackendContainer> = backends
+        .iter()
+        .filter(|b| b.status == ContainerStatus::Running)
+        .collect();
+    let assumed_running = running.is_empty();
+    let pool: Vec<&BackendContainer> = if assumed_running { backends.iter().collect() } else { running };
+
+    let mut reads = 0;
+    let mut writes = 0;
+    let mut total = 0;
+    let routes = EXAMPLE_REQUESTS
+        .iter()
+        .map(|(kind, request)| {
+            // 读写分离按请求类型分别轮询，其余策略共用一个序号
+            let seq = match (strategy, kind) {
+                (SchedulerStrategy::ReadWriteSplit, RequestKind::Read) => &mut reads,
+                (SchedulerStrategy::ReadWriteSplit, RequestKind::Write) => &mut writes,
+                _ => &mut total,
+            };
+            let backend = route(strategy, &pool, *kind, *seq).map(|b| b.name.clone());
+            *seq += 1;
+            RouteExample { kind: *kind, request, backend }
+        })
+        .collect();
+
+    StrategyExample { routes, assumed_running }
+}
+
+/// 按策略可处理读请求的运行中后端，批量解密等读负载在这些实例间分发
+pub fn read_targets<'a>(strategy: &SchedulerStrategy, backends: &'a [BackendContainer]) -> Vec<&'a BackendContainer> {
+    let running: Vec<&BackendContainer> = backends
+        .iter()
+        .filter(|b| b.status == ContainerStatus::Running)
+        .collect();
+    candidates(strategy, &running, RequestKind::Read)
+}
@@ -1,409 +1,6051 @@
 use anyhow::{Context, Result};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
 
-use crate::models::{BusinessGroup, MiddlewareContainer, BackendContainer, GroupStatus, ContainerStatus};
-use crate::api::{ApiClient, ApiClientConfig};
-use crate::config::{ConfigManager};
+use crate::models::{self, AgentSettings, ContainerSpec, OperationPlan, PlannedAction, PlannedChange, ReadScaleOut, StartItem, StatusTransition, TransitionField, Alert, MonitoringPolicy, GroupAccess, MONITORING_POLICY_VERSION, PolicyImportMode, ConfigPreset, HealthProbe, AlertPolicy, AutoRestartMode, AutoRestartPolicy, ConnectionPoolSettings, NetworkProfile, PortConflict, PortMapping, SshTunnel, AlertSeverity, AllowedCommand, AuditEntry, DiscoveryTtl, HealthStatus, StaleBackend, KubernetesLink, AppConfigField, OtlpSettings, JobCheckpoint, JobKind, JobRecord, JobStatus, RetryPolicy, RequestCollection, SavedRequest, DockerHost, HistoryRedaction, PlaygroundHistoryEntry, SchedulerStrategy, WeightAdjustment, Webhook, WebhookAction, LogEntry, LogEvidence, BusinessGroup, MiddlewareContainer, BackendContainer, GroupId, MiddlewareId, BackendId, GroupStatus, ContainerStatus, UiProfile, UiRoleAssignment, UiSession, LocalUser, PasswordPolicy};
+use crate::api::{self, AgentExecResponse, ApiClient, ApiClientConfig, RawResponse};
+use crate::config::{Config, ConfigManager};
+use crate::compose;
+use crate::password;
+use crate::repaint::{self, RepaintSource};
+use crate::tuning::BackendTuning;
+use crate::metrics::{self, MetricsSample, MetricsStore};
+use crate::jobs;
+use crate::chunking::{self, PayloadMode};
+use crate::alerting::{self, HealthObservation, HealthSignal, HealthTracker};
+use crate::kubernetes::{self, Workload};
+use crate::docker::{self, ContainerStats, InspectTarget, PullProgress};
+use crate::telemetry;
+use crate::probe::{self, ProbeTarget};
+use crate::runtime::{self, ContainerRuntime};
+use crate::tunnel::{self, Tunnel, TunnelStatus};
+use crate::agent::{self, AgentTarget};
+use crate::registry;
+use crate::warmup;
+use crate::upstream::UpstreamServer;
+use crate::reconcile::{self, HostScan, Orphan, ReconcileReport};
+use crate::snapshot::{self, SnapshotManifest};
+use crate::weights;
+use crate::systemd::{self, UnitFile};
+use crate::migration::{Migration, MigrationStep};
+use crate::verification::{self, VerificationReport, VerificationTarget};
+use crate::state::{Records, StateStore};
+use crate::tasks::{self, TaskPool};
+use crate::audit_export::{self, AuditChainStatus, AuditExportFormat, AuditFilter, AuditIntegrity};
+use crate::events::{EntityKind, EventBus, ModelEvent};
 
 /// 业务组服务
 pub struct BusinessGroupService {
-    pub config_manager: ConfigManager,
+    state: StateStore,
 }
 
 impl BusinessGroupService {
     /// 创建新的业务组服务
-    pub fn new(config_manager: ConfigManager) -> Self {
+    pub fn new(state: StateStore) -> Self {
         Self {
-            config_manager,
+            state,
         }
     }
     
     /// 获取所有业务组
     pub fn get_all_business_groups(&self) -> Result<Vec<BusinessGroup>> {
-        let config = self.config_manager.load_config()?;
-        Ok(config.app_state.business_groups.clone())
+        Ok(self.state.read(|state| state.business_groups.to_vec()))
     }
     
-    /// 添加业务组
+    /// 添加业务组，组带有专用网络时在本机Docker上创建
+    ///
+    /// 网络创建失败时业务组仍会保留，组内容器启动时会在所在主机上再次创建
     pub fn add_business_group(&self, group: BusinessGroup) -> Result<()> {
-        let mut config = self.config_manager.load_config()?;
-        config.app_state.business_groups.push(group);
-        self.config_manager.save_config(&config)
+        if let Some(network) = &group.docker_network {
+            network.validate()?;
+        }
+        let network = group.docker_network.clone();
+        self.state.update(|state| {
+            state.business_groups.push(group);
+            Ok(())
+        })?;
+        if let Some(network) = network {
+            docker::ensure_network(None, &network).context("业务组已创建，但创建Docker网络失败")?;
+        }
+        Ok(())
+    }
+    
+    /// 从docker-compose文件导入业务组，已存在同名业务组时拒绝导入
+    pub fn import_compose(&self, path: &str) -> Result<BusinessGroup> {
+        let group = compose::read(path)?;
+        self.state.update(|state| {
+            if state.business_groups.iter().any(|g| g.name == group.name) {
+                anyhow::bail!("业务组已存在: {}", group.name);
+            }
+            state.business_groups.push(group.clone());
+            Ok(())
+        })?;
+        Ok(group)
+    }
+    
+    /// 将业务组导出为docker-compose文件内容
+    pub fn export_compose(&self, group_id: &GroupId) -> Result<String> {
+        let group = self
+            .get_business_group(group_id)?
+            .ok_or_else(|| anyhow::anyhow!("业务组不存在: {}", group_id))?;
+        compose::render(&group)
     }
     
     /// 更新业务组
     pub fn update_business_group(&self, group: BusinessGroup) -> Result<()> {
-        let mut config = self.config_manager.load_config()?;
-        
-        if let Some(index) = config.app_state.business_groups.iter().position(|g| g.id == group.id) {
-            config.app_state.business_groups[index] = group;
-            self.config_manager.save_config(&config)
-        } else {
-            anyhow::bail!("业务组不存在: {}", group.id)
-        }
+        self.state.update(|state| {
+            let group_id = group.id.clone();
+            *state.group_mut(&GroupId::from(&group_id))? = group;
+            Ok(())
+        })
     }
     
-    /// 删除业务组
-    pub fn delete_business_group(&self, group_id: &str) -> Result<()> {
-        let mut config = self.config_manager.load_config()?;
+    /// 删除业务组，并尽量清理组内容器所在主机上的专用网络
+    pub fn delete_business_group(&self, group_id: &GroupId) -> Result<()> {
+        self.state.authorize(group_id)?;
+        let group = self.get_business_group(group_id)?;
+        self.state.update(|state| {
+            state.business_groups.retain(|g| g.id != group_id);
+            Ok(())
+        })?;
         
-        config.app_state.business_groups.retain(|g| g.id != group_id);
-        self.config_manager.save_config(&config)
+        let Some((group, network)) = group.and_then(|g| g.docker_network.clone().map(|n| (g, n))) else {
+            return Ok(());
+        };
+        let mut host_ids: Vec<Option<String>> = vec![None];
+        let containers = group.middlewares
+            .iter()
+            .map(|m| (&m.docker_host_id, &m.docker_run_params))
+            .chain(
+                group.backend_containers
+                    .iter()
+                    .chain(group.middlewares.iter().flat_map(|m| m.backend_containers.iter()))
+                    .map(|b| (&b.docker_host_id, &b.docker_run_params)),
+            );
+        for (host_id, params) in containers {
+            if !params.trim().is_empty() && !host_ids.contains(host_id) {
+                host_ids.push(host_id.clone());
+            }
+        }
+        for host_id in host_ids {
+            if let Err(e) = docker_host(&self.state, &host_id).and_then(|host| docker::remove_network(host.as_ref(), &network.name)) {
+                tracing::warn!("清理业务组 {} 的网络失败: {:#}", group.name, e);
+            }
+        }
+        Ok(())
     }
     
     /// 获取业务组
-    pub fn get_business_group(&self, group_id: &str) -> Result<Option<BusinessGroup>> {
-        let config = self.config_manager.load_config()?;
-        
-        let group = config.app_state.business_groups
-            .iter()
-            .find(|g| g.id == group_id)
-            .cloned();
-        
-        Ok(group)
+    pub fn get_business_group(&self, group_id: &GroupId) -> Result<Option<BusinessGroup>> {
+        Ok(self.state.read(|state| state.group(group_id).ok().cloned()))
     }
     
-    /// 启动业务组
-    pub fn start_business_group(&self, group_id: &str) -> Result<()> {
-        let mut config = self.config_manager.load_config()?;
-        
-        if let Some(group) = config.app_state.business_groups.iter_mut().find(|g| g.id == group_id) {
-            group.status = GroupStatus::Starting;
-            // 这里可以添加实际的启动逻辑
-            group.status = GroupStatus::Running;
-            self.config_manager.save_config(&config)
+    /// 设置业务组状态
+    fn set_group_status(&self, group_id: &GroupId, status: GroupStatus) -> Result<()> {
+        self.state.update(|state| {
+            state.group_mut(group_id)?.status = status;
+            Ok(())
+        })
+    }
+    
+    /// 启动业务组：按依赖顺序逐个启动未运行的后端与中间层，不等待就绪；依赖存在循环时不启动任何容器
+    pub fn start_business_group(&self, group_id: &GroupId) -> Result<()> {
+        self.state.authorize(group_id)?;
+        let group = self
+            .get_business_group(group_id)?
+            .ok_or_else(|| anyhow::anyhow!("业务组不存在: {}", group_id))?;
+        for item in group.start_order()? {
+            self.start_member(group_id, item)?;
+        }
+        self.set_group_status(group_id, GroupStatus::Running)
+    }
+
+    /// 启动组内一个未运行的容器，返回是否启动了容器
+    pub fn start_member(&self, group_id: &GroupId, item: StartItem) -> Result<bool> {
+        self.state.authorize(group_id)?;
+        if matches!(item.status(), ContainerStatus::Running | ContainerStatus::Starting) {
+            return Ok(false);
+        }
+        let result = match item {
+            StartItem::Middleware(middleware) => MiddlewareService::new(self.state.clone()).start_middleware(group_id, &MiddlewareId::from(&middleware.id)),
+            StartItem::Backend(backend, middleware_id) => BackendService::new(self.state.clone()).start_backend(group_id, middleware_id.map(MiddlewareId::from).as_ref(), &BackendId::from(&backend.id)),
+        };
+        result.with_context(|| format!("{} 启动失败，已停止启动业务组", item.name()))?;
+        Ok(true)
+    }
+    
+    /// 组内容器按停止的顺序排列：启动顺序的逆序，依赖存在循环时先停中间层再停后端
+    fn stop_order(group: &BusinessGroup) -> Vec<StartItem<'_>> {
+        match group.start_order() {
+            Ok(mut items) => {
+                items.reverse();
+                items
+            }
+            Err(_) => group.middlewares
+                .iter()
+                .map(StartItem::Middleware)
+                .chain(group.middlewares.iter().flat_map(|m| m.backend_containers.iter().map(move |b| StartItem::Backend(b, Some(m.id.as_str())))))
+                .chain(group.backend_containers.iter().map(|b| StartItem::Backend(b, None)))
+                .collect(),
+        }
+    }
+
+    /// 停止业务组：按启动顺序的逆序停止未停止的中间层与后端
+    pub fn stop_business_group(&self, group_id: &GroupId) -> Result<()> {
+        self.state.authorize(group_id)?;
+        let group = self
+            .get_business_group(group_id)?
+            .ok_or_else(|| anyhow::anyhow!("业务组不存在: {}", group_id))?;
+        for item in Self::stop_order(&group) {
+            self.stop_member(group_id, item)?;
+        }
+        self.set_group_status(group_id, GroupStatus::Stopped)
+    }
+
+    /// 停止组内一个未停止的容器，返回是否停止了容器
+    pub fn stop_member(&self, group_id: &GroupId, item: StartItem) -> Result<bool> {
+        self.state.authorize(group_id)?;
+        if *item.status() == ContainerStatus::Stopped {
+            return Ok(false);
+        }
+        let result = match item {
+            StartItem::Middleware(middleware) => MiddlewareService::new(self.state.clone()).stop_middleware(group_id, &MiddlewareId::from(&middleware.id)),
+            StartItem::Backend(backend, middleware_id) => BackendService::new(self.state.clone()).stop_backend(group_id, middleware_id.map(MiddlewareId::from).as_ref(), &BackendId::from(&backend.id)),
+        };
+        result.with_context(|| format!("{} 停止失败，其余容器保持原状", item.name()))?;
+        Ok(true)
+    }
+
+    /// 组内容器使用的运行时，由外部管理时为空
+    fn member_runtime(&self, group: &BusinessGroup, item: &StartItem) -> Result<Option<Box<dyn ContainerRuntime>>> {
+        self.state.read(|state| match item {
+            StartItem::Middleware(middleware) => runtime::for_middleware(state, group, middleware),
+            StartItem::Backend(backend, middleware_id) => {
+                let middleware = middleware_id.and_then(|id| group.middlewares.iter().find(|m| m.id == id));
+                runtime::for_backend(state, group, middleware, backend)
+            }
+        })
+    }
+
+    /// 按容器的状态与运行时生成计划中的一项，运行时无法创建时将原因记为说明
+    fn plan_member(&self, group: &BusinessGroup, item: &StartItem, skip: bool, start: bool) -> PlannedChange {
+        let (action, detail) = if skip {
+            (PlannedAction::Skip, if start { "已在运行" } else { "已停止" }.to_string())
         } else {
-            anyhow::bail!("业务组不存在: {}", group_id)
+            match self.member_runtime(group, item) {
+                Ok(Some(runtime)) if start => runtime.plan_start(),
+                Ok(Some(runtime)) => runtime.plan_stop(),
+                Ok(None) => (PlannedAction::StatusOnly, "未配置运行参数，由外部管理".to_string()),
+                Err(e) => (PlannedAction::Skip, format!("无法执行: {:#}", e)),
+            }
+        };
+        PlannedChange {
+            kind: item.kind(),
+            id: item.id().to_string(),
+            name: item.name().to_string(),
+            action,
+            detail,
+        }
+    }
+
+    /// 启动业务组的执行计划，按启动顺序列出每个容器；依赖存在循环时报错
+    pub fn plan_start(&self, group_id: &GroupId) -> Result<OperationPlan> {
+        let group = self
+            .get_business_group(group_id)?
+            .ok_or_else(|| anyhow::anyhow!("业务组不存在: {}", group_id))?;
+        let changes = group
+            .start_order()?
+            .iter()
+            .map(|item| {
+                let running = matches!(item.status(), ContainerStatus::Running | ContainerStatus::Starting);
+                self.plan_member(&group, item, running, true)
+            })
+            .collect();
+        Ok(OperationPlan {
+            title: format!("启动业务组 {}", group.name),
+            changes,
+        })
+    }
+
+    /// 停止业务组的执行计划，按停止顺序列出每个容器
+    pub fn plan_stop(&self, group_id: &GroupId) -> Result<OperationPlan> {
+        let group = self
+            .get_business_group(group_id)?
+            .ok_or_else(|| anyhow::anyhow!("业务组不存在: {}", group_id))?;
+        let changes = Self::stop_order(&group)
+            .iter()
+            .map(|item| self.plan_member(&group, item, *item.status() == ContainerStatus::Stopped, false))
+            .collect();
+        Ok(OperationPlan {
+            title: format!("停止业务组 {}", group.name),
+            changes,
+        })
+    }
+
+    /// 升级镜像的执行计划：`targets` 为（容器ID, 目标镜像），运行中的容器重新创建，其余只修改定义
+    pub fn plan_upgrade(&self, group_id: &GroupId, targets: &[(String, String)]) -> Result<OperationPlan> {
+        let group = self
+            .get_business_group(group_id)?
+            .ok_or_else(|| anyhow::anyhow!("业务组不存在: {}", group_id))?;
+        let changes = Self::upgrade_order(&group, targets)?
+            .into_iter()
+            .map(|(item, target)| {
+                let current = ContainerSpec::parse(item.docker_run_params()).map(|s| s.image).unwrap_or_default();
+                let (action, detail) = match item.status() {
+                    ContainerStatus::Running | ContainerStatus::Starting => {
+                        (PlannedAction::Recreate, format!("{} → {}，拉取后重新创建", current, target))
+                    }
+                    _ => (PlannedAction::Update, format!("{} → {}，下次启动时生效", current, target)),
+                };
+                PlannedChange {
+                    kind: item.kind(),
+                    id: item.id().to_string(),
+                    name: item.name().to_string(),
+                    action,
+                    detail,
+                }
+            })
+            .collect();
+        Ok(OperationPlan {
+            title: format!("升级业务组 {} 的镜像", group.name),
+            changes,
+        })
+    }
+
+    /// 待升级的容器按启动顺序排列，依赖先于依赖方升级
+    fn upgrade_order<'a>(group: &'a BusinessGroup, targets: &'a [(String, String)]) -> Result<Vec<(StartItem<'a>, &'a str)>> {
+        Ok(group
+            .start_order()?
+            .into_iter()
+            .filter_map(|item| {
+                let target = targets.iter().find(|(id, _)| id == item.id())?;
+                Some((item, target.1.as_str()))
+            })
+            .collect())
+    }
+
+    /// 按启动顺序升级组内容器的镜像，`skip` 为已升级的容器数；每升级完一个容器回调一次，返回本次升级的容器数
+    pub fn upgrade_business_group(
+        &self,
+        group_id: &str,
+        targets: &[(String, String)],
+        skip: usize,
+        cancel: &AtomicBool,
+        mut progress: impl FnMut(usize, usize),
+    ) -> Result<usize> {
+        let group = self
+            .get_business_group(&GroupId::from(group_id))?
+            .ok_or_else(|| anyhow::anyhow!("业务组不存在: {}", group_id))?;
+        let order = Self::upgrade_order(&group, targets)?;
+        let mut upgraded = 0;
+        for (index, (item, target)) in order.iter().enumerate().skip(skip) {
+            if cancel.load(Ordering::Relaxed) {
+                return Err(jobs::cancelled());
+            }
+            self.upgrade_member(&GroupId::from(group_id), item.id(), target, cancel)
+                .with_context(|| format!("{} 升级失败，已停止升级业务组", item.name()))?;
+            upgraded += 1;
+            progress(index + 1, order.len());
+        }
+        Ok(upgraded)
+    }
+
+    /// 将组内一个容器的镜像改为目标镜像并在其主机上拉取，运行中的容器停止后按新的定义重新创建
+    pub fn upgrade_member(&self, group_id: &GroupId, entity_id: &str, image: &str, cancel: &AtomicBool) -> Result<()> {
+        self.state.authorize(group_id)?;
+        let group = self
+            .get_business_group(group_id)?
+            .ok_or_else(|| anyhow::anyhow!("业务组不存在: {}", group_id))?;
+        let item = group
+            .start_order()?
+            .into_iter()
+            .find(|item| item.id() == entity_id)
+            .ok_or_else(|| anyhow::anyhow!("容器不存在: {}", entity_id))?;
+        let mut spec = ContainerSpec::parse(item.docker_run_params())?;
+        if spec.image != image {
+            spec.image = image.to_string();
+            match item {
+                StartItem::Middleware(middleware) => {
+                    let mut updated = middleware.clone();
+                    updated.docker_run_params = spec.to_params();
+                    MiddlewareService::new(self.state.clone()).update_middleware(group_id, updated)?;
+                }
+                StartItem::Backend(backend, middleware_id) => {
+                    let mut updated = backend.clone();
+                    updated.docker_run_params = spec.to_params();
+                    BackendService::new(self.state.clone()).update_backend(group_id, middleware_id.map(MiddlewareId::from).as_ref(), updated)?;
+                }
+            }
+        }
+        let host = docker_host(&self.state, item.docker_host_id())?;
+        docker::pull(host.as_ref(), image, cancel, |_| {})?;
+        if matches!(item.status(), ContainerStatus::Running | ContainerStatus::Starting) {
+            self.stop_member(group_id, item)?;
+            let group = self
+                .get_business_group(group_id)?
+                .ok_or_else(|| anyhow::anyhow!("业务组不存在: {}", group_id))?;
+            let item = group
+                .start_order()?
+                .into_iter()
+                .find(|item| item.id() == entity_id)
+                .ok_or_else(|| anyhow::anyhow!("容器不存在: {}", entity_id))?;
+            self.start_member(group_id, item)?;
+        }
+        Ok(())
+    }
+    
+    /// 为业务组创建快照：保存业务组的容器定义与中间层配置，可选导出组内容器挂载的命名卷；
+    /// 每导出一个卷回调一次（已导出数, 总数）
+    pub fn snapshot_group(
+        &self,
+        group_id: &str,
+        path: &str,
+        include_volumes: bool,
+        cancel: &AtomicBool,
+        mut progress: impl FnMut(usize, usize),
+    ) -> Result<SnapshotManifest> {
+        let group = self
+            .get_business_group(&GroupId::from(group_id))?
+            .ok_or_else(|| anyhow::anyhow!("业务组不存在: {}", group_id))?;
+        let volumes = if include_volumes { snapshot::named_volumes(&group) } else { Vec::new() };
+        let mut archives = Vec::new();
+        for (index, volume) in volumes.iter().enumerate() {
+            if cancel.load(Ordering::Relaxed) {
+                return Err(jobs::cancelled());
+            }
+            progress(index, volumes.len());
+            let host = docker_host(&self.state, &volume.host_id)?;
+            let archive = docker::export_volume(host.as_ref(), &volume.image, &volume.name)
+                .with_context(|| format!("导出卷 {} 失败", volume.name))?;
+            archives.push((volume.name.clone(), archive));
         }
+        progress(volumes.len(), volumes.len());
+        let manifest = SnapshotManifest {
+            version: snapshot::SNAPSHOT_VERSION,
+            created_at: Utc::now(),
+            group_name: group.name.clone(),
+            volumes,
+        };
+        snapshot::write(path, &manifest, &group, &archives)?;
+        Ok(manifest)
     }
     
-    /// 停止业务组
-    pub fn stop_business_group(&self, group_id: &str) -> Result<()> {
-        let mut config = self.config_manager.load_config()?;
+    /// 由快照恢复业务组，组内由Docker管理的容器改在目标主机上运行，恢复后为停止状态；`name` 为空时沿用快照中的名称
+    ///
+    /// 快照中的ID已被使用时（如原业务组仍存在）按副本恢复并重新生成ID；显式指定的容器名称
+    /// 在目标主机上已被使用时加后缀，避免启动时删除他人的同名容器。恢复卷数据时目标主机上
+    /// 已存在同名卷则拒绝恢复，不会覆盖已有数据。每写入一个卷回调一次（已写入数, 总数）
+    pub fn restore_group(
+        &self,
+        path: &str,
+        host_id: Option<&str>,
+        name: &str,
+        restore_volumes: bool,
+        cancel: &AtomicBool,
+        mut progress: impl FnMut(usize, usize),
+    ) -> Result<BusinessGroup> {
+        let host_id = host_id.map(str::to_string);
+        let host = docker_host(&self.state, &host_id)?;
+        let snapshot = snapshot::read(path)?;
         
-        if let Some(group) = config.app_state.business_groups.iter_mut().find(|g| g.id == group_id) {
-            group.status = GroupStatus::Stopping;
-            // 这里可以添加实际的停止逻辑
-            group.status = GroupStatus::Stopped;
-            self.config_manager.save_config(&config)
+        let mut group = snapshot.group;
+        let (taken, mut container_names) = self.state.read(|state| {
+            let taken = state.business_groups.iter().any(|g| {
+                g.id == group.id
+                    || group.middlewares.iter().any(|m| g.contains(&m.id) || m.backend_containers.iter().any(|b| g.contains(&b.id)))
+                    || group.backend_containers.iter().any(|b| g.contains(&b.id))
+            });
+            // 目标主机上已使用的显式容器名称
+            let mut names = HashSet::new();
+            for g in &state.business_groups {
+                let members = g.middlewares
+                    .iter()
+                    .map(|m| (&m.docker_run_params, &m.docker_host_id))
+                    .chain(
+                        g.middlewares
+                            .iter()
+                            .flat_map(|m| m.backend_containers.iter())
+                            .chain(g.backend_containers.iter())
+                            .map(|b| (&b.docker_run_params, &b.docker_host_id)),
+                    );
+                names.extend(
+                    members
+                        .filter(|(_, member_host)| **member_host == host_id)
+                        .filter_map(|(params, _)| snapshot::explicit_container_name(params)),
+                );
+            }
+            (taken, names)
+        });
+        if taken {
+            group = group.duplicate();
         } else {
-            anyhow::bail!("业务组不存在: {}", group_id)
+            group.status = GroupStatus::Stopped;
+            group.updated_at = Utc::now();
+            for middleware in &mut group.middlewares {
+                middleware.status = ContainerStatus::Stopped;
+                middleware.health = HealthStatus::Unknown;
+                for backend in &mut middleware.backend_containers {
+                    backend.status = ContainerStatus::Stopped;
+                    backend.health = HealthStatus::Unknown;
+                }
+            }
+            for backend in &mut group.backend_containers {
+                backend.status = ContainerStatus::Stopped;
+                backend.health = HealthStatus::Unknown;
+            }
+        }
+        if !name.trim().is_empty() {
+            group.name = name.trim().to_string();
+        }
+        
+        let mut relocate = |params: &mut String, member_host: &mut Option<String>, kubernetes: bool| -> Result<()> {
+            if params.trim().is_empty() || kubernetes {
+                return Ok(());
+            }
+            *member_host = host_id.clone();
+            let mut spec = ContainerSpec::parse(params)?;
+            if !spec.name.is_empty() {
+                if container_names.contains(&spec.name) {
+                    spec.name = next_copy_name(&spec.name, &container_names);
+                    *params = spec.to_params();
+                }
+                container_names.insert(spec.name);
+            }
+            Ok(())
+        };
+        for middleware in &mut group.middlewares {
+            relocate(&mut middleware.docker_run_params, &mut middleware.docker_host_id, middleware.kubernetes.is_some())?;
+            for backend in &mut middleware.backend_containers {
+                relocate(&mut backend.docker_run_params, &mut backend.docker_host_id, backend.kubernetes.is_some())?;
+            }
+        }
+        for backend in &mut group.backend_containers {
+            relocate(&mut backend.docker_run_params, &mut backend.docker_host_id, backend.kubernetes.is_some())?;
+        }
+        for middleware in &mut group.middlewares {
+            middleware.config.crud_api.instances = middleware.crud_instances();
+        }
+        if self.state.read(|state| state.business_groups.iter().any(|g| g.name == group.name)) {
+            anyhow::bail!("业务组已存在: {}", group.name);
+        }
+        
+        if restore_volumes {
+            let volumes = &snapshot.manifest.volumes;
+            for volume in volumes {
+                if docker::volume_exists(host.as_ref(), &volume.name)? {
+                    anyhow::bail!("目标主机上已存在卷 {}，为避免覆盖已有数据，请先删除该卷或不恢复卷数据", volume.name);
+                }
+            }
+            for (index, volume) in volumes.iter().enumerate() {
+                if cancel.load(Ordering::Relaxed) {
+                    return Err(jobs::cancelled());
+                }
+                progress(index, volumes.len());
+                let archive = snapshot.volumes.get(&volume.name).cloned().unwrap_or_default();
+                docker::import_volume(host.as_ref(), &volume.image, &volume.name, archive)
+                    .with_context(|| format!("恢复卷 {} 失败", volume.name))?;
+            }
+            progress(volumes.len(), volumes.len());
         }
+        
+        self.state.update(|state| {
+            if state.business_groups.iter().any(|g| g.name == group.name) {
+                anyhow::bail!("业务组已存在: {}", group.name);
+            }
+            state.business_groups.push(group.clone());
+            Ok(())
+        })?;
+        Ok(group)
     }
     
     /// 重启业务组
-    pub fn restart_business_group(&self, group_id: &str) -> Result<()> {
+    pub fn restart_business_group(&self, group_id: &GroupId) -> Result<()> {
+        self.state.authorize(group_id)?;
         self.stop_business_group(group_id)?;
         self.start_business_group(group_id)
     }
+
+    /// 滚动重启业务组：按启动顺序逐个重启运行中的后端，每个后端通过健康探测后才重启下一个，
+    /// 未就绪时停止滚动，其余后端保持运行
+    ///
+    /// `skip` 为已完成重启的后端数，用于从中断处继续；每开始重启一个后端回调一次（序号, 全部待重启后端），
+    /// 返回本次重启的后端数
+    pub fn rolling_restart_business_group(
+        &self,
+        group_id: &str,
+        skip: usize,
+        cancel: &AtomicBool,
+        mut progress: impl FnMut(usize, &[BackendContainer]),
+    ) -> Result<usize> {
+        let group = self
+            .get_business_group(&GroupId::from(group_id))?
+            .ok_or_else(|| anyhow::anyhow!("业务组不存在: {}", group_id))?;
+        let owners: HashMap<&str, &str> = group.middlewares
+            .iter()
+            .flat_map(|m| m.backend_containers.iter().map(move |b| (b.id.as_str(), m.id.as_str())))
+            .collect();
+        let backends: Vec<BackendContainer> = group.backend_start_order
+            .stages(group.middlewares.iter().flat_map(|m| m.backend_containers.iter()).chain(group.backend_containers.iter()))
+            .into_iter()
+            .flat_map(|(_, backends)| backends)
+            .filter(|b| b.status == ContainerStatus::Running)
+            .cloned()
+            .collect();
+
+        let service = BackendService::new(self.state.clone());
+        let mut restarted = 0;
+        for (index, backend) in backends.iter().enumerate().skip(skip) {
+            if cancel.load(Ordering::Relaxed) {
+                return Err(jobs::cancelled());
+            }
+            progress(index, &backends);
+            let middleware_id = owners.get(backend.id.as_str()).copied();
+            service
+                .restart_backend(&GroupId::from(&group.id), middleware_id.map(MiddlewareId::from).as_ref(), &BackendId::from(&backend.id))
+                .with_context(|| format!("{} 重启失败，已停止滚动重启", backend.name))?;
+            let current = service.get_backend(&GroupId::from(&group.id), middleware_id.map(MiddlewareId::from).as_ref(), &BackendId::from(&backend.id))?;
+            service
+                .wait_ready(&group, &[&current], cancel)
+                .with_context(|| format!("{} 未就绪，已停止滚动重启", backend.name))?;
+            restarted += 1;
+        }
+        progress(backends.len(), &backends);
+        self.set_group_status(&GroupId::from(group_id), GroupStatus::Running)?;
+        Ok(restarted)
+    }
 }
 
 /// 中间层容器服务
 pub struct MiddlewareService {
-    config_manager: ConfigManager,
+    state: StateStore,
 }
 
 impl MiddlewareService {
     /// 创建新的中间层容器服务
-    pub fn new(config_manager: ConfigManager) -> Self {
+    pub fn new(state: StateStore) -> Self {
         Self {
-            config_manager,
+            state,
         }
     }
     
     /// 添加中间层容器到业务组
-    pub fn add_middleware_to_group(&self, group_id: &str, middleware: MiddlewareContainer) -> Result<()> {
-        let mut config = self.config_manager.load_config()?;
-        
-        if let Some(group) = config.app_state.business_groups.iter_mut().find(|g| g.id == group_id) {
-            group.middlewares.push(middleware);
-            self.config_manager.save_config(&config)
-        } else {
-            anyhow::bail!("业务组不存在: {}", group_id)
-        }
+    pub fn add_middleware_to_group(&self, group_id: &GroupId, middleware: MiddlewareContainer) -> Result<()> {
+        self.state.update(|state| {
+            state.group_mut(group_id)?.middlewares.push(middleware);
+            Ok(())
+        })
     }
     
     /// 更新中间层容器
-    pub fn update_middleware(&self, group_id: &str, middleware: MiddlewareContainer) -> Result<()> {
-        let mut config = self.config_manager.load_config()?;
-        
-        if let Some(group) = config.app_state.business_groups.iter_mut().find(|g| g.id == group_id) {
-            if let Some(index) = group.middlewares.iter().position(|m| m.id == middleware.id) {
-                group.middlewares[index] = middleware;
-                self.config_manager.save_config(&config)
-            } else {
-                anyhow::bail!("中间层容器不存在: {}", middleware.id)
+    pub fn update_middleware(&self, group_id: &GroupId, middleware: MiddlewareContainer) -> Result<()> {
+        self.state.update(|state| {
+            let middleware_id = middleware.id.clone();
+            *state.middleware_mut(group_id, &MiddlewareId::from(&middleware_id))? = middleware;
+            Ok(())
+        })
+    }
+    
+    /// 将同一配置字段值一次性写入多个中间层，返回更新的数量
+    pub fn apply_config_field(&self, targets: &[String], field: AppConfigField, value: &str) -> Result<usize> {
+        self.state.update(|state| {
+            let mut updated = 0;
+            let groups = state.business_groups.matching_mut(|g| g.middlewares.iter().any(|m| targets.contains(&m.id)));
+            for middleware in groups.flat_map(|g| g.middlewares.iter_mut()) {
+                if targets.contains(&middleware.id) {
+                    field.set(&mut middleware.config, value)?;
+                    updated += 1;
+                }
             }
-        } else {
-            anyhow::bail!("业务组不存在: {}", group_id)
-        }
+            Ok(updated)
+        })
     }
     
     /// 删除中间层容器
-    pub fn delete_middleware(&self, group_id: &str, middleware_id: &str) -> Result<()> {
-        let mut config = self.config_manager.load_config()?;
-        
-        if let Some(group) = config.app_state.business_groups.iter_mut().find(|g| g.id == group_id) {
-            group.middlewares.retain(|m| m.id != middleware_id);
-            self.config_manager.save_config(&config)
-        } else {
-            anyhow::bail!("业务组不存在: {}", group_id)
-        }
+    pub fn delete_middleware(&self, group_id: &GroupId, middleware_id: &MiddlewareId) -> Result<()> {
+        self.state.authorize(group_id)?;
+        self.state.update(|state| {
+            state.group_mut(group_id)?.middlewares.retain(|m| m.id != middleware_id);
+            Ok(())
+        })
+    }
+    
+    /// 将指定的后端容器从一个中间层转移到另一个中间层
+    pub fn transfer_backends(&self, from_id: &str, to_id: &str, backend_ids: &[String]) -> Result<()> {
+        self.state.update(|state| {
+            let from = state
+                .find_middleware_mut(&MiddlewareId::from(from_id))
+                .ok_or_else(|| anyhow::anyhow!("中间层容器不存在: {}", from_id))?;
+            let (moved, kept): (Vec<BackendContainer>, Vec<BackendContainer>) = std::mem::take(&mut from.backend_containers)
+                .into_iter()
+                .partition(|b| backend_ids.contains(&b.id));
+            from.backend_containers = kept;
+            
+            let to = state
+                .find_middleware_mut(&MiddlewareId::from(to_id))
+                .ok_or_else(|| anyhow::anyhow!("中间层容器不存在: {}", to_id))?;
+            to.backend_containers.extend(moved);
+            Ok(())
+        })
+    }
+    
+    /// 设置中间层容器状态
+    pub fn set_middleware_status(&self, group_id: &GroupId, middleware_id: &MiddlewareId, status: ContainerStatus) -> Result<()> {
+        self.state.authorize(group_id)?;
+        self.state.update(|state| {
+            state.middleware_mut(group_id, middleware_id)?.status = status;
+            Ok(())
+        })
     }
     
-    /// 启动中间层容器
-    pub fn start_middleware(&self, group_id: &str, middleware_id: &str) -> Result<()> {
-        let mut config = self.config_manager.load_config()?;
+    /// 设置中间层容器健康状态，状态未变化时不写入配置
+    pub fn set_middleware_health(&self, middleware_id: &MiddlewareId, health: HealthStatus) -> Result<()> {
+        let unchanged = self.state.read(|state| {
+            state.find_middleware(middleware_id).is_some_and(|(_, m)| m.health == health)
+        });
+        if unchanged {
+            return Ok(());
+        }
         
-        if let Some(group) = config.app_state.business_groups.iter_mut().find(|g| g.id == group_id) {
-            if let Some(middleware) = group.middlewares.iter_mut().find(|m| m.id == middleware_id) {
-                middleware.status = ContainerStatus::Starting;
-                // 这里可以添加实际的启动逻辑
-                middleware.status = ContainerStatus::Running;
-                self.config_manager.save_config(&config)
-            } else {
-                anyhow::bail!("中间层容器不存在: {}", middleware_id)
+        self.state.update(|state| {
+            let middleware = state
+                .find_middleware_mut(middleware_id)
+                .ok_or_else(|| anyhow::anyhow!("中间层容器不存在: {}", middleware_id))?;
+            middleware.health = health;
+            Ok(())
+        })
+    }
+    
+    /// 查找中间层容器
+    fn get_middleware(&self, group_id: &GroupId, middleware_id: &MiddlewareId) -> Result<MiddlewareContainer> {
+        self.state.read(|state| Ok(state.middleware(group_id, middleware_id)?.1.clone()))
+    }
+    
+    /// 中间层使用的运行时，由外部管理时为空
+    fn middleware_runtime(&self, group_id: &GroupId, middleware_id: &MiddlewareId) -> Result<Option<Box<dyn ContainerRuntime>>> {
+        self.state.read(|state| {
+            let (group, middleware) = state.middleware(group_id, middleware_id)?;
+            runtime::for_middleware(state, group, middleware)
+        })
+    }
+
+    /// 记录中间层部署到的集群工作负载
+    fn set_middleware_link(&self, group_id: &GroupId, middleware_id: &MiddlewareId, link: KubernetesLink) -> Result<()> {
+        self.state.update(|state| {
+            state.middleware_mut(group_id, middleware_id)?.kubernetes = Some(link);
+            Ok(())
+        })
+    }
+
+    /// 启动中间层容器，置为启动中，预热检查结束后由 `complete_start` 更新最终状态
+    ///
+    /// 由中间层选择的运行时创建并启动容器，失败时置为错误；
+    /// 宿主机端口已被其他运行中的容器占用时拒绝启动
+    pub fn start_middleware(&self, group_id: &GroupId, middleware_id: &MiddlewareId) -> Result<()> {
+        self.state.authorize(group_id)?;
+        let middleware = self.get_middleware(group_id, middleware_id)?;
+        let runtime = match self.middleware_runtime(group_id, middleware_id) {
+            Ok(Some(runtime)) => runtime,
+            Ok(None) => return self.set_middleware_status(group_id, middleware_id, ContainerStatus::Starting),
+            Err(e) => {
+                self.set_middleware_status(group_id, middleware_id, ContainerStatus::Error)?;
+                return Err(e);
             }
-        } else {
-            anyhow::bail!("业务组不存在: {}", group_id)
+        };
+        ensure_ports_free(&self.state, &middleware.id, &middleware.docker_host_id, runtime.host_ports())?;
+        if let Err(e) = runtime.start() {
+            self.set_middleware_status(group_id, middleware_id, ContainerStatus::Error)?;
+            return Err(e);
+        }
+        if let Some(link) = runtime.kubernetes_link() {
+            self.set_middleware_link(group_id, middleware_id, link)?;
         }
+        self.set_middleware_status(group_id, middleware_id, ContainerStatus::Starting)
+    }
+
+    /// 调整中间层的副本数，运行中的中间层立即由运行时扩缩容
+    pub fn scale_middleware(&self, group_id: &GroupId, middleware_id: &MiddlewareId, replicas: u32) -> Result<()> {
+        self.state.authorize(group_id)?;
+        let middleware = self.get_middleware(group_id, middleware_id)?;
+        if matches!(middleware.status, ContainerStatus::Running | ContainerStatus::Starting)
+            && let Some(runtime) = self.middleware_runtime(group_id, middleware_id)?
+        {
+            runtime.scale(replicas)?;
+        }
+        self.state.update(|state| {
+            state.middleware_mut(group_id, middleware_id)?.replicas = replicas;
+            Ok(())
+        })
     }
     
-    /// 停止中间层容器
-    pub fn stop_middleware(&self, group_id: &str, middleware_id: &str) -> Result<()> {
-        let mut config = self.config_manager.load_config()?;
-        
-        if let Some(group) = config.app_state.business_groups.iter_mut().find(|g| g.id == group_id) {
-            if let Some(middleware) = group.middlewares.iter_mut().find(|m| m.id == middleware_id) {
-                middleware.status = ContainerStatus::Stopping;
-                // 这里可以添加实际的停止逻辑
-                middleware.status = ContainerStatus::Stopped;
-                self.config_manager.save_config(&config)
-            } else {
-                anyhow::bail!("中间层容器不存在: {}", middleware_id)
-            }
-        } else {
-            anyhow::bail!("业务组不存在: {}", group_id)
+    /// 根据预热结果将中间层置为运行中或错误
+    pub fn complete_start(&self, group_id: &GroupId, middleware_id: &MiddlewareId, warmed_up: bool) -> Result<()> {
+        self.state.authorize(group_id)?;
+        let status = if warmed_up { ContainerStatus::Running } else { ContainerStatus::Error };
+        self.set_middleware_status(group_id, middleware_id, status)
+    }
+    
+    /// 停止中间层容器：Docker运行时停止并保留容器，Kubernetes运行时缩容到0
+    pub fn stop_middleware(&self, group_id: &GroupId, middleware_id: &MiddlewareId) -> Result<()> {
+        self.state.authorize(group_id)?;
+        if let Some(runtime) = self.middleware_runtime(group_id, middleware_id)? {
+            runtime.stop()?;
         }
+        self.set_middleware_status(group_id, middleware_id, ContainerStatus::Stopped)
     }
     
     /// 重启中间层容器
-    pub fn restart_middleware(&self, group_id: &str, middleware_id: &str) -> Result<()> {
+    pub fn restart_middleware(&self, group_id: &GroupId, middleware_id: &MiddlewareId) -> Result<()> {
+        self.state.authorize(group_id)?;
         self.stop_middleware(group_id, middleware_id)?;
         self.start_middleware(group_id, middleware_id)
     }
 }
 
+/// 等待后端通过健康探测的最长时间
+const BACKEND_READY_TIMEOUT: Duration = Duration::from_secs(120);
+/// 缩容时读实例移出实例列表后，等待进行中的请求完成的时间
+const SCALE_IN_DRAIN: Duration = Duration::from_secs(10);
+/// 克隆后端时最多顺延的端口数
+const MAX_CLONE_PORT_OFFSET: u16 = 1000;
+
 /// 后端容器服务
 pub struct BackendService {
-    config_manager: ConfigManager,
+    state: StateStore,
 }
 
 impl BackendService {
     /// 创建新的后端容器服务
-    pub fn new(config_manager: ConfigManager) -> Self {
+    pub fn new(state: StateStore) -> Self {
         Self {
-            config_manager,
+            state,
         }
     }
     
-    /// 添加后端容器到中间层
-    pub fn add_backend_to_middleware(&self, group_id: &str, middleware_id: &str, backend: BackendContainer) -> Result<()> {
-        let mut config = self.config_manager.load_config()?;
-        
-        if let Some(group) = config.app_state.business_groups.iter_mut().find(|g| g.id == group_id) {
-            if let Some(middleware) = group.middlewares.iter_mut().find(|m| m.id == middleware_id) {
-                middleware.backend_containers.push(backend);
-                self.config_manager.save_config(&config)
-            } else {
-                anyhow::bail!("中间层容器不存在: {}", middleware_id)
+    /// 修改业务组或中间层下的后端容器列表
+    fn update_backends(
+        &self,
+        group_id: &str,
+        middleware_id: Option<&str>,
+        f: impl FnOnce(&mut Vec<BackendContainer>) -> Result<()>,
+    ) -> Result<()> {
+        self.state.update(|state| f(state.backends_mut(&GroupId::from(group_id), middleware_id.map(MiddlewareId::from).as_ref())?))
+    }
+    
+    /// 克隆后端到同一列表中：名称后缀递增，地址端口与映射的宿主机端口顺延到未被其他容器使用的端口，返回新后端
+    pub fn clone_backend(&self, group_id: &GroupId, middleware_id: Option<&MiddlewareId>, backend_id: &BackendId) -> Result<BackendContainer> {
+        let clone = self.state.read(|state| {
+            let (group, middleware, source) = state.backend(group_id, middleware_id, backend_id)?;
+            let siblings = middleware.map_or(&group.backend_containers, |m| &m.backend_containers);
+            
+            // 所有容器已使用的地址（主机:端口，含副本）与容器名称
+            let mut used_targets = HashSet::new();
+            let mut container_names = HashSet::new();
+            for group in &state.business_groups {
+                for middleware in &group.middlewares {
+                    used_targets.extend(tunnel::target(&middleware.url));
+                    container_names.extend(ContainerSpec::parse(&middleware.docker_run_params).ok().map(|s| s.name));
+                }
+                let backends = group.middlewares
+                    .iter()
+                    .flat_map(|m| m.backend_containers.iter())
+                    .chain(group.backend_containers.iter());
+                for backend in backends {
+                    used_targets.extend((0..backend.replicas.max(1)).filter_map(|i| tunnel::target(&backend.replica_url(i))));
+                    container_names.extend(ContainerSpec::parse(&backend.docker_run_params).ok().map(|s| s.name));
+                }
             }
-        } else {
-            anyhow::bail!("业务组不存在: {}", group_id)
-        }
+            let names: HashSet<String> = siblings.iter().map(|b| b.name.clone()).collect();
+            let name = next_copy_name(&source.name, &names);
+            let container_name = ContainerSpec::parse(&source.docker_run_params)
+                .map(|spec| next_copy_name(&spec.name, &container_names))
+                .unwrap_or_default();
+            
+            // 从原后端副本占用的端口之后开始查找
+            let first = u16::try_from(source.replicas.max(1)).unwrap_or(u16::MAX);
+            for offset in first..=first.saturating_add(MAX_CLONE_PORT_OFFSET) {
+                let clone = source.clone_as(&name, &container_name, offset)?;
+                let url_free = (0..clone.replicas.max(1))
+                    .all(|i| tunnel::target(&clone.replica_url(i)).is_none_or(|t| !used_targets.contains(&t)));
+                let ports_free = clone.docker_run_params.trim().is_empty()
+                    || state.port_conflicts(&clone.id, &clone.docker_host_id, &clone.container_spec()?.ports).is_empty();
+                if url_free && ports_free {
+                    return Ok(clone);
+                }
+            }
+            anyhow::bail!("在 {} 个端口内未找到可用端口", MAX_CLONE_PORT_OFFSET)
+        })?;
+        self.update_backends(group_id, middleware_id.map(MiddlewareId::as_str), |backends| {
+            let index = backends.iter().position(|b| b.id == backend_id).map_or(backends.len(), |i| i + 1);
+            backends.insert(index, clone.clone());
+            Ok(())
+        })?;
+        Ok(clone)
+    }
+    
+    /// 添加后端容器到中间层
+    pub fn add_backend_to_middleware(&self, group_id: &GroupId, middleware_id: &MiddlewareId, backend: BackendContainer) -> Result<()> {
+        self.update_backends(group_id, Some(middleware_id), |backends| {
+            backends.push(backend);
+            Ok(())
+        })
     }
     
     /// 直接添加后端容器到业务组
-    pub fn add_backend_to_group(&self, group_id: &str, backend: BackendContainer) -> Result<()> {
-        let mut config = self.config_manager.load_config()?;
-        
-        if let Some(group) = config.app_state.business_groups.iter_mut().find(|g| g.id == group_id) {
-            group.backend_containers.push(backend);
-            self.config_manager.save_config(&config)
-        } else {
-            anyhow::bail!("业务组不存在: {}", group_id)
-        }
+    pub fn add_backend_to_group(&self, group_id: &GroupId, backend: BackendContainer) -> Result<()> {
+        self.update_backends(group_id, None, |backends| {
+            backends.push(backend);
+            Ok(())
+        })
     }
     
     /// 更新后端容器
-    pub fn update_backend(&self, group_id: &str, middleware_id: Option<&str>, backend: BackendContainer) -> Result<()> {
-        let mut config = self.config_manager.load_config()?;
-        
-        if let Some(group) = config.app_state.business_groups.iter_mut().find(|g| g.id == group_id) {
-            match middleware_id {
-                Some(middleware_id) => {
-                    // 更新中间层下的后端容器
-                    if let Some(middleware) = group.middlewares.iter_mut().find(|m| m.id == middleware_id) {
-                        if let Some(index) = middleware.backend_containers.iter().position(|b| b.id == backend.id) {
-                            middleware.backend_containers[index] = backend;
-                            self.config_manager.save_config(&config)
-                        } else {
-                            anyhow::bail!("后端容器不存在: {}", backend.id)
-                        }
-                    } else {
-                        anyhow::bail!("中间层容器不存在: {}", middleware_id)
-                    }
-                },
-                None => {
-                    // 更新业务组直接管理的后端容器
-                    if let Some(index) = group.backend_containers.iter().position(|b| b.id == backend.id) {
-                        group.backend_containers[index] = backend;
-                        self.config_manager.save_config(&config)
-                    } else {
-                        anyhow::bail!("后端容器不存在: {}", backend.id)
-                    }
+    pub fn update_backend(&self, group_id: &GroupId, middleware_id: Option<&MiddlewareId>, backend: BackendContainer) -> Result<()> {
+        self.update_backends(group_id, middleware_id.map(MiddlewareId::as_str), |backends| {
+            if let Some(index) = backends.iter().position(|b| b.id == backend.id) {
+                backends[index] = backend;
+                Ok(())
+            } else {
+                anyhow::bail!("后端容器不存在: {}", backend.id)
+            }
+        })
+    }
+    
+    /// 批量写入后端的超时与重试次数，所在中间层的实例列表随之重新生成并推送；返回有变化的后端数
+    pub fn apply_tuning(&self, tunings: &[BackendTuning]) -> Result<usize> {
+        for tuning in tunings {
+            self.state.authorize(&GroupId::from(&tuning.group_id))?;
+        }
+        let changed = self.state.update(|state| {
+            let mut changed = 0;
+            for tuning in tunings {
+                let backend = state.backend_mut(&GroupId::from(&tuning.group_id), tuning.middleware_id.as_deref().map(MiddlewareId::from).as_ref(), &BackendId::from(&tuning.backend_id))?;
+                if backend.timeout != tuning.suggestion.timeout_ms || backend.retries != tuning.suggestion.retries {
+                    backend.timeout = tuning.suggestion.timeout_ms;
+                    backend.retries = tuning.suggestion.retries;
+                    changed += 1;
                 }
             }
-        } else {
-            anyhow::bail!("业务组不存在: {}", group_id)
+            Ok(changed)
+        })?;
+        let middlewares: HashSet<(&str, &str)> = tunings
+            .iter()
+            .filter_map(|t| t.middleware_id.as_deref().map(|m| (t.group_id.as_str(), m)))
+            .collect();
+        for (group_id, middleware_id) in middlewares {
+            self.publish_instances(&GroupId::from(group_id), Some(&MiddlewareId::from(middleware_id)))?;
+        }
+        Ok(changed)
+    }
+    
+    /// 删除后端容器，先由运行时删除其所有副本，避免停止的容器遗留在主机上
+    pub fn delete_backend(&self, group_id: &GroupId, middleware_id: Option<&MiddlewareId>, backend_id: &BackendId) -> Result<()> {
+        self.state.authorize(group_id)?;
+        if let Some(runtime) = self.backend_runtime(group_id, middleware_id, backend_id)? {
+            runtime.remove()?;
         }
+        self.update_backends(group_id, middleware_id.map(MiddlewareId::as_str), |backends| {
+            backends.retain(|b| b.id != backend_id);
+            Ok(())
+        })
     }
     
-    /// 删除后端容器
-    pub fn delete_backend(&self, group_id: &str, middleware_id: Option<&str>, backend_id: &str) -> Result<()> {
-        let mut config = self.config_manager.load_config()?;
+    /// 设置后端容器状态
+    fn set_backend_status(&self, group_id: &GroupId, middleware_id: Option<&MiddlewareId>, backend_id: &BackendId, status: ContainerStatus) -> Result<()> {
+        self.state.update(|state| {
+            state.backend_mut(group_id, middleware_id, backend_id)?.status = status;
+            Ok(())
+        })
+    }
+    
+    /// 设置后端容器健康状态，状态未变化时不写入配置
+    pub fn set_backend_health(&self, backend_id: &BackendId, health: HealthStatus) -> Result<()> {
+        if self.state.read(|state| state.find_backend(backend_id).is_some_and(|b| b.health == health)) {
+            return Ok(());
+        }
         
-        if let Some(group) = config.app_state.business_groups.iter_mut().find(|g| g.id == group_id) {
-            match middleware_id {
-                Some(middleware_id) => {
-                    // 删除中间层下的后端容器
-                    if let Some(middleware) = group.middlewares.iter_mut().find(|m| m.id == middleware_id) {
-                        middleware.backend_containers.retain(|b| b.id != backend_id);
-                        self.config_manager.save_config(&config)
-                    } else {
-                        anyhow::bail!("中间层容器不存在: {}", middleware_id)
-                    }
-                },
-                None => {
-                    // 删除业务组直接管理的后端容器
-                    group.backend_containers.retain(|b| b.id != backend_id);
-                    self.config_manager.save_config(&config)
-                }
-            }
-        } else {
-            anyhow::bail!("业务组不存在: {}", group_id)
-        }
-    }
-    
-    /// 启动后端容器
-    pub fn start_backend(&self, group_id: &str, middleware_id: Option<&str>, backend_id: &str) -> Result<()> {
-        let mut config = self.config_manager.load_config()?;
-        
-        if let Some(group) = config.app_state.business_groups.iter_mut().find(|g| g.id == group_id) {
-            match middleware_id {
-                Some(middleware_id) => {
-                    // 启动中间层下的后端容器
-                    if let Some(middleware) = group.middlewares.iter_mut().find(|m| m.id == middleware_id) {
-                        if let Some(backend) = middleware.backend_containers.iter_mut().find(|b| b.id == backend_id) {
-                            backend.status = ContainerStatus::Starting;
-                            // 这里可以添加实际的启动逻辑
-                            backend.status = ContainerStatus::Running;
-                            self.config_manager.save_config(&config)
-                        } else {
-                            anyhow::bail!("后端容器不存在: {}", backend_id)
-                        }
-                    } else {
-                        anyhow::bail!("中间层容器不存在: {}", middleware_id)
-                    }
-                },
-                None => {
-                    // 启动业务组直接管理的后端容器
-                    if let Some(backend) = group.backend_containers.iter_mut().find(|b| b.id == backend_id) {
-                        backend.status = ContainerStatus::Starting;
-                        // 这里可以添加实际的启动逻辑
-                        backend.status = ContainerStatus::Running;
-                        self.config_manager.save_config(&config)
-                    } else {
-                        anyhow::bail!("后端容器不存在: {}", backend_id)
-                    }
-                }
-            }
-        } else {
-            anyhow::bail!("业务组不存在: {}", group_id)
-        }
-    }
-    
-    /// 停止后端容器
-    pub fn stop_backend(&self, group_id: &str, middleware_id: Option<&str>, backend_id: &str) -> Result<()> {
-        let mut config = self.config_manager.load_config()?;
-        
-        if let Some(group) = config.app_state.business_groups.iter_mut().find(|g| g.id == group_id) {
-            match middleware_id {
-                Some(middleware_id) => {
-                    // 停止中间层下的后端容器
-                    if let Some(middleware) = group.middlewares.iter_mut().find(|m| m.id == middleware_id) {
-                        if let Some(backend) = middleware.backend_containers.iter_mut().find(|b| b.id == backend_id) {
-                            backend.status = ContainerStatus::Stopping;
-                            // 这里可以添加实际的停止逻辑
-                            backend.status = ContainerStatus::Stopped;
-                            self.config_manager.save_config(&config)
-                        } else {
-                            anyhow::bail!("后端容器不存在: {}", backend_id)
-                        }
-                    } else {
-                        anyhow::bail!("中间层容器不存在: {}", middleware_id)
-                    }
-                },
-                None => {
-                    // 停止业务组直接管理的后端容器
-                    if let Some(backend) = group.backend_containers.iter_mut().find(|b| b.id == backend_id) {
-                        backend.status = ContainerStatus::Stopping;
-                        // 这里可以添加实际的停止逻辑
-                        backend.status = ContainerStatus::Stopped;
-                        self.config_manager.save_config(&config)
-                    } else {
-                        anyhow::bail!("后端容器不存在: {}", backend_id)
-                    }
-                }
+        self.state.update(|state| {
+            let backend = state
+                .find_backend_mut(backend_id)
+                .ok_or_else(|| anyhow::anyhow!("后端容器不存在: {}", backend_id))?;
+            backend.health = health;
+            Ok(())
+        })
+    }
+    
+    /// 查找后端容器
+    fn get_backend(&self, group_id: &GroupId, middleware_id: Option<&MiddlewareId>, backend_id: &BackendId) -> Result<BackendContainer> {
+        self.state.read(|state| Ok(state.backend(group_id, middleware_id, backend_id)?.2.clone()))
+    }
+    
+    /// 后端使用的运行时，由外部管理时为空
+    fn backend_runtime(&self, group_id: &GroupId, middleware_id: Option<&MiddlewareId>, backend_id: &BackendId) -> Result<Option<Box<dyn ContainerRuntime>>> {
+        self.state.read(|state| {
+            let (group, middleware, backend) = state.backend(group_id, middleware_id, backend_id)?;
+            runtime::for_backend(state, group, middleware, backend)
+        })
+    }
+
+    /// 修改后端容器的字段
+    fn modify_backend(&self, group_id: &GroupId, middleware_id: Option<&MiddlewareId>, backend_id: &BackendId, modify: impl FnOnce(&mut BackendContainer)) -> Result<()> {
+        self.state.update(|state| {
+            modify(state.backend_mut(group_id, middleware_id, backend_id)?);
+            Ok(())
+        })
+    }
+
+    /// 启动后端容器，由所属中间层选择的运行时创建并启动容器，失败时置为错误；
+    /// 宿主机端口已被其他运行中的容器占用时拒绝启动
+    pub fn start_backend(&self, group_id: &GroupId, middleware_id: Option<&MiddlewareId>, backend_id: &BackendId) -> Result<()> {
+        self.state.authorize(group_id)?;
+        let backend = self.get_backend(group_id, middleware_id, backend_id)?;
+        let runtime = match self.backend_runtime(group_id, middleware_id, backend_id) {
+            Ok(Some(runtime)) => runtime,
+            Ok(None) => return self.set_backend_status(group_id, middleware_id, backend_id, ContainerStatus::Running),
+            Err(e) => {
+                self.set_backend_status(group_id, middleware_id, backend_id, ContainerStatus::Error)?;
+                return Err(e);
             }
-        } else {
-            anyhow::bail!("业务组不存在: {}", group_id)
+        };
+        ensure_ports_free(&self.state, &backend.id, &backend.docker_host_id, runtime.host_ports())?;
+        if let Err(e) = runtime.start() {
+            self.set_backend_status(group_id, middleware_id, backend_id, ContainerStatus::Error)?;
+            return Err(e);
         }
+        if let Some(link) = runtime.kubernetes_link() {
+            self.modify_backend(group_id, middleware_id, backend_id, |b| b.kubernetes = Some(link))?;
+        }
+        self.set_backend_status(group_id, middleware_id, backend_id, ContainerStatus::Running)
     }
     
-    /// 重启后端容器
-    pub fn restart_backend(&self, group_id: &str, middleware_id: Option<&str>, backend_id: &str) -> Result<()> {
-        self.stop_backend(group_id, middleware_id, backend_id)?;
-        self.start_backend(group_id, middleware_id, backend_id)
+    /// 停止后端容器：Docker运行时停止并保留容器，Kubernetes运行时缩容到0
+    pub fn stop_backend(&self, group_id: &GroupId, middleware_id: Option<&MiddlewareId>, backend_id: &BackendId) -> Result<()> {
+        self.state.authorize(group_id)?;
+        if let Some(runtime) = self.backend_runtime(group_id, middleware_id, backend_id)? {
+            runtime.stop()?;
+        }
+        self.set_backend_status(group_id, middleware_id, backend_id, ContainerStatus::Stopped)
     }
-}
 
-/// API服务
-pub struct ApiService {
-    api_client: Option<ApiClient>,
-}
+    /// 调整后端的副本数，运行中的后端立即由运行时扩缩容，并将各副本的地址注册到中间层的实例列表
+    pub fn scale_backend(&self, group_id: &GroupId, middleware_id: Option<&MiddlewareId>, backend_id: &BackendId, replicas: u32) -> Result<()> {
+        self.state.authorize(group_id)?;
+        let backend = self.get_backend(group_id, middleware_id, backend_id)?;
+        if matches!(backend.status, ContainerStatus::Running | ContainerStatus::Starting)
+            && let Some(runtime) = self.backend_runtime(group_id, middleware_id, backend_id)?
+        {
+            // Docker运行时新增的副本占用新的宿主机端口
+            if !runtime.host_ports().is_empty() && replicas > backend.replicas {
+                let spec = backend.container_spec()?;
+                let ports: Vec<PortMapping> = (backend.replicas..replicas).flat_map(|index| spec.replica_ports(index)).collect();
+                ensure_ports_free(&self.state, &backend.id, &backend.docker_host_id, &ports)?;
+            }
+            runtime.scale(replicas)?;
+        }
+        self.modify_backend(group_id, middleware_id, backend_id, |b| b.replicas = replicas)?;
+        self.publish_instances(group_id, middleware_id)
+    }
 
-impl ApiService {
-    /// 创建新的API服务
-    pub fn new() -> Self {
-        Self {
-            api_client: None,
+    /// 按计划在中间层下新建已停止的读实例，返回新实例
+    pub fn add_read_replicas(&self, group_id: &GroupId, middleware_id: &MiddlewareId, plan: &ReadScaleOut) -> Result<Vec<BackendContainer>> {
+        let middleware = self.state.read(|state| Ok::<_, anyhow::Error>(state.middleware(group_id, middleware_id)?.1.clone()))?;
+        let backends = plan.backends(&middleware)?;
+        self.update_backends(group_id, Some(middleware_id), |list| {
+            list.extend(backends.iter().cloned());
+            Ok(())
+        })?;
+        Ok(backends)
+    }
+
+    /// 将孤儿容器纳管为业务组或中间层下的后端，纳管到中间层时沿用其超时与重试设置并重新生成实例列表
+    pub fn adopt_orphan(&self, group_id: &GroupId, middleware_id: Option<&MiddlewareId>, orphan: &Orphan) -> Result<BackendContainer> {
+        if !orphan.adoptable() {
+            anyhow::bail!("容器 {} {}，不能纳管", orphan.container.name, orphan.reason.label());
+        }
+        let id = &orphan.container.model_id;
+        let exists = self.state.read(|state| state.find_middleware(&MiddlewareId::from(id)).is_some() || state.find_backend(&BackendId::from(id)).is_some());
+        if exists {
+            anyhow::bail!("容器 {} 已在配置中", orphan.container.name);
+        }
+        let mut backend = orphan.to_backend();
+        if let Some(middleware_id) = middleware_id {
+            let crud_api = self.state.read(|state| Ok::<_, anyhow::Error>(state.middleware(group_id, middleware_id)?.1.config.crud_api.clone()))?;
+            backend.timeout = crud_api.timeout;
+            backend.retries = crud_api.retries;
         }
+        self.update_backends(group_id, middleware_id.map(MiddlewareId::as_str), |list| {
+            list.push(backend.clone());
+            Ok(())
+        })?;
+        self.publish_instances(group_id, middleware_id)?;
+        Ok(backend)
     }
     
-    /// 连接到API服务器
-    pub fn connect_to_api(&mut self, base_url: &str, timeout: u64) -> Result<()> {
-        let config = ApiClientConfig {
-            base_url: base_url.to_string(),
-            timeout,
-        };
-        
-        let client = ApiClient::new(config)?;
-        self.api_client = Some(client);
-        Ok(())
+    /// 将负载均衡配置中的上游服务器导入为中间层下的后端，地址已存在的服务器跳过，
+    /// 导入后重新生成中间层的实例列表。返回新增的后端
+    pub fn import_upstream(&self, group_id: &GroupId, middleware_id: &MiddlewareId, servers: &[UpstreamServer]) -> Result<Vec<BackendContainer>> {
+        let middleware = self.state.read(|state| Ok::<_, anyhow::Error>(state.middleware(group_id, middleware_id)?.1.clone()))?;
+        let mut backends: Vec<BackendContainer> = Vec::new();
+        for server in servers {
+            let exists = middleware.backend_containers.iter().chain(&backends).any(|b| b.url == server.url);
+            if !exists {
+                backends.push(server.to_backend(&middleware));
+            }
+        }
+        if backends.is_empty() {
+            return Ok(backends);
+        }
+        self.update_backends(group_id, Some(middleware_id), |list| {
+            list.extend(backends.iter().cloned());
+            Ok(())
+        })?;
+        self.publish_instances(group_id, Some(middleware_id))?;
+        Ok(backends)
+    }
+
+    /// 启动新建的读实例并等待通过健康探测，再重新生成中间层的实例列表；
+    /// 任一步失败或取消时停止并删除这些实例，避免未就绪的实例被调度。每启动一个实例回调一次已启动数
+    pub fn scale_out_reads(
+        &self,
+        group_id: &str,
+        middleware_id: &str,
+        backend_ids: &[String],
+        cancel: &AtomicBool,
+        mut progress: impl FnMut(usize),
+    ) -> Result<usize> {
+        let result = (|| {
+            let mut started = Vec::new();
+            for backend_id in backend_ids {
+                if cancel.load(Ordering::Relaxed) {
+                    return Err(jobs::cancelled());
+                }
+                progress(started.len());
+                let backend = self.get_backend(&GroupId::from(group_id), Some(&MiddlewareId::from(middleware_id)), &BackendId::from(backend_id))?;
+                if backend.status != ContainerStatus::Running {
+                    self.start_backend(&GroupId::from(group_id), Some(&MiddlewareId::from(middleware_id)), &BackendId::from(backend_id))
+                        .with_context(|| format!("{} 启动失败", backend.name))?;
+                }
+                started.push(self.get_backend(&GroupId::from(group_id), Some(&MiddlewareId::from(middleware_id)), &BackendId::from(backend_id))?);
+            }
+            progress(started.len());
+            let group = self.state
+                .read(|s| s.group(&GroupId::from(group_id)).ok().cloned())
+                .ok_or_else(|| anyhow::anyhow!("业务组不存在: {}", group_id))?;
+            self.wait_ready(&group, &started.iter().collect::<Vec<_>>(), cancel)?;
+            self.register_instances(&GroupId::from(group_id), Some(&MiddlewareId::from(middleware_id)))?;
+            Ok(started.len())
+        })();
+        if result.is_err() {
+            for backend_id in backend_ids {
+                let _ = self.stop_backend(&GroupId::from(group_id), Some(&MiddlewareId::from(middleware_id)), &BackendId::from(backend_id));
+                let _ = self.delete_backend(&GroupId::from(group_id), Some(&MiddlewareId::from(middleware_id)), &BackendId::from(backend_id));
+            }
+            // 实例列表可能已包含新实例，按删除后的后端重新生成
+            let _ = self.register_instances(&GroupId::from(group_id), Some(&MiddlewareId::from(middleware_id)));
+        }
+        result.context("扩容失败，新建的读实例已删除")
+    }
+
+    /// 缩容读实例：先将实例移出中间层的实例列表，等待进行中的请求完成后逐个停止并删除；
+    /// 写实例与混合实例不能缩容，已删除的实例跳过。删除前取消或失败时恢复实例列表。每处理一个实例回调一次已删除数
+    pub fn scale_in_reads(
+        &self,
+        group_id: &str,
+        middleware_id: &str,
+        backend_ids: &[String],
+        cancel: &AtomicBool,
+        mut progress: impl FnMut(usize),
+    ) -> Result<usize> {
+        let backends: Vec<BackendContainer> = backend_ids
+            .iter()
+            .filter_map(|id| self.get_backend(&GroupId::from(group_id), Some(&MiddlewareId::from(middleware_id)), &BackendId::from(id)).ok())
+            .collect();
+        if let Some(backend) = backends.iter().find(|b| b.instance_type != "read") {
+            anyhow::bail!("{} 不是读实例，不能缩容", backend.name);
+        }
+        let backend_ids: Vec<String> = backends.iter().map(|b| b.id.clone()).collect();
+        let drained = (|| {
+            self.register_instances_excluding(&GroupId::from(group_id), Some(&MiddlewareId::from(middleware_id)), &backend_ids)?;
+            let started = Instant::now();
+            while started.elapsed() < SCALE_IN_DRAIN {
+                if cancel.load(Ordering::Relaxed) {
+                    return Err(jobs::cancelled());
+                }
+                std::thread::sleep(Duration::from_millis(100));
+            }
+            Ok(())
+        })();
+        if let Err(e) = drained {
+            let _ = self.register_instances(&GroupId::from(group_id), Some(&MiddlewareId::from(middleware_id)));
+            return Err(e).context("缩容未完成，读实例已恢复到实例列表");
+        }
+
+        let mut removed = 0;
+        for backend in &backends {
+            progress(removed);
+            self.stop_backend(&GroupId::from(group_id), Some(&MiddlewareId::from(middleware_id)), &BackendId::from(&backend.id))
+                .with_context(|| format!("{} 已移出实例列表，但停止失败", backend.name))?;
+            self.delete_backend(&GroupId::from(group_id), Some(&MiddlewareId::from(middleware_id)), &BackendId::from(&backend.id))?;
+            removed += 1;
+        }
+        progress(removed);
+        Ok(removed)
+    }
+
+    /// 按下属后端及其副本重新生成中间层的调度实例列表，中间层运行中时推送到服务
+    fn register_instances(&self, group_id: &GroupId, middleware_id: Option<&MiddlewareId>) -> Result<()> {
+        self.register_instances_excluding(group_id, middleware_id, &[])
+    }
+
+    /// 同 [`Self::register_instances`]，`excluded` 中的后端不加入实例列表
+    fn register_instances_excluding(&self, group_id: &GroupId, middleware_id: Option<&MiddlewareId>, excluded: &[String]) -> Result<()> {
+        let Some((client, middleware)) = self.regenerate_instances(group_id, middleware_id, excluded)? else {
+            return Ok(());
+        };
+        tasks::block_on(client.update_config(&middleware.config)).context("副本已调整，但推送实例列表到中间层失败")
+    }
+
+    /// 同 [`Self::register_instances`]，但不等待推送完成，推送结果由界面从后台任务中收取；
+    /// 用于界面线程上的操作，避免中间层响应缓慢时界面卡住
+    fn publish_instances(&self, group_id: &GroupId, middleware_id: Option<&MiddlewareId>) -> Result<()> {
+        let Some((client, middleware)) = self.regenerate_instances(group_id, middleware_id, &[])? else {
+            return Ok(());
+        };
+        tasks::submit(&format!("推送 {} 的实例列表", middleware.name), async move {
+            client.update_config(&middleware.config).await.context("实例列表已更新，但推送到中间层失败")
+        });
+        Ok(())
+    }
+
+    /// 重新生成中间层的实例列表并保存，中间层运行中时返回推送所用的客户端与更新后的中间层
+    fn regenerate_instances(&self, group_id: &GroupId, middleware_id: Option<&MiddlewareId>, excluded: &[String]) -> Result<Option<(ApiClient, MiddlewareContainer)>> {
+        let Some(middleware_id) = middleware_id else {
+            return Ok(None);
+        };
+        let middleware = self.state.update(|state| {
+            let middleware = state.middleware_mut(group_id, middleware_id)?;
+            let mut registered = middleware.clone();
+            registered.backend_containers.retain(|b| !excluded.contains(&b.id));
+            middleware.config.crud_api.instances = registered.crud_instances();
+            Ok(middleware.clone())
+        })?;
+        if middleware.status != ContainerStatus::Running {
+            return Ok(None);
+        }
+        let client = ApiClient::new(ApiClientConfig {
+            base_url: middleware.api_base_url(),
+            timeout: middleware.config.crud_api.timeout,
+            network: self.state.read(|s| s.network_for(middleware_id)),
+            trace_id: None,
+        })?;
+        Ok(Some((client, middleware)))
+    }
+    
+    /// 重启后端容器
+    pub fn restart_backend(&self, group_id: &GroupId, middleware_id: Option<&MiddlewareId>, backend_id: &BackendId) -> Result<()> {
+        self.state.authorize(group_id)?;
+        self.stop_backend(group_id, middleware_id, backend_id)?;
+        self.start_backend(group_id, middleware_id, backend_id)
+    }
+
+    /// 等待一批后端通过健康探测，未启用探测的后端视为就绪
+    pub fn wait_ready(&self, group: &BusinessGroup, backends: &[&BackendContainer], cancel: &AtomicBool) -> Result<()> {
+        let started = Instant::now();
+        let mut pending: Vec<&BackendContainer> = backends.iter().copied().filter(|b| b.health_probe.enabled).collect();
+        loop {
+            pending.retain(|backend| {
+                let target = self.state.read(|s| ProbeTarget::backend(s, group, backend));
+                probe::run(&backend.health_probe, &target).is_err()
+            });
+            if pending.is_empty() {
+                return Ok(());
+            }
+            if started.elapsed() >= BACKEND_READY_TIMEOUT {
+                let names: Vec<&str> = pending.iter().map(|b| b.name.as_str()).collect();
+                anyhow::bail!("{} 在 {} 秒内未通过健康探测", names.join(", "), BACKEND_READY_TIMEOUT.as_secs());
+            }
+            for _ in 0..10 {
+                if cancel.load(Ordering::Relaxed) {
+                    return Err(jobs::cancelled());
+                }
+                std::thread::sleep(Duration::from_millis(100));
+            }
+        }
+    }
+}
+
+/// 告警服务，告警与抑制设置保存在状态的记录中，随状态批量写入
+pub struct AlertService {
+    state: StateStore,
+    /// 读写异常检测规则
+    config_manager: ConfigManager,
+    /// 模型变更事件订阅
+    events: Receiver<ModelEvent>,
+    /// 健康检查结果的迟滞与抖动跟踪
+    tracker: HealthTracker,
+}
+
+impl AlertService {
+    /// 创建新的告警服务
+    pub fn new(config_manager: ConfigManager, state: StateStore, bus: &EventBus) -> Self {
+        Self {
+            state,
+            config_manager,
+            events: bus.subscribe(),
+            tracker: HealthTracker::new(),
+        }
+    }
+    
+    /// 根据模型变更事件触发告警，返回新增告警数量
+    ///
+    /// 中间层的健康告警由后台轮询结果经 `observe_health` 触发，这里只处理后端的健康变化
+    pub fn process_events(&mut self) -> Result<usize> {
+        let events: Vec<ModelEvent> = self.events.try_iter().collect();
+        if events.is_empty() {
+            return Ok(0);
+        }
+        
+        let policy = self.state.read_records(|r| r.alert_policy.clone());
+        let now = Utc::now();
+        let mut alerts = Vec::new();
+        for event in events {
+            match event {
+                ModelEvent::StatusChanged { kind, name, to, .. } if to == ContainerStatus::Error.label() => {
+                    alerts.push(Alert::new(&name, &format!("{}进入错误状态", kind.label()), AlertSeverity::Critical));
+                }
+                ModelEvent::HealthChanged { kind: kind @ EntityKind::Backend, id, name, to, .. }
+                    if matches!(to, HealthStatus::Healthy | HealthStatus::Unhealthy) =>
+                {
+                    let observation = HealthObservation { kind, id, name, healthy: to == HealthStatus::Healthy };
+                    alerts.extend(self.observe(&observation, &policy, now));
+                }
+                _ => {}
+            }
+        }
+        self.save_alerts(alerts)
+    }
+    
+    /// 记录后台健康检查结果，连续失败或恢复达到阈值时告警，返回新增告警数量
+    pub fn observe_health(&mut self, observations: &[HealthObservation]) -> Result<usize> {
+        if observations.is_empty() {
+            return Ok(0);
+        }
+        let policy = self.state.read_records(|r| r.alert_policy.clone());
+        let now = Utc::now();
+        let alerts: Vec<Alert> = observations
+            .iter()
+            .filter_map(|observation| self.observe(observation, &policy, now))
+            .collect();
+        self.save_alerts(alerts)
+    }
+    
+    fn observe(&mut self, observation: &HealthObservation, policy: &AlertPolicy, now: DateTime<Utc>) -> Option<Alert> {
+        let kind = observation.kind.label();
+        let (message, severity) = match self.tracker.observe(observation, policy, now)? {
+            HealthSignal::Down => (format!("{}健康检查连续失败", kind), AlertSeverity::Warning),
+            HealthSignal::Recovered => (format!("{}健康检查已恢复", kind), AlertSeverity::Info),
+            HealthSignal::Flapping => (format!("{}健康状态反复变化，暂停上下线告警", kind), AlertSeverity::Warning),
+            HealthSignal::Stable => (format!("{}健康状态已稳定", kind), AlertSeverity::Info),
+        };
+        Some(Alert::new(&observation.name, &message, severity))
+    }
+    
+    /// 合并重复告警后记入状态，返回新增告警数量
+    fn save_alerts(&self, alerts: Vec<Alert>) -> Result<usize> {
+        if alerts.is_empty() {
+            return Ok(0);
+        }
+        self.state.update_records(move |records| Ok(alerts.iter().filter(|alert| Self::push_alert(records, (*alert).clone())).count()))
+    }
+    
+    /// 去重窗口内存在未确认的相同告警时只累加次数，否则新增，返回是否新增
+    fn push_alert(records: &mut Records, alert: Alert) -> bool {
+        alerting::push_deduplicated(&mut records.alerts, alert, &records.alert_policy)
+    }
+    
+    /// 保存告警抑制设置
+    pub fn set_policy(&self, policy: AlertPolicy) -> Result<()> {
+        self.state.update_records(move |records| {
+            records.alert_policy = policy.clone();
+            Ok(())
+        })
+    }
+    
+    /// 将告警抑制设置与异常检测规则导出为监控策略文件
+    pub fn export_monitoring_policy(&self, path: &str) -> Result<MonitoringPolicy> {
+        let config = self.config_manager.load_config()?;
+        let policy = MonitoringPolicy {
+            version: MONITORING_POLICY_VERSION,
+            alert_policy: self.state.read_records(|r| r.alert_policy.clone()),
+            anomaly_rules: config.anomaly_rules,
+            anomaly_spike_threshold: config.anomaly_spike_threshold,
+        };
+        let content = serde_json::to_string_pretty(&policy).context("无法序列化监控策略")?;
+        std::fs::write(path, content).with_context(|| format!("无法写入监控策略文件: {}", path))?;
+        Ok(policy)
+    }
+    
+    /// 从监控策略文件导入告警抑制设置与异常检测规则，返回导入后生效的策略
+    pub fn import_monitoring_policy(&self, path: &str, mode: PolicyImportMode) -> Result<MonitoringPolicy> {
+        let content = std::fs::read_to_string(path).with_context(|| format!("无法读取监控策略文件: {}", path))?;
+        let imported: MonitoringPolicy = serde_json::from_str(&content).with_context(|| format!("无法解析监控策略文件: {}", path))?;
+        if imported.version > MONITORING_POLICY_VERSION {
+            anyhow::bail!("监控策略文件版本 {} 高于当前支持的版本 {}", imported.version, MONITORING_POLICY_VERSION);
+        }
+        
+        let policy = self.config_manager.update(|config| {
+            config.anomaly_spike_threshold = imported.anomaly_spike_threshold;
+            match mode {
+                PolicyImportMode::Replace => config.anomaly_rules = imported.anomaly_rules,
+                PolicyImportMode::Merge => {
+                    for rule in imported.anomaly_rules {
+                        match config.anomaly_rules.iter_mut().find(|r| r.id == rule.id) {
+                            Some(existing) => *existing = rule,
+                            None => config.anomaly_rules.push(rule),
+                        }
+                    }
+                }
+            }
+            Ok(MonitoringPolicy {
+                version: MONITORING_POLICY_VERSION,
+                alert_policy: imported.alert_policy,
+                anomaly_rules: config.anomaly_rules.clone(),
+                anomaly_spike_threshold: config.anomaly_spike_threshold,
+            })
+        })?;
+        self.set_policy(policy.alert_policy.clone())?;
+        Ok(policy)
+    }
+    
+    /// 获取所有告警
+    pub fn get_alerts(&self) -> Result<Vec<Alert>> {
+        Ok(self.state.read_records(|r| r.alerts.clone()))
+    }
+    
+    /// 当前的告警抑制设置
+    pub fn policy(&self) -> AlertPolicy {
+        self.state.read_records(|r| r.alert_policy.clone())
+    }
+    
+    /// 触发告警，与未确认的相同告警合并
+    pub fn raise_alert(&self, alert: Alert) -> Result<()> {
+        self.state.update_records(move |records| {
+            Self::push_alert(records, alert.clone());
+            Ok(())
+        })
+    }
+    
+    /// 确认告警
+    pub fn acknowledge_alert(&self, alert_id: &str) -> Result<()> {
+        let alert_id = alert_id.to_string();
+        self.state.update_records(move |records| {
+            let alert = records.alerts.iter_mut().find(|a| a.id == alert_id).ok_or_else(|| anyhow::anyhow!("告警不存在: {}", alert_id))?;
+            alert.acknowledged = true;
+            Ok(())
+        })
+    }
+    
+    /// 附加日志证据到告警
+    pub fn attach_evidence(&self, alert_id: &str, evidence: LogEvidence) -> Result<()> {
+        let alert_id = alert_id.to_string();
+        self.state.update_records(move |records| {
+            let alert = records.alerts.iter_mut().find(|a| a.id == alert_id).ok_or_else(|| anyhow::anyhow!("告警不存在: {}", alert_id))?;
+            alert.evidence.push(evidence.clone());
+            Ok(())
+        })
+    }
+    
+    /// 清除已确认的告警
+    pub fn clear_acknowledged(&self) -> Result<()> {
+        self.state.update_records(|records| {
+            records.alerts.retain(|a| !a.acknowledged);
+            Ok(())
+        })
+    }
+}
+
+/// 审计服务，记录操作人对模型的变更；审计日志保存在状态的记录中，随状态批量写入
+pub struct AuditService {
+    state: StateStore,
+    /// 模型变更事件订阅
+    events: Receiver<ModelEvent>,
+    /// 当前操作人
+    actor: String,
+}
+
+impl AuditService {
+    /// 创建新的审计服务
+    pub fn new(state: StateStore, bus: &EventBus) -> Self {
+        let actor = std::env::var("USER")
+            .or_else(|_| std::env::var("USERNAME"))
+            .unwrap_or_else(|_| "unknown".to_string());
+        Self {
+            state,
+            events: bus.subscribe(),
+            actor,
+        }
+    }
+    
+    /// 当前操作人
+    pub fn actor(&self) -> &str {
+        &self.actor
+    }
+    
+    /// 切换操作人，本地用户登录后以用户名记录审计日志
+    pub fn set_actor(&mut self, actor: &str) {
+        self.actor = actor.to_string();
+    }
+    
+    /// 直接写入一条审计日志
+    pub fn record(&self, action: &str, entity_kind: Option<EntityKind>, entity_id: Option<&str>) -> Result<()> {
+        self.record_as(&self.actor, action, entity_kind, entity_id)
+    }
+    
+    /// 写入一条带追踪ID的审计日志，用于界面发起且会调用服务接口的操作
+    pub fn record_traced(&self, action: &str, entity_kind: Option<EntityKind>, entity_id: Option<&str>, trace_id: &str) -> Result<()> {
+        let mut entry = AuditEntry::new(&self.actor, action, entity_kind, entity_id);
+        entry.trace_id = Some(trace_id.to_string());
+        self.append(vec![entry])
+    }
+    
+    /// 以指定操作人写入审计日志，用于外部系统触发的操作
+    pub fn record_as(&self, actor: &str, action: &str, entity_kind: Option<EntityKind>, entity_id: Option<&str>) -> Result<()> {
+        self.append(vec![AuditEntry::new(actor, action, entity_kind, entity_id)])
+    }
+    
+    /// 将条目依次接入哈希链，由状态写入时持久化；写入时重新接在磁盘上的链尾
+    fn append(&self, entries: Vec<AuditEntry>) -> Result<()> {
+        self.state.update_records(move |records| {
+            for entry in &entries {
+                audit_export::append(&mut records.audit_log, &mut records.audit_chain, entry.clone())?;
+            }
+            Ok(())
+        })
+    }
+    
+    /// 校验审计日志哈希链，发现历史记录被修改或删除时返回错误
+    pub fn verify_integrity(&self) -> Result<AuditChainStatus> {
+        self.state.read_records(|r| audit_export::verify_log(&r.audit_log, r.audit_chain.as_ref()))
+    }
+    
+    /// 获取审计日志
+    pub fn get_entries(&self) -> Result<Vec<AuditEntry>> {
+        Ok(self.state.read_records(|r| r.audit_log.clone()))
+    }
+    
+    /// 将符合筛选条件的审计日志导出到文件，返回导出条数
+    pub fn export(&self, path: &str, format: AuditExportFormat, filter: &AuditFilter, integrity: AuditIntegrity, key: &str) -> Result<usize> {
+        let entries: Vec<AuditEntry> = self.get_entries()?
+            .into_iter()
+            .filter(|e| filter.matches(e))
+            .collect();
+        let count = entries.len();
+        let content = audit_export::render(entries, format, integrity, key, &self.actor)?;
+        std::fs::write(path, content).context(format!("无法写入导出文件: {}", path))?;
+        Ok(count)
+    }
+    
+    /// 校验审计日志JSON导出文件的哈希链，返回校验通过的条数
+    pub fn verify_export(&self, path: &str, key: &str) -> Result<usize> {
+        let content = std::fs::read_to_string(path).context(format!("无法读取导出文件: {}", path))?;
+        audit_export::verify(&content, key)
+    }
+    
+    /// 将收到的模型变更事件写入审计日志，返回新增条数
+    ///
+    /// 运行状态与健康状态的变化多来自监控轮询，由状态历史记录，不写入审计日志；
+    /// 操作人发起的启停由发起处单独记录，见 [`ContainerAction::audited_entity`]
+    pub fn process_events(&self) -> Result<usize> {
+        let entries: Vec<AuditEntry> = self.events
+            .try_iter()
+            .filter(|event| !matches!(event, ModelEvent::StatusChanged { .. } | ModelEvent::HealthChanged { .. }))
+            .map(|event| {
+                let entity = event.entity();
+                AuditEntry::new(&self.actor, &event.describe(), entity.map(|(kind, _)| kind), entity.map(|(_, id)| id))
+            })
+            .collect();
+        if entries.is_empty() {
+            return Ok(0);
+        }
+        
+        let count = entries.len();
+        self.append(entries)?;
+        Ok(count)
+    }
+}
+
+/// 每个容器保留的状态变化记录条数
+const MAX_STATUS_HISTORY: usize = 100;
+
+/// 状态历史服务，将容器的运行状态与健康状态变化记入状态，随状态批量写入
+pub struct StatusHistoryService {
+    state: StateStore,
+    /// 模型变更事件订阅
+    events: Receiver<ModelEvent>,
+}
+
+impl StatusHistoryService {
+    /// 创建新的状态历史服务
+    pub fn new(state: StateStore, bus: &EventBus) -> Self {
+        Self {
+            state,
+            events: bus.subscribe(),
+        }
+    }
+    
+    /// 获取所有容器的状态变化记录，按时间先后排列
+    pub fn get_history(&self) -> Result<Vec<StatusTransition>> {
+        Ok(self.state.read_records(|r| r.status_history.clone()))
+    }
+    
+    /// 记录收到的容器状态变化，删除容器时一并删除其记录；返回是否有变化
+    pub fn process_events(&self) -> Result<bool> {
+        let mut transitions = Vec::new();
+        let mut deleted = HashSet::new();
+        for event in self.events.try_iter() {
+            let transition = |id: &str, field, from: &str, to: &str| StatusTransition {
+                timestamp: Utc::now(),
+                entity_id: id.to_string(),
+                field,
+                from: from.to_string(),
+                to: to.to_string(),
+            };
+            match event {
+                ModelEvent::StatusChanged { kind: EntityKind::Middleware | EntityKind::Backend, id, from, to, .. } => {
+                    transitions.push(transition(&id, TransitionField::Status, &from, &to));
+                }
+                ModelEvent::HealthChanged { kind: EntityKind::Middleware | EntityKind::Backend, id, from, to, .. } => {
+                    transitions.push(transition(&id, TransitionField::Health, from.label(), to.label()));
+                }
+                ModelEvent::Deleted { kind: EntityKind::Middleware | EntityKind::Backend, id, .. } => {
+                    deleted.insert(id);
+                }
+                _ => {}
+            }
+        }
+        if transitions.is_empty() && deleted.is_empty() {
+            return Ok(false);
+        }
+        
+        self.state.update_records(move |records| {
+            records.status_history.retain(|t| !deleted.contains(&t.entity_id));
+            records.status_history.extend(transitions.iter().cloned());
+            // 每个容器只保留最近的记录
+            let mut counts: HashMap<String, usize> = HashMap::new();
+            let mut keep: Vec<bool> = records.status_history
+                .iter()
+                .rev()
+                .map(|t| {
+                    let count = counts.entry(t.entity_id.clone()).or_insert(0);
+                    *count += 1;
+                    *count <= MAX_STATUS_HISTORY
+                })
+                .collect();
+            keep.reverse();
+            let mut keep = keep.into_iter();
+            records.status_history.retain(|_| keep.next().unwrap_or(true));
+            Ok(true)
+        })
+    }
+}
+
+/// Webhook服务，管理外部触发操作的Webhook及监听设置
+pub struct WebhookService {
+    config_manager: ConfigManager,
+    state: StateStore,
+}
+
+impl WebhookService {
+    /// 创建新的Webhook服务
+    pub fn new(config_manager: ConfigManager, state: StateStore) -> Self {
+        Self {
+            config_manager,
+            state,
+        }
+    }
+    
+    /// Webhook目标所在的业务组，目标不存在时为空
+    pub fn target_group(&self, action: &WebhookAction) -> Option<GroupId> {
+        let target = match action {
+            WebhookAction::RestartGroup { group_id } => group_id,
+            WebhookAction::PushConfig { middleware_id } => middleware_id,
+        };
+        self.state.read(|s| s.group_of(target).map(|g| GroupId::from(&g.id)))
+    }
+    
+    /// 当前操作人须能管理Webhook目标所在的业务组
+    fn authorize(&self, action: &WebhookAction) -> Result<()> {
+        let group_id = self.target_group(action).ok_or_else(|| anyhow::anyhow!("{}的目标不存在", action.label()))?;
+        self.state.authorize(&group_id)
+    }
+    
+    /// 获取所有Webhook
+    pub fn get_webhooks(&self) -> Result<Vec<Webhook>> {
+        Ok(self.config_manager.load_config()?.webhooks)
+    }
+    
+    /// 以当前操作人为所有者添加Webhook
+    pub fn add_webhook(&self, mut webhook: Webhook) -> Result<()> {
+        self.authorize(&webhook.action)?;
+        webhook.owner = self.state.access().actor;
+        self.config_manager.update(|config| {
+            config.webhooks.push(webhook);
+            Ok(())
+        })
+    }
+    
+    /// 删除Webhook，目标仍存在时须能管理其业务组
+    pub fn delete_webhook(&self, webhook_id: &str) -> Result<()> {
+        if let Some(group_id) = self.find(webhook_id)?.and_then(|w| self.target_group(&w.action)) {
+            self.state.authorize(&group_id)?;
+        }
+        self.config_manager.update(|config| {
+            config.webhooks.retain(|w| w.id != webhook_id);
+            Ok(())
+        })
+    }
+    
+    /// 启用或停用Webhook，启用时须能管理目标所在的业务组
+    pub fn set_webhook_enabled(&self, webhook_id: &str, enabled: bool) -> Result<()> {
+        if enabled {
+            let webhook = self.find(webhook_id)?.ok_or_else(|| anyhow::anyhow!("Webhook不存在: {}", webhook_id))?;
+            self.authorize(&webhook.action)?;
+        }
+        self.config_manager.update(|config| {
+            let webhook = config.webhooks
+                .iter_mut()
+                .find(|w| w.id == webhook_id)
+                .ok_or_else(|| anyhow::anyhow!("Webhook不存在: {}", webhook_id))?;
+            webhook.enabled = enabled;
+            Ok(())
+        })
+    }
+    
+    fn find(&self, webhook_id: &str) -> Result<Option<Webhook>> {
+        Ok(self.get_webhooks()?.into_iter().find(|w| w.id == webhook_id))
+    }
+    
+    /// 保存监听设置
+    pub fn set_listener(&self, enabled: bool, listen: &str) -> Result<()> {
+        self.config_manager.update(|config| {
+            config.webhook_enabled = enabled;
+            config.webhook_listen = listen.to_string();
+            Ok(())
+        })
+    }
+}
+
+/// 远程命令执行结果
+pub struct CommandRun {
+    pub middleware_id: String,
+    pub middleware_name: String,
+    pub command: AllowedCommand,
+    pub result: Result<AgentExecResponse>,
+}
+
+/// 远程命令服务，只允许授权操作人通过Agent执行白名单中的命令
+pub struct CommandService {
+    config_manager: ConfigManager,
+    state: StateStore,
+    /// 执行中的命令
+    tasks: TaskPool<CommandRun>,
+}
+
+impl CommandService {
+    /// 创建新的远程命令服务
+    pub fn new(config_manager: ConfigManager, state: StateStore) -> Self {
+        Self {
+            config_manager,
+            state,
+            tasks: TaskPool::new(),
+        }
+    }
+    
+    /// 获取命令白名单
+    pub fn get_allowlist(&self) -> Result<Vec<AllowedCommand>> {
+        Ok(self.config_manager.load_config()?.command_allowlist)
+    }
+    
+    /// 只有可使用完整视图的操作人可以修改白名单与授权操作人
+    fn authorize_admin(config: &Config, actor: &str) -> Result<()> {
+        authorize_role_manager(config, actor, "修改远程命令白名单与授权操作人")
+    }
+    
+    /// 添加白名单命令
+    pub fn add_command(&self, command: AllowedCommand, actor: &str) -> Result<()> {
+        self.config_manager.update(|config| {
+            Self::authorize_admin(config, actor)?;
+            config.command_allowlist.push(command);
+            Ok(())
+        })
+    }
+    
+    /// 删除白名单命令，返回被删除的命令
+    pub fn delete_command(&self, command_id: &str, actor: &str) -> Result<AllowedCommand> {
+        self.config_manager.update(|config| {
+            Self::authorize_admin(config, actor)?;
+            let index = config.command_allowlist
+                .iter()
+                .position(|c| c.id == command_id)
+                .ok_or_else(|| anyhow::anyhow!("白名单命令不存在: {}", command_id))?;
+            Ok(config.command_allowlist.remove(index))
+        })
+    }
+    
+    /// 设置授权操作人
+    pub fn set_operators(&self, operators: Vec<String>, actor: &str) -> Result<()> {
+        self.config_manager.update(|config| {
+            Self::authorize_admin(config, actor)?;
+            config.command_operators = operators;
+            Ok(())
+        })
+    }
+    
+    /// 是否有命令正在执行
+    pub fn is_running(&self) -> bool {
+        self.tasks.is_busy()
+    }
+    
+    /// 校验权限后在后台通过Agent执行白名单命令，返回要执行的命令
+    pub fn run(&mut self, middleware: &MiddlewareContainer, command_id: &str, actor: &str, trace_id: &str) -> Result<AllowedCommand> {
+        // 执行前重新读取配置，避免使用已被移出白名单的命令
+        let config = self.config_manager.load_config()?;
+        let command = config.command_allowlist
+            .into_iter()
+            .find(|c| c.id == command_id)
+            .ok_or_else(|| anyhow::anyhow!("命令不在白名单中: {}", command_id))?;
+        self.spawn(middleware, command.clone(), actor, trace_id)?;
+        Ok(command)
+    }
+    
+    /// 通过Agent在中间层主机上安装systemd单元文件，与白名单命令使用相同的授权
+    pub fn install_units(&mut self, middleware: &MiddlewareContainer, units: &[UnitFile], actor: &str, trace_id: &str) -> Result<()> {
+        let names: Vec<&str> = units.iter().map(|u| u.name.as_str()).collect();
+        let script: Vec<String> = units.iter().map(systemd::install_command).collect();
+        let command = AllowedCommand::new(&format!("安装systemd单元 {}", names.join(", ")), &script.join("\n"));
+        self.spawn(middleware, command, actor, trace_id)
+    }
+    
+    /// 校验操作人与Agent后在后台执行命令
+    fn spawn(&mut self, middleware: &MiddlewareContainer, command: AllowedCommand, actor: &str, trace_id: &str) -> Result<()> {
+        if self.is_running() {
+            anyhow::bail!("已有命令正在执行");
+        }
+        self.state.authorize_entity(&middleware.id)?;
+        let config = self.config_manager.load_config()?;
+        if !config.command_operators.iter().any(|o| o == actor) {
+            anyhow::bail!("操作人 {} 无权执行远程命令", actor);
+        }
+        if !middleware.agent_installed {
+            anyhow::bail!("中间层 {} 未安装Agent", middleware.name);
+        }
+        
+        let client_config = ApiClientConfig {
+            base_url: middleware.api_base_url(),
+            timeout: middleware.config.crud_api.timeout,
+            network: self.state.read(|s| s.network_for(&middleware.id)),
+            trace_id: Some(trace_id.to_string()),
+        };
+        let middleware_id = middleware.id.clone();
+        let middleware_name = middleware.name.clone();
+        let label = format!("{} 执行 {}", middleware_name, command.name);
+        self.tasks.spawn(&label, async move {
+            let result = match ApiClient::new(client_config) {
+                Ok(client) => client.agent_exec(&command.command).await,
+                Err(e) => Err(e),
+            };
+            CommandRun { middleware_id, middleware_name, command, result }
+        });
+        Ok(())
+    }
+    
+    /// 收取命令执行结果
+    pub fn poll(&mut self) -> Option<CommandRun> {
+        self.tasks.poll().pop().map(|(_, run)| run)
+    }
+}
+
+/// 界面角色服务，按操作人决定可使用的界面配置
+pub struct RoleService {
+    config_manager: ConfigManager,
+}
+
+/// 只有可管理界面角色（使用完整视图）的操作人可以修改权限相关的配置，`action` 用于拒绝时的说明
+fn authorize_role_manager(config: &Config, actor: &str, action: &str) -> Result<()> {
+    if !models::ui_profile_for(&config.ui_roles, config.default_ui_profile, actor).can_manage_roles() {
+        anyhow::bail!("操作人 {} 无权{}", actor, action);
+    }
+    Ok(())
+}
+
+impl RoleService {
+    /// 创建新的界面角色服务
+    pub fn new(config_manager: ConfigManager) -> Self {
+        Self { config_manager }
+    }
+    
+    /// 获取角色分配与默认配置
+    pub fn get_roles(&self) -> Result<(Vec<UiRoleAssignment>, UiProfile)> {
+        let config = self.config_manager.load_config()?;
+        Ok((config.ui_roles, config.default_ui_profile))
+    }
+    
+    /// 为操作人分配界面配置与可管理的业务组，已分配的覆盖；业务组为空时不限。`by` 为执行分配的操作人
+    pub fn assign(&self, actor: &str, profile: UiProfile, groups: Vec<String>, by: &str) -> Result<()> {
+        let actor = actor.trim();
+        if actor.is_empty() {
+            anyhow::bail!("操作人不能为空");
+        }
+        self.config_manager.update(|config| {
+            authorize_role_manager(config, by, "管理界面角色")?;
+            match config.ui_roles.iter_mut().find(|a| a.actor == actor) {
+                Some(assignment) => {
+                    assignment.profile = profile;
+                    assignment.groups = groups;
+                }
+                None => config.ui_roles.push(UiRoleAssignment { actor: actor.to_string(), profile, groups }),
+            }
+            Ok(())
+        })
+    }
+    
+    /// 取消操作人的分配，之后使用默认配置
+    pub fn unassign(&self, actor: &str, by: &str) -> Result<()> {
+        self.config_manager.update(|config| {
+            authorize_role_manager(config, by, "管理界面角色")?;
+            let len = config.ui_roles.len();
+            config.ui_roles.retain(|a| a.actor != actor);
+            if config.ui_roles.len() == len {
+                anyhow::bail!("操作人未分配界面配置: {}", actor);
+            }
+            Ok(())
+        })
+    }
+    
+    /// 设置未分配操作人使用的默认配置
+    pub fn set_default(&self, profile: UiProfile, by: &str) -> Result<()> {
+        self.config_manager.update(|config| {
+            authorize_role_manager(config, by, "管理界面角色")?;
+            config.default_ui_profile = profile;
+            Ok(())
+        })
+    }
+}
+
+/// 心跳超过该时长未更新的会话视为已退出
+const SESSION_STALE_AFTER: Duration = Duration::from_secs(120);
+
+/// 会话心跳的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionCheck {
+    Active,
+    /// 被强制登出，附带执行的操作人
+    Revoked(String),
+}
+
+/// 会话服务，在配置文件旁的会话文件中登记运行中的实例，供管理员查看与强制登出
+pub struct SessionService {
+    config_manager: ConfigManager,
+    path: PathBuf,
+    session: UiSession,
+}
+
+impl SessionService {
+    /// 创建本实例的会话，首次心跳时登记
+    pub fn new(config_manager: &ConfigManager, actor: &str) -> Self {
+        let host = std::env::var("HOSTNAME")
+            .or_else(|_| std::env::var("COMPUTERNAME"))
+            .unwrap_or_else(|_| "unknown".to_string());
+        let now = Utc::now();
+        Self {
+            config_manager: config_manager.clone(),
+            path: config_manager.sessions_path(),
+            session: UiSession {
+                id: config_manager.instance_id().to_string(),
+                actor: actor.to_string(),
+                host,
+                started_at: now,
+                last_input: now,
+                heartbeat: now,
+                revoked_by: None,
+            },
+        }
+    }
+    
+    /// 本实例的会话
+    pub fn current(&self) -> &UiSession {
+        &self.session
+    }
+    
+    /// 切换会话的操作人，本地用户登录后调用
+    pub fn set_actor(&mut self, actor: &str) {
+        self.session.actor = actor.to_string();
+    }
+    
+    /// 读取会话文件，文件不存在时为空
+    fn load(&self) -> Result<Vec<UiSession>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = std::fs::read_to_string(&self.path)
+            .context(format!("无法读取会话文件: {}", self.path.display()))?;
+        serde_json::from_str(&content).context(format!("无法解析会话文件: {}", self.path.display()))
+    }
+    
+    /// 写入会话文件，去掉心跳已过期的会话
+    fn save(&self, mut sessions: Vec<UiSession>) -> Result<()> {
+        let now = Utc::now();
+        sessions.retain(|s| (now - s.heartbeat).to_std().unwrap_or_default() < SESSION_STALE_AFTER);
+        let content = serde_json::to_string_pretty(&sessions).context("无法序列化会话")?;
+        // 临时文件名带实例ID，共用会话文件的其他实例不会写入同一临时文件
+        let temp_path = self.path.with_extension(format!("{}.tmp", self.session.id));
+        std::fs::write(&temp_path, content).context(format!("无法写入会话文件: {}", temp_path.display()))?;
+        std::fs::rename(&temp_path, &self.path).context(format!("无法替换会话文件: {}", self.path.display()))
+    }
+    
+    /// 更新本实例的心跳与最近操作时间，被强制登出时返回执行的操作人
+    ///
+    /// 会话文件的读取-修改-写入与配置共用写锁，心跳、强制登出与注销不会互相覆盖
+    pub fn heartbeat(&mut self, last_input: DateTime<Utc>) -> Result<SessionCheck> {
+        let _guard = self.config_manager.lock_writes();
+        let mut sessions = self.load()?;
+        self.session.last_input = last_input;
+        self.session.heartbeat = Utc::now();
+        match sessions.iter_mut().find(|s| s.id == self.session.id) {
+            Some(stored) => {
+                if let Some(by) = &stored.revoked_by {
+                    return Ok(SessionCheck::Revoked(by.clone()));
+                }
+                *stored = self.session.clone();
+            }
+            None => sessions.push(self.session.clone()),
+        }
+        self.save(sessions)?;
+        Ok(SessionCheck::Active)
+    }
+    
+    /// 心跳未过期的会话，按登录时间排列
+    pub fn list(&self) -> Result<Vec<UiSession>> {
+        let now = Utc::now();
+        let mut sessions = self.load()?;
+        sessions.retain(|s| (now - s.heartbeat).to_std().unwrap_or_default() < SESSION_STALE_AFTER);
+        sessions.sort_by_key(|s| s.started_at);
+        Ok(sessions)
+    }
+    
+    /// 强制登出其他会话，目标实例在下次心跳时退出
+    pub fn revoke(&self, session_id: &str, by: &str) -> Result<()> {
+        if session_id == self.session.id {
+            anyhow::bail!("不能强制登出当前会话");
+        }
+        let _guard = self.config_manager.lock_writes();
+        let mut sessions = self.load()?;
+        let session = sessions
+            .iter_mut()
+            .find(|s| s.id == session_id)
+            .ok_or_else(|| anyhow::anyhow!("会话不存在: {}", session_id))?;
+        session.revoked_by = Some(by.to_string());
+        self.save(sessions)
+    }
+    
+    /// 注销本实例的会话，退出时调用
+    pub fn end(&self) -> Result<()> {
+        let _guard = self.config_manager.lock_writes();
+        let mut sessions = self.load()?;
+        sessions.retain(|s| s.id != self.session.id);
+        self.save(sessions)
+    }
+}
+
+/// 登录校验通过后的密码状态
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoginOutcome {
+    Accepted,
+    /// 须先修改密码才能进入，附带原因
+    MustChange(String),
+}
+
+/// 本地用户服务，管理用户、密码策略与登录校验
+pub struct UserService {
+    config_manager: ConfigManager,
+}
+
+impl UserService {
+    /// 创建新的本地用户服务
+    pub fn new(config_manager: ConfigManager) -> Self {
+        Self { config_manager }
+    }
+    
+    /// 获取本地用户与密码策略
+    pub fn get_users(&self) -> Result<(Vec<LocalUser>, PasswordPolicy)> {
+        let config = self.config_manager.load_config()?;
+        Ok((config.local_users, config.password_policy))
+    }
+    
+    /// 设置密码策略，已设置的密码在下次登录时按新的有效期判断
+    pub fn set_policy(&self, policy: PasswordPolicy, by: &str) -> Result<()> {
+        if policy.min_length == 0 {
+            anyhow::bail!("密码最小长度不能为0");
+        }
+        self.config_manager.update(|config| {
+            authorize_role_manager(config, by, "修改密码策略")?;
+            config.password_policy = policy;
+            Ok(())
+        })
+    }
+    
+    /// 以临时密码新建用户，首次登录时须修改
+    pub fn create(&self, name: &str, password: &str, by: &str) -> Result<()> {
+        let name = name.trim();
+        if name.is_empty() {
+            anyhow::bail!("用户名不能为空");
+        }
+        self.config_manager.update(|config| {
+            authorize_role_manager(config, by, "管理本地用户")?;
+            if config.local_users.iter().any(|u| u.name == name) {
+                anyhow::bail!("用户已存在: {}", name);
+            }
+            Self::check_policy(&config.password_policy, password)?;
+            let now = Utc::now();
+            config.local_users.push(LocalUser {
+                name: name.to_string(),
+                password_hash: password::hash(password)?,
+                password_changed_at: now,
+                must_change: true,
+                created_at: now,
+            });
+            Ok(())
+        })
+    }
+    
+    /// 以临时密码重置用户密码，下次登录时须修改
+    pub fn reset_password(&self, name: &str, password: &str, by: &str) -> Result<()> {
+        self.config_manager.update(|config| {
+            authorize_role_manager(config, by, "管理本地用户")?;
+            Self::check_policy(&config.password_policy, password)?;
+            let user = Self::user_mut(config, name)?;
+            user.password_hash = password::hash(password)?;
+            user.password_changed_at = Utc::now();
+            user.must_change = true;
+            Ok(())
+        })
+    }
+    
+    /// 删除用户，其界面角色分配保留
+    pub fn delete(&self, name: &str, by: &str) -> Result<()> {
+        self.config_manager.update(|config| {
+            authorize_role_manager(config, by, "管理本地用户")?;
+            let len = config.local_users.len();
+            config.local_users.retain(|u| u.name != name);
+            if config.local_users.len() == len {
+                anyhow::bail!("用户不存在: {}", name);
+            }
+            Ok(())
+        })
+    }
+    
+    /// 校验用户名与密码；摘要为其他Argon2变体或参数低于当前默认值时以Argon2id重新计算并保存
+    pub fn authenticate(&self, name: &str, password: &str) -> Result<LoginOutcome> {
+        let config = self.config_manager.load_config()?;
+        let policy = config.password_policy;
+        // 用户不存在与密码错误返回相同的错误，不暴露用户是否存在
+        let Some(user) = config.local_users.into_iter().find(|u| u.name == name) else {
+            password::verify_dummy(password);
+            anyhow::bail!("用户名或密码错误");
+        };
+        // 摘要损坏按密码错误处理，耗时与错误都与密码错误相同，不暴露用户是否存在
+        let verified = password::verify(&user.password_hash, password).unwrap_or_else(|e| {
+            tracing::error!("用户 {} 的密码摘要无法校验: {:#}", name, e);
+            password::verify_dummy(password);
+            false
+        });
+        if !verified {
+            anyhow::bail!("用户名或密码错误");
+        }
+        let outcome = if user.must_change {
+            LoginOutcome::MustChange("首次登录或密码已被重置，请设置新密码".to_string())
+        } else if policy.is_expired(user.password_changed_at, Utc::now()) {
+            LoginOutcome::MustChange(format!("密码已超过 {} 天有效期，请设置新密码", policy.expiry_days))
+        } else {
+            LoginOutcome::Accepted
+        };
+        if password::needs_upgrade(&user.password_hash) {
+            let upgraded = password::hash(password)?;
+            self.config_manager.update(|config| {
+                // 校验期间密码已被修改时不覆盖
+                if let Some(user) = config.local_users.iter_mut().find(|u| u.name == name && u.password_hash == user.password_hash) {
+                    user.password_hash = upgraded;
+                }
+                Ok(())
+            })?;
+            tracing::info!("用户 {} 的密码摘要已升级为Argon2id", name);
+        }
+        Ok(outcome)
+    }
+    
+    /// 修改密码：校验原密码，新密码须满足策略且与原密码不同
+    pub fn change_password(&self, name: &str, current: &str, new: &str) -> Result<()> {
+        self.config_manager.update(|config| {
+            Self::check_policy(&config.password_policy, new)?;
+            let user = Self::user_mut(config, name)?;
+            if !password::verify(&user.password_hash, current)? {
+                anyhow::bail!("原密码错误");
+            }
+            if current == new {
+                anyhow::bail!("新密码不能与原密码相同");
+            }
+            user.password_hash = password::hash(new)?;
+            user.password_changed_at = Utc::now();
+            user.must_change = false;
+            Ok(())
+        })
+    }
+    
+    fn user_mut<'a>(config: &'a mut Config, name: &str) -> Result<&'a mut LocalUser> {
+        config
+            .local_users
+            .iter_mut()
+            .find(|u| u.name == name)
+            .ok_or_else(|| anyhow::anyhow!("用户不存在: {}", name))
+    }
+    
+    fn check_policy(policy: &PasswordPolicy, password: &str) -> Result<()> {
+        let violations = policy.violations(password);
+        if !violations.is_empty() {
+            anyhow::bail!("密码不满足策略: {}", violations.join("，"));
+        }
+        Ok(())
+    }
+}
+
+/// Agent操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AgentAction {
+    /// 复制并启动Agent后确认其响应
+    Install,
+    /// 只检查Agent是否响应
+    Check,
+}
+
+impl AgentAction {
+    /// 显示名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            AgentAction::Install => "安装Agent",
+            AgentAction::Check => "检查Agent",
+        }
+    }
+}
+
+/// Agent操作的结果
+pub struct AgentRun {
+    pub middleware_id: String,
+    pub middleware_name: String,
+    pub action: AgentAction,
+    pub result: Result<String>,
+}
+
+/// Agent服务，将Agent部署到中间层容器或主机并确认其响应，结果写回中间层的Agent状态
+pub struct AgentService {
+    config_manager: ConfigManager,
+    state: StateStore,
+    sender: Sender<AgentRun>,
+    receiver: Receiver<AgentRun>,
+    /// 操作进行中的中间层
+    in_flight: HashSet<String>,
+}
+
+impl AgentService {
+    /// 创建新的Agent服务
+    pub fn new(config_manager: ConfigManager, state: StateStore) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            config_manager,
+            state,
+            sender,
+            receiver,
+            in_flight: HashSet::new(),
+        }
+    }
+
+    /// 获取Agent部署设置
+    pub fn get_settings(&self) -> Result<AgentSettings> {
+        Ok(self.config_manager.load_config()?.agent)
+    }
+
+    /// 保存Agent部署设置
+    pub fn set_settings(&self, settings: AgentSettings) -> Result<()> {
+        self.config_manager.update(|config| {
+            config.agent = settings;
+            Ok(())
+        })
+    }
+
+    /// 中间层是否有Agent操作正在进行
+    pub fn is_busy(&self, middleware_id: &MiddlewareId) -> bool {
+        self.in_flight.contains(middleware_id.as_str())
+    }
+
+    /// 是否有任何Agent操作正在进行
+    pub fn is_running(&self) -> bool {
+        !self.in_flight.is_empty()
+    }
+
+    /// 在后台执行Agent操作：安装时先按中间层的运行方式部署，再等待Agent在其端口上响应
+    pub fn run(&mut self, group_id: &GroupId, middleware_id: &MiddlewareId, action: AgentAction) -> Result<()> {
+        self.state.authorize(group_id)?;
+        if self.is_busy(middleware_id) {
+            anyhow::bail!("该中间层的Agent操作正在进行");
+        }
+        let settings = self.get_settings()?;
+        let middleware = MiddlewareService::new(self.state.clone()).get_middleware(group_id, middleware_id)?;
+        let url = agent::agent_url(&middleware, &settings)?;
+        let network = self.state.read(|s| s.network_for(middleware_id));
+        let target = match action {
+            AgentAction::Install => {
+                let host = docker_host(&self.state, &middleware.docker_host_id)?;
+                Some(AgentTarget::for_middleware(&middleware, host)?)
+            }
+            AgentAction::Check => None,
+        };
+
+        self.in_flight.insert(middleware.id.clone());
+        let sender = self.sender.clone();
+        std::thread::spawn(move || {
+            let result = match &target {
+                Some(target) => agent::deploy(target, &settings)
+                    .and_then(|_| agent::wait_ready(&url, network))
+                    .map(|_| format!("已通过{}部署，Agent在 {} 上响应", target.label(), url)),
+                None => agent::check(&url, network).map(|_| format!("Agent在 {} 上响应", url)),
+            };
+            let _ = sender.send(AgentRun {
+                middleware_id: middleware.id,
+                middleware_name: middleware.name,
+                action,
+                result,
+            });
+        });
+        Ok(())
+    }
+
+    /// 收取操作结果并写回中间层：响应时标记为已安装且健康，安装失败不改变安装标记，检查失败标记为不健康
+    pub fn poll(&mut self) -> Vec<AgentRun> {
+        let runs: Vec<AgentRun> = self.receiver.try_iter().collect();
+        for run in &runs {
+            self.in_flight.remove(&run.middleware_id);
+            let ok = run.result.is_ok();
+            let result = self.state.update(|state| {
+                if let Some(middleware) = state.find_middleware_mut(&MiddlewareId::from(&run.middleware_id)) {
+                    if ok {
+                        middleware.agent_installed = true;
+                        middleware.agent_health = Some(HealthStatus::Healthy);
+                    } else if run.action == AgentAction::Check || middleware.agent_installed {
+                        middleware.agent_health = Some(HealthStatus::Unhealthy);
+                    }
+                }
+                Ok(())
+            });
+            if let Err(e) = result {
+                tracing::warn!("写回Agent状态失败: {:#}", e);
+            }
+        }
+        runs
+    }
+}
+
+/// API服务，汇总服务内部提交的后台请求
+pub struct ApiService;
+
+impl ApiService {
+    /// 创建新的API服务
+    pub fn new() -> Self {
+        Self
+    }
+    
+    /// 服务内部提交、尚未完成的请求数
+    pub fn pending(&self) -> u64 {
+        tasks::background_pending()
+    }
+    
+    /// 收取服务内部提交的后台请求结果，返回失败请求的日志
+    pub fn poll(&self) -> Vec<LogEntry> {
+        tasks::take_background()
+            .into_iter()
+            .filter_map(|finished| {
+                let error = finished.result.err()?;
+                Some(LogEntry {
+                    timestamp: finished.finished_at,
+                    source: "API".to_string(),
+                    message: format!("{}失败（{:.1} 秒）: {}", finished.label, finished.elapsed.as_secs_f64(), error),
+                })
+            })
+            .collect()
+    }
+}
+
+/// 接口调试历史保留数量
+const MAX_PLAYGROUND_HISTORY: usize = 200;
+/// 历史记录中响应体的最大保存长度
+const MAX_HISTORY_RESPONSE_BYTES: usize = 4096;
+
+/// 按保存方式处理请求内容
+fn redact(value: &str, redaction: HistoryRedaction) -> String {
+    match redaction {
+        HistoryRedaction::Plain => value.to_string(),
+        HistoryRedaction::Hashed if value.is_empty() => String::new(),
+        HistoryRedaction::Hashed => format!("sha256:{}", hex::encode(Sha256::digest(value.as_bytes()))),
+        HistoryRedaction::Redacted => format!("<已隐藏 {} 字节>", value.len()),
+    }
+}
+
+/// 接口调试服务
+pub struct PlaygroundService {
+    config_manager: ConfigManager,
+    state: StateStore,
+    /// 正在发送的请求
+    tasks: TaskPool<Result<RawResponse>>,
+    /// 正在发送的请求（中间层ID, 中间层名称, 请求, 追踪ID），收到结果后记入历史
+    pending: Option<(String, String, SavedRequest, String)>,
+}
+
+impl PlaygroundService {
+    /// 创建新的接口调试服务
+    pub fn new(config_manager: ConfigManager, state: StateStore) -> Self {
+        Self {
+            config_manager,
+            state,
+            tasks: TaskPool::new(),
+            pending: None,
+        }
+    }
+    
+    /// 获取调试历史
+    pub fn get_history(&self) -> Result<Vec<PlaygroundHistoryEntry>> {
+        Ok(self.config_manager.load_config()?.playground_history)
+    }
+    
+    /// 清空调试历史
+    pub fn clear_history(&self) -> Result<()> {
+        self.config_manager.update(|config| {
+            config.playground_history.clear();
+            Ok(())
+        })
+    }
+    
+    /// 设置历史中请求内容的保存方式，只影响之后的记录
+    pub fn set_redaction(&self, redaction: HistoryRedaction) -> Result<()> {
+        self.config_manager.update(|config| {
+            config.playground_history_redaction = redaction;
+            Ok(())
+        })
+    }
+    
+    /// 将请求结果记入历史
+    fn record(&self, middleware_id: String, middleware_name: String, mut request: SavedRequest, trace_id: String, result: &Result<RawResponse>) -> Result<()> {
+        let redaction = self.config_manager.load_config()?.playground_history_redaction;
+        request.body = redact(&request.body, redaction);
+        for (name, value) in &mut request.headers {
+            if SavedRequest::is_sensitive_header(name) {
+                *value = redact(value, redaction);
+            }
+        }
+        
+        let (status, elapsed_ms, response, error) = match result {
+            Ok(response) => {
+                let mut body = response.body.clone();
+                if body.len() > MAX_HISTORY_RESPONSE_BYTES {
+                    let mut end = MAX_HISTORY_RESPONSE_BYTES;
+                    while !body.is_char_boundary(end) {
+                        end -= 1;
+                    }
+                    body.truncate(end);
+                    body.push('…');
+                }
+                (Some(response.status), Some(response.elapsed_ms), body, None)
+            }
+            Err(e) => (None, None, String::new(), Some(format!("{:#}", e))),
+        };
+        
+        let entry = PlaygroundHistoryEntry {
+            id: Uuid::new_v4().to_string(),
+            timestamp: Utc::now(),
+            middleware_id,
+            middleware_name,
+            request,
+            replayable: redaction == HistoryRedaction::Plain,
+            status,
+            elapsed_ms,
+            response,
+            error,
+            trace_id: Some(trace_id),
+        };
+        self.config_manager.update(|config| {
+            config.playground_history.push(entry);
+            let excess = config.playground_history.len().saturating_sub(MAX_PLAYGROUND_HISTORY);
+            config.playground_history.drain(..excess);
+            Ok(())
+        })
+    }
+    
+    /// 获取所有请求集合
+    pub fn get_collections(&self) -> Result<Vec<RequestCollection>> {
+        Ok(self.config_manager.load_config()?.request_collections)
+    }
+    
+    /// 修改请求集合并保存
+    fn update_collections(&self, f: impl FnOnce(&mut Vec<RequestCollection>) -> Result<()>) -> Result<()> {
+        self.config_manager.update(|config| {
+            f(&mut config.request_collections)
+        })
+    }
+    
+    /// 新建请求集合
+    pub fn add_collection(&self, name: &str) -> Result<()> {
+        self.update_collections(|collections| {
+            collections.push(RequestCollection::new(name));
+            Ok(())
+        })
+    }
+    
+    /// 删除请求集合
+    pub fn delete_collection(&self, collection_id: &str) -> Result<()> {
+        self.update_collections(|collections| {
+            let len = collections.len();
+            collections.retain(|c| c.id != collection_id);
+            if collections.len() == len {
+                anyhow::bail!("请求集合不存在: {}", collection_id);
+            }
+            Ok(())
+        })
+    }
+    
+    /// 保存请求到集合，ID相同的请求会被覆盖
+    pub fn save_request(&self, collection_id: &str, request: SavedRequest) -> Result<()> {
+        self.update_collections(|collections| {
+            let collection = collections
+                .iter_mut()
+                .find(|c| c.id == collection_id)
+                .context(format!("请求集合不存在: {}", collection_id))?;
+            match collection.requests.iter_mut().find(|r| r.id == request.id) {
+                Some(existing) => *existing = request,
+                None => collection.requests.push(request),
+            }
+            Ok(())
+        })
+    }
+    
+    /// 从集合中删除请求
+    pub fn delete_request(&self, collection_id: &str, request_id: &str) -> Result<()> {
+        self.update_collections(|collections| {
+            let collection = collections
+                .iter_mut()
+                .find(|c| c.id == collection_id)
+                .context(format!("请求集合不存在: {}", collection_id))?;
+            let len = collection.requests.len();
+            collection.requests.retain(|r| r.id != request_id);
+            if collection.requests.len() == len {
+                anyhow::bail!("请求不存在: {}", request_id);
+            }
+            Ok(())
+        })
+    }
+    
+    /// 是否有请求正在发送
+    pub fn is_sending(&self) -> bool {
+        self.tasks.is_busy()
+    }
+    
+    /// 在后台任务中向中间层发送请求，请求携带给定的追踪ID
+    pub fn send(&mut self, middleware: &MiddlewareContainer, request: SavedRequest, trace_id: &str) {
+        self.pending = Some((middleware.id.clone(), middleware.name.clone(), request.clone(), trace_id.to_string()));
+        let config = ApiClientConfig {
+            base_url: middleware.api_base_url(),
+            timeout: middleware.config.crud_api.timeout,
+            network: self.state.read(|s| s.network_for(&middleware.id)),
+            trace_id: Some(trace_id.to_string()),
+        };
+        let label = format!("{} {}", request.method.label(), request.path);
+        self.tasks.spawn(&label, async move {
+            ApiClient::new(config)?.send_raw(&request).await
+        });
+    }
+    
+    /// 收取发送结果并记入历史
+    pub fn poll(&mut self) -> Option<Result<RawResponse>> {
+        let result = match self.tasks.poll().pop() {
+            Some((_, result)) => result,
+            None if self.pending.is_some() && !self.tasks.is_busy() => Err(anyhow::anyhow!("请求任务异常退出")),
+            None => return None,
+        };
+        if let Some((middleware_id, middleware_name, request, trace_id)) = self.pending.take()
+            && let Err(e) = self.record(middleware_id, middleware_name, request, trace_id, &result)
+        {
+            tracing::warn!("保存调试历史失败: {:#}", e);
+        }
+        Some(result)
+    }
+}
+
+/// 大数据加解密的进度消息
+enum PayloadMessage {
+    /// 已完成的块数与总块数
+    Progress(usize, usize),
+    /// 处理结束，成功时为结果摘要
+    Finished(Result<String>),
+}
+
+/// 大数据加解密服务，从文件读取数据，超过块大小时分块调用中间层并写入结果文件
+pub struct PayloadService {
+    config_manager: ConfigManager,
+    state: StateStore,
+    receiver: Option<Receiver<PayloadMessage>>,
+    progress: (usize, usize),
+}
+
+impl PayloadService {
+    /// 创建新的大数据加解密服务
+    pub fn new(config_manager: ConfigManager, state: StateStore) -> Self {
+        Self {
+            config_manager,
+            state,
+            receiver: None,
+            progress: (0, 0),
+        }
+    }
+    
+    /// 设置分块大小
+    pub fn set_chunk_bytes(&self, chunk_bytes: usize) -> Result<()> {
+        self.config_manager.update(|config| {
+            config.payload_chunk_bytes = chunk_bytes;
+            Ok(())
+        })
+    }
+    
+    /// 是否正在处理
+    pub fn is_running(&self) -> bool {
+        self.receiver.is_some()
+    }
+    
+    /// 当前进度（已完成块数, 总块数）
+    pub fn progress(&self) -> (usize, usize) {
+        self.progress
+    }
+    
+    /// 在后台处理文件，各块请求携带给定的追踪ID
+    pub fn start(&mut self, middleware: &MiddlewareContainer, mode: PayloadMode, input: &str, output: &str, chunk_bytes: usize, trace_id: &str) {
+        if self.is_running() {
+            return;
+        }
+        let config = ApiClientConfig {
+            base_url: middleware.api_base_url(),
+            timeout: middleware.config.crud_api.timeout,
+            network: self.state.read(|s| s.network_for(&middleware.id)),
+            trace_id: Some(trace_id.to_string()),
+        };
+        let (input, output) = (input.to_string(), output.to_string());
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            let progress = |done, total| {
+                let _ = sender.send(PayloadMessage::Progress(done, total));
+            };
+            let result = (|| {
+                let data = std::fs::read_to_string(&input).context(format!("无法读取文件: {}", input))?;
+                let client = ApiClient::new(config)?;
+                let result = match mode {
+                    PayloadMode::Encrypt => tasks::block_on(chunking::encrypt(&client, &data, chunk_bytes, progress))?,
+                    PayloadMode::Decrypt => tasks::block_on(chunking::decrypt(&client, data.trim(), progress))?,
+                };
+                std::fs::write(&output, &result).context(format!("无法写入文件: {}", output))?;
+                Ok(format!("{}完成: {} 字节 → {} 字节，已写入 {}", mode.label(), data.len(), result.len(), output))
+            })();
+            let _ = sender.send(PayloadMessage::Finished(result));
+        });
+        self.progress = (0, 0);
+        self.receiver = Some(receiver);
+    }
+    
+    /// 收取进度，处理结束时返回结果
+    pub fn poll(&mut self) -> Option<Result<String>> {
+        let receiver = self.receiver.as_ref()?;
+        loop {
+            match receiver.try_recv() {
+                Ok(PayloadMessage::Progress(done, total)) => self.progress = (done, total),
+                Ok(PayloadMessage::Finished(result)) => {
+                    self.receiver = None;
+                    return Some(result);
+                }
+                Err(TryRecvError::Empty) => return None,
+                Err(TryRecvError::Disconnected) => {
+                    self.receiver = None;
+                    return Some(Err(anyhow::anyhow!("处理线程异常退出")));
+                }
+            }
+        }
+    }
+}
+
+/// 数据完整性校验服务
+pub struct VerificationService {
+    /// 正在进行的校验
+    tasks: TaskPool<VerificationReport>,
+}
+
+impl VerificationService {
+    /// 创建新的校验服务
+    pub fn new() -> Self {
+        Self {
+            tasks: TaskPool::new(),
+        }
+    }
+    
+    /// 是否正在校验
+    pub fn is_running(&self) -> bool {
+        self.tasks.is_busy()
+    }
+    
+    /// 在后台任务中校验密文能否在所选中间层上解密，中间层附带其业务组的网络配置
+    pub fn start(&mut self, middlewares: &[(MiddlewareContainer, Option<NetworkProfile>)], ciphertexts: Vec<String>, trace_id: &str) {
+        if self.is_running() {
+            return;
+        }
+        
+        let targets: Vec<VerificationTarget> = middlewares
+            .iter()
+            .map(|(m, network)| VerificationTarget {
+                name: m.name.clone(),
+                config: ApiClientConfig {
+                    base_url: m.api_base_url(),
+                    timeout: m.config.crud_api.timeout,
+                    network: network.clone(),
+                    trace_id: Some(trace_id.to_string()),
+                },
+            })
+            .collect();
+        self.tasks.spawn("数据校验", async move {
+            verification::verify(&targets, &ciphertexts).await
+        });
+    }
+    
+    /// 收取校验报告
+    pub fn poll(&mut self) -> Option<VerificationReport> {
+        self.tasks.poll().pop().map(|(_, report)| report)
+    }
+}
+
+/// 指标采集结果
+struct MetricsScrapeResult {
+    middleware_id: String,
+    middleware_name: String,
+    result: Result<String>,
+    timestamp: chrono::DateTime<Utc>,
+    latency_ms: f64,
+}
+
+/// 指标采集服务
+pub struct MetricsService {
+    /// 指标存储
+    pub store: MetricsStore,
+    /// 当前采集轮次
+    round: u64,
+    /// 后台采集
+    tasks: TaskPool<Vec<MetricsScrapeResult>>,
+}
+
+impl MetricsService {
+    /// 创建新的指标采集服务
+    pub fn new() -> Self {
+        Self {
+            store: MetricsStore::new(),
+            round: 0,
+            tasks: TaskPool::new(),
+        }
+    }
+    
+    /// 是否正在采集
+    pub fn is_scraping(&self) -> bool {
+        self.tasks.is_busy()
+    }
+    
+    /// 在后台任务中并发采集所有中间层的指标，按业务组选用的网络配置访问
+    pub fn scrape(&mut self, groups: &[BusinessGroup], profiles: &[NetworkProfile]) {
+        if self.is_scraping() {
+            return;
+        }
+        
+        let targets: Vec<(String, String, ApiClientConfig)> = groups
+            .iter()
+            .flat_map(|g| {
+                let network = g.network_profile(profiles).cloned();
+                g.middlewares.iter().map(move |m| {
+                    let config = ApiClientConfig {
+                        base_url: m.url.clone(),
+                        timeout: m.config.crud_api.timeout,
+                        network: network.clone(),
+                        trace_id: None,
+                    };
+                    (m.id.clone(), m.name.clone(), config)
+                })
+            })
+            .collect();
+        
+        let scrapes = targets.into_iter().map(|(middleware_id, middleware_name, config)| async move {
+            let started = Instant::now();
+            let result = match ApiClient::new(config) {
+                Ok(client) => client.get_metrics().await,
+                Err(e) => Err(e),
+            };
+            MetricsScrapeResult {
+                middleware_id,
+                middleware_name,
+                result,
+                timestamp: Utc::now(),
+                latency_ms: started.elapsed().as_secs_f64() * 1000.0,
+            }
+        });
+        self.tasks.spawn("采集指标", futures_util::future::join_all(scrapes));
+    }
+    
+    /// 收取后台采集结果，返回采集失败的日志
+    pub fn poll(&mut self) -> Vec<LogEntry> {
+        let Some((_, results)) = self.tasks.poll().pop() else {
+            return Vec::new();
+        };
+        
+        self.round += 1;
+        
+        let mut errors = Vec::new();
+        for scrape in results {
+            match scrape.result {
+                Ok(text) => self.store.record(&scrape.middleware_id, MetricsSample {
+                    round: self.round,
+                    timestamp: scrape.timestamp,
+                    counters: metrics::parse_metrics(&text),
+                    latency_ms: scrape.latency_ms,
+                }),
+                Err(e) => errors.push(LogEntry {
+                    timestamp: scrape.timestamp,
+                    source: scrape.middleware_name,
+                    message: format!("采集指标失败: {}", e),
+                }),
+            }
+        }
+        errors
+    }
+}
+
+/// 健康探测结果
+struct HealthPollResult {
+    kind: EntityKind,
+    id: String,
+    name: String,
+    failure_threshold: u32,
+    result: Result<Duration>,
+}
+
+/// 计算错误率时保留的最近探测次数
+const PROBE_STATS_WINDOW: usize = 20;
+/// 调优时用于计算延迟分布的最近成功探测次数
+const LATENCY_SAMPLE_WINDOW: usize = 200;
+
+/// 容器最近的探测统计，供列表中的指标徽标显示
+#[derive(Debug, Clone, Default)]
+pub struct ProbeStats {
+    /// 最近一次探测返回的时间
+    pub last_checked: Option<DateTime<Utc>>,
+    /// 最近一次成功探测的耗时
+    pub latency: Option<Duration>,
+    /// 最近的探测是否成功，最多保留 [`PROBE_STATS_WINDOW`] 次
+    recent: VecDeque<bool>,
+    /// 最近成功探测的耗时（毫秒），最多保留 [`LATENCY_SAMPLE_WINDOW`] 次
+    latencies: VecDeque<f64>,
+}
+
+impl ProbeStats {
+    /// 记录一次探测结果
+    fn record<T>(&mut self, result: &std::result::Result<Duration, T>) {
+        self.last_checked = Some(Utc::now());
+        if let Ok(elapsed) = result {
+            self.latency = Some(*elapsed);
+            if self.latencies.len() == LATENCY_SAMPLE_WINDOW {
+                self.latencies.pop_front();
+            }
+            self.latencies.push_back(elapsed.as_secs_f64() * 1000.0);
+        }
+        if self.recent.len() == PROBE_STATS_WINDOW {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(result.is_ok());
+    }
+    
+    /// 最近探测中失败的百分比
+    pub fn error_percent(&self) -> f64 {
+        if self.recent.is_empty() {
+            return 0.0;
+        }
+        let failed = self.recent.iter().filter(|ok| !**ok).count();
+        failed as f64 * 100.0 / self.recent.len() as f64
+    }
+    
+    /// 最近成功探测的耗时（毫秒），按时间先后排列
+    pub fn latency_samples(&self) -> Vec<f64> {
+        self.latencies.iter().copied().collect()
+    }
+    
+    /// 最近一次探测是否失败
+    pub fn last_failed(&self) -> bool {
+        self.recent.back().is_some_and(|ok| !ok)
+    }
+}
+
+/// 立即检查中单个容器的探测结果
+struct GroupCheckResult {
+    check_id: u64,
+    id: String,
+    result: Result<Duration>,
+}
+
+/// 立即检查中的一个容器
+#[derive(Debug, Clone)]
+pub struct GroupCheckRow {
+    pub kind: EntityKind,
+    pub id: String,
+    pub name: String,
+    /// 检查前的健康状态
+    pub previous: HealthStatus,
+    /// 探测耗时或失败原因，尚未返回时为空
+    pub result: Option<std::result::Result<Duration, String>>,
+}
+
+impl GroupCheckRow {
+    /// 检查后的健康状态，尚未返回时为检查中
+    pub fn current(&self) -> HealthStatus {
+        match &self.result {
+            Some(Ok(_)) => HealthStatus::Healthy,
+            Some(Err(_)) => HealthStatus::Unhealthy,
+            None => HealthStatus::Checking,
+        }
+    }
+}
+
+/// 对一个业务组的立即检查
+#[derive(Debug, Clone)]
+pub struct GroupCheck {
+    pub group_id: String,
+    pub group_name: String,
+    pub started_at: DateTime<Utc>,
+    pub rows: Vec<GroupCheckRow>,
+    /// 未运行而跳过的容器数量
+    pub skipped: usize,
+}
+
+impl GroupCheck {
+    /// 是否所有容器都已返回结果
+    pub fn is_finished(&self) -> bool {
+        self.rows.iter().all(|r| r.result.is_some())
+    }
+}
+
+/// 到期需要探测的容器
+struct ProbeJob {
+    kind: EntityKind,
+    id: String,
+    name: String,
+    probe: HealthProbe,
+    target: ProbeTarget,
+}
+
+/// 排队等待执行的探测
+enum QueuedProbe {
+    /// 按探测间隔发起的探测
+    Poll(ProbeJob),
+    /// 立即检查中的探测：（检查序号, 探测）
+    Check(u64, ProbeJob),
+}
+
+/// 健康探测队列的概况
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ProbeQueueStats {
+    /// 排队等待的探测数
+    pub queued: usize,
+    /// 正在执行的探测数
+    pub active: usize,
+    /// 同时进行的探测数上限
+    pub concurrency: usize,
+}
+
+/// 健康探测服务，按各中间层与后端自己的探测设置在后台检查健康状态
+///
+/// 连续失败达到阈值才置为不健康，一次成功即恢复健康
+pub struct HealthService {
+    state: StateStore,
+    /// 各容器上次发起探测的时间
+    last_polled: HashMap<String, Instant>,
+    /// 正在探测的容器
+    in_flight: HashSet<String>,
+    /// 各容器连续失败的次数
+    failures: HashMap<String, u32>,
+    /// 各容器最近的探测统计，包括立即检查
+    stats: HashMap<String, ProbeStats>,
+    sender: Sender<HealthPollResult>,
+    receiver: Receiver<HealthPollResult>,
+    /// 最近一次立即检查，重新检查时替换
+    group_check: Option<GroupCheck>,
+    /// 立即检查的序号，丢弃被替换的检查迟到的结果
+    check_id: u64,
+    check_sender: Sender<GroupCheckResult>,
+    check_receiver: Receiver<GroupCheckResult>,
+    /// 等待空闲名额的探测，立即检查排在前面
+    queue: VecDeque<QueuedProbe>,
+    /// 正在执行的探测数，由探测线程结束时减少
+    active: Arc<AtomicUsize>,
+    /// 同时进行的探测数上限
+    concurrency: usize,
+}
+
+impl HealthService {
+    /// 创建新的健康探测服务
+    pub fn new(state: StateStore, concurrency: usize) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let (check_sender, check_receiver) = mpsc::channel();
+        Self {
+            state,
+            last_polled: HashMap::new(),
+            in_flight: HashSet::new(),
+            failures: HashMap::new(),
+            stats: HashMap::new(),
+            sender,
+            receiver,
+            group_check: None,
+            check_id: 0,
+            check_sender,
+            check_receiver,
+            queue: VecDeque::new(),
+            active: Arc::default(),
+            concurrency: concurrency.max(1),
+        }
+    }
+    
+    /// 设置同时进行的探测数上限，已在执行的探测不受影响
+    pub fn set_concurrency(&mut self, concurrency: usize) {
+        self.concurrency = concurrency.max(1);
+    }
+    
+    /// 探测队列的概况
+    pub fn queue_stats(&self) -> ProbeQueueStats {
+        ProbeQueueStats {
+            queued: self.queue.len(),
+            active: self.active.load(Ordering::Relaxed),
+            concurrency: self.concurrency,
+        }
+    }
+    
+    /// 在并发上限内从队列取出探测并在后台线程中执行，被替换的立即检查的探测直接丢弃
+    fn dispatch(&mut self) {
+        while self.active.load(Ordering::Relaxed) < self.concurrency {
+            let Some(queued) = self.queue.pop_front() else {
+                break;
+            };
+            let active = self.active.clone();
+            match queued {
+                QueuedProbe::Poll(job) => {
+                    let sender = self.sender.clone();
+                    active.fetch_add(1, Ordering::Relaxed);
+                    std::thread::spawn(move || {
+                        let started = Instant::now();
+                        let result = probe::run(&job.probe, &job.target).map(|()| started.elapsed());
+                        active.fetch_sub(1, Ordering::Relaxed);
+                        let _ = sender.send(HealthPollResult {
+                            kind: job.kind,
+                            id: job.id,
+                            name: job.name,
+                            failure_threshold: job.probe.failure_threshold,
+                            result,
+                        });
+                        repaint::request(RepaintSource::Health);
+                    });
+                }
+                QueuedProbe::Check(check_id, job) => {
+                    if check_id != self.check_id {
+                        continue;
+                    }
+                    let sender = self.check_sender.clone();
+                    active.fetch_add(1, Ordering::Relaxed);
+                    std::thread::spawn(move || {
+                        let started = Instant::now();
+                        let result = probe::run(&job.probe, &job.target).map(|()| started.elapsed());
+                        active.fetch_sub(1, Ordering::Relaxed);
+                        let _ = sender.send(GroupCheckResult { check_id, id: job.id, result });
+                        repaint::request(RepaintSource::Health);
+                    });
+                }
+            }
+        }
+    }
+    
+    /// 最近一次立即检查
+    pub fn group_check(&self) -> Option<&GroupCheck> {
+        self.group_check.as_ref()
+    }
+    
+    /// 关闭立即检查的结果
+    pub fn dismiss_group_check(&mut self) {
+        self.group_check = None;
+    }
+    
+    /// 立即探测业务组中所有运行中的容器，不等待各自的探测间隔，也不受探测开关限制
+    ///
+    /// 探测排在队列最前，在并发上限内执行；结果直接决定健康状态，不累计连续失败次数
+    pub fn check_group_now(&mut self, group_id: &GroupId) -> Result<()> {
+        let (group_name, jobs, skipped) = self.state.read(|state| {
+            let group = state.business_groups
+                .iter()
+                .find(|g| g.id == group_id)
+                .ok_or_else(|| anyhow::anyhow!("业务组不存在: {}", group_id))?;
+            let mut jobs = Vec::new();
+            let mut skipped = 0;
+            for middleware in &group.middlewares {
+                if middleware.status != ContainerStatus::Running {
+                    skipped += 1;
+                    continue;
+                }
+                jobs.push((ProbeJob {
+                    kind: EntityKind::Middleware,
+                    id: middleware.id.clone(),
+                    name: middleware.name.clone(),
+                    probe: middleware.health_probe.clone(),
+                    target: ProbeTarget::middleware(state, group, middleware),
+                }, middleware.health.clone()));
+            }
+            let backends = group.middlewares
+                .iter()
+                .flat_map(|m| m.backend_containers.iter())
+                .chain(group.backend_containers.iter());
+            for backend in backends {
+                if backend.status != ContainerStatus::Running {
+                    skipped += 1;
+                    continue;
+                }
+                jobs.push((ProbeJob {
+                    kind: EntityKind::Backend,
+                    id: backend.id.clone(),
+                    name: backend.name.clone(),
+                    probe: backend.health_probe.clone(),
+                    target: ProbeTarget::backend(state, group, backend),
+                }, backend.health.clone()));
+            }
+            Ok::<_, anyhow::Error>((group.name.clone(), jobs, skipped))
+        })?;
+        
+        self.check_id += 1;
+        let mut rows = Vec::new();
+        for (job, previous) in jobs.into_iter().rev() {
+            self.last_polled.insert(job.id.clone(), Instant::now());
+            rows.push(GroupCheckRow {
+                kind: job.kind,
+                id: job.id.clone(),
+                name: job.name.clone(),
+                previous,
+                result: None,
+            });
+            self.queue.push_front(QueuedProbe::Check(self.check_id, job));
+        }
+        rows.reverse();
+        self.group_check = Some(GroupCheck {
+            group_id: group_id.to_string(),
+            group_name,
+            started_at: Utc::now(),
+            rows,
+            skipped,
+        });
+        Ok(())
+    }
+    
+    /// 收取立即检查的结果并写入健康状态，返回本次收到的健康观察；暂停后台任务时仍需收取
+    pub fn poll_group_check(&mut self) -> Vec<HealthObservation> {
+        let middleware_service = MiddlewareService::new(self.state.clone());
+        let backend_service = BackendService::new(self.state.clone());
+        let mut observations = Vec::new();
+        while let Ok(checked) = self.check_receiver.try_recv() {
+            let Some(check) = self.group_check.as_mut().filter(|_| checked.check_id == self.check_id) else {
+                continue;
+            };
+            let Some(row) = check.rows.iter_mut().find(|r| r.id == checked.id) else {
+                continue;
+            };
+            self.stats.entry(row.id.clone()).or_default().record(&checked.result);
+            row.result = Some(checked.result.map_err(|e| format!("{:#}", e)));
+            let health = row.current();
+            self.failures.remove(&row.id);
+            observations.push(HealthObservation {
+                kind: row.kind,
+                id: row.id.clone(),
+                name: row.name.clone(),
+                healthy: health == HealthStatus::Healthy,
+            });
+            // 容器可能已被删除，忽略即可
+            let _ = match row.kind {
+                EntityKind::Backend => backend_service.set_backend_health(&BackendId::from(&row.id), health),
+                _ => middleware_service.set_middleware_health(&MiddlewareId::from(&row.id), health),
+            };
+        }
+        self.dispatch();
+        observations
+    }
+    
+    /// 是否有立即检查尚未全部返回
+    pub fn is_checking_group(&self) -> bool {
+        self.group_check.as_ref().is_some_and(|c| !c.is_finished())
+    }
+    
+    /// 容器当前连续失败的次数
+    pub fn failure_count(&self, entity_id: &str) -> u32 {
+        self.failures.get(entity_id).copied().unwrap_or(0)
+    }
+    
+    /// 容器最近的探测统计，尚未探测过时为空
+    pub fn probe_stats(&self, entity_id: &str) -> Option<&ProbeStats> {
+        self.stats.get(entity_id)
+    }
+    
+    /// 收取探测结果并为到期的容器发起新的探测，返回本次判定出的健康观察
+    pub fn tick(&mut self) -> Vec<HealthObservation> {
+        let middleware_service = MiddlewareService::new(self.state.clone());
+        let backend_service = BackendService::new(self.state.clone());
+        let mut observations = Vec::new();
+        while let Ok(poll) = self.receiver.try_recv() {
+            self.in_flight.remove(&poll.id);
+            self.stats.entry(poll.id.clone()).or_default().record(&poll.result);
+            let health = match poll.result {
+                Ok(_) => {
+                    self.failures.remove(&poll.id);
+                    HealthStatus::Healthy
+                }
+                Err(e) => {
+                    let failures = self.failures.entry(poll.id.clone()).or_insert(0);
+                    *failures += 1;
+                    tracing::debug!("{} {} 健康探测失败（连续 {} 次）: {:#}", poll.kind.label(), poll.name, failures, e);
+                    if *failures < poll.failure_threshold.max(1) {
+                        continue;
+                    }
+                    HealthStatus::Unhealthy
+                }
+            };
+            observations.push(HealthObservation {
+                kind: poll.kind,
+                id: poll.id.clone(),
+                name: poll.name,
+                healthy: health == HealthStatus::Healthy,
+            });
+            // 容器可能已被删除，忽略即可
+            let _ = match poll.kind {
+                EntityKind::Backend => backend_service.set_backend_health(&BackendId::from(&poll.id), health),
+                _ => middleware_service.set_middleware_health(&MiddlewareId::from(&poll.id), health),
+            };
+        }
+        
+        // 排队期间不再重复加入，探测间隔从加入队列时算起
+        for job in self.due_jobs() {
+            self.last_polled.insert(job.id.clone(), Instant::now());
+            self.in_flight.insert(job.id.clone());
+            self.queue.push_back(QueuedProbe::Poll(job));
+        }
+        self.dispatch();
+        observations
+    }
+    
+    /// 启用探测、运行中且已到间隔的容器
+    fn due_jobs(&self) -> Vec<ProbeJob> {
+        self.state.read(|state| {
+            let due = |id: &str, probe: &HealthProbe, status: &ContainerStatus| {
+                let interval = Duration::from_secs(probe.interval_secs.max(1));
+                probe.enabled
+                    && *status == ContainerStatus::Running
+                    && !self.in_flight.contains(id)
+                    && self.last_polled.get(id).is_none_or(|t| t.elapsed() >= interval)
+            };
+            let mut jobs = Vec::new();
+            for group in &state.business_groups {
+                for middleware in group.middlewares.iter().filter(|m| due(&m.id, &m.health_probe, &m.status)) {
+                    jobs.push(ProbeJob {
+                        kind: EntityKind::Middleware,
+                        id: middleware.id.clone(),
+                        name: middleware.name.clone(),
+                        probe: middleware.health_probe.clone(),
+                        target: ProbeTarget::middleware(state, group, middleware),
+                    });
+                }
+                let backends = group.middlewares
+                    .iter()
+                    .flat_map(|m| m.backend_containers.iter())
+                    .chain(group.backend_containers.iter())
+                    .filter(|b| due(&b.id, &b.health_probe, &b.status));
+                for backend in backends {
+                    jobs.push(ProbeJob {
+                        kind: EntityKind::Backend,
+                        id: backend.id.clone(),
+                        name: backend.name.clone(),
+                        probe: backend.health_probe.clone(),
+                        target: ProbeTarget::backend(state, group, backend),
+                    });
+                }
+            }
+            jobs
+        })
+    }
+}
+
+/// 界面发起、需要访问Docker或Kubernetes的操作
+#[derive(Debug, Clone)]
+pub enum ContainerAction {
+    StartMiddleware { group_id: String, middleware_id: String },
+    StopMiddleware { group_id: String, middleware_id: String },
+    RestartMiddleware { group_id: String, middleware_id: String },
+    StartBackend { group_id: String, middleware_id: Option<String>, backend_id: String },
+    StopBackend { group_id: String, middleware_id: Option<String>, backend_id: String },
+    RestartBackend { group_id: String, middleware_id: Option<String>, backend_id: String },
+    RestartGroup { group_id: String },
+    /// 调整中间层的副本数，增减容器
+    ScaleMiddleware { group_id: String, middleware_id: String, replicas: u32 },
+    /// 调整后端的副本数，增减容器
+    ScaleBackend { group_id: String, middleware_id: Option<String>, backend_id: String, replicas: u32 },
+    /// 添加业务组，组带有专用网络时在本机Docker上创建
+    CreateGroup { group: Box<BusinessGroup> },
+    /// 删除业务组并清理各主机上的专用网络
+    DeleteGroup { group_id: String },
+    /// 停止并删除孤儿容器
+    RemoveOrphan { orphan: Box<Orphan> },
+}
+
+impl ContainerAction {
+    /// 操作的容器或业务组ID
+    pub fn target_id(&self) -> &str {
+        match self {
+            ContainerAction::StartMiddleware { middleware_id, .. }
+            | ContainerAction::StopMiddleware { middleware_id, .. }
+            | ContainerAction::RestartMiddleware { middleware_id, .. }
+            | ContainerAction::ScaleMiddleware { middleware_id, .. } => middleware_id,
+            ContainerAction::StartBackend { backend_id, .. }
+            | ContainerAction::StopBackend { backend_id, .. }
+            | ContainerAction::RestartBackend { backend_id, .. }
+            | ContainerAction::ScaleBackend { backend_id, .. } => backend_id,
+            ContainerAction::RestartGroup { group_id } | ContainerAction::DeleteGroup { group_id } => group_id,
+            ContainerAction::CreateGroup { group } => &group.id,
+            ContainerAction::RemoveOrphan { orphan } => &orphan.container.name,
+        }
+    }
+
+    /// 显示名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            ContainerAction::StartMiddleware { .. } | ContainerAction::StartBackend { .. } => "启动",
+            ContainerAction::StopMiddleware { .. } | ContainerAction::StopBackend { .. } => "停止",
+            ContainerAction::RestartMiddleware { .. } | ContainerAction::RestartBackend { .. } | ContainerAction::RestartGroup { .. } => "重启",
+            ContainerAction::ScaleMiddleware { .. } | ContainerAction::ScaleBackend { .. } => "调整副本数",
+            ContainerAction::CreateGroup { .. } => "创建",
+            ContainerAction::DeleteGroup { .. } | ContainerAction::RemoveOrphan { .. } => "删除",
+        }
+    }
+
+    /// 启停操作的实体，完成后写入审计日志；它们只引起状态变化，审计服务不从模型事件记录。
+    /// 调整副本数与增删业务组由模型事件记录，孤儿容器清理单独记录
+    pub fn audited_entity(&self) -> Option<(EntityKind, &str)> {
+        match self {
+            ContainerAction::StartMiddleware { middleware_id, .. }
+            | ContainerAction::StopMiddleware { middleware_id, .. }
+            | ContainerAction::RestartMiddleware { middleware_id, .. } => Some((EntityKind::Middleware, middleware_id)),
+            ContainerAction::StartBackend { backend_id, .. }
+            | ContainerAction::StopBackend { backend_id, .. }
+            | ContainerAction::RestartBackend { backend_id, .. } => Some((EntityKind::Backend, backend_id)),
+            ContainerAction::RestartGroup { group_id } => Some((EntityKind::Group, group_id)),
+            ContainerAction::ScaleMiddleware { .. }
+            | ContainerAction::ScaleBackend { .. }
+            | ContainerAction::CreateGroup { .. }
+            | ContainerAction::DeleteGroup { .. }
+            | ContainerAction::RemoveOrphan { .. } => None,
+        }
+    }
+
+    fn run(&self, state: &StateStore) -> Result<()> {
+        let middlewares = MiddlewareService::new(state.clone());
+        let backends = BackendService::new(state.clone());
+        match self {
+            ContainerAction::StartMiddleware { group_id, middleware_id } => middlewares.start_middleware(&GroupId::from(group_id), &MiddlewareId::from(middleware_id)),
+            ContainerAction::StopMiddleware { group_id, middleware_id } => middlewares.stop_middleware(&GroupId::from(group_id), &MiddlewareId::from(middleware_id)),
+            ContainerAction::RestartMiddleware { group_id, middleware_id } => middlewares.restart_middleware(&GroupId::from(group_id), &MiddlewareId::from(middleware_id)),
+            ContainerAction::StartBackend { group_id, middleware_id, backend_id } => backends.start_backend(&GroupId::from(group_id), middleware_id.as_deref().map(MiddlewareId::from).as_ref(), &BackendId::from(backend_id)),
+            ContainerAction::StopBackend { group_id, middleware_id, backend_id } => backends.stop_backend(&GroupId::from(group_id), middleware_id.as_deref().map(MiddlewareId::from).as_ref(), &BackendId::from(backend_id)),
+            ContainerAction::RestartBackend { group_id, middleware_id, backend_id } => backends.restart_backend(&GroupId::from(group_id), middleware_id.as_deref().map(MiddlewareId::from).as_ref(), &BackendId::from(backend_id)),
+            ContainerAction::RestartGroup { group_id } => BusinessGroupService::new(state.clone()).restart_business_group(&GroupId::from(group_id)),
+            ContainerAction::ScaleMiddleware { group_id, middleware_id, replicas } => middlewares.scale_middleware(&GroupId::from(group_id), &MiddlewareId::from(middleware_id), *replicas),
+            ContainerAction::ScaleBackend { group_id, middleware_id, backend_id, replicas } => backends.scale_backend(&GroupId::from(group_id), middleware_id.as_deref().map(MiddlewareId::from).as_ref(), &BackendId::from(backend_id), *replicas),
+            ContainerAction::CreateGroup { group } => BusinessGroupService::new(state.clone()).add_business_group(group.as_ref().clone()),
+            ContainerAction::DeleteGroup { group_id } => BusinessGroupService::new(state.clone()).delete_business_group(&GroupId::from(group_id)),
+            ContainerAction::RemoveOrphan { orphan } => DockerService::new(state.clone()).remove_orphan(orphan),
+        }
+    }
+}
+
+/// 容器操作服务：界面提交的启停、副本数调整、业务组增删与孤儿容器清理在后台任务中访问Docker与Kubernetes，
+/// 界面每帧收取结果，不会因主机响应缓慢而卡住
+pub struct ContainerActionService {
+    state: StateStore,
+    tasks: TaskPool<(ContainerAction, Result<()>)>,
+    /// 有操作进行中的容器或业务组
+    busy: HashSet<String>,
+}
+
+impl ContainerActionService {
+    /// 创建新的容器操作服务
+    pub fn new(state: StateStore) -> Self {
+        Self {
+            state,
+            tasks: TaskPool::new(),
+            busy: HashSet::new(),
+        }
+    }
+
+    /// 在后台执行操作；同一容器或业务组已有操作进行中时忽略
+    pub fn submit(&mut self, action: ContainerAction) {
+        if !self.busy.insert(action.target_id().to_string()) {
+            return;
+        }
+        let state = self.state.clone();
+        let label = format!("{} {}", action.label(), action.target_id());
+        self.tasks.spawn_blocking(&label, move || {
+            let result = action.run(&state);
+            (action, result)
+        });
+    }
+
+    /// 容器或业务组是否有操作进行中
+    pub fn is_busy(&self, id: &str) -> bool {
+        self.busy.contains(id)
+    }
+
+    /// 收取已完成的操作及其结果
+    pub fn poll(&mut self) -> Vec<(ContainerAction, Result<()>)> {
+        let finished: Vec<(ContainerAction, Result<()>)> = self.tasks.poll().into_iter().map(|(_, r)| r).collect();
+        for (action, _) in &finished {
+            self.busy.remove(action.target_id());
+        }
+        // 异常退出的操作没有结果，不再占用进行中标记
+        if !self.tasks.is_busy() {
+            self.busy.clear();
+        }
+        finished
+    }
+}
+
+/// 预热检查结果
+struct WarmupResult {
+    group_id: String,
+    middleware_id: String,
+    middleware_name: String,
+    result: Result<Duration>,
+}
+
+/// 启动预热服务，在后台确认中间层就绪后才将其置为运行中
+pub struct WarmupService {
+    state: StateStore,
+    /// 正在预热的中间层
+    warming: HashSet<String>,
+    tasks: TaskPool<WarmupResult>,
+}
+
+impl WarmupService {
+    /// 创建新的预热服务
+    pub fn new(state: StateStore) -> Self {
+        Self {
+            state,
+            warming: HashSet::new(),
+            tasks: TaskPool::new(),
+        }
+    }
+    
+    /// 是否有中间层正在预热
+    pub fn is_warming(&self) -> bool {
+        !self.warming.is_empty()
+    }
+    
+    /// 在后台任务中对中间层执行预热检查
+    pub fn begin(&mut self, group_id: &GroupId, middleware: MiddlewareContainer) {
+        if !self.warming.insert(middleware.id.clone()) {
+            return;
+        }
+        let group_id = group_id.to_string();
+        let network = self.state.read(|s| s.network_for(&middleware.id));
+        let label = format!("预热 {}", middleware.name);
+        self.tasks.spawn(&label, async move {
+            let result = warmup::run(&middleware, network).await;
+            WarmupResult {
+                group_id,
+                middleware_id: middleware.id,
+                middleware_name: middleware.name,
+                result,
+            }
+        });
+    }
+    
+    /// 收取预热结果并更新中间层状态，返回需要记录的日志
+    pub fn poll(&mut self) -> Vec<LogEntry> {
+        let middleware_service = MiddlewareService::new(self.state.clone());
+        let mut logs = Vec::new();
+        for (_, warmup) in self.tasks.poll() {
+            self.warming.remove(&warmup.middleware_id);
+            let message = match &warmup.result {
+                Ok(elapsed) => format!("预热完成，耗时 {:.1} 秒，已置为运行中", elapsed.as_secs_f64()),
+                Err(e) => format!("预热失败: {:#}", e),
+            };
+            logs.push(LogEntry::new(&warmup.middleware_name, &message));
+            
+            let result = middleware_service.complete_start(&GroupId::from(&warmup.group_id), &MiddlewareId::from(&warmup.middleware_id), warmup.result.is_ok());
+            if let Err(e) = result {
+                logs.push(LogEntry::new(&warmup.middleware_name, &format!("更新状态失败: {:#}", e)));
+            }
+        }
+        // 异常退出的预热没有结果，不再占用预热标记
+        if !self.tasks.is_busy() {
+            self.warming.clear();
+        }
+        logs
+    }
+}
+
+/// 集群状态同步间隔
+const KUBERNETES_SYNC_INTERVAL: Duration = Duration::from_secs(15);
+/// 发现后端最后报告时间的刷新间隔
+const LAST_SEEN_REFRESH: chrono::Duration = chrono::Duration::minutes(1);
+
+/// 一个命名空间的发现结果
+type Discovery = (String, Result<Vec<Workload>>);
+
+/// Kubernetes服务，发现并导入带标签的工作负载，并让关联容器的状态跟随集群
+pub struct KubernetesService {
+    config_manager: ConfigManager,
+    state: StateStore,
+    /// 手动发现的结果接收端
+    discovery: Option<Receiver<Discovery>>,
+    /// 后台状态同步的结果接收端
+    sync: Option<Receiver<Vec<Discovery>>>,
+    last_sync: Option<Instant>,
+}
+
+impl KubernetesService {
+    /// 创建新的Kubernetes服务
+    pub fn new(config_manager: ConfigManager, state: StateStore) -> Self {
+        Self {
+            config_manager,
+            state,
+            discovery: None,
+            sync: None,
+            last_sync: None,
+        }
+    }
+    
+    /// 获取导入使用的命名空间
+    pub fn get_namespace(&self) -> Result<String> {
+        Ok(self.config_manager.load_config()?.kubernetes_namespace)
+    }
+    
+    /// 设置导入使用的命名空间
+    pub fn set_namespace(&self, namespace: &str) -> Result<()> {
+        self.config_manager.update_if(|config| {
+            if config.kubernetes_namespace == namespace {
+                return Ok(false);
+            }
+            config.kubernetes_namespace = namespace.to_string();
+            Ok(true)
+        })?;
+        Ok(())
+    }
+    
+    /// 是否正在发现
+    pub fn is_discovering(&self) -> bool {
+        self.discovery.is_some()
+    }
+    
+    /// 在后台列出命名空间中的工作负载
+    pub fn discover(&mut self, namespace: &str) {
+        if self.is_discovering() {
+            return;
+        }
+        let namespace = namespace.to_string();
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            let result = kubernetes::discover(&namespace);
+            let _ = sender.send((namespace, result));
+        });
+        self.discovery = Some(receiver);
+    }
+    
+    /// 收取发现结果，成功时同时刷新已关联容器的状态
+    pub fn poll_discovery(&mut self) -> Option<Discovery> {
+        let discovery = match self.discovery.as_ref()?.try_recv() {
+            Ok(discovery) => discovery,
+            Err(TryRecvError::Empty) => return None,
+            Err(TryRecvError::Disconnected) => {
+                self.discovery = None;
+                return Some((String::new(), Err(anyhow::anyhow!("发现线程异常退出"))));
+            }
+        };
+        self.discovery = None;
+        if let Ok(workloads) = &discovery.1
+            && let Err(e) = self.apply_cluster_status(&discovery.0, workloads)
+        {
+            tracing::warn!("同步Kubernetes状态失败: {:#}", e);
+        }
+        Some(discovery)
+    }
+    
+    /// 将尚未关联的工作负载导入为业务组，同名业务组合并，返回导入的工作负载数量
+    pub fn import(&self, namespace: &str, workloads: &[Workload]) -> Result<usize> {
+        let linked = self.linked_deployments(namespace);
+        let new: Vec<Workload> = workloads
+            .iter()
+            .filter(|w| !linked.contains(&w.deployment))
+            .cloned()
+            .collect();
+        if new.is_empty() {
+            return Ok(0);
+        }
+        
+        let groups = kubernetes::to_groups(namespace, &new);
+        self.state.update(|state| {
+            for group in groups {
+                let existing = state.business_groups.iter().position(|g| g.name == group.name);
+                match existing {
+                    Some(g) => {
+                        let existing = &mut state.business_groups[g];
+                        existing.middlewares.extend(group.middlewares);
+                        existing.backend_containers.extend(group.backend_containers);
+                    }
+                    None => state.business_groups.push(group),
+                }
+            }
+            Ok(())
+        })?;
+        Ok(new.len())
+    }
+    
+    /// 命名空间中已关联的Deployment名称
+    fn linked_deployments(&self, namespace: &str) -> HashSet<String> {
+        self.state.read(|state| {
+            state.business_groups
+                .iter()
+                .flat_map(kubernetes::group_links)
+                .filter(|l| l.namespace == namespace)
+                .map(|l| l.deployment.clone())
+                .collect()
+        })
+    }
+    
+    /// 按集群中的副本就绪情况更新关联容器的状态，集群中已不存在的置为已停止；
+    /// 仍在集群中的发现后端同时刷新最后报告时间
+    fn apply_cluster_status(&self, namespace: &str, workloads: &[Workload]) -> Result<()> {
+        let now = Utc::now();
+        let status_of = |link: &Option<KubernetesLink>| {
+            let link = link.as_ref().filter(|l| l.namespace == namespace)?;
+            Some(
+                workloads
+                    .iter()
+                    .find(|w| w.deployment == link.deployment)
+                    .map(Workload::status)
+                    .unwrap_or(ContainerStatus::Stopped),
+            )
+        };
+        let reported = |backend: &BackendContainer| {
+            backend.kubernetes
+                .as_ref()
+                .filter(|l| l.namespace == namespace)
+                .is_some_and(|l| workloads.iter().any(|w| w.deployment == l.deployment))
+        };
+        // 最后报告时间只在超过刷新间隔后写入，避免每次同步都保存配置
+        let needs_refresh = |backend: &BackendContainer| {
+            backend.last_seen.is_some_and(|t| now - t >= LAST_SEEN_REFRESH) && reported(backend)
+        };
+        let backend_changed = |b: &BackendContainer| status_of(&b.kubernetes).is_some_and(|s| s != b.status) || needs_refresh(b);
+        let group_changed = |group: &BusinessGroup| {
+            let middlewares = group.middlewares.iter().any(|m| {
+                status_of(&m.kubernetes).is_some_and(|s| s != m.status)
+                    || m.backend_containers.iter().any(backend_changed)
+            });
+            middlewares || group.backend_containers.iter().any(backend_changed)
+        };
+        let changed = self.state.read(|state| state.business_groups.iter().any(group_changed));
+        if !changed {
+            return Ok(());
+        }
+        
+        let update_backend = |backend: &mut BackendContainer| {
+            if let Some(status) = status_of(&backend.kubernetes) {
+                backend.status = status;
+            }
+            if backend.last_seen.is_some() && reported(backend) {
+                backend.last_seen = Some(now);
+            }
+        };
+        self.state.update(|state| {
+            for group in state.business_groups.matching_mut(group_changed) {
+                for middleware in &mut group.middlewares {
+                    if let Some(status) = status_of(&middleware.kubernetes) {
+                        middleware.status = status;
+                    }
+                    middleware.backend_containers.iter_mut().for_each(update_backend);
+                }
+                group.backend_containers.iter_mut().for_each(update_backend);
+            }
+            Ok(())
+        })
+    }
+    
+    /// 获取发现后端的存活期限设置
+    pub fn get_discovery_ttl(&self) -> DiscoveryTtl {
+        self.config_manager.load_config().map(|c| c.discovery_ttl).unwrap_or_default()
+    }
+    
+    /// 设置发现后端的存活期限
+    pub fn set_discovery_ttl(&self, ttl: DiscoveryTtl) -> Result<()> {
+        self.config_manager.update(|config| {
+            config.discovery_ttl = ttl;
+            Ok(())
+        })
+    }
+    
+    /// 超过存活期限未被集群报告的发现后端，即待移除列表
+    pub fn stale_backends(&self, ttl: &DiscoveryTtl) -> Vec<StaleBackend> {
+        self.state.read(|state| state.stale_backends(ttl, Utc::now()))
+    }
+    
+    /// 保留失联的发现后端：不再按存活期限跟踪，此后与手动创建的后端相同
+    pub fn keep_backend(&self, backend_id: &BackendId) -> Result<()> {
+        self.state.update(|state| {
+            let backend = state.find_backend_mut(backend_id)
+                .ok_or_else(|| anyhow::anyhow!("后端容器不存在: {}", backend_id))?;
+            backend.last_seen = None;
+            Ok(())
+        })
+    }
+    
+    /// 移除已到自动移除时间的失联后端，返回被移除的后端
+    fn remove_expired(&self) -> Result<Vec<StaleBackend>> {
+        let now = Utc::now();
+        let due: Vec<StaleBackend> = self.stale_backends(&self.get_discovery_ttl()).into_iter().filter(|s| s.is_due(now)).collect();
+        if due.is_empty() {
+            return Ok(due);
+        }
+        let ids: HashSet<&str> = due.iter().map(|s| s.backend_id.as_str()).collect();
+        self.state.update(|state| {
+            let holds_due = |group: &BusinessGroup| {
+                group.middlewares.iter().flat_map(|m| m.backend_containers.iter()).chain(group.backend_containers.iter()).any(|b| ids.contains(b.id.as_str()))
+            };
+            for group in state.business_groups.matching_mut(holds_due) {
+                for middleware in &mut group.middlewares {
+                    middleware.backend_containers.retain(|b| !ids.contains(b.id.as_str()));
+                }
+                group.backend_containers.retain(|b| !ids.contains(b.id.as_str()));
+            }
+            Ok(())
+        })?;
+        Ok(due)
+    }
+    
+    /// 收取状态同步结果，并在到期时为所有已关联的命名空间发起新的同步
+    ///
+    /// 同步完成后移除已到自动移除时间的失联后端，返回被移除的后端
+    pub fn tick(&mut self) -> Vec<StaleBackend> {
+        let mut removed = Vec::new();
+        if let Some(receiver) = &self.sync {
+            match receiver.try_recv() {
+                Ok(results) => {
+                    self.sync = None;
+                    let mut synced = true;
+                    for (namespace, result) in results {
+                        let result = result.and_then(|workloads| self.apply_cluster_status(&namespace, &workloads));
+                        if let Err(e) = result {
+                            synced = false;
+                            tracing::warn!("同步命名空间 {} 的Kubernetes状态失败: {:#}", namespace, e);
+                        }
+                    }
+                    // 集群不可达时无法区分后端是否已删除，只在全部命名空间同步成功后移除
+                    if synced {
+                        match self.remove_expired() {
+                            Ok(expired) => removed = expired,
+                            Err(e) => tracing::warn!("移除失联的发现后端失败: {:#}", e),
+                        }
+                    }
+                }
+                Err(TryRecvError::Empty) => return removed,
+                Err(TryRecvError::Disconnected) => self.sync = None,
+            }
+        }
+        
+        if self.last_sync.is_some_and(|t| t.elapsed() < KUBERNETES_SYNC_INTERVAL) {
+            return removed;
+        }
+        self.last_sync = Some(Instant::now());
+        
+        let namespaces: HashSet<String> = self.state.read(|state| {
+            state.business_groups
+                .iter()
+                .flat_map(kubernetes::group_links)
+                .map(|l| l.namespace.clone())
+                .collect()
+        });
+        if namespaces.is_empty() {
+            return removed;
+        }
+        
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            let results = namespaces.into_iter().map(|ns| {
+                let result = kubernetes::discover(&ns);
+                (ns, result)
+            }).collect();
+            let _ = sender.send(results);
+        });
+        self.sync = Some(receiver);
+        removed
+    }
+}
+
+/// Docker容器状态同步间隔
+const DOCKER_SYNC_INTERVAL: Duration = Duration::from_secs(10);
+
+/// 查找容器所在的Docker主机，未指定时为本机
+fn docker_host(state: &StateStore, host_id: &Option<String>) -> Result<Option<DockerHost>> {
+    let Some(host_id) = host_id else {
+        return Ok(None);
+    };
+    state.read(|state| {
+        state.docker_hosts
+            .iter()
+            .find(|h| &h.id == host_id)
+            .cloned()
+            .map(Some)
+            .ok_or_else(|| anyhow::anyhow!("Docker主机不存在: {}", host_id))
+    })
+}
+
+/// 克隆的名称：以 `-数字` 结尾的名称递增该数字，否则添加 `-2`，跳过已使用的名称；空名称保持为空
+fn next_copy_name(name: &str, taken: &HashSet<String>) -> String {
+    if name.is_empty() {
+        return String::new();
+    }
+    let (base, mut number) = match name.rsplit_once('-').and_then(|(base, suffix)| Some((base, suffix.parse::<u32>().ok()?))) {
+        Some((base, number)) if !base.is_empty() => (base, number),
+        _ => (name, 1),
+    };
+    loop {
+        number += 1;
+        let candidate = format!("{}-{}", base, number);
+        if !taken.contains(&candidate) {
+            return candidate;
+        }
+    }
+}
+
+/// 容器映射的宿主机端口被同一主机上其他运行中的容器占用时返回错误
+fn ensure_ports_free(state: &StateStore, entity_id: &str, host_id: &Option<String>, ports: &[PortMapping]) -> Result<()> {
+    let conflict = state.read(|state| {
+        state.port_conflicts(entity_id, host_id, ports)
+            .into_iter()
+            .find(|c| c.running)
+    });
+    match conflict {
+        Some(conflict) => anyhow::bail!("{}，拒绝启动", conflict),
+        None => Ok(()),
+    }
+}
+
+/// 由Docker管理的容器，关联Kubernetes的容器与主机已不存在的容器除外
+fn managed_containers(state: &StateStore) -> Vec<InspectTarget> {
+    state.read(|state| {
+        let target = |id: &str, params: &str, host_id: &Option<String>, kubernetes: &Option<KubernetesLink>| {
+            if params.trim().is_empty() || kubernetes.is_some() {
+                return None;
+            }
+            let host = match host_id {
+                Some(host_id) => Some(state.docker_hosts.iter().find(|h| &h.id == host_id)?.clone()),
+                None => None,
+            };
+            Some(InspectTarget {
+                id: id.to_string(),
+                params: params.to_string(),
+                host,
+            })
+        };
+        let mut targets = Vec::new();
+        for group in &state.business_groups {
+            for middleware in &group.middlewares {
+                targets.extend(target(&middleware.id, &middleware.docker_run_params, &middleware.docker_host_id, &middleware.kubernetes));
+                targets.extend(
+                    middleware.backend_containers
+                        .iter()
+                        .filter_map(|b| target(&b.id, &b.docker_run_params, &b.docker_host_id, &b.kubernetes)),
+                );
+            }
+            targets.extend(
+                group.backend_containers
+                    .iter()
+                    .filter_map(|b| target(&b.id, &b.docker_run_params, &b.docker_host_id, &b.kubernetes)),
+            );
+        }
+        targets
+    })
+}
+
+/// Docker服务，管理Docker主机，并让配置了运行参数的容器状态跟随实际的Docker容器
+pub struct DockerService {
+    state: StateStore,
+    sync: Option<Receiver<Result<HashMap<String, ContainerStatus>>>>,
+    last_sync: Option<Instant>,
+    /// 主机连接测试结果接收端
+    test: Option<Receiver<(String, Result<String>)>>,
+    /// 对账扫描结果接收端
+    scan: Option<Receiver<ReconcileReport>>,
+}
+
+impl DockerService {
+    /// 创建新的Docker服务
+    pub fn new(state: StateStore) -> Self {
+        Self {
+            state,
+            sync: None,
+            last_sync: None,
+            test: None,
+            scan: None,
+        }
+    }
+    
+    /// 查找所有业务组中与给定端口映射冲突的其他容器
+    pub fn port_conflicts(&self, entity_id: &str, host_id: &Option<String>, ports: &[PortMapping]) -> Vec<PortConflict> {
+        self.state.read(|state| state.port_conflicts(entity_id, host_id, ports))
+    }
+    
+    /// 获取所有Docker主机
+    pub fn get_hosts(&self) -> Vec<DockerHost> {
+        self.state.read(|state| state.docker_hosts.clone())
+    }
+    
+    /// 添加Docker主机
+    pub fn add_host(&self, host: DockerHost) -> Result<()> {
+        self.state.update(|state| {
+            state.docker_hosts.push(host);
+            Ok(())
+        })
+    }
+    
+    /// 更新Docker主机
+    pub fn update_host(&self, host: DockerHost) -> Result<()> {
+        self.state.update(|state| {
+            let existing = state.docker_hosts
+                .iter_mut()
+                .find(|h| h.id == host.id)
+                .ok_or_else(|| anyhow::anyhow!("Docker主机不存在: {}", host.id))?;
+            *existing = host;
+            Ok(())
+        })
+    }
+    
+    /// 删除Docker主机，仍有容器使用时拒绝删除
+    pub fn delete_host(&self, host_id: &str) -> Result<()> {
+        self.state.update(|state| {
+            let in_use = state.business_groups.iter().any(|group| {
+                let uses = |id: &Option<String>| id.as_deref() == Some(host_id);
+                group.middlewares.iter().any(|m| {
+                    uses(&m.docker_host_id) || m.backend_containers.iter().any(|b| uses(&b.docker_host_id))
+                }) || group.backend_containers.iter().any(|b| uses(&b.docker_host_id))
+            });
+            if in_use {
+                anyhow::bail!("仍有容器使用该Docker主机，请先修改这些容器的主机");
+            }
+            state.docker_hosts.retain(|h| h.id != host_id);
+            Ok(())
+        })
+    }
+    
+    /// 是否正在测试主机连接
+    pub fn is_testing(&self) -> bool {
+        self.test.is_some()
+    }
+    
+    /// 在后台测试主机连接
+    pub fn test_host(&mut self, host: DockerHost) {
+        if self.is_testing() {
+            return;
+        }
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            let result = docker::ping(Some(&host));
+            let _ = sender.send((host.id, result));
+        });
+        self.test = Some(receiver);
+    }
+    
+    /// 收取主机连接测试结果（主机ID, 结果）
+    pub fn poll_test(&mut self) -> Option<(String, Result<String>)> {
+        let result = match self.test.as_ref()?.try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => return None,
+            Err(TryRecvError::Disconnected) => (String::new(), Err(anyhow::anyhow!("测试线程异常退出"))),
+        };
+        self.test = None;
+        Some(result)
+    }
+    
+    /// 是否正在对账扫描
+    pub fn is_scanning(&self) -> bool {
+        self.scan.is_some()
+    }
+    
+    /// 在后台列出本机与所有主机上带有管理标签的容器，并与配置对比
+    pub fn scan(&mut self) {
+        if self.is_scanning() {
+            return;
+        }
+        let state = self.state.clone();
+        let hosts: Vec<Option<DockerHost>> = std::iter::once(None)
+            .chain(self.get_hosts().into_iter().map(Some))
+            .collect();
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            let scans = hosts
+                .into_iter()
+                .map(|host| {
+                    let containers = docker::list_labeled(host.as_ref());
+                    HostScan { host, containers }
+                })
+                .collect();
+            let _ = sender.send(state.read(|state| reconcile::reconcile(state, scans)));
+        });
+        self.scan = Some(receiver);
+    }
+    
+    /// 收取对账扫描结果
+    pub fn poll_scan(&mut self) -> Option<ReconcileReport> {
+        match self.scan.as_ref()?.try_recv() {
+            Ok(report) => {
+                self.scan = None;
+                Some(report)
+            }
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => {
+                self.scan = None;
+                None
+            }
+        }
+    }
+    
+    /// 停止并删除孤儿容器
+    pub fn remove_orphan(&self, orphan: &Orphan) -> Result<()> {
+        let host = docker_host(&self.state, &orphan.host_id)?;
+        docker::remove_container(host.as_ref(), &orphan.container.name)
+    }
+    
+    /// 将实际状态写回模型；启动中的中间层由预热检查决定最终状态，不会被置为运行中
+    ///
+    /// 返回运行中或启动中却已退出的容器
+    fn apply(&self, statuses: &HashMap<String, ContainerStatus>) -> Result<Vec<ContainerExit>> {
+        let target = |id: &str, current: &ContainerStatus| {
+            let status = statuses.get(id)?;
+            let warming = *current == ContainerStatus::Starting && *status == ContainerStatus::Running;
+            (status != current && !warming).then(|| status.clone())
+        };
+        let exited = |id: &str, current: &ContainerStatus| {
+            matches!(current, ContainerStatus::Running | ContainerStatus::Starting)
+                && matches!(target(id, current), Some(ContainerStatus::Stopped | ContainerStatus::Error))
+        };
+        let (changed, exits) = self.state.read(|state| {
+            let mut changed = false;
+            let mut exits = Vec::new();
+            for group in &state.business_groups {
+                for middleware in &group.middlewares {
+                    changed |= target(&middleware.id, &middleware.status).is_some();
+                    if exited(&middleware.id, &middleware.status) {
+                        exits.push(ContainerExit {
+                            kind: EntityKind::Middleware,
+                            group_id: group.id.clone(),
+                            middleware_id: Some(middleware.id.clone()),
+                            id: middleware.id.clone(),
+                            name: middleware.name.clone(),
+                            failed: statuses.get(&middleware.id) == Some(&ContainerStatus::Error),
+                            policy: middleware.restart_policy.clone(),
+                        });
+                    }
+                    for backend in &middleware.backend_containers {
+                        changed |= target(&backend.id, &backend.status).is_some();
+                        if exited(&backend.id, &backend.status) {
+                            exits.push(ContainerExit::backend(&GroupId::from(&group.id), Some(&MiddlewareId::from(&middleware.id)), backend, statuses));
+                        }
+                    }
+                }
+                for backend in &group.backend_containers {
+                    changed |= target(&backend.id, &backend.status).is_some();
+                    if exited(&backend.id, &backend.status) {
+                        exits.push(ContainerExit::backend(&GroupId::from(&group.id), None, backend, statuses));
+                    }
+                }
+            }
+            (changed, exits)
+        });
+        if !changed {
+            return Ok(exits);
+        }
+        
+        let backend_changed = |b: &BackendContainer| target(&b.id, &b.status).is_some();
+        let group_changed = |group: &BusinessGroup| {
+            group.middlewares.iter().any(|m| target(&m.id, &m.status).is_some() || m.backend_containers.iter().any(backend_changed))
+                || group.backend_containers.iter().any(backend_changed)
+        };
+        self.state.update(|state| {
+            for group in state.business_groups.matching_mut(group_changed) {
+                for middleware in &mut group.middlewares {
+                    if let Some(status) = target(&middleware.id, &middleware.status) {
+                        middleware.status = status;
+                    }
+                    for backend in &mut middleware.backend_containers {
+                        if let Some(status) = target(&backend.id, &backend.status) {
+                            backend.status = status;
+                        }
+                    }
+                }
+                for backend in &mut group.backend_containers {
+                    if let Some(status) = target(&backend.id, &backend.status) {
+                        backend.status = status;
+                    }
+                }
+            }
+            Ok(())
+        })?;
+        Ok(exits)
+    }
+    
+    /// 收取状态同步结果，并在到期时发起新的同步；返回本次同步发现的已退出容器
+    pub fn tick(&mut self) -> Vec<ContainerExit> {
+        let mut exits = Vec::new();
+        if let Some(receiver) = &self.sync {
+            match receiver.try_recv() {
+                Ok(result) => {
+                    self.sync = None;
+                    match result.and_then(|statuses| self.apply(&statuses)) {
+                        Ok(exited) => exits = exited,
+                        Err(e) => tracing::warn!("同步Docker容器状态失败: {:#}", e),
+                    }
+                }
+                Err(TryRecvError::Empty) => return exits,
+                Err(TryRecvError::Disconnected) => self.sync = None,
+            }
+        }
+        
+        if self.last_sync.is_some_and(|t| t.elapsed() < DOCKER_SYNC_INTERVAL) {
+            return exits;
+        }
+        self.last_sync = Some(Instant::now());
+        
+        let targets = managed_containers(&self.state);
+        if targets.is_empty() {
+            return exits;
+        }
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            let _ = sender.send(docker::inspect(&targets));
+        });
+        self.sync = Some(receiver);
+        exits
+    }
+}
+
+/// 运行中却已退出的容器
+#[derive(Debug, Clone)]
+pub struct ContainerExit {
+    pub kind: EntityKind,
+    pub group_id: String,
+    /// 中间层自身或后端所属的中间层，业务组直属后端为空
+    pub middleware_id: Option<String>,
+    pub id: String,
+    pub name: String,
+    /// 是否非零退出或异常终止
+    pub failed: bool,
+    pub policy: AutoRestartPolicy,
+}
+
+impl ContainerExit {
+    fn backend(group_id: &GroupId, middleware_id: Option<&MiddlewareId>, backend: &BackendContainer, statuses: &HashMap<String, ContainerStatus>) -> Self {
+        Self {
+            kind: EntityKind::Backend,
+            group_id: group_id.to_string(),
+            middleware_id: middleware_id.map(MiddlewareId::to_string),
+            id: backend.id.clone(),
+            name: backend.name.clone(),
+            failed: statuses.get(&backend.id) == Some(&ContainerStatus::Error),
+            policy: backend.restart_policy.clone(),
+        }
+    }
+    
+    /// 重新启动容器
+    fn restart(&self, state: &StateStore) -> Result<()> {
+        match self.kind {
+            EntityKind::Middleware => MiddlewareService::new(state.clone()).start_middleware(&GroupId::from(&self.group_id), &MiddlewareId::from(&self.id)),
+            _ => BackendService::new(state.clone()).start_backend(&GroupId::from(&self.group_id), self.middleware_id.as_deref().map(MiddlewareId::from).as_ref(), &BackendId::from(&self.id)),
+        }
+    }
+}
+
+/// 容器稳定运行超过该时长后，连续重启次数重新计算
+const RESTART_STABLE_AFTER: Duration = Duration::from_secs(600);
+
+/// 单个容器的自动重启状态
+struct RestartTracker {
+    exit: ContainerExit,
+    /// 已连续重启的次数
+    attempts: u32,
+    last_restart: Option<Instant>,
+    /// 下一次重启的时间，为空时不等待重启
+    due: Option<Instant>,
+}
+
+/// 自动重启的执行结果
+pub struct SupervisorEvent {
+    pub log: LogEntry,
+    /// 重启成功的中间层，需要开始预热检查
+    pub warmup: Option<(String, MiddlewareContainer)>,
+}
+
+/// 容器监管服务，按各容器的自动重启策略重新启动意外退出的容器
+///
+/// 退出由Docker状态同步发现；等待重启期间容器状态被其他操作改变（例如手动启动或停止）时取消重启
+pub struct SupervisorService {
+    state: StateStore,
+    events: Receiver<ModelEvent>,
+    trackers: HashMap<String, RestartTracker>,
+    sender: Sender<(String, Result<()>)>,
+    receiver: Receiver<(String, Result<()>)>,
+    in_flight: HashSet<String>,
+}
+
+impl SupervisorService {
+    /// 创建新的容器监管服务
+    pub fn new(state: StateStore, bus: &EventBus) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            state,
+            events: bus.subscribe(),
+            trackers: HashMap::new(),
+            sender,
+            receiver,
+            in_flight: HashSet::new(),
+        }
+    }
+    
+    /// 等待重启的容器及剩余秒数
+    pub fn pending(&self) -> Vec<(String, u64)> {
+        let now = Instant::now();
+        self.trackers
+            .values()
+            .filter_map(|t| Some((t.exit.name.clone(), t.due?.saturating_duration_since(now).as_secs())))
+            .collect()
+    }
+    
+    /// 处理容器退出，按策略安排重启
+    pub fn observe(&mut self, exits: Vec<ContainerExit>) -> Vec<LogEntry> {
+        // 退出本身产生的状态事件已在队列中，先处理掉以免误判为人工操作
+        self.drain_events(&exits.iter().map(|e| e.id.clone()).collect());
+        let mut logs = Vec::new();
+        for exit in exits {
+            let how = if exit.failed { "异常退出" } else { "已退出" };
+            if !exit.policy.applies(exit.failed) {
+                if exit.policy.mode != AutoRestartMode::Never {
+                    logs.push(LogEntry::new(&exit.name, &format!("容器{}，按策略“{}”不重启", how, exit.policy.mode.label())));
+                }
+                self.trackers.remove(&exit.id);
+                continue;
+            }
+            let tracker = self.trackers.entry(exit.id.clone()).or_insert_with(|| RestartTracker {
+                exit: exit.clone(),
+                attempts: 0,
+                last_restart: None,
+                due: None,
+            });
+            if tracker.last_restart.is_some_and(|t| t.elapsed() >= RESTART_STABLE_AFTER) {
+                tracker.attempts = 0;
+            }
+            tracker.exit = exit;
+            logs.push(Self::schedule(tracker, how));
+        }
+        logs
+    }
+    
+    /// 安排下一次重启，超过次数上限时放弃
+    fn schedule(tracker: &mut RestartTracker, how: &str) -> LogEntry {
+        let policy = &tracker.exit.policy;
+        if policy.max_retries > 0 && tracker.attempts >= policy.max_retries {
+            tracker.due = None;
+            return LogEntry::new(&tracker.exit.name, &format!("容器{}，已连续重启 {} 次，达到上限后不再重启", how, tracker.attempts));
+        }
+        let delay = policy.delay_secs(tracker.attempts);
+        tracker.due = Some(Instant::now() + Duration::from_secs(delay));
+        LogEntry::new(&tracker.exit.name, &format!("容器{}，将在 {} 秒后自动重启（第 {} 次）", how, delay, tracker.attempts + 1))
+    }
+    
+    /// 收取重启结果并执行到期的重启
+    pub fn tick(&mut self) -> Vec<SupervisorEvent> {
+        let mut results = Vec::new();
+        while let Ok(result) = self.receiver.try_recv() {
+            results.push(result);
+        }
+        // 重启线程自身产生的状态事件不视为人工操作
+        let finished: HashSet<String> = results.iter().map(|(id, _)| id.clone()).collect();
+        self.drain_events(&finished);
+        
+        let mut events = Vec::new();
+        for (id, result) in results {
+            self.in_flight.remove(&id);
+            let Some(tracker) = self.trackers.get_mut(&id) else {
+                continue;
+            };
+            match result {
+                Ok(()) => {
+                    let warmup = (tracker.exit.kind == EntityKind::Middleware)
+                        .then(|| MiddlewareService::new(self.state.clone()).get_middleware(&GroupId::from(&tracker.exit.group_id), &MiddlewareId::from(&id)).ok())
+                        .flatten()
+                        .map(|m| (tracker.exit.group_id.clone(), m));
+                    events.push(SupervisorEvent {
+                        log: LogEntry::new(&tracker.exit.name, &format!("已自动重启（第 {} 次）", tracker.attempts)),
+                        warmup,
+                    });
+                }
+                Err(e) => {
+                    let how = &format!("自动重启失败: {:#}", e);
+                    events.push(SupervisorEvent { log: Self::schedule(tracker, how), warmup: None });
+                }
+            }
+        }
+        
+        let now = Instant::now();
+        for (id, tracker) in &mut self.trackers {
+            if self.in_flight.contains(id) || tracker.due.is_none_or(|due| due > now) {
+                continue;
+            }
+            tracker.due = None;
+            tracker.attempts += 1;
+            tracker.last_restart = Some(now);
+            self.in_flight.insert(id.clone());
+            let exit = tracker.exit.clone();
+            let state = self.state.clone();
+            let sender = self.sender.clone();
+            std::thread::spawn(move || {
+                let result = exit.restart(&state);
+                let _ = sender.send((exit.id, result));
+            });
+        }
+        events
+    }
+    
+    /// 处理模型事件：等待重启的容器状态发生变化或被删除时取消重启
+    fn drain_events(&mut self, ignore: &HashSet<String>) {
+        while let Ok(event) = self.events.try_recv() {
+            match &event {
+                ModelEvent::StatusChanged { id, .. } | ModelEvent::Deleted { id, .. } if !ignore.contains(id) => {
+                    if self.trackers.get(id).is_some_and(|t| t.due.is_some()) {
+                        tracing::info!("容器 {} 状态已被其他操作改变，取消自动重启", id);
+                    }
+                    if !self.in_flight.contains(id) {
+                        self.trackers.remove(id);
+                    }
+                }
+                ModelEvent::Reloaded => self.trackers.retain(|id, _| self.in_flight.contains(id)),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// 容器资源统计采集间隔
+const STATS_INTERVAL: Duration = Duration::from_secs(5);
+/// 每个容器保留的统计样本数
+const STATS_WINDOW: usize = 120;
+
+/// 一次资源统计采样
+#[derive(Debug, Clone)]
+pub struct StatsSample {
+    pub timestamp: DateTime<Utc>,
+    pub stats: ContainerStats,
+}
+
+/// 容器资源统计服务，定期采集运行中容器的CPU、内存与网络用量并保留最近的样本
+pub struct StatsService {
+    state: StateStore,
+    receiver: Option<Receiver<Result<HashMap<String, ContainerStats>>>>,
+    last_poll: Option<Instant>,
+    samples: HashMap<String, VecDeque<StatsSample>>,
+}
+
+impl StatsService {
+    /// 创建新的资源统计服务
+    pub fn new(state: StateStore) -> Self {
+        Self {
+            state,
+            receiver: None,
+            last_poll: None,
+            samples: HashMap::new(),
+        }
+    }
+    
+    /// 容器最近的统计样本，按时间先后排列
+    pub fn samples(&self, id: &str) -> Option<&VecDeque<StatsSample>> {
+        self.samples.get(id).filter(|s| !s.is_empty())
+    }
+    
+    /// 容器最新的统计样本
+    pub fn latest(&self, id: &str) -> Option<&StatsSample> {
+        self.samples.get(id)?.back()
+    }
+    
+    /// 容器最近两次采样之间的网络速率（接收, 发送），单位为字节每秒
+    pub fn network_rate(&self, id: &str) -> Option<(f64, f64)> {
+        let samples = self.samples.get(id)?;
+        let [previous, latest] = [samples.get(samples.len().checked_sub(2)?)?, samples.back()?];
+        let seconds = (latest.timestamp - previous.timestamp).num_milliseconds() as f64 / 1000.0;
+        if seconds <= 0.0 {
+            return None;
+        }
+        let rate = |current: u64, before: u64| current.saturating_sub(before) as f64 / seconds;
+        Some((rate(latest.stats.rx_bytes, previous.stats.rx_bytes), rate(latest.stats.tx_bytes, previous.stats.tx_bytes)))
+    }
+    
+    /// 收取采集结果，并在到期时对运行中的容器发起新的采集
+    pub fn tick(&mut self) {
+        if let Some(receiver) = &self.receiver {
+            match receiver.try_recv() {
+                Ok(result) => {
+                    self.receiver = None;
+                    match result {
+                        Ok(stats) => self.record(stats),
+                        Err(e) => tracing::warn!("采集容器资源统计失败: {:#}", e),
+                    }
+                }
+                Err(TryRecvError::Empty) => return,
+                Err(TryRecvError::Disconnected) => self.receiver = None,
+            }
+        }
+        
+        if self.last_poll.is_some_and(|t| t.elapsed() < STATS_INTERVAL) {
+            return;
+        }
+        self.last_poll = Some(Instant::now());
+        let running: HashSet<String> = self.state.read(|state| {
+            let mut ids = HashSet::new();
+            for group in &state.business_groups {
+                for middleware in &group.middlewares {
+                    if middleware.status == ContainerStatus::Running {
+                        ids.insert(middleware.id.clone());
+                    }
+                    ids.extend(middleware.backend_containers.iter().filter(|b| b.status == ContainerStatus::Running).map(|b| b.id.clone()));
+                }
+                ids.extend(group.backend_containers.iter().filter(|b| b.status == ContainerStatus::Running).map(|b| b.id.clone()));
+            }
+            ids
+        });
+        let targets: Vec<InspectTarget> = managed_containers(&self.state)
+            .into_iter()
+            .filter(|t| running.contains(&t.id))
+            .collect();
+        // 已停止或删除的容器不再显示旧数据
+        self.samples.retain(|id, _| running.contains(id));
+        if targets.is_empty() {
+            return;
+        }
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            let _ = sender.send(docker::stats(&targets));
+        });
+        self.receiver = Some(receiver);
+    }
+    
+    fn record(&mut self, stats: HashMap<String, ContainerStats>) {
+        let timestamp = Utc::now();
+        for (id, stats) in stats {
+            let samples = self.samples.entry(id).or_default();
+            if samples.len() >= STATS_WINDOW {
+                samples.pop_front();
+            }
+            samples.push_back(StatsSample { timestamp, stats });
+        }
+    }
+}
+
+/// 检查镜像更新的间隔
+const IMAGE_CHECK_INTERVAL: Duration = Duration::from_secs(6 * 3600);
+
+/// 容器镜像的版本检查结果
+#[derive(Debug, Clone)]
+pub struct ImageVersion {
+    /// 检查时容器定义中的镜像
+    pub image: String,
+    /// 主机上镜像的摘要
+    pub digest: Option<String>,
+    /// 仓库中同一标签当前的摘要
+    pub remote_digest: Option<String>,
+    /// 仓库中同一变体的更高版本标签
+    pub newer_tag: Option<String>,
+    pub checked_at: DateTime<Utc>,
+    /// 检查失败的原因，部分失败时其余结果仍然有效
+    pub error: Option<String>,
+}
+
+impl ImageVersion {
+    /// 可升级到的镜像：有更高版本标签时换用该标签，同一标签在仓库中已更新时重新拉取该标签
+    pub fn upgrade_target(&self) -> Option<String> {
+        if let Some(tag) = &self.newer_tag {
+            let (name, _) = match self.image.rfind(':').filter(|i| !self.image[*i..].contains('/')) {
+                Some(i) => self.image.split_at(i),
+                None => (self.image.as_str(), ""),
+            };
+            return Some(format!("{}:{}", name, tag));
+        }
+        match (&self.digest, &self.remote_digest) {
+            (Some(local), Some(remote)) if local != remote => Some(self.image.clone()),
+            _ => None,
+        }
+    }
+    
+    /// 检查镜像的版本：比较主机与仓库中的摘要，并在仓库中查找更高版本的标签
+    fn check(host: Option<&DockerHost>, image: &str) -> Self {
+        let mut errors = Vec::new();
+        let digests = docker::image_digests(host, image)
+            .inspect_err(|e| errors.push(format!("{:#}", e)))
+            .unwrap_or_default();
+        let newer_tag = registry::ImageReference::parse(image)
+            .and_then(|reference| {
+                let tags = tasks::block_on(registry::list_tags(&reference))?;
+                Ok(registry::newer_tag(&reference.tag, &tags))
+            })
+            .inspect_err(|e| errors.push(format!("{:#}", e)))
+            .ok()
+            .flatten();
+        Self {
+            image: image.to_string(),
+            digest: digests.local,
+            remote_digest: digests.remote,
+            newer_tag,
+            checked_at: Utc::now(),
+            error: (!errors.is_empty()).then(|| errors.join("; ")),
+        }
+    }
+}
+
+/// 镜像更新服务，定期检查由Docker管理的容器所用镜像在仓库中是否有更新
+pub struct ImageUpdateService {
+    state: StateStore,
+    /// 各容器的检查结果
+    versions: HashMap<String, ImageVersion>,
+    receiver: Option<Receiver<HashMap<String, ImageVersion>>>,
+    last_check: Option<Instant>,
+}
+
+impl ImageUpdateService {
+    /// 创建新的镜像更新服务
+    pub fn new(state: StateStore) -> Self {
+        Self {
+            state,
+            versions: HashMap::new(),
+            receiver: None,
+            last_check: None,
+        }
+    }
+    
+    /// 容器的检查结果，容器定义中的镜像已修改时不再有效
+    pub fn version(&self, id: &str, image: &str) -> Option<&ImageVersion> {
+        self.versions.get(id).filter(|v| v.image == image)
+    }
+    
+    /// 是否正在检查
+    pub fn is_checking(&self) -> bool {
+        self.receiver.is_some()
+    }
+    
+    /// 丢弃容器的检查结果，升级后等待下次检查
+    pub fn forget(&mut self, id: &str) {
+        self.versions.remove(id);
+    }
+    
+    /// 立即在后台检查所有容器的镜像
+    pub fn check_now(&mut self) {
+        if self.is_checking() {
+            return;
+        }
+        self.last_check = Some(Instant::now());
+        let targets = managed_containers(&self.state);
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            // 同一主机上的同一镜像只检查一次
+            let mut checked: HashMap<(Option<String>, String), ImageVersion> = HashMap::new();
+            let mut versions = HashMap::new();
+            for target in targets {
+                let Ok(spec) = ContainerSpec::parse(&target.params) else {
+                    continue;
+                };
+                let key = (target.host.as_ref().map(|h| h.id.clone()), spec.image.clone());
+                let version = checked
+                    .entry(key)
+                    .or_insert_with(|| ImageVersion::check(target.host.as_ref(), &spec.image))
+                    .clone();
+                versions.insert(target.id, version);
+            }
+            let _ = sender.send(versions);
+        });
+        self.receiver = Some(receiver);
+    }
+    
+    /// 收取检查结果，并在到期时发起新的检查；返回发现可升级的容器数量
+    pub fn tick(&mut self) -> usize {
+        if let Some(receiver) = &self.receiver {
+            match receiver.try_recv() {
+                Ok(versions) => {
+                    self.receiver = None;
+                    let upgradable = versions.values().filter(|v| v.upgrade_target().is_some()).count();
+                    self.versions = versions;
+                    return upgradable;
+                }
+                Err(TryRecvError::Empty) => return 0,
+                Err(TryRecvError::Disconnected) => self.receiver = None,
+            }
+        }
+        if self.last_check.is_none_or(|t| t.elapsed() >= IMAGE_CHECK_INTERVAL) {
+            self.check_now();
+        }
+        0
+    }
+}
+
+/// 检查需要读取日志的容器的间隔
+const LOG_STREAM_SYNC_INTERVAL: Duration = Duration::from_secs(5);
+/// 每个容器保留的日志行数
+const MAX_CONTAINER_LOG_LINES: usize = 2000;
+/// 首次读取日志时读取的历史行数
+const LOG_STREAM_TAIL: usize = 200;
+
+/// 容器输出的一行日志
+#[derive(Debug, Clone)]
+pub struct ContainerLogLine {
+    pub timestamp: DateTime<Utc>,
+    pub text: String,
+}
+
+/// 各容器的日志缓冲，读取线程写入，界面读取
+type ContainerLogBuffers = Arc<std::sync::Mutex<HashMap<String, VecDeque<ContainerLogLine>>>>;
+
+/// 一个容器的日志读取线程
+struct LogStream {
+    name: String,
+    cancel: Arc<AtomicBool>,
+}
+
+/// 结束的日志读取
+struct LogStreamEnd {
+    id: String,
+    /// 读取线程的取消标志，用于区分同一容器先后的读取
+    cancel: Arc<AtomicBool>,
+    /// 结束时的Unix秒
+    ended_at: i64,
+    error: Option<String>,
+}
+
+impl Drop for LogStream {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// 容器日志服务，为运行中的Docker容器各启动一个 `docker logs --follow` 读取线程，输出保存在按容器分开的有限缓冲中
+///
+/// 容器停止后停止读取但保留已读到的日志；读取中断后按中断时间接续，避免重复
+pub struct LogStreamService {
+    state: StateStore,
+    buffers: ContainerLogBuffers,
+    streams: HashMap<String, LogStream>,
+    sender: Sender<LogStreamEnd>,
+    receiver: Receiver<LogStreamEnd>,
+    /// 各容器上次读取结束的时间
+    ended_at: HashMap<String, i64>,
+    /// 读取出错的容器，连续出错时只记录一次
+    failing: HashSet<String>,
+    last_sync: Option<Instant>,
+}
+
+impl LogStreamService {
+    /// 创建新的容器日志服务
+    pub fn new(state: StateStore) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            state,
+            buffers: Arc::default(),
+            streams: HashMap::new(),
+            sender,
+            receiver,
+            ended_at: HashMap::new(),
+            failing: HashSet::new(),
+            last_sync: None,
+        }
+    }
+    
+    /// 容器是否正在读取日志
+    pub fn is_streaming(&self, id: &str) -> bool {
+        self.streams.contains_key(id)
+    }
+    
+    /// 是否有容器正在读取日志
+    pub fn is_active(&self) -> bool {
+        !self.streams.is_empty()
+    }
+    
+    /// 有日志的容器ID
+    pub fn containers(&self) -> Vec<String> {
+        let buffers = self.buffers.lock().unwrap_or_else(|e| e.into_inner());
+        buffers.keys().cloned().collect()
+    }
+    
+    /// 容器已读到的日志，按时间先后排列
+    pub fn lines(&self, id: &str) -> Vec<ContainerLogLine> {
+        let buffers = self.buffers.lock().unwrap_or_else(|e| e.into_inner());
+        buffers.get(id).map(|lines| lines.iter().cloned().collect()).unwrap_or_default()
+    }
+    
+    /// 清空容器已读到的日志，读取继续
+    pub fn clear(&self, id: &str) {
+        let mut buffers = self.buffers.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(lines) = buffers.get_mut(id) {
+            lines.clear();
+        }
+    }
+    
+    /// 收取结束的读取，并在到期时为运行中的容器开始读取、为已停止或删除的容器停止读取；返回读取出错的日志
+    pub fn tick(&mut self) -> Vec<LogEntry> {
+        let mut logs = Vec::new();
+        while let Ok(end) = self.receiver.try_recv() {
+            self.ended_at.insert(end.id.clone(), end.ended_at);
+            // 已停止读取的线程迟到的结束不影响之后重新开始的读取
+            let Some(stream) = self.streams.get(&end.id).filter(|s| Arc::ptr_eq(&s.cancel, &end.cancel)) else {
+                continue;
+            };
+            let name = stream.name.clone();
+            self.streams.remove(&end.id);
+            match end.error {
+                Some(error) if self.failing.insert(end.id.clone()) => {
+                    logs.push(LogEntry::new(&name, &format!("读取容器日志中断: {}", error)));
+                }
+                Some(_) => {}
+                None => {
+                    self.failing.remove(&end.id);
+                }
+            }
+        }
+        
+        if self.last_sync.is_some_and(|t| t.elapsed() < LOG_STREAM_SYNC_INTERVAL) {
+            return logs;
+        }
+        self.last_sync = Some(Instant::now());
+        // 所有容器的名称，以及其中运行中或启动中的容器
+        let (running, existing): (HashSet<String>, HashMap<String, String>) = self.state.read(|state| {
+            let mut running = HashSet::new();
+            let mut existing = HashMap::new();
+            let mut add = |id: &str, name: &str, status: &ContainerStatus| {
+                existing.insert(id.to_string(), name.to_string());
+                if matches!(status, ContainerStatus::Running | ContainerStatus::Starting) {
+                    running.insert(id.to_string());
+                }
+            };
+            for group in &state.business_groups {
+                for middleware in &group.middlewares {
+                    add(&middleware.id, &middleware.name, &middleware.status);
+                    for backend in &middleware.backend_containers {
+                        add(&backend.id, &backend.name, &backend.status);
+                    }
+                }
+                for backend in &group.backend_containers {
+                    add(&backend.id, &backend.name, &backend.status);
+                }
+            }
+            (running, existing)
+        });
+        
+        // 停止读取已停止的容器，释放读取状态即置位取消
+        self.streams.retain(|id, _| running.contains(id));
+        // 已删除的容器不再保留日志
+        self.buffers.lock().unwrap_or_else(|e| e.into_inner()).retain(|id, _| existing.contains_key(id));
+        self.ended_at.retain(|id, _| existing.contains_key(id));
+        self.failing.retain(|id| running.contains(id));
+        
+        for target in managed_containers(&self.state) {
+            if !running.contains(&target.id) || self.streams.contains_key(&target.id) {
+                continue;
+            }
+            let cancel = Arc::new(AtomicBool::new(false));
+            let name = existing.get(&target.id).cloned().unwrap_or_default();
+            self.streams.insert(target.id.clone(), LogStream { name, cancel: cancel.clone() });
+            let since = self.ended_at.get(&target.id).copied();
+            let buffers = self.buffers.clone();
+            let sender = self.sender.clone();
+            std::thread::spawn(move || {
+                let result = docker::follow_logs(target.host.as_ref(), &target.id, &target.params, LOG_STREAM_TAIL, since, &cancel, |text| {
+                    let mut buffers = buffers.lock().unwrap_or_else(|e| e.into_inner());
+                    let lines = buffers.entry(target.id.clone()).or_default();
+                    if lines.len() >= MAX_CONTAINER_LOG_LINES {
+                        lines.pop_front();
+                    }
+                    lines.push_back(ContainerLogLine { timestamp: Utc::now(), text: text.to_string() });
+                });
+                let _ = sender.send(LogStreamEnd {
+                    id: target.id,
+                    cancel,
+                    ended_at: Utc::now().timestamp(),
+                    error: result.err().map(|e| format!("{:#}", e)),
+                });
+            });
+        }
+        logs
+    }
+}
+
+/// 每个容器保留的控制台输出上限
+const MAX_SCROLLBACK_BYTES: usize = 512 * 1024;
+
+/// 控制台输出的类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConsoleStream {
+    /// 用户输入的命令
+    Command,
+    Output,
+    /// 退出码等提示
+    Status,
+    Error,
+}
+
+/// 控制台中的一段输出
+#[derive(Debug, Clone)]
+pub struct ConsoleEntry {
+    pub stream: ConsoleStream,
+    pub text: String,
+}
+
+/// 可以打开控制台的容器
+#[derive(Debug, Clone)]
+pub struct ConsoleTarget {
+    pub id: String,
+    pub kind: EntityKind,
+    /// 业务组 / 中间层 / 后端
+    pub label: String,
+}
+
+/// 执行线程发回的消息
+enum ExecMessage {
+    Output(String),
+    Finished(Result<Option<i64>>),
+}
+
+/// 执行中的命令
+struct ConsoleExec {
+    container_id: String,
+    receiver: Receiver<ExecMessage>,
+    cancel: Arc<AtomicBool>,
+}
+
+/// 容器控制台服务，通过Docker exec在运行中的容器内执行命令并保留各容器的输出
+///
+/// 与远程命令使用相同的授权操作人名单
+pub struct ConsoleService {
+    config_manager: ConfigManager,
+    state: StateStore,
+    scrollback: HashMap<String, Vec<ConsoleEntry>>,
+    exec: Option<ConsoleExec>,
+}
+
+impl ConsoleService {
+    /// 创建新的容器控制台服务
+    pub fn new(config_manager: ConfigManager, state: StateStore) -> Self {
+        Self {
+            config_manager,
+            state,
+            scrollback: HashMap::new(),
+            exec: None,
+        }
+    }
+    
+    /// 由Docker管理且正在运行的中间层与后端
+    pub fn targets(&self) -> Vec<ConsoleTarget> {
+        let managed: HashSet<String> = managed_containers(&self.state).into_iter().map(|t| t.id).collect();
+        self.state.read(|state| {
+            let mut targets = Vec::new();
+            let mut push = |id: &str, kind: EntityKind, label: String, status: &ContainerStatus| {
+                if *status == ContainerStatus::Running && managed.contains(id) {
+                    targets.push(ConsoleTarget { id: id.to_string(), kind, label });
+                }
+            };
+            for group in &state.business_groups {
+                for middleware in &group.middlewares {
+                    push(&middleware.id, EntityKind::Middleware, format!("{} / {}", group.name, middleware.name), &middleware.status);
+                    for backend in &middleware.backend_containers {
+                        push(&backend.id, EntityKind::Backend, format!("{} / {} / {}", group.name, middleware.name, backend.name), &backend.status);
+                    }
+                }
+                for backend in &group.backend_containers {
+                    push(&backend.id, EntityKind::Backend, format!("{} / {}", group.name, backend.name), &backend.status);
+                }
+            }
+            targets
+        })
+    }
+    
+    /// 容器的控制台输出
+    pub fn scrollback(&self, container_id: &str) -> &[ConsoleEntry] {
+        self.scrollback.get(container_id).map(Vec::as_slice).unwrap_or_default()
+    }
+    
+    /// 清空容器的控制台输出
+    pub fn clear(&mut self, container_id: &str) {
+        self.scrollback.remove(container_id);
+    }
+    
+    /// 正在执行命令的容器
+    pub fn running(&self) -> Option<&str> {
+        self.exec.as_ref().map(|e| e.container_id.as_str())
+    }
+    
+    /// 校验操作人后在后台执行命令
+    pub fn run(&mut self, container_id: &str, command: &str, working_dir: &str, actor: &str) -> Result<()> {
+        if self.exec.is_some() {
+            anyhow::bail!("已有命令正在执行");
+        }
+        let config = self.config_manager.load_config()?;
+        if !config.command_operators.iter().any(|o| o == actor) {
+            anyhow::bail!("操作人 {} 无权在容器中执行命令", actor);
+        }
+        self.state.authorize_entity(container_id)?;
+        let target = managed_containers(&self.state)
+            .into_iter()
+            .find(|t| t.id == container_id)
+            .ok_or_else(|| anyhow::anyhow!("容器未由Docker管理或已删除: {}", container_id))?;
+        
+        self.append(container_id, ConsoleStream::Command, format!("$ {}\n", command));
+        let command = command.to_string();
+        let working_dir = working_dir.to_string();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = mpsc::channel();
+        let cancelled = cancel.clone();
+        std::thread::spawn(move || {
+            let output = sender.clone();
+            let result = docker::exec(target.host.as_ref(), &target.id, &target.params, &command, &working_dir, &cancelled, |text| {
+                let _ = output.send(ExecMessage::Output(text.to_string()));
+            });
+            let _ = sender.send(ExecMessage::Finished(result));
+        });
+        self.exec = Some(ConsoleExec {
+            container_id: container_id.to_string(),
+            receiver,
+            cancel,
+        });
+        Ok(())
+    }
+    
+    /// 中止正在执行的命令，容器内的进程不会被终止
+    pub fn cancel(&self) {
+        if let Some(exec) = &self.exec {
+            exec.cancel.store(true, Ordering::Relaxed);
+        }
+    }
+    
+    /// 收取执行输出，命令结束时返回容器ID
+    pub fn poll(&mut self) -> Option<String> {
+        let exec = self.exec.as_ref()?;
+        let container_id = exec.container_id.clone();
+        let mut messages = Vec::new();
+        let mut disconnected = false;
+        loop {
+            match exec.receiver.try_recv() {
+                Ok(message) => messages.push(message),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    disconnected = true;
+                    break;
+                }
+            }
+        }
+        
+        let mut finished = disconnected;
+        for message in messages {
+            match message {
+                ExecMessage::Output(text) => self.append(&container_id, ConsoleStream::Output, text),
+                ExecMessage::Finished(result) => {
+                    let (stream, text) = match result {
+                        Ok(Some(0)) => (ConsoleStream::Status, String::new()),
+                        Ok(Some(code)) => (ConsoleStream::Status, format!("[退出码 {}]\n", code)),
+                        Ok(None) => (ConsoleStream::Status, "[已中止]\n".to_string()),
+                        Err(e) => (ConsoleStream::Error, format!("{:#}\n", e)),
+                    };
+                    if !text.is_empty() {
+                        self.append(&container_id, stream, text);
+                    }
+                    finished = true;
+                }
+            }
+        }
+        if !finished {
+            return None;
+        }
+        self.exec = None;
+        Some(container_id)
+    }
+    
+    /// 追加输出，超过上限时丢弃最早的内容
+    fn append(&mut self, container_id: &str, stream: ConsoleStream, text: String) {
+        let entries = self.scrollback.entry(container_id.to_string()).or_default();
+        match entries.last_mut() {
+            Some(last) if last.stream == stream && stream == ConsoleStream::Output => last.text.push_str(&text),
+            _ => entries.push(ConsoleEntry { stream, text }),
+        }
+        let mut total: usize = entries.iter().map(|e| e.text.len()).sum();
+        while total > MAX_SCROLLBACK_BYTES && entries.len() > 1 {
+            total -= entries.remove(0).text.len();
+        }
+    }
+}
+
+/// 检查SSH隧道状态的间隔
+const TUNNEL_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+/// SSH隧道断开后重新连接的间隔
+const TUNNEL_RETRY_DELAY: Duration = Duration::from_secs(10);
+
+/// 一个实体的SSH隧道
+struct TunnelEntry {
+    name: String,
+    tunnel: Option<Tunnel>,
+    status: TunnelStatus,
+    /// 断开后下次重新连接的时间
+    retry_at: Option<Instant>,
+}
+
+/// SSH隧道服务，为配置了隧道的中间层与后端维持经跳板机的端口转发，断开后自动重新连接
+///
+/// 已连接的隧道登记为路由，接口请求与TCP探测访问这些实体时改经本机端口
+pub struct TunnelService {
+    state: StateStore,
+    entries: HashMap<String, TunnelEntry>,
+    last_check: Option<Instant>,
+}
+
+impl TunnelService {
+    /// 创建新的SSH隧道服务
+    pub fn new(state: StateStore) -> Self {
+        Self {
+            state,
+            entries: HashMap::new(),
+            last_check: None,
+        }
+    }
+
+    /// 实体的隧道状态，未配置隧道时为空
+    pub fn status(&self, entity_id: &str) -> Option<&TunnelStatus> {
+        self.entries.get(entity_id).map(|e| &e.status)
+    }
+
+    /// 是否有配置了隧道的实体
+    pub fn is_active(&self) -> bool {
+        !self.entries.is_empty()
+    }
+
+    /// 断开并立即重新建立实体的隧道
+    pub fn reconnect(&mut self, entity_id: &str) {
+        if let Some(entry) = self.entries.get_mut(entity_id) {
+            entry.tunnel = None;
+            entry.retry_at = None;
+            entry.status = TunnelStatus::Connecting;
+        }
+        self.last_check = None;
+    }
+
+    /// 按模型中的隧道设置建立或关闭隧道并检查状态，返回连接与断开的日志
+    pub fn tick(&mut self) -> Vec<LogEntry> {
+        if self.last_check.is_some_and(|t| t.elapsed() < TUNNEL_CHECK_INTERVAL) {
+            return Vec::new();
+        }
+        self.last_check = Some(Instant::now());
+        let wanted: Vec<(String, String, SshTunnel, String)> = self.state.read(|state| {
+            let mut wanted = Vec::new();
+            for group in &state.business_groups {
+                for middleware in &group.middlewares {
+                    if let Some(tunnel) = &middleware.tunnel {
+                        wanted.push((middleware.id.clone(), middleware.name.clone(), tunnel.clone(), middleware.api_base_url()));
+                    }
+                }
+                for backend in group.middlewares.iter().flat_map(|m| m.backend_containers.iter()).chain(group.backend_containers.iter()) {
+                    if let Some(tunnel) = &backend.tunnel {
+                        wanted.push((backend.id.clone(), backend.name.clone(), tunnel.clone(), backend.url.clone()));
+                    }
+                }
+            }
+            wanted
+        });
+        self.entries.retain(|id, _| wanted.iter().any(|(wanted_id, ..)| wanted_id == id));
+
+        let now = Instant::now();
+        let mut logs = Vec::new();
+        for (id, name, settings, url) in wanted {
+            let entry = self.entries.entry(id).or_insert_with(|| TunnelEntry {
+                name: name.clone(),
+                tunnel: None,
+                status: TunnelStatus::Connecting,
+                retry_at: None,
+            });
+            entry.name = name;
+            let Some(target) = tunnel::target(&url) else {
+                entry.tunnel = None;
+                entry.status = TunnelStatus::Failed(format!("地址中没有主机或端口: {}", url));
+                continue;
+            };
+            // 隧道设置或实体地址修改后重新建立
+            if entry.tunnel.as_ref().is_some_and(|t| t.settings != settings || t.target != target) {
+                entry.tunnel = None;
+                entry.retry_at = None;
+            }
+            if entry.tunnel.is_none() {
+                if entry.retry_at.is_some_and(|at| at > now) {
+                    continue;
+                }
+                match Tunnel::open(&settings, &target) {
+                    Ok(tunnel) => entry.tunnel = Some(tunnel),
+                    Err(e) => {
+                        let status = TunnelStatus::Failed(format!("{:#}", e));
+                        Self::record(entry, status, &mut logs);
+                        entry.retry_at = Some(now + TUNNEL_RETRY_DELAY);
+                        continue;
+                    }
+                }
+            }
+            let Some(tunnel) = entry.tunnel.as_mut() else {
+                continue;
+            };
+            let status = tunnel.check();
+            if matches!(status, TunnelStatus::Failed(_)) {
+                entry.tunnel = None;
+                entry.retry_at = Some(now + TUNNEL_RETRY_DELAY);
+            }
+            Self::record(entry, status, &mut logs);
+        }
+
+        tunnel::set_routes(
+            self.entries
+                .values()
+                .filter_map(|e| match (&e.tunnel, &e.status) {
+                    (Some(tunnel), TunnelStatus::Connected { local_port }) => Some((tunnel.target.clone(), *local_port)),
+                    _ => None,
+                })
+                .collect(),
+        );
+        logs
+    }
+
+    /// 更新隧道状态，连接成功与断开时记录日志
+    fn record(entry: &mut TunnelEntry, status: TunnelStatus, logs: &mut Vec<LogEntry>) {
+        if status == entry.status {
+            return;
+        }
+        match &status {
+            TunnelStatus::Connected { .. } => logs.push(LogEntry::new(&entry.name, &format!("SSH隧道{}", status.label()))),
+            TunnelStatus::Failed(_) => logs.push(LogEntry::new(
+                &entry.name,
+                &format!("SSH隧道{}，{} 秒后重新连接", status.label(), TUNNEL_RETRY_DELAY.as_secs()),
+            )),
+            TunnelStatus::Connecting => {}
+        }
+        entry.status = status;
+    }
+}
+
+/// 网络配置服务，管理代理、DNS、TLS与超时设置，并为业务组指定所用的网络配置
+pub struct NetworkService {
+    config_manager: ConfigManager,
+    state: StateStore,
+}
+
+impl NetworkService {
+    /// 创建新的网络配置服务
+    pub fn new(config_manager: ConfigManager, state: StateStore) -> Self {
+        Self { config_manager, state }
+    }
+    
+    /// 保存连接池设置，缓存的接口客户端按新设置重建
+    pub fn set_pool_settings(&self, settings: ConnectionPoolSettings) -> Result<()> {
+        self.config_manager.update(|config| {
+            config.connection_pool = settings.clone();
+            Ok(())
+        })?;
+        api::set_pool_settings(settings);
+        Ok(())
+    }
+    
+    /// 获取所有网络配置
+    pub fn get_profiles(&self) -> Vec<NetworkProfile> {
+        self.state.read(|state| state.network_profiles.clone())
+    }
+    
+    /// 添加网络配置
+    pub fn add_profile(&self, profile: NetworkProfile) -> Result<()> {
+        self.state.update(|state| {
+            state.network_profiles.push(profile);
+            Ok(())
+        })
+    }
+    
+    /// 更新网络配置
+    pub fn update_profile(&self, profile: NetworkProfile) -> Result<()> {
+        self.state.update(|state| {
+            let existing = state.network_profiles
+                .iter_mut()
+                .find(|p| p.id == profile.id)
+                .ok_or_else(|| anyhow::anyhow!("网络配置不存在: {}", profile.id))?;
+            *existing = profile.clone();
+            Ok(())
+        })?;
+        api::evict_network_profile(&profile.id);
+        Ok(())
+    }
+    
+    /// 删除网络配置，仍有业务组使用时拒绝删除
+    pub fn delete_profile(&self, profile_id: &str) -> Result<()> {
+        self.state.update(|state| {
+            if state.business_groups.iter().any(|g| g.network_profile_id.as_deref() == Some(profile_id)) {
+                anyhow::bail!("仍有业务组使用该网络配置，请先修改这些业务组的网络配置");
+            }
+            state.network_profiles.retain(|p| p.id != profile_id);
+            Ok(())
+        })?;
+        api::evict_network_profile(profile_id);
+        Ok(())
+    }
+    
+    /// 为业务组指定网络配置，为空时使用默认网络设置
+    pub fn assign(&self, group_id: &GroupId, profile_id: Option<String>) -> Result<()> {
+        self.state.update(|state| {
+            if let Some(id) = &profile_id
+                && !state.network_profiles.iter().any(|p| &p.id == id)
+            {
+                anyhow::bail!("网络配置不存在: {}", id);
+            }
+            let group = state.group_mut(group_id)?;
+            group.network_profile_id = profile_id;
+            group.updated_at = Utc::now();
+            Ok(())
+        })
+    }
+}
+
+/// 镜像拉取成功后需要启动的容器
+#[derive(Debug, Clone)]
+pub enum PendingStart {
+    Middleware { group_id: String, middleware_id: String },
+    Backend { group_id: String, middleware_id: Option<String>, backend_id: String },
+}
+
+/// 拉取线程发回的消息
+enum PullEvent {
+    Progress(PullProgress),
+    Done(Result<()>),
+}
+
+/// 进行中或刚结束的镜像拉取
+pub struct ImagePull {
+    pub image: String,
+    /// 目标主机名称
+    pub host_name: String,
+    pub progress: PullProgress,
+    /// 结束后的错误信息，成功结束的拉取不会保留
+    pub error: Option<String>,
+    /// 拉取成功后需要启动的容器
+    pub then: Option<PendingStart>,
+    cancel: Arc<AtomicBool>,
+    receiver: Option<Receiver<PullEvent>>,
+}
+
+impl ImagePull {
+    /// 是否仍在拉取
+    pub fn is_running(&self) -> bool {
+        self.receiver.is_some()
+    }
+}
+
+/// 镜像服务，在后台拉取容器缺少的镜像并汇报进度
+pub struct ImageService {
+    state: StateStore,
+    pull: Option<ImagePull>,
+}
+
+impl ImageService {
+    /// 创建新的镜像服务
+    pub fn new(state: StateStore) -> Self {
+        Self {
+            state,
+            pull: None,
+        }
+    }
+    
+    /// 当前的拉取
+    pub fn current(&self) -> Option<&ImagePull> {
+        self.pull.as_ref()
+    }
+    
+    /// 是否正在拉取
+    pub fn is_pulling(&self) -> bool {
+        self.pull.as_ref().is_some_and(ImagePull::is_running)
+    }
+    
+    /// 在后台拉取镜像，成功后由调用方启动 `then` 指定的容器
+    pub fn pull(&mut self, host_id: &Option<String>, image: &str, then: Option<PendingStart>) -> Result<()> {
+        if self.is_pulling() {
+            anyhow::bail!("已有镜像正在拉取");
+        }
+        let host = docker_host(&self.state, host_id)?;
+        let host_name = host.as_ref().map(|h| h.name.clone()).unwrap_or_else(|| "本机".to_string());
+        let cancel = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = mpsc::channel();
+        let flag = cancel.clone();
+        let target = image.to_string();
+        std::thread::spawn(move || {
+            let result = docker::pull(host.as_ref(), &target, &flag, |progress| {
+                let _ = sender.send(PullEvent::Progress(progress.clone()));
+            });
+            let _ = sender.send(PullEvent::Done(result));
+        });
+        self.pull = Some(ImagePull {
+            image: image.to_string(),
+            host_name,
+            progress: PullProgress::default(),
+            error: None,
+            then,
+            cancel,
+            receiver: Some(receiver),
+        });
+        Ok(())
+    }
+    
+    /// 取消进行中的拉取
+    pub fn cancel(&self) {
+        if let Some(pull) = &self.pull {
+            pull.cancel.store(true, Ordering::Relaxed);
+        }
+    }
+    
+    /// 关闭已结束的拉取
+    pub fn dismiss(&mut self) {
+        if !self.is_pulling() {
+            self.pull = None;
+        }
+    }
+    
+    /// 收取拉取进度；成功结束时清除拉取并返回镜像与待启动的容器
+    pub fn poll(&mut self) -> Option<(String, Option<PendingStart>)> {
+        let pull = self.pull.as_mut()?;
+        let receiver = pull.receiver.as_ref()?;
+        let mut result = None;
+        loop {
+            match receiver.try_recv() {
+                Ok(PullEvent::Progress(progress)) => pull.progress = progress,
+                Ok(PullEvent::Done(done)) => {
+                    result = Some(done);
+                    break;
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    result = Some(Err(anyhow::anyhow!("拉取线程异常退出")));
+                    break;
+                }
+            }
+        }
+        match result? {
+            Ok(()) => {
+                let pull = self.pull.take()?;
+                Some((pull.image, pull.then))
+            }
+            Err(e) => {
+                pull.receiver = None;
+                pull.error = Some(format!("{:#}", e));
+                None
+            }
+        }
+    }
+}
+
+/// 权重调整记录保留数量
+const MAX_WEIGHT_HISTORY: usize = 200;
+
+/// 自适应权重服务，按延迟与错误率定期重算负载均衡后端的权重并推送到中间层
+pub struct WeightService {
+    config_manager: ConfigManager,
+    state: StateStore,
+    tasks: TaskPool<(WeightAdjustment, Option<MiddlewareContainer>)>,
+    /// 正在调整的中间层
+    in_flight: HashSet<String>,
+    last_adjusted: HashMap<String, Instant>,
+}
+
+impl WeightService {
+    /// 创建新的自适应权重服务
+    pub fn new(config_manager: ConfigManager, state: StateStore) -> Self {
+        Self {
+            config_manager,
+            state,
+            tasks: TaskPool::new(),
+            in_flight: HashSet::new(),
+            last_adjusted: HashMap::new(),
+        }
+    }
+    
+    /// 是否有正在进行的调整
+    pub fn is_busy(&self) -> bool {
+        !self.in_flight.is_empty()
+    }
+    
+    /// 中间层是否正在调整
+    pub fn is_adjusting(&self, middleware_id: &MiddlewareId) -> bool {
+        self.in_flight.contains(middleware_id.as_str())
+    }
+    
+    /// 获取调整记录
+    pub fn get_history(&self) -> Result<Vec<WeightAdjustment>> {
+        Ok(self.config_manager.load_config()?.weight_history)
+    }
+    
+    /// 立即对中间层发起一次调整
+    pub fn adjust_now(&mut self, middleware: &MiddlewareContainer, trace_id: &str) {
+        self.spawn(middleware.clone(), Some(trace_id.to_string()));
+    }
+    
+    /// 在后台调整权重，手动触发时带有追踪ID
+    fn spawn(&mut self, middleware: MiddlewareContainer, trace_id: Option<String>) {
+        if !self.in_flight.insert(middleware.id.clone()) {
+            return;
+        }
+        self.last_adjusted.insert(middleware.id.clone(), Instant::now());
+        let network = self.state.read(|s| s.network_for(&middleware.id));
+        let label = format!("调整 {} 的权重", middleware.name);
+        self.tasks.spawn(&label, async move {
+            weights::adjust(&middleware, network.as_ref(), trace_id.as_deref()).await
+        });
+    }
+    
+    /// 手动设置后端权重，固定后不再参与自适应调整
+    pub fn set_backend_weight(&self, middleware_id: &MiddlewareId, backend_id: &BackendId, weight: u32, pinned: bool) -> Result<()> {
+        self.state.update(|state| {
+            let middleware = state.find_middleware_mut(middleware_id)
+                .ok_or_else(|| anyhow::anyhow!("中间层容器不存在: {}", middleware_id))?;
+            let backend = middleware.backend_containers
+                .iter_mut()
+                .find(|b| b.id == backend_id)
+                .ok_or_else(|| anyhow::anyhow!("后端容器不存在: {}", backend_id))?;
+            backend.weight = weight;
+            backend.weight_pinned = pinned;
+            middleware.config.crud_api.instances = middleware.crud_instances();
+            Ok(())
+        })
+    }
+    
+    /// 收取调整结果，推送成功的写回模型，所有结果记入历史
+    pub fn poll(&mut self) -> Vec<WeightAdjustment> {
+        let results = self.tasks.poll();
+        if !self.tasks.is_busy() {
+            self.in_flight.clear();
+        }
+        if results.is_empty() {
+            return Vec::new();
+        }
+        
+        let mut adjustments = Vec::new();
+        for (_, (adjustment, updated)) in results {
+            self.in_flight.remove(&adjustment.middleware_id);
+            if let Some(updated) = updated {
+                let result = self.state.update(|state| {
+                    // 调整期间可能已被修改，只写回权重与实例列表
+                    if let Some(middleware) = state.find_middleware_mut(&MiddlewareId::from(&updated.id)) {
+                        for backend in &mut middleware.backend_containers {
+                            if let Some(new) = updated.backend_containers.iter().find(|b| b.id == backend.id)
+                                && !backend.weight_pinned
+                            {
+                                backend.weight = new.weight;
+                            }
+                        }
+                        middleware.config.crud_api.instances = middleware.crud_instances();
+                    }
+                    Ok(())
+                });
+                if let Err(e) = result {
+                    tracing::warn!("写回权重失败: {:#}", e);
+                }
+            }
+            adjustments.push(adjustment);
+        }
+        
+        let result = self.config_manager.update(|config| {
+            config.weight_history.extend(adjustments.iter().cloned());
+            let excess = config.weight_history.len().saturating_sub(MAX_WEIGHT_HISTORY);
+            config.weight_history.drain(..excess);
+            Ok(())
+        });
+        if let Err(e) = result {
+            tracing::warn!("保存权重调整记录失败: {:#}", e);
+        }
+        adjustments
+    }
+    
+    /// 为到期的负载均衡中间层发起调整
+    pub fn tick(&mut self) {
+        let due: Vec<MiddlewareContainer> = self.state.read(|state| {
+            state.business_groups
+                .iter()
+                .flat_map(|g| g.middlewares.iter())
+                .filter(|m| {
+                    m.adaptive_weights.enabled
+                        && m.config.crud_api.strategy == SchedulerStrategy::LoadBalance
+                        && m.status == ContainerStatus::Running
+                        && !m.backend_containers.is_empty()
+                })
+                .filter(|m| {
+                    let interval = Duration::from_secs(m.adaptive_weights.interval_secs.max(1));
+                    self.last_adjusted.get(&m.id).is_none_or(|t| t.elapsed() >= interval)
+                })
+                .cloned()
+                .collect()
+        });
+        for middleware in due {
+            self.spawn(middleware, None);
+        }
+    }
+}
+
+/// 迁移步骤执行结果
+struct MigrationOutcome {
+    migration: Migration,
+    rollback: bool,
+    result: Result<MigrationStep>,
+}
+
+/// 中间层迁移服务，逐步在后台执行迁移或回滚
+pub struct MigrationService {
+    state: StateStore,
+    /// 当前迁移，执行中时为发起前的快照
+    migration: Option<Migration>,
+    receiver: Option<Receiver<MigrationOutcome>>,
+}
+
+impl MigrationService {
+    /// 创建新的迁移服务
+    pub fn new(state: StateStore) -> Self {
+        Self {
+            state,
+            migration: None,
+            receiver: None,
+        }
+    }
+    
+    /// 当前迁移
+    pub fn current(&self) -> Option<&Migration> {
+        self.migration.as_ref()
+    }
+    
+    /// 是否有步骤正在执行
+    pub fn is_running(&self) -> bool {
+        self.receiver.is_some()
+    }
+    
+    /// 开始新的迁移
+    pub fn begin(&mut self, migration: Migration) -> Result<()> {
+        if migration.source_id == migration.target_id {
+            anyhow::bail!("源中间层与目标中间层不能相同");
+        }
+        if self.is_running() {
+            anyhow::bail!("迁移步骤正在执行");
+        }
+        self.migration = Some(migration);
+        Ok(())
+    }
+    
+    /// 结束当前迁移
+    pub fn close(&mut self) {
+        if !self.is_running() {
+            self.migration = None;
+        }
+    }
+    
+    /// 在后台执行下一步
+    pub fn run_next(&mut self) {
+        self.spawn(false);
+    }
+    
+    /// 在后台回滚最近完成的一步
+    pub fn rollback_last(&mut self) {
+        self.spawn(true);
+    }
+    
+    fn spawn(&mut self, rollback: bool) {
+        if self.is_running() {
+            return;
+        }
+        let Some(mut migration) = self.migration.clone() else {
+            return;
+        };
+        let state = self.state.clone();
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            let result = if rollback {
+                migration.rollback_last(&state)
+            } else {
+                migration.run_next(&state)
+            };
+            let _ = sender.send(MigrationOutcome { migration, rollback, result });
+        });
+        self.receiver = Some(receiver);
+    }
+    
+    /// 收取步骤执行结果，返回需要记录的日志
+    pub fn poll(&mut self) -> Option<LogEntry> {
+        let outcome = match self.receiver.as_ref()?.try_recv() {
+            Ok(outcome) => outcome,
+            Err(TryRecvError::Empty) => return None,
+            Err(TryRecvError::Disconnected) => {
+                self.receiver = None;
+                return Some(LogEntry::new("迁移", "迁移线程异常退出"));
+            }
+        };
+        self.receiver = None;
+        
+        let action = if outcome.rollback { "回滚" } else { "执行" };
+        let message = match &outcome.result {
+            Ok(step) => format!("{}步骤成功: {}", action, step.label()),
+            Err(e) => format!("{}步骤失败: {:#}", action, e),
+        };
+        self.migration = Some(outcome.migration);
+        Some(LogEntry::new("迁移", &message))
+    }
+}
+
+/// 保留的任务历史条数
+const MAX_JOB_HISTORY: usize = 200;
+/// 同时运行的任务数量上限
+const MAX_RUNNING_JOBS: usize = 2;
+
+/// 后台任务执行结果
+struct JobOutcome {
+    job_id: String,
+    result: Result<String>,
+}
+
+/// 后台任务服务，负责排队、执行、取消与重试
+pub struct JobService {
+    config_manager: ConfigManager,
+    /// 任务执行时修改的共享状态
+    state: StateStore,
+    /// 排队中与运行中的任务
+    jobs: Vec<JobRecord>,
+    /// 运行中任务的取消标记
+    cancel_flags: HashMap<String, Arc<AtomicBool>>,
+    /// 等待重试的任务及其可执行时间
+    retry_at: HashMap<String, Instant>,
+    sender: Sender<JobOutcome>,
+    receiver: Receiver<JobOutcome>,
+    /// 运行中任务上报的检查点
+    checkpoint_sender: Sender<(String, JobCheckpoint)>,
+    checkpoint_receiver: Receiver<(String, JobCheckpoint)>,
+}
+
+impl JobService {
+    /// 创建新的任务服务，恢复上次退出时未结束的任务
+    pub fn new(config_manager: ConfigManager, state: StateStore) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let (checkpoint_sender, checkpoint_receiver) = mpsc::channel();
+        let mut jobs = config_manager.load_config().map(|c| c.active_jobs).unwrap_or_default();
+        for job in &mut jobs {
+            // 中断的执行不计入重试次数
+            if job.status == JobStatus::Running {
+                job.status = JobStatus::Queued;
+                job.attempts = job.attempts.saturating_sub(1);
+            }
+        }
+        if !jobs.is_empty() {
+            tracing::info!("恢复 {} 个未结束的任务", jobs.len());
+        }
+        Self {
+            config_manager,
+            state,
+            jobs,
+            cancel_flags: HashMap::new(),
+            retry_at: HashMap::new(),
+            sender,
+            receiver,
+            checkpoint_sender,
+            checkpoint_receiver,
+        }
+    }
+    
+    /// 排队中与运行中的任务
+    pub fn active_jobs(&self) -> &[JobRecord] {
+        &self.jobs
+    }
+    
+    /// 是否有未结束的任务
+    pub fn has_active_jobs(&self) -> bool {
+        !self.jobs.is_empty()
+    }
+    
+    /// 获取已结束的任务记录
+    pub fn get_history(&self) -> Result<Vec<JobRecord>> {
+        let config = self.config_manager.load_config()?;
+        Ok(config.job_history)
+    }
+    
+    /// 清空任务记录
+    pub fn clear_history(&self) -> Result<()> {
+        self.config_manager.update(|config| {
+            config.job_history.clear();
+            Ok(())
+        })
+    }
+    
+    /// 获取重试策略
+    pub fn get_retry_policy(&self) -> Result<RetryPolicy> {
+        let config = self.config_manager.load_config()?;
+        Ok(config.job_retry_policy)
+    }
+    
+    /// 保存重试策略
+    pub fn set_retry_policy(&self, policy: RetryPolicy) -> Result<()> {
+        self.config_manager.update(|config| {
+            config.job_retry_policy = policy;
+            Ok(())
+        })
+    }
+    
+    /// 提交任务，按当前重试策略排队，任务发出的请求携带给定的追踪ID；
+    /// 给定 `actor` 时任务以该操作人的业务组权限执行，否则使用界面当前操作人的权限
+    pub fn enqueue(&mut self, kind: JobKind, trace_id: &str, actor: Option<&str>) -> Result<()> {
+        let policy = self.get_retry_policy()?;
+        let mut job = JobRecord::new(kind, policy);
+        job.trace_id = Some(trace_id.to_string());
+        job.actor = actor.map(str::to_string);
+        self.jobs.push(job);
+        self.schedule();
+        self.save_active()
+    }
+    
+    /// 取消任务：排队中的任务立即结束，运行中的任务等待执行线程退出
+    pub fn cancel(&mut self, job_id: &str) -> Result<()> {
+        if let Some(flag) = self.cancel_flags.get(job_id) {
+            flag.store(true, Ordering::Relaxed);
+            return Ok(());
+        }
+        
+        match self.jobs.iter().position(|j| j.id == job_id) {
+            Some(index) => {
+                let mut job = self.jobs.remove(index);
+                self.retry_at.remove(job_id);
+                job.status = JobStatus::Cancelled;
+                job.message = "任务已取消".to_string();
+                self.finish(job)
+            }
+            None => anyhow::bail!("任务不存在: {}", job_id),
+        }
+    }
+    
+    /// 收取执行结果与检查点并调度任务，返回本次结束的任务
+    pub fn poll(&mut self) -> Vec<JobRecord> {
+        let mut finished = Vec::new();
+        let mut changed = false;
+        
+        while let Ok((job_id, checkpoint)) = self.checkpoint_receiver.try_recv() {
+            if let Some(job) = self.jobs.iter_mut().find(|j| j.id == job_id) {
+                job.checkpoint = Some(checkpoint);
+                changed = true;
+            }
+        }
+        
+        while let Ok(outcome) = self.receiver.try_recv() {
+            changed = true;
+            let cancelled = self.cancel_flags
+                .remove(&outcome.job_id)
+                .is_some_and(|flag| flag.load(Ordering::Relaxed));
+            let Some(index) = self.jobs.iter().position(|j| j.id == outcome.job_id) else {
+                continue;
+            };
+            let job = &mut self.jobs[index];
+            
+            match outcome.result {
+                Ok(message) => {
+                    job.status = JobStatus::Succeeded;
+                    job.message = message;
+                }
+                Err(e) => {
+                    job.message = e.to_string();
+                    if cancelled {
+                        job.status = JobStatus::Cancelled;
+                    } else if job.attempts < job.policy.max_attempts {
+                        job.status = JobStatus::Queued;
+                        let backoff = Duration::from_secs(job.policy.backoff_secs * job.attempts as u64);
+                        self.retry_at.insert(job.id.clone(), Instant::now() + backoff);
+                        continue;
+                    } else {
+                        job.status = JobStatus::Failed;
+                    }
+                }
+            }
+            job.finished_at = Some(Utc::now());
+            finished.push(self.jobs.remove(index));
+        }
+        
+        changed |= self.schedule();
+        for job in &finished {
+            if let Err(e) = self.finish(job.clone()) {
+                tracing::error!("保存任务记录失败: {}", e);
+            }
+        }
+        if changed
+            && finished.is_empty()
+            && let Err(e) = self.save_active()
+        {
+            tracing::error!("保存任务进度失败: {}", e);
+        }
+        finished
+    }
+    
+    /// 启动可执行的排队任务，返回是否启动了任务
+    fn schedule(&mut self) -> bool {
+        let now = Instant::now();
+        let mut started = false;
+        for job in &mut self.jobs {
+            if self.cancel_flags.len() >= MAX_RUNNING_JOBS {
+                break;
+            }
+            if job.status != JobStatus::Queued || self.retry_at.get(&job.id).is_some_and(|t| *t > now) {
+                continue;
+            }
+            
+            self.retry_at.remove(&job.id);
+            job.status = JobStatus::Running;
+            job.attempts += 1;
+            started = true;
+            
+            let cancel = Arc::new(AtomicBool::new(false));
+            self.cancel_flags.insert(job.id.clone(), cancel.clone());
+            let job_id = job.id.clone();
+            let kind = job.kind.clone();
+            let trace_id = job.trace_id.clone();
+            let state = self.state.clone();
+            let sender = self.sender.clone();
+            let actor = job.actor.clone();
+            let config_manager = self.config_manager.clone();
+            let checkpoint = jobs::Checkpointer::new(job.id.clone(), job.checkpoint.clone(), self.checkpoint_sender.clone());
+            std::thread::spawn(move || {
+                let result = Self::state_for(&state, &config_manager, actor.as_deref())
+                    .and_then(|state| jobs::run_job(&kind, &cancel, &state, &checkpoint, trace_id.as_deref()));
+                let _ = sender.send(JobOutcome { job_id, result });
+            });
+        }
+        started
+    }
+    
+    /// 任务执行时使用的状态：指定了操作人时按其当前的角色分配确定业务组权限
+    fn state_for(state: &StateStore, config_manager: &ConfigManager, actor: Option<&str>) -> Result<StateStore> {
+        let Some(actor) = actor else {
+            return Ok(state.clone());
+        };
+        let config = config_manager.load_config()?;
+        Ok(state.with_access(GroupAccess::for_actor(&config.ui_roles, config.default_ui_profile, actor)))
+    }
+    
+    /// 保存未结束的任务及其检查点，重启后据此恢复
+    fn save_active(&self) -> Result<()> {
+        self.config_manager.update(|config| {
+            config.active_jobs = self.jobs.clone();
+            Ok(())
+        })
+    }
+    
+    /// 记录已结束的任务，同时保存剩余的未结束任务
+    fn finish(&self, mut job: JobRecord) -> Result<()> {
+        job.finished_at.get_or_insert_with(Utc::now);
+        self.config_manager.update(|config| {
+            config.active_jobs = self.jobs.clone();
+            config.job_history.push(job);
+            if config.job_history.len() > MAX_JOB_HISTORY {
+                let excess = config.job_history.len() - MAX_JOB_HISTORY;
+                config.job_history.drain(..excess);
+            }
+            Ok(())
+        })
+    }
+}
+
+/// 每个中间层检索的最近日志行数
+const TRACE_LOG_LINES: u32 = 1000;
+
+/// 中间层日志中携带追踪ID的一行
+#[derive(Debug, Clone)]
+pub struct TraceLogLine {
+    pub middleware_name: String,
+    pub line: String,
+}
+
+/// 一次按追踪ID检索中间层日志的结果
+#[derive(Debug, Clone)]
+pub struct TraceSearch {
+    pub trace_id: String,
+    pub lines: Vec<TraceLogLine>,
+    /// 日志获取失败的中间层及原因
+    pub errors: Vec<(String, String)>,
+}
+
+/// 链路追踪服务，在各中间层最近的日志中检索追踪ID
+pub struct TraceService {
+    state: StateStore,
+    tasks: TaskPool<TraceSearch>,
+}
+
+impl TraceService {
+    /// 创建新的链路追踪服务
+    pub fn new(state: StateStore) -> Self {
+        Self {
+            state,
+            tasks: TaskPool::new(),
+        }
+    }
+    
+    /// 是否正在检索
+    pub fn is_searching(&self) -> bool {
+        self.tasks.is_busy()
     }
     
-    /// 获取API客户端
-    pub fn get_api_client(&self) -> Result<&ApiClient> {
-        self.api_client.as_ref().context("未连接到API服务器")
+    /// 在后台并发拉取所有运行中中间层的日志并筛选含追踪ID的行
+    pub fn search(&mut self, trace_id: &str) {
+        if self.is_searching() {
+            return;
+        }
+        let targets: Vec<(MiddlewareContainer, Option<NetworkProfile>)> = self.state.read(|s| {
+            s.business_groups
+                .iter()
+                .flat_map(|g| g.middlewares.iter())
+                .filter(|m| m.status == ContainerStatus::Running)
+                .map(|m| (m.clone(), s.network_for(&m.id)))
+                .collect()
+        });
+        let trace_id = trace_id.trim().to_string();
+        let fetches = targets.into_iter().map(|(middleware, network)| async move {
+            let config = ApiClientConfig {
+                base_url: middleware.api_base_url(),
+                timeout: middleware.config.crud_api.timeout,
+                network,
+                trace_id: None,
+            };
+            let logs = match ApiClient::new(config) {
+                Ok(client) => client.get_logs(TRACE_LOG_LINES).await,
+                Err(e) => Err(e),
+            };
+            (middleware, logs)
+        });
+        let label = format!("检索追踪ID {}", trace_id);
+        self.tasks.spawn(&label, async move {
+            let mut search = TraceSearch { trace_id, lines: Vec::new(), errors: Vec::new() };
+            for (middleware, logs) in futures_util::future::join_all(fetches).await {
+                match logs {
+                    Ok(logs) => search.lines.extend(
+                        logs.into_iter()
+                            .filter(|line| line.contains(&search.trace_id))
+                            .map(|line| TraceLogLine {
+                                middleware_name: middleware.name.clone(),
+                                line,
+                            }),
+                    ),
+                    Err(e) => search.errors.push((middleware.name, format!("{:#}", e))),
+                }
+            }
+            search
+        });
+    }
+    
+    /// 收取检索结果
+    pub fn poll(&mut self) -> Option<TraceSearch> {
+        self.tasks.poll().pop().map(|(_, search)| search)
+    }
+}
+
+/// 链路导出服务，管理管理器自身span的OTLP导出设置
+pub struct TelemetryService {
+    config_manager: ConfigManager,
+}
+
+impl TelemetryService {
+    /// 创建新的链路导出服务
+    pub fn new(config_manager: ConfigManager) -> Self {
+        Self { config_manager }
+    }
+    
+    /// 保存导出设置并立即生效
+    pub fn set_settings(&self, settings: OtlpSettings) -> Result<()> {
+        if settings.enabled && !settings.endpoint.trim().starts_with("http") {
+            anyhow::bail!("收集器地址应以 http:// 或 https:// 开头: {}", settings.endpoint);
+        }
+        self.config_manager.update(|config| {
+            config.otlp = settings.clone();
+            Ok(())
+        })?;
+        telemetry::configure(settings);
+        Ok(())
+    }
+}
+
+/// 中间层配置模板服务，内置模板与用户保存的模板一并提供
+pub struct ConfigPresetService {
+    config_manager: ConfigManager,
+}
+
+impl ConfigPresetService {
+    /// 创建新的配置模板服务
+    pub fn new(config_manager: ConfigManager) -> Self {
+        Self { config_manager }
+    }
+    
+    /// 获取所有模板，内置模板在前
+    pub fn get_presets(&self) -> Result<Vec<ConfigPreset>> {
+        let mut presets = ConfigPreset::builtin();
+        presets.extend(self.config_manager.load_config()?.config_presets);
+        Ok(presets)
+    }
+    
+    /// 保存用户模板，同名模板被覆盖
+    pub fn save_preset(&self, preset: ConfigPreset) -> Result<()> {
+        if preset.name.trim().is_empty() {
+            anyhow::bail!("模板名称不能为空");
+        }
+        if ConfigPreset::builtin().iter().any(|p| p.name == preset.name) {
+            anyhow::bail!("不能覆盖内置模板: {}", preset.name);
+        }
+        self.config_manager.update(|config| {
+            config.config_presets.retain(|p| p.name != preset.name);
+            config.config_presets.push(preset);
+            Ok(())
+        })
+    }
+    
+    /// 删除用户模板
+    pub fn delete_preset(&self, preset_id: &str) -> Result<()> {
+        self.config_manager.update(|config| {
+            let before = config.config_presets.len();
+            config.config_presets.retain(|p| p.id != preset_id);
+            if config.config_presets.len() == before {
+                anyhow::bail!("配置模板不存在: {}", preset_id);
+            }
+            Ok(())
+        })
     }
 }
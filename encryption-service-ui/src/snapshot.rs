@@ -0,0 +1,149 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+
+use crate::models::{BusinessGroup, ContainerSpec};
+
+/// 快照格式版本，格式不兼容时递增
+pub const SNAPSHOT_VERSION: u32 = 1;
+/// 归档中的清单文件
+const MANIFEST_FILE: &str = "snapshot.json";
+/// 归档中的业务组文件，包含容器定义与中间层配置
+const GROUP_FILE: &str = "group.json";
+/// 归档中存放卷数据的目录，每个卷一个 `名称.tar`
+const VOLUMES_DIR: &str = "volumes/";
+
+/// 快照中的命名卷
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SnapshotVolume {
+    pub name: String,
+    /// 读写卷时临时容器使用的镜像，取第一个挂载该卷的容器的镜像
+    pub image: String,
+    /// 创建快照时卷所在的Docker主机，本机为空
+    #[serde(default)]
+    pub host_id: Option<String>,
+}
+
+/// 快照清单
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    pub version: u32,
+    pub created_at: DateTime<Utc>,
+    pub group_name: String,
+    /// 已归档数据的命名卷，未包含卷数据时为空
+    pub volumes: Vec<SnapshotVolume>,
+}
+
+/// 读取的快照
+pub struct Snapshot {
+    pub manifest: SnapshotManifest,
+    pub group: BusinessGroup,
+    /// 卷名称到卷数据归档
+    pub volumes: HashMap<String, Vec<u8>>,
+}
+
+/// 挂载来源是否为命名卷：宿主机路径以 `/`、`.`、`~` 开头或包含路径分隔符
+fn is_named_volume(source: &str) -> bool {
+    !source.is_empty()
+        && !source.starts_with(['/', '.', '~'])
+        && !source.contains(['/', '\\'])
+}
+
+/// 业务组中由Docker管理的容器挂载的命名卷，同名卷只列出一次
+pub fn named_volumes(group: &BusinessGroup) -> Vec<SnapshotVolume> {
+    let specs = group.middlewares
+        .iter()
+        .filter(|m| !m.docker_run_params.trim().is_empty() && m.kubernetes.is_none())
+        .map(|m| (m.container_spec(), &m.docker_host_id))
+        .chain(
+            group.middlewares
+                .iter()
+                .flat_map(|m| m.backend_containers.iter())
+                .chain(group.backend_containers.iter())
+                .filter(|b| !b.docker_run_params.trim().is_empty() && b.kubernetes.is_none())
+                .map(|b| (b.container_spec(), &b.docker_host_id)),
+        );
+    let mut volumes: Vec<SnapshotVolume> = Vec::new();
+    for (spec, host_id) in specs {
+        let Ok(spec) = spec else {
+            continue;
+        };
+        for mount in spec.volumes.iter().filter(|v| is_named_volume(&v.source)) {
+            if !volumes.iter().any(|v| v.name == mount.source) {
+                volumes.push(SnapshotVolume {
+                    name: mount.source.clone(),
+                    image: spec.image.clone(),
+                    host_id: host_id.clone(),
+                });
+            }
+        }
+    }
+    volumes
+}
+
+/// 容器定义中显式指定的容器名称
+pub fn explicit_container_name(params: &str) -> Option<String> {
+    ContainerSpec::parse(params).ok().map(|spec| spec.name).filter(|name| !name.is_empty())
+}
+
+fn append(builder: &mut tar::Builder<GzEncoder<File>>, path: &str, data: &[u8]) -> Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(Utc::now().timestamp().max(0) as u64);
+    header.set_cksum();
+    builder.append_data(&mut header, path, data).with_context(|| format!("无法写入 {}", path))
+}
+
+/// 写入快照文件（gzip压缩的tar归档）：清单、业务组与各卷的数据归档
+pub fn write(path: &str, manifest: &SnapshotManifest, group: &BusinessGroup, volumes: &[(String, Vec<u8>)]) -> Result<()> {
+    let file = File::create(path).with_context(|| format!("无法创建快照文件 {}", path))?;
+    let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+    append(&mut builder, MANIFEST_FILE, &serde_json::to_vec_pretty(manifest)?)?;
+    append(&mut builder, GROUP_FILE, &serde_json::to_vec_pretty(group)?)?;
+    for (name, archive) in volumes {
+        append(&mut builder, &format!("{}{}.tar", VOLUMES_DIR, name), archive)?;
+    }
+    builder
+        .into_inner()
+        .and_then(|encoder| encoder.finish())
+        .with_context(|| format!("无法写入快照文件 {}", path))?;
+    Ok(())
+}
+
+/// 读取快照文件，拒绝更高版本的快照
+pub fn read(path: &str) -> Result<Snapshot> {
+    let file = File::open(path).with_context(|| format!("无法打开快照文件 {}", path))?;
+    let mut archive = tar::Archive::new(GzDecoder::new(file));
+    let mut manifest = None;
+    let mut group = None;
+    let mut volumes = HashMap::new();
+    for entry in archive.entries().context("快照文件格式错误")? {
+        let mut entry = entry.context("快照文件格式错误")?;
+        let entry_path = entry.path()?.to_string_lossy().into_owned();
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data).with_context(|| format!("无法读取快照中的 {}", entry_path))?;
+        if entry_path == MANIFEST_FILE {
+            manifest = Some(serde_json::from_slice::<SnapshotManifest>(&data).context("快照清单格式错误")?);
+        } else if entry_path == GROUP_FILE {
+            group = Some(serde_json::from_slice::<BusinessGroup>(&data).context("快照中的业务组格式错误")?);
+        } else if let Some(name) = entry_path.strip_prefix(VOLUMES_DIR).and_then(|p| p.strip_suffix(".tar")) {
+            volumes.insert(name.to_string(), data);
+        }
+    }
+    let manifest = manifest.ok_or_else(|| anyhow::anyhow!("快照中缺少 {}", MANIFEST_FILE))?;
+    if manifest.version > SNAPSHOT_VERSION {
+        anyhow::bail!("快照版本 {} 高于当前支持的版本 {}，请升级本程序", manifest.version, SNAPSHOT_VERSION);
+    }
+    let group = group.ok_or_else(|| anyhow::anyhow!("快照中缺少 {}", GROUP_FILE))?;
+    if let Some(volume) = manifest.volumes.iter().find(|v| !volumes.contains_key(&v.name)) {
+        anyhow::bail!("快照中缺少卷 {} 的数据", volume.name);
+    }
+    Ok(Snapshot { manifest, group, volumes })
+}
@@ -0,0 +1,743 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::thread::JoinHandle;
+use std::time::{Duration, SystemTime};
+
+use crate::config::{Config, ConfigManager};
+use crate::diagnostics::{self, Metric};
+use crate::events::{self, EventBus, ModelEvent};
+use crate::models::{Alert, AlertPolicy, AppState, AuditChainAnchor, AuditEntry, BusinessGroups, GroupAccess, GroupId, StatusTransition};
+
+/// 各服务共享的应用状态
+///
+/// 所有修改都在写锁内先作用于副本，成功后替换内存状态并标记为未写入，
+/// 再将前后差异作为模型事件发布到事件总线，因此后台线程与界面线程
+/// 可以安全地并发读写，读取与修改都不访问配置文件。副本中的业务组写时复制，
+/// 只有被修改的业务组会被复制、比较并更新实体ID索引；状态整体替换时重建索引，
+/// 按ID查找业务组、中间层与后端无需逐层遍历。未写入的修改由
+/// [`StateStore::flush`] 显式持久化，由 [`AutoSaver`] 按间隔在后台调用，退出时再写入一次。
+///
+/// 多个实例共用同一配置文件时，依靠文件修改时间与配置修订号发现他人的修改：
+/// 没有未写入的修改且文件在上次读写后变化时，先合并磁盘上的最新状态再应用本次修改。
+/// 修订号之间是否有他人的写入按本实例写入过的修订号判断，见 [`ConfigManager::peer_modified`]。
+///
+/// 审计日志、告警与状态历史同样只保存在内存中，经 [`StateStore::update_records`] 修改，
+/// 与模型的修改一起由 [`StateStore::flush`] 批量写入，不在界面线程上逐条读写配置文件。
+///
+/// 修改创建、更新或删除了当前操作人无权管理的业务组中的实体时整体拒绝，
+/// 因此业务组权限对所有经状态修改的服务方法生效。运行状态与健康状态由监控写入，
+/// 不在此检查，启停等直接操作容器的方法先调用 [`StateStore::authorize`]。
+#[derive(Clone)]
+pub struct StateStore {
+    state: Arc<RwLock<Synced>>,
+    config_manager: ConfigManager,
+    bus: EventBus,
+    /// 当前操作人的业务组权限
+    access: Arc<RwLock<GroupAccess>>,
+}
+
+/// 内存状态及其对应的配置修订号
+struct Synced {
+    app_state: AppState,
+    revision: u64,
+    /// 上次读写时配置文件的修改时间
+    modified: Option<SystemTime>,
+    /// 是否有尚未写入配置文件的修改
+    dirty: bool,
+    /// 上次写入后本实例修改过的部分，写入时只有它们以内存为准
+    touched: Touched,
+    /// 每次修改递增，写入期间状态又被修改时据此保留未写入标记
+    generation: u64,
+    records: Records,
+    /// 上次写入后对记录的修改，写入时按顺序重放到磁盘上的记录
+    record_changes: Vec<RecordChange>,
+}
+
+impl Synced {
+    /// 没有未写入的记录修改时改用配置文件中的记录，有时留待写入时合并
+    fn refresh_records(&mut self, config: &mut Config) {
+        if self.record_changes.is_empty() {
+            self.records = Records::take(config);
+        }
+    }
+
+    /// 以配置文件中的状态建立
+    fn loaded(mut config: Config, config_manager: &ConfigManager) -> Self {
+        let records = Records::take(&mut config);
+        let mut app_state = config.app_state;
+        app_state.reindex();
+        Self {
+            app_state,
+            revision: config.revision,
+            modified: config_manager.modified_time(),
+            dirty: false,
+            touched: Touched::default(),
+            generation: 0,
+            records,
+            record_changes: Vec::new(),
+        }
+    }
+}
+
+/// 审计日志、告警与状态历史，随事件频繁追加，保存在内存中批量写入
+#[derive(Debug, Clone, Default)]
+pub struct Records {
+    pub audit_log: Vec<AuditEntry>,
+    pub audit_chain: Option<AuditChainAnchor>,
+    pub alerts: Vec<Alert>,
+    pub alert_policy: AlertPolicy,
+    pub status_history: Vec<StatusTransition>,
+}
+
+impl Records {
+    /// 从配置中取出记录
+    fn take(config: &mut Config) -> Self {
+        Self {
+            audit_log: std::mem::take(&mut config.audit_log),
+            audit_chain: config.audit_chain.take(),
+            alerts: std::mem::take(&mut config.alerts),
+            alert_policy: std::mem::take(&mut config.alert_policy),
+            status_history: std::mem::take(&mut config.status_history),
+        }
+    }
+
+    /// 将记录放回配置
+    fn store(self, config: &mut Config) {
+        config.audit_log = self.audit_log;
+        config.audit_chain = self.audit_chain;
+        config.alerts = self.alerts;
+        config.alert_policy = self.alert_policy;
+        config.status_history = self.status_history;
+    }
+
+    /// 依次应用修改，失败的修改记录警告后跳过
+    fn replay(&mut self, changes: &[RecordChange]) {
+        for change in changes {
+            if let Err(e) = change(self) {
+                tracing::warn!("记录的修改无法应用到最新配置，已跳过: {:#}", e);
+            }
+        }
+    }
+}
+
+/// 对记录的一次修改，写入时可能重放到其他实例写入后的记录上
+type RecordChange = Arc<dyn Fn(&mut Records) -> Result<()> + Send + Sync>;
+
+/// 上次写入后本实例修改过的业务组、主机与网络配置的ID，以及是否修改过选中项
+#[derive(Debug, Clone, Default)]
+struct Touched {
+    groups: HashSet<String>,
+    hosts: HashSet<String>,
+    profiles: HashSet<String>,
+    selection: bool,
+}
+
+impl Touched {
+    /// 记录一次修改前后变化的主机、网络配置与选中项，业务组由模型事件记录
+    fn record(&mut self, old: &AppState, new: &AppState) {
+        self.hosts.extend(changed_ids(&old.docker_hosts, &new.docker_hosts, |h| &h.id));
+        self.profiles.extend(changed_ids(&old.network_profiles, &new.network_profiles, |p| &p.id));
+        self.selection |= old.selected_group_id != new.selected_group_id
+            || old.selected_middleware_id != new.selected_middleware_id
+            || old.selected_backend_id != new.selected_backend_id;
+    }
+}
+
+/// 两个列表间新增、删除或内容变化的元素ID
+fn changed_ids<T: PartialEq>(old: &[T], new: &[T], id: impl Fn(&T) -> &str) -> Vec<String> {
+    let before: HashMap<&str, &T> = old.iter().map(|item| (id(item), item)).collect();
+    let after: HashMap<&str, &T> = new.iter().map(|item| (id(item), item)).collect();
+    let changed = after.iter().filter(|(key, item)| before.get(*key) != Some(*item)).map(|(key, _)| key);
+    let removed = before.keys().filter(|key| !after.contains_key(*key));
+    changed.chain(removed).map(|key| key.to_string()).collect()
+}
+
+impl StateStore {
+    /// 从配置文件加载状态，加载失败时以空状态启动并返回错误
+    pub fn load(config_manager: ConfigManager, bus: EventBus) -> (Self, Result<()>) {
+        let (state, result) = match config_manager.load_config() {
+            Ok(config) => (Synced::loaded(config, &config_manager), Ok(())),
+            Err(e) => (Synced::loaded(Config::default(), &config_manager), Err(e)),
+        };
+        let store = Self {
+            state: Arc::new(RwLock::new(state)),
+            config_manager,
+            bus,
+            access: Arc::default(),
+        };
+        (store, result)
+    }
+
+    /// 获取读锁，等待时间计入诊断
+    fn read_state(&self) -> RwLockReadGuard<'_, Synced> {
+        diagnostics::timed(Metric::LockWait, || self.state.read().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// 获取写锁，等待时间计入诊断
+    fn write_state(&self) -> RwLockWriteGuard<'_, Synced> {
+        diagnostics::timed(Metric::LockWait, || self.state.write().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// 只读访问状态
+    pub fn read<R>(&self, f: impl FnOnce(&AppState) -> R) -> R {
+        let state = self.read_state();
+        f(&state.app_state)
+    }
+
+    /// 修改内存状态并标记为未写入，成功后发布变更事件
+    ///
+    /// 若配置文件已被其他实例修改，修改会作用于磁盘上的最新状态；
+    /// 此时修改失败（例如目标已被对方删除）会作为冲突报告。
+    pub fn update<R>(&self, f: impl FnOnce(&mut AppState) -> Result<R>) -> Result<R> {
+        if self.config_manager.is_read_only() {
+            anyhow::bail!("配置处于只读恢复模式，修改未保存");
+        }
+        let mut state = self.write_state();
+
+        let mut events = Vec::new();
+        let mut rebased = false;
+        let modified = self.config_manager.modified_time();
+        if !state.dirty && modified != state.modified {
+            let mut config = self.config_manager.load_config()?;
+            state.modified = modified;
+            if config.revision != state.revision {
+                state.refresh_records(&mut config);
+                events = events::diff(&state.app_state, &config.app_state);
+                state.app_state = config.app_state;
+                state.app_state.reindex();
+                rebased = self.config_manager.peer_modified(state.revision, config.revision);
+                state.revision = config.revision;
+            }
+        }
+
+        let mut next = state.app_state.clone();
+        let result = f(&mut next).and_then(|r| {
+            let changes = events::diff(&state.app_state, &next);
+            self.check_access(&state.app_state, &next, &changes)?;
+            let touched: Vec<String> = changes
+                .iter()
+                .filter_map(|c| c.entity())
+                .filter_map(|(_, id)| next.group_of(id).or_else(|| state.app_state.group_of(id)))
+                .map(|g| g.id.clone())
+                .collect();
+            state.touched.groups.extend(touched);
+            let synced = &mut *state;
+            synced.touched.record(&synced.app_state, &next);
+            events.extend(changes);
+            let previous = std::mem::replace(&mut state.app_state, next);
+            state.app_state.refresh_index(previous);
+            state.dirty = true;
+            state.generation += 1;
+            Ok(r)
+        });
+        drop(state);
+        self.bus.publish(&events);
+
+        match result {
+            Err(e) if rebased => Err(e.context("与其他实例的修改冲突，已同步最新配置")),
+            result => result,
+        }
+    }
+
+    /// 设置当前操作人的业务组权限，界面角色变化时调用
+    pub fn set_access(&self, access: GroupAccess) {
+        *self.access.write().unwrap_or_else(|e| e.into_inner()) = access;
+    }
+
+    /// 以另一操作人的业务组权限访问同一状态的副本，用于代表他人执行的任务
+    pub fn with_access(&self, access: GroupAccess) -> Self {
+        Self {
+            access: Arc::new(RwLock::new(access)),
+            ..self.clone()
+        }
+    }
+
+    /// 当前操作人的业务组权限
+    pub fn access(&self) -> GroupAccess {
+        self.access.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// 当前操作人能否管理业务组，不能时返回错误
+    pub fn authorize(&self, group_id: &GroupId) -> Result<()> {
+        let access = self.access.read().unwrap_or_else(|e| e.into_inner());
+        if access.can_manage(group_id) {
+            return Ok(());
+        }
+        let name = self.read(|s| s.group(group_id).map_or_else(|_| group_id.to_string(), |g| g.name.clone()));
+        anyhow::bail!("操作人 {} 对业务组 {} 只有只读权限", access.actor, name)
+    }
+
+    /// 当前操作人能否管理实体所在的业务组，实体不存在时不检查，由调用方报告
+    pub fn authorize_entity(&self, entity_id: &str) -> Result<()> {
+        match self.read(|s| s.group_of(entity_id).map(|g| g.id.clone())) {
+            Some(group_id) => self.authorize(&GroupId::from(&group_id)),
+            None => Ok(()),
+        }
+    }
+
+    /// 检查修改涉及的实体所在业务组是否都可管理，运行状态与健康状态的变化不检查
+    fn check_access(&self, old: &AppState, new: &AppState, changes: &[ModelEvent]) -> Result<()> {
+        let access = self.access.read().unwrap_or_else(|e| e.into_inner());
+        for change in changes {
+            let (ModelEvent::Created { id, name, .. } | ModelEvent::Updated { id, name, .. } | ModelEvent::Deleted { id, name, .. }) = change else {
+                continue;
+            };
+            let Some(group) = new.group_of(id).or_else(|| old.group_of(id)) else {
+                continue;
+            };
+            if !access.can_manage(&group.id) {
+                anyhow::bail!("操作人 {} 对业务组 {} 只有只读权限，不能修改 {}", access.actor, group.name, name);
+            }
+        }
+        Ok(())
+    }
+
+    /// 只读访问审计日志、告警与状态历史
+    pub fn read_records<R>(&self, f: impl FnOnce(&Records) -> R) -> R {
+        let state = self.read_state();
+        f(&state.records)
+    }
+
+    /// 修改内存中的记录，由下次写入持久化
+    ///
+    /// 修改就地生效，失败时应在改动记录前返回错误。写入时修改会按顺序重放到磁盘上的记录，
+    /// 其他实例在此期间追加的记录因此得以保留；重放失败的修改（例如要确认的告警已被对方清除）被跳过
+    pub fn update_records<R>(&self, f: impl Fn(&mut Records) -> Result<R> + Send + Sync + 'static) -> Result<R> {
+        if self.config_manager.is_read_only() {
+            anyhow::bail!("配置处于只读恢复模式，修改未保存");
+        }
+        let mut state = self.write_state();
+        let result = f(&mut state.records)?;
+        state.record_changes.push(Arc::new(move |records| f(records).map(drop)));
+        Ok(result)
+    }
+
+    /// 是否有尚未写入配置文件的修改
+    pub fn is_dirty(&self) -> bool {
+        let state = self.read_state();
+        state.dirty || !state.record_changes.is_empty()
+    }
+
+    /// 将未写入的修改持久化到配置文件，返回是否写入
+    ///
+    /// 业务组、主机与网络配置只覆盖本实例修改过的，选中项只在本实例修改过时覆盖，
+    /// 其余都采用磁盘上的版本；记录的修改重放到磁盘上的记录。写入前配置文件若已被其他实例修改，
+    /// 合并后的状态同步到内存，其他实例的修改不会丢失。读取、合并与写入在跨进程的写锁内完成
+    pub fn flush(&self) -> Result<bool> {
+        self.flush_with(true)
+    }
+
+    /// 只写入审计日志、告警与状态历史，关闭自动保存时由保存线程调用，模型的修改仍等待手动保存
+    pub fn flush_records(&self) -> Result<bool> {
+        self.flush_with(false)
+    }
+
+    fn flush_with(&self, include_state: bool) -> Result<bool> {
+        // 先在状态锁内取得快照再在配置写锁内写入，两把锁不嵌套获取；
+        // 模型没有未写入的修改时同样参与合并，借此同步其他实例的修改
+        let (snapshot, revision, changes) = {
+            let state = self.read_state();
+            let merge_app = include_state || !state.dirty;
+            if !(merge_app && state.dirty) && state.record_changes.is_empty() {
+                return Ok(false);
+            }
+            let snapshot = merge_app.then(|| (state.app_state.clone(), state.touched.clone(), state.generation));
+            (snapshot, state.revision, state.record_changes.clone())
+        };
+        let ((written, records), revision) = self.config_manager.update_revision(|config| {
+            let peer_modified = self.config_manager.peer_modified(revision, config.revision);
+            if peer_modified {
+                tracing::info!("配置文件在写入前被其他实例修改（修订号 {} → {}），合并后写入", revision, config.revision);
+            }
+            let mut written = None;
+            if let Some((app_state, touched, _)) = &snapshot {
+                config.app_state = merge_state(std::mem::take(&mut config.app_state), app_state, touched);
+                written = peer_modified.then(|| config.app_state.clone());
+            }
+            let mut records = Records::take(config);
+            records.replay(&changes);
+            records.clone().store(config);
+            Ok((written, records))
+        }).context("写入配置失败")?;
+
+        let mut state = self.write_state();
+        // 写入期间新增的记录修改重放到写入后的记录上，留待下次写入
+        state.record_changes.drain(..changes.len());
+        let mut records = records;
+        records.replay(&state.record_changes);
+        state.records = records;
+        // 模型有未写入的修改却未参与写入时保留原修订号，下次写入仍能发现期间其他实例的修改
+        let Some((_, _, generation)) = snapshot else {
+            return Ok(true);
+        };
+        state.revision = revision;
+        state.modified = self.config_manager.modified_time();
+        // 写入期间又有修改时保留未写入标记，由下次写入合并
+        if state.generation != generation {
+            return Ok(true);
+        }
+        state.dirty = false;
+        state.touched = Touched::default();
+        let mut events = Vec::new();
+        if let Some(merged) = written {
+            events = events::diff(&state.app_state, &merged);
+            if !events.is_empty() {
+                state.app_state = merged;
+                state.app_state.reindex();
+            }
+        }
+        drop(state);
+        self.bus.publish(&events);
+        Ok(true)
+    }
+
+    /// 同步配置文件中的最新状态，返回文件是否被其他实例修改过；有未写入的修改时不同步
+    pub fn sync(&self) -> Result<bool> {
+        if self.is_dirty() {
+            return Ok(false);
+        }
+        let mut config = self.config_manager.load_config().context("同步配置失败")?;
+        let mut state = self.write_state();
+        // 读取期间又有新的修改时留待下次同步，以免被磁盘上的状态覆盖
+        if state.dirty || !state.record_changes.is_empty() {
+            return Ok(false);
+        }
+        state.modified = self.config_manager.modified_time();
+        if config.revision == state.revision {
+            return Ok(false);
+        }
+
+        let peer_modified = self.config_manager.peer_modified(state.revision, config.revision);
+        state.refresh_records(&mut config);
+        let events = events::diff(&state.app_state, &config.app_state);
+        state.app_state = config.app_state;
+        state.app_state.reindex();
+        state.revision = config.revision;
+        drop(state);
+        self.bus.publish(&events);
+        Ok(peer_modified)
+    }
+
+    /// 丢弃内存状态及未写入的修改，从配置文件重新加载；尚未写入的记录保留并应用到重新加载的记录上
+    pub fn reload(&self) -> Result<()> {
+        let config = self.config_manager.load_config().context("重新加载状态失败")?;
+        let mut state = self.write_state();
+        let changes = std::mem::take(&mut state.record_changes);
+        *state = Synced::loaded(config, &self.config_manager);
+        state.records.replay(&changes);
+        state.record_changes = changes;
+        drop(state);
+        self.bus.publish(&[ModelEvent::Reloaded]);
+        Ok(())
+    }
+}
+
+/// 写入时合并整个状态：各列表按 [`merge_by_id`] 合并，选中项本实例修改过时以内存为准
+fn merge_state(stored: AppState, memory: &AppState, touched: &Touched) -> AppState {
+    // 逐个字段解构，新增字段时须在此决定合并方式；索引由合并结果重建
+    let AppState { business_groups, docker_hosts, network_profiles, selected_group_id, selected_middleware_id, selected_backend_id, index: _ } = stored;
+    let mut merged = memory.clone();
+    merged.business_groups = merge_groups(business_groups, &memory.business_groups, &touched.groups);
+    merged.docker_hosts = merge_by_id(docker_hosts, &memory.docker_hosts, &touched.hosts, |h| &h.id);
+    merged.network_profiles = merge_by_id(network_profiles, &memory.network_profiles, &touched.profiles, |p| &p.id);
+    if !touched.selection {
+        merged.selected_group_id = selected_group_id;
+        merged.selected_middleware_id = selected_middleware_id;
+        merged.selected_backend_id = selected_backend_id;
+    }
+    merged.reindex();
+    merged
+}
+
+/// 写入时合并业务组，见 [`merge_by_id`]
+fn merge_groups(stored: BusinessGroups, memory: &BusinessGroups, touched: &HashSet<String>) -> BusinessGroups {
+    merge_by_id(stored, memory, touched, |g| &g.id).into()
+}
+
+/// 写入时按ID合并列表：本实例修改过的以内存为准，其余以磁盘为准；
+/// 未修改且磁盘上已没有的是被其他实例删除的，磁盘上有而内存中没有且未修改的是其他实例新建的，追加在末尾
+fn merge_by_id<'a, T: Clone + 'a>(
+    stored: impl IntoIterator<Item = T>,
+    memory: impl IntoIterator<Item = &'a T>,
+    touched: &HashSet<String>,
+    id: impl Fn(&T) -> &str,
+) -> Vec<T> {
+    let mut order = Vec::new();
+    let mut stored: HashMap<String, T> = stored
+        .into_iter()
+        .map(|item| {
+            order.push(id(&item).to_string());
+            (id(&item).to_string(), item)
+        })
+        .collect();
+    let mut merged = Vec::new();
+    for item in memory {
+        let key = id(item);
+        let on_disk = stored.remove(key);
+        if touched.contains(key) {
+            merged.push(item.clone());
+        } else if let Some(on_disk) = on_disk {
+            merged.push(on_disk);
+        }
+    }
+    merged.extend(order.iter().filter(|key| !touched.contains(*key)).filter_map(|key| stored.remove(key)));
+    merged
+}
+
+/// 自动保存的设置与结果，在界面线程与保存线程间共享
+struct SaverSettings {
+    enabled: bool,
+    interval: Duration,
+    stopped: bool,
+    /// 最近一次写入的时间
+    last_saved: Option<DateTime<Utc>>,
+    /// 保存线程中尚未被界面收取的写入错误，连续失败只记录第一次
+    errors: Vec<String>,
+    failing: bool,
+}
+
+/// 后台自动保存：在独立线程中按间隔把状态中未写入的修改写入配置文件，
+/// 间隔内的多次修改合并为一次写入，界面线程不再同步写文件
+pub struct AutoSaver {
+    shared: Arc<(Mutex<SaverSettings>, Condvar)>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl AutoSaver {
+    /// 启动保存线程，`interval_secs` 为写入间隔（秒）
+    pub fn spawn(store: StateStore, enabled: bool, interval_secs: u64) -> Self {
+        let shared = Arc::new((
+            Mutex::new(SaverSettings {
+                enabled,
+                interval: Self::interval(interval_secs),
+                stopped: false,
+                last_saved: None,
+                errors: Vec::new(),
+                failing: false,
+            }),
+            Condvar::new(),
+        ));
+        let thread_shared = shared.clone();
+        let handle = std::thread::Builder::new()
+            .name("auto-save".to_string())
+            .spawn(move || Self::run(store, &thread_shared))
+            .map_err(|e| tracing::error!("无法启动自动保存线程: {}", e))
+            .ok();
+        Self { shared, handle }
+    }
+
+    /// 保存线程：每个间隔检查一次，有未写入的修改时写入
+    fn run(store: StateStore, shared: &(Mutex<SaverSettings>, Condvar)) {
+        let (lock, wake) = shared;
+        let mut settings = lock.lock().unwrap_or_else(|e| e.into_inner());
+        loop {
+            let interval = settings.interval;
+            settings = wake.wait_timeout(settings, interval).unwrap_or_else(|e| e.into_inner()).0;
+            if settings.stopped {
+                return;
+            }
+            if !store.is_dirty() {
+                continue;
+            }
+            let enabled = settings.enabled;
+            drop(settings);
+            // 关闭自动保存时模型的修改等待手动保存，审计日志等记录仍按间隔写入
+            let result = if enabled { store.flush() } else { store.flush_records() };
+            settings = lock.lock().unwrap_or_else(|e| e.into_inner());
+            match result {
+                Ok(written) => {
+                    if written {
+                        settings.last_saved = Some(Utc::now());
+                    }
+                    settings.failing = false;
+                }
+                Err(e) => {
+                    if !settings.failing {
+                        settings.errors.push(format!("{:#}", e));
+                    }
+                    settings.failing = true;
+                }
+            }
+        }
+    }
+
+    /// 写入间隔，至少1秒
+    fn interval(secs: u64) -> Duration {
+        Duration::from_secs(secs.max(1))
+    }
+
+    fn settings(&self) -> MutexGuard<'_, SaverSettings> {
+        self.shared.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 修改自动保存设置，从现在起重新计时
+    pub fn configure(&self, enabled: bool, interval_secs: u64) {
+        let mut settings = self.settings();
+        settings.enabled = enabled;
+        settings.interval = Self::interval(interval_secs);
+        drop(settings);
+        self.shared.1.notify_all();
+    }
+
+    /// 最近一次自动写入的时间
+    pub fn last_saved(&self) -> Option<DateTime<Utc>> {
+        self.settings().last_saved
+    }
+
+    /// 收取保存线程中的写入错误
+    pub fn take_errors(&self) -> Vec<String> {
+        std::mem::take(&mut self.settings().errors)
+    }
+}
+
+impl Drop for AutoSaver {
+    fn drop(&mut self) {
+        self.settings().stopped = true;
+        self.shared.1.notify_all();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit_export;
+    use crate::models::{AlertSeverity, BusinessGroup, DockerConnection, DockerHost, NetworkProfile};
+
+    fn group(id: &str, name: &str) -> BusinessGroup {
+        BusinessGroup { id: id.to_string(), name: name.to_string(), ..BusinessGroup::default() }
+    }
+
+    fn groups(items: &[(&str, &str)]) -> BusinessGroups {
+        items.iter().map(|(id, name)| group(id, name)).collect()
+    }
+
+    fn touched(ids: &[&str]) -> HashSet<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    fn names(groups: &BusinessGroups) -> Vec<(&str, &str)> {
+        groups.iter().map(|g| (g.id.as_str(), g.name.as_str())).collect()
+    }
+
+    #[test]
+    fn concurrent_edits_keep_both_sides() {
+        let stored = groups(&[("a", "a-peer"), ("b", "b"), ("c", "c-peer")]);
+        let memory = groups(&[("a", "a"), ("b", "b-local"), ("c", "c")]);
+        let merged = merge_groups(stored, &memory, &touched(&["b"]));
+        assert_eq!(names(&merged), [("a", "a-peer"), ("b", "b-local"), ("c", "c-peer")]);
+    }
+
+    #[test]
+    fn same_group_edited_on_both_sides_keeps_local() {
+        let stored = groups(&[("a", "a-peer")]);
+        let memory = groups(&[("a", "a-local")]);
+        assert_eq!(names(&merge_groups(stored, &memory, &touched(&["a"]))), [("a", "a-local")]);
+    }
+
+    #[test]
+    fn deletes_on_either_side_are_applied() {
+        // 本实例删除了b，其他实例删除了c
+        let stored = groups(&[("a", "a"), ("b", "b"), ("d", "d")]);
+        let memory = groups(&[("a", "a"), ("c", "c"), ("d", "d")]);
+        let merged = merge_groups(stored, &memory, &touched(&["b"]));
+        assert_eq!(names(&merged), [("a", "a"), ("d", "d")]);
+    }
+
+    #[test]
+    fn local_edit_wins_over_peer_delete_and_local_delete_over_peer_edit() {
+        let stored = groups(&[("b", "b-peer")]);
+        let memory = groups(&[("a", "a-local")]);
+        let merged = merge_groups(stored, &memory, &touched(&["a", "b"]));
+        assert_eq!(names(&merged), [("a", "a-local")]);
+    }
+
+    #[test]
+    fn creations_on_both_sides_are_kept() {
+        let stored = groups(&[("a", "a"), ("peer", "peer")]);
+        let memory = groups(&[("a", "a"), ("local", "local")]);
+        let merged = merge_groups(stored, &memory, &touched(&["local"]));
+        // 其他实例新建的业务组追加在末尾
+        assert_eq!(names(&merged), [("a", "a"), ("local", "local"), ("peer", "peer")]);
+    }
+
+    #[test]
+    fn flush_merges_every_collection_with_peer_edits() {
+        let dir = std::env::temp_dir().join(format!("encryption-service-ui-state-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.json").to_string_lossy().to_string();
+        let host = |name: &str| DockerHost::new(name, DockerConnection::Tcp, "10.0.0.1:2375");
+        let (shared, local_only) = (host("shared"), host("local"));
+
+        let peer = ConfigManager::new(path.clone());
+        peer.update(|config| {
+            config.app_state.docker_hosts.push(shared.clone());
+            config.app_state.selected_group_id = Some("g-peer".to_string());
+            Ok(())
+        }).unwrap();
+        let (store, loaded) = StateStore::load(ConfigManager::new(path.clone()), EventBus::new());
+        loaded.unwrap();
+
+        // 其他实例修改共有的主机并新建网络配置，本实例只新建主机
+        let profile = NetworkProfile::new("peer");
+        peer.update(|config| {
+            config.app_state.docker_hosts[0].name = "shared-peer".to_string();
+            config.app_state.network_profiles.push(profile.clone());
+            Ok(())
+        }).unwrap();
+        store.update(|state| {
+            state.docker_hosts.push(local_only.clone());
+            Ok(())
+        }).unwrap();
+        assert!(store.flush().unwrap());
+
+        let stored = peer.load_config().unwrap().app_state;
+        let hosts: Vec<&str> = stored.docker_hosts.iter().map(|h| h.name.as_str()).collect();
+        assert_eq!(hosts, ["shared-peer", "local"]);
+        assert_eq!(stored.network_profiles, [profile]);
+        assert_eq!(stored.selected_group_id.as_deref(), Some("g-peer"));
+        // 合并结果同步回内存
+        assert_eq!(store.read(|s| s.network_profiles.len()), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn records_are_written_on_flush_after_peer_appends() {
+        let dir = std::env::temp_dir().join(format!("encryption-service-ui-records-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.json").to_string_lossy().to_string();
+        let peer = ConfigManager::new(path.clone());
+        let (store, loaded) = StateStore::load(ConfigManager::new(path.clone()), EventBus::new());
+        loaded.unwrap();
+
+        store.update_records(|records| {
+            records.alerts.push(Alert::new("local", "健康检查连续失败", AlertSeverity::Warning));
+            audit_export::append(&mut records.audit_log, &mut records.audit_chain, AuditEntry::new("local", "本实例的操作", None, None))
+        }).unwrap();
+        // 记录只在内存中，写入前不读写配置文件
+        assert!(store.is_dirty());
+        assert!(peer.load_config().unwrap().alerts.is_empty());
+        assert_eq!(store.read_records(|r| r.alerts.len()), 1);
+
+        peer.update(|config| {
+            config.alerts.push(Alert::new("peer", "进入错误状态", AlertSeverity::Critical));
+            audit_export::append(&mut config.audit_log, &mut config.audit_chain, AuditEntry::new("peer", "其他实例的操作", None, None))
+        }).unwrap();
+        assert!(store.flush().unwrap());
+        assert!(!store.is_dirty());
+
+        let stored = peer.load_config().unwrap();
+        let sources: Vec<&str> = stored.alerts.iter().map(|a| a.source.as_str()).collect();
+        assert_eq!(sources, ["peer", "local"]);
+        let actions: Vec<&str> = stored.audit_log.iter().map(|e| e.action.as_str()).collect();
+        assert_eq!(actions, ["其他实例的操作", "本实例的操作"]);
+        // 本实例的审计日志重新接在磁盘上的链尾，哈希链仍然完整
+        assert_eq!(audit_export::verify_log(&stored.audit_log, stored.audit_chain.as_ref()).unwrap().verified, 2);
+        assert_eq!(store.read_records(|r| r.audit_log.len()), 2);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
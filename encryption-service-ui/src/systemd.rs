@@ -0,0 +1,260 @@
+use std::fmt::Write;
+
+use crate::models::{BackendContainer, BusinessGroup, MiddlewareContainer};
+
+/// systemd重启策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestartPolicy {
+    No,
+    OnFailure,
+    Always,
+}
+
+impl RestartPolicy {
+    /// 所有重启策略
+    pub const ALL: [RestartPolicy; 3] = [RestartPolicy::No, RestartPolicy::OnFailure, RestartPolicy::Always];
+
+    /// 单元文件中的取值
+    pub fn value(&self) -> &'static str {
+        match self {
+            RestartPolicy::No => "no",
+            RestartPolicy::OnFailure => "on-failure",
+            RestartPolicy::Always => "always",
+        }
+    }
+}
+
+/// 单元文件生成选项
+#[derive(Debug, Clone)]
+pub struct UnitOptions {
+    /// 中间层可执行文件路径
+    pub middleware_exec: String,
+    /// 后端可执行文件路径
+    pub backend_exec: String,
+    /// 运行服务的系统用户
+    pub user: String,
+    pub restart: RestartPolicy,
+    /// 重启间隔（秒）
+    pub restart_sec: u32,
+}
+
+impl Default for UnitOptions {
+    fn default() -> Self {
+        Self {
+            middleware_exec: "/usr/local/bin/encryption-service".to_string(),
+            backend_exec: "/usr/local/bin/crud-api".to_string(),
+            user: "encryption".to_string(),
+            restart: RestartPolicy::OnFailure,
+            restart_sec: 5,
+        }
+    }
+}
+
+/// 生成的单元文件
+#[derive(Debug, Clone)]
+pub struct UnitFile {
+    /// 单元名称（含 .service 后缀）
+    pub name: String,
+    pub content: String,
+}
+
+/// 由实体名称生成合法的单元名称
+fn unit_name(prefix: &str, name: &str) -> String {
+    let slug: String = name
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let slug = slug.split('-').filter(|s| !s.is_empty()).collect::<Vec<_>>().join("-");
+    if slug.is_empty() {
+        format!("{}.service", prefix)
+    } else {
+        format!("{}-{}.service", prefix, slug)
+    }
+}
+
+/// 去掉取值中的换行并转义 `%` 说明符，避免取值改写单元文件中的其他配置
+fn escape_value(value: &str) -> String {
+    value.replace(['\r', '\n'], " ").replace('%', "%%")
+}
+
+/// 转义Environment取值：反斜杠、引号与换行按C风格转义，`%` 说明符写为 `%%`
+fn escape_env(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+        .replace('%', "%%")
+}
+
+/// 写入单元文件的公共部分
+fn write_unit(
+    description: &str,
+    exec: &str,
+    env_file: &str,
+    env: &[(&str, String)],
+    options: &UnitOptions,
+) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "[Unit]");
+    let _ = writeln!(out, "Description={}", escape_value(description));
+    let _ = writeln!(out, "After=network-online.target");
+    let _ = writeln!(out, "Wants=network-online.target");
+    let _ = writeln!(out);
+    let _ = writeln!(out, "[Service]");
+    let _ = writeln!(out, "Type=simple");
+    let _ = writeln!(out, "User={}", escape_value(&options.user));
+    let _ = writeln!(out, "ExecStart={}", escape_value(exec));
+    for (key, value) in env {
+        let _ = writeln!(out, "Environment=\"{}={}\"", key, escape_env(value));
+    }
+    // 密钥等敏感配置放在单独的环境文件中，不写入单元文件
+    let _ = writeln!(out, "EnvironmentFile=-{}", env_file);
+    let _ = writeln!(out, "Restart={}", options.restart.value());
+    let _ = writeln!(out, "RestartSec={}", options.restart_sec);
+    let _ = writeln!(out);
+    let _ = writeln!(out, "[Install]");
+    let _ = writeln!(out, "WantedBy=multi-user.target");
+    out
+}
+
+/// 生成中间层的单元文件
+pub fn middleware_unit(middleware: &MiddlewareContainer, options: &UnitOptions) -> UnitFile {
+    let name = unit_name("encryption-mw", &middleware.name);
+    let env = middleware.environment();
+    let env_file = format!("/etc/encryption-service/{}.env", name.trim_end_matches(".service"));
+    let content = write_unit(
+        &format!("加密服务中间层 {}", middleware.name),
+        &options.middleware_exec,
+        &env_file,
+        &env,
+        options,
+    );
+    UnitFile { name, content }
+}
+
+/// 生成后端的单元文件
+pub fn backend_unit(backend: &BackendContainer, options: &UnitOptions) -> UnitFile {
+    let name = unit_name("encryption-backend", &backend.name);
+    let env = backend.environment();
+    let env_file = format!("/etc/encryption-service/{}.env", name.trim_end_matches(".service"));
+    let content = write_unit(
+        &format!("加密服务后端 {}", backend.name),
+        &options.backend_exec,
+        &env_file,
+        &env,
+        options,
+    );
+    UnitFile { name, content }
+}
+
+/// 生成业务组内所有中间层与后端的单元文件
+pub fn group_units(group: &BusinessGroup, options: &UnitOptions) -> Vec<UnitFile> {
+    let mut units = Vec::new();
+    for middleware in &group.middlewares {
+        units.push(middleware_unit(middleware, options));
+        units.extend(middleware.backend_containers.iter().map(|b| backend_unit(b, options)));
+    }
+    units.extend(group.backend_containers.iter().map(|b| backend_unit(b, options)));
+    units
+}
+
+/// 通过Agent安装单元文件的shell命令
+pub fn install_command(unit: &UnitFile) -> String {
+    format!(
+        "cat > /etc/systemd/system/{name} <<'UNIT_EOF'\n{content}UNIT_EOF\nsystemctl daemon-reload && systemctl enable {name}",
+        name = unit.name,
+        content = unit.content,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn middleware(name: &str) -> MiddlewareContainer {
+        MiddlewareContainer { name: name.to_string(), ..MiddlewareContainer::default() }
+    }
+
+    fn backend(name: &str) -> BackendContainer {
+        BackendContainer { name: name.to_string(), ..BackendContainer::default() }
+    }
+
+    #[test]
+    fn unit_names_are_slugged() {
+        assert_eq!(unit_name("encryption-mw", "Payment API #1"), "encryption-mw-payment-api-1.service");
+        assert_eq!(unit_name("encryption-mw", "--a__b--"), "encryption-mw-a-b.service");
+        // 全部为非ASCII字符时只保留前缀
+        assert_eq!(unit_name("encryption-backend", "支付后端"), "encryption-backend.service");
+    }
+
+    #[test]
+    fn middleware_unit_contains_service_settings() {
+        let options = UnitOptions { restart: RestartPolicy::Always, restart_sec: 10, ..UnitOptions::default() };
+        let unit = middleware_unit(&middleware("pay"), &options);
+        assert_eq!(unit.name, "encryption-mw-pay.service");
+        let lines: Vec<&str> = unit.content.lines().collect();
+        for expected in [
+            "[Unit]",
+            "Description=加密服务中间层 pay",
+            "[Service]",
+            "User=encryption",
+            "ExecStart=/usr/local/bin/encryption-service",
+            "Environment=\"SERVER_PORT=9999\"",
+            "EnvironmentFile=-/etc/encryption-service/encryption-mw-pay.env",
+            "Restart=always",
+            "RestartSec=10",
+            "WantedBy=multi-user.target",
+        ] {
+            assert!(lines.contains(&expected), "missing {expected:?} in\n{}", unit.content);
+        }
+        // 密钥不写入单元文件
+        assert!(!unit.content.contains("default_jwt_secret"));
+    }
+
+    #[test]
+    fn values_cannot_inject_directives() {
+        assert_eq!(escape_env(r#"a"b\c"#), r#"a\"b\\c"#);
+        assert_eq!(escape_env("100%\nExecStartPre=/bin/sh"), "100%%\\nExecStartPre=/bin/sh");
+        assert_eq!(escape_value("x\r\nUser=root %h"), "x  User=root %%h");
+
+        let mut backend = backend("db\nExecStartPre=/bin/rm -rf /");
+        backend.url = "http://db:8080/?q=\"1\"\nUser=root".to_string();
+        let options = UnitOptions { user: "svc\nUser=root".to_string(), ..UnitOptions::default() };
+        let unit = backend_unit(&backend, &options);
+        let lines: Vec<&str> = unit.content.lines().collect();
+        assert!(lines.iter().all(|line| !line.starts_with("ExecStartPre=") && *line != "User=root"));
+        assert!(lines.contains(&r#"Environment="LISTEN_URL=http://db:8080/?q=\"1\"\nUser=root""#));
+        assert!(lines.contains(&"User=svc User=root"));
+        // 单元内容中不会出现提前结束here-document的行
+        assert_eq!(install_command(&unit).matches("UNIT_EOF").count(), 2);
+    }
+
+    #[test]
+    fn group_units_cover_all_entities() {
+        let mut mw = middleware("mw");
+        mw.backend_containers = vec![backend("inner")];
+        let group = BusinessGroup {
+            middlewares: vec![mw],
+            backend_containers: vec![backend("shared")],
+            ..BusinessGroup::default()
+        };
+        let names: Vec<String> = group_units(&group, &UnitOptions::default()).into_iter().map(|u| u.name).collect();
+        assert_eq!(names, [
+            "encryption-mw-mw.service",
+            "encryption-backend-inner.service",
+            "encryption-backend-shared.service",
+        ]);
+    }
+
+    #[test]
+    fn install_command_writes_and_enables_unit() {
+        let unit = UnitFile { name: "encryption-mw-pay.service".to_string(), content: "[Unit]\n".to_string() };
+        assert_eq!(
+            install_command(&unit),
+            "cat > /etc/systemd/system/encryption-mw-pay.service <<'UNIT_EOF'\n[Unit]\nUNIT_EOF\n\
+             systemctl daemon-reload && systemctl enable encryption-mw-pay.service",
+        );
+    }
+}
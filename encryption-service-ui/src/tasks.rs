@@ -0,0 +1,173 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+
+use crate::repaint::{self, RepaintSource};
+
+/// 后台任务池的工作线程数
+const WORKER_THREADS: usize = 4;
+
+/// 进程内共享的异步运行时。API客户端按目标主机缓存，池中的连接由建立它的运行时驱动，
+/// API、Docker、Kubernetes与镜像仓库的请求都在此运行时上执行，连接才不会随临时运行时一起关闭
+fn runtime() -> &'static tokio::runtime::Runtime {
+    static RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
+    RUNTIME.get_or_init(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(WORKER_THREADS)
+            .thread_name("api-task")
+            .enable_all()
+            .build()
+            .expect("无法创建异步运行时")
+    })
+}
+
+/// 在后台线程中等待异步操作完成；不能在界面线程或运行时的异步任务中调用，
+/// 界面发起的同步操作经 [`TaskPool::spawn_blocking`] 提交
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    runtime().block_on(future)
+}
+
+/// 在运行时上执行异步操作，不收取结果，由操作自行保存
+pub fn spawn<F>(future: F)
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    runtime().spawn(future);
+}
+
+/// 任务池：界面线程提交异步操作后立即返回，每帧收取已完成的结果，不阻塞界面；任务完成时请求重绘
+pub struct TaskPool<T> {
+    sender: Sender<(u64, T)>,
+    receiver: Receiver<(u64, T)>,
+    next_id: u64,
+    /// 进行中的任务：ID到（说明, 运行时中的任务）
+    in_flight: BTreeMap<u64, (String, JoinHandle<()>)>,
+}
+
+impl<T: Send + 'static> TaskPool<T> {
+    /// 创建空的任务池
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            sender,
+            receiver,
+            next_id: 0,
+            in_flight: BTreeMap::new(),
+        }
+    }
+
+    /// 提交异步操作，返回任务ID
+    pub fn spawn<F>(&mut self, label: &str, future: F) -> u64
+    where
+        F: Future<Output = T> + Send + 'static,
+    {
+        self.next_id += 1;
+        let id = self.next_id;
+        let sender = self.sender.clone();
+        let handle = runtime().spawn(async move {
+            let _ = sender.send((id, future.await));
+            repaint::request(RepaintSource::Task);
+        });
+        self.in_flight.insert(id, (label.to_string(), handle));
+        id
+    }
+
+    /// 在运行时的阻塞线程池中执行同步操作，操作内可调用 [`block_on`]；返回任务ID
+    pub fn spawn_blocking<F>(&mut self, label: &str, f: F) -> u64
+    where
+        F: FnOnce() -> T + Send + 'static,
+    {
+        self.next_id += 1;
+        let id = self.next_id;
+        let sender = self.sender.clone();
+        let handle = runtime().spawn_blocking(move || {
+            let _ = sender.send((id, f()));
+            repaint::request(RepaintSource::Task);
+        });
+        self.in_flight.insert(id, (label.to_string(), handle));
+        id
+    }
+
+    /// 收取已完成任务的结果：（任务ID, 结果）；异常退出的任务没有结果，只从进行中移除
+    pub fn poll(&mut self) -> Vec<(u64, T)> {
+        // 任务先发送结果再结束，先取已结束的任务再收取结果，才不会把刚发送结果的任务当作异常退出
+        let ended: Vec<u64> = self.in_flight
+            .iter()
+            .filter(|(_, (_, handle))| handle.is_finished())
+            .map(|(id, _)| *id)
+            .collect();
+        let finished: Vec<(u64, T)> = self.receiver.try_iter().collect();
+        for (id, _) in &finished {
+            self.in_flight.remove(id);
+        }
+        for id in ended {
+            if let Some((label, _)) = self.in_flight.remove(&id) {
+                tracing::error!("后台任务异常退出: {}", label);
+            }
+        }
+        finished
+    }
+
+    /// 是否有任务进行中
+    pub fn is_busy(&self) -> bool {
+        !self.in_flight.is_empty()
+    }
+}
+
+impl<T: Send + 'static> Default for TaskPool<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 不经界面发起、在服务内部提交的API请求的结果
+#[derive(Debug, Clone)]
+pub struct BackgroundResult {
+    pub label: String,
+    pub finished_at: DateTime<Utc>,
+    pub elapsed: Duration,
+    pub result: Result<(), String>,
+}
+
+/// 服务按需创建且不持有任务池，内部提交的请求结果汇总在进程内，由界面每帧收取
+static BACKGROUND: Mutex<Vec<BackgroundResult>> = Mutex::new(Vec::new());
+static BACKGROUND_PENDING: AtomicU64 = AtomicU64::new(0);
+
+/// 在运行时上执行服务内部的API请求，结果由 [`take_background`] 收取
+pub fn submit<F>(label: &str, future: F)
+where
+    F: Future<Output = Result<()>> + Send + 'static,
+{
+    let label = label.to_string();
+    BACKGROUND_PENDING.fetch_add(1, Ordering::Relaxed);
+    runtime().spawn(async move {
+        let started = Instant::now();
+        let result = future.await.map_err(|e| format!("{:#}", e));
+        if let Ok(mut finished) = BACKGROUND.lock() {
+            finished.push(BackgroundResult {
+                label,
+                finished_at: Utc::now(),
+                elapsed: started.elapsed(),
+                result,
+            });
+        }
+        BACKGROUND_PENDING.fetch_sub(1, Ordering::Relaxed);
+        repaint::request(RepaintSource::Task);
+    });
+}
+
+/// 收取服务内部提交的请求结果
+pub fn take_background() -> Vec<BackgroundResult> {
+    BACKGROUND.lock().map(|mut finished| std::mem::take(&mut *finished)).unwrap_or_default()
+}
+
+/// 服务内部提交、尚未完成的请求数
+pub fn background_pending() -> u64 {
+    BACKGROUND_PENDING.load(Ordering::Relaxed)
+}
@@ -0,0 +1,355 @@
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Mutex, OnceLock, RwLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+use uuid::Uuid;
+
+use crate::models::OtlpSettings;
+use crate::tasks;
+
+/// 只导出本程序自身的span，依赖库的span不导出
+const TARGET_PREFIX: &str = env!("CARGO_CRATE_NAME");
+/// 批量导出的间隔
+const EXPORT_INTERVAL: Duration = Duration::from_secs(5);
+/// 单批导出的span上限
+const MAX_BATCH: usize = 512;
+/// 导出失败时最多积压的span数量，超出后丢弃最早的
+const MAX_PENDING: usize = 4096;
+/// 导出请求超时
+const EXPORT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// span在OTLP中的类型：内部操作
+const SPAN_KIND_INTERNAL: u8 = 1;
+/// 状态码：错误
+const STATUS_CODE_ERROR: u8 = 2;
+
+/// 日志订阅器在进程启动时安装，导出设置与统计因此在进程内共享，由界面在启动与修改设置时写入
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static SETTINGS: RwLock<Option<OtlpSettings>> = RwLock::new(None);
+static EXPORTER: OnceLock<Mutex<Sender<FinishedSpan>>> = OnceLock::new();
+static STATS: Mutex<ExportStats> = Mutex::new(ExportStats {
+    exported: 0,
+    failed: 0,
+    dropped: 0,
+    last_export: None,
+    last_error: None,
+});
+
+/// 导出统计
+#[derive(Debug, Clone)]
+pub struct ExportStats {
+    /// 已成功导出的span数量
+    pub exported: u64,
+    /// 导出失败的批次数
+    pub failed: u64,
+    /// 积压过多被丢弃的span数量
+    pub dropped: u64,
+    pub last_export: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+}
+
+/// 应用导出设置，首次启用时启动后台导出线程
+pub fn configure(settings: OtlpSettings) {
+    let enabled = settings.enabled && !settings.endpoint.trim().is_empty();
+    if let Ok(mut current) = SETTINGS.write() {
+        *current = Some(settings);
+    }
+    if enabled {
+        EXPORTER.get_or_init(|| {
+            let (sender, receiver) = mpsc::channel();
+            std::thread::spawn(move || export_loop(receiver));
+            Mutex::new(sender)
+        });
+    }
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// 当前导出统计
+pub fn stats() -> ExportStats {
+    STATS.lock().map(|s| s.clone()).unwrap_or_else(|e| e.into_inner().clone())
+}
+
+/// 是否导出该span或事件，用作订阅层的过滤条件
+pub fn captures(metadata: &Metadata<'_>) -> bool {
+    metadata.target().starts_with(TARGET_PREFIX)
+}
+
+/// 创建收集span的订阅层
+pub fn layer() -> OtlpLayer {
+    OtlpLayer
+}
+
+/// 已结束、等待导出的span
+struct FinishedSpan {
+    trace_id: String,
+    span_id: String,
+    parent_span_id: Option<String>,
+    name: String,
+    start: SystemTime,
+    end: SystemTime,
+    attributes: Vec<(String, String)>,
+    error: Option<String>,
+}
+
+impl FinishedSpan {
+    fn to_json(&self) -> Value {
+        let nanos = |t: SystemTime| t.duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos().to_string();
+        let mut span = json!({
+            "traceId": self.trace_id,
+            "spanId": self.span_id,
+            "name": self.name,
+            "kind": SPAN_KIND_INTERNAL,
+            "startTimeUnixNano": nanos(self.start),
+            "endTimeUnixNano": nanos(self.end),
+            "attributes": attributes_json(self.attributes.iter().map(|(k, v)| (k.as_str(), v.as_str()))),
+        });
+        if let Some(parent) = &self.parent_span_id {
+            span["parentSpanId"] = json!(parent);
+        }
+        if let Some(error) = &self.error {
+            span["status"] = json!({ "code": STATUS_CODE_ERROR, "message": error });
+        }
+        span
+    }
+}
+
+fn attributes_json<'a>(attributes: impl Iterator<Item = (&'a str, &'a str)>) -> Value {
+    attributes
+        .map(|(key, value)| json!({ "key": key, "value": { "stringValue": value } }))
+        .collect()
+}
+
+/// 进行中的span，保存在订阅器的span扩展中
+struct SpanData {
+    trace_id: String,
+    span_id: String,
+    parent_span_id: Option<String>,
+    start: SystemTime,
+    attributes: Vec<(String, String)>,
+    error: Option<String>,
+}
+
+/// 记录span字段，`trace_id` 字段同时作为OTLP追踪ID，使界面操作的追踪ID与导出的链路一致
+#[derive(Default)]
+struct FieldVisitor {
+    attributes: Vec<(String, String)>,
+    trace_id: Option<String>,
+    error: Option<String>,
+}
+
+impl Visit for FieldVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "trace_id" && is_hex_id(value, 32) {
+            self.trace_id = Some(value.to_ascii_lowercase());
+        }
+        self.record_value(field, value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.record_value(field, format!("{:?}", value));
+    }
+}
+
+impl FieldVisitor {
+    fn record_value(&mut self, field: &Field, value: String) {
+        if field.name() == "error" {
+            self.error = Some(value.clone());
+        }
+        self.attributes.push((field.name().to_string(), value));
+    }
+}
+
+fn is_hex_id(value: &str, len: usize) -> bool {
+    value.len() == len && value.chars().all(|c| c.is_ascii_hexdigit()) && value.chars().any(|c| c != '0')
+}
+
+fn new_span_id() -> String {
+    Uuid::new_v4().simple().to_string()[..16].to_string()
+}
+
+/// 收集本程序span并在结束时交给导出线程的订阅层
+pub struct OtlpLayer;
+
+impl<S> Layer<S> for OtlpLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if !ENABLED.load(Ordering::Relaxed) {
+            return;
+        }
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut visitor = FieldVisitor::default();
+        attrs.record(&mut visitor);
+        let parent = span
+            .parent()
+            .and_then(|p| p.extensions().get::<SpanData>().map(|d| (d.trace_id.clone(), d.span_id.clone())));
+        let (trace_id, parent_span_id) = match (visitor.trace_id, parent) {
+            (Some(trace_id), parent) => (trace_id, parent.map(|(_, span_id)| span_id)),
+            (None, Some((trace_id, span_id))) => (trace_id, Some(span_id)),
+            (None, None) => (Uuid::new_v4().simple().to_string(), None),
+        };
+        span.extensions_mut().insert(SpanData {
+            trace_id,
+            span_id: new_span_id(),
+            parent_span_id,
+            start: SystemTime::now(),
+            attributes: visitor.attributes,
+            error: None,
+        });
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut extensions = span.extensions_mut();
+        let Some(data) = extensions.get_mut::<SpanData>() else {
+            return;
+        };
+        let mut visitor = FieldVisitor::default();
+        values.record(&mut visitor);
+        data.attributes.extend(visitor.attributes);
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.event_span(event) else {
+            return;
+        };
+        let mut extensions = span.extensions_mut();
+        let Some(data) = extensions.get_mut::<SpanData>() else {
+            return;
+        };
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+        if let Some(error) = visitor.error {
+            data.error = Some(error);
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let Some(data) = span.extensions_mut().remove::<SpanData>() else {
+            return;
+        };
+        let Some(sender) = EXPORTER.get() else {
+            return;
+        };
+        let finished = FinishedSpan {
+            trace_id: data.trace_id,
+            span_id: data.span_id,
+            parent_span_id: data.parent_span_id,
+            name: span.name().to_string(),
+            start: data.start,
+            end: SystemTime::now(),
+            attributes: data.attributes,
+            error: data.error,
+        };
+        if let Ok(sender) = sender.lock() {
+            let _ = sender.send(finished);
+        }
+    }
+}
+
+/// 后台导出线程：按间隔或批次上限把span发送到收集器，失败的批次保留到下次重试
+fn export_loop(receiver: Receiver<FinishedSpan>) {
+    let mut pending: Vec<FinishedSpan> = Vec::new();
+    let mut last_flush = Instant::now();
+    let mut client: Option<reqwest::Client> = None;
+    loop {
+        match receiver.recv_timeout(EXPORT_INTERVAL) {
+            Ok(span) => pending.push(span),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => return,
+        }
+        if pending.len() > MAX_PENDING {
+            let excess = pending.len() - MAX_PENDING;
+            pending.drain(..excess);
+            update_stats(|s| s.dropped += excess as u64);
+        }
+        if pending.is_empty() || (pending.len() < MAX_BATCH && last_flush.elapsed() < EXPORT_INTERVAL) {
+            continue;
+        }
+        last_flush = Instant::now();
+
+        let Some(settings) = SETTINGS.read().ok().and_then(|s| s.clone()).filter(|s| s.enabled) else {
+            // 导出已关闭，丢弃积压
+            pending.clear();
+            continue;
+        };
+        if client.is_none() {
+            match reqwest::Client::builder().timeout(EXPORT_TIMEOUT).build() {
+                Ok(built) => client = Some(built),
+                Err(e) => {
+                    update_stats(|s| {
+                        s.failed += 1;
+                        s.last_error = Some(format!("无法创建导出客户端: {}", e));
+                    });
+                    continue;
+                }
+            }
+        }
+        let Some(client) = &client else {
+            continue;
+        };
+
+        let batch_len = pending.len().min(MAX_BATCH);
+        match tasks::block_on(send_batch(client, &settings, &pending[..batch_len])) {
+            Ok(()) => {
+                pending.drain(..batch_len);
+                update_stats(|s| {
+                    s.exported += batch_len as u64;
+                    s.last_export = Some(Utc::now());
+                    s.last_error = None;
+                });
+            }
+            Err(e) => update_stats(|s| {
+                s.failed += 1;
+                s.last_error = Some(format!("{:#}", e));
+            }),
+        }
+    }
+}
+
+fn update_stats(update: impl FnOnce(&mut ExportStats)) {
+    let mut stats = STATS.lock().unwrap_or_else(|e| e.into_inner());
+    update(&mut stats);
+}
+
+/// 以OTLP/HTTP JSON格式发送一批span
+async fn send_batch(client: &reqwest::Client, settings: &OtlpSettings, spans: &[FinishedSpan]) -> anyhow::Result<()> {
+    let body = json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": attributes_json([("service.name", settings.service_name.trim())].into_iter()),
+            },
+            "scopeSpans": [{
+                "scope": { "name": TARGET_PREFIX, "version": env!("CARGO_PKG_VERSION") },
+                "spans": spans.iter().map(FinishedSpan::to_json).collect::<Vec<_>>(),
+            }],
+        }],
+    });
+    let mut request = client.post(settings.traces_url()).json(&body);
+    for (name, value) in &settings.headers {
+        if !name.trim().is_empty() {
+            request = request.header(name.trim(), value);
+        }
+    }
+    let response = request.send().await?;
+    let status = response.status();
+    if !status.is_success() {
+        anyhow::bail!("收集器返回 {} {}", status, response.text().await.unwrap_or_default());
+    }
+    Ok(())
+}
@@ -0,0 +1,331 @@
+use std::fmt::Write;
+
+use crate::models::{BackendContainer, BusinessGroup, MiddlewareContainer};
+
+/// Terraform提供方
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TerraformProvider {
+    Docker,
+    Kubernetes,
+}
+
+impl TerraformProvider {
+    /// 显示名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            TerraformProvider::Docker => "Docker",
+            TerraformProvider::Kubernetes => "Kubernetes",
+        }
+    }
+
+    /// 按提供方渲染业务组
+    pub fn render(&self, group: &BusinessGroup) -> String {
+        match self {
+            TerraformProvider::Docker => to_docker(group),
+            TerraformProvider::Kubernetes => to_kubernetes(group),
+        }
+    }
+}
+
+/// 生成HCL标识符，只保留字母数字与下划线；名称可能全是中文，因此附加ID前缀保证唯一
+fn identifier(prefix: &str, name: &str, id: &str) -> String {
+    let mut parts = vec![prefix.to_string()];
+    let slug: String = name
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    parts.extend(slug.split('_').filter(|s| !s.is_empty()).map(str::to_string));
+    parts.push(id.chars().filter(char::is_ascii_alphanumeric).take(8).collect());
+    parts.join("_")
+}
+
+/// 生成Kubernetes资源名称（小写字母数字与连字符）
+fn k8s_name(identifier: &str) -> String {
+    identifier.replace('_', "-")
+}
+
+/// 转义HCL字符串，避免被当作插值或模板指令，换行等控制字符按转义序列输出
+fn escape_hcl(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+        .replace('\t', "\\t")
+        .replace("${", "$${")
+        .replace("%{", "%%{")
+}
+
+/// 注释中的文本，换行替换为空格以免后续内容被当作配置
+fn comment(text: &str) -> String {
+    text.replace(['\r', '\n'], " ")
+}
+
+/// 从URL中解析端口，未写明时按协议取默认端口
+fn url_port(url: &str) -> u16 {
+    let (scheme, rest) = url.split_once("://").unwrap_or(("http", url));
+    let authority = rest.split('/').next().unwrap_or(rest);
+    authority
+        .rsplit_once(':')
+        .and_then(|(_, port)| port.parse().ok())
+        .unwrap_or(if scheme == "https" { 443 } else { 80 })
+}
+
+/// 输出镜像变量定义
+fn write_variables(out: &mut String) {
+    let _ = writeln!(out, "variable \"middleware_image\" {{");
+    let _ = writeln!(out, "  type    = string");
+    let _ = writeln!(out, "  default = \"encryption-service:latest\"");
+    let _ = writeln!(out, "}}");
+    let _ = writeln!(out);
+    let _ = writeln!(out, "variable \"backend_image\" {{");
+    let _ = writeln!(out, "  type    = string");
+    let _ = writeln!(out, "  default = \"crud-api:latest\"");
+    let _ = writeln!(out, "}}");
+}
+
+/// 待导出的容器
+struct Container {
+    /// 资源标识
+    id: String,
+    name: String,
+    /// 镜像变量名
+    image: &'static str,
+    port: u16,
+    env: Vec<(&'static str, String)>,
+}
+
+/// 业务组中的所有容器
+fn containers(group: &BusinessGroup) -> Vec<Container> {
+    let middleware = |m: &MiddlewareContainer| Container {
+        id: identifier("mw", &m.name, &m.id),
+        name: m.name.clone(),
+        image: "middleware_image",
+        port: m.config.server.port,
+        env: m.environment(),
+    };
+    let backend = |b: &BackendContainer| Container {
+        id: identifier("backend", &b.name, &b.id),
+        name: b.name.clone(),
+        image: "backend_image",
+        port: url_port(&b.url),
+        env: b.environment(),
+    };
+
+    let mut result = Vec::new();
+    for m in &group.middlewares {
+        result.push(middleware(m));
+        result.extend(m.backend_containers.iter().map(backend));
+    }
+    result.extend(group.backend_containers.iter().map(backend));
+    result
+}
+
+/// 导出为Docker提供方的HCL
+pub fn to_docker(group: &BusinessGroup) -> String {
+    let network = identifier("net", &group.name, &group.id);
+    let mut out = String::new();
+    let _ = writeln!(out, "# 业务组: {}", comment(&group.name));
+    let _ = writeln!(out, "terraform {{");
+    let _ = writeln!(out, "  required_providers {{");
+    let _ = writeln!(out, "    docker = {{");
+    let _ = writeln!(out, "      source = \"kreuzwerker/docker\"");
+    let _ = writeln!(out, "    }}");
+    let _ = writeln!(out, "  }}");
+    let _ = writeln!(out, "}}");
+    let _ = writeln!(out);
+    write_variables(&mut out);
+    let _ = writeln!(out);
+    let _ = writeln!(out, "resource \"docker_network\" \"{}\" {{", network);
+    let _ = writeln!(out, "  name = \"{}\"", k8s_name(&network));
+    let _ = writeln!(out, "}}");
+
+    for Container { id, name, image, port, env } in containers(group) {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "resource \"docker_container\" \"{}\" {{", id);
+        let _ = writeln!(out, "  name  = \"{}\"", escape_hcl(&name));
+        let _ = writeln!(out, "  image = var.{}", image);
+        let _ = writeln!(out, "  restart = \"unless-stopped\"");
+        let _ = writeln!(out, "  env = [");
+        for (key, value) in &env {
+            let _ = writeln!(out, "    \"{}={}\",", key, escape_hcl(value));
+        }
+        let _ = writeln!(out, "  ]");
+        let _ = writeln!(out, "  ports {{");
+        let _ = writeln!(out, "    internal = {}", port);
+        let _ = writeln!(out, "    external = {}", port);
+        let _ = writeln!(out, "  }}");
+        let _ = writeln!(out, "  networks_advanced {{");
+        let _ = writeln!(out, "    name = docker_network.{}.name", network);
+        let _ = writeln!(out, "  }}");
+        let _ = writeln!(out, "}}");
+    }
+    out
+}
+
+/// 导出为Kubernetes提供方的HCL
+pub fn to_kubernetes(group: &BusinessGroup) -> String {
+    let namespace = identifier("ns", &group.name, &group.id);
+    let mut out = String::new();
+    let _ = writeln!(out, "# 业务组: {}", comment(&group.name));
+    let _ = writeln!(out, "terraform {{");
+    let _ = writeln!(out, "  required_providers {{");
+    let _ = writeln!(out, "    kubernetes = {{");
+    let _ = writeln!(out, "      source = \"hashicorp/kubernetes\"");
+    let _ = writeln!(out, "    }}");
+    let _ = writeln!(out, "  }}");
+    let _ = writeln!(out, "}}");
+    let _ = writeln!(out);
+    write_variables(&mut out);
+    let _ = writeln!(out);
+    let _ = writeln!(out, "resource \"kubernetes_namespace\" \"{}\" {{", namespace);
+    let _ = writeln!(out, "  metadata {{");
+    let _ = writeln!(out, "    name = \"{}\"", k8s_name(&namespace));
+    let _ = writeln!(out, "  }}");
+    let _ = writeln!(out, "}}");
+
+    for Container { id, name, image, port, env } in containers(group) {
+        let app = k8s_name(&id);
+        let _ = writeln!(out);
+        let _ = writeln!(out, "# {}", comment(&name));
+        let _ = writeln!(out, "resource \"kubernetes_deployment\" \"{}\" {{", id);
+        let _ = writeln!(out, "  metadata {{");
+        let _ = writeln!(out, "    name      = \"{}\"", app);
+        let _ = writeln!(out, "    namespace = kubernetes_namespace.{}.metadata[0].name", namespace);
+        let _ = writeln!(out, "  }}");
+        let _ = writeln!(out, "  spec {{");
+        let _ = writeln!(out, "    replicas = 1");
+        let _ = writeln!(out, "    selector {{");
+        let _ = writeln!(out, "      match_labels = {{ app = \"{}\" }}", app);
+        let _ = writeln!(out, "    }}");
+        let _ = writeln!(out, "    template {{");
+        let _ = writeln!(out, "      metadata {{");
+        let _ = writeln!(out, "        labels = {{ app = \"{}\" }}", app);
+        let _ = writeln!(out, "      }}");
+        let _ = writeln!(out, "      spec {{");
+        let _ = writeln!(out, "        container {{");
+        let _ = writeln!(out, "          name  = \"{}\"", app);
+        let _ = writeln!(out, "          image = var.{}", image);
+        let _ = writeln!(out, "          port {{");
+        let _ = writeln!(out, "            container_port = {}", port);
+        let _ = writeln!(out, "          }}");
+        for (key, value) in &env {
+            let _ = writeln!(out, "          env {{");
+            let _ = writeln!(out, "            name  = \"{}\"", key);
+            let _ = writeln!(out, "            value = \"{}\"", escape_hcl(value));
+            let _ = writeln!(out, "          }}");
+        }
+        let _ = writeln!(out, "        }}");
+        let _ = writeln!(out, "      }}");
+        let _ = writeln!(out, "    }}");
+        let _ = writeln!(out, "  }}");
+        let _ = writeln!(out, "}}");
+        let _ = writeln!(out);
+        let _ = writeln!(out, "resource \"kubernetes_service\" \"{}\" {{", id);
+        let _ = writeln!(out, "  metadata {{");
+        let _ = writeln!(out, "    name      = \"{}\"", app);
+        let _ = writeln!(out, "    namespace = kubernetes_namespace.{}.metadata[0].name", namespace);
+        let _ = writeln!(out, "  }}");
+        let _ = writeln!(out, "  spec {{");
+        let _ = writeln!(out, "    selector = {{ app = \"{}\" }}", app);
+        let _ = writeln!(out, "    port {{");
+        let _ = writeln!(out, "      port        = {}", port);
+        let _ = writeln!(out, "      target_port = {}", port);
+        let _ = writeln!(out, "    }}");
+        let _ = writeln!(out, "  }}");
+        let _ = writeln!(out, "}}");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn group() -> BusinessGroup {
+        let mut middleware = MiddlewareContainer {
+            id: "0a1b2c3d-4e5f".to_string(),
+            name: "Pay API".to_string(),
+            ..MiddlewareContainer::default()
+        };
+        middleware.backend_containers = vec![BackendContainer {
+            id: "ffeeddcc-bbaa".to_string(),
+            name: "订单库".to_string(),
+            url: "https://orders.internal/api".to_string(),
+            ..BackendContainer::default()
+        }];
+        BusinessGroup {
+            id: "99887766-5544".to_string(),
+            name: "支付".to_string(),
+            middlewares: vec![middleware],
+            ..BusinessGroup::default()
+        }
+    }
+
+    #[test]
+    fn identifiers_are_hcl_safe() {
+        assert_eq!(identifier("mw", "Pay API", "0a1b2c3d-4e5f"), "mw_pay_api_0a1b2c3d");
+        assert_eq!(identifier("backend", "订单库", "ff-ee-dd-cc-bb"), "backend_ffeeddcc");
+        assert_eq!(k8s_name("mw_pay_api_0a1b2c3d"), "mw-pay-api-0a1b2c3d");
+    }
+
+    #[test]
+    fn url_ports_default_by_scheme() {
+        assert_eq!(url_port("http://db:8080/v1"), 8080);
+        assert_eq!(url_port("https://db/v1"), 443);
+        assert_eq!(url_port("http://db"), 80);
+        assert_eq!(url_port("db:5432"), 5432);
+        assert_eq!(url_port("http://db:bad"), 80);
+    }
+
+    #[test]
+    fn strings_are_escaped() {
+        assert_eq!(escape_hcl(r#"say "hi" \ now"#), r#"say \"hi\" \\ now"#);
+        assert_eq!(escape_hcl("${var.secret} %{if true}x%{endif}"), "$${var.secret} %%{if true}x%%{endif}");
+        assert_eq!(escape_hcl("a\nb\tc\r"), "a\\nb\\tc\\r");
+        // 单独的 $ 与 % 不需要转义
+        assert_eq!(escape_hcl("100% $5"), "100% $5");
+    }
+
+    #[test]
+    fn docker_export_contains_all_containers() {
+        let hcl = to_docker(&group());
+        assert!(hcl.starts_with("# 业务组: 支付\n"));
+        assert!(hcl.contains("resource \"docker_network\" \"net_99887766\" {\n  name = \"net-99887766\"\n}"));
+        assert!(hcl.contains("resource \"docker_container\" \"mw_pay_api_0a1b2c3d\" {\n  name  = \"Pay API\"\n  image = var.middleware_image\n"));
+        assert!(hcl.contains("resource \"docker_container\" \"backend_ffeeddcc\" {\n  name  = \"订单库\"\n  image = var.backend_image\n"));
+        assert!(hcl.contains("    internal = 9999\n    external = 9999\n"));
+        assert!(hcl.contains("    internal = 443\n"));
+        assert!(hcl.contains("    \"LISTEN_URL=https://orders.internal/api\",\n"));
+        assert!(hcl.contains("    name = docker_network.net_99887766.name\n"));
+        assert!(!hcl.contains("default_jwt_secret"));
+    }
+
+    #[test]
+    fn kubernetes_export_creates_deployment_and_service() {
+        let hcl = to_kubernetes(&group());
+        assert!(hcl.contains("resource \"kubernetes_namespace\" \"ns_99887766\" {\n  metadata {\n    name = \"ns-99887766\"\n"));
+        for id in ["mw_pay_api_0a1b2c3d", "backend_ffeeddcc"] {
+            assert!(hcl.contains(&format!("resource \"kubernetes_deployment\" \"{id}\" {{")));
+            assert!(hcl.contains(&format!("resource \"kubernetes_service\" \"{id}\" {{")));
+            assert!(hcl.contains(&format!("selector = {{ app = \"{}\" }}", k8s_name(id))));
+        }
+        assert!(hcl.contains("            name  = \"SERVER_PORT\"\n            value = \"9999\"\n"));
+        assert!(hcl.contains("      target_port = 443\n"));
+    }
+
+    #[test]
+    fn names_cannot_inject_configuration() {
+        let mut group = group();
+        group.name = "支付\nresource \"null_resource\" \"x\" {}".to_string();
+        group.middlewares[0].name = "a\"\n}\nresource \"null_resource\" \"y\" {".to_string();
+        group.middlewares[0].backend_containers[0].url = "http://db/${file(\"/etc/passwd\")}".to_string();
+        for hcl in [to_docker(&group), to_kubernetes(&group)] {
+            assert!(!hcl.lines().any(|line| line.starts_with("resource \"null_resource\"")), "{hcl}");
+            assert!(!hcl.contains("\"${file"));
+        }
+        let hcl = to_docker(&group);
+        assert!(hcl.contains(r#"  name  = "a\"\n}\nresource \"null_resource\" \"y\" {""#));
+        assert!(hcl.contains(r#""LISTEN_URL=http://db/$${file(\"/etc/passwd\")}","#));
+    }
+}
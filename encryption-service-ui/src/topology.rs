@@ -0,0 +1,386 @@
+use std::fmt::Write;
+
+use crate::models::{BackendContainer, BusinessGroup, ContainerStatus, GroupStatus};
+
+/// 拓扑导出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TopologyFormat {
+    Dot,
+    Mermaid,
+}
+
+impl TopologyFormat {
+    /// 显示名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            TopologyFormat::Dot => "Graphviz (DOT)",
+            TopologyFormat::Mermaid => "Mermaid",
+        }
+    }
+
+    /// 文件扩展名
+    pub fn extension(&self) -> &'static str {
+        match self {
+            TopologyFormat::Dot => "dot",
+            TopologyFormat::Mermaid => "mmd",
+        }
+    }
+
+    /// 按格式渲染拓扑
+    pub fn render(&self, groups: &[BusinessGroup]) -> String {
+        match self {
+            TopologyFormat::Dot => to_dot(groups),
+            TopologyFormat::Mermaid => to_mermaid(groups),
+        }
+    }
+}
+
+/// 生成节点ID（UUID中的连字符在两种格式中都不合法）
+fn node_id(prefix: &str, id: &str) -> String {
+    format!("{}_{}", prefix, id.replace('-', ""))
+}
+
+/// 业务组状态对应的填充颜色
+pub fn group_rgb(status: &GroupStatus) -> [u8; 3] {
+    match status {
+        GroupStatus::Running => [0xc8, 0xe6, 0xc9],
+        GroupStatus::Stopped => [0xe0, 0xe0, 0xe0],
+        GroupStatus::Starting | GroupStatus::Stopping => [0xff, 0xf9, 0xc4],
+        GroupStatus::Error => [0xff, 0xcd, 0xd2],
+        GroupStatus::Degraded => [0xff, 0xe0, 0xb2],
+    }
+}
+
+/// 容器状态对应的填充颜色
+pub fn container_rgb(status: &ContainerStatus) -> [u8; 3] {
+    match status {
+        ContainerStatus::Running => [0xc8, 0xe6, 0xc9],
+        ContainerStatus::Stopped => [0xe0, 0xe0, 0xe0],
+        ContainerStatus::Starting | ContainerStatus::Stopping => [0xff, 0xf9, 0xc4],
+        ContainerStatus::Error => [0xff, 0xcd, 0xd2],
+    }
+}
+
+/// 颜色转为十六进制表示
+pub fn hex(rgb: [u8; 3]) -> String {
+    format!("#{:02x}{:02x}{:02x}", rgb[0], rgb[1], rgb[2])
+}
+
+/// 容器状态对应的Mermaid样式类
+fn container_class(status: &ContainerStatus) -> &'static str {
+    match status {
+        ContainerStatus::Running => "running",
+        ContainerStatus::Stopped => "stopped",
+        ContainerStatus::Starting | ContainerStatus::Stopping => "transition",
+        ContainerStatus::Error => "error",
+    }
+}
+
+/// 转义DOT字符串
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// 转义Mermaid字符串
+fn escape_mermaid(text: &str) -> String {
+    text.replace('"', "#quot;")
+}
+
+/// 后端节点标签：逐行转义后以格式的换行标记连接，换行标记本身不能被转义
+fn backend_label(backend: &BackendContainer, escape: fn(&str) -> String, newline: &str) -> String {
+    backend_lines(backend).iter().map(|line| escape(line)).collect::<Vec<_>>().join(newline)
+}
+
+/// 导出为Graphviz DOT格式
+pub fn to_dot(groups: &[BusinessGroup]) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "digraph topology {{");
+    let _ = writeln!(out, "    rankdir=LR;");
+    let _ = writeln!(out, "    node [shape=box, style=\"rounded,filled\", fontname=\"sans-serif\"];");
+
+    for group in groups {
+        let group_id = node_id("g", &group.id);
+        let _ = writeln!(out);
+        let _ = writeln!(
+            out,
+            "    {} [label=\"{}\\n{}\", shape=folder, fillcolor=\"{}\"];",
+            group_id,
+            escape_dot(&group.name),
+            group.rollup_status().label(),
+            hex(group_rgb(&group.rollup_status())),
+        );
+
+        for middleware in &group.middlewares {
+            let middleware_id = node_id("m", &middleware.id);
+            let _ = writeln!(
+                out,
+                "    {} [label=\"{}\\n{}\\n{} / {}\", fillcolor=\"{}\"];",
+                middleware_id,
+                escape_dot(&middleware.name),
+                escape_dot(&middleware.url),
+                middleware.status.label(),
+                middleware.health.label(),
+                hex(container_rgb(&middleware.status)),
+            );
+            let _ = writeln!(out, "    {} -> {};", group_id, middleware_id);
+
+            for backend in &middleware.backend_containers {
+                let backend_id = node_id("b", &backend.id);
+                let _ = writeln!(
+                    out,
+                    "    {} [label=\"{}\", shape=cylinder, fillcolor=\"{}\"];",
+                    backend_id,
+                    backend_label(backend, escape_dot, "\\n"),
+                    hex(container_rgb(&backend.status)),
+                );
+                let _ = writeln!(out, "    {} -> {};", middleware_id, backend_id);
+            }
+        }
+
+        for backend in &group.backend_containers {
+            let backend_id = node_id("b", &backend.id);
+            let _ = writeln!(
+                out,
+                "    {} [label=\"{}\", shape=cylinder, fillcolor=\"{}\"];",
+                backend_id,
+                backend_label(backend, escape_dot, "\\n"),
+                hex(container_rgb(&backend.status)),
+            );
+            let _ = writeln!(out, "    {} -> {} [style=dashed];", group_id, backend_id);
+        }
+    }
+
+    let _ = writeln!(out, "}}");
+    out
+}
+
+/// 导出为Mermaid流程图格式
+pub fn to_mermaid(groups: &[BusinessGroup]) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "flowchart LR");
+    let _ = writeln!(out, "    classDef running fill:#c8e6c9,stroke:#2e7d32");
+    let _ = writeln!(out, "    classDef stopped fill:#e0e0e0,stroke:#616161");
+    let _ = writeln!(out, "    classDef transition fill:#fff9c4,stroke:#f9a825");
+    let _ = writeln!(out, "    classDef error fill:#ffcdd2,stroke:#c62828");
+
+    for group in groups {
+        let group_id = node_id("g", &group.id);
+        let _ = writeln!(out);
+        let _ = writeln!(
+            out,
+            "    {}[/\"{}<br/>{}\"/]",
+            group_id,
+            escape_mermaid(&group.name),
+            group.rollup_status().label(),
+        );
+
+        for middleware in &group.middlewares {
+            let middleware_id = node_id("m", &middleware.id);
+            let _ = writeln!(
+                out,
+                "    {}[\"{}<br/>{}<br/>{} / {}\"]:::{}",
+                middleware_id,
+                escape_mermaid(&middleware.name),
+                escape_mermaid(&middleware.url),
+                middleware.status.label(),
+                middleware.health.label(),
+                container_class(&middleware.status),
+            );
+            let _ = writeln!(out, "    {} --> {}", group_id, middleware_id);
+
+            for backend in &middleware.backend_containers {
+                let backend_id = node_id("b", &backend.id);
+                let _ = writeln!(
+                    out,
+                    "    {}[(\"{}\")]:::{}",
+                    backend_id,
+                    backend_label(backend, escape_mermaid, "<br/>"),
+                    container_class(&backend.status),
+                );
+                let _ = writeln!(out, "    {} --> {}", middleware_id, backend_id);
+            }
+        }
+
+        for backend in &group.backend_containers {
+            let backend_id = node_id("b", &backend.id);
+            let _ = writeln!(
+                out,
+                "    {}[(\"{}\")]:::{}",
+                backend_id,
+                backend_label(backend, escape_mermaid, "<br/>"),
+                container_class(&backend.status),
+            );
+            let _ = writeln!(out, "    {} -.-> {}", group_id, backend_id);
+        }
+    }
+
+    out
+}
+
+/// 节点宽度
+const NODE_WIDTH: f32 = 220.0;
+/// 节点高度
+const NODE_HEIGHT: f32 = 56.0;
+/// 列间距
+const COLUMN_SPACING: f32 = 280.0;
+/// 行间距
+const ROW_SPACING: f32 = 72.0;
+/// 画布边距
+const MARGIN: f32 = 20.0;
+
+/// 拓扑图节点
+#[derive(Debug, Clone)]
+pub struct LayoutNode {
+    pub lines: Vec<String>,
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub fill: [u8; 3],
+}
+
+/// 拓扑图连线
+#[derive(Debug, Clone)]
+pub struct LayoutEdge {
+    pub from: usize,
+    pub to: usize,
+    /// 业务组直接管理的后端使用虚线
+    pub dashed: bool,
+}
+
+/// 分层布局后的拓扑图（业务组 → 中间层 → 后端）
+#[derive(Debug, Clone, Default)]
+pub struct TopologyLayout {
+    pub nodes: Vec<LayoutNode>,
+    pub edges: Vec<LayoutEdge>,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl TopologyLayout {
+    /// 添加节点，返回节点索引
+    fn add_node(&mut self, column: usize, row: f32, lines: Vec<String>, fill: [u8; 3]) -> usize {
+        self.nodes.push(LayoutNode {
+            lines,
+            x: MARGIN + column as f32 * COLUMN_SPACING,
+            y: MARGIN + row * ROW_SPACING,
+            width: NODE_WIDTH,
+            height: NODE_HEIGHT,
+            fill,
+        });
+        self.nodes.len() - 1
+    }
+}
+
+/// 后端节点文本
+fn backend_lines(backend: &BackendContainer) -> Vec<String> {
+    vec![
+        backend.name.clone(),
+        format!("{} [{}]", backend.url, backend.instance_type),
+        format!("{} / {}", backend.status.label(), backend.health.label()),
+    ]
+}
+
+/// 计算分层布局，父节点纵向居中于其子节点
+pub fn layout(groups: &[BusinessGroup]) -> TopologyLayout {
+    let mut result = TopologyLayout::default();
+    let mut row = 0.0_f32;
+
+    for group in groups {
+        let group_start = row;
+        let mut children = Vec::new();
+
+        for middleware in &group.middlewares {
+            let middleware_start = row;
+            let mut backends = Vec::new();
+            for backend in &middleware.backend_containers {
+                backends.push(result.add_node(2, row, backend_lines(backend), container_rgb(&backend.status)));
+                row += 1.0;
+            }
+            if backends.is_empty() {
+                row += 1.0;
+            }
+
+            let middleware_index = result.add_node(
+                1,
+                (middleware_start + row - 1.0) / 2.0,
+                vec![
+                    middleware.name.clone(),
+                    middleware.url.clone(),
+                    format!("{} / {}", middleware.status.label(), middleware.health.label()),
+                ],
+                container_rgb(&middleware.status),
+            );
+            for backend_index in backends {
+                result.edges.push(LayoutEdge { from: middleware_index, to: backend_index, dashed: false });
+            }
+            children.push((middleware_index, false));
+        }
+
+        for backend in &group.backend_containers {
+            children.push((result.add_node(2, row, backend_lines(backend), container_rgb(&backend.status)), true));
+            row += 1.0;
+        }
+        if children.is_empty() {
+            row += 1.0;
+        }
+
+        let group_index = result.add_node(
+            0,
+            (group_start + row - 1.0) / 2.0,
+            vec![group.name.clone(), group.rollup_status().label().to_string()],
+            group_rgb(&group.rollup_status()),
+        );
+        for (child, dashed) in children {
+            result.edges.push(LayoutEdge { from: group_index, to: child, dashed });
+        }
+
+        // 业务组之间留出半行间隔
+        row += 0.5;
+    }
+
+    result.width = MARGIN * 2.0 + COLUMN_SPACING * 2.0 + NODE_WIDTH;
+    result.height = MARGIN * 2.0 + (row - 0.5).max(1.0) * ROW_SPACING;
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::MiddlewareContainer;
+
+    fn group() -> BusinessGroup {
+        let backend = BackendContainer {
+            id: "b-1".to_string(),
+            name: r#"db "primary" C:\data"#.to_string(),
+            url: "http://db:8000".to_string(),
+            ..BackendContainer::default()
+        };
+        BusinessGroup {
+            id: "g-1".to_string(),
+            name: r#"订单 "核心""#.to_string(),
+            middlewares: vec![MiddlewareContainer {
+                id: "m-1".to_string(),
+                backend_containers: vec![backend],
+                ..MiddlewareContainer::default()
+            }],
+            ..BusinessGroup::default()
+        }
+    }
+
+    #[test]
+    fn dot_escapes_quotes_and_keeps_line_breaks() {
+        let dot = to_dot(&[group()]);
+        assert!(dot.contains(r#"g_g1 [label="订单 \"核心\"\n"#), "{}", dot);
+        assert!(dot.contains(r#"b_b1 [label="db \"primary\" C:\\data\nhttp://db:8000 [mixed]\n"#), "{}", dot);
+        // 换行标记不能被再次转义，否则Graphviz显示字面的 \n
+        assert!(!dot.contains(r"\\n"), "{}", dot);
+    }
+
+    #[test]
+    fn mermaid_escapes_quotes_and_keeps_line_breaks() {
+        let mermaid = to_mermaid(&[group()]);
+        assert!(mermaid.contains(r#"g_g1[/"订单 #quot;核心#quot;<br/>"#), "{}", mermaid);
+        assert!(mermaid.contains(r#"b_b1[("db #quot;primary#quot; C:\data<br/>http://db:8000 [mixed]<br/>"#), "{}", mermaid);
+    }
+}
@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+
+/// 给出建议至少需要的延迟样本数
+pub const MIN_SAMPLES: usize = 10;
+/// 建议超时为P99延迟的倍数，为偶发的慢请求留出余量
+const TIMEOUT_HEADROOM: f64 = 2.0;
+/// 建议超时的下限与上限（毫秒）
+const MIN_TIMEOUT_MS: u64 = 200;
+const MAX_TIMEOUT_MS: u64 = 60_000;
+/// 单个请求含重试的最长总耗时（毫秒），超出时减少重试次数
+const RETRY_BUDGET_MS: u64 = 30_000;
+
+/// 一个后端观测到的延迟分布（毫秒）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LatencyDistribution {
+    pub samples: usize,
+    pub p50: f64,
+    pub p90: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
+impl LatencyDistribution {
+    /// 由延迟样本计算分布，没有样本时为空
+    pub fn from_samples(samples: &[f64]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        // 最近秩法：第p百分位为排序后第 ⌈p·n⌉ 个样本
+        let percentile = |p: f64| sorted[((p * sorted.len() as f64).ceil() as usize).clamp(1, sorted.len()) - 1];
+        Some(Self {
+            samples: sorted.len(),
+            p50: percentile(0.50),
+            p90: percentile(0.90),
+            p99: percentile(0.99),
+            max: sorted[sorted.len() - 1],
+        })
+    }
+}
+
+/// 把延迟样本按等宽区间分桶：（区间下限, 区间上限, 样本数）
+pub fn histogram(samples: &[f64], buckets: usize) -> Vec<(f64, f64, usize)> {
+    let max = samples.iter().copied().fold(0.0, f64::max);
+    if samples.is_empty() || buckets == 0 || max <= 0.0 {
+        return Vec::new();
+    }
+    let width = max / buckets as f64;
+    let mut counts = vec![0; buckets];
+    for sample in samples {
+        counts[((sample / width) as usize).min(buckets - 1)] += 1;
+    }
+    counts
+        .into_iter()
+        .enumerate()
+        .map(|(i, count)| (i as f64 * width, (i + 1) as f64 * width, count))
+        .collect()
+}
+
+/// 建议的超时与重试次数
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TuningSuggestion {
+    pub timeout_ms: u64,
+    pub retries: u32,
+}
+
+/// 按延迟分布与错误率给出建议，样本不足 [`MIN_SAMPLES`] 时为空：
+/// 超时取P99的两倍并向上取整到100毫秒；没有失败时重试1次，错误率越高重试越多，
+/// 但含重试的总耗时不超过30秒
+pub fn suggest(distribution: &LatencyDistribution, error_percent: f64) -> Option<TuningSuggestion> {
+    if distribution.samples < MIN_SAMPLES {
+        return None;
+    }
+    let timeout_ms = ((distribution.p99 * TIMEOUT_HEADROOM / 100.0).ceil() as u64 * 100).clamp(MIN_TIMEOUT_MS, MAX_TIMEOUT_MS);
+    let mut retries = if error_percent <= 0.0 {
+        1
+    } else if error_percent <= 10.0 {
+        2
+    } else {
+        3
+    };
+    while retries > 0 && timeout_ms * (retries as u64 + 1) > RETRY_BUDGET_MS {
+        retries -= 1;
+    }
+    Some(TuningSuggestion { timeout_ms, retries })
+}
+
+/// 要写入一个后端的超时与重试次数
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackendTuning {
+    pub group_id: String,
+    /// 所在中间层，直接属于业务组的后端为空
+    pub middleware_id: Option<String>,
+    pub backend_id: String,
+    pub suggestion: TuningSuggestion,
+}
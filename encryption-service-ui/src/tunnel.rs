@@ -0,0 +1,165 @@
+use anyhow::{Context, Result};
+use std::io::Read;
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::process::{Child, Command, Stdio};
+use std::sync::RwLock;
+use std::time::Duration;
+
+use crate::models::SshTunnel;
+
+/// 本机转发监听的地址
+const LOCAL_HOST: &str = "127.0.0.1";
+/// 检查本机转发端口是否可连接的超时
+const CHECK_TIMEOUT: Duration = Duration::from_millis(100);
+/// ssh保活间隔秒数，跳板机连接中断后ssh据此退出
+const SERVER_ALIVE_INTERVAL_SECS: u32 = 15;
+
+/// 已建立的隧道（目标主机:端口, 本机端口），请求与探测按此改写地址；由隧道服务在状态变化时整体替换
+static ROUTES: RwLock<Vec<(String, u16)>> = RwLock::new(Vec::new());
+
+/// 替换已建立的隧道
+pub fn set_routes(routes: Vec<(String, u16)>) {
+    if let Ok(mut current) = ROUTES.write() {
+        *current = routes;
+    }
+}
+
+/// 地址的目标已建立隧道时改写为本机转发地址，其余地址原样返回
+pub fn route(url: &str) -> String {
+    let Ok(routes) = ROUTES.read() else {
+        return url.to_string();
+    };
+    if routes.is_empty() {
+        return url.to_string();
+    }
+    let Ok(mut parsed) = reqwest::Url::parse(url) else {
+        return url.to_string();
+    };
+    let Some(target) = target_of(&parsed) else {
+        return url.to_string();
+    };
+    let Some((_, local_port)) = routes.iter().find(|(t, _)| *t == target) else {
+        return url.to_string();
+    };
+    if parsed.set_host(Some(LOCAL_HOST)).is_err() || parsed.set_port(Some(*local_port)).is_err() {
+        return url.to_string();
+    }
+    let mut routed = parsed.to_string();
+    // 原地址没有路径时不添加结尾的斜杠，避免拼接接口路径时出现双斜杠
+    if !url.ends_with('/') && parsed.path() == "/" {
+        routed.pop();
+    }
+    routed
+}
+
+/// 地址中的目标主机与端口
+pub fn target(url: &str) -> Option<String> {
+    target_of(&reqwest::Url::parse(url).ok()?)
+}
+
+fn target_of(url: &reqwest::Url) -> Option<String> {
+    Some(format!("{}:{}", url.host_str()?, url.port_or_known_default()?))
+}
+
+/// 隧道状态
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TunnelStatus {
+    /// ssh已启动，本机端口尚未可连接
+    Connecting,
+    /// 本机端口可连接，请求经隧道转发
+    Connected { local_port: u16 },
+    /// ssh已退出，等待重新连接
+    Failed(String),
+}
+
+impl TunnelStatus {
+    /// 显示文本
+    pub fn label(&self) -> String {
+        match self {
+            TunnelStatus::Connecting => "连接中".to_string(),
+            TunnelStatus::Connected { local_port } => format!("已连接，经 {}:{} 转发", LOCAL_HOST, local_port),
+            TunnelStatus::Failed(error) => format!("已断开: {}", error),
+        }
+    }
+}
+
+/// 一条经跳板机的本地端口转发，由系统的ssh命令建立，释放时结束ssh进程
+pub struct Tunnel {
+    /// 目标主机:端口
+    pub target: String,
+    pub settings: SshTunnel,
+    pub local_port: u16,
+    child: Child,
+}
+
+impl Tunnel {
+    /// 启动ssh进程转发到目标，本机端口为0时自动选择空闲端口
+    pub fn open(settings: &SshTunnel, target: &str) -> Result<Self> {
+        let bastion = settings.bastion.trim();
+        if bastion.is_empty() {
+            anyhow::bail!("未指定跳板机");
+        }
+        let local_port = match settings.local_port {
+            0 => free_port()?,
+            port => port,
+        };
+        let mut command = Command::new("ssh");
+        command
+            .args(["-N", "-o", "BatchMode=yes", "-o", "ExitOnForwardFailure=yes"])
+            .args(["-o", &format!("ServerAliveInterval={}", SERVER_ALIVE_INTERVAL_SECS)])
+            .args(["-L", &format!("{}:{}:{}", LOCAL_HOST, local_port, target)])
+            .args(["-p", &settings.port.to_string()]);
+        if !settings.ssh_key.trim().is_empty() {
+            command.args(["-i", settings.ssh_key.trim()]);
+        }
+        let child = command
+            .arg(bastion)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("无法执行ssh命令")?;
+        Ok(Self {
+            target: target.to_string(),
+            settings: settings.clone(),
+            local_port,
+            child,
+        })
+    }
+
+    /// 检查ssh进程与本机端口，进程已退出时返回其错误输出
+    pub fn check(&mut self) -> TunnelStatus {
+        match self.child.try_wait() {
+            Ok(Some(status)) => {
+                let mut stderr = String::new();
+                if let Some(mut pipe) = self.child.stderr.take() {
+                    let _ = pipe.read_to_string(&mut stderr);
+                }
+                let reason = stderr.lines().rev().find(|l| !l.trim().is_empty()).unwrap_or("").trim().to_string();
+                TunnelStatus::Failed(if reason.is_empty() { format!("ssh已退出（{}）", status) } else { reason })
+            }
+            Err(e) => TunnelStatus::Failed(format!("无法获取ssh进程状态: {}", e)),
+            Ok(None) => {
+                let address = SocketAddr::from(([127, 0, 0, 1], self.local_port));
+                if TcpStream::connect_timeout(&address, CHECK_TIMEOUT).is_ok() {
+                    TunnelStatus::Connected { local_port: self.local_port }
+                } else {
+                    TunnelStatus::Connecting
+                }
+            }
+        }
+    }
+}
+
+impl Drop for Tunnel {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// 由系统分配一个本机空闲端口
+fn free_port() -> Result<u16> {
+    let listener = TcpListener::bind((LOCAL_HOST, 0)).context("无法分配本机端口")?;
+    Ok(listener.local_addr()?.port())
+}
@@ -0,0 +1,729 @@
+use anyhow::Result;
+use std::fmt::Write;
+
+use crate::models::{BackendContainer, MiddlewareContainer, ProbeKind, SchedulerStrategy};
+
+/// nginx与HAProxy的默认权重为1，本程序的默认权重为100，导入时按比例换算
+const WEIGHT_SCALE: u32 = 100;
+/// HAProxy允许的最大权重
+const HAPROXY_MAX_WEIGHT: u32 = 256;
+/// HAProxy段中结束上一段的关键字
+const HAPROXY_SECTIONS: [&str; 13] = [
+    "global", "defaults", "frontend", "backend", "listen", "resolvers", "peers",
+    "userlist", "mailers", "program", "http-errors", "cache", "ring",
+];
+
+/// 可导入的负载均衡配置格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpstreamFormat {
+    /// nginx的 `upstream` 块
+    Nginx,
+    /// HAProxy的 `backend` 与 `listen` 段
+    Haproxy,
+}
+
+impl UpstreamFormat {
+    pub const ALL: [UpstreamFormat; 2] = [UpstreamFormat::Nginx, UpstreamFormat::Haproxy];
+
+    /// 显示名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            UpstreamFormat::Nginx => "nginx upstream",
+            UpstreamFormat::Haproxy => "HAProxy backend",
+        }
+    }
+
+    /// 导出文件的扩展名
+    pub fn extension(&self) -> &'static str {
+        match self {
+            UpstreamFormat::Nginx => "conf",
+            UpstreamFormat::Haproxy => "cfg",
+        }
+    }
+
+    /// 按中间层的后端与调度策略生成配置片段：单容器模式下其余实例为备用服务器，
+    /// 读写分离模式生成读、写两组并按请求方法分流，负载均衡模式按权重轮询
+    pub fn export(&self, middleware: &MiddlewareContainer) -> String {
+        let name = upstream_name(middleware);
+        let strategy = &middleware.config.crud_api.strategy;
+        let servers = export_servers(middleware);
+        let mut out = String::new();
+        let _ = writeln!(out, "# 由 encryption-service 导出：中间层 {}，{}", middleware.name, strategy.label());
+        let _ = writeln!(out, "# {}", strategy.description());
+        let pools: Vec<(String, Vec<&ExportServer>)> = match strategy {
+            SchedulerStrategy::ReadWriteSplit => vec![
+                (format!("{}_read", name), servers.iter().filter(|s| s.instance_type != "write").collect()),
+                (format!("{}_write", name), servers.iter().filter(|s| s.instance_type != "read").collect()),
+            ],
+            _ => vec![(name.clone(), servers.iter().collect())],
+        };
+        let single = *strategy == SchedulerStrategy::Single;
+        match self {
+            UpstreamFormat::Nginx => export_nginx(&mut out, middleware, &name, &pools, single),
+            UpstreamFormat::Haproxy => export_haproxy(&mut out, middleware, &name, &pools, single),
+        }
+        out
+    }
+
+    /// 读取配置中的所有上游服务器组
+    pub fn parse(&self, content: &str) -> Result<Vec<UpstreamBlock>> {
+        let blocks = match self {
+            UpstreamFormat::Nginx => parse_nginx(content)?,
+            UpstreamFormat::Haproxy => parse_haproxy(content),
+        };
+        if blocks.is_empty() {
+            anyhow::bail!("配置中没有{}", match self {
+                UpstreamFormat::Nginx => "upstream块",
+                UpstreamFormat::Haproxy => "backend或listen段",
+            });
+        }
+        Ok(blocks)
+    }
+}
+
+/// 一组上游服务器：nginx的upstream块或HAProxy的backend、listen段
+#[derive(Debug, Clone)]
+pub struct UpstreamBlock {
+    pub name: String,
+    pub servers: Vec<UpstreamServer>,
+    /// 无法导入的服务器及原因
+    pub skipped: Vec<String>,
+}
+
+/// 上游服务器
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpstreamServer {
+    /// HAProxy中的服务器名称，nginx中为地址
+    pub name: String,
+    pub url: String,
+    /// 已换算为本程序的权重
+    pub weight: u32,
+    /// 仅在其他服务器不可用时使用
+    pub backup: bool,
+    /// 已标记为停用
+    pub disabled: bool,
+}
+
+impl UpstreamServer {
+    /// 转换为中间层下由外部管理的后端，超时与重试沿用中间层的设置
+    pub fn to_backend(&self, middleware: &MiddlewareContainer) -> BackendContainer {
+        BackendContainer {
+            name: self.name.clone(),
+            url: self.url.clone(),
+            instance_type: "mixed".to_string(),
+            timeout: middleware.config.crud_api.timeout,
+            retries: middleware.config.crud_api.retries,
+            weight: self.weight,
+            ..BackendContainer::default()
+        }
+    }
+}
+
+/// 拆分 `主机[:端口]` 形式的地址，IPv6地址须带方括号
+fn split_address(address: &str) -> Option<(&str, Option<u16>)> {
+    if let Some(rest) = address.strip_prefix('[') {
+        let (host, rest) = rest.split_once(']')?;
+        return match rest.strip_prefix(':') {
+            Some(port) => Some((host, Some(port.parse().ok()?))),
+            None if rest.is_empty() => Some((host, None)),
+            None => None,
+        };
+    }
+    match address.rsplit_once(':') {
+        Some((host, port)) if !host.contains(':') => Some((host, Some(port.parse().ok()?))),
+        Some(_) => None,
+        None => Some((address, None)),
+    }
+}
+
+fn server_url(scheme: &str, host: &str, port: u16) -> String {
+    if host.contains(':') {
+        format!("{}://[{}]:{}", scheme, host, port)
+    } else {
+        format!("{}://{}:{}", scheme, host, port)
+    }
+}
+
+/// nginx配置的词：普通词、`{`、`}` 与 `;`，注释已去除
+fn nginx_words(content: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    for line in content.lines() {
+        let line = line.split('#').next().unwrap_or_default();
+        let mut word = String::new();
+        for c in line.chars() {
+            match c {
+                '{' | '}' | ';' => {
+                    if !word.is_empty() {
+                        words.push(std::mem::take(&mut word));
+                    }
+                    words.push(c.to_string());
+                }
+                c if c.is_whitespace() => {
+                    if !word.is_empty() {
+                        words.push(std::mem::take(&mut word));
+                    }
+                }
+                c => word.push(c),
+            }
+        }
+        if !word.is_empty() {
+            words.push(word);
+        }
+    }
+    words
+}
+
+/// 按 `;` 与花括号将词组成语句，返回（语句, 语句后是否开始块）
+fn nginx_statements(words: &[String]) -> Vec<(Vec<&str>, Option<char>)> {
+    let mut statements = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    for word in words {
+        match word.as_str() {
+            ";" => statements.push((std::mem::take(&mut current), None)),
+            "{" => statements.push((std::mem::take(&mut current), Some('{'))),
+            "}" => {
+                if !current.is_empty() {
+                    statements.push((std::mem::take(&mut current), None));
+                }
+                statements.push((Vec::new(), Some('}')));
+            }
+            word => current.push(word),
+        }
+    }
+    statements
+}
+
+/// 被 `proxy_pass https://名称` 引用的upstream名称；导出的片段把引用写在注释中，注释中的引用同样识别
+fn nginx_https_targets(content: &str) -> Vec<&str> {
+    let words: Vec<&str> = content
+        .split(|c: char| c.is_whitespace() || matches!(c, ';' | '{' | '}' | '#'))
+        .filter(|w| !w.is_empty())
+        .collect();
+    words
+        .windows(2)
+        .filter(|pair| pair[0] == "proxy_pass")
+        .filter_map(|pair| pair[1].strip_prefix("https://"))
+        .map(|target| target.split('/').next().unwrap_or(target))
+        .collect()
+}
+
+/// 读取nginx配置中的upstream块；被 `proxy_pass https://名称` 引用的块按HTTPS访问
+fn parse_nginx(content: &str) -> Result<Vec<UpstreamBlock>> {
+    let words = nginx_words(content);
+    let statements = nginx_statements(&words);
+    let https = nginx_https_targets(content);
+
+    let mut blocks = Vec::new();
+    let mut depth = 0usize;
+    let mut current: Option<(usize, UpstreamBlock)> = None;
+    for (statement, brace) in &statements {
+        match brace {
+            Some('{') => {
+                depth += 1;
+                if statement.first() == Some(&"upstream") {
+                    let Some(name) = statement.get(1) else {
+                        anyhow::bail!("upstream块缺少名称");
+                    };
+                    current = Some((depth, UpstreamBlock { name: name.to_string(), servers: Vec::new(), skipped: Vec::new() }));
+                }
+                continue;
+            }
+            Some(_) => {
+                if let Some((block_depth, block)) = current.take() {
+                    if block_depth == depth {
+                        blocks.push(block);
+                    } else {
+                        current = Some((block_depth, block));
+                    }
+                }
+                depth = depth.checked_sub(1).ok_or_else(|| anyhow::anyhow!("nginx配置中的花括号不匹配"))?;
+                continue;
+            }
+            None => {}
+        }
+        let Some((_, block)) = current.as_mut() else {
+            continue;
+        };
+        if statement.first() != Some(&"server") {
+            continue;
+        }
+        let Some(address) = statement.get(1) else {
+            block.skipped.push("server 语句缺少地址".to_string());
+            continue;
+        };
+        if address.starts_with("unix:") {
+            block.skipped.push(format!("{}: 不支持Unix套接字", address));
+            continue;
+        }
+        let Some((host, port)) = split_address(address) else {
+            block.skipped.push(format!("{}: 地址无法解析", address));
+            continue;
+        };
+        let scheme = if https.contains(&block.name.as_str()) { "https" } else { "http" };
+        let mut server = UpstreamServer {
+            name: address.to_string(),
+            url: server_url(scheme, host, port.unwrap_or(if scheme == "https" { 443 } else { 80 })),
+            weight: WEIGHT_SCALE,
+            backup: false,
+            disabled: false,
+        };
+        for param in &statement[2..] {
+            match param.split_once('=') {
+                Some(("weight", value)) => match value.parse::<u32>() {
+                    Ok(weight) => server.weight = weight.saturating_mul(WEIGHT_SCALE),
+                    Err(_) => block.skipped.push(format!("{}: 权重无效 {}，按默认权重导入", address, value)),
+                },
+                None if *param == "backup" => server.backup = true,
+                None if *param == "down" => server.disabled = true,
+                _ => {}
+            }
+        }
+        block.servers.push(server);
+    }
+    if current.is_some() || depth != 0 {
+        anyhow::bail!("nginx配置中的花括号不匹配");
+    }
+    Ok(blocks)
+}
+
+/// 读取HAProxy配置中的backend与listen段，同一段中 `default-server` 的权重与ssl设置作用于其后的服务器
+fn parse_haproxy(content: &str) -> Vec<UpstreamBlock> {
+    let mut blocks = Vec::new();
+    let mut current: Option<UpstreamBlock> = None;
+    let mut defaults: Vec<String> = Vec::new();
+    for line in content.lines() {
+        let line = line.split('#').next().unwrap_or_default();
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let Some(keyword) = words.first() else {
+            continue;
+        };
+        if HAPROXY_SECTIONS.contains(keyword) {
+            blocks.extend(current.take());
+            defaults.clear();
+            if matches!(*keyword, "backend" | "listen") {
+                let name = words.get(1).copied().unwrap_or(*keyword);
+                current = Some(UpstreamBlock { name: name.to_string(), servers: Vec::new(), skipped: Vec::new() });
+            }
+            continue;
+        }
+        let Some(block) = current.as_mut() else {
+            continue;
+        };
+        match *keyword {
+            "default-server" => defaults = words[1..].iter().map(|w| w.to_string()).collect(),
+            "server-template" => block.skipped.push(format!("{}: 不支持server-template", words.get(1).unwrap_or(&""))),
+            "server" => {
+                let (Some(name), Some(address)) = (words.get(1), words.get(2)) else {
+                    block.skipped.push(format!("{}: server 语句缺少地址", line.trim()));
+                    continue;
+                };
+                let params: Vec<&str> = defaults.iter().map(String::as_str).chain(words[3..].iter().copied()).collect();
+                // 去掉 `ipv4@` 等地址族前缀
+                let address = address.rsplit('@').next().unwrap_or(address);
+                let Some((host, port)) = split_address(address).filter(|(host, _)| !host.is_empty()) else {
+                    block.skipped.push(format!("{}: 地址无法解析 {}", name, address));
+                    continue;
+                };
+                // 未写端口或端口为偏移量时沿用客户端连接的端口，无法确定实际端口
+                let Some(port) = port else {
+                    block.skipped.push(format!("{}: 地址 {} 未指定端口", name, address));
+                    continue;
+                };
+                let ssl = params.contains(&"ssl") && !params.contains(&"no-ssl");
+                let mut server = UpstreamServer {
+                    name: name.to_string(),
+                    url: server_url(if ssl { "https" } else { "http" }, host, port),
+                    weight: WEIGHT_SCALE,
+                    backup: false,
+                    disabled: false,
+                };
+                let mut params = params.iter();
+                while let Some(param) = params.next() {
+                    match *param {
+                        "weight" => match params.next().map(|w| w.parse::<u32>()) {
+                            Some(Ok(weight)) => server.weight = weight.saturating_mul(WEIGHT_SCALE),
+                            _ => block.skipped.push(format!("{}: 权重无效，按默认权重导入", name)),
+                        },
+                        "backup" => server.backup = true,
+                        "disabled" => server.disabled = true,
+                        "enabled" => server.disabled = false,
+                        _ => {}
+                    }
+                }
+                block.servers.push(server);
+            }
+            _ => {}
+        }
+    }
+    blocks.extend(current);
+    blocks
+}
+
+/// 导出的一个后端实例，副本各自成为一个服务器
+struct ExportServer {
+    name: String,
+    host: String,
+    port: u16,
+    https: bool,
+    instance_type: String,
+    weight: u32,
+}
+
+/// 上游名称，只保留字母数字与下划线；名称可能全是中文，因此附加ID前缀保证唯一
+fn upstream_name(middleware: &MiddlewareContainer) -> String {
+    let slug: String = middleware.name
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    let mut parts: Vec<String> = slug.split('_').filter(|s| !s.is_empty()).map(str::to_string).collect();
+    parts.push(middleware.id.chars().filter(char::is_ascii_alphanumeric).take(8).collect());
+    parts.join("_")
+}
+
+/// 中间层实例列表中的服务器，权重按最大公约数约简；地址无法解析的实例跳过
+fn export_servers(middleware: &MiddlewareContainer) -> Vec<ExportServer> {
+    let mut servers: Vec<ExportServer> = middleware
+        .crud_instances()
+        .into_iter()
+        .filter_map(|instance| {
+            let url = reqwest::Url::parse(&instance.url).ok()?;
+            let name = middleware.backend_containers.iter().find_map(|b| {
+                (0..b.replicas.max(1))
+                    .find(|&index| b.replica_id(index) == instance.id)
+                    .map(|index| if index == 0 { b.name.clone() } else { format!("{}-{}", b.name, index + 1) })
+            });
+            Some(ExportServer {
+                name: name.unwrap_or(instance.id),
+                host: url.host_str()?.to_string(),
+                port: url.port_or_known_default()?,
+                https: url.scheme() == "https",
+                instance_type: instance.instance_type,
+                weight: instance.weight,
+            })
+        })
+        .collect();
+    let divisor = servers.iter().map(|s| s.weight).filter(|&w| w > 0).fold(0, gcd);
+    if divisor > 1 {
+        servers.iter_mut().for_each(|s| s.weight /= divisor);
+    }
+    servers
+}
+
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+/// 配置中使用的服务器地址，IPv6地址加方括号
+fn export_address(server: &ExportServer) -> String {
+    if server.host.contains(':') && !server.host.starts_with('[') {
+        format!("[{}]:{}", server.host, server.port)
+    } else {
+        format!("{}:{}", server.host, server.port)
+    }
+}
+
+/// HAProxy的服务器名称不能包含空白
+fn haproxy_server_name(name: &str) -> String {
+    name.chars().map(|c| if c.is_whitespace() { '_' } else { c }).collect()
+}
+
+fn export_nginx(out: &mut String, middleware: &MiddlewareContainer, name: &str, pools: &[(String, Vec<&ExportServer>)], single: bool) {
+    let crud_api = &middleware.config.crud_api;
+    for (pool, servers) in pools {
+        let _ = writeln!(out, "upstream {} {{", pool);
+        if servers.is_empty() {
+            let _ = writeln!(out, "    # 没有可用的后端实例");
+        }
+        for (index, server) in servers.iter().enumerate() {
+            let mut line = format!("    server {}", export_address(server));
+            match server.weight {
+                0 => line.push_str(" down"),
+                1 => {}
+                weight => {
+                    let _ = write!(line, " weight={}", weight);
+                }
+            }
+            let _ = write!(line, " max_fails={} fail_timeout={}s", crud_api.retries.max(1), crud_api.health_check_interval.max(1));
+            if single && index > 0 {
+                line.push_str(" backup");
+            }
+            let _ = writeln!(out, "{}; # {}", line, server.name);
+        }
+        let _ = writeln!(out, "}}");
+        let _ = writeln!(out);
+    }
+    let scheme = if pools.iter().flat_map(|(_, s)| s).any(|s| s.https) { "https" } else { "http" };
+    let target = match pools {
+        [(pool, _)] => pool.clone(),
+        _ => {
+            let _ = writeln!(out, "# 读请求（GET、HEAD）发往读实例组，其余请求发往写实例组");
+            let _ = writeln!(out, "map $request_method ${}_pool {{", name);
+            let _ = writeln!(out, "    default {}_write;", name);
+            let _ = writeln!(out, "    GET {}_read;", name);
+            let _ = writeln!(out, "    HEAD {}_read;", name);
+            let _ = writeln!(out, "}}");
+            let _ = writeln!(out);
+            format!("${}_pool", name)
+        }
+    };
+    let _ = writeln!(out, "# 在 server 块中引用：");
+    let _ = writeln!(out, "# location / {{");
+    let _ = writeln!(out, "#     proxy_pass {}://{};", scheme, target);
+    let _ = writeln!(out, "#     proxy_read_timeout {}ms;", crud_api.timeout);
+    let _ = writeln!(out, "#     proxy_next_upstream_tries {};", crud_api.retries + 1);
+    let _ = writeln!(out, "# }}");
+}
+
+fn export_haproxy(out: &mut String, middleware: &MiddlewareContainer, name: &str, pools: &[(String, Vec<&ExportServer>)], single: bool) {
+    let crud_api = &middleware.config.crud_api;
+    let health = middleware.backend_containers
+        .iter()
+        .map(|b| &b.health_probe)
+        .find(|p| p.enabled && p.kind == ProbeKind::Http);
+    // HAProxy的权重上限为256，超出时按比例缩小
+    let max = pools.iter().flat_map(|(_, s)| s).map(|s| s.weight).max().unwrap_or(0);
+    let scale = |weight: u32| match weight {
+        0 => 0,
+        w if max > HAPROXY_MAX_WEIGHT => ((w as u64 * HAPROXY_MAX_WEIGHT as u64 / max as u64) as u32).max(1),
+        w => w,
+    };
+    for (pool, servers) in pools {
+        let _ = writeln!(out, "backend {}", pool);
+        let _ = writeln!(out, "    balance roundrobin");
+        let _ = writeln!(out, "    timeout server {}ms", crud_api.timeout);
+        let _ = writeln!(out, "    retries {}", crud_api.retries);
+        let _ = writeln!(out, "    option redispatch");
+        if let Some(probe) = health {
+            let _ = writeln!(out, "    option httpchk GET {}", probe.path);
+            let _ = writeln!(out, "    default-server inter {}s fall {}", probe.interval_secs.max(1), probe.failure_threshold.max(1));
+        }
+        if servers.is_empty() {
+            let _ = writeln!(out, "    # 没有可用的后端实例");
+        }
+        for (index, server) in servers.iter().enumerate() {
+            let mut line = format!("    server {} {} weight {}", haproxy_server_name(&server.name), export_address(server), scale(server.weight));
+            if health.is_some() {
+                line.push_str(" check");
+            }
+            if server.https {
+                line.push_str(" ssl");
+            }
+            if single && index > 0 {
+                line.push_str(" backup");
+            }
+            let _ = writeln!(out, "{}", line);
+        }
+        let _ = writeln!(out);
+    }
+    if pools.len() > 1 {
+        let _ = writeln!(out, "# 在 frontend 中按请求方法分流，读请求（GET、HEAD）发往读实例组：");
+        let _ = writeln!(out, "#     acl {}_read_method method GET HEAD", name);
+        let _ = writeln!(out, "#     use_backend {}_read if {}_read_method", name, name);
+        let _ = writeln!(out, "#     default_backend {}_write", name);
+    } else {
+        let _ = writeln!(out, "# 在 frontend 中引用：");
+        let _ = writeln!(out, "#     default_backend {}", name);
+    }
+    if pools.iter().flat_map(|(_, s)| s).any(|s| s.https) {
+        let _ = writeln!(out, "# 后端使用HTTPS，需在 global 中配置 ca-base 或为服务器指定 ca-file 以校验证书");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::RuntimeKind;
+
+    fn urls(block: &UpstreamBlock) -> Vec<&str> {
+        block.servers.iter().map(|s| s.url.as_str()).collect()
+    }
+
+    #[test]
+    fn nginx_import_reads_weights_and_flags() {
+        let conf = r#"
+# 全局注释
+upstream api {
+    server 10.0.0.1:8000 weight=3;   # 主实例
+    server 10.0.0.2:8000 backup;
+    server [::1]:8001 down;
+    # server 10.0.0.9:8000;
+    server 10.0.0.3;
+}
+server {
+    location / { proxy_pass https://api/v1; }
+}
+"#;
+        let blocks = UpstreamFormat::Nginx.parse(conf).unwrap();
+        assert_eq!(blocks.len(), 1);
+        let block = &blocks[0];
+        assert_eq!(block.name, "api");
+        assert_eq!(urls(block), ["https://10.0.0.1:8000", "https://10.0.0.2:8000", "https://[::1]:8001", "https://10.0.0.3:443"]);
+        assert_eq!(block.servers.iter().map(|s| s.weight).collect::<Vec<_>>(), [300, 100, 100, 100]);
+        assert!(block.servers[1].backup && !block.servers[1].disabled);
+        assert!(block.servers[2].disabled && !block.servers[2].backup);
+        assert!(block.skipped.is_empty());
+    }
+
+    #[test]
+    fn nginx_import_skips_malformed_servers() {
+        let conf = "upstream api {\n    server unix:/tmp/api.sock;\n    server 10.0.0.1:http;\n    server 10.0.0.2:8000 weight=heavy;\n    server;\n}\n";
+        let block = &UpstreamFormat::Nginx.parse(conf).unwrap()[0];
+        // 权重无效的服务器按默认权重导入并记录原因
+        assert_eq!(urls(block), ["http://10.0.0.2:8000"]);
+        assert_eq!(block.servers[0].weight, WEIGHT_SCALE);
+        assert_eq!(block.skipped.len(), 4);
+
+        assert!(UpstreamFormat::Nginx.parse("upstream api {\n    server 10.0.0.1:8000;\n").is_err());
+        assert!(UpstreamFormat::Nginx.parse("upstream api {\n}\n}\n").is_err());
+        assert!(UpstreamFormat::Nginx.parse("upstream {\n}\n").is_err());
+        assert!(UpstreamFormat::Nginx.parse("# upstream api { server a:1; }\nhttp {\n}\n").is_err());
+    }
+
+    #[test]
+    fn haproxy_import_reads_sections_and_defaults() {
+        let conf = r#"
+global
+    maxconn 100
+backend api   # 注释
+    default-server weight 2
+    server s1 10.0.0.1:8000 check
+    server s2 10.0.0.2:8000 weight 5 backup
+    server s3 10.0.0.3:8443 ssl disabled
+    #server s4 10.0.0.4:8000
+frontend fe
+    server ignored 10.0.0.9:80
+listen stats
+    server s5 ipv4@10.0.0.5:9000
+"#;
+        let blocks = UpstreamFormat::Haproxy.parse(conf).unwrap();
+        assert_eq!(blocks.iter().map(|b| b.name.as_str()).collect::<Vec<_>>(), ["api", "stats"]);
+        let api = &blocks[0];
+        assert_eq!(urls(api), ["http://10.0.0.1:8000", "http://10.0.0.2:8000", "https://10.0.0.3:8443"]);
+        assert_eq!(api.servers.iter().map(|s| s.weight).collect::<Vec<_>>(), [200, 500, 200]);
+        assert!(api.servers[1].backup);
+        assert!(api.servers[2].disabled);
+        assert_eq!(urls(&blocks[1]), ["http://10.0.0.5:9000"]);
+        // default-server 只作用于所在的段
+        assert_eq!(blocks[1].servers[0].weight, WEIGHT_SCALE);
+    }
+
+    #[test]
+    fn haproxy_import_skips_malformed_servers() {
+        let conf = "backend api\n    server s1\n    server s2 10.0.0.2\n    server s3 10.0.0.3:8000 weight\n    server-template web 3 10.0.0.4:80\n";
+        let block = &UpstreamFormat::Haproxy.parse(conf).unwrap()[0];
+        assert_eq!(urls(block), ["http://10.0.0.3:8000"]);
+        assert_eq!(block.skipped.len(), 4);
+
+        assert!(UpstreamFormat::Haproxy.parse("global\n    maxconn 100\nfrontend fe\n    bind :80\n").is_err());
+    }
+
+    fn backend(id: &str, url: &str, instance_type: &str, weight: u32, replicas: u32) -> BackendContainer {
+        BackendContainer {
+            id: id.to_string(),
+            name: id.to_string(),
+            url: url.to_string(),
+            instance_type: instance_type.to_string(),
+            weight,
+            replicas,
+            ..BackendContainer::default()
+        }
+    }
+
+    fn middleware(strategy: SchedulerStrategy) -> MiddlewareContainer {
+        let mut middleware = MiddlewareContainer {
+            id: "mw-0001".to_string(),
+            name: "支付 API".to_string(),
+            runtime: RuntimeKind::Docker,
+            backend_containers: vec![
+                backend("b1", "http://10.0.0.1:8000", "write", 100, 1),
+                backend("b2", "http://10.0.0.2:8000", "read", 300, 2),
+                backend("b3", "http://[fd00::3]:8000", "read", 0, 1),
+            ],
+            ..MiddlewareContainer::default()
+        };
+        middleware.config.crud_api.strategy = strategy;
+        middleware
+    }
+
+    /// 导入后的服务器：（地址, 权重, 备用, 停用）
+    type Server = (String, u32, bool, bool);
+
+    /// 导出后再导入，返回各组的名称与服务器
+    fn round_trip(format: UpstreamFormat, middleware: &MiddlewareContainer) -> Vec<(String, Vec<Server>)> {
+        let exported = format.export(middleware);
+        let blocks = format.parse(&exported).unwrap();
+        blocks
+            .into_iter()
+            .map(|block| {
+                assert!(block.skipped.is_empty(), "{:?}", block.skipped);
+                let servers = block.servers.into_iter().map(|s| (s.url, s.weight, s.backup, s.disabled)).collect();
+                (block.name, servers)
+            })
+            .collect()
+    }
+
+    fn server(url: &str, weight: u32, backup: bool, disabled: bool) -> Server {
+        (url.to_string(), weight, backup, disabled)
+    }
+
+    #[test]
+    fn nginx_export_round_trips_load_balance() {
+        let blocks = round_trip(UpstreamFormat::Nginx, &middleware(SchedulerStrategy::LoadBalance));
+        assert_eq!(blocks, [("api_mw0001".to_string(), vec![
+            server("http://10.0.0.1:8000", 100, false, false),
+            server("http://10.0.0.2:8000", 300, false, false),
+            server("http://10.0.0.2:8001", 300, false, false),
+            // 权重为0的实例导出为 down
+            server("http://[fd00::3]:8000", 100, false, true),
+        ])]);
+    }
+
+    #[test]
+    fn haproxy_export_round_trips_load_balance() {
+        let middleware = middleware(SchedulerStrategy::LoadBalance);
+        let blocks = round_trip(UpstreamFormat::Haproxy, &middleware);
+        assert_eq!(blocks, [("api_mw0001".to_string(), vec![
+            server("http://10.0.0.1:8000", 100, false, false),
+            server("http://10.0.0.2:8000", 300, false, false),
+            server("http://10.0.0.2:8001", 300, false, false),
+            server("http://[fd00::3]:8000", 0, false, false),
+        ])]);
+        // HAProxy保留服务器名称，副本带序号
+        let names: Vec<String> = UpstreamFormat::Haproxy.parse(&UpstreamFormat::Haproxy.export(&middleware)).unwrap()[0]
+            .servers
+            .iter()
+            .map(|s| s.name.clone())
+            .collect();
+        assert_eq!(names, ["b1", "b2", "b2-2", "b3"]);
+    }
+
+    #[test]
+    fn export_round_trips_read_write_split() {
+        for format in UpstreamFormat::ALL {
+            let blocks = round_trip(format, &middleware(SchedulerStrategy::ReadWriteSplit));
+            let names: Vec<&str> = blocks.iter().map(|(name, _)| name.as_str()).collect();
+            assert_eq!(names, ["api_mw0001_read", "api_mw0001_write"], "{:?}", format);
+            let urls = |servers: &[Server]| servers.iter().map(|s| s.0.clone()).collect::<Vec<_>>();
+            assert_eq!(urls(&blocks[0].1), ["http://10.0.0.2:8000", "http://10.0.0.2:8001", "http://[fd00::3]:8000"]);
+            assert_eq!(urls(&blocks[1].1), ["http://10.0.0.1:8000"]);
+        }
+    }
+
+    #[test]
+    fn export_round_trips_single_with_backups_and_https() {
+        let mut middleware = middleware(SchedulerStrategy::Single);
+        middleware.backend_containers.truncate(2);
+        for backend in &mut middleware.backend_containers {
+            backend.url = backend.url.replace("http://", "https://");
+        }
+        for format in UpstreamFormat::ALL {
+            let blocks = round_trip(format, &middleware);
+            assert_eq!(blocks, [("api_mw0001".to_string(), vec![
+                server("https://10.0.0.1:8000", 100, false, false),
+                server("https://10.0.0.2:8000", 300, true, false),
+                server("https://10.0.0.2:8001", 300, true, false),
+            ])], "{:?}", format);
+        }
+    }
+}
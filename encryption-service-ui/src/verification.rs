@@ -0,0 +1,117 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use std::fmt::Write;
+
+use crate::api::{ApiClient, ApiClientConfig};
+use crate::chunking;
+
+/// 报告中密文的显示长度
+const PREVIEW_LEN: usize = 24;
+
+/// 待校验的解密目标
+#[derive(Debug, Clone)]
+pub struct VerificationTarget {
+    pub name: String,
+    pub config: ApiClientConfig,
+}
+
+/// 单条密文在单个目标上的校验结果
+#[derive(Debug, Clone)]
+pub struct VerificationResult {
+    pub ciphertext: String,
+    pub target: String,
+    /// 解密成功时为明文长度，失败时为错误信息
+    pub outcome: Result<usize, String>,
+}
+
+impl VerificationResult {
+    /// 截断后的密文
+    pub fn preview(&self) -> String {
+        if self.ciphertext.chars().count() > PREVIEW_LEN {
+            format!("{}…", self.ciphertext.chars().take(PREVIEW_LEN).collect::<String>())
+        } else {
+            self.ciphertext.clone()
+        }
+    }
+}
+
+/// 数据完整性校验报告
+#[derive(Debug, Clone)]
+pub struct VerificationReport {
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub results: Vec<VerificationResult>,
+}
+
+impl VerificationReport {
+    /// 通过的数量
+    pub fn passed(&self) -> usize {
+        self.results.iter().filter(|r| r.outcome.is_ok()).count()
+    }
+
+    /// 失败的数量
+    pub fn failed(&self) -> usize {
+        self.results.len() - self.passed()
+    }
+
+    /// 渲染为文本报告
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "数据完整性校验报告");
+        let _ = writeln!(out, "开始时间: {}", self.started_at.format("%Y-%m-%d %H:%M:%S"));
+        let _ = writeln!(out, "结束时间: {}", self.finished_at.format("%Y-%m-%d %H:%M:%S"));
+        let _ = writeln!(out, "通过: {}  失败: {}", self.passed(), self.failed());
+        let _ = writeln!(out);
+        for result in &self.results {
+            match &result.outcome {
+                Ok(len) => {
+                    let _ = writeln!(out, "[通过] {} @ {} (明文 {} 字节)", result.preview(), result.target, len);
+                }
+                Err(e) => {
+                    let _ = writeln!(out, "[失败] {} @ {}: {}", result.preview(), result.target, e);
+                }
+            }
+        }
+        out
+    }
+}
+
+/// 解析密文输入，每行一条，忽略空行与 `#` 开头的注释
+pub fn parse_ciphertexts(text: &str) -> Vec<String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+/// 从文件读取密文
+pub fn load_ciphertexts(path: &str) -> Result<Vec<String>> {
+    let text = std::fs::read_to_string(path).context(format!("无法读取密文文件: {}", path))?;
+    Ok(parse_ciphertexts(&text))
+}
+
+/// 在每个目标上逐条解密密文，分块密文按清单逐块解密
+pub async fn verify(targets: &[VerificationTarget], ciphertexts: &[String]) -> VerificationReport {
+    let started_at = Utc::now();
+    let mut results = Vec::new();
+    for target in targets {
+        let client = ApiClient::new(target.config.clone());
+        for ciphertext in ciphertexts {
+            let outcome = match &client {
+                Ok(client) => chunking::decrypt(client, ciphertext, |_, _| {}).await.map(|plain| plain.len()).map_err(|e| format!("{:#}", e)),
+                Err(e) => Err(format!("{:#}", e)),
+            };
+            results.push(VerificationResult {
+                ciphertext: ciphertext.clone(),
+                target: target.name.clone(),
+                outcome,
+            });
+        }
+    }
+    VerificationReport {
+        started_at,
+        finished_at: Utc::now(),
+        results,
+    }
+}
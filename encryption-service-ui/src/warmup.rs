@@ -0,0 +1,62 @@
+use anyhow::Result;
+use std::time::{Duration, Instant};
+
+use crate::api::{ApiClient, ApiClientConfig};
+use crate::models::{HealthStatus, MiddlewareContainer, NetworkProfile};
+
+/// 往返测试使用的样例数据
+const ROUND_TRIP_PAYLOAD: &str = "warmup-probe";
+
+/// 对刚启动的中间层执行预热检查，成功时返回预热耗时
+///
+/// 需要连续若干次健康检查通过，中途失败会重新计数；
+/// 启用往返测试时还要求加密后解密能得到原文。
+pub async fn run(middleware: &MiddlewareContainer, network: Option<NetworkProfile>) -> Result<Duration> {
+    let warmup = &middleware.warmup;
+    let client = ApiClient::new(ApiClientConfig {
+        base_url: middleware.api_base_url(),
+        timeout: middleware.config.crud_api.timeout,
+        network,
+        trace_id: None,
+    })?;
+
+    let started = Instant::now();
+    let deadline = Duration::from_secs(warmup.timeout_secs);
+    let mut successes = 0;
+    let mut last_error = String::from("健康检查未通过");
+    while successes < warmup.required_successes {
+        if started.elapsed() >= deadline {
+            anyhow::bail!(
+                "预热超时（{} 秒内仅连续健康 {}/{} 次）: {}",
+                warmup.timeout_secs,
+                successes,
+                warmup.required_successes,
+                last_error,
+            );
+        }
+        match client.health_check().await {
+            Ok(HealthStatus::Healthy) => successes += 1,
+            Ok(health) => {
+                successes = 0;
+                last_error = format!("健康状态为{}", health.label());
+            }
+            Err(e) => {
+                successes = 0;
+                last_error = format!("{:#}", e);
+            }
+        }
+        if successes < warmup.required_successes {
+            tokio::time::sleep(Duration::from_millis(warmup.probe_interval_ms)).await;
+        }
+    }
+
+    if warmup.round_trip {
+        let encrypted = client.encrypt(ROUND_TRIP_PAYLOAD).await?;
+        let decrypted = client.decrypt(&encrypted).await?;
+        if decrypted != ROUND_TRIP_PAYLOAD {
+            anyhow::bail!("往返测试失败: 解密结果与原文不一致");
+        }
+    }
+
+    Ok(started.elapsed())
+}
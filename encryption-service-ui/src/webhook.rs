@@ -0,0 +1,233 @@
+use anyhow::Result;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::Arc;
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use crate::config::ConfigManager;
+use crate::models::Webhook;
+
+/// 携带签名的请求头，取值为 `sha256=<十六进制HMAC>`
+pub const SIGNATURE_HEADER: &str = "X-Signature-256";
+/// 携带请求时间的请求头，取值为Unix时间戳（秒），与请求体一同签名
+pub const TIMESTAMP_HEADER: &str = "X-Signature-Timestamp";
+/// Webhook路径前缀，完整路径为 `/hooks/<id>`
+pub const PATH_PREFIX: &str = "/hooks/";
+/// 请求体大小上限
+const MAX_BODY_BYTES: usize = 64 * 1024;
+/// 请求时间与本机时间允许的最大偏差（秒），超出的请求视为过期；窗口内重复的签名视为重放
+const MAX_SKEW_SECS: i64 = 300;
+
+/// 监听线程收到的Webhook请求
+#[derive(Debug, Clone)]
+pub enum WebhookEvent {
+    /// 签名校验通过，需要执行操作
+    Triggered { hook: Webhook, remote: String },
+    /// 请求被拒绝
+    Rejected { hook_id: String, remote: String, reason: String },
+}
+
+/// 以密钥对 `<时间戳>.<请求体>` 计算HMAC
+fn mac(secret: &str, timestamp: &str, body: &[u8]) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC接受任意长度的密钥");
+    mac.update(timestamp.as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    mac
+}
+
+/// 计算请求的签名，时间戳与 [`TIMESTAMP_HEADER`] 中的相同
+pub fn sign(secret: &str, timestamp: &str, body: &[u8]) -> String {
+    format!("sha256={}", hex::encode(mac(secret, timestamp, body).finalize().into_bytes()))
+}
+
+/// 以常量时间校验签名
+pub fn verify(secret: &str, timestamp: &str, body: &[u8], signature: &str) -> bool {
+    let Some(expected) = signature.strip_prefix("sha256=").and_then(|hex| hex::decode(hex).ok()) else {
+        return false;
+    };
+    mac(secret, timestamp, body).verify_slice(&expected).is_ok()
+}
+
+/// 解析请求时间戳，与本机时间相差超过 [`MAX_SKEW_SECS`] 时视为过期
+fn fresh_timestamp(timestamp: &str, now: i64) -> Option<i64> {
+    timestamp.trim().parse::<i64>().ok().filter(|sent| (now - sent).abs() <= MAX_SKEW_SECS)
+}
+
+/// 最近收到的有效签名及其请求时间，用于拒绝重放；早于时间窗口的签名会因过期被拒绝，不再保留
+#[derive(Default)]
+struct SeenSignatures(HashMap<String, i64>);
+
+impl SeenSignatures {
+    /// 记录签名，已见过时返回false；十六进制不区分大小写，按小写比较
+    fn insert(&mut self, signature: &str, timestamp: i64, now: i64) -> bool {
+        self.0.retain(|_, seen| now - *seen <= MAX_SKEW_SECS);
+        self.0.insert(signature.trim().to_ascii_lowercase(), timestamp).is_none()
+    }
+}
+
+/// Webhook监听服务，在后台线程接收请求，由界面线程收取并执行操作
+pub struct WebhookServer {
+    server: Arc<tiny_http::Server>,
+    listen: String,
+    receiver: Receiver<WebhookEvent>,
+}
+
+impl WebhookServer {
+    /// 在指定地址启动监听，每次请求时从配置读取最新的Webhook列表
+    pub fn start(listen: &str, config_manager: ConfigManager) -> Result<Self> {
+        let server = tiny_http::Server::http(listen)
+            .map_err(|e| anyhow::anyhow!("无法监听 {}: {}", listen, e))?;
+        let server = Arc::new(server);
+        let (sender, receiver) = mpsc::channel();
+
+        let worker = server.clone();
+        std::thread::spawn(move || {
+            let mut seen = SeenSignatures::default();
+            // unblock 后 recv 返回错误，线程随之退出
+            while let Ok(request) = worker.recv() {
+                handle(request, &config_manager, &sender, &mut seen);
+            }
+        });
+
+        Ok(Self {
+            server,
+            listen: listen.to_string(),
+            receiver,
+        })
+    }
+
+    /// 监听地址
+    pub fn listen(&self) -> &str {
+        &self.listen
+    }
+
+    /// 收取已收到的请求
+    pub fn poll(&self) -> Vec<WebhookEvent> {
+        self.receiver.try_iter().collect()
+    }
+}
+
+impl Drop for WebhookServer {
+    fn drop(&mut self) {
+        self.server.unblock();
+    }
+}
+
+/// 读取请求头的值
+fn header(request: &tiny_http::Request, name: &'static str) -> Option<String> {
+    request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv(name))
+        .map(|h| h.value.as_str().to_string())
+}
+
+/// 读取请求体，超过上限时返回 `Ok(None)`，不截断
+fn read_body(request: &mut tiny_http::Request) -> std::io::Result<Option<Vec<u8>>> {
+    if request.body_length().is_some_and(|len| len > MAX_BODY_BYTES) {
+        return Ok(None);
+    }
+    let mut body = Vec::new();
+    request.as_reader().take(MAX_BODY_BYTES as u64 + 1).read_to_end(&mut body)?;
+    Ok((body.len() <= MAX_BODY_BYTES).then_some(body))
+}
+
+/// 处理单个请求并回复
+fn handle(mut request: tiny_http::Request, config_manager: &ConfigManager, sender: &Sender<WebhookEvent>, seen: &mut SeenSignatures) {
+    let remote = request.remote_addr().map(|a| a.to_string()).unwrap_or_default();
+    // 查询参数与片段不属于ID
+    let path = request.url().split(['?', '#']).next().unwrap_or_default();
+    let Some(hook_id) = path.strip_prefix(PATH_PREFIX).map(str::to_string) else {
+        let _ = request.respond(tiny_http::Response::from_string("not found").with_status_code(404));
+        return;
+    };
+    let reject = |reason: &str| WebhookEvent::Rejected {
+        hook_id: hook_id.clone(),
+        remote: remote.clone(),
+        reason: reason.to_string(),
+    };
+
+    let (status, event) = if *request.method() != tiny_http::Method::Post {
+        (405, reject("请求方法不是POST"))
+    } else {
+        let signature = header(&request, SIGNATURE_HEADER);
+        let timestamp = header(&request, TIMESTAMP_HEADER);
+        let body = read_body(&mut request);
+        let hook = config_manager
+            .load_config()
+            .ok()
+            .and_then(|config| config.webhooks.into_iter().find(|h| h.id == hook_id));
+        let now = Utc::now().timestamp();
+
+        match (hook, signature, timestamp, body) {
+            (.., Err(_)) => (400, reject("无法读取请求体")),
+            (.., Ok(None)) => (413, reject(&format!("请求体超过 {} 字节", MAX_BODY_BYTES))),
+            (None, ..) => (404, reject("Webhook不存在")),
+            (Some(hook), ..) if !hook.enabled => (403, reject(&format!("Webhook {} 已停用", hook.name))),
+            (Some(hook), None, ..) | (Some(hook), _, None, _) => {
+                (401, reject(&format!("Webhook {} 的请求缺少签名或时间戳", hook.name)))
+            }
+            (Some(hook), Some(signature), Some(timestamp), Ok(Some(body))) => {
+                match fresh_timestamp(&timestamp, now) {
+                    _ if !verify(&hook.secret, &timestamp, &body, &signature) => {
+                        (401, reject(&format!("Webhook {} 的签名校验失败", hook.name)))
+                    }
+                    None => (401, reject(&format!("Webhook {} 的请求时间戳无效或已过期", hook.name))),
+                    Some(sent) if !seen.insert(&signature, sent, now) => {
+                        (409, reject(&format!("Webhook {} 的请求重复，已按重放拒绝", hook.name)))
+                    }
+                    Some(_) => (202, WebhookEvent::Triggered { hook, remote: remote.clone() }),
+                }
+            }
+        }
+    };
+
+    let _ = sender.send(event);
+    let body = if status == 202 { "accepted" } else { "rejected" };
+    let _ = request.respond(tiny_http::Response::from_string(body).with_status_code(status));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signature_covers_timestamp_and_body() {
+        let signature = sign("secret", "1700000000", b"{}");
+        assert!(signature.starts_with("sha256="));
+        assert!(verify("secret", "1700000000", b"{}", &signature));
+        let uppercase = format!("sha256={}", signature.trim_start_matches("sha256=").to_ascii_uppercase());
+        assert!(verify("secret", "1700000000", b"{}", &uppercase));
+        assert!(!verify("other", "1700000000", b"{}", &signature));
+        assert!(!verify("secret", "1700000001", b"{}", &signature));
+        assert!(!verify("secret", "1700000000", b"{ }", &signature));
+        assert!(!verify("secret", "1700000000", b"{}", signature.trim_start_matches("sha256=")));
+        assert!(!verify("secret", "1700000000", b"{}", "sha256=not-hex"));
+    }
+
+    #[test]
+    fn stale_or_malformed_timestamps_are_rejected() {
+        let now = 1_700_000_000;
+        assert_eq!(fresh_timestamp(" 1700000000 ", now), Some(now));
+        assert_eq!(fresh_timestamp(&(now - MAX_SKEW_SECS).to_string(), now), Some(now - MAX_SKEW_SECS));
+        assert_eq!(fresh_timestamp(&(now + MAX_SKEW_SECS + 1).to_string(), now), None);
+        assert_eq!(fresh_timestamp(&(now - MAX_SKEW_SECS - 1).to_string(), now), None);
+        assert_eq!(fresh_timestamp("yesterday", now), None);
+    }
+
+    #[test]
+    fn replayed_signatures_are_rejected_within_window() {
+        let now = 1_700_000_000;
+        let mut seen = SeenSignatures::default();
+        assert!(seen.insert("sha256=ABCD", now, now));
+        assert!(!seen.insert("sha256=abcd", now, now + 10));
+        assert!(seen.insert("sha256=ef01", now, now + 10));
+        // 窗口外的签名不再保留，届时请求已因过期被拒绝
+        assert!(seen.insert("sha256=abcd", now, now + MAX_SKEW_SECS + 1));
+        assert_eq!(seen.0.len(), 1);
+    }
+}
@@ -0,0 +1,154 @@
+use chrono::Utc;
+use std::time::Instant;
+use uuid::Uuid;
+
+use crate::api::{ApiClient, ApiClientConfig};
+use crate::models::{BackendContainer, HealthStatus, MiddlewareContainer, NetworkProfile, WeightAdjustment, WeightEntry};
+
+/// 非固定后端的权重总和
+const WEIGHT_TOTAL: f64 = 100.0;
+
+/// 逐次探测后端健康接口，返回成功探测的平均延迟与错误率
+async fn probe(backend: &BackendContainer, probes: u32, network: Option<&NetworkProfile>, trace_id: Option<&str>) -> (Option<f64>, f64) {
+    let probes = probes.max(1);
+    let client = ApiClient::new(ApiClientConfig {
+        base_url: backend.url.clone(),
+        timeout: backend.timeout,
+        network: network.cloned(),
+        trace_id: trace_id.map(str::to_string),
+    });
+    let Ok(client) = client else {
+        return (None, 1.0);
+    };
+
+    let mut latencies = Vec::new();
+    for _ in 0..probes {
+        let started = Instant::now();
+        if matches!(client.health_check().await, Ok(HealthStatus::Healthy)) {
+            latencies.push(started.elapsed().as_secs_f64() * 1000.0);
+        }
+    }
+    let latency = (!latencies.is_empty()).then(|| latencies.iter().sum::<f64>() / latencies.len() as f64);
+    let error_rate = 1.0 - latencies.len() as f64 / probes as f64;
+    (latency, error_rate)
+}
+
+/// 按延迟与错误率计算非固定后端的权重：得分为成功率除以延迟，按得分比例分配，
+/// 可用后端至少为1，全部失败的后端为0
+fn compute(entries: &mut [WeightEntry]) {
+    let score = |e: &WeightEntry| e.latency_ms.map(|ms| (1.0 - e.error_rate) / ms.max(1.0)).unwrap_or(0.0);
+    let total: f64 = entries.iter().filter(|e| !e.pinned).map(score).sum();
+    if total <= 0.0 {
+        return;
+    }
+    for entry in entries.iter_mut().filter(|e| !e.pinned) {
+        let share = score(entry) / total * WEIGHT_TOTAL;
+        entry.weight = if entry.latency_ms.is_some() { (share.round() as u32).max(1) } else { 0 };
+    }
+}
+
+/// 测量中间层下所有后端并重新计算权重，成功时将新的实例列表推送到服务
+///
+/// 手动触发时带有追踪ID，探测与推送请求均携带此ID；
+/// 返回调整记录与推送后的中间层，推送失败或所有后端均不可用时中间层为空
+pub async fn adjust(middleware: &MiddlewareContainer, network: Option<&NetworkProfile>, trace_id: Option<&str>) -> (WeightAdjustment, Option<MiddlewareContainer>) {
+    let mut entries: Vec<WeightEntry> = Vec::with_capacity(middleware.backend_containers.len());
+    for backend in &middleware.backend_containers {
+        let (latency_ms, error_rate) = probe(backend, middleware.adaptive_weights.probes, network, trace_id).await;
+        entries.push(WeightEntry {
+            backend_id: backend.id.clone(),
+            backend_name: backend.name.clone(),
+            latency_ms,
+            error_rate,
+            weight: backend.weight,
+            pinned: backend.weight_pinned,
+        });
+    }
+    compute(&mut entries);
+
+    let mut updated = middleware.clone();
+    for backend in &mut updated.backend_containers {
+        if let Some(entry) = entries.iter().find(|e| e.backend_id == backend.id) {
+            backend.weight = entry.weight;
+        }
+    }
+    updated.config.crud_api.instances = updated.crud_instances();
+
+    let result = if updated.backend_containers.iter().all(|b| b.weight == 0) {
+        Err(anyhow::anyhow!("没有可用的后端"))
+    } else {
+        match ApiClient::new(ApiClientConfig {
+            base_url: middleware.api_base_url(),
+            timeout: middleware.config.crud_api.timeout,
+            network: network.cloned(),
+            trace_id: trace_id.map(str::to_string),
+        }) {
+            Ok(client) => client.update_config(&updated.config).await,
+            Err(e) => Err(e),
+        }
+    };
+
+    let adjustment = WeightAdjustment {
+        id: Uuid::new_v4().to_string(),
+        middleware_id: middleware.id.clone(),
+        middleware_name: middleware.name.clone(),
+        timestamp: Utc::now(),
+        entries,
+        manual: trace_id.is_some(),
+        error: result.as_ref().err().map(|e| format!("{:#}", e)),
+        trace_id: trace_id.map(str::to_string),
+    };
+    (adjustment, result.ok().map(|_| updated))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(latency_ms: Option<f64>, error_rate: f64) -> WeightEntry {
+        WeightEntry {
+            backend_id: String::new(),
+            backend_name: String::new(),
+            latency_ms,
+            error_rate,
+            weight: 100,
+            pinned: false,
+        }
+    }
+
+    fn weights(mut entries: Vec<WeightEntry>) -> Vec<u32> {
+        compute(&mut entries);
+        entries.iter().map(|e| e.weight).collect()
+    }
+
+    #[test]
+    fn equal_latency_splits_evenly() {
+        assert_eq!(weights(vec![entry(Some(20.0), 0.0), entry(Some(20.0), 0.0)]), [50, 50]);
+        assert_eq!(weights(vec![entry(Some(5.0), 0.0); 3]), [33, 33, 33]);
+    }
+
+    #[test]
+    fn zero_latency_counts_as_one_millisecond() {
+        assert_eq!(weights(vec![entry(Some(0.0), 0.0), entry(Some(0.4), 0.0), entry(Some(1.0), 0.0)]), [33, 33, 33]);
+        assert_eq!(weights(vec![entry(Some(0.0), 0.0), entry(Some(3.0), 0.0)]), [75, 25]);
+    }
+
+    #[test]
+    fn skewed_latency_and_errors_shift_weight() {
+        assert_eq!(weights(vec![entry(Some(10.0), 0.0), entry(Some(30.0), 0.0)]), [75, 25]);
+        // 错误率按比例降低得分
+        assert_eq!(weights(vec![entry(Some(10.0), 0.5), entry(Some(10.0), 0.0)]), [33, 67]);
+        // 很慢的可用后端至少保留1
+        assert_eq!(weights(vec![entry(Some(1.0), 0.0), entry(Some(100_000.0), 0.0)]), [100, 1]);
+    }
+
+    #[test]
+    fn failed_and_pinned_backends() {
+        let mut pinned = entry(Some(1.0), 0.0);
+        pinned.pinned = true;
+        pinned.weight = 7;
+        assert_eq!(weights(vec![entry(Some(10.0), 0.0), entry(None, 1.0), pinned]), [100, 0, 7]);
+        // 全部失败时保留原权重
+        assert_eq!(weights(vec![entry(None, 1.0), entry(Some(10.0), 1.0)]), [100, 100]);
+    }
+}